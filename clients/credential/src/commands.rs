@@ -5,6 +5,7 @@ use clap::{ArgGroup, Args, Subcommand};
 use log::*;
 use nym_bandwidth_controller::acquire::state::State;
 use nym_bin_common::completions::ArgShell;
+use nym_bin_common::passphrase::ArgCredentialsPassphrase;
 use nym_credential_storage::persistent_storage::PersistentStorage;
 use nym_validator_client::nyxd::traits::DkgQueryClient;
 
@@ -50,6 +51,11 @@ pub(crate) struct Run {
     /// Recovery mode, when enabled, tries to recover any deposit data dumped in recovery_dir
     #[clap(long)]
     pub(crate) recovery_mode: bool,
+
+    /// Source of the passphrase used for encrypting the credentials stored in the client's database.
+    /// It has to match the passphrase used by the client itself.
+    #[clap(flatten)]
+    pub(crate) credentials_passphrase: ArgCredentialsPassphrase,
 }
 
 pub(crate) async fn recover_credentials<C: DkgQueryClient + Send + Sync>(
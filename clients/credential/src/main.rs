@@ -75,8 +75,16 @@ async fn main() -> Result<()> {
                 .client_home_directory
                 .join(DATA_DIR)
                 .join(CRED_DB_FILE_NAME);
-            let shared_storage =
-                nym_credential_storage::initialise_persistent_storage(db_path).await;
+            let shared_storage = match r.credentials_passphrase.read_passphrase()? {
+                Some(passphrase) => {
+                    nym_credential_storage::initialise_encrypted_persistent_storage(
+                        db_path,
+                        &passphrase,
+                    )
+                    .await
+                }
+                None => nym_credential_storage::initialise_persistent_storage(db_path).await,
+            };
             let recovery_storage = recovery_storage::RecoveryStorage::new(r.recovery_dir)?;
 
             let network_details = NymNetworkDetails::new_from_env();
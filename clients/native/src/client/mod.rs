@@ -38,20 +38,50 @@ pub struct SocketClient {
     /// Client configuration options, including, among other things, packet sending rates,
     /// key filepaths, etc.
     config: Config,
+
+    /// Optional passphrase used for encrypting the stored bandwidth credentials.
+    credentials_passphrase: Option<String>,
+
+    /// Optional passphrase used for encrypting the private keys stored on disk.
+    key_passphrase: Option<String>,
 }
 
 impl SocketClient {
     pub fn new(config: Config) -> Self {
-        SocketClient { config }
+        SocketClient {
+            config,
+            credentials_passphrase: None,
+            key_passphrase: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_credentials_passphrase(mut self, credentials_passphrase: Option<String>) -> Self {
+        self.credentials_passphrase = credentials_passphrase;
+        self
+    }
+
+    #[must_use]
+    pub fn with_key_passphrase(mut self, key_passphrase: Option<String>) -> Self {
+        self.key_passphrase = key_passphrase;
+        self
     }
 
     async fn create_bandwidth_controller(
         config: &Config,
+        credentials_passphrase: Option<&str>,
     ) -> BandwidthController<Client<QueryNyxdClient>, PersistentStorage> {
-        let storage = nym_credential_storage::initialise_persistent_storage(
-            config.get_base().get_database_path(),
-        )
-        .await;
+        let database_path = config.get_base().get_database_path();
+        let storage = match credentials_passphrase {
+            Some(passphrase) => {
+                nym_credential_storage::initialise_encrypted_persistent_storage(
+                    database_path,
+                    passphrase,
+                )
+                .await
+            }
+            None => nym_credential_storage::initialise_persistent_storage(database_path).await,
+        };
 
         create_bandwidth_controller(config.get_base(), storage)
     }
@@ -61,7 +91,6 @@ impl SocketClient {
         client_input: ClientInput,
         client_output: ClientOutput,
         client_state: ClientState,
-        self_address: &Recipient,
         shutdown: nym_task::TaskClient,
     ) {
         info!("Starting websocket listener...");
@@ -73,11 +102,13 @@ impl SocketClient {
 
         let ClientOutput {
             received_buffer_request_sender,
+            ..
         } = client_output;
 
         let ClientState {
             shared_lane_queue_lengths,
             reply_controller_sender,
+            client_address,
             ..
         } = client_state;
 
@@ -85,7 +116,7 @@ impl SocketClient {
             input_sender,
             connection_command_sender,
             received_buffer_request_sender,
-            self_address,
+            client_address,
             shared_lane_queue_lengths,
             reply_controller_sender,
         );
@@ -105,7 +136,7 @@ impl SocketClient {
 
     fn key_store(&self) -> OnDiskKeys {
         let pathfinder = ClientKeyPathfinder::new_from_config(self.config.get_base());
-        OnDiskKeys::new(pathfinder)
+        OnDiskKeys::new(pathfinder).with_key_passphrase(self.key_passphrase.clone())
     }
 
     // TODO: see if this could also be shared with socks5 client / nym-sdk maybe
@@ -114,7 +145,13 @@ impl SocketClient {
         let bandwidth_controller = if self.config.get_base().get_disabled_credentials_mode() {
             None
         } else {
-            Some(Self::create_bandwidth_controller(&self.config).await)
+            Some(
+                Self::create_bandwidth_controller(
+                    &self.config,
+                    self.credentials_passphrase.as_deref(),
+                )
+                .await,
+            )
         };
 
         let base_client = BaseClientBuilder::new_from_base_config(
@@ -148,7 +185,6 @@ impl SocketClient {
             client_input,
             client_output,
             client_state,
-            &self_address,
             started_client.task_manager.subscribe(),
         );
 
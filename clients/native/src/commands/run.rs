@@ -12,6 +12,9 @@ use crate::{
 };
 use clap::Args;
 use log::*;
+use nym_bin_common::passphrase::{
+    read_key_and_credentials_passphrases, ArgCredentialsPassphrase, ArgKeyPassphrase,
+};
 use nym_bin_common::version_checker::is_minor_version_compatible;
 use nym_config::NymConfig;
 use nym_crypto::asymmetric::identity;
@@ -61,6 +64,14 @@ pub(crate) struct Run {
     /// with bandwidth credential requirement.
     #[clap(long, hide = true)]
     enabled_credentials_mode: Option<bool>,
+
+    /// If the passphrase for encrypting the bandwidth credentials stored by this client is specified
+    /// and the existing credentials were stored in plaintext, they will get encrypted.
+    #[clap(flatten)]
+    credentials_passphrase: ArgCredentialsPassphrase,
+
+    #[clap(flatten)]
+    key_passphrase: ArgKeyPassphrase,
 }
 
 impl From<Run> for OverrideConfig {
@@ -99,6 +110,8 @@ fn version_check(cfg: &Config) -> bool {
 
 pub(crate) async fn execute(args: &Run) -> Result<(), Box<dyn Error + Send + Sync>> {
     let id = &args.id;
+    let (key_passphrase, credentials_passphrase) =
+        read_key_and_credentials_passphrases(&args.key_passphrase, &args.credentials_passphrase)?;
 
     // in case we're using old config, try to upgrade it
     // (if we're using the current version, it's a no-op)
@@ -128,5 +141,9 @@ pub(crate) async fn execute(args: &Run) -> Result<(), Box<dyn Error + Send + Syn
         return Err(Box::new(ClientError::FailedLocalVersionCheck));
     }
 
-    SocketClient::new(config).run_socket_forever().await
+    SocketClient::new(config)
+        .with_credentials_passphrase(credentials_passphrase)
+        .with_key_passphrase(key_passphrase)
+        .run_socket_forever()
+        .await
 }
@@ -6,6 +6,7 @@ use futures::{SinkExt, StreamExt};
 use log::*;
 use nym_client_core::client::replies::reply_controller::requests::ReplyControllerSender;
 use nym_client_core::client::{
+    address::ClientAddressReceiver,
     inbound_messages::{InputMessage, InputMessageSender},
     received_buffer::{
         ReceivedBufferMessage, ReceivedBufferRequestSender, ReconstructedMessagesReceiver,
@@ -38,7 +39,7 @@ pub(crate) struct HandlerBuilder {
     msg_input: InputMessageSender,
     client_connection_tx: ConnectionCommandSender,
     buffer_requester: ReceivedBufferRequestSender,
-    self_full_address: Recipient,
+    self_full_address: ClientAddressReceiver,
     lane_queue_lengths: LaneQueueLengths,
     reply_controller_sender: ReplyControllerSender,
 }
@@ -48,7 +49,7 @@ impl HandlerBuilder {
         msg_input: InputMessageSender,
         client_connection_tx: ConnectionCommandSender,
         buffer_requester: ReceivedBufferRequestSender,
-        self_full_address: &Recipient,
+        self_full_address: ClientAddressReceiver,
         lane_queue_lengths: LaneQueueLengths,
         reply_controller_sender: ReplyControllerSender,
     ) -> Self {
@@ -56,7 +57,7 @@ impl HandlerBuilder {
             msg_input,
             client_connection_tx,
             buffer_requester,
-            self_full_address: *self_full_address,
+            self_full_address,
             lane_queue_lengths,
             reply_controller_sender,
         }
@@ -68,7 +69,7 @@ impl HandlerBuilder {
             msg_input: self.msg_input.clone(),
             client_connection_tx: self.client_connection_tx.clone(),
             buffer_requester: self.buffer_requester.clone(),
-            self_full_address: self.self_full_address,
+            self_full_address: self.self_full_address.clone(),
             socket: None,
             received_response_type: Default::default(),
             lane_queue_lengths: self.lane_queue_lengths.clone(),
@@ -81,7 +82,7 @@ pub(crate) struct Handler {
     msg_input: InputMessageSender,
     client_connection_tx: ConnectionCommandSender,
     buffer_requester: ReceivedBufferRequestSender,
-    self_full_address: Recipient,
+    self_full_address: ClientAddressReceiver,
     socket: Option<WebSocketStream<TcpStream>>,
     received_response_type: ReceivedResponseType,
     lane_queue_lengths: LaneQueueLengths,
@@ -233,7 +234,8 @@ impl Handler {
     }
 
     fn handle_self_address(&self) -> ServerResponse {
-        ServerResponse::SelfAddress(Box::new(self.self_full_address))
+        // the address might have changed if we failed over to another gateway
+        ServerResponse::SelfAddress(Box::new(self.self_full_address.borrow().recipient))
     }
 
     fn handle_closed_connection(&self, connection_id: u64) -> Option<ServerResponse> {
@@ -8,6 +8,7 @@ use crate::{
 };
 use clap::Args;
 use nym_bin_common::output_format::OutputFormat;
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use nym_config::NymConfig;
 use nym_credential_storage::persistent_storage::PersistentStorage;
 use nym_crypto::asymmetric::identity;
@@ -79,6 +80,9 @@ pub(crate) struct Init {
 
     #[clap(short, long, default_value_t = OutputFormat::default())]
     output: OutputFormat,
+
+    #[clap(flatten)]
+    key_passphrase: ArgKeyPassphrase,
 }
 
 impl From<Init> for OverrideConfig {
@@ -125,6 +129,7 @@ pub(crate) async fn execute(args: &Init) -> Result<(), Socks5ClientError> {
     eprintln!("Initialising client...");
 
     let id = &args.id;
+    let key_passphrase = args.key_passphrase.read_passphrase()?;
     let provider_address = &args.provider;
 
     let already_init = Config::default_config_file_path(id).exists();
@@ -163,6 +168,7 @@ pub(crate) async fn execute(args: &Init) -> Result<(), Socks5ClientError> {
         user_chosen_gateway_id,
         config.get_base(),
         args.latency_based_selection,
+        key_passphrase.as_deref(),
     )
     .await
     .tap_err(|err| eprintln!("Failed to setup gateway\nError: {err}"))?;
@@ -177,7 +183,10 @@ pub(crate) async fn execute(args: &Init) -> Result<(), Socks5ClientError> {
 
     print_saved_config(&config);
 
-    let address = nym_client_core::init::get_client_address_from_stored_keys(config.get_base())?;
+    let address = nym_client_core::init::get_client_address_from_stored_keys(
+        config.get_base(),
+        key_passphrase.as_deref(),
+    )?;
     let init_results = InitResults::new(&config, &address);
     println!("{}", args.output.format(&init_results));
 
@@ -1,7 +1,10 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use nym_bin_common::version_checker::Version;
+use nym_client_core::client::key_manager::persistence::OnDiskKeys;
+use nym_client_core::config::persistence::key_pathfinder::ClientKeyPathfinder;
 use nym_config::NymConfig;
 use nym_socks5_client_core::config::{Config, MISSING_VALUE};
 
@@ -53,6 +56,12 @@ pub(crate) struct Upgrade {
     /// Id of the nym-client we want to upgrade
     #[clap(long)]
     id: String,
+
+    // if specified, any plaintext private keys will get encrypted with the provided passphrase.
+    // keys that are already encrypted must have been encrypted with the same passphrase,
+    // as changing it is not supported
+    #[clap(flatten)]
+    key_passphrase: ArgKeyPassphrase,
 }
 
 fn parse_config_version(config: &Config) -> Version {
@@ -153,6 +162,22 @@ pub(crate) fn execute(args: &Upgrade) {
         process::exit(1);
     }
 
+    let key_passphrase = args.key_passphrase.read_passphrase().unwrap_or_else(|err| {
+        eprintln!("failed to read the key passphrase! - {err}");
+        process::exit(1)
+    });
+    if let Some(key_passphrase) = key_passphrase {
+        let pathfinder = ClientKeyPathfinder::new_from_config(existing_config.get_base());
+        OnDiskKeys::new(pathfinder)
+            .with_key_passphrase(Some(key_passphrase))
+            .encrypt_stored_keys()
+            .unwrap_or_else(|err| {
+                eprintln!("failed to encrypt the stored keys! - {err}");
+                process::exit(1)
+            });
+        println!("Encrypted the stored private keys");
+    }
+
     // here be upgrade path to 0.9.X and beyond based on version number from config
     do_upgrade(existing_config, args, &package_version)
 }
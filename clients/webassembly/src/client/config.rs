@@ -9,10 +9,11 @@
 use nym_client_core::config::{
     Acknowledgements as ConfigAcknowledgements, CoverTraffic as ConfigCoverTraffic,
     DebugConfig as ConfigDebug, GatewayConnection as ConfigGatewayConnection,
-    GatewayEndpointConfig, ReplySurbs as ConfigReplySurbs, Topology as ConfigTopology,
-    Traffic as ConfigTraffic,
+    GatewayEndpointConfig, MessageReconstruction as ConfigMessageReconstruction,
+    ReplySurbs as ConfigReplySurbs, Topology as ConfigTopology, Traffic as ConfigTraffic,
 };
-use nym_sphinx::params::PacketSize;
+use nym_sphinx::chunking::ErasureCoding;
+use nym_sphinx::params::{PacketSize, PacketType};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use url::Url;
@@ -79,6 +80,14 @@ pub struct Traffic {
 
     /// Controls whether the sent sphinx packet use the NON-DEFAULT bigger size.
     pub use_extended_packet_size: bool,
+
+    /// Controls whether the sent messages use the Outfox rather than the Sphinx packet format.
+    pub use_outfox: bool,
+
+    /// If set, specifies the number of additional parity packets, relative to the number of data packets
+    /// and expressed in percent, sent alongside messages for the purposes of forward error correction.
+    /// It is only applied to messages that fit in a single set, i.e. ones shorter than 255 packets.
+    pub erasure_coding_parity_percentage: Option<u8>,
 }
 
 impl From<Traffic> for ConfigTraffic {
@@ -96,6 +105,14 @@ impl From<Traffic> for ConfigTraffic {
                 .disable_main_poisson_packet_distribution,
             primary_packet_size: PacketSize::RegularPacket,
             secondary_packet_size: use_extended_packet_size,
+            packet_type: if traffic.use_outfox {
+                PacketType::Outfox
+            } else {
+                PacketType::Mix
+            },
+            erasure_coding: traffic
+                .erasure_coding_parity_percentage
+                .map(ErasureCoding::new),
         }
     }
 }
@@ -109,6 +126,10 @@ impl From<ConfigTraffic> for Traffic {
             disable_main_poisson_packet_distribution: traffic
                 .disable_main_poisson_packet_distribution,
             use_extended_packet_size: traffic.secondary_packet_size.is_some(),
+            use_outfox: traffic.packet_type.is_outfox(),
+            erasure_coding_parity_percentage: traffic
+                .erasure_coding
+                .map(|erasure_coding| erasure_coding.parity_percentage),
         }
     }
 }
@@ -159,6 +180,24 @@ pub struct GatewayConnection {
     /// How long we're willing to wait for a response to a message sent to the gateway,
     /// before giving up on it.
     pub gateway_response_timeout_ms: u64,
+
+    /// Controls whether the client should attempt to re-register with a different gateway,
+    /// chosen from the current network topology, once its current gateway keeps on failing.
+    /// It's disabled by default as doing so changes the address of the client,
+    /// so anyone wishing to reach it has to learn about the new one.
+    pub enable_gateway_failover: bool,
+
+    /// Maximum number of fallback gateways, ranked by their latency, that are going to be
+    /// considered during a failover attempt.
+    pub maximum_fallback_gateways: usize,
+
+    /// Number of consecutive failures to send packets to the gateway (after its own reconnection
+    /// attempts have already been exhausted) after which the gateway is considered to be dead.
+    pub gateway_failover_threshold: usize,
+
+    /// Controls whether the client should keep an authenticated connection to a secondary gateway
+    /// at all times, so that the failover could happen without having to register first.
+    pub enable_secondary_gateway: bool,
 }
 
 impl From<GatewayConnection> for ConfigGatewayConnection {
@@ -167,6 +206,10 @@ impl From<GatewayConnection> for ConfigGatewayConnection {
             gateway_response_timeout: Duration::from_millis(
                 gateway_connection.gateway_response_timeout_ms,
             ),
+            enable_gateway_failover: gateway_connection.enable_gateway_failover,
+            maximum_fallback_gateways: gateway_connection.maximum_fallback_gateways,
+            gateway_failover_threshold: gateway_connection.gateway_failover_threshold,
+            enable_secondary_gateway: gateway_connection.enable_secondary_gateway,
         }
     }
 }
@@ -176,6 +219,10 @@ impl From<ConfigGatewayConnection> for GatewayConnection {
         GatewayConnection {
             gateway_response_timeout_ms: gateway_connection.gateway_response_timeout.as_millis()
                 as u64,
+            enable_gateway_failover: gateway_connection.enable_gateway_failover,
+            maximum_fallback_gateways: gateway_connection.maximum_fallback_gateways,
+            gateway_failover_threshold: gateway_connection.gateway_failover_threshold,
+            enable_secondary_gateway: gateway_connection.enable_secondary_gateway,
         }
     }
 }
@@ -198,6 +245,11 @@ pub struct Acknowledgements {
     /// it is assumed it was lost and retransmission of the data packet happens.
     /// In an ideal network with 0 latency, this value would have been 0.
     pub ack_wait_addition_ms: u64,
+
+    /// Maximum number of times a packet of a tracked message is going to get retransmitted before
+    /// the client gives up on delivering the message. If set to 0, the packet is going to get
+    /// retransmitted until it's acknowledged.
+    pub maximum_retransmissions: u32,
 }
 
 impl From<Acknowledgements> for ConfigAcknowledgements {
@@ -206,6 +258,7 @@ impl From<Acknowledgements> for ConfigAcknowledgements {
             average_ack_delay: Duration::from_millis(acknowledgements.average_ack_delay_ms),
             ack_wait_multiplier: acknowledgements.ack_wait_multiplier,
             ack_wait_addition: Duration::from_millis(acknowledgements.ack_wait_addition_ms),
+            maximum_retransmissions: acknowledgements.maximum_retransmissions,
         }
     }
 }
@@ -216,6 +269,7 @@ impl From<ConfigAcknowledgements> for Acknowledgements {
             average_ack_delay_ms: acknowledgements.average_ack_delay.as_millis() as u64,
             ack_wait_multiplier: acknowledgements.ack_wait_multiplier,
             ack_wait_addition_ms: acknowledgements.ack_wait_addition.as_millis() as u64,
+            maximum_retransmissions: acknowledgements.maximum_retransmissions,
         }
     }
 }
@@ -294,6 +348,11 @@ pub struct ReplySurbs {
     /// Defines maximum amount of time given reply key is going to be valid for.
     /// This is going to be superseded by key rotation once implemented.
     pub maximum_reply_key_age_ms: u64,
+
+    /// Defines how often the changes made to the reply storage, such as received reply surbs or sent reply keys,
+    /// are written to the persistent storage, so that they would survive an unclean shutdown.
+    /// It's only applicable to storage backends capable of incremental writes.
+    pub reply_storage_flush_interval_ms: u64,
 }
 
 impl From<ReplySurbs> for ConfigReplySurbs {
@@ -313,6 +372,9 @@ impl From<ReplySurbs> for ConfigReplySurbs {
             ),
             maximum_reply_surb_age: Duration::from_millis(reply_surbs.maximum_reply_surb_age_ms),
             maximum_reply_key_age: Duration::from_millis(reply_surbs.maximum_reply_key_age_ms),
+            reply_storage_flush_interval: Duration::from_millis(
+                reply_surbs.reply_storage_flush_interval_ms,
+            ),
         }
     }
 }
@@ -334,6 +396,51 @@ impl From<ConfigReplySurbs> for ReplySurbs {
                 .as_millis() as u64,
             maximum_reply_surb_age_ms: reply_surbs.maximum_reply_surb_age.as_millis() as u64,
             maximum_reply_key_age_ms: reply_surbs.maximum_reply_key_age.as_millis() as u64,
+            reply_storage_flush_interval_ms: reply_surbs.reply_storage_flush_interval.as_millis()
+                as u64,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone)]
+pub struct MessageReconstruction {
+    /// Defines maximum amount of time, since receiving its first fragment, an incomplete message
+    /// is going to be kept in memory before it's assumed its remaining fragments got lost.
+    pub maximum_partial_message_age_ms: u64,
+
+    /// Defines maximum number of bytes the client is going to keep in memory for all incomplete messages.
+    /// Once exceeded, the oldest incomplete messages are going to be dropped.
+    pub maximum_buffered_bytes: usize,
+
+    /// Defines how often the client is going to check for incomplete messages that have become stale.
+    pub stale_message_check_interval_ms: u64,
+}
+
+impl From<MessageReconstruction> for ConfigMessageReconstruction {
+    fn from(message_reconstruction: MessageReconstruction) -> Self {
+        ConfigMessageReconstruction {
+            maximum_partial_message_age: Duration::from_millis(
+                message_reconstruction.maximum_partial_message_age_ms,
+            ),
+            maximum_buffered_bytes: message_reconstruction.maximum_buffered_bytes,
+            stale_message_check_interval: Duration::from_millis(
+                message_reconstruction.stale_message_check_interval_ms,
+            ),
+        }
+    }
+}
+
+impl From<ConfigMessageReconstruction> for MessageReconstruction {
+    fn from(message_reconstruction: ConfigMessageReconstruction) -> Self {
+        MessageReconstruction {
+            maximum_partial_message_age_ms: message_reconstruction
+                .maximum_partial_message_age
+                .as_millis() as u64,
+            maximum_buffered_bytes: message_reconstruction.maximum_buffered_bytes,
+            stale_message_check_interval_ms: message_reconstruction
+                .stale_message_check_interval
+                .as_millis() as u64,
         }
     }
 }
@@ -359,6 +466,9 @@ pub struct Debug {
 
     /// Defines all configuration options related to reply SURBs.
     pub reply_surbs: ReplySurbs,
+
+    /// Defines all configuration options related to reconstruction of received messages.
+    pub message_reconstruction: MessageReconstruction,
 }
 
 impl From<Debug> for ConfigDebug {
@@ -370,6 +480,7 @@ impl From<Debug> for ConfigDebug {
             acknowledgements: debug.acknowledgements.into(),
             topology: debug.topology.into(),
             reply_surbs: debug.reply_surbs.into(),
+            message_reconstruction: debug.message_reconstruction.into(),
         }
     }
 }
@@ -383,6 +494,7 @@ impl From<ConfigDebug> for Debug {
             acknowledgements: debug.acknowledgements.into(),
             topology: debug.topology.into(),
             reply_surbs: debug.reply_surbs.into(),
+            message_reconstruction: debug.message_reconstruction.into(),
         }
     }
 }
@@ -5,10 +5,10 @@ use crate::error::WasmClientError;
 use crate::topology::WasmNymTopology;
 use js_sys::Promise;
 use nym_client_core::client::replies::reply_storage::browser_backend;
+use nym_client_core::client::topology_control::nym_topology_with_sphinx_keys;
 use nym_client_core::config;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
-use nym_topology::NymTopology;
 use nym_validator_client::NymApiClient;
 use url::Url;
 use wasm_bindgen::prelude::wasm_bindgen;
@@ -58,10 +58,16 @@ pub(crate) async fn current_network_topology_async(
     };
 
     let api_client = NymApiClient::new(url);
-    let mixnodes = api_client.get_cached_active_mixnodes().await?;
-    let gateways = api_client.get_cached_gateways().await?;
+    let mixnodes = api_client
+        .get_cached_active_mixnodes_with_sphinx_keys()
+        .await?;
+    let gateways = api_client.get_cached_gateways_with_sphinx_keys().await?;
+    let epoch_id = api_client
+        .get_current_epoch()
+        .await?
+        .current_epoch_absolute_id();
 
-    Ok(NymTopology::from_detailed(mixnodes, gateways).into())
+    Ok(nym_topology_with_sphinx_keys(mixnodes, gateways, epoch_id).into())
 }
 
 #[wasm_bindgen]
@@ -189,6 +189,8 @@ impl TryFrom<WasmMixNode> for mix::Node {
                 .map_err(MixnodeConversionError::from)?,
             sphinx_key: encryption::PublicKey::from_base58_string(&value.sphinx_key)
                 .map_err(MixnodeConversionError::from)?,
+            sphinx_key_validity: None,
+            announced_sphinx_keys: None,
             layer: Layer::try_from(value.layer)
                 .map_err(|_| WasmTopologyError::InvalidMixLayer { value: value.layer })?,
             version: value.version,
@@ -256,6 +258,8 @@ impl TryFrom<WasmGateway> for gateway::Node {
                 .map_err(GatewayConversionError::from)?,
             sphinx_key: encryption::PublicKey::from_base58_string(&value.sphinx_key)
                 .map_err(GatewayConversionError::from)?,
+            sphinx_key_validity: None,
+            announced_sphinx_keys: None,
             version: value.version,
         })
     }
@@ -7,7 +7,7 @@ use nym_credential_storage::storage::Storage;
 use nym_credentials::coconut::bandwidth::{BandwidthVoucher, TOTAL_ATTRIBUTES};
 use nym_credentials::coconut::utils::obtain_aggregate_signature;
 use nym_crypto::asymmetric::{encryption, identity};
-use nym_network_defaults::VOUCHER_INFO;
+use nym_network_defaults::{TICKETBOOK_VALUE, TICKETBOOK_VOUCHER_INFO, VOUCHER_INFO};
 use nym_validator_client::nyxd::traits::CoconutBandwidthSigningClient;
 use nym_validator_client::nyxd::traits::DkgQueryClient;
 use nym_validator_client::nyxd::tx::Hash;
@@ -20,6 +20,30 @@ use std::str::FromStr;
 pub mod state;
 
 pub async fn deposit<C>(client: &C, amount: Coin) -> Result<State, BandwidthControllerError>
+where
+    C: CoconutBandwidthSigningClient,
+{
+    deposit_voucher(client, amount, VOUCHER_INFO).await
+}
+
+/// Deposits the funds for a ticketbook, i.e. a credential that is going to be spent in
+/// `TICKETBOOK_SIZE` unlinkable tickets rather than all at once.
+pub async fn deposit_ticketbook<C>(
+    client: &C,
+    denom: &str,
+) -> Result<State, BandwidthControllerError>
+where
+    C: CoconutBandwidthSigningClient,
+{
+    let amount = Coin::new(TICKETBOOK_VALUE as u128, denom);
+    deposit_voucher(client, amount, TICKETBOOK_VOUCHER_INFO).await
+}
+
+async fn deposit_voucher<C>(
+    client: &C,
+    amount: Coin,
+    voucher_info: &str,
+) -> Result<State, BandwidthControllerError>
 where
     C: CoconutBandwidthSigningClient,
 {
@@ -32,7 +56,7 @@ where
     let tx_hash = client
         .deposit(
             amount,
-            String::from(VOUCHER_INFO),
+            voucher_info.to_string(),
             signing_keypair.public_key.clone(),
             encryption_keypair.public_key.clone(),
             None,
@@ -44,7 +68,7 @@ where
     let voucher = BandwidthVoucher::new(
         &params,
         voucher_value,
-        VOUCHER_INFO.to_string(),
+        voucher_info.to_string(),
         Hash::from_str(&tx_hash).map_err(|_| BandwidthControllerError::InvalidTxHash)?,
         identity::PrivateKey::from_base58_string(&signing_keypair.private_key)?,
         encryption::PrivateKey::from_base58_string(&encryption_keypair.private_key)?,
@@ -82,7 +106,7 @@ where
     storage
         .insert_coconut_credential(
             state.voucher.get_voucher_value(),
-            VOUCHER_INFO.to_string(),
+            state.voucher.get_voucher_info(),
             state.voucher.get_private_attributes()[0].to_bs58(),
             state.voucher.get_private_attributes()[1].to_bs58(),
             signature.to_bs58(),
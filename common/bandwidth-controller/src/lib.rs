@@ -4,13 +4,19 @@
 use crate::error::BandwidthControllerError;
 
 use nym_credential_storage::error::StorageError;
+use nym_credential_storage::models::CoconutCredential;
 use nym_credential_storage::storage::Storage;
+use nym_network_defaults::{TICKETBOOK_SIZE, TICKETBOOK_VOUCHER_INFO};
+use rand::rngs::OsRng;
+use rand::seq::IteratorRandom;
 
 use std::str::FromStr;
+use std::sync::Arc;
 use {
-    nym_coconut_interface::Base58,
+    nym_coconut_interface::{Base58, TicketIndex},
     nym_credentials::coconut::{
-        bandwidth::prepare_for_spending, utils::obtain_aggregate_verification_key,
+        bandwidth::{prepare_for_spending, prepare_ticket_for_spending},
+        utils::obtain_aggregate_verification_key,
     },
 };
 
@@ -26,20 +32,70 @@ pub mod error;
 #[cfg(target_arch = "wasm32")]
 pub mod wasm_mockups;
 
+/// Responsible for spending the stored bandwidth credentials.
+/// Cloned controllers share the same underlying storage and client,
+/// so they could be used by multiple gateway connections at the same time.
 pub struct BandwidthController<C, St: Storage> {
-    storage: St,
-    client: C,
+    storage: Arc<St>,
+    client: Arc<C>,
 }
 
 impl<C, St: Storage> BandwidthController<C, St> {
     pub fn new(storage: St, client: C) -> Self {
-        BandwidthController { storage, client }
+        BandwidthController {
+            storage: Arc::new(storage),
+            client: Arc::new(client),
+        }
     }
 
     pub fn storage(&self) -> &St {
         &self.storage
     }
 
+    /// Retrieves the next stored credential alongside the index of its ticket that should be spent,
+    /// if it's a ticketbook.
+    async fn get_next_coconut_credential(
+        &self,
+    ) -> Result<(CoconutCredential, Option<TicketIndex>), BandwidthControllerError>
+    where
+        <St as Storage>::StorageError: Send + Sync + 'static,
+    {
+        loop {
+            let bandwidth_credential = self
+                .storage
+                .get_next_coconut_credential()
+                .await
+                .map_err(|err| BandwidthControllerError::CredentialStorageError(Box::new(err)))?;
+            if bandwidth_credential.voucher_info != TICKETBOOK_VOUCHER_INFO {
+                return Ok((bandwidth_credential, None));
+            }
+
+            let spent_tickets = self
+                .storage
+                .get_spent_tickets(bandwidth_credential.id)
+                .await
+                .map_err(|err| BandwidthControllerError::CredentialStorageError(Box::new(err)))?;
+
+            // the index of the ticket is revealed upon spending it, so they're used in random order
+            // so that it wouldn't leak how many tickets of the book have already been used
+            let next_ticket = (0..TICKETBOOK_SIZE)
+                .filter(|ticket_index| !spent_tickets.contains(ticket_index))
+                .choose(&mut OsRng);
+            match next_ticket {
+                Some(ticket_index) => return Ok((bandwidth_credential, Some(ticket_index))),
+                // all tickets have been spent, but the book hasn't been consumed,
+                // for example because the gateway has rejected the last one
+                None => self
+                    .storage
+                    .consume_coconut_credential(bandwidth_credential.id)
+                    .await
+                    .map_err(|err| {
+                        BandwidthControllerError::CredentialStorageError(Box::new(err))
+                    })?,
+            }
+        }
+    }
+
     pub async fn prepare_coconut_credential(
         &self,
     ) -> Result<(nym_coconut_interface::Credential, i64), BandwidthControllerError>
@@ -47,11 +103,7 @@ impl<C, St: Storage> BandwidthController<C, St> {
         C: DkgQueryClient + Sync + Send,
         <St as Storage>::StorageError: Send + Sync + 'static,
     {
-        let bandwidth_credential = self
-            .storage
-            .get_next_coconut_credential()
-            .await
-            .map_err(|err| BandwidthControllerError::CredentialStorageError(Box::new(err)))?;
+        let (bandwidth_credential, ticket_index) = self.get_next_coconut_credential().await?;
         let voucher_value = u64::from_str(&bandwidth_credential.voucher_value)
             .map_err(|_| StorageError::InconsistentData)?;
         let voucher_info = bandwidth_credential.voucher_info.clone();
@@ -65,16 +117,18 @@ impl<C, St: Storage> BandwidthController<C, St> {
             .map_err(|_| StorageError::InconsistentData)?;
 
         #[cfg(not(target_arch = "wasm32"))]
-        let coconut_api_clients =
-            nym_validator_client::CoconutApiClient::all_coconut_api_clients(&self.client, epoch_id)
-                .await?;
+        let coconut_api_clients = nym_validator_client::CoconutApiClient::all_coconut_api_clients(
+            &*self.client,
+            epoch_id,
+        )
+        .await?;
         #[cfg(target_arch = "wasm32")]
         let coconut_api_clients = vec![];
         let verification_key = obtain_aggregate_verification_key(&coconut_api_clients).await?;
 
         // the below would only be executed once we know where we want to spend it (i.e. which gateway and stuff)
-        Ok((
-            prepare_for_spending(
+        let credential = match ticket_index {
+            None => prepare_for_spending(
                 voucher_value,
                 voucher_info,
                 serial_number,
@@ -83,14 +137,47 @@ impl<C, St: Storage> BandwidthController<C, St> {
                 &signature,
                 &verification_key,
             )?,
-            bandwidth_credential.id,
-        ))
+            Some(ticket_index) => {
+                let ticket = prepare_ticket_for_spending(
+                    voucher_value,
+                    voucher_info,
+                    serial_number,
+                    binding_number,
+                    ticket_index,
+                    epoch_id,
+                    &signature,
+                    &verification_key,
+                )?;
+
+                // mark the ticket as spent before it's even sent to the gateway,
+                // so that it would never get reused, even if it wasn't accepted
+                self.storage
+                    .insert_spent_ticket(bandwidth_credential.id, ticket_index)
+                    .await
+                    .map_err(|err| {
+                        BandwidthControllerError::CredentialStorageError(Box::new(err))
+                    })?;
+                ticket
+            }
+        };
+
+        Ok((credential, bandwidth_credential.id))
     }
 
     pub async fn consume_credential(&self, id: i64) -> Result<(), BandwidthControllerError>
     where
         <St as Storage>::StorageError: Send + Sync + 'static,
     {
+        // ticketbooks are only consumed once all of their tickets have been spent
+        let spent_tickets = self
+            .storage
+            .get_spent_tickets(id)
+            .await
+            .map_err(|err| BandwidthControllerError::CredentialStorageError(Box::new(err)))?;
+        if !spent_tickets.is_empty() && (spent_tickets.len() as u64) < TICKETBOOK_SIZE {
+            return Ok(());
+        }
+
         // JS: shouldn't we send some contract/validator/gateway message here to actually, you know,
         // consume it?
         self.storage
@@ -100,15 +187,11 @@ impl<C, St: Storage> BandwidthController<C, St> {
     }
 }
 
-impl<C, St> Clone for BandwidthController<C, St>
-where
-    C: Clone,
-    St: Storage + Clone,
-{
+impl<C, St: Storage> Clone for BandwidthController<C, St> {
     fn clone(&self) -> Self {
         BandwidthController {
-            storage: self.storage.clone(),
-            client: self.client.clone(),
+            storage: Arc::clone(&self.storage),
+            client: Arc::clone(&self.client),
         }
     }
 }
@@ -5,4 +5,5 @@ pub mod build_information;
 pub mod completions;
 pub mod logging;
 pub mod output_format;
+pub mod passphrase;
 pub mod version_checker;
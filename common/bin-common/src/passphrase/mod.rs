@@ -0,0 +1,186 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Args;
+use std::io;
+
+/// Arguments for supplying the passphrase used for encrypting the private keys stored on disk.
+/// Note that the passphrase itself is deliberately never accepted as a plain argument,
+/// so that it would not leak via the process list or the shell history.
+#[derive(Args, Clone, Debug, Default)]
+pub struct ArgKeyPassphrase {
+    /// File descriptor from which the passphrase for encrypting the private keys should be read.
+    #[clap(long, conflicts_with = "key_passphrase_env")]
+    pub key_passphrase_fd: Option<i32>,
+
+    /// Name of the environmental variable containing the passphrase for encrypting the private keys.
+    #[clap(long)]
+    pub key_passphrase_env: Option<String>,
+}
+
+impl ArgKeyPassphrase {
+    /// Attempts to read the passphrase from the specified source.
+    /// Returns `None` if no source has been specified.
+    pub fn read_passphrase(&self) -> io::Result<Option<String>> {
+        read_passphrase(
+            self.key_passphrase_fd,
+            self.key_passphrase_env.as_deref(),
+            "key",
+        )
+    }
+}
+
+/// Arguments for supplying the passphrase used for encrypting the bandwidth credentials stored on disk.
+/// Just like the key passphrase, it's deliberately never accepted as a plain argument.
+#[derive(Args, Clone, Debug, Default)]
+pub struct ArgCredentialsPassphrase {
+    /// File descriptor from which the passphrase for encrypting the stored bandwidth credentials should be read.
+    #[clap(long, conflicts_with = "credentials_passphrase_env")]
+    pub credentials_passphrase_fd: Option<i32>,
+
+    /// Name of the environmental variable containing the passphrase for encrypting the stored bandwidth credentials.
+    #[clap(long)]
+    pub credentials_passphrase_env: Option<String>,
+}
+
+impl ArgCredentialsPassphrase {
+    /// Attempts to read the passphrase from the specified source.
+    /// Returns `None` if no source has been specified.
+    pub fn read_passphrase(&self) -> io::Result<Option<String>> {
+        read_passphrase(
+            self.credentials_passphrase_fd,
+            self.credentials_passphrase_env.as_deref(),
+            "credentials",
+        )
+    }
+}
+
+/// Reads both the key and the credentials passphrases. If both of them are meant to be read from
+/// the same file descriptor, it's only read once and its content is used for both passphrases.
+pub fn read_key_and_credentials_passphrases(
+    key: &ArgKeyPassphrase,
+    credentials: &ArgCredentialsPassphrase,
+) -> io::Result<(Option<String>, Option<String>)> {
+    match (key.key_passphrase_fd, credentials.credentials_passphrase_fd) {
+        (Some(key_fd), Some(credentials_fd)) if key_fd == credentials_fd => {
+            let passphrase = read_passphrase(Some(key_fd), None, "key and credentials")?;
+            Ok((passphrase.clone(), passphrase))
+        }
+        _ => Ok((key.read_passphrase()?, credentials.read_passphrase()?)),
+    }
+}
+
+fn read_passphrase(fd: Option<i32>, env: Option<&str>, kind: &str) -> io::Result<Option<String>> {
+    let passphrase = if let Some(fd) = fd {
+        read_passphrase_from_fd(fd).map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!("could not read the {kind} passphrase from fd {fd}: {err}"),
+            )
+        })?
+    } else if let Some(var) = env {
+        std::env::var(var).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("could not read the {kind} passphrase from '{var}': {err}"),
+            )
+        })?
+    } else {
+        return Ok(None);
+    };
+
+    if passphrase.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("the provided {kind} passphrase is empty"),
+        ));
+    }
+
+    Ok(Some(passphrase))
+}
+
+#[cfg(unix)]
+fn read_passphrase_from_fd(fd: i32) -> io::Result<String> {
+    use std::io::Read;
+    use std::mem::ManuallyDrop;
+    use std::os::unix::io::FromRawFd;
+
+    if fd < 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "file descriptors can't be negative",
+        ));
+    }
+
+    // SAFETY: the caller explicitly handed us this descriptor to read the passphrase from.
+    // It's only borrowed, i.e. it's never closed on our side, as it might be, for example,
+    // the stdin or a descriptor that's still going to be used by somebody else.
+    let mut file = ManuallyDrop::new(unsafe { std::fs::File::from_raw_fd(fd) });
+    let mut passphrase = String::new();
+    file.read_to_string(&mut passphrase)?;
+
+    // get rid of the trailing newline, if any
+    let trimmed_len = passphrase.trim_end_matches(['\r', '\n']).len();
+    passphrase.truncate(trimmed_len);
+    Ok(passphrase)
+}
+
+#[cfg(not(unix))]
+fn read_passphrase_from_fd(_fd: i32) -> io::Result<String> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "reading the passphrase from a file descriptor is only supported on unix systems",
+    ))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::net::UnixStream;
+
+    fn passphrase_fd(passphrase: &str) -> UnixStream {
+        let (mut writer, reader) = UnixStream::pair().unwrap();
+        writer.write_all(passphrase.as_bytes()).unwrap();
+        reader
+    }
+
+    #[test]
+    fn descriptor_is_not_closed_after_reading() {
+        let reader = passphrase_fd("foomp\n");
+        let fd = reader.as_raw_fd();
+        assert_eq!(read_passphrase_from_fd(fd).unwrap(), "foomp");
+
+        // the descriptor is still open, there's just nothing more to read
+        assert_eq!(read_passphrase_from_fd(fd).unwrap(), "");
+        drop(reader);
+    }
+
+    #[test]
+    fn negative_descriptors_are_rejected() {
+        assert_eq!(
+            read_passphrase_from_fd(-1).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn shared_descriptor_is_read_once() {
+        let reader = passphrase_fd("foomp");
+        let fd = reader.as_raw_fd();
+        let key = ArgKeyPassphrase {
+            key_passphrase_fd: Some(fd),
+            key_passphrase_env: None,
+        };
+        let credentials = ArgCredentialsPassphrase {
+            credentials_passphrase_fd: Some(fd),
+            credentials_passphrase_env: None,
+        };
+
+        let (key_passphrase, credentials_passphrase) =
+            read_key_and_credentials_passphrases(&key, &credentials).unwrap();
+        assert_eq!(key_passphrase.as_deref(), Some("foomp"));
+        assert_eq!(credentials_passphrase.as_deref(), Some("foomp"));
+    }
+}
@@ -0,0 +1 @@
+CREATE INDEX reply_surb_sender_id_index ON reply_surb (reply_surb_sender_id);
@@ -0,0 +1,33 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::GatewayEndpointConfig;
+use nym_sphinx::addressing::clients::Recipient;
+use tokio::sync::watch;
+
+pub(crate) type ClientAddressSender = watch::Sender<ClientAddress>;
+pub type ClientAddressReceiver = watch::Receiver<ClientAddress>;
+
+pub(crate) fn new_address_channel(
+    initial: ClientAddress,
+) -> (ClientAddressSender, ClientAddressReceiver) {
+    watch::channel(initial)
+}
+
+/// The current address of the client alongside the gateway it's attached to.
+/// It might change throughout the lifetime of the client if it ever fails over to another gateway,
+/// in which case it has to be re-announced to anyone wishing to reach it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientAddress {
+    pub recipient: Recipient,
+    pub gateway_endpoint: GatewayEndpointConfig,
+}
+
+impl ClientAddress {
+    pub fn new(recipient: Recipient, gateway_endpoint: GatewayEndpointConfig) -> Self {
+        ClientAddress {
+            recipient,
+            gateway_endpoint,
+        }
+    }
+}
@@ -2,12 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::received_buffer::ReceivedBufferMessage;
+use crate::client::address::{new_address_channel, ClientAddress, ClientAddressReceiver};
 use crate::client::base_client::storage::MixnetClientStorage;
 use crate::client::cover_traffic_stream::LoopCoverTrafficStream;
+use crate::client::delivery_events::{
+    DeliveryEventReceiver, DeliveryListenerRegistrar, DeliveryListenerRegistrationReceiver,
+};
+use crate::client::gateway_failover::GatewayFailover;
 use crate::client::inbound_messages::{InputMessage, InputMessageReceiver, InputMessageSender};
 use crate::client::key_manager::persistence::KeyStore;
 use crate::client::key_manager::ManagedKeys;
-use crate::client::mix_traffic::{BatchMixMessageSender, MixTrafficController};
+use crate::client::local_gateway::LocalGateway;
+use crate::client::mix_traffic::{BatchMixMessageSender, GatewayTransceiver, MixTrafficController};
 use crate::client::real_messages_control;
 use crate::client::real_messages_control::RealMessagesController;
 use crate::client::received_buffer::{
@@ -22,7 +28,7 @@ use crate::client::topology_control::nym_api_provider::NymApiTopologyProvider;
 use crate::client::topology_control::{
     TopologyAccessor, TopologyRefresher, TopologyRefresherConfig,
 };
-use crate::config::{Config, DebugConfig, GatewayEndpointConfig};
+use crate::config::{Config, DebugConfig, GatewayEndpointConfig, MessageReconstruction};
 use crate::error::ClientCoreError;
 use crate::{config, spawn_future};
 use futures::channel::mpsc;
@@ -32,7 +38,7 @@ use nym_credential_storage::storage::Storage as CredentialStorage;
 use nym_crypto::asymmetric::{encryption, identity};
 use nym_gateway_client::{
     AcknowledgementReceiver, AcknowledgementSender, GatewayClient, MixnetMessageReceiver,
-    MixnetMessageSender,
+    MixnetMessageSender, PacketRouter,
 };
 use nym_sphinx::acknowledgements::AckKey;
 use nym_sphinx::addressing::clients::Recipient;
@@ -43,6 +49,7 @@ use nym_task::{TaskClient, TaskManager};
 use nym_topology::provider_trait::TopologyProvider;
 use rand::thread_rng;
 use std::sync::Arc;
+use std::time::Duration;
 use tap::TapFallible;
 use url::Url;
 
@@ -75,6 +82,7 @@ impl ClientInput {
 #[derive(Clone)]
 pub struct ClientOutput {
     pub received_buffer_request_sender: ReceivedBufferRequestSender,
+    pub delivery_listener_registrar: DeliveryListenerRegistrar,
 }
 
 impl ClientOutput {
@@ -91,6 +99,16 @@ impl ClientOutput {
 
         Ok(reconstructed_receiver)
     }
+
+    pub fn register_delivery_listener(&mut self) -> Result<DeliveryEventReceiver, ClientCoreError> {
+        let (delivery_event_sender, delivery_event_receiver) = mpsc::unbounded();
+
+        self.delivery_listener_registrar
+            .unbounded_send(delivery_event_sender)
+            .map_err(|_| ClientCoreError::FailedToRegisterDeliveryListener)?;
+
+        Ok(delivery_event_receiver)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -98,6 +116,9 @@ pub struct ClientState {
     pub shared_lane_queue_lengths: LaneQueueLengths,
     pub reply_controller_sender: ReplyControllerSender,
     pub topology_accessor: TopologyAccessor,
+
+    /// The current address of this client. It changes if the client ever fails over to another gateway.
+    pub client_address: ClientAddressReceiver,
 }
 
 pub enum ClientInputStatus {
@@ -164,6 +185,7 @@ pub struct BaseClientBuilder<'a, C, S: MixnetClientStorage> {
     key_store: S::KeyStore,
 
     custom_topology_provider: Option<Box<dyn TopologyProvider>>,
+    local_gateway: Option<Box<dyn LocalGateway>>,
     bandwidth_controller: Option<BandwidthController<C, S::CredentialStore>>,
     managed_keys: ManagedKeys,
 }
@@ -190,6 +212,7 @@ where
             key_store,
             managed_keys: ManagedKeys::Invalidated,
             custom_topology_provider: None,
+            local_gateway: None,
         }
     }
 
@@ -210,6 +233,7 @@ where
             nym_api_endpoints,
             reply_storage_backend,
             custom_topology_provider: None,
+            local_gateway: None,
             bandwidth_controller,
             key_store,
             managed_keys: ManagedKeys::Invalidated,
@@ -221,6 +245,14 @@ where
         self
     }
 
+    /// Makes the client use the provided gateway running within the same process instead of
+    /// connecting to the one specified in the gateway config. Note that the gateway id still
+    /// has to be set in the config as it determines the address of this client.
+    pub fn with_local_gateway(mut self, local_gateway: Box<dyn LocalGateway>) -> Self {
+        self.local_gateway = Some(local_gateway);
+        self
+    }
+
     // note: do **NOT** make this method public as its only valid usage is from within `start_base`
     // because it relies on the crypto keys being already loaded
     fn as_mix_recipient(&self) -> Recipient {
@@ -238,7 +270,7 @@ where
     fn start_cover_traffic_stream(
         debug_config: &DebugConfig,
         ack_key: Arc<AckKey>,
-        self_address: Recipient,
+        self_address: ClientAddressReceiver,
         topology_accessor: TopologyAccessor,
         mix_tx: BatchMixMessageSender,
         shutdown: TaskClient,
@@ -270,6 +302,7 @@ where
         reply_controller_receiver: ReplyControllerReceiver,
         lane_queue_lengths: LaneQueueLengths,
         client_connection_rx: ConnectionCommandReceiver,
+        delivery_listener_receiver: DeliveryListenerRegistrationReceiver,
         shutdown: TaskClient,
     ) {
         info!("Starting real traffic stream...");
@@ -285,6 +318,7 @@ where
             reply_controller_receiver,
             lane_queue_lengths,
             client_connection_rx,
+            delivery_listener_receiver,
         )
         .start_with_shutdown(shutdown);
     }
@@ -297,6 +331,7 @@ where
         mixnet_receiver: MixnetMessageReceiver,
         reply_key_storage: SentReplyKeys,
         reply_controller_sender: ReplyControllerSender,
+        reconstruction_config: MessageReconstruction,
         shutdown: TaskClient,
     ) {
         info!("Starting received messages buffer controller...");
@@ -307,6 +342,7 @@ where
                 mixnet_receiver,
                 reply_key_storage,
                 reply_controller_sender,
+                reconstruction_config,
             );
         controller.start_with_shutdown(shutdown)
     }
@@ -366,6 +402,22 @@ where
         Ok(gateway_client)
     }
 
+    async fn start_local_gateway(
+        &self,
+        mut local_gateway: Box<dyn LocalGateway>,
+        mixnet_message_sender: MixnetMessageSender,
+        ack_sender: AcknowledgementSender,
+        shutdown: TaskClient,
+    ) -> Result<Box<dyn LocalGateway>, ClientCoreError> {
+        let packet_router = PacketRouter::new(ack_sender, mixnet_message_sender, shutdown);
+        local_gateway
+            .register_client(*self.managed_keys.identity_public_key(), packet_router)
+            .await
+            .tap_err(|err| log::error!("Could not register with the local gateway - {err}"))?;
+
+        Ok(local_gateway)
+    }
+
     fn setup_topology_provider(
         custom_provider: Option<Box<dyn TopologyProvider>>,
         nym_api_urls: Vec<Url>,
@@ -427,14 +479,16 @@ where
     // over it. Perhaps GatewayClient needs to be thread-shareable or have some channel for
     // requests?
     fn start_mix_traffic_controller(
-        gateway_client: GatewayClient<C, S::CredentialStore>,
+        gateway_client: GatewayTransceiver<C, S::CredentialStore>,
+        gateway_failover: Option<GatewayFailover<C, S::CredentialStore>>,
         shutdown: TaskClient,
     ) -> BatchMixMessageSender
     where
         <S::CredentialStore as CredentialStorage>::StorageError: Send + Sync + 'static,
     {
         info!("Starting mix traffic controller...");
-        let (mix_traffic_controller, mix_tx) = MixTrafficController::new(gateway_client);
+        let (mix_traffic_controller, mix_tx) =
+            MixTrafficController::new(gateway_client, gateway_failover);
         mix_traffic_controller.start_with_shutdown(shutdown);
         mix_tx
     }
@@ -442,6 +496,7 @@ where
     // TODO: rename it as it implies the data is persistent whilst one can use InMemBackend
     async fn setup_persistent_reply_storage(
         backend: S::ReplyStore,
+        flush_interval: Duration,
         shutdown: TaskClient,
     ) -> Result<CombinedReplyStorage, ClientCoreError>
     where
@@ -460,7 +515,7 @@ where
         let store_clone = mem_store.clone();
         spawn_future(async move {
             persistent_storage
-                .flush_on_shutdown(store_clone, shutdown)
+                .run_with_shutdown(store_clone, flush_interval, shutdown)
                 .await
         });
 
@@ -510,15 +565,45 @@ where
             reply_controller::requests::new_control_channels();
 
         let self_address = self.as_mix_recipient();
+        let (client_address_sender, client_address) = new_address_channel(ClientAddress::new(
+            self_address,
+            self.gateway_config.clone(),
+        ));
+
+        let gateway_failover_enabled = self.debug_config.gateway_connection.enable_gateway_failover;
+
+        // if we're able to fail over to another gateway, losing the connection to the current one
+        // shouldn't bring the whole client down
+        let mut gateway_shutdown = task_manager.subscribe();
+        if gateway_failover_enabled {
+            gateway_shutdown.mark_as_success();
+        }
 
         // the components are started in very specific order. Unless you know what you are doing,
         // do not change that.
-        let gateway_client = self
-            .start_gateway_client(mixnet_messages_sender, ack_sender, task_manager.subscribe())
-            .await?;
+        let gateway_client = match self.local_gateway.take() {
+            Some(local_gateway) => GatewayTransceiver::Local(
+                self.start_local_gateway(
+                    local_gateway,
+                    mixnet_messages_sender.clone(),
+                    ack_sender.clone(),
+                    gateway_shutdown,
+                )
+                .await?,
+            ),
+            None => GatewayTransceiver::Remote(
+                self.start_gateway_client(
+                    mixnet_messages_sender.clone(),
+                    ack_sender.clone(),
+                    gateway_shutdown,
+                )
+                .await?,
+            ),
+        };
 
         let reply_storage = Self::setup_persistent_reply_storage(
             self.reply_storage_backend,
+            self.debug_config.reply_surbs.reply_storage_flush_interval,
             task_manager.subscribe(),
         )
         .await?;
@@ -541,15 +626,38 @@ where
             mixnet_messages_receiver,
             reply_storage.key_storage(),
             reply_controller_sender.clone(),
+            self.debug_config.message_reconstruction,
             task_manager.subscribe(),
         );
 
+        // there's nothing to fail over to if the gateway lives within the same process
+        let gateway_failover = match &gateway_client {
+            GatewayTransceiver::Remote(gateway_client) if gateway_failover_enabled => {
+                Some(GatewayFailover::new(
+                    self.debug_config.gateway_connection,
+                    self.disabled_credentials,
+                    self.managed_keys.identity_keypair(),
+                    *self.managed_keys.encryption_public_key(),
+                    shared_topology_accessor.clone(),
+                    mixnet_messages_sender,
+                    ack_sender,
+                    gateway_client.bandwidth_controller().cloned(),
+                    client_address_sender,
+                    task_manager.subscribe(),
+                ))
+            }
+            _ => None,
+        };
+
         // The sphinx_message_sender is the transmitter for any component generating sphinx packets
         // that are to be sent to the mixnet. They are used by cover traffic stream and real
         // traffic stream.
         // The MixTrafficController then sends the actual traffic
-        let sphinx_message_sender =
-            Self::start_mix_traffic_controller(gateway_client, task_manager.subscribe());
+        let sphinx_message_sender = Self::start_mix_traffic_controller(
+            gateway_client,
+            gateway_failover,
+            task_manager.subscribe(),
+        );
 
         // Channels that the websocket listener can use to signal downstream to the real traffic
         // controller that connections are closed.
@@ -559,10 +667,13 @@ where
         // primarily to throttle incoming connections (e.g socks5 for attached network-requesters)
         let shared_lane_queue_lengths = LaneQueueLengths::new();
 
+        // Channel used for subscribing to the delivery status of tracked messages
+        let (delivery_listener_registrar, delivery_listener_receiver) = mpsc::unbounded();
+
         let controller_config = real_messages_control::Config::new(
             self.debug_config,
             self.managed_keys.ack_key(),
-            self_address,
+            client_address.clone(),
         );
 
         Self::start_real_traffic_controller(
@@ -576,6 +687,7 @@ where
             reply_controller_receiver,
             shared_lane_queue_lengths.clone(),
             client_connection_rx,
+            delivery_listener_receiver,
             task_manager.subscribe(),
         );
 
@@ -587,7 +699,7 @@ where
             Self::start_cover_traffic_stream(
                 self.debug_config,
                 self.managed_keys.ack_key(),
-                self_address,
+                client_address.clone(),
                 shared_topology_accessor.clone(),
                 sphinx_message_sender,
                 task_manager.subscribe(),
@@ -608,12 +720,14 @@ where
             client_output: ClientOutputStatus::AwaitingConsumer {
                 client_output: ClientOutput {
                     received_buffer_request_sender,
+                    delivery_listener_registrar,
                 },
             },
             client_state: ClientState {
                 shared_lane_queue_lengths,
                 reply_controller_sender,
                 topology_accessor: shared_topology_accessor,
+                client_address,
             },
             task_manager,
         })
@@ -1,6 +1,7 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::address::ClientAddressReceiver;
 use crate::client::mix_traffic::BatchMixMessageSender;
 use crate::client::topology_control::TopologyAccessor;
 use crate::{config, spawn_future};
@@ -8,7 +9,6 @@ use futures::task::{Context, Poll};
 use futures::{Future, Stream, StreamExt};
 use log::*;
 use nym_sphinx::acknowledgements::AckKey;
-use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::cover::generate_loop_cover_packet;
 use nym_sphinx::params::PacketSize;
 use nym_sphinx::utils::sample_poisson_duration;
@@ -50,7 +50,7 @@ where
     mix_tx: BatchMixMessageSender,
 
     /// Represents full address of this client.
-    our_full_destination: Recipient,
+    our_full_destination: ClientAddressReceiver,
 
     /// Instance of a cryptographically secure random number generator.
     rng: R,
@@ -111,7 +111,7 @@ impl LoopCoverTrafficStream<OsRng> {
         ack_key: Arc<AckKey>,
         average_ack_delay: Duration,
         mix_tx: BatchMixMessageSender,
-        our_full_destination: Recipient,
+        our_full_destination: ClientAddressReceiver,
         topology_access: TopologyAccessor,
         traffic_config: config::Traffic,
         cover_config: config::CoverTraffic,
@@ -173,12 +173,12 @@ impl LoopCoverTrafficStream<OsRng> {
         // TODO for way down the line: in very rare cases (during topology update) we might have
         // to wait a really tiny bit before actually obtaining the permit hence messing with our
         // poisson delay, but is it really a problem?
+        let our_full_destination = self.our_full_destination.borrow().recipient;
         let topology_permit = self.topology_access.get_read_permit().await;
         // the ack is sent back to ourselves (and then ignored)
-        let topology_ref = match topology_permit.try_get_valid_topology_ref(
-            &self.our_full_destination,
-            Some(&self.our_full_destination),
-        ) {
+        let topology_ref = match topology_permit
+            .try_get_valid_topology_ref(&our_full_destination, Some(&our_full_destination))
+        {
             Ok(topology) => topology,
             Err(err) => {
                 warn!("We're not going to send any loop cover message this time, as the current topology seem to be invalid - {err}");
@@ -190,7 +190,7 @@ impl LoopCoverTrafficStream<OsRng> {
             &mut self.rng,
             topology_ref,
             &self.ack_key,
-            &self.our_full_destination,
+            &our_full_destination,
             self.average_ack_delay,
             self.cover_traffic.loop_cover_traffic_average_delay,
             cover_traffic_packet_size,
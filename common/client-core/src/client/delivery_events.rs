@@ -0,0 +1,69 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::channel::mpsc;
+use rand::{rngs::OsRng, RngCore};
+use std::fmt::{self, Display, Formatter};
+
+/// Channel used for notifying listeners about the delivery status of tracked messages.
+pub type DeliveryEventSender = mpsc::UnboundedSender<DeliveryEvent>;
+
+/// Receiver part of the [`DeliveryEventSender`]
+pub type DeliveryEventReceiver = mpsc::UnboundedReceiver<DeliveryEvent>;
+
+/// Channel used for registering new delivery event listeners with the `ActionController`.
+pub type DeliveryListenerRegistrar = mpsc::UnboundedSender<DeliveryEventSender>;
+
+/// Receiver part of the [`DeliveryListenerRegistrar`]
+pub(crate) type DeliveryListenerRegistrationReceiver = mpsc::UnboundedReceiver<DeliveryEventSender>;
+
+/// Identifier of a message whose delivery is being tracked by the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MessageId(u64);
+
+impl MessageId {
+    pub fn new_random() -> Self {
+        MessageId(OsRng.next_u64())
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for MessageId {
+    fn from(value: u64) -> Self {
+        MessageId(value)
+    }
+}
+
+impl Display for MessageId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Change in the delivery status of a tracked message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryEvent {
+    /// All fragments of the message got acknowledged.
+    Delivered(MessageId),
+
+    /// One of the fragments of the message has not been acknowledged in time
+    /// and is going to be retransmitted.
+    Retransmitting(MessageId),
+
+    /// One of the fragments of the message has exceeded the maximum number of retransmissions
+    /// and thus the client has given up on delivering the message.
+    GivenUp(MessageId),
+}
+
+impl DeliveryEvent {
+    pub fn message_id(&self) -> MessageId {
+        match self {
+            DeliveryEvent::Delivered(id)
+            | DeliveryEvent::Retransmitting(id)
+            | DeliveryEvent::GivenUp(id) => *id,
+        }
+    }
+}
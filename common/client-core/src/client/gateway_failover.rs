@@ -0,0 +1,573 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::address::{ClientAddress, ClientAddressReceiver, ClientAddressSender};
+use crate::client::topology_control::TopologyAccessor;
+use crate::config::{GatewayConnection, GatewayEndpointConfig};
+use crate::error::{ClientCoreError, ClientCoreStatusMessage};
+use crate::init::helpers::rank_gateways_by_latency;
+use crate::spawn_future;
+use futures::StreamExt;
+use log::{debug, info, trace, warn};
+use nym_bandwidth_controller::BandwidthController;
+use nym_credential_storage::storage::Storage;
+use nym_crypto::asymmetric::{encryption, identity};
+use nym_gateway_client::{AcknowledgementSender, GatewayClient, MixnetMessageSender};
+use nym_sphinx::addressing::clients::Recipient;
+use nym_task::TaskClient;
+use nym_topology::gateway;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, Notify};
+
+#[cfg(target_arch = "wasm32")]
+use nym_bandwidth_controller::wasm_mockups::DkgQueryClient;
+#[cfg(not(target_arch = "wasm32"))]
+use nym_validator_client::nyxd::traits::DkgQueryClient;
+
+// we don't want to be measuring latency to every single gateway on the network,
+// so only a random sample (of this many times the number of fallbacks we want) is considered
+const CANDIDATE_SAMPLE_FACTOR: usize = 4;
+
+// how often the fallback candidates are re-ranked, unless explicitly requested sooner
+const CANDIDATE_RANKING_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Chooses up to `amount` random gateways out of the provided ones, ignoring the `excluded` ones.
+fn sample_candidates<R: Rng>(
+    rng: &mut R,
+    gateways: &[gateway::Node],
+    excluded: &[identity::PublicKey],
+    amount: usize,
+) -> Vec<gateway::Node> {
+    let candidates = gateways
+        .iter()
+        .filter(|gateway| !excluded.contains(&gateway.identity_key))
+        .collect::<Vec<_>>();
+
+    candidates
+        .choose_multiple(rng, amount)
+        .map(|gateway| (*gateway).clone())
+        .collect()
+}
+
+/// Chooses up to `amount` best ranked candidates, ignoring the `excluded` ones.
+fn next_candidates(
+    ranked: &[gateway::Node],
+    excluded: &[identity::PublicKey],
+    amount: usize,
+) -> Vec<gateway::Node> {
+    ranked
+        .iter()
+        .filter(|gateway| !excluded.contains(&gateway.identity_key))
+        .take(amount)
+        .cloned()
+        .collect()
+}
+
+/// Background task periodically measuring latency to a sample of the gateways on the network
+/// so that the failover wouldn't have to do it while the client is unable to send any packets.
+struct CandidateRanker {
+    topology_accessor: TopologyAccessor,
+    client_address: ClientAddressReceiver,
+    sample_size: usize,
+    ranking_request: Arc<Notify>,
+    ranked_candidates: watch::Sender<Vec<gateway::Node>>,
+}
+
+impl CandidateRanker {
+    fn current_gateway(&self) -> Option<identity::PublicKey> {
+        identity::PublicKey::from_base58_string(
+            &self.client_address.borrow().gateway_endpoint.gateway_id,
+        )
+        .ok()
+    }
+
+    async fn rank_candidates(&self) {
+        let Some(topology) = self.topology_accessor.current_topology().await else {
+            warn!("there's no valid network topology available - can't rank any fallback gateways");
+            return;
+        };
+
+        let excluded = self.current_gateway().into_iter().collect::<Vec<_>>();
+        let candidates = sample_candidates(
+            &mut thread_rng(),
+            topology.gateways(),
+            &excluded,
+            self.sample_size,
+        );
+
+        trace!(
+            "measuring latency to {} fallback gateway candidates",
+            candidates.len()
+        );
+        let ranked = rank_gateways_by_latency(candidates, self.sample_size).await;
+        debug!("ranked {} fallback gateway candidates", ranked.len());
+
+        self.ranked_candidates.send_replace(ranked);
+    }
+
+    fn start_with_shutdown(self, mut shutdown: TaskClient) {
+        spawn_future(async move {
+            debug!("Started CandidateRanker with graceful shutdown support");
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let mut interval = tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(
+                CANDIDATE_RANKING_INTERVAL,
+            ));
+
+            #[cfg(target_arch = "wasm32")]
+            let mut interval = gloo_timers::future::IntervalStream::new(
+                CANDIDATE_RANKING_INTERVAL.as_millis() as u32,
+            );
+
+            while !shutdown.is_shutdown() {
+                tokio::select! {
+                    _ = interval.next() => {
+                        self.rank_candidates().await;
+                    },
+                    _ = self.ranking_request.notified() => {
+                        self.rank_candidates().await;
+                    },
+                    _ = shutdown.recv() => {
+                        log::trace!("CandidateRanker: Received shutdown");
+                    },
+                }
+            }
+            shutdown.recv_timeout().await;
+            log::debug!("CandidateRanker: Exiting");
+        })
+    }
+}
+
+struct SecondaryGateway<C, St: Storage> {
+    endpoint: GatewayEndpointConfig,
+    client: GatewayClient<C, St>,
+}
+
+/// Responsible for moving the client over to a different gateway, chosen out of the candidates
+/// ranked in the background by the [`CandidateRanker`], once its current gateway is considered to be dead.
+///
+/// Note that the failover only lasts for the lifetime of the client - the new gateway is not persisted,
+/// and upon restart the client is going to attempt to use its originally registered gateway again.
+pub struct GatewayFailover<C, St: Storage> {
+    config: GatewayConnection,
+    disabled_credentials: bool,
+
+    local_identity: Arc<identity::KeyPair>,
+    local_encryption_key: encryption::PublicKey,
+
+    mixnet_message_sender: MixnetMessageSender,
+    ack_sender: AcknowledgementSender,
+    bandwidth_controller: Option<BandwidthController<C, St>>,
+
+    client_address: ClientAddressSender,
+
+    /// Fallback gateways, ranked by their latency, as last measured by the [`CandidateRanker`].
+    ranked_candidates: watch::Receiver<Vec<gateway::Node>>,
+
+    /// Used for asking the [`CandidateRanker`] to re-rank the candidates ahead of its schedule.
+    ranking_request: Arc<Notify>,
+
+    /// Gateways that we've already failed to use and thus are not going to be considered again.
+    failed_gateways: Vec<identity::PublicKey>,
+
+    /// Already authenticated connection to another gateway, if the secondary gateway mode is enabled.
+    secondary: Option<SecondaryGateway<C, St>>,
+
+    /// Task client given to all the newly created gateway clients. It's marked as a success
+    /// so that a dead gateway connection wouldn't bring the whole client down with it.
+    shutdown: TaskClient,
+}
+
+impl<C, St> GatewayFailover<C, St>
+where
+    C: Sync + Send + 'static,
+    St: Storage + 'static,
+    <St as Storage>::StorageError: Send + Sync + 'static,
+{
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        config: GatewayConnection,
+        disabled_credentials: bool,
+        local_identity: Arc<identity::KeyPair>,
+        local_encryption_key: encryption::PublicKey,
+        topology_accessor: TopologyAccessor,
+        mixnet_message_sender: MixnetMessageSender,
+        ack_sender: AcknowledgementSender,
+        bandwidth_controller: Option<BandwidthController<C, St>>,
+        client_address: ClientAddressSender,
+        mut shutdown: TaskClient,
+    ) -> Self {
+        shutdown.mark_as_success();
+
+        let ranking_request = Arc::new(Notify::new());
+        let (ranked_sender, ranked_candidates) = watch::channel(Vec::new());
+
+        CandidateRanker {
+            topology_accessor,
+            client_address: client_address.subscribe(),
+            sample_size: config.maximum_fallback_gateways * CANDIDATE_SAMPLE_FACTOR,
+            ranking_request: Arc::clone(&ranking_request),
+            ranked_candidates: ranked_sender,
+        }
+        .start_with_shutdown(shutdown.clone());
+
+        GatewayFailover {
+            config,
+            disabled_credentials,
+            local_identity,
+            local_encryption_key,
+            mixnet_message_sender,
+            ack_sender,
+            bandwidth_controller,
+            client_address,
+            ranked_candidates,
+            ranking_request,
+            failed_gateways: Vec::new(),
+            secondary: None,
+            shutdown,
+        }
+    }
+
+    pub(crate) fn failover_threshold(&self) -> usize {
+        self.config.gateway_failover_threshold
+    }
+
+    /// Resolves once the [`CandidateRanker`] has published a new ranking of the fallback gateways.
+    /// If the ranker is no longer running, it never resolves.
+    pub(crate) async fn candidates_updated(&mut self) {
+        if self.ranked_candidates.changed().await.is_err() {
+            futures::future::pending::<()>().await
+        }
+    }
+
+    fn current_gateway(&self) -> Option<identity::PublicKey> {
+        identity::PublicKey::from_base58_string(
+            &self.client_address.borrow().gateway_endpoint.gateway_id,
+        )
+        .ok()
+    }
+
+    fn excluded_gateways(&self) -> Vec<identity::PublicKey> {
+        let mut excluded = self.failed_gateways.clone();
+        excluded.extend(self.current_gateway());
+        excluded.extend(
+            self.secondary
+                .as_ref()
+                .map(|secondary| secondary.client.gateway_identity()),
+        );
+        excluded
+    }
+
+    // every gateway client gets its own clone of the bandwidth controller,
+    // so that all of them could keep on claiming bandwidth using the same credentials
+    fn new_gateway_client(&self, gateway: &gateway::Node) -> GatewayClient<C, St> {
+        let mut gateway_client = GatewayClient::new(
+            gateway.clients_address(),
+            Arc::clone(&self.local_identity),
+            gateway.identity_key,
+            None,
+            self.mixnet_message_sender.clone(),
+            self.ack_sender.clone(),
+            self.config.gateway_response_timeout,
+            self.bandwidth_controller.clone(),
+            self.shutdown.clone(),
+        );
+        gateway_client.set_disabled_credentials_mode(self.disabled_credentials);
+        gateway_client
+    }
+}
+
+impl<C, St> GatewayFailover<C, St>
+where
+    C: DkgQueryClient + Sync + Send + 'static,
+    St: Storage + 'static,
+    <St as Storage>::StorageError: Send + Sync + 'static,
+{
+    async fn register_with(
+        &self,
+        gateway: &gateway::Node,
+    ) -> Result<GatewayClient<C, St>, ClientCoreError> {
+        let mut gateway_client = self.new_gateway_client(gateway);
+        gateway_client.authenticate_and_start().await?;
+        Ok(gateway_client)
+    }
+
+    async fn register_with_fallback(
+        &mut self,
+    ) -> Option<(GatewayEndpointConfig, GatewayClient<C, St>)> {
+        let fallbacks = next_candidates(
+            &self.ranked_candidates.borrow(),
+            &self.excluded_gateways(),
+            self.config.maximum_fallback_gateways,
+        );
+        if fallbacks.is_empty() {
+            warn!("there are no ranked fallback gateways available");
+            self.ranking_request.notify_one();
+            return None;
+        }
+
+        for gateway in fallbacks {
+            let id = gateway.identity_key;
+            info!("attempting to register with gateway {id}...");
+            match self.register_with(&gateway).await {
+                Ok(gateway_client) => return Some((gateway.into(), gateway_client)),
+                Err(err) => {
+                    warn!("failed to register with gateway {id}: {err}");
+                    self.failed_gateways.push(id);
+                }
+            }
+        }
+
+        // all of the best candidates have failed us, so get some new ones
+        self.ranking_request.notify_one();
+        None
+    }
+
+    /// Makes sure there's an authenticated connection to a secondary gateway
+    /// that could be switched to immediately, if that mode is enabled.
+    pub(crate) async fn ensure_secondary_gateway(&mut self) {
+        if !self.config.enable_secondary_gateway || self.secondary.is_some() {
+            return;
+        }
+
+        match self.register_with_fallback().await {
+            Some((endpoint, client)) => {
+                info!(
+                    "established connection to the secondary gateway {}",
+                    endpoint.gateway_id
+                );
+                self.secondary = Some(SecondaryGateway { endpoint, client })
+            }
+            None => warn!("failed to establish connection to any secondary gateway"),
+        }
+    }
+
+    fn announce_new_address(
+        &mut self,
+        gateway_identity: identity::PublicKey,
+        gateway_endpoint: GatewayEndpointConfig,
+    ) {
+        let recipient = Recipient::new(
+            *self.local_identity.public_key(),
+            self.local_encryption_key,
+            gateway_identity,
+        );
+
+        info!("the address of this client has changed to {recipient}");
+        self.shutdown
+            .send_status_msg(Box::new(ClientCoreStatusMessage::GatewayFailover {
+                gateway: gateway_endpoint.gateway_id.clone(),
+                address: recipient,
+            }));
+        self.client_address
+            .send_replace(ClientAddress::new(recipient, gateway_endpoint));
+    }
+
+    /// Attempts to move the client over to another gateway (either the secondary one, if available,
+    /// or the best ranked responsive fallback). If successful, returns the client for the new gateway.
+    pub(crate) async fn fail_over(&mut self) -> Option<GatewayClient<C, St>> {
+        if let Some(current) = self.current_gateway() {
+            warn!("gateway {current} is considered to be dead - attempting to fail over to another one");
+            if !self.failed_gateways.contains(&current) {
+                self.failed_gateways.push(current);
+            }
+        }
+
+        let (gateway_endpoint, gateway_client) = match self.secondary.take() {
+            Some(secondary) => (secondary.endpoint, secondary.client),
+            None => match self.register_with_fallback().await {
+                Some(fallback) => fallback,
+                None => {
+                    warn!("failed to fail over to any of the fallback gateways");
+                    return None;
+                }
+            },
+        };
+
+        debug!("failing over to gateway {}", gateway_endpoint.gateway_id);
+        self.announce_new_address(gateway_client.gateway_identity(), gateway_endpoint);
+        self.ensure_secondary_gateway().await;
+        self.ranking_request.notify_one();
+
+        Some(gateway_client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::address::new_address_channel;
+    use futures::channel::mpsc;
+    use nym_credential_storage::ephemeral_storage::EphemeralStorage;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn gateways(rng: &mut StdRng, n: usize) -> Vec<gateway::Node> {
+        (0..n)
+            .map(|i| gateway::Node {
+                owner: format!("owner{i}"),
+                host: "1.2.3.4".parse().unwrap(),
+                mix_host: "1.2.3.4:1789".parse().unwrap(),
+                clients_port: 9000,
+                identity_key: *identity::KeyPair::new(rng).public_key(),
+                sphinx_key: *encryption::KeyPair::new(rng).public_key(),
+                sphinx_key_validity: None,
+                announced_sphinx_keys: None,
+                version: "1.1.14".to_string(),
+            })
+            .collect()
+    }
+
+    fn ids(gateways: &[gateway::Node]) -> Vec<identity::PublicKey> {
+        gateways
+            .iter()
+            .map(|gateway| gateway.identity_key)
+            .collect()
+    }
+
+    #[test]
+    fn sampled_candidates_are_unique_and_not_excluded() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let all = gateways(&mut rng, 20);
+        let excluded = ids(&all[..5]);
+
+        let sampled = sample_candidates(&mut rng, &all, &excluded, 8);
+        let sampled_ids = ids(&sampled);
+        assert_eq!(sampled.len(), 8);
+        for id in &sampled_ids {
+            assert!(!excluded.contains(id));
+            assert_eq!(sampled_ids.iter().filter(|other| *other == id).count(), 1);
+        }
+    }
+
+    #[test]
+    fn sampled_candidates_are_limited_by_available_gateways() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let all = gateways(&mut rng, 6);
+        let excluded = ids(&all[..2]);
+
+        let sampled = sample_candidates(&mut rng, &all, &excluded, 10);
+        assert_eq!(sampled.len(), 4);
+
+        assert!(sample_candidates(&mut rng, &all, &ids(&all), 10).is_empty());
+    }
+
+    #[test]
+    fn next_candidates_follow_the_ranking() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let ranked = gateways(&mut rng, 5);
+
+        let next = next_candidates(&ranked, &[], 3);
+        assert_eq!(ids(&next), ids(&ranked[..3]));
+    }
+
+    #[test]
+    fn failed_over_gateway_is_followed_by_the_next_best_candidate() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let ranked = gateways(&mut rng, 5);
+
+        // we've failed over to the best candidate and then it died as well,
+        // while the third one failed during registration
+        let excluded = vec![ranked[0].identity_key, ranked[2].identity_key];
+        let next = next_candidates(&ranked, &excluded, 2);
+        assert_eq!(
+            ids(&next),
+            vec![ranked[1].identity_key, ranked[3].identity_key]
+        );
+
+        // eventually we run out of candidates altogether
+        assert!(next_candidates(&ranked, &ids(&ranked), 2).is_empty());
+    }
+
+    #[tokio::test]
+    async fn bandwidth_can_be_claimed_by_both_the_old_and_the_new_gateway_clients() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let nodes = gateways(&mut rng, 2);
+        let local_identity = Arc::new(identity::KeyPair::new(&mut rng));
+        let local_encryption_key = *encryption::KeyPair::new(&mut rng).public_key();
+        let (mixnet_message_sender, _) = mpsc::unbounded();
+        let (ack_sender, _) = mpsc::unbounded();
+
+        let storage = EphemeralStorage::default();
+        for _ in 0..2 {
+            storage
+                .insert_coconut_credential(
+                    "100".to_string(),
+                    "BandwidthVoucher".to_string(),
+                    "serial".to_string(),
+                    "binding".to_string(),
+                    "signature".to_string(),
+                    "1".to_string(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let primary: GatewayClient<(), EphemeralStorage> = GatewayClient::new(
+            nodes[0].clients_address(),
+            Arc::clone(&local_identity),
+            nodes[0].identity_key,
+            None,
+            mixnet_message_sender.clone(),
+            ack_sender.clone(),
+            Duration::from_secs(1),
+            Some(BandwidthController::new(storage, ())),
+            TaskClient::dummy(),
+        );
+
+        let recipient = Recipient::new(
+            *local_identity.public_key(),
+            local_encryption_key,
+            nodes[0].identity_key,
+        );
+        let (client_address, _) =
+            new_address_channel(ClientAddress::new(recipient, nodes[0].clone().into()));
+        let failover = GatewayFailover::new(
+            GatewayConnection::default(),
+            false,
+            local_identity,
+            local_encryption_key,
+            TopologyAccessor::new(),
+            mixnet_message_sender,
+            ack_sender,
+            primary.bandwidth_controller().cloned(),
+            client_address,
+            TaskClient::dummy(),
+        );
+        let fallback = failover.new_gateway_client(&nodes[1]);
+
+        // the new client claims bandwidth using one of the shared credentials...
+        let fallback_controller = fallback.bandwidth_controller().unwrap();
+        let claimed = fallback_controller
+            .storage()
+            .get_next_coconut_credential()
+            .await
+            .unwrap();
+        fallback_controller
+            .consume_credential(claimed.id)
+            .await
+            .unwrap();
+
+        // ...while the old one is still able to claim the remaining one
+        let primary_controller = primary.bandwidth_controller().unwrap();
+        let remaining = primary_controller
+            .storage()
+            .get_next_coconut_credential()
+            .await
+            .unwrap();
+        assert_ne!(claimed.id, remaining.id);
+        primary_controller
+            .consume_credential(remaining.id)
+            .await
+            .unwrap();
+
+        assert!(fallback_controller
+            .storage()
+            .get_next_coconut_credential()
+            .await
+            .is_err());
+    }
+}
@@ -1,8 +1,10 @@
 // Copyright 2020-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::delivery_events::MessageId;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nym_sphinx::chunking::ErasureCoding;
 use nym_sphinx::forwarding::packet::MixPacket;
 use nym_task::connections::TransmissionLane;
 
@@ -53,6 +55,25 @@ pub enum InputMessage {
         data: Vec<u8>,
         lane: TransmissionLane,
     },
+
+    /// Wraps the underlying message so that its delivery would be tracked and the relevant
+    /// `DeliveryEvent`s would be emitted.
+    ///
+    /// Note that currently only `Regular` and `Anonymous` messages can be tracked.
+    Tracked {
+        message_id: MessageId,
+        message: Box<InputMessage>,
+    },
+
+    /// Wraps the underlying message so that it would be sent with the specified forward error
+    /// correction rather than the default one from the client configuration.
+    /// Setting `erasure_coding` to `None` disables it for this particular message.
+    ///
+    /// Note that it is not applicable to `Premade` messages.
+    WithErasureCoding {
+        erasure_coding: Option<ErasureCoding>,
+        message: Box<InputMessage>,
+    },
 }
 
 impl InputMessage {
@@ -94,12 +115,31 @@ impl InputMessage {
         }
     }
 
+    pub fn new_tracked(message_id: MessageId, message: InputMessage) -> Self {
+        InputMessage::Tracked {
+            message_id,
+            message: Box::new(message),
+        }
+    }
+
+    pub fn new_with_erasure_coding(
+        erasure_coding: Option<ErasureCoding>,
+        message: InputMessage,
+    ) -> Self {
+        InputMessage::WithErasureCoding {
+            erasure_coding,
+            message: Box::new(message),
+        }
+    }
+
     pub fn lane(&self) -> &TransmissionLane {
         match self {
             InputMessage::Regular { lane, .. }
             | InputMessage::Anonymous { lane, .. }
             | InputMessage::Reply { lane, .. }
             | InputMessage::Premade { lane, .. } => lane,
+            InputMessage::Tracked { message, .. }
+            | InputMessage::WithErasureCoding { message, .. } => message.lane(),
         }
     }
 }
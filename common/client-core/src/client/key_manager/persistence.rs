@@ -64,19 +64,37 @@ pub enum OnDiskKeysError {
 #[cfg(not(target_arch = "wasm32"))]
 pub struct OnDiskKeys {
     pathfinder: ClientKeyPathfinder,
+
+    /// Optional passphrase used for encrypting the private keys stored on disk.
+    key_passphrase: Option<String>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl From<ClientKeyPathfinder> for OnDiskKeys {
     fn from(pathfinder: ClientKeyPathfinder) -> Self {
-        OnDiskKeys { pathfinder }
+        OnDiskKeys::new(pathfinder)
     }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl OnDiskKeys {
     pub fn new(pathfinder: ClientKeyPathfinder) -> Self {
-        OnDiskKeys { pathfinder }
+        OnDiskKeys {
+            pathfinder,
+            key_passphrase: None,
+        }
+    }
+
+    /// Encrypt the private keys with the provided passphrase (if any). Note that any existing
+    /// plaintext keys are still going to be loaded, but they will be encrypted on the next store.
+    #[must_use]
+    pub fn with_key_passphrase(mut self, key_passphrase: Option<String>) -> Self {
+        self.key_passphrase = key_passphrase;
+        self
+    }
+
+    fn passphrase(&self) -> Option<&[u8]> {
+        self.key_passphrase.as_deref().map(str::as_bytes)
     }
 
     fn load_key<T: PemStorableKey>(
@@ -84,10 +102,12 @@ impl OnDiskKeys {
         path: &std::path::Path,
         name: impl Into<String>,
     ) -> Result<T, OnDiskKeysError> {
-        nym_pemstore::load_key(path).map_err(|err| OnDiskKeysError::KeyLoadFailure {
-            key: name.into(),
-            path: path.to_str().map(|s| s.to_owned()).unwrap_or_default(),
-            err,
+        nym_pemstore::load_key_with_passphrase(path, self.passphrase()).map_err(|err| {
+            OnDiskKeysError::KeyLoadFailure {
+                key: name.into(),
+                path: path.to_str().map(|s| s.to_owned()).unwrap_or_default(),
+                err,
+            }
         })
     }
 
@@ -96,10 +116,12 @@ impl OnDiskKeys {
         paths: KeyPairPath,
         name: impl Into<String>,
     ) -> Result<T, OnDiskKeysError> {
-        nym_pemstore::load_keypair(&paths).map_err(|err| OnDiskKeysError::KeyPairLoadFailure {
-            keys: name.into(),
-            paths,
-            err,
+        nym_pemstore::load_keypair_with_passphrase(&paths, self.passphrase()).map_err(|err| {
+            OnDiskKeysError::KeyPairLoadFailure {
+                keys: name.into(),
+                paths,
+                err,
+            }
         })
     }
 
@@ -109,10 +131,12 @@ impl OnDiskKeys {
         path: &std::path::Path,
         name: impl Into<String>,
     ) -> Result<(), OnDiskKeysError> {
-        nym_pemstore::store_key(key, path).map_err(|err| OnDiskKeysError::KeyStoreFailure {
-            key: name.into(),
-            path: path.to_str().map(|s| s.to_owned()).unwrap_or_default(),
-            err,
+        nym_pemstore::store_key_with_passphrase(key, path, self.passphrase()).map_err(|err| {
+            OnDiskKeysError::KeyStoreFailure {
+                key: name.into(),
+                path: path.to_str().map(|s| s.to_owned()).unwrap_or_default(),
+                err,
+            }
         })
     }
 
@@ -122,13 +146,13 @@ impl OnDiskKeys {
         paths: KeyPairPath,
         name: impl Into<String>,
     ) -> Result<(), OnDiskKeysError> {
-        nym_pemstore::store_keypair(keys, &paths).map_err(|err| {
-            OnDiskKeysError::KeyPairStoreFailure {
+        nym_pemstore::store_keypair_with_passphrase(keys, &paths, self.passphrase()).map_err(
+            |err| OnDiskKeysError::KeyPairStoreFailure {
                 keys: name.into(),
                 paths,
                 err,
-            }
-        })
+            },
+        )
     }
 
     fn load_keys(&self) -> Result<KeyManager, OnDiskKeysError> {
@@ -152,6 +176,14 @@ impl OnDiskKeys {
         ))
     }
 
+    /// Loads all the stored keys and stores them again, so that any plaintext keys would get
+    /// encrypted with the current passphrase. Note that the keys that are already encrypted
+    /// can only be loaded if they were encrypted with that same passphrase.
+    pub fn encrypt_stored_keys(&self) -> Result<(), OnDiskKeysError> {
+        let keys = self.load_keys()?;
+        self.store_keys(&keys)
+    }
+
     fn store_keys(&self, keys: &KeyManager) -> Result<(), OnDiskKeysError> {
         let identity_paths = self.pathfinder.identity_key_pair_path();
         let encryption_paths = self.pathfinder.encryption_key_pair_path();
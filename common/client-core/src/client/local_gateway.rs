@@ -0,0 +1,39 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use nym_crypto::asymmetric::identity;
+use nym_gateway_client::error::GatewayClientError;
+use nym_gateway_client::packet_router::PacketRouter;
+use nym_sphinx::forwarding::packet::MixPacket;
+
+/// Gateway running within the same process as the client, for example as a part of a simulated
+/// mixnet. When used, the client does not establish the websocket connection and instead
+/// exchanges all the packets with the gateway directly.
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+pub trait LocalGateway: Send {
+    /// Registers the client with the provided identity so that any packets the gateway
+    /// receives for it would get pushed through the `packet_router`.
+    async fn register_client(
+        &mut self,
+        client: identity::PublicKey,
+        packet_router: PacketRouter,
+    ) -> Result<(), GatewayClientError>;
+
+    async fn send_mix_packets(&mut self, packets: Vec<MixPacket>)
+        -> Result<(), GatewayClientError>;
+}
+
+#[cfg(target_arch = "wasm32")]
+#[async_trait(?Send)]
+pub trait LocalGateway {
+    async fn register_client(
+        &mut self,
+        client: identity::PublicKey,
+        packet_router: PacketRouter,
+    ) -> Result<(), GatewayClientError>;
+
+    async fn send_mix_packets(&mut self, packets: Vec<MixPacket>)
+        -> Result<(), GatewayClientError>;
+}
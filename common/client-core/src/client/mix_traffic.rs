@@ -1,8 +1,11 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::gateway_failover::GatewayFailover;
+use crate::client::local_gateway::LocalGateway;
 use crate::spawn_future;
 use log::*;
+use nym_gateway_client::error::GatewayClientError;
 use nym_gateway_client::GatewayClient;
 use nym_sphinx::forwarding::packet::MixPacket;
 
@@ -20,15 +23,54 @@ pub type BatchMixMessageReceiver = tokio::sync::mpsc::Receiver<Vec<MixPacket>>;
 pub const MIX_MESSAGE_RECEIVER_BUFFER_SIZE: usize = 32;
 const MAX_FAILURE_COUNT: usize = 100;
 
+/// Keeps track of the consecutive failures to send packets to the gateway.
+#[derive(Debug, Default)]
+struct GatewayFailures {
+    /// All consecutive failures, regardless of their cause.
+    consecutive: usize,
+
+    /// Consecutive failures caused by the connection to the gateway itself.
+    /// Only those indicate the gateway might be dead and thus count towards the failover threshold.
+    consecutive_connection: usize,
+}
+
+impl GatewayFailures {
+    fn record_failure(&mut self, err: &GatewayClientError) {
+        self.consecutive += 1;
+        if err.is_connection_failure() {
+            self.consecutive_connection += 1;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.consecutive = 0;
+        self.consecutive_connection = 0;
+    }
+
+    fn exceeds_failover_threshold(&self, threshold: usize) -> bool {
+        self.consecutive_connection >= threshold
+    }
+}
+
+/// Gateway the mix packets are sent through.
+pub enum GatewayTransceiver<C, St: Storage> {
+    /// Standard gateway reached over the websocket connection.
+    Remote(GatewayClient<C, St>),
+
+    /// Gateway running within the same process, such as one of a simulated mixnet.
+    Local(Box<dyn LocalGateway>),
+}
+
 pub struct MixTrafficController<C, St: Storage> {
     // TODO: most likely to be replaced by some higher level construct as
     // later on gateway_client will need to be accessible by other entities
-    gateway_client: GatewayClient<C, St>,
+    gateway_client: GatewayTransceiver<C, St>,
+    gateway_failover: Option<GatewayFailover<C, St>>,
     mix_rx: BatchMixMessageReceiver,
 
     // TODO: this is temporary work-around.
     // in long run `gateway_client` will be moved away from `MixTrafficController` anyway.
-    consecutive_gateway_failure_count: usize,
+    gateway_failures: GatewayFailures,
 }
 
 impl<C, St> MixTrafficController<C, St>
@@ -38,37 +80,82 @@ where
     <St as Storage>::StorageError: Send + Sync + 'static,
 {
     pub fn new(
-        gateway_client: GatewayClient<C, St>,
+        gateway_client: GatewayTransceiver<C, St>,
+        gateway_failover: Option<GatewayFailover<C, St>>,
     ) -> (MixTrafficController<C, St>, BatchMixMessageSender) {
         let (sphinx_message_sender, sphinx_message_receiver) =
             tokio::sync::mpsc::channel(MIX_MESSAGE_RECEIVER_BUFFER_SIZE);
         (
             MixTrafficController {
                 gateway_client,
+                gateway_failover,
                 mix_rx: sphinx_message_receiver,
-                consecutive_gateway_failure_count: 0,
+                gateway_failures: GatewayFailures::default(),
             },
             sphinx_message_sender,
         )
     }
 
-    async fn on_messages(&mut self, mut mix_packets: Vec<MixPacket>) {
-        debug_assert!(!mix_packets.is_empty());
+    fn should_fail_over(&self) -> bool {
+        match &self.gateway_failover {
+            Some(failover) => self
+                .gateway_failures
+                .exceeds_failover_threshold(failover.failover_threshold()),
+            None => false,
+        }
+    }
 
-        let result = if mix_packets.len() == 1 {
-            let mix_packet = mix_packets.pop().unwrap();
-            self.gateway_client.send_mix_packet(mix_packet).await
-        } else {
-            self.gateway_client
-                .batch_send_mix_packets(mix_packets)
-                .await
+    async fn fail_over(&mut self) {
+        let Some(failover) = self.gateway_failover.as_mut() else {
+            return;
         };
 
+        if let Some(new_gateway_client) = failover.fail_over().await {
+            let old_gateway_client = std::mem::replace(
+                &mut self.gateway_client,
+                GatewayTransceiver::Remote(new_gateway_client),
+            );
+            if let GatewayTransceiver::Remote(mut old_gateway_client) = old_gateway_client {
+                if let Err(err) = old_gateway_client.close_connection().await {
+                    debug!("failed to cleanly close the connection to the old gateway: {err}");
+                }
+            }
+            self.gateway_failures.reset();
+        }
+    }
+
+    async fn send_mix_packets(
+        &mut self,
+        mut mix_packets: Vec<MixPacket>,
+    ) -> Result<(), GatewayClientError> {
+        match &mut self.gateway_client {
+            GatewayTransceiver::Remote(gateway_client) => {
+                if mix_packets.len() == 1 {
+                    let mix_packet = mix_packets.pop().unwrap();
+                    gateway_client.send_mix_packet(mix_packet).await
+                } else {
+                    gateway_client.batch_send_mix_packets(mix_packets).await
+                }
+            }
+            GatewayTransceiver::Local(local_gateway) => {
+                local_gateway.send_mix_packets(mix_packets).await
+            }
+        }
+    }
+
+    async fn on_messages(&mut self, mix_packets: Vec<MixPacket>) {
+        debug_assert!(!mix_packets.is_empty());
+
+        let result = self.send_mix_packets(mix_packets).await;
+
         match result {
             Err(err) => {
                 error!("Failed to send sphinx packet(s) to the gateway! - {err}");
-                self.consecutive_gateway_failure_count += 1;
-                if self.consecutive_gateway_failure_count == MAX_FAILURE_COUNT {
+                self.gateway_failures.record_failure(&err);
+                if self.should_fail_over() {
+                    self.fail_over().await;
+                }
+                if self.gateway_failures.consecutive == MAX_FAILURE_COUNT {
                     // todo: in the future this should initiate a 'graceful' shutdown or try
                     // to reconnect?
                     panic!("failed to send sphinx packet to the gateway {MAX_FAILURE_COUNT} times in a row - assuming the gateway is dead. Can't do anything about it yet :(")
@@ -76,11 +163,18 @@ where
             }
             Ok(_) => {
                 trace!("We *might* have managed to forward sphinx packet(s) to the gateway!");
-                self.consecutive_gateway_failure_count = 0;
+                self.gateway_failures.reset();
             }
         }
     }
 
+    async fn wait_for_fallback_candidates(gateway_failover: &mut Option<GatewayFailover<C, St>>) {
+        match gateway_failover {
+            Some(failover) => failover.candidates_updated().await,
+            None => futures::future::pending().await,
+        }
+    }
+
     pub fn start_with_shutdown(mut self, mut shutdown: nym_task::TaskClient) {
         spawn_future(async move {
             debug!("Started MixTrafficController with graceful shutdown support");
@@ -96,6 +190,11 @@ where
                             break;
                         }
                     },
+                    _ = Self::wait_for_fallback_candidates(&mut self.gateway_failover) => {
+                        if let Some(failover) = self.gateway_failover.as_mut() {
+                            failover.ensure_secondary_gateway().await;
+                        }
+                    },
                     _ = shutdown.recv_with_delay() => {
                         log::trace!("MixTrafficController: Received shutdown");
                         break;
@@ -107,3 +206,38 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_connection_failures_count_towards_failover() {
+        let mut failures = GatewayFailures::default();
+
+        failures.record_failure(&GatewayClientError::NotEnoughBandwidth(100, 10));
+        failures.record_failure(&GatewayClientError::NoMoreBandwidthCredentials);
+        assert_eq!(failures.consecutive, 2);
+        assert!(!failures.exceeds_failover_threshold(1));
+
+        failures.record_failure(&GatewayClientError::ConnectionAbruptlyClosed);
+        assert_eq!(failures.consecutive, 3);
+        assert!(failures.exceeds_failover_threshold(1));
+    }
+
+    #[test]
+    fn failover_threshold_requires_enough_consecutive_failures() {
+        let mut failures = GatewayFailures::default();
+
+        failures.record_failure(&GatewayClientError::Timeout);
+        failures.record_failure(&GatewayClientError::ConnectionNotEstablished);
+        assert!(!failures.exceeds_failover_threshold(3));
+
+        failures.record_failure(&GatewayClientError::ConnectionClosedGatewayShutdown);
+        assert!(failures.exceeds_failover_threshold(3));
+
+        failures.reset();
+        assert_eq!(failures.consecutive, 0);
+        assert!(!failures.exceeds_failover_threshold(3));
+    }
+}
@@ -1,11 +1,15 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod address;
 pub mod base_client;
 pub mod cover_traffic_stream;
+pub mod delivery_events;
+pub mod gateway_failover;
 pub(crate) mod helpers;
 pub mod inbound_messages;
 pub mod key_manager;
+pub mod local_gateway;
 pub mod mix_traffic;
 pub mod real_messages_control;
 pub mod received_buffer;
@@ -2,8 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::PendingAcknowledgement;
+use crate::client::delivery_events::{
+    DeliveryEvent, DeliveryEventSender, DeliveryListenerRegistrationReceiver, MessageId,
+};
 use crate::client::real_messages_control::acknowledgement_control::RetransmissionRequestSender;
 use futures::channel::mpsc;
+use futures::stream::FusedStream;
 use futures::StreamExt;
 use log::*;
 use nym_nonexhaustive_delayqueue::{Expired, NonExhaustiveDelayQueue, QueueKey};
@@ -19,6 +23,22 @@ pub(crate) type AckActionReceiver = mpsc::UnboundedReceiver<Action>;
 // The actual data being sent off as well as potential key to the delay queue
 type PendingAckEntry = (Arc<PendingAcknowledgement>, Option<QueueKey>);
 
+/// Delivery state of a tracked message.
+struct TrackedMessage {
+    /// Fragments of the message that have not yet been acknowledged.
+    remaining_fragments: usize,
+}
+
+/// Acknowledgement state of a set sent with forward error correction.
+struct ErasureCodedSet {
+    /// Number of fragments that still have to be acknowledged before the recipient
+    /// is guaranteed to be able to reconstruct the set.
+    required_acks: usize,
+
+    /// All fragments of the set, including the parity ones.
+    fragments: Vec<FragmentIdentifier>,
+}
+
 // we can either:
 // - have a completely new set of packets we just sent and need to create entries for
 // - received an ack so we want to remove an entry
@@ -26,8 +46,9 @@ type PendingAckEntry = (Arc<PendingAcknowledgement>, Option<QueueKey>);
 // - update the internal sphinx delay of an expired packet
 pub(crate) enum Action {
     /// Inserts new `PendingAcknowledgement`s into the 'shared' state.
+    /// If the `MessageId` is provided, `DeliveryEvent`s are going to be emitted for them.
     /// Initiated by `InputMessageListener`
-    InsertPending(Vec<PendingAcknowledgement>),
+    InsertPending(Vec<PendingAcknowledgement>, Option<MessageId>),
 
     /// Removes given `PendingAcknowledgement` from the 'shared' state. Also cancels the retransmission timer.
     /// Initiated by `AcknowledgementListener`
@@ -46,7 +67,14 @@ pub(crate) enum Action {
 
 impl Action {
     pub(crate) fn new_insert(pending_acks: Vec<PendingAcknowledgement>) -> Self {
-        Action::InsertPending(pending_acks)
+        Action::InsertPending(pending_acks, None)
+    }
+
+    pub(crate) fn new_tracked_insert(
+        pending_acks: Vec<PendingAcknowledgement>,
+        message_id: MessageId,
+    ) -> Self {
+        Action::InsertPending(pending_acks, Some(message_id))
     }
 
     pub(crate) fn new_remove(frag_id: FragmentIdentifier) -> Self {
@@ -69,6 +97,9 @@ pub(super) struct Config {
 
     /// Given ack timeout in the form a * BASE_DELAY + b, it specifies the multiplier `a`
     ack_wait_multiplier: f64,
+
+    /// Maximum number of retransmissions of a packet of a tracked message before giving up on it.
+    maximum_retransmissions: Option<u32>,
 }
 
 impl Config {
@@ -76,8 +107,17 @@ impl Config {
         Config {
             ack_wait_addition,
             ack_wait_multiplier,
+            maximum_retransmissions: None,
         }
     }
+
+    pub(super) fn with_maximum_retransmissions(
+        mut self,
+        maximum_retransmissions: Option<u32>,
+    ) -> Self {
+        self.maximum_retransmissions = maximum_retransmissions;
+        self
+    }
 }
 
 pub(super) struct ActionController {
@@ -100,6 +140,23 @@ pub(super) struct ActionController {
 
     /// Channel for notifying `RetransmissionRequestListener` about expired acknowledgements.
     retransmission_sender: RetransmissionRequestSender,
+
+    /// Number of times each of the pending fragments has already been retransmitted.
+    retransmissions: HashMap<FragmentIdentifier, u32>,
+
+    /// Messages whose delivery is being tracked alongside the identifiers of the fragments they consist of.
+    tracked_messages: HashMap<MessageId, TrackedMessage>,
+    tracked_fragments: HashMap<FragmentIdentifier, MessageId>,
+
+    /// Sets sent with forward error correction that have not yet received sufficient number of acks.
+    /// Once they do, the remaining fragments no longer need to be retransmitted.
+    erasure_coded_sets: HashMap<i32, ErasureCodedSet>,
+
+    /// Channel for receiving new listeners for `DeliveryEvent`s.
+    delivery_listener_receiver: DeliveryListenerRegistrationReceiver,
+
+    /// All listeners interested in `DeliveryEvent`s.
+    delivery_listeners: Vec<DeliveryEventSender>,
 }
 
 impl ActionController {
@@ -107,6 +164,7 @@ impl ActionController {
         config: Config,
         retransmission_sender: RetransmissionRequestSender,
         incoming_actions: AckActionReceiver,
+        delivery_listener_receiver: DeliveryListenerRegistrationReceiver,
     ) -> Self {
         ActionController {
             config,
@@ -114,14 +172,65 @@ impl ActionController {
             pending_acks_timers: NonExhaustiveDelayQueue::new(),
             incoming_actions,
             retransmission_sender,
+            retransmissions: HashMap::new(),
+            tracked_messages: HashMap::new(),
+            tracked_fragments: HashMap::new(),
+            erasure_coded_sets: HashMap::new(),
+            delivery_listener_receiver,
+            delivery_listeners: Vec::new(),
         }
     }
 
-    fn handle_insert(&mut self, pending_acks: Vec<PendingAcknowledgement>) {
+    fn emit_delivery_event(&mut self, event: DeliveryEvent) {
+        trace!("emitting {:?}", event);
+        // get rid of any listeners that have gone away in the meantime
+        self.delivery_listeners
+            .retain(|listener| listener.unbounded_send(event).is_ok());
+    }
+
+    // the fragment is no longer pending, either because it got acknowledged or because we gave up
+    fn untrack_fragment(&mut self, frag_id: &FragmentIdentifier) -> Option<MessageId> {
+        self.retransmissions.remove(frag_id);
+        let message_id = self.tracked_fragments.remove(frag_id)?;
+        let tracked = self.tracked_messages.get_mut(&message_id)?;
+        tracked.remaining_fragments -= 1;
+        Some(message_id)
+    }
+
+    fn handle_insert(
+        &mut self,
+        pending_acks: Vec<PendingAcknowledgement>,
+        message_id: Option<MessageId>,
+    ) {
+        if let Some(message_id) = message_id {
+            self.tracked_messages.insert(
+                message_id,
+                TrackedMessage {
+                    remaining_fragments: pending_acks.len(),
+                },
+            );
+        }
+
         for pending_ack in pending_acks {
             let frag_id = pending_ack.message_chunk.fragment_identifier();
             trace!("{} is inserted", frag_id);
 
+            if let Some(message_id) = message_id {
+                self.tracked_fragments.insert(frag_id, message_id);
+            }
+
+            let chunk = &pending_ack.message_chunk;
+            if chunk.parity_fragments() > 0 {
+                self.erasure_coded_sets
+                    .entry(chunk.id())
+                    .or_insert_with(|| ErasureCodedSet {
+                        required_acks: chunk.data_fragments() as usize,
+                        fragments: Vec::with_capacity(chunk.total_fragments() as usize),
+                    })
+                    .fragments
+                    .push(frag_id);
+            }
+
             if self
                 .pending_acks_data
                 .insert(frag_id, (Arc::new(pending_ack), None))
@@ -170,7 +279,19 @@ impl ActionController {
                     frag_id
                 );
             }
-            Some((_, queue_key)) => {
+            Some((pending_ack, queue_key)) => {
+                if let Some(message_id) = self.untrack_fragment(&frag_id) {
+                    let delivered = self
+                        .tracked_messages
+                        .get(&message_id)
+                        .map(|tracked| tracked.remaining_fragments == 0)
+                        .unwrap_or_default();
+                    if delivered {
+                        self.tracked_messages.remove(&message_id);
+                        self.emit_delivery_event(DeliveryEvent::Delivered(message_id));
+                    }
+                }
+
                 if let Some(queue_key) = queue_key {
                     // there are no possible checks here, we must GUARANTEE that we NEVER try
                     // to remove an entry that doesn't exist (and we MUST GUARANTEE that
@@ -185,6 +306,30 @@ impl ActionController {
                         frag_id
                     );
                 }
+
+                self.handle_erasure_coded_ack(pending_ack.message_chunk.id());
+            }
+        }
+    }
+
+    // if the set was erasure coded and we have received enough acks for the recipient
+    // to be able to reconstruct it, there's no point in (re)transmitting the remaining fragments
+    fn handle_erasure_coded_ack(&mut self, set_id: i32) {
+        let Some(erasure_coded_set) = self.erasure_coded_sets.get_mut(&set_id) else {
+            return;
+        };
+
+        erasure_coded_set.required_acks = erasure_coded_set.required_acks.saturating_sub(1);
+        if erasure_coded_set.required_acks > 0 {
+            return;
+        }
+
+        // the entry must be removed first so that we wouldn't get back in here
+        let erasure_coded_set = self.erasure_coded_sets.remove(&set_id).unwrap();
+        for frag_id in erasure_coded_set.fragments {
+            if self.pending_acks_data.contains_key(&frag_id) {
+                trace!("{frag_id} is no longer required for reconstructing its set");
+                self.handle_remove(frag_id)
             }
         }
     }
@@ -212,6 +357,49 @@ impl ActionController {
         }
     }
 
+    // removes all remaining fragments of the message, as there's no point in trying to deliver
+    // any of them if one has already been abandoned
+    fn give_up_on_message(&mut self, message_id: MessageId) {
+        let remaining: Vec<_> = self
+            .tracked_fragments
+            .iter()
+            .filter(|(_, id)| **id == message_id)
+            .map(|(frag_id, _)| *frag_id)
+            .collect();
+
+        for frag_id in remaining {
+            self.untrack_fragment(&frag_id);
+            if let Some((pending_ack, _)) = self.pending_acks_data.get(&frag_id) {
+                self.erasure_coded_sets
+                    .remove(&pending_ack.message_chunk.id());
+            }
+            if let Some((_, Some(queue_key))) = self.pending_acks_data.remove(&frag_id) {
+                self.pending_acks_timers.remove(&queue_key);
+            }
+        }
+
+        self.tracked_messages.remove(&message_id);
+        self.emit_delivery_event(DeliveryEvent::GivenUp(message_id));
+    }
+
+    // returns whether the fragment has already been retransmitted the maximum allowed number of times.
+    // only fragments of tracked messages are ever given up on
+    fn exceeded_retransmissions(&mut self, frag_id: FragmentIdentifier) -> bool {
+        let Some(maximum_retransmissions) = self.config.maximum_retransmissions else {
+            return false;
+        };
+        if !self.tracked_fragments.contains_key(&frag_id) {
+            return false;
+        }
+
+        let retransmissions = self.retransmissions.entry(frag_id).or_default();
+        if *retransmissions >= maximum_retransmissions {
+            return true;
+        }
+        *retransmissions += 1;
+        false
+    }
+
     // note: when the entry expires it's automatically removed from pending_acks_timers
     fn handle_expired_ack_timer(
         &mut self,
@@ -225,6 +413,18 @@ impl ActionController {
 
         trace!("{} has expired", frag_id);
 
+        if self.pending_acks_data.contains_key(&frag_id) && self.exceeded_retransmissions(frag_id) {
+            warn!("{frag_id} has exceeded the maximum number of retransmissions. Giving up on it");
+            if let Some((pending_ack, _)) = self.pending_acks_data.remove(&frag_id) {
+                self.erasure_coded_sets
+                    .remove(&pending_ack.message_chunk.id());
+            }
+            if let Some(message_id) = self.tracked_fragments.get(&frag_id).copied() {
+                self.give_up_on_message(message_id)
+            }
+            return;
+        }
+
         if let Some((pending_ack_data, queue_key)) = self.pending_acks_data.get_mut(&frag_id) {
             if queue_key.is_none() {
                 // this branch should be IMPOSSIBLE under ANY condition. It would imply the timeout
@@ -245,15 +445,31 @@ impl ActionController {
                     "Failed to send pending ack for retransmission"
                 );
             }
+
+            if let Some(message_id) = self.tracked_fragments.get(&frag_id).copied() {
+                self.emit_delivery_event(DeliveryEvent::Retransmitting(message_id));
+            }
         } else {
             // this shouldn't cause any issues but shouldn't have happened to begin with!
             error!("An already removed pending ack has expired")
         }
     }
 
+    // listeners registered before the message got sent have to learn about its delivery, but the
+    // registration and the message arrive through different channels, so pick up any outstanding
+    // registrations before handling the action
+    fn register_pending_delivery_listeners(&mut self) {
+        while let Ok(Some(delivery_listener)) = self.delivery_listener_receiver.try_next() {
+            self.delivery_listeners.push(delivery_listener)
+        }
+    }
+
     fn process_action(&mut self, action: Action) {
+        self.register_pending_delivery_listeners();
         match action {
-            Action::InsertPending(pending_acks) => self.handle_insert(pending_acks),
+            Action::InsertPending(pending_acks, message_id) => {
+                self.handle_insert(pending_acks, message_id)
+            }
             Action::RemovePending(frag_id) => self.handle_remove(frag_id),
             Action::StartTimer(frag_id) => self.handle_start_timer(frag_id),
             Action::UpdateDelay(frag_id, delay) => self.handle_update_delay(frag_id, delay),
@@ -281,6 +497,11 @@ impl ActionController {
                         break;
                     }
                 },
+                delivery_listener = self.delivery_listener_receiver.next(), if !self.delivery_listener_receiver.is_terminated() => {
+                    if let Some(delivery_listener) = delivery_listener {
+                        self.delivery_listeners.push(delivery_listener)
+                    }
+                },
                 _ = shutdown.recv_with_delay() => {
                     log::trace!("ActionController: Received shutdown");
                 }
@@ -290,3 +511,340 @@ impl ActionController {
         log::debug!("ActionController: Exiting");
     }
 }
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::client::delivery_events::DeliveryEventReceiver;
+    use crate::client::real_messages_control::acknowledgement_control::RetransmissionRequestReceiver;
+    use nym_crypto::asymmetric::{encryption, identity};
+    use nym_sphinx::addressing::clients::Recipient;
+    use nym_sphinx::chunking::split_into_sets;
+    use rand::rngs::OsRng;
+
+    struct TestController {
+        controller: ActionController,
+        delivery_events: DeliveryEventReceiver,
+        retransmission_requests: RetransmissionRequestReceiver,
+
+        // the senders have to be kept alive for the duration of the test
+        _action_sender: AckActionSender,
+        _delivery_listener_registrar: mpsc::UnboundedSender<DeliveryEventSender>,
+    }
+
+    impl TestController {
+        fn new(maximum_retransmissions: Option<u32>) -> Self {
+            // make the timers fire as soon as they're started
+            let config = Config::new(Duration::ZERO, 0.)
+                .with_maximum_retransmissions(maximum_retransmissions);
+            let (retransmission_sender, retransmission_requests) = mpsc::unbounded();
+            let (action_sender, incoming_actions) = mpsc::unbounded();
+            let (delivery_listener_registrar, delivery_listener_receiver) = mpsc::unbounded();
+
+            let mut controller = ActionController::new(
+                config,
+                retransmission_sender,
+                incoming_actions,
+                delivery_listener_receiver,
+            );
+            let (delivery_sender, delivery_events) = mpsc::unbounded();
+            controller.delivery_listeners.push(delivery_sender);
+
+            TestController {
+                controller,
+                delivery_events,
+                retransmission_requests,
+                _action_sender: action_sender,
+                _delivery_listener_registrar: delivery_listener_registrar,
+            }
+        }
+
+        // sends a tracked message and starts the timers of all of its fragments
+        fn send_tracked_message(
+            &mut self,
+            num_fragments: usize,
+            message_id: MessageId,
+        ) -> Vec<FragmentIdentifier> {
+            let pending_acks = pending_acks(num_fragments);
+            let frag_ids: Vec<_> = pending_acks
+                .iter()
+                .map(|pending_ack| pending_ack.inner_fragment_identifier())
+                .collect();
+
+            self.controller
+                .process_action(Action::new_tracked_insert(pending_acks, message_id));
+            for frag_id in &frag_ids {
+                self.controller
+                    .process_action(Action::new_start_timer(*frag_id));
+            }
+            frag_ids
+        }
+
+        async fn expire_next_timer(&mut self) -> FragmentIdentifier {
+            let expired = self.controller.pending_acks_timers.next().await.unwrap();
+            let frag_id = *expired.get_ref();
+            self.controller
+                .handle_expired_ack_timer(expired, &mut nym_task::TaskClient::dummy());
+            frag_id
+        }
+
+        // simulates what `RetransmissionRequestListener` does upon receiving the request
+        fn retransmit(&mut self) {
+            let weak_ack = self.retransmission_requests.try_next().unwrap().unwrap();
+            let frag_id = weak_ack.upgrade().unwrap().inner_fragment_identifier();
+            drop(weak_ack);
+
+            self.controller.process_action(Action::new_update_delay(
+                frag_id,
+                SphinxDelay::new_from_nanos(0),
+            ));
+            self.controller
+                .process_action(Action::new_start_timer(frag_id));
+        }
+
+        fn emitted_events(&mut self) -> Vec<DeliveryEvent> {
+            let mut events = Vec::new();
+            while let Ok(Some(event)) = self.delivery_events.try_next() {
+                events.push(event)
+            }
+            events
+        }
+
+        fn assert_nothing_is_pending(&self) {
+            assert!(self.controller.pending_acks_data.is_empty());
+            assert!(self.controller.pending_acks_timers.is_empty());
+            assert!(self.controller.tracked_messages.is_empty());
+            assert!(self.controller.tracked_fragments.is_empty());
+            assert!(self.controller.retransmissions.is_empty());
+        }
+    }
+
+    fn pending_acks(num_fragments: usize) -> Vec<PendingAcknowledgement> {
+        let recipient = Recipient::new(
+            *identity::KeyPair::new(&mut OsRng).public_key(),
+            *encryption::KeyPair::new(&mut OsRng).public_key(),
+            *identity::KeyPair::new(&mut OsRng).public_key(),
+        );
+
+        let max_plaintext_size = 200;
+        let mut message_len = 1;
+        loop {
+            let fragments: Vec<_> =
+                split_into_sets(&mut OsRng, &vec![42u8; message_len], max_plaintext_size)
+                    .into_iter()
+                    .flatten()
+                    .collect();
+            if fragments.len() == num_fragments {
+                return fragments
+                    .into_iter()
+                    .map(|fragment| {
+                        PendingAcknowledgement::new_known(
+                            fragment,
+                            SphinxDelay::new_from_nanos(0),
+                            recipient,
+                        )
+                    })
+                    .collect();
+            }
+            message_len += max_plaintext_size / 2;
+        }
+    }
+
+    #[tokio::test]
+    async fn message_is_delivered_once_all_fragments_are_acknowledged() {
+        let mut test = TestController::new(Some(3));
+        let message_id = MessageId::from(42);
+        let frag_ids = test.send_tracked_message(3, message_id);
+
+        test.controller
+            .process_action(Action::new_remove(frag_ids[0]));
+        test.controller
+            .process_action(Action::new_remove(frag_ids[2]));
+        assert!(test.emitted_events().is_empty());
+
+        test.controller
+            .process_action(Action::new_remove(frag_ids[1]));
+        assert_eq!(
+            test.emitted_events(),
+            vec![DeliveryEvent::Delivered(message_id)]
+        );
+        test.assert_nothing_is_pending();
+    }
+
+    #[tokio::test]
+    async fn expired_fragments_are_retransmitted() {
+        let mut test = TestController::new(Some(3));
+        let message_id = MessageId::from(42);
+        let frag_ids = test.send_tracked_message(1, message_id);
+
+        assert_eq!(test.expire_next_timer().await, frag_ids[0]);
+        assert_eq!(
+            test.emitted_events(),
+            vec![DeliveryEvent::Retransmitting(message_id)]
+        );
+        test.retransmit();
+
+        // acknowledgement of the retransmitted fragment still completes the delivery
+        test.controller
+            .process_action(Action::new_remove(frag_ids[0]));
+        assert_eq!(
+            test.emitted_events(),
+            vec![DeliveryEvent::Delivered(message_id)]
+        );
+        test.assert_nothing_is_pending();
+    }
+
+    #[tokio::test]
+    async fn message_is_given_up_on_after_exceeding_maximum_retransmissions() {
+        let mut test = TestController::new(Some(2));
+        let message_id = MessageId::from(42);
+        let frag_ids = test.send_tracked_message(2, message_id);
+
+        // the other fragment has been delivered just fine
+        test.controller
+            .process_action(Action::new_remove(frag_ids[1]));
+
+        for _ in 0..2 {
+            assert_eq!(test.expire_next_timer().await, frag_ids[0]);
+            assert_eq!(
+                test.emitted_events(),
+                vec![DeliveryEvent::Retransmitting(message_id)]
+            );
+            test.retransmit();
+        }
+
+        test.expire_next_timer().await;
+        assert_eq!(
+            test.emitted_events(),
+            vec![DeliveryEvent::GivenUp(message_id)]
+        );
+        assert!(test.retransmission_requests.try_next().is_err());
+        test.assert_nothing_is_pending();
+
+        // late acknowledgement doesn't change anything
+        test.controller
+            .process_action(Action::new_remove(frag_ids[0]));
+        assert!(test.emitted_events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn giving_up_on_fragment_abandons_the_whole_message() {
+        let mut test = TestController::new(Some(0));
+        let message_id = MessageId::from(42);
+        let other_message_id = MessageId::from(123);
+        test.send_tracked_message(3, message_id);
+
+        test.expire_next_timer().await;
+        assert_eq!(
+            test.emitted_events(),
+            vec![DeliveryEvent::GivenUp(message_id)]
+        );
+        test.assert_nothing_is_pending();
+
+        // other messages are unaffected
+        let frag_ids = test.send_tracked_message(1, other_message_id);
+        test.controller
+            .process_action(Action::new_remove(frag_ids[0]));
+        assert_eq!(
+            test.emitted_events(),
+            vec![DeliveryEvent::Delivered(other_message_id)]
+        );
+    }
+
+    #[tokio::test]
+    async fn fragments_are_retransmitted_indefinitely_without_limit() {
+        let mut test = TestController::new(None);
+        let message_id = MessageId::from(42);
+        let frag_ids = test.send_tracked_message(1, message_id);
+
+        for _ in 0..20 {
+            assert_eq!(test.expire_next_timer().await, frag_ids[0]);
+            test.retransmit();
+        }
+        assert_eq!(
+            test.emitted_events(),
+            vec![DeliveryEvent::Retransmitting(message_id); 20]
+        );
+    }
+
+    #[tokio::test]
+    async fn untracked_messages_are_never_given_up_on() {
+        let mut test = TestController::new(Some(0));
+        let pending_acks = pending_acks(2);
+        let frag_ids: Vec<_> = pending_acks
+            .iter()
+            .map(|pending_ack| pending_ack.inner_fragment_identifier())
+            .collect();
+
+        test.controller
+            .process_action(Action::new_insert(pending_acks));
+        test.controller
+            .process_action(Action::new_start_timer(frag_ids[0]));
+        test.controller
+            .process_action(Action::new_remove(frag_ids[1]));
+
+        for _ in 0..3 {
+            assert_eq!(test.expire_next_timer().await, frag_ids[0]);
+            test.retransmit();
+        }
+        assert!(test.emitted_events().is_empty());
+
+        test.controller
+            .process_action(Action::new_remove(frag_ids[0]));
+        assert!(test.emitted_events().is_empty());
+        test.assert_nothing_is_pending();
+    }
+
+    #[tokio::test]
+    async fn tracked_messages_without_listeners_are_given_up_on() {
+        let mut test = TestController::new(Some(0));
+        test.controller.delivery_listeners.clear();
+        let message_id = MessageId::from(42);
+        test.send_tracked_message(1, message_id);
+
+        test.expire_next_timer().await;
+        assert!(test.retransmission_requests.try_next().is_err());
+        test.assert_nothing_is_pending();
+    }
+
+    #[tokio::test]
+    async fn listener_registered_right_before_sending_learns_about_delivery() {
+        let TestController {
+            mut controller,
+            retransmission_requests: _retransmission_requests,
+            _action_sender: action_sender,
+            _delivery_listener_registrar: delivery_listener_registrar,
+            ..
+        } = TestController::new(None);
+        controller.delivery_listeners.clear();
+
+        let message_id = MessageId::from(42);
+        let pending_acks = pending_acks(1);
+        let frag_id = pending_acks[0].inner_fragment_identifier();
+
+        // register and send back to back, so that both channels are ready by the time
+        // the controller gets to poll them
+        let (delivery_sender, mut delivery_events) = mpsc::unbounded();
+        delivery_listener_registrar
+            .unbounded_send(delivery_sender)
+            .unwrap();
+        action_sender
+            .unbounded_send(Action::new_tracked_insert(pending_acks, message_id))
+            .unwrap();
+        action_sender
+            .unbounded_send(Action::new_remove(frag_id))
+            .unwrap();
+
+        let controller_task = tokio::spawn(async move {
+            controller
+                .run_with_shutdown(nym_task::TaskClient::dummy())
+                .await
+        });
+
+        let event = tokio::time::timeout(Duration::from_secs(5), delivery_events.next())
+            .await
+            .unwrap();
+        assert_eq!(event, Some(DeliveryEvent::Delivered(message_id)));
+        controller_task.abort();
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright 2021-2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::delivery_events::MessageId;
 use crate::client::inbound_messages::{InputMessage, InputMessageReceiver};
 use crate::client::real_messages_control::message_handler::MessageHandler;
 use crate::client::real_messages_control::real_traffic_stream::RealMessage;
@@ -8,6 +9,7 @@ use crate::client::replies::reply_controller::ReplyControllerSender;
 use log::*;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nym_sphinx::chunking::ErasureCoding;
 use nym_sphinx::forwarding::packet::MixPacket;
 use nym_task::connections::TransmissionLane;
 use rand::{CryptoRng, Rng};
@@ -60,10 +62,11 @@ where
         recipient_tag: AnonymousSenderTag,
         data: Vec<u8>,
         lane: TransmissionLane,
+        erasure_coding: Option<ErasureCoding>,
     ) {
         // offload reply handling to the dedicated task
         self.reply_controller_sender
-            .send_reply(recipient_tag, data, lane)
+            .send_reply(recipient_tag, data, lane, erasure_coding)
     }
 
     async fn handle_plain_message(
@@ -71,10 +74,12 @@ where
         recipient: Recipient,
         content: Vec<u8>,
         lane: TransmissionLane,
+        message_id: Option<MessageId>,
+        erasure_coding: Option<ErasureCoding>,
     ) {
         if let Err(err) = self
             .message_handler
-            .try_send_plain_message(recipient, content, lane)
+            .try_send_plain_message(recipient, content, lane, message_id, erasure_coding)
             .await
         {
             warn!("failed to send a plain message - {err}")
@@ -87,38 +92,103 @@ where
         content: Vec<u8>,
         reply_surbs: u32,
         lane: TransmissionLane,
+        message_id: Option<MessageId>,
+        erasure_coding: Option<ErasureCoding>,
     ) {
         if let Err(err) = self
             .message_handler
-            .try_send_message_with_reply_surbs(recipient, content, reply_surbs, lane)
+            .try_send_message_with_reply_surbs(
+                recipient,
+                content,
+                reply_surbs,
+                lane,
+                message_id,
+                erasure_coding,
+            )
             .await
         {
             warn!("failed to send a repliable message - {err}")
         }
     }
 
-    async fn on_input_message(&mut self, msg: InputMessage) {
+    async fn on_input_message(&mut self, mut msg: InputMessage) {
+        let mut message_id = None;
+        let mut erasure_coding = self.message_handler.default_erasure_coding();
+        let mut erasure_coding_overridden = false;
+
+        // unwrap the message from its (possible) tracking and erasure coding wrappers
+        let msg = loop {
+            msg = match msg {
+                InputMessage::Tracked {
+                    message_id: id,
+                    message,
+                } if message_id.is_none() => {
+                    message_id = Some(id);
+                    *message
+                }
+                InputMessage::WithErasureCoding {
+                    erasure_coding: requested,
+                    message,
+                } => {
+                    erasure_coding = requested;
+                    erasure_coding_overridden = true;
+                    *message
+                }
+                msg => break msg,
+            }
+        };
+
+        if message_id.is_some()
+            && matches!(
+                msg,
+                InputMessage::Reply { .. } | InputMessage::Premade { .. }
+            )
+        {
+            warn!("delivery of replies and premade packets can't be tracked");
+        }
+
+        if erasure_coding_overridden && matches!(msg, InputMessage::Premade { .. }) {
+            warn!("erasure coding can't be applied to premade packets");
+        }
+
         match msg {
             InputMessage::Regular {
                 recipient,
                 data,
                 lane,
-            } => self.handle_plain_message(recipient, data, lane).await,
+            } => {
+                self.handle_plain_message(recipient, data, lane, message_id, erasure_coding)
+                    .await
+            }
             InputMessage::Anonymous {
                 recipient,
                 data,
                 reply_surbs,
                 lane,
             } => {
-                self.handle_repliable_message(recipient, data, reply_surbs, lane)
-                    .await
+                self.handle_repliable_message(
+                    recipient,
+                    data,
+                    reply_surbs,
+                    lane,
+                    message_id,
+                    erasure_coding,
+                )
+                .await
+            }
+            InputMessage::Tracked { .. } => {
+                warn!("received nested tracked message - it is going to be dropped")
+            }
+            InputMessage::WithErasureCoding { .. } => {
+                unreachable!("all erasure coding wrappers have already been unwrapped")
             }
             InputMessage::Reply {
                 recipient_tag,
                 data,
                 lane,
             } => {
-                self.handle_reply(recipient_tag, data, lane).await;
+                self.handle_reply(recipient_tag, data, lane, erasure_coding)
+                    .await;
             }
             InputMessage::Premade { msgs, lane } => self.handle_premade_packets(msgs, lane).await,
         };
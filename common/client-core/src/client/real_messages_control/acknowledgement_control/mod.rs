@@ -7,6 +7,7 @@ use self::{
     retransmission_request_listener::RetransmissionRequestListener,
     sent_notification_listener::SentNotificationListener,
 };
+use crate::client::delivery_events::DeliveryListenerRegistrationReceiver;
 use crate::client::inbound_messages::InputMessageReceiver;
 use crate::client::real_messages_control::message_handler::MessageHandler;
 use crate::client::replies::reply_controller::ReplyControllerSender;
@@ -134,6 +135,9 @@ pub(super) struct AcknowledgementControllerConnectors {
 
     /// Channel used for receiving request by `ActionController` to deal with anything ack-related,
     ack_action_receiver: AckActionReceiver,
+
+    /// Channel used for registering listeners for the delivery status of tracked messages.
+    delivery_listener_receiver: DeliveryListenerRegistrationReceiver,
 }
 
 impl AcknowledgementControllerConnectors {
@@ -143,6 +147,7 @@ impl AcknowledgementControllerConnectors {
         ack_receiver: AcknowledgementReceiver,
         ack_action_sender: AckActionSender,
         ack_action_receiver: AckActionReceiver,
+        delivery_listener_receiver: DeliveryListenerRegistrationReceiver,
     ) -> Self {
         AcknowledgementControllerConnectors {
             input_receiver,
@@ -150,6 +155,7 @@ impl AcknowledgementControllerConnectors {
             ack_receiver,
             ack_action_sender,
             ack_action_receiver,
+            delivery_listener_receiver,
         }
     }
 }
@@ -162,6 +168,9 @@ pub(super) struct Config {
     /// Given ack timeout in the form a * BASE_DELAY + b, it specifies the multiplier `a`
     ack_wait_multiplier: f64,
 
+    /// Maximum number of retransmissions of a packet of a tracked message before giving up on it.
+    maximum_retransmissions: Option<u32>,
+
     /// Predefined packet size used for the encapsulated messages.
     packet_size: PacketSize,
 }
@@ -171,10 +180,18 @@ impl Config {
         Config {
             ack_wait_addition,
             ack_wait_multiplier,
+            maximum_retransmissions: None,
             packet_size: Default::default(),
         }
     }
 
+    /// Sets the maximum number of retransmissions of tracked packets, with 0 meaning there's no limit.
+    pub fn with_maximum_retransmissions(mut self, maximum_retransmissions: u32) -> Self {
+        self.maximum_retransmissions =
+            (maximum_retransmissions > 0).then_some(maximum_retransmissions);
+        self
+    }
+
     pub fn with_custom_packet_size(mut self, packet_size: PacketSize) -> Self {
         self.packet_size = packet_size;
         self
@@ -206,11 +223,13 @@ where
         let (retransmission_tx, retransmission_rx) = mpsc::unbounded();
 
         let action_config =
-            action_controller::Config::new(config.ack_wait_addition, config.ack_wait_multiplier);
+            action_controller::Config::new(config.ack_wait_addition, config.ack_wait_multiplier)
+                .with_maximum_retransmissions(config.maximum_retransmissions);
         let action_controller = ActionController::new(
             action_config,
             retransmission_tx,
             connectors.ack_action_receiver,
+            connectors.delivery_listener_receiver,
         );
 
         // will listen for any acks coming from the network
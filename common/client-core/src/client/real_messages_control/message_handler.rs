@@ -1,6 +1,8 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::address::ClientAddressReceiver;
+use crate::client::delivery_events::MessageId;
 use crate::client::real_messages_control::acknowledgement_control::PendingAcknowledgement;
 use crate::client::real_messages_control::real_traffic_stream::{
     BatchRealMessageSender, RealMessage,
@@ -14,8 +16,9 @@ use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::{AnonymousSenderTag, RepliableMessage, ReplyMessage};
 use nym_sphinx::anonymous_replies::{ReplySurb, SurbEncryptionKey};
 use nym_sphinx::chunking::fragment::{Fragment, FragmentIdentifier};
+use nym_sphinx::chunking::ErasureCoding;
 use nym_sphinx::message::NymMessage;
-use nym_sphinx::params::{PacketSize, DEFAULT_NUM_MIX_HOPS};
+use nym_sphinx::params::{PacketSize, PacketType, DEFAULT_NUM_MIX_HOPS};
 use nym_sphinx::preparer::{MessagePreparer, PreparedFragment};
 use nym_sphinx::Delay;
 use nym_task::connections::TransmissionLane;
@@ -88,8 +91,8 @@ pub(crate) struct Config {
     ack_key: Arc<AckKey>,
 
     /// Address of this client which also represent an address to which all acknowledgements
-    /// and surb-based are going to be sent.
-    sender_address: Recipient,
+    /// and surb-based are going to be sent. It might change if the client fails over to another gateway.
+    sender_address: ClientAddressReceiver,
 
     /// Average delay a data packet is going to get delay at a single mixnode.
     average_packet_delay: Duration,
@@ -106,12 +109,19 @@ pub(crate) struct Config {
 
     /// Optional secondary predefined packet size used for the encapsulated messages.
     secondary_packet_size: Option<PacketSize>,
+
+    /// Format of the packets used for the encapsulated messages.
+    packet_type: PacketType,
+
+    /// Default forward error correction applied to the sent messages,
+    /// unless overridden for a particular message.
+    erasure_coding: Option<ErasureCoding>,
 }
 
 impl Config {
     pub fn new(
         ack_key: Arc<AckKey>,
-        sender_address: Recipient,
+        sender_address: ClientAddressReceiver,
         average_packet_delay: Duration,
         average_ack_delay: Duration,
     ) -> Self {
@@ -123,6 +133,8 @@ impl Config {
             num_mix_hops: DEFAULT_NUM_MIX_HOPS,
             primary_packet_size: PacketSize::default(),
             secondary_packet_size: None,
+            packet_type: PacketType::default(),
+            erasure_coding: None,
         }
     }
 
@@ -144,6 +156,18 @@ impl Config {
         self.secondary_packet_size = packet_size;
         self
     }
+
+    /// Allows setting non-default format of the packets sent out.
+    pub fn with_packet_type(mut self, packet_type: PacketType) -> Self {
+        self.packet_type = packet_type;
+        self
+    }
+
+    /// Allows setting the default forward error correction of the sent messages.
+    pub fn with_erasure_coding(mut self, erasure_coding: Option<ErasureCoding>) -> Self {
+        self.erasure_coding = erasure_coding;
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -176,11 +200,12 @@ where
     {
         let message_preparer = MessagePreparer::new(
             rng,
-            config.sender_address,
+            config.sender_address.borrow().recipient,
             config.average_packet_delay,
             config.average_ack_delay,
         )
-        .with_mix_hops(config.num_mix_hops);
+        .with_mix_hops(config.num_mix_hops)
+        .with_packet_type(config.packet_type);
 
         MessageHandler {
             config,
@@ -194,6 +219,24 @@ where
         }
     }
 
+    pub(crate) fn default_erasure_coding(&self) -> Option<ErasureCoding> {
+        self.config.erasure_coding
+    }
+
+    fn sender_address(&self) -> Recipient {
+        self.config.sender_address.borrow().recipient
+    }
+
+    // make sure all the packets we're about to create (and their acks and reply SURBs)
+    // are pointing at our current gateway
+    fn update_sender_address(&mut self) {
+        if self.config.sender_address.has_changed().unwrap_or_default() {
+            let sender_address = self.config.sender_address.borrow_and_update().recipient;
+            debug!("our address has changed to {sender_address}");
+            self.message_preparer.set_sender_address(sender_address);
+        }
+    }
+
     fn get_or_create_sender_tag(&mut self, recipient: &Recipient) -> AnonymousSenderTag {
         if let Some(existing) = self.tag_storage.try_get_existing(recipient) {
             trace!("we already had sender tag for {recipient}");
@@ -211,7 +254,7 @@ where
         &self,
         permit: &'a TopologyReadPermit<'a>,
     ) -> Result<&'a NymTopology, PreparationError> {
-        match permit.try_get_valid_topology_ref(&self.config.sender_address, None) {
+        match permit.try_get_valid_topology_ref(&self.sender_address(), None) {
             Ok(topology_ref) => Ok(topology_ref),
             Err(err) => {
                 warn!("Could not process the packet - the network topology is invalid - {err}");
@@ -224,7 +267,7 @@ where
         // if secondary packet was never set, then it's obvious we have to use the primary packet
         let Some(secondary_packet) = self.config.secondary_packet_size else {
             trace!("only primary packet size is available");
-            return self.config.primary_packet_size
+            return self.config.primary_packet_size;
         };
 
         let primary_count =
@@ -246,6 +289,7 @@ where
         &mut self,
         amount: usize,
     ) -> Result<(Vec<ReplySurb>, Vec<SurbEncryptionKey>), PreparationError> {
+        self.update_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
 
@@ -318,20 +362,39 @@ where
     ) -> Result<(), SurbWrappedPreparationError> {
         debug!("requesting {amount} reply SURBs from {from}");
 
-        let surbs_request =
-            ReplyMessage::new_surb_request_message(self.config.sender_address, amount);
+        let surbs_request = ReplyMessage::new_surb_request_message(self.sender_address(), amount);
         self.try_send_single_surb_message(from, surbs_request, reply_surb, true)
             .await
     }
 
     // // TODO: this will require additional argument to make it use different variant of `ReplyMessage`
-    pub(crate) fn split_reply_message(&mut self, message: Vec<u8>) -> Vec<Fragment> {
+    pub(crate) fn split_reply_message(
+        &mut self,
+        message: Vec<u8>,
+        erasure_coding: Option<ErasureCoding>,
+    ) -> Vec<Fragment> {
         let msg = NymMessage::new_reply(ReplyMessage::new_data_message(message));
         let packet_size = self.optimal_packet_size(&msg);
         debug!("Using {packet_size} packets for {msg}");
 
-        self.message_preparer
-            .pad_and_split_message(msg, packet_size)
+        self.pad_and_split_message(msg, packet_size, erasure_coding)
+    }
+
+    fn pad_and_split_message(
+        &mut self,
+        message: NymMessage,
+        packet_size: PacketSize,
+        erasure_coding: Option<ErasureCoding>,
+    ) -> Vec<Fragment> {
+        let (fragments, erasure_coded) = self
+            .message_preparer
+            .pad_and_split_message_with_erasure_coding(message, packet_size, erasure_coding);
+        if erasure_coding.is_some() && !erasure_coded {
+            warn!(
+                "the message is too long to fit in a single set of packets - it's going to be sent without any erasure coding"
+            )
+        }
+        fragments
     }
 
     pub(crate) async fn send_retransmission_reply_chunks(
@@ -417,10 +480,18 @@ where
         recipient: Recipient,
         message: Vec<u8>,
         lane: TransmissionLane,
+        message_id: Option<MessageId>,
+        erasure_coding: Option<ErasureCoding>,
     ) -> Result<(), PreparationError> {
         let message = NymMessage::new_plain(message);
-        self.try_split_and_send_non_reply_message(message, recipient, lane)
-            .await
+        self.try_split_and_send_non_reply_message(
+            message,
+            recipient,
+            lane,
+            message_id,
+            erasure_coding,
+        )
+        .await
     }
 
     pub(crate) async fn try_split_and_send_non_reply_message(
@@ -428,19 +499,20 @@ where
         message: NymMessage,
         recipient: Recipient,
         lane: TransmissionLane,
+        message_id: Option<MessageId>,
+        erasure_coding: Option<ErasureCoding>,
     ) -> Result<(), PreparationError> {
         // TODO: I really dislike existence of this assertion, it implies code has to be re-organised
         debug_assert!(!matches!(message, NymMessage::Reply(_)));
 
         // TODO2: it's really annoying we have to get topology permit again here due to borrow-checker
+        self.update_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
 
         let packet_size = self.optimal_packet_size(&message);
         debug!("Using {packet_size} packets for {message}");
-        let fragments = self
-            .message_preparer
-            .pad_and_split_message(message, packet_size);
+        let fragments = self.pad_and_split_message(message, packet_size, erasure_coding);
 
         let mut pending_acks = Vec::with_capacity(fragments.len());
         let mut real_messages = Vec::with_capacity(fragments.len());
@@ -466,7 +538,10 @@ where
             pending_acks.push(pending_ack);
         }
 
-        self.insert_pending_acks(pending_acks);
+        match message_id {
+            Some(message_id) => self.insert_tracked_pending_acks(pending_acks, message_id),
+            None => self.insert_pending_acks(pending_acks),
+        }
         self.forward_messages(real_messages, lane).await;
 
         Ok(())
@@ -490,6 +565,8 @@ where
             message,
             recipient,
             TransmissionLane::AdditionalReplySurbs,
+            None,
+            self.config.erasure_coding,
         )
         .await?;
 
@@ -505,6 +582,8 @@ where
         message: Vec<u8>,
         num_reply_surbs: u32,
         lane: TransmissionLane,
+        message_id: Option<MessageId>,
+        erasure_coding: Option<ErasureCoding>,
     ) -> Result<(), SurbWrappedPreparationError> {
         let sender_tag = self.get_or_create_sender_tag(&recipient);
         let (reply_surbs, reply_keys) = self
@@ -514,8 +593,14 @@ where
         let message =
             NymMessage::new_repliable(RepliableMessage::new_data(message, sender_tag, reply_surbs));
 
-        self.try_split_and_send_non_reply_message(message, recipient, lane)
-            .await?;
+        self.try_split_and_send_non_reply_message(
+            message,
+            recipient,
+            lane,
+            message_id,
+            erasure_coding,
+        )
+        .await?;
 
         log::trace!("storing {} reply keys", reply_keys.len());
         self.reply_key_storage.insert_multiple(reply_keys);
@@ -528,6 +613,7 @@ where
         recipient: Recipient,
         chunk: Fragment,
     ) -> Result<PreparedFragment, PreparationError> {
+        self.update_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
 
@@ -552,6 +638,7 @@ where
             reply_surbs.len()
         );
 
+        self.update_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = match self.get_topology(&topology_permit) {
             Ok(topology) => topology,
@@ -580,6 +667,7 @@ where
         reply_surb: ReplySurb,
         chunk: Fragment,
     ) -> Result<PreparedFragment, SurbWrappedPreparationError> {
+        self.update_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = match self.get_topology(&topology_permit) {
             Ok(topology) => topology,
@@ -606,6 +694,16 @@ where
             .expect("action control task has died")
     }
 
+    pub(crate) fn insert_tracked_pending_acks(
+        &self,
+        pending_acks: Vec<PendingAcknowledgement>,
+        message_id: MessageId,
+    ) {
+        self.action_sender
+            .unbounded_send(Action::new_tracked_insert(pending_acks, message_id))
+            .expect("action control task has died")
+    }
+
     // tells real message sender (with the poisson timer) to send this to the mix network
     pub(crate) async fn forward_messages(
         &self,
@@ -8,6 +8,8 @@
 use self::{
     acknowledgement_control::AcknowledgementController, real_traffic_stream::OutQueueControl,
 };
+use crate::client::address::ClientAddressReceiver;
+use crate::client::delivery_events::DeliveryListenerRegistrationReceiver;
 use crate::client::real_messages_control::message_handler::MessageHandler;
 use crate::client::replies::reply_controller::{
     ReplyController, ReplyControllerReceiver, ReplyControllerSender,
@@ -25,7 +27,6 @@ use futures::channel::mpsc;
 use log::*;
 use nym_gateway_client::AcknowledgementReceiver;
 use nym_sphinx::acknowledgements::AckKey;
-use nym_sphinx::addressing::clients::Recipient;
 use nym_task::connections::{ConnectionCommandReceiver, LaneQueueLengths};
 use rand::{rngs::OsRng, CryptoRng, Rng};
 use std::sync::Arc;
@@ -44,7 +45,7 @@ pub struct Config {
     ack_key: Arc<AckKey>,
 
     /// Address of `this` client.
-    self_recipient: Recipient,
+    self_recipient: ClientAddressReceiver,
 
     /// Specifies all traffic related configuration options.
     traffic: config::Traffic,
@@ -65,6 +66,7 @@ impl<'a> From<&'a Config> for acknowledgement_control::Config {
             cfg.acks.ack_wait_addition,
             cfg.acks.ack_wait_multiplier,
         )
+        .with_maximum_retransmissions(cfg.acks.maximum_retransmissions)
         .with_custom_packet_size(cfg.traffic.primary_packet_size)
     }
 }
@@ -73,7 +75,7 @@ impl<'a> From<&'a Config> for real_traffic_stream::Config {
     fn from(cfg: &'a Config) -> Self {
         real_traffic_stream::Config::new(
             Arc::clone(&cfg.ack_key),
-            cfg.self_recipient,
+            cfg.self_recipient.clone(),
             cfg.acks.average_ack_delay,
             cfg.traffic,
             cfg.cover_traffic.cover_traffic_primary_size_ratio,
@@ -91,12 +93,14 @@ impl<'a> From<&'a Config> for message_handler::Config {
     fn from(cfg: &'a Config) -> Self {
         message_handler::Config::new(
             Arc::clone(&cfg.ack_key),
-            cfg.self_recipient,
+            cfg.self_recipient.clone(),
             cfg.traffic.average_packet_delay,
             cfg.acks.average_ack_delay,
         )
         .with_custom_primary_packet_size(cfg.traffic.primary_packet_size)
         .with_custom_secondary_packet_size(cfg.traffic.secondary_packet_size)
+        .with_packet_type(cfg.traffic.packet_type)
+        .with_erasure_coding(cfg.traffic.erasure_coding)
     }
 }
 
@@ -104,7 +108,7 @@ impl Config {
     pub fn new(
         base_client_debug_config: &config::DebugConfig,
         ack_key: Arc<AckKey>,
-        self_recipient: Recipient,
+        self_recipient: ClientAddressReceiver,
     ) -> Self {
         Config {
             ack_key,
@@ -142,6 +146,7 @@ impl RealMessagesController<OsRng> {
         reply_controller_receiver: ReplyControllerReceiver,
         lane_queue_lengths: LaneQueueLengths,
         client_connection_rx: ConnectionCommandReceiver,
+        delivery_listener_receiver: DeliveryListenerRegistrationReceiver,
     ) -> Self {
         let rng = OsRng;
 
@@ -155,6 +160,7 @@ impl RealMessagesController<OsRng> {
             ack_receiver,
             ack_action_tx.clone(),
             ack_action_rx,
+            delivery_listener_receiver,
         );
 
         // create all configs for the components
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use self::sending_delay_controller::SendingDelayController;
+use crate::client::address::ClientAddressReceiver;
 use crate::client::mix_traffic::BatchMixMessageSender;
 use crate::client::real_messages_control::acknowledgement_control::SentPacketNotificationSender;
 use crate::client::topology_control::TopologyAccessor;
@@ -11,7 +12,6 @@ use futures::task::{Context, Poll};
 use futures::{Future, Stream, StreamExt};
 use log::*;
 use nym_sphinx::acknowledgements::AckKey;
-use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::chunking::fragment::FragmentIdentifier;
 use nym_sphinx::cover::generate_loop_cover_packet;
 use nym_sphinx::forwarding::packet::MixPacket;
@@ -39,7 +39,7 @@ pub(crate) struct Config {
     ack_key: Arc<AckKey>,
 
     /// Represents full address of this client.
-    our_full_destination: Recipient,
+    our_full_destination: ClientAddressReceiver,
 
     /// Average delay an acknowledgement packet is going to get delay at a single mixnode.
     average_ack_delay: Duration,
@@ -55,7 +55,7 @@ pub(crate) struct Config {
 impl Config {
     pub(crate) fn new(
         ack_key: Arc<AckKey>,
-        our_full_destination: Recipient,
+        our_full_destination: ClientAddressReceiver,
         average_ack_delay: Duration,
         traffic: config::Traffic,
         cover_traffic_primary_size_ratio: f64,
@@ -136,7 +136,7 @@ impl From<PreparedFragment> for RealMessage {
 
 impl RealMessage {
     pub(crate) fn packet_size(&self) -> usize {
-        self.mix_packet.sphinx_packet().len()
+        self.mix_packet.packet().len()
     }
 
     pub(crate) fn new(mix_packet: MixPacket, fragment_id: Option<FragmentIdentifier>) -> Self {
@@ -200,7 +200,7 @@ where
 
     fn loop_cover_message_size(&mut self) -> PacketSize {
         let Some(secondary_packet_size) = self.config.traffic.secondary_packet_size else {
-            return self.config.traffic.primary_packet_size
+            return self.config.traffic.primary_packet_size;
         };
 
         let use_primary = self
@@ -225,12 +225,12 @@ where
                 // TODO for way down the line: in very rare cases (during topology update) we might have
                 // to wait a really tiny bit before actually obtaining the permit hence messing with our
                 // poisson delay, but is it really a problem?
+                let our_full_destination = self.config.our_full_destination.borrow().recipient;
                 let topology_permit = self.topology_access.get_read_permit().await;
                 // the ack is sent back to ourselves (and then ignored)
-                let topology_ref = match topology_permit.try_get_valid_topology_ref(
-                    &self.config.our_full_destination,
-                    Some(&self.config.our_full_destination),
-                ) {
+                let topology_ref = match topology_permit
+                    .try_get_valid_topology_ref(&our_full_destination, Some(&our_full_destination))
+                {
                     Ok(topology) => topology,
                     Err(err) => {
                         warn!("We're not going to send any loop cover message this time, as the current topology seem to be invalid - {err}");
@@ -243,7 +243,7 @@ where
                         &mut self.rng,
                         topology_ref,
                         &self.config.ack_key,
-                        &self.config.our_full_destination,
+                        &our_full_destination,
                         self.config.average_ack_delay,
                         self.config.traffic.average_packet_delay,
                         cover_traffic_packet_size,
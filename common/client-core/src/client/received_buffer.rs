@@ -1,8 +1,11 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::helpers::new_interval_stream;
 use crate::client::replies::reply_controller::ReplyControllerSender;
 use crate::client::replies::reply_storage::SentReplyKeys;
+use crate::config::MessageReconstruction;
+use crate::error::ClientCoreStatusMessage;
 use crate::spawn_future;
 use futures::channel::mpsc;
 use futures::lock::Mutex;
@@ -15,11 +18,13 @@ use nym_sphinx::anonymous_replies::requests::{
     RepliableMessage, RepliableMessageContent, ReplyMessage, ReplyMessageContent,
 };
 use nym_sphinx::anonymous_replies::{encryption_key::EncryptionKeyDigest, SurbEncryptionKey};
+use nym_sphinx::chunking::reconstruction::ReconstructionLimits;
 use nym_sphinx::message::{NymMessage, PlainMessage};
 use nym_sphinx::params::ReplySurbKeyDigestAlgorithm;
 use nym_sphinx::receiver::{MessageReceiver, MessageRecoveryError, ReconstructedMessage};
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 
 // Buffer Requests to say "hey, send any reconstructed messages to this channel"
 // or to say "hey, I'm going offline, don't send anything more to me. Just buffer them instead"
@@ -141,12 +146,21 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
         local_encryption_keypair: Arc<encryption::KeyPair>,
         reply_key_storage: SentReplyKeys,
         reply_controller_sender: ReplyControllerSender,
+        reconstruction_config: MessageReconstruction,
     ) -> Self {
+        let mut message_receiver = R::new();
+        message_receiver
+            .reconstructor()
+            .set_limits(ReconstructionLimits {
+                maximum_partial_message_age: reconstruction_config.maximum_partial_message_age,
+                maximum_buffered_bytes: reconstruction_config.maximum_buffered_bytes,
+            });
+
         ReceivedMessagesBuffer {
             inner: Arc::new(Mutex::new(ReceivedMessagesBufferInner {
                 messages: Vec::new(),
                 local_encryption_keypair,
-                message_receiver: R::new(),
+                message_receiver,
                 message_sender: None,
                 recently_reconstructed: HashSet::new(),
             })),
@@ -155,6 +169,33 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
         }
     }
 
+    async fn remove_stale_partial_messages(&mut self, shutdown: &mut nym_task::TaskClient) {
+        let dropped = self
+            .inner
+            .lock()
+            .await
+            .message_receiver
+            .reconstructor()
+            .remove_stale_sets();
+        if dropped.is_empty() {
+            return;
+        }
+
+        let sets = dropped.len();
+        let bytes: usize = dropped.iter().map(|set| set.buffered_bytes).sum();
+        warn!("dropped {sets} incomplete message set(s) ({bytes} bytes) as their remaining fragments have not been received in time");
+        for set in dropped {
+            debug!(
+                "dropped set {} ({}/{} fragments received)",
+                set.set_id, set.received_fragments, set.total_fragments
+            );
+        }
+
+        shutdown.send_status_msg(Box::new(
+            ClientCoreStatusMessage::DroppedIncompleteMessages { sets, bytes },
+        ));
+    }
+
     async fn disconnect_sender(&mut self) {
         let mut guard = self.inner.lock().await;
         if guard.message_sender.is_none() {
@@ -429,16 +470,19 @@ impl<R: MessageReceiver> RequestReceiver<R> {
 struct FragmentedMessageReceiver<R: MessageReceiver> {
     received_buffer: ReceivedMessagesBuffer<R>,
     mixnet_packet_receiver: MixnetMessageReceiver,
+    stale_message_check_interval: Duration,
 }
 
 impl<R: MessageReceiver> FragmentedMessageReceiver<R> {
     fn new(
         received_buffer: ReceivedMessagesBuffer<R>,
         mixnet_packet_receiver: MixnetMessageReceiver,
+        stale_message_check_interval: Duration,
     ) -> Self {
         FragmentedMessageReceiver {
             received_buffer,
             mixnet_packet_receiver,
+            stale_message_check_interval,
         }
     }
 
@@ -447,6 +491,8 @@ impl<R: MessageReceiver> FragmentedMessageReceiver<R> {
         mut shutdown: nym_task::TaskClient,
     ) -> Result<(), MessageRecoveryError> {
         debug!("Started FragmentedMessageReceiver with graceful shutdown support");
+        let mut stale_inspection = new_interval_stream(self.stale_message_check_interval);
+
         while !shutdown.is_shutdown() {
             tokio::select! {
                 new_messages = self.mixnet_packet_receiver.next() => {
@@ -457,6 +503,9 @@ impl<R: MessageReceiver> FragmentedMessageReceiver<R> {
                         break;
                     }
                 },
+                _ = stale_inspection.next() => {
+                    self.received_buffer.remove_stale_partial_messages(&mut shutdown).await
+                },
                 _ = shutdown.recv_with_delay() => {
                     log::trace!("FragmentedMessageReceiver: Received shutdown");
                 }
@@ -480,17 +529,20 @@ impl<R: MessageReceiver + Clone + Send + 'static> ReceivedMessagesBufferControll
         mixnet_packet_receiver: MixnetMessageReceiver,
         reply_key_storage: SentReplyKeys,
         reply_controller_sender: ReplyControllerSender,
+        reconstruction_config: MessageReconstruction,
     ) -> Self {
         let received_buffer = ReceivedMessagesBuffer::new(
             local_encryption_keypair,
             reply_key_storage,
             reply_controller_sender,
+            reconstruction_config,
         );
 
         ReceivedMessagesBufferController {
             fragmented_message_receiver: FragmentedMessageReceiver::new(
                 received_buffer.clone(),
                 mixnet_packet_receiver,
+                reconstruction_config.stale_message_check_interval,
             ),
             request_receiver: RequestReceiver::new(received_buffer, query_receiver),
         }
@@ -11,6 +11,7 @@ use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nym_sphinx::anonymous_replies::ReplySurb;
 use nym_sphinx::chunking::fragment::{Fragment, FragmentIdentifier};
+use nym_sphinx::chunking::ErasureCoding;
 use nym_task::connections::{ConnectionId, TransmissionLane};
 use rand::{CryptoRng, Rng};
 use std::cmp::{max, min};
@@ -185,6 +186,7 @@ where
         recipient_tag: AnonymousSenderTag,
         data: Vec<u8>,
         lane: TransmissionLane,
+        erasure_coding: Option<ErasureCoding>,
     ) {
         if !self
             .full_reply_storage
@@ -196,7 +198,9 @@ where
         }
 
         trace!("handling reply to {:?}", recipient_tag);
-        let mut fragments = self.message_handler.split_reply_message(data);
+        let mut fragments = self
+            .message_handler
+            .split_reply_message(data, erasure_coding);
         let total_size = fragments.len();
         trace!("This reply requires {:?} SURBs", total_size);
 
@@ -653,7 +657,11 @@ where
                 recipient,
                 message,
                 lane,
-            } => self.handle_send_reply(recipient, message, lane).await,
+                erasure_coding,
+            } => {
+                self.handle_send_reply(recipient, message, lane, erasure_coding)
+                    .await
+            }
             ReplyControllerMessage::AdditionalSurbs {
                 sender_tag,
                 reply_surbs,
@@ -723,7 +731,11 @@ where
                 continue;
             }
 
-            let Some(last_received) = self.full_reply_storage.surbs_storage_ref().surbs_last_received_at(pending_reply_target) else {
+            let Some(last_received) = self
+                .full_reply_storage
+                .surbs_storage_ref()
+                .surbs_last_received_at(pending_reply_target)
+            else {
                 error!("we have {} pending replies for {pending_reply_target}, but we somehow never received any reply surbs from them!", vals.total_size());
                 to_remove.push(*pending_reply_target);
                 continue;
@@ -807,7 +819,8 @@ where
 
             // this should never ever happen (famous last words, eh?), but in case it DOES happen eventually
             // purge that malformed data
-            let Ok(sent_at) = OffsetDateTime::from_unix_timestamp(reply_key.sent_at_timestamp) else {
+            let Ok(sent_at) = OffsetDateTime::from_unix_timestamp(reply_key.sent_at_timestamp)
+            else {
                 error!("somehow our stored timestamp ({}) for one of our reply key is corrupted!. Going to remove all the entry", reply_key.sent_at_timestamp);
                 to_remove_keys.push(*digest);
                 continue;
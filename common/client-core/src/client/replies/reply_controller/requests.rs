@@ -7,6 +7,7 @@ use log::error;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nym_sphinx::anonymous_replies::ReplySurb;
+use nym_sphinx::chunking::ErasureCoding;
 use nym_task::connections::{ConnectionId, TransmissionLane};
 use std::sync::Weak;
 
@@ -45,12 +46,14 @@ impl ReplyControllerSender {
         recipient: AnonymousSenderTag,
         message: Vec<u8>,
         lane: TransmissionLane,
+        erasure_coding: Option<ErasureCoding>,
     ) {
         self.0
             .unbounded_send(ReplyControllerMessage::SendReply {
                 recipient,
                 message,
                 lane,
+                erasure_coding,
             })
             .expect("ReplyControllerReceiver has died!")
     }
@@ -131,6 +134,7 @@ pub(crate) enum ReplyControllerMessage {
         recipient: AnonymousSenderTag,
         message: Vec<u8>,
         lane: TransmissionLane,
+        erasure_coding: Option<ErasureCoding>,
     },
 
     AdditionalSurbs {
@@ -3,7 +3,8 @@
 
 use crate::client::replies::reply_storage::backend::fs_backend::error::StorageError;
 use crate::client::replies::reply_storage::backend::fs_backend::models::{
-    ReplySurbStorageMetadata, StoredReplyKey, StoredReplySurb, StoredSenderTag, StoredSurbSender,
+    ReplySurbStorageMetadata, StorageChanges, StoredReplyKey, StoredReplySurb, StoredSenderTag,
+    StoredSurbSender,
 };
 use log::{error, info};
 use sqlx::ConnectOptions;
@@ -95,14 +96,6 @@ impl StorageManager {
             .map(|r| r.previous_flush_timestamp)
     }
 
-    pub(crate) async fn set_flush_status(&self, in_progress: bool) -> Result<(), sqlx::Error> {
-        let in_progress_int = i64::from(in_progress);
-        sqlx::query!("UPDATE status SET flush_in_progress = ?", in_progress_int)
-            .execute(&self.connection_pool)
-            .await?;
-        Ok(())
-    }
-
     pub(crate) async fn get_client_in_use_status(&self) -> Result<bool, sqlx::Error> {
         sqlx::query!("SELECT client_in_use FROM status;")
             .fetch_one(&self.connection_pool)
@@ -131,19 +124,6 @@ impl StorageManager {
             .await
     }
 
-    pub(crate) async fn insert_tag(&self, stored_tag: StoredSenderTag) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            r#"
-                INSERT INTO sender_tag(recipient, tag) VALUES (?, ?);
-            "#,
-            stored_tag.recipient,
-            stored_tag.tag
-        )
-        .execute(&self.connection_pool)
-        .await?;
-        Ok(())
-    }
-
     pub(crate) async fn delete_all_reply_keys(&self) -> Result<(), sqlx::Error> {
         sqlx::query!("DELETE FROM reply_key;")
             .execute(&self.connection_pool)
@@ -157,46 +137,12 @@ impl StorageManager {
             .await
     }
 
-    pub(crate) async fn insert_reply_key(
-        &self,
-        stored_reply_key: StoredReplyKey,
-    ) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            r#"
-                INSERT INTO reply_key(key_digest, reply_key, sent_at_timestamp) VALUES (?, ?, ?);
-            "#,
-            stored_reply_key.key_digest,
-            stored_reply_key.reply_key,
-            stored_reply_key.sent_at_timestamp
-        )
-        .execute(&self.connection_pool)
-        .await?;
-        Ok(())
-    }
-
     pub(crate) async fn get_surb_senders(&self) -> Result<Vec<StoredSurbSender>, sqlx::Error> {
         sqlx::query_as!(StoredSurbSender, "SELECT * FROM reply_surb_sender;",)
             .fetch_all(&self.connection_pool)
             .await
     }
 
-    pub(crate) async fn insert_surb_sender(
-        &self,
-        stored_surb_sender: StoredSurbSender,
-    ) -> Result<i64, sqlx::Error> {
-        let id = sqlx::query!(
-            r#"
-                INSERT INTO reply_surb_sender(tag, last_sent_timestamp) VALUES (?, ?);
-            "#,
-            stored_surb_sender.tag,
-            stored_surb_sender.last_sent_timestamp
-        )
-        .execute(&self.connection_pool)
-        .await?
-        .last_insert_rowid();
-        Ok(id)
-    }
-
     pub(crate) async fn get_reply_surbs(
         &self,
         sender_id: i64,
@@ -222,22 +168,6 @@ impl StorageManager {
         Ok(())
     }
 
-    pub(crate) async fn insert_reply_surb(
-        &self,
-        stored_reply_surb: StoredReplySurb,
-    ) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            r#"
-                INSERT INTO reply_surb(reply_surb_sender_id, reply_surb) VALUES (?, ?);
-            "#,
-            stored_reply_surb.reply_surb_sender_id,
-            stored_reply_surb.reply_surb
-        )
-        .execute(&self.connection_pool)
-        .await?;
-        Ok(())
-    }
-
     pub(crate) async fn get_reply_surb_storage_metadata(
         &self,
     ) -> Result<ReplySurbStorageMetadata, sqlx::Error> {
@@ -264,4 +194,122 @@ impl StorageManager {
         ).execute(&self.connection_pool).await?;
         Ok(())
     }
+
+    /// Writes all the provided changes, alongside the new flush timestamp, within a single transaction,
+    /// so that the storage is never left in a partially updated state.
+    ///
+    /// # Arguments
+    ///
+    /// * `changes`: all entries modified since the previous call.
+    /// * `flush_timestamp`: unix timestamp indicating when the changes got persisted.
+    pub(crate) async fn apply_changes(
+        &self,
+        changes: StorageChanges,
+        flush_timestamp: i64,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.connection_pool.begin().await?;
+
+        for stored_tag in changes.updated_tags {
+            sqlx::query!(
+                "INSERT OR REPLACE INTO sender_tag(recipient, tag) VALUES (?, ?);",
+                stored_tag.recipient,
+                stored_tag.tag
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        for recipient in changes.removed_tags_recipients {
+            sqlx::query!("DELETE FROM sender_tag WHERE recipient = ?;", recipient)
+                .execute(&mut tx)
+                .await?;
+        }
+
+        for stored_reply_key in changes.updated_reply_keys {
+            sqlx::query!(
+                r#"
+                    INSERT OR REPLACE INTO reply_key(key_digest, reply_key, sent_at_timestamp) VALUES (?, ?, ?);
+                "#,
+                stored_reply_key.key_digest,
+                stored_reply_key.reply_key,
+                stored_reply_key.sent_at_timestamp
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        for key_digest in changes.removed_reply_keys_digests {
+            sqlx::query!("DELETE FROM reply_key WHERE key_digest = ?;", key_digest)
+                .execute(&mut tx)
+                .await?;
+        }
+
+        for (stored_surb_sender, reply_surbs) in changes.updated_surb_senders {
+            sqlx::query!(
+                r#"
+                    INSERT INTO reply_surb_sender(tag, last_sent_timestamp) VALUES (?, ?)
+                    ON CONFLICT(tag) DO UPDATE SET last_sent_timestamp = excluded.last_sent_timestamp;
+                "#,
+                stored_surb_sender.tag,
+                stored_surb_sender.last_sent_timestamp
+            )
+            .execute(&mut tx)
+            .await?;
+
+            let sender_id = sqlx::query!(
+                "SELECT id FROM reply_surb_sender WHERE tag = ?;",
+                stored_surb_sender.tag
+            )
+            .fetch_one(&mut tx)
+            .await?
+            .id;
+
+            // the surbs are consumed from the front and appended at the back,
+            // so it's simpler to just replace all of them rather than to figure out the difference
+            sqlx::query!(
+                "DELETE FROM reply_surb WHERE reply_surb_sender_id = ?;",
+                sender_id
+            )
+            .execute(&mut tx)
+            .await?;
+
+            for reply_surb in reply_surbs {
+                let stored_reply_surb = StoredReplySurb {
+                    reply_surb_sender_id: sender_id,
+                    reply_surb,
+                };
+                sqlx::query!(
+                    "INSERT INTO reply_surb(reply_surb_sender_id, reply_surb) VALUES (?, ?);",
+                    stored_reply_surb.reply_surb_sender_id,
+                    stored_reply_surb.reply_surb
+                )
+                .execute(&mut tx)
+                .await?;
+            }
+        }
+
+        for tag in changes.removed_surb_senders_tags {
+            sqlx::query!(
+                r#"
+                    DELETE FROM reply_surb WHERE reply_surb_sender_id IN (SELECT id FROM reply_surb_sender WHERE tag = ?);
+                "#,
+                tag
+            )
+            .execute(&mut tx)
+            .await?;
+
+            sqlx::query!("DELETE FROM reply_surb_sender WHERE tag = ?;", tag)
+                .execute(&mut tx)
+                .await?;
+        }
+
+        sqlx::query!(
+            "UPDATE status SET previous_flush_timestamp = ?",
+            flush_timestamp
+        )
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await
+    }
 }
@@ -3,16 +3,15 @@
 
 use crate::client::replies::reply_storage::backend::fs_backend::manager::StorageManager;
 use crate::client::replies::reply_storage::backend::fs_backend::models::{
-    ReplySurbStorageMetadata, StoredReplyKey, StoredReplySurb, StoredSenderTag, StoredSurbSender,
+    ReplySurbStorageMetadata, StorageChanges, StoredReplyKey, StoredSenderTag, StoredSurbSender,
 };
 use crate::client::replies::reply_storage::surb_storage::ReceivedReplySurbs;
 use crate::client::replies::reply_storage::{
     CombinedReplyStorage, ReceivedReplySurbsMap, ReplyStorageBackend, SentReplyKeys, UsedSenderTags,
 };
 use async_trait::async_trait;
-use log::{error, info, warn};
+use log::{debug, error, info, warn};
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
-use std::fs;
 use std::path::{Path, PathBuf};
 use time::OffsetDateTime;
 
@@ -24,14 +23,10 @@ mod models;
 
 #[derive(Debug)]
 pub struct Backend {
-    temporary_old_path: Option<PathBuf>,
-    database_path: PathBuf,
     manager: StorageManager,
 }
 
 impl Backend {
-    const OLD_EXTENSION: &'static str = "old";
-
     pub async fn init<P: AsRef<Path>>(database_path: P) -> Result<Self, StorageError> {
         let owned_path: PathBuf = database_path.as_ref().into();
         if owned_path.file_name().is_none() {
@@ -43,11 +38,7 @@ impl Backend {
         let manager = StorageManager::init(database_path, true).await?;
         manager.create_status_table().await?;
 
-        let backend = Backend {
-            temporary_old_path: None,
-            database_path: owned_path,
-            manager,
-        };
+        let backend = Backend { manager };
 
         Ok(backend)
     }
@@ -64,6 +55,8 @@ impl Backend {
 
         // the database flush wasn't fully finished and thus the data is in inconsistent state
         // (we don't really know what's properly saved or what's not)
+        // note: this could only have happened with the databases created before the changes
+        // started getting written through in atomic batches
         if manager.get_flush_status().await? {
             return Err(StorageError::IncompleteDataFlush);
         }
@@ -74,13 +67,11 @@ impl Backend {
             return Err(StorageError::IncompleteDataFlush);
         }
 
-        // the process has gone down without full graceful shutdown,
-        // meaning the database doesn't contain valid data anymore
-        // so we have to purge it
+        // the process has gone down without full graceful shutdown. however, since all changes
+        // are written through in atomic batches, the database is still in a consistent state,
+        // it's just missing whatever has happened since the last batch got persisted
         if manager.get_client_in_use_status().await? {
-            error!("the client hasn't undergone through graceful shutdown the last time it's gone down - we can't trust its reply surbs or stored encryption keys. They shall get purged");
-            manager.delete_all_reply_surb_data().await?;
-            manager.delete_all_reply_keys().await?;
+            warn!("the client hasn't undergone through graceful shutdown the last time it's gone down - the most recent changes to its reply surbs and keys might have been lost");
         }
 
         if let Err(err) = manager.get_reply_surb_storage_metadata().await {
@@ -121,59 +112,78 @@ impl Backend {
         }
 
         Ok(Backend {
-            temporary_old_path: None,
-            database_path: owned_path,
             // manager: StorageManagerState::Storage(manager),
             manager,
         })
     }
 
-    async fn close_pool(&mut self) {
-        self.manager.connection_pool.close().await;
+    fn collect_tags_changes(&self, tags: &UsedSenderTags, changes: &mut StorageChanges) {
+        for recipient in tags.take_changes() {
+            match tags.get(&recipient) {
+                Some(tag) => changes
+                    .updated_tags
+                    .push(StoredSenderTag::new(recipient, tag)),
+                None => changes.removed_tags_recipients.push(recipient.to_vec()),
+            }
+        }
     }
 
-    async fn rotate(&mut self) -> Result<(), StorageError> {
-        self.close_pool().await;
-
-        let new_extension = if let Some(existing_extension) =
-            self.database_path.extension().and_then(|ext| ext.to_str())
-        {
-            format!("{existing_extension}.{}", Self::OLD_EXTENSION)
-        } else {
-            Self::OLD_EXTENSION.to_string()
-        };
-
-        let mut temp_old = self.database_path.clone();
-        temp_old.set_extension(new_extension);
+    fn collect_reply_keys_changes(&self, reply_keys: &SentReplyKeys, changes: &mut StorageChanges) {
+        for digest in reply_keys.take_changes() {
+            match reply_keys.get(&digest) {
+                Some(key) => changes
+                    .updated_reply_keys
+                    .push(StoredReplyKey::new(digest, key)),
+                None => changes.removed_reply_keys_digests.push(digest.to_vec()),
+            }
+        }
+    }
 
-        fs::rename(&self.database_path, &temp_old)
-            .map_err(|err| StorageError::DatabaseRenameError { source: err })?;
-        self.manager = StorageManager::init(&self.database_path, true).await?;
-        self.manager.create_status_table().await?;
+    fn collect_reply_surbs_changes(
+        &self,
+        reply_surbs: &ReceivedReplySurbsMap,
+        changes: &mut StorageChanges,
+    ) {
+        for tag in reply_surbs.take_changes() {
+            // make sure to not hold the map reference for longer than necessary
+            let updated = reply_surbs.get(&tag).map(|received_surbs| {
+                let sender = StoredSurbSender::new(tag, received_surbs.surbs_last_received_at());
+                let surbs = received_surbs
+                    .surbs_ref()
+                    .iter()
+                    .map(|reply_surb| reply_surb.to_bytes())
+                    .collect();
+                (sender, surbs)
+            });
 
-        self.temporary_old_path = Some(temp_old);
-        Ok(())
+            match updated {
+                Some(updated) => changes.updated_surb_senders.push(updated),
+                None => changes
+                    .removed_surb_senders_tags
+                    .push(tag.to_bytes().to_vec()),
+            }
+        }
     }
 
-    fn remove_old(&mut self) -> Result<(), StorageError> {
-        if let Some(old_path) = self.temporary_old_path.take() {
-            fs::remove_file(old_path)
-                .map_err(|err| StorageError::DatabaseOldFileRemoveError { source: err })
-        } else {
-            warn!("the old database file doesn't seem to exist!");
-            Ok(())
+    async fn persist_changes(&self, storage: &CombinedReplyStorage) -> Result<(), StorageError> {
+        let mut changes = StorageChanges::default();
+        self.collect_tags_changes(storage.tags_storage_ref(), &mut changes);
+        self.collect_reply_keys_changes(storage.key_storage_ref(), &mut changes);
+        self.collect_reply_surbs_changes(storage.surbs_storage_ref(), &mut changes);
+
+        if changes.is_empty() {
+            return Ok(());
         }
-    }
 
-    async fn start_storage_flush(&self) -> Result<(), StorageError> {
-        Ok(self.manager.set_flush_status(true).await?)
-    }
+        debug!(
+            "persisting changes to {} sender tags, {} reply keys and {} reply surb senders",
+            changes.updated_tags.len() + changes.removed_tags_recipients.len(),
+            changes.updated_reply_keys.len() + changes.removed_reply_keys_digests.len(),
+            changes.updated_surb_senders.len() + changes.removed_surb_senders_tags.len(),
+        );
 
-    async fn end_storage_flush(&self) -> Result<(), StorageError> {
-        self.manager
-            .set_previous_flush_timestamp(OffsetDateTime::now_utc().unix_timestamp())
-            .await?;
-        Ok(self.manager.set_flush_status(false).await?)
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        Ok(self.manager.apply_changes(changes, now).await?)
     }
 
     async fn start_client_use(&self) -> Result<(), StorageError> {
@@ -197,16 +207,6 @@ impl Backend {
         Ok(UsedSenderTags::from_raw(raw))
     }
 
-    async fn dump_sender_tags(&self, tags: &UsedSenderTags) -> Result<(), StorageError> {
-        for map_ref in tags.as_raw_iter() {
-            let (recipient, tag) = map_ref.pair();
-            self.manager
-                .insert_tag(StoredSenderTag::new(*recipient, *tag))
-                .await?;
-        }
-        Ok(())
-    }
-
     async fn get_stored_reply_keys(&self) -> Result<SentReplyKeys, StorageError> {
         let stored = self.manager.get_reply_keys().await?;
 
@@ -220,16 +220,6 @@ impl Backend {
         Ok(SentReplyKeys::from_raw(raw))
     }
 
-    async fn dump_sender_reply_keys(&self, reply_keys: &SentReplyKeys) -> Result<(), StorageError> {
-        for map_ref in reply_keys.as_raw_iter() {
-            let (digest, key) = map_ref.pair();
-            self.manager
-                .insert_reply_key(StoredReplyKey::new(*digest, *key))
-                .await?;
-        }
-        Ok(())
-    }
-
     async fn get_stored_reply_surbs(&self) -> Result<ReceivedReplySurbsMap, StorageError> {
         let surb_senders = self.manager.get_surb_senders().await?;
 
@@ -260,29 +250,6 @@ impl Backend {
         ))
     }
 
-    async fn dump_reply_surbs(
-        &self,
-        reply_surbs: &ReceivedReplySurbsMap,
-    ) -> Result<(), StorageError> {
-        for map_ref in reply_surbs.as_raw_iter() {
-            let (tag, received_surbs) = map_ref.pair();
-            let sender_id = self
-                .manager
-                .insert_surb_sender(StoredSurbSender::new(
-                    *tag,
-                    received_surbs.surbs_last_received_at(),
-                ))
-                .await?;
-
-            for reply_surb in received_surbs.surbs_ref() {
-                self.manager
-                    .insert_reply_surb(StoredReplySurb::new(sender_id, reply_surb))
-                    .await?
-            }
-        }
-        Ok(())
-    }
-
     async fn get_reply_surb_storage_metadata(
         &self,
     ) -> Result<ReplySurbStorageMetadata, StorageError> {
@@ -314,29 +281,34 @@ impl ReplyStorageBackend for Backend {
         self.start_client_use().await
     }
 
-    async fn flush_surb_storage(
+    async fn flush_pending_changes(
         &mut self,
         storage: &CombinedReplyStorage,
     ) -> Result<(), Self::StorageError> {
-        // close all connections (there should be none! and rename the file to contain .old extension)
-        self.rotate().await?;
-        self.start_storage_flush().await?;
+        self.persist_changes(storage).await
+    }
 
-        self.dump_sender_tags(storage.tags_storage_ref()).await?;
-        self.dump_sender_reply_keys(storage.key_storage_ref())
+    async fn flush_surb_storage(
+        &mut self,
+        storage: &CombinedReplyStorage,
+    ) -> Result<(), Self::StorageError> {
+        self.dump_reply_surb_storage_metadata(storage.surbs_storage_ref())
             .await?;
-        let surbs_ref = storage.surbs_storage_ref();
-        self.dump_reply_surb_storage_metadata(surbs_ref).await?;
-        self.dump_reply_surbs(surbs_ref).await?;
 
-        self.remove_old()?;
-        self.end_storage_flush().await
+        // everything but the most recent changes has already been written through
+        self.persist_changes(storage).await
     }
 
     async fn init_fresh(&mut self, fresh: &CombinedReplyStorage) -> Result<(), Self::StorageError> {
-        // for now nothing more to do apart from dumping the metadata
         self.dump_reply_surb_storage_metadata(fresh.surbs_storage_ref())
-            .await
+            .await?;
+
+        // mark the storage as valid so that it could be loaded even if the client
+        // has gone down before persisting any changes
+        Ok(self
+            .manager
+            .set_previous_flush_timestamp(OffsetDateTime::now_utc().unix_timestamp())
+            .await?)
     }
 
     async fn load_surb_storage(&self) -> Result<CombinedReplyStorage, Self::StorageError> {
@@ -344,10 +316,141 @@ impl ReplyStorageBackend for Backend {
         let tags = self.get_stored_tags().await?;
         let reply_surbs = self.get_stored_reply_surbs().await?;
 
-        Ok(CombinedReplyStorage::load(reply_keys, reply_surbs, tags))
+        let storage = CombinedReplyStorage::load(reply_keys, reply_surbs, tags);
+
+        // from now on, all the changes are going to be written through
+        storage.enable_change_tracking();
+        Ok(storage)
     }
 
     async fn stop_storage_session(self) -> Result<(), Self::StorageError> {
         self.stop_client_use().await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_crypto::asymmetric::{encryption, identity};
+    use nym_sphinx::addressing::clients::Recipient;
+    use nym_sphinx::anonymous_replies::SurbEncryptionKey;
+    use rand::rngs::OsRng;
+
+    fn random_recipient() -> Recipient {
+        Recipient::new(
+            *identity::KeyPair::new(&mut OsRng).public_key(),
+            *encryption::KeyPair::new(&mut OsRng).public_key(),
+            *identity::KeyPair::new(&mut OsRng).public_key(),
+        )
+    }
+
+    async fn fresh_backend(database_path: &Path) -> (Backend, CombinedReplyStorage) {
+        let mut backend = Backend::init(database_path).await.unwrap();
+        backend
+            .init_fresh(&CombinedReplyStorage::new(10, 100))
+            .await
+            .unwrap();
+        let storage = backend.load_surb_storage().await.unwrap();
+        (backend, storage)
+    }
+
+    async fn reload(database_path: &Path) -> CombinedReplyStorage {
+        Backend::try_load(database_path)
+            .await
+            .unwrap()
+            .load_surb_storage()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn changes_are_written_through() {
+        let dir = tempfile::tempdir().unwrap();
+        let database_path = dir.path().join("surbs.sqlite");
+        let (mut backend, storage) = fresh_backend(&database_path).await;
+
+        let recipient = random_recipient();
+        let tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let key = SurbEncryptionKey::new(&mut OsRng);
+        storage.tags_storage_ref().insert_new(&recipient, tag);
+        storage.key_storage_ref().insert_multiple(vec![key]);
+
+        backend.flush_pending_changes(&storage).await.unwrap();
+
+        let loaded = reload(&database_path).await;
+        assert_eq!(
+            loaded.tags_storage_ref().try_get_existing(&recipient),
+            Some(tag)
+        );
+        assert!(loaded
+            .key_storage_ref()
+            .get(&key.compute_digest())
+            .is_some());
+
+        // all the changes have already been persisted
+        let mut changes = StorageChanges::default();
+        backend.collect_tags_changes(storage.tags_storage_ref(), &mut changes);
+        backend.collect_reply_keys_changes(storage.key_storage_ref(), &mut changes);
+        assert!(changes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn removals_are_written_through() {
+        let dir = tempfile::tempdir().unwrap();
+        let database_path = dir.path().join("surbs.sqlite");
+        let (mut backend, storage) = fresh_backend(&database_path).await;
+
+        let kept = SurbEncryptionKey::new(&mut OsRng);
+        let removed = SurbEncryptionKey::new(&mut OsRng);
+        storage
+            .key_storage_ref()
+            .insert_multiple(vec![kept, removed]);
+        backend.flush_pending_changes(&storage).await.unwrap();
+
+        storage.key_storage_ref().remove(removed.compute_digest());
+        backend.flush_pending_changes(&storage).await.unwrap();
+
+        let loaded = reload(&database_path).await;
+        assert!(loaded
+            .key_storage_ref()
+            .get(&kept.compute_digest())
+            .is_some());
+        assert!(loaded
+            .key_storage_ref()
+            .get(&removed.compute_digest())
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn unflushed_changes_are_persisted_on_shutdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let database_path = dir.path().join("surbs.sqlite");
+        let (mut backend, storage) = fresh_backend(&database_path).await;
+
+        let key = SurbEncryptionKey::new(&mut OsRng);
+        storage.key_storage_ref().insert_multiple(vec![key]);
+        backend.flush_surb_storage(&storage).await.unwrap();
+
+        let loaded = reload(&database_path).await;
+        assert!(loaded
+            .key_storage_ref()
+            .get(&key.compute_digest())
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn surb_storage_metadata_is_persisted_on_shutdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let database_path = dir.path().join("surbs.sqlite");
+        let (mut backend, _) = fresh_backend(&database_path).await;
+
+        backend
+            .flush_surb_storage(&CombinedReplyStorage::new(20, 200))
+            .await
+            .unwrap();
+
+        let loaded = reload(&database_path).await;
+        assert_eq!(loaded.surbs_storage_ref().min_surb_threshold(), 20);
+        assert_eq!(loaded.surbs_storage_ref().max_surb_threshold(), 200);
+    }
+}
@@ -148,15 +148,6 @@ pub(crate) struct StoredReplySurb {
     pub(crate) reply_surb: Vec<u8>,
 }
 
-impl StoredReplySurb {
-    pub(crate) fn new(reply_surb_sender_id: i64, reply_surb: &ReplySurb) -> Self {
-        StoredReplySurb {
-            reply_surb_sender_id,
-            reply_surb: reply_surb.to_bytes(),
-        }
-    }
-}
-
 impl TryFrom<StoredReplySurb> for ReplySurb {
     type Error = StorageError;
 
@@ -181,3 +172,30 @@ impl ReplySurbStorageMetadata {
         }
     }
 }
+
+/// Set of changes made to the reply storage since they were last persisted.
+/// All of them are written within a single transaction.
+#[derive(Default)]
+pub(crate) struct StorageChanges {
+    pub(crate) updated_tags: Vec<StoredSenderTag>,
+    pub(crate) removed_tags_recipients: Vec<Vec<u8>>,
+
+    pub(crate) updated_reply_keys: Vec<StoredReplyKey>,
+    pub(crate) removed_reply_keys_digests: Vec<Vec<u8>>,
+
+    // as the senders might not have been assigned their ids yet,
+    // the surbs are stored in their raw form
+    pub(crate) updated_surb_senders: Vec<(StoredSurbSender, Vec<Vec<u8>>)>,
+    pub(crate) removed_surb_senders_tags: Vec<Vec<u8>>,
+}
+
+impl StorageChanges {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.updated_tags.is_empty()
+            && self.removed_tags_recipients.is_empty()
+            && self.updated_reply_keys.is_empty()
+            && self.removed_reply_keys_digests.is_empty()
+            && self.updated_surb_senders.is_empty()
+            && self.removed_surb_senders_tags.is_empty()
+    }
+}
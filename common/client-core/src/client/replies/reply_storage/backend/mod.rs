@@ -69,6 +69,16 @@ pub trait ReplyStorageBackend: Sized {
         Ok(())
     }
 
+    /// Persists all changes made to the storage since the previous call, so that they would not
+    /// get lost if the client went down without a graceful shutdown.
+    /// Backends that are only capable of saving their data on shutdown can safely ignore it.
+    async fn flush_pending_changes(
+        &mut self,
+        _storage: &CombinedReplyStorage,
+    ) -> Result<(), Self::StorageError> {
+        Ok(())
+    }
+
     // reply keys and surbs would need additional field set when data is loaded
     // so if there's some failure, we'd trash it all
     async fn flush_surb_storage(
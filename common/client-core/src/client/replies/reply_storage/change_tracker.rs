@@ -0,0 +1,124 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use dashmap::DashSet;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Keeps track of the entries that got modified since they were last persisted,
+/// so that the backend could write them through without having to dump the entire storage.
+///
+/// It's disabled by default as otherwise, with backends that don't persist data incrementally,
+/// the set of modified entries would keep on growing for the entire lifetime of the client.
+#[derive(Debug)]
+pub(crate) struct ChangeTracker<K: Eq + Hash> {
+    enabled: AtomicBool,
+    modified: DashSet<K>,
+}
+
+impl<K> ChangeTracker<K>
+where
+    K: Eq + Hash + Clone,
+{
+    pub(crate) fn new() -> Self {
+        ChangeTracker {
+            enabled: AtomicBool::new(false),
+            modified: DashSet::new(),
+        }
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
+    pub(crate) fn enable(&self) {
+        self.enabled.store(true, Ordering::Relaxed)
+    }
+
+    // note: this has to be called AFTER the entry got modified. otherwise, if the changes
+    // got taken in between, the backend might end up persisting the old value
+    pub(crate) fn mark(&self, key: K) {
+        if self.enabled.load(Ordering::Relaxed) {
+            self.modified.insert(key);
+        }
+    }
+
+    /// Returns all entries modified since the previous call.
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
+    pub(crate) fn take(&self) -> Vec<K> {
+        let candidates: Vec<_> = self.modified.iter().map(|key| key.key().clone()).collect();
+
+        // only return the entries we have actually removed ourselves. anything marked after
+        // the removal stays in the set and is going to be returned by the next call
+        candidates
+            .into_iter()
+            .filter_map(|key| self.modified.remove(&key))
+            .collect()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
+mod tests {
+    use super::*;
+
+    fn sorted(mut keys: Vec<u32>) -> Vec<u32> {
+        keys.sort_unstable();
+        keys
+    }
+
+    #[test]
+    fn nothing_is_tracked_until_enabled() {
+        let tracker = ChangeTracker::new();
+        tracker.mark(1);
+        assert!(tracker.take().is_empty());
+
+        tracker.enable();
+        tracker.mark(2);
+        assert_eq!(tracker.take(), vec![2]);
+    }
+
+    #[test]
+    fn changes_are_drained_once_taken() {
+        let tracker = ChangeTracker::new();
+        tracker.enable();
+        tracker.mark(1);
+        tracker.mark(2);
+        tracker.mark(1);
+
+        assert_eq!(sorted(tracker.take()), vec![1, 2]);
+        assert!(tracker.take().is_empty());
+    }
+
+    #[test]
+    fn entries_marked_after_taking_are_not_lost() {
+        let tracker = ChangeTracker::new();
+        tracker.enable();
+        tracker.mark(1);
+        assert_eq!(tracker.take(), vec![1]);
+
+        tracker.mark(1);
+        tracker.mark(3);
+        assert_eq!(sorted(tracker.take()), vec![1, 3]);
+    }
+
+    #[test]
+    fn concurrent_marks_are_never_lost() {
+        let tracker = std::sync::Arc::new(ChangeTracker::new());
+        tracker.enable();
+
+        let marker = {
+            let tracker = std::sync::Arc::clone(&tracker);
+            std::thread::spawn(move || {
+                for key in 0..10_000u32 {
+                    tracker.mark(key)
+                }
+            })
+        };
+
+        let mut taken = Vec::new();
+        while !marker.is_finished() {
+            taken.extend(tracker.take());
+        }
+        marker.join().unwrap();
+        taken.extend(tracker.take());
+
+        assert_eq!(sorted(taken), (0..10_000).collect::<Vec<_>>());
+    }
+}
@@ -34,6 +34,15 @@ impl CombinedReplyStorage {
         }
     }
 
+    /// Starts keeping track of all modified entries, so that they could be persisted
+    /// by the storage backend without having to dump the entire state.
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
+    pub(crate) fn enable_change_tracking(&self) {
+        self.sent_reply_keys.enable_change_tracking();
+        self.received_reply_surbs.enable_change_tracking();
+        self.used_tags.enable_change_tracking();
+    }
+
     pub fn key_storage(&self) -> SentReplyKeys {
         self.sent_reply_keys.clone()
     }
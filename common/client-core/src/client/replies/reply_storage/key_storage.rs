@@ -1,6 +1,7 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::replies::reply_storage::change_tracker::ChangeTracker;
 use dashmap::iter::Iter;
 use dashmap::DashMap;
 use nym_sphinx::anonymous_replies::encryption_key::EncryptionKeyDigest;
@@ -17,6 +18,7 @@ pub struct SentReplyKeys {
 #[derive(Debug)]
 struct SentReplyKeysInner {
     data: DashMap<EncryptionKeyDigest, UsedReplyKey>,
+    changes: ChangeTracker<EncryptionKeyDigest>,
 }
 
 impl SentReplyKeys {
@@ -24,6 +26,7 @@ impl SentReplyKeys {
         SentReplyKeys {
             inner: Arc::new(SentReplyKeysInner {
                 data: DashMap::new(),
+                changes: ChangeTracker::new(),
             }),
         }
     }
@@ -33,10 +36,26 @@ impl SentReplyKeys {
         SentReplyKeys {
             inner: Arc::new(SentReplyKeysInner {
                 data: raw.into_iter().collect(),
+                changes: ChangeTracker::new(),
             }),
         }
     }
 
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
+    pub(crate) fn enable_change_tracking(&self) {
+        self.inner.changes.enable()
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
+    pub(crate) fn take_changes(&self) -> Vec<EncryptionKeyDigest> {
+        self.inner.changes.take()
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
+    pub(crate) fn get(&self, digest: &EncryptionKeyDigest) -> Option<UsedReplyKey> {
+        self.inner.data.get(digest).map(|r| *r.value())
+    }
+
     pub(crate) fn as_raw_iter(&self) -> Iter<'_, EncryptionKeyDigest, UsedReplyKey> {
         self.inner.data.iter()
     }
@@ -49,15 +68,23 @@ impl SentReplyKeys {
     }
 
     pub(crate) fn insert(&self, key: UsedReplyKey) {
-        self.inner.data.insert(key.compute_digest(), key);
+        let digest = key.compute_digest();
+        self.inner.data.insert(digest, key);
+        self.inner.changes.mark(digest);
     }
 
     pub(crate) fn try_pop(&self, digest: EncryptionKeyDigest) -> Option<UsedReplyKey> {
-        self.inner.data.remove(&digest).map(|(_k, v)| v)
+        let key = self.inner.data.remove(&digest).map(|(_k, v)| v);
+        if key.is_some() {
+            self.inner.changes.mark(digest);
+        }
+        key
     }
 
     pub(crate) fn remove(&self, digest: EncryptionKeyDigest) {
-        self.inner.data.remove(&digest);
+        if self.inner.data.remove(&digest).is_some() {
+            self.inner.changes.mark(digest);
+        }
     }
 }
 
@@ -7,7 +7,12 @@ pub use crate::client::replies::reply_storage::surb_storage::ReceivedReplySurbsM
 pub use crate::client::replies::reply_storage::tag_storage::UsedSenderTags;
 pub use backend::*;
 
+use crate::client::helpers::new_interval_stream;
+use futures::StreamExt;
+use std::time::Duration;
+
 mod backend;
+mod change_tracker;
 mod combined;
 mod key_storage;
 mod surb_storage;
@@ -33,10 +38,12 @@ where
         self.backend.load_surb_storage().await
     }
 
-    // this will have to get enabled after merging develop
-    pub async fn flush_on_shutdown(
+    /// Periodically persists all the changes made to the in-memory storage and performs
+    /// the final data flush once the shutdown signal is received.
+    pub async fn run_with_shutdown(
         mut self,
         mem_state: CombinedReplyStorage,
+        flush_interval: Duration,
         mut shutdown: nym_task::TaskClient,
     ) {
         use log::{debug, error, info, warn};
@@ -47,7 +54,21 @@ where
             return;
         }
 
-        shutdown.recv().await;
+        let mut flush_inspection = new_interval_stream(flush_interval);
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown.recv() => {
+                    log::trace!("PersistentReplyStorage: Received shutdown");
+                    break;
+                }
+                _ = flush_inspection.next() => {
+                    if let Err(err) = self.backend.flush_pending_changes(&mem_state).await {
+                        error!("failed to persist the recent changes to our reply-related data: {err}")
+                    }
+                }
+            }
+        }
 
         info!("PersistentReplyStorage is flushing all reply-related data to underlying storage");
         warn!("you MUST NOT forcefully shutdown now or you risk data corruption!");
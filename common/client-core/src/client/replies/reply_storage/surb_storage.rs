@@ -1,6 +1,7 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::replies::reply_storage::change_tracker::ChangeTracker;
 use dashmap::iter::Iter;
 use dashmap::DashMap;
 use log::trace;
@@ -19,6 +20,7 @@ pub struct ReceivedReplySurbsMap {
 #[derive(Debug)]
 struct ReceivedReplySurbsMapInner {
     data: DashMap<AnonymousSenderTag, ReceivedReplySurbs>,
+    changes: ChangeTracker<AnonymousSenderTag>,
 
     // the minimum amount of surbs that have to be kept in storage for requests for more surbs
     min_surb_threshold: AtomicUsize,
@@ -35,6 +37,7 @@ impl ReceivedReplySurbsMap {
         ReceivedReplySurbsMap {
             inner: Arc::new(ReceivedReplySurbsMapInner {
                 data: DashMap::new(),
+                changes: ChangeTracker::new(),
                 min_surb_threshold: AtomicUsize::new(min_surb_threshold),
                 max_surb_threshold: AtomicUsize::new(max_surb_threshold),
             }),
@@ -50,23 +53,47 @@ impl ReceivedReplySurbsMap {
         ReceivedReplySurbsMap {
             inner: Arc::new(ReceivedReplySurbsMapInner {
                 data: raw.into_iter().collect(),
+                changes: ChangeTracker::new(),
                 min_surb_threshold: AtomicUsize::new(min_surb_threshold),
                 max_surb_threshold: AtomicUsize::new(max_surb_threshold),
             }),
         }
     }
 
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
+    pub(crate) fn enable_change_tracking(&self) {
+        self.inner.changes.enable()
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
+    pub(crate) fn take_changes(&self) -> Vec<AnonymousSenderTag> {
+        self.inner.changes.take()
+    }
+
+    // note: the returned reference holds a read lock on the underlying map shard,
+    // so it must not be held across any await points
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
+    pub(crate) fn get(
+        &self,
+        target: &AnonymousSenderTag,
+    ) -> Option<dashmap::mapref::one::Ref<'_, AnonymousSenderTag, ReceivedReplySurbs>> {
+        self.inner.data.get(target)
+    }
+
     pub(crate) fn as_raw_iter(&self) -> Iter<'_, AnonymousSenderTag, ReceivedReplySurbs> {
         self.inner.data.iter()
     }
 
     pub(crate) fn remove(&self, target: &AnonymousSenderTag) {
-        self.inner.data.remove(target);
+        if self.inner.data.remove(target).is_some() {
+            self.inner.changes.mark(*target);
+        }
     }
 
     pub(crate) fn reset_surbs_last_received_at(&self, target: &AnonymousSenderTag) {
         if let Some(mut entry) = self.inner.data.get_mut(target) {
             entry.surbs_last_received_at_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+            self.inner.changes.mark(*target);
         }
     }
 
@@ -143,7 +170,11 @@ impl ReceivedReplySurbsMap {
             if surbs_left < self.min_surb_threshold() + amount {
                 (None, surbs_left)
             } else {
-                entry.get_reply_surbs(amount)
+                let retrieved = entry.get_reply_surbs(amount);
+                if retrieved.0.is_some() {
+                    self.inner.changes.mark(*target);
+                }
+                retrieved
             }
         } else {
             (None, 0)
@@ -154,10 +185,13 @@ impl ReceivedReplySurbsMap {
         &self,
         target: &AnonymousSenderTag,
     ) -> Option<(Option<ReplySurb>, usize)> {
-        self.inner
-            .data
-            .get_mut(target)
-            .map(|mut s| s.get_reply_surb())
+        self.inner.data.get_mut(target).map(|mut entry| {
+            let retrieved = entry.get_reply_surb();
+            if retrieved.0.is_some() {
+                self.inner.changes.mark(*target);
+            }
+            retrieved
+        })
     }
 
     pub(crate) fn get_reply_surb(
@@ -169,7 +203,11 @@ impl ReceivedReplySurbsMap {
             if surbs_left < self.min_surb_threshold() {
                 (None, surbs_left)
             } else {
-                entry.get_reply_surb()
+                let retrieved = entry.get_reply_surb();
+                if retrieved.0.is_some() {
+                    self.inner.changes.mark(*target);
+                }
+                retrieved
             }
         })
     }
@@ -185,6 +223,7 @@ impl ReceivedReplySurbsMap {
             let new_entry = ReceivedReplySurbs::new(surbs.into_iter().collect());
             self.inner.data.insert(*target, new_entry);
         }
+        self.inner.changes.mark(*target);
     }
 }
 
@@ -1,14 +1,12 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::replies::reply_storage::change_tracker::ChangeTracker;
 use dashmap::DashMap;
 use nym_sphinx::addressing::clients::{Recipient, RecipientBytes};
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use std::sync::Arc;
 
-#[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
-use dashmap::iter::Iter;
-
 #[derive(Debug, Clone)]
 pub struct UsedSenderTags {
     inner: Arc<UsedSenderTagsInner>,
@@ -17,6 +15,7 @@ pub struct UsedSenderTags {
 #[derive(Debug)]
 struct UsedSenderTagsInner {
     data: DashMap<RecipientBytes, AnonymousSenderTag>,
+    changes: ChangeTracker<RecipientBytes>,
 }
 
 impl UsedSenderTags {
@@ -24,6 +23,7 @@ impl UsedSenderTags {
         UsedSenderTags {
             inner: Arc::new(UsedSenderTagsInner {
                 data: DashMap::new(),
+                changes: ChangeTracker::new(),
             }),
         }
     }
@@ -33,17 +33,30 @@ impl UsedSenderTags {
         UsedSenderTags {
             inner: Arc::new(UsedSenderTagsInner {
                 data: raw.into_iter().collect(),
+                changes: ChangeTracker::new(),
             }),
         }
     }
 
     #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
-    pub(crate) fn as_raw_iter(&self) -> Iter<'_, RecipientBytes, AnonymousSenderTag> {
-        self.inner.data.iter()
+    pub(crate) fn enable_change_tracking(&self) {
+        self.inner.changes.enable()
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
+    pub(crate) fn take_changes(&self) -> Vec<RecipientBytes> {
+        self.inner.changes.take()
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
+    pub(crate) fn get(&self, recipient: &RecipientBytes) -> Option<AnonymousSenderTag> {
+        self.inner.data.get(recipient).map(|r| *r.value())
     }
 
     pub(crate) fn insert_new(&self, recipient: &Recipient, tag: AnonymousSenderTag) {
-        self.inner.data.insert(recipient.to_bytes(), tag);
+        let recipient = recipient.to_bytes();
+        self.inner.data.insert(recipient, tag);
+        self.inner.changes.mark(recipient);
     }
 
     pub(crate) fn try_get_existing(&self, recipient: &Recipient) -> Option<AnonymousSenderTag> {
@@ -5,12 +5,15 @@ use crate::spawn_future;
 pub(crate) use accessor::{TopologyAccessor, TopologyReadPermit};
 use futures::StreamExt;
 use log::*;
+pub use nym_api_provider::nym_topology_with_sphinx_keys;
 use nym_topology::provider_trait::TopologyProvider;
 use nym_topology::NymTopologyError;
 use std::time::Duration;
 
 mod accessor;
 pub(crate) mod nym_api_provider;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod snapshot_provider;
 
 // TODO: move it to config later
 const MAX_FAILURE_COUNT: usize = 10;
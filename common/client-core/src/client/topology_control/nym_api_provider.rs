@@ -3,10 +3,18 @@
 
 use async_trait::async_trait;
 use log::{error, warn};
+use nym_crypto::asymmetric::encryption;
 use nym_topology::provider_trait::TopologyProvider;
-use nym_topology::{nym_topology_from_detailed, NymTopology, NymTopologyError};
+use nym_topology::{
+    nym_topology_from_detailed, AnnouncedSphinxKey, AnnouncedSphinxKeys, NymTopology,
+    NymTopologyError, SphinxKeyValidity,
+};
+use nym_validator_client::models::{
+    GatewayBondWithSphinxKeys, MixNodeDetailsWithSphinxKeys, NodeSphinxKeys,
+};
 use rand::prelude::SliceRandom;
 use rand::thread_rng;
+use std::collections::HashMap;
 use url::Url;
 
 pub(crate) struct NymApiTopologyProvider {
@@ -61,7 +69,11 @@ impl NymApiTopologyProvider {
     }
 
     async fn get_current_compatible_topology(&mut self) -> Option<NymTopology> {
-        let mixnodes = match self.validator_client.get_cached_active_mixnodes().await {
+        let mixnodes = match self
+            .validator_client
+            .get_cached_active_mixnodes_with_sphinx_keys()
+            .await
+        {
             Err(err) => {
                 error!("failed to get network mixnodes - {err}");
                 return None;
@@ -69,7 +81,11 @@ impl NymApiTopologyProvider {
             Ok(mixes) => mixes,
         };
 
-        let gateways = match self.validator_client.get_cached_gateways().await {
+        let gateways = match self
+            .validator_client
+            .get_cached_gateways_with_sphinx_keys()
+            .await
+        {
             Err(err) => {
                 error!("failed to get network gateways - {err}");
                 return None;
@@ -77,7 +93,16 @@ impl NymApiTopologyProvider {
             Ok(gateways) => gateways,
         };
 
-        let topology = nym_topology_from_detailed(mixnodes, gateways)
+        let epoch_id = match self.validator_client.get_current_epoch().await {
+            Err(err) => {
+                error!("failed to get the current epoch - {err}");
+                return None;
+            }
+            Ok(interval) => interval.current_epoch_absolute_id(),
+        };
+
+        // make sure we're only going to use the sphinx keys the nodes are using in the current epoch
+        let topology = nym_topology_with_sphinx_keys(mixnodes, gateways, epoch_id)
             .filter_system_version(&self.client_version);
 
         if let Err(err) = self.check_layer_distribution(&topology) {
@@ -90,6 +115,101 @@ impl NymApiTopologyProvider {
     }
 }
 
+/// Constructs the network topology out of the nym api responses, making all nodes use the sphinx keys
+/// they are using during the specified epoch. Nodes without any valid key are left out.
+pub fn nym_topology_with_sphinx_keys(
+    mixnodes: Vec<MixNodeDetailsWithSphinxKeys>,
+    gateways: Vec<GatewayBondWithSphinxKeys>,
+    epoch_id: u32,
+) -> NymTopology {
+    let mut announced_keys = HashMap::new();
+    let mixnodes = mixnodes
+        .into_iter()
+        .map(|mixnode| {
+            if let Some(keys) = mixnode.sphinx_keys {
+                let identity = &mixnode.details.bond_information.mix_node.identity_key;
+                announced_keys.insert(identity.clone(), announced_sphinx_keys(identity, keys));
+            }
+            mixnode.details
+        })
+        .collect();
+    let gateways = gateways
+        .into_iter()
+        .map(|gateway| {
+            if let Some(keys) = gateway.sphinx_keys {
+                let identity = &gateway.bond.gateway.identity_key;
+                announced_keys.insert(identity.clone(), announced_sphinx_keys(identity, keys));
+            }
+            gateway.bond
+        })
+        .collect();
+
+    with_announced_sphinx_keys(
+        nym_topology_from_detailed(mixnodes, gateways),
+        &announced_keys,
+    )
+    .with_sphinx_keys_for_epoch(epoch_id)
+}
+
+fn announced_sphinx_keys(identity: &str, keys: NodeSphinxKeys) -> AnnouncedSphinxKeys {
+    AnnouncedSphinxKeys {
+        bonded_key_valid_until: keys.bonded_key_valid_until,
+        keys: keys
+            .announced
+            .into_iter()
+            .filter_map(|announced| {
+                match encryption::PublicKey::from_base58_string(&announced.sphinx_key) {
+                    Ok(sphinx_key) => Some(AnnouncedSphinxKey {
+                        sphinx_key,
+                        validity: SphinxKeyValidity {
+                            valid_from: announced.validity.valid_from,
+                            valid_until: announced.validity.valid_until,
+                        },
+                    }),
+                    Err(err) => {
+                        warn!("{identity} has announced a malformed sphinx key - {err}");
+                        None
+                    }
+                }
+            })
+            .collect(),
+    }
+}
+
+fn with_announced_sphinx_keys(
+    topology: NymTopology,
+    announced_keys: &HashMap<String, AnnouncedSphinxKeys>,
+) -> NymTopology {
+    let keys_of = |identity: String| announced_keys.get(&identity).cloned();
+
+    let mixes = topology
+        .mixes()
+        .iter()
+        .map(|(layer, nodes)| {
+            let nodes = nodes
+                .iter()
+                .cloned()
+                .map(|mut node| {
+                    node.announced_sphinx_keys = keys_of(node.identity_key.to_base58_string());
+                    node
+                })
+                .collect();
+            (*layer, nodes)
+        })
+        .collect();
+    let gateways = topology
+        .gateways()
+        .iter()
+        .cloned()
+        .map(|mut node| {
+            node.announced_sphinx_keys = keys_of(node.identity_key.to_base58_string());
+            node
+        })
+        .collect();
+
+    NymTopology::new(mixes, gateways)
+}
+
 // hehe, wasm
 #[cfg(not(target_arch = "wasm32"))]
 #[async_trait]
@@ -0,0 +1,455 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use nym_crypto::asymmetric::{encryption, identity};
+use nym_topology::mix::MixnodeConversionError;
+use nym_topology::provider_trait::TopologyProvider;
+use nym_topology::{gateway, mix, MixLayer, NymTopology, NymTopologyError};
+use nym_validator_client::models::{
+    SignedTopologySnapshot, SphinxKeyValidity, TopologySnapshot, TopologySnapshotGateway,
+    TopologySnapshotMixNode,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use thiserror::Error;
+use time::OffsetDateTime;
+use url::Url;
+
+/// Maximum age of the cached snapshot that's still going to be used for starting up the client.
+/// Older snapshots are very unlikely to still reflect the state of the network.
+pub const MAX_CACHED_SNAPSHOT_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Error)]
+pub enum TopologySnapshotError {
+    #[error("the snapshot has been signed by an untrusted nym-api: {signer}")]
+    UntrustedSigner { signer: String },
+
+    #[error("the snapshot signature is malformed: {0}")]
+    MalformedSignature(#[from] identity::Ed25519RecoveryError),
+
+    #[error("the snapshot signature is invalid: {0}")]
+    InvalidSignature(#[from] identity::SignatureError),
+
+    #[error("the signed snapshot is malformed: {0}")]
+    MalformedSnapshot(#[from] serde_json::Error),
+
+    #[error(
+        "the snapshot is for epoch {received}, but we have already seen one for epoch {latest}"
+    )]
+    StaleSnapshot { received: u32, latest: u32 },
+
+    #[error("the snapshot has been generated at {generated_at}, which is too long ago to be used")]
+    ExpiredSnapshot { generated_at: i64 },
+}
+
+/// Verifies the signature on the snapshot and makes sure it's not older than the latest one we've seen.
+fn verify_snapshot(
+    signed_snapshot: &SignedTopologySnapshot,
+    trusted_signers: &[identity::PublicKey],
+    latest_epoch: Option<u32>,
+) -> Result<TopologySnapshot, TopologySnapshotError> {
+    let signer = identity::PublicKey::from_base58_string(&signed_snapshot.signer)?;
+    if !trusted_signers.contains(&signer) {
+        return Err(TopologySnapshotError::UntrustedSigner {
+            signer: signed_snapshot.signer.clone(),
+        });
+    }
+
+    let signature = identity::Signature::from_base58_string(&signed_snapshot.signature)?;
+    signer.verify(signed_snapshot.signable_bytes(), &signature)?;
+
+    let snapshot = signed_snapshot.decode_snapshot()?;
+    if let Some(latest) = latest_epoch {
+        if snapshot.epoch_id < latest {
+            return Err(TopologySnapshotError::StaleSnapshot {
+                received: snapshot.epoch_id,
+                latest,
+            });
+        }
+    }
+
+    Ok(snapshot)
+}
+
+/// Makes sure the snapshot is recent enough to be used for starting up the client.
+fn check_snapshot_age(
+    snapshot: &TopologySnapshot,
+    now: OffsetDateTime,
+    max_age: Duration,
+) -> Result<(), TopologySnapshotError> {
+    let cutoff = now.unix_timestamp() - max_age.as_secs() as i64;
+    if snapshot.generated_at < cutoff {
+        return Err(TopologySnapshotError::ExpiredSnapshot {
+            generated_at: snapshot.generated_at,
+        });
+    }
+    Ok(())
+}
+
+/// Topology provider that retrieves compact, per-epoch snapshots of the network topology
+/// from nym-api and only accepts them if they have been signed by one of the trusted nym-apis.
+///
+/// Optionally, the last valid snapshot is persisted on disk so that it could be used for
+/// starting up the client even if no nym-api is reachable.
+pub struct SignedSnapshotTopologyProvider {
+    validator_client: nym_validator_client::client::NymApiClient,
+    nym_api_urls: Vec<Url>,
+    currently_used_api: usize,
+
+    trusted_signers: Vec<identity::PublicKey>,
+    client_version: String,
+
+    snapshot_cache_path: Option<PathBuf>,
+    latest_epoch: Option<u32>,
+}
+
+impl SignedSnapshotTopologyProvider {
+    pub fn new(
+        nym_api_urls: Vec<Url>,
+        trusted_signers: Vec<identity::PublicKey>,
+        client_version: String,
+    ) -> Self {
+        SignedSnapshotTopologyProvider {
+            validator_client: nym_validator_client::client::NymApiClient::new(
+                nym_api_urls[0].clone(),
+            ),
+            nym_api_urls,
+            currently_used_api: 0,
+            trusted_signers,
+            client_version,
+            snapshot_cache_path: None,
+            latest_epoch: None,
+        }
+    }
+
+    /// Persist the last valid snapshot at the provided path.
+    #[must_use]
+    pub fn with_snapshot_cache(mut self, snapshot_cache_path: PathBuf) -> Self {
+        self.snapshot_cache_path = Some(snapshot_cache_path);
+        self
+    }
+
+    fn use_next_nym_api(&mut self) {
+        if self.nym_api_urls.len() == 1 {
+            warn!("There's only a single nym API available - it won't be possible to use a different one");
+            return;
+        }
+
+        self.currently_used_api = (self.currently_used_api + 1) % self.nym_api_urls.len();
+        self.validator_client
+            .change_nym_api(self.nym_api_urls[self.currently_used_api].clone())
+    }
+
+    fn verify_snapshot(
+        &self,
+        signed_snapshot: &SignedTopologySnapshot,
+    ) -> Result<TopologySnapshot, TopologySnapshotError> {
+        verify_snapshot(signed_snapshot, &self.trusted_signers, self.latest_epoch)
+    }
+
+    fn load_cached_snapshot(&self) -> Option<SignedTopologySnapshot> {
+        let path = self.snapshot_cache_path.as_ref()?;
+        let raw = match fs::read(path) {
+            Ok(raw) => raw,
+            Err(err) => {
+                debug!("failed to read cached topology snapshot from {path:?}: {err}");
+                return None;
+            }
+        };
+
+        match serde_json::from_slice(&raw) {
+            Ok(snapshot) => Some(snapshot),
+            Err(err) => {
+                warn!("the cached topology snapshot at {path:?} is malformed: {err}");
+                None
+            }
+        }
+    }
+
+    fn persist_snapshot(&self, signed_snapshot: &SignedTopologySnapshot) {
+        let Some(path) = self.snapshot_cache_path.as_ref() else {
+            return;
+        };
+
+        // serialization of a plain struct can't possibly fail
+        let raw = serde_json::to_vec(signed_snapshot).unwrap();
+        if let Err(err) = fs::write(path, raw) {
+            warn!("failed to persist the topology snapshot at {path:?}: {err}")
+        }
+    }
+
+    async fn get_verified_snapshot(&mut self) -> Option<TopologySnapshot> {
+        match self.validator_client.get_topology_snapshot().await {
+            Ok(signed_snapshot) => match self.verify_snapshot(&signed_snapshot) {
+                Ok(snapshot) => {
+                    self.persist_snapshot(&signed_snapshot);
+                    return Some(snapshot);
+                }
+                Err(err) => {
+                    error!("the received topology snapshot is invalid - {err}");
+                    self.use_next_nym_api();
+                }
+            },
+            Err(err) => {
+                error!("failed to get the topology snapshot - {err}");
+                self.use_next_nym_api();
+            }
+        }
+
+        // if we have never obtained any topology, attempt to fallback to the cached one,
+        // so that we could cold start even if the nym-api is not available
+        if self.latest_epoch.is_some() {
+            return None;
+        }
+
+        let cached = self.load_cached_snapshot()?;
+        let verified = self.verify_snapshot(&cached).and_then(|snapshot| {
+            check_snapshot_age(
+                &snapshot,
+                OffsetDateTime::now_utc(),
+                MAX_CACHED_SNAPSHOT_AGE,
+            )
+            .map(|_| snapshot)
+        });
+        match verified {
+            Ok(snapshot) => {
+                info!(
+                    "using the cached topology snapshot from epoch {}",
+                    snapshot.epoch_id
+                );
+                Some(snapshot)
+            }
+            Err(err) => {
+                warn!("the cached topology snapshot is invalid - {err}");
+                None
+            }
+        }
+    }
+
+    async fn get_current_compatible_topology(&mut self) -> Option<NymTopology> {
+        let snapshot = self.get_verified_snapshot().await?;
+        let epoch_id = snapshot.epoch_id;
+        self.latest_epoch = Some(epoch_id);
+
+        // make sure we're not going to use any sphinx key that the node is no longer (or not yet) using
+        let topology = topology_from_snapshot(snapshot)
+            .filter_system_version(&self.client_version)
+            .with_sphinx_keys_for_epoch(epoch_id);
+
+        if let Err(err) = check_layer_distribution(&topology) {
+            warn!("The current filtered active topology has extremely skewed layer distribution. It cannot be used: {err}");
+            None
+        } else {
+            Some(topology)
+        }
+    }
+}
+
+// see `NymApiTopologyProvider::check_layer_distribution` for the explanation of the thresholds
+fn check_layer_distribution(active_topology: &NymTopology) -> Result<(), NymTopologyError> {
+    let lower_threshold = 0.15;
+    let upper_threshold = 0.66;
+    active_topology.ensure_even_layer_distribution(lower_threshold, upper_threshold)
+}
+
+fn snapshot_key_validity(validity: SphinxKeyValidity) -> nym_topology::SphinxKeyValidity {
+    nym_topology::SphinxKeyValidity {
+        valid_from: validity.valid_from,
+        valid_until: validity.valid_until,
+    }
+}
+
+fn snapshot_mixnode(node: TopologySnapshotMixNode) -> Result<mix::Node, MixnodeConversionError> {
+    let host = mix::Node::parse_host(&node.host)?;
+    let mix_host = mix::Node::extract_mix_host(&host, node.mix_port)?;
+
+    Ok(mix::Node {
+        mix_id: node.mix_id,
+        owner: node.owner,
+        host,
+        mix_host,
+        identity_key: identity::PublicKey::from_base58_string(&node.identity_key)?,
+        sphinx_key: encryption::PublicKey::from_base58_string(&node.sphinx_key)?,
+        sphinx_key_validity: node.sphinx_key_validity.map(snapshot_key_validity),
+        announced_sphinx_keys: None,
+        layer: node.layer,
+        version: node.version,
+    })
+}
+
+fn snapshot_gateway(
+    node: TopologySnapshotGateway,
+) -> Result<gateway::Node, gateway::GatewayConversionError> {
+    let host = gateway::Node::parse_host(&node.host)?;
+    let mix_host = gateway::Node::extract_mix_host(&host, node.mix_port)?;
+
+    Ok(gateway::Node {
+        owner: node.owner,
+        host,
+        mix_host,
+        clients_port: node.clients_port,
+        identity_key: identity::PublicKey::from_base58_string(&node.identity_key)?,
+        sphinx_key: encryption::PublicKey::from_base58_string(&node.sphinx_key)?,
+        sphinx_key_validity: node.sphinx_key_validity.map(snapshot_key_validity),
+        announced_sphinx_keys: None,
+        version: node.version,
+    })
+}
+
+fn topology_from_snapshot(snapshot: TopologySnapshot) -> NymTopology {
+    let mut mixes: BTreeMap<MixLayer, Vec<mix::Node>> = BTreeMap::new();
+    for node in snapshot.mixnodes {
+        let mix_id = node.mix_id;
+        match snapshot_mixnode(node) {
+            Ok(mix) => mixes.entry(mix.layer.into()).or_default().push(mix),
+            Err(err) => warn!("Mix {mix_id} is malformed - {err}"),
+        }
+    }
+
+    let mut gateways = Vec::with_capacity(snapshot.gateways.len());
+    for node in snapshot.gateways {
+        let gateway_id = node.identity_key.clone();
+        match snapshot_gateway(node) {
+            Ok(gateway) => gateways.push(gateway),
+            Err(err) => warn!("Gateway {gateway_id} is malformed - {err}"),
+        }
+    }
+
+    NymTopology::new(mixes, gateways)
+}
+
+#[async_trait]
+impl TopologyProvider for SignedSnapshotTopologyProvider {
+    async fn get_new_topology(&mut self) -> Option<NymTopology> {
+        self.get_current_compatible_topology().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    fn snapshot(epoch_id: u32, generated_at: i64) -> TopologySnapshot {
+        TopologySnapshot {
+            epoch_id,
+            generated_at,
+            mixnodes: Vec::new(),
+            gateways: Vec::new(),
+        }
+    }
+
+    fn sign_raw(keys: &identity::KeyPair, raw_snapshot: String) -> SignedTopologySnapshot {
+        let signature = keys.private_key().sign(raw_snapshot.as_bytes());
+        SignedTopologySnapshot {
+            snapshot: raw_snapshot,
+            signer: keys.public_key().to_base58_string(),
+            signature: signature.to_base58_string(),
+        }
+    }
+
+    fn sign(keys: &identity::KeyPair, snapshot: &TopologySnapshot) -> SignedTopologySnapshot {
+        sign_raw(keys, snapshot.encode())
+    }
+
+    #[test]
+    fn snapshot_signed_by_trusted_signer_is_accepted() {
+        let keys = identity::KeyPair::new(&mut OsRng);
+        let signed = sign(&keys, &snapshot(42, 1000));
+
+        let verified = verify_snapshot(&signed, &[*keys.public_key()], Some(41)).unwrap();
+        assert_eq!(verified, snapshot(42, 1000));
+
+        // the same epoch might get re-signed if some node has announced a new sphinx key
+        assert!(verify_snapshot(&signed, &[*keys.public_key()], Some(42)).is_ok());
+    }
+
+    #[test]
+    fn snapshot_with_unknown_fields_is_accepted() {
+        // the snapshot might have been produced by a newer nym-api
+        let keys = identity::KeyPair::new(&mut OsRng);
+        let raw =
+            r#"{"epoch_id":42,"generated_at":1000,"mixnodes":[],"gateways":[],"new_field":123}"#;
+        let signed = sign_raw(&keys, raw.to_string());
+
+        let verified = verify_snapshot(&signed, &[*keys.public_key()], None).unwrap();
+        assert_eq!(verified, snapshot(42, 1000));
+    }
+
+    #[test]
+    fn snapshot_signed_by_untrusted_signer_is_rejected() {
+        let keys = identity::KeyPair::new(&mut OsRng);
+        let trusted = identity::KeyPair::new(&mut OsRng);
+        let signed = sign(&keys, &snapshot(42, 1000));
+
+        assert!(matches!(
+            verify_snapshot(&signed, &[*trusted.public_key()], None),
+            Err(TopologySnapshotError::UntrustedSigner { .. })
+        ));
+        assert!(matches!(
+            verify_snapshot(&signed, &[], None),
+            Err(TopologySnapshotError::UntrustedSigner { .. })
+        ));
+    }
+
+    #[test]
+    fn snapshot_with_bad_signature_is_rejected() {
+        let keys = identity::KeyPair::new(&mut OsRng);
+        let trusted = [*keys.public_key()];
+
+        let mut tampered = sign(&keys, &snapshot(42, 1000));
+        tampered.snapshot = snapshot(43, 1000).encode();
+        assert!(matches!(
+            verify_snapshot(&tampered, &trusted, None),
+            Err(TopologySnapshotError::InvalidSignature(_))
+        ));
+
+        // signature made by somebody else on behalf of the trusted signer
+        let impostor = identity::KeyPair::new(&mut OsRng);
+        let mut forged = sign(&impostor, &snapshot(42, 1000));
+        forged.signer = keys.public_key().to_base58_string();
+        assert!(matches!(
+            verify_snapshot(&forged, &trusted, None),
+            Err(TopologySnapshotError::InvalidSignature(_))
+        ));
+
+        let mut malformed = sign(&keys, &snapshot(42, 1000));
+        malformed.signature = "foomp".to_string();
+        assert!(matches!(
+            verify_snapshot(&malformed, &trusted, None),
+            Err(TopologySnapshotError::MalformedSignature(_))
+        ));
+    }
+
+    #[test]
+    fn snapshot_from_stale_epoch_is_rejected() {
+        let keys = identity::KeyPair::new(&mut OsRng);
+        let signed = sign(&keys, &snapshot(41, 1000));
+
+        assert!(matches!(
+            verify_snapshot(&signed, &[*keys.public_key()], Some(42)),
+            Err(TopologySnapshotError::StaleSnapshot {
+                received: 41,
+                latest: 42
+            })
+        ));
+    }
+
+    #[test]
+    fn old_snapshot_is_expired() {
+        let now = OffsetDateTime::now_utc();
+        let max_age = Duration::from_secs(60 * 60);
+        let generated_at = now.unix_timestamp() - 60 * 60;
+
+        assert!(check_snapshot_age(&snapshot(42, generated_at), now, max_age).is_ok());
+        assert!(check_snapshot_age(&snapshot(42, now.unix_timestamp()), now, max_age).is_ok());
+        assert!(matches!(
+            check_snapshot_age(&snapshot(42, generated_at - 1), now, max_age),
+            Err(TopologySnapshotError::ExpiredSnapshot { .. })
+        ));
+    }
+}
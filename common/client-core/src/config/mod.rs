@@ -3,7 +3,8 @@
 
 use nym_config::defaults::NymNetworkDetails;
 use nym_config::{NymConfig, OptionalSet, CRED_DB_FILE_NAME};
-use nym_sphinx::params::PacketSize;
+use nym_sphinx::chunking::ErasureCoding;
+use nym_sphinx::params::{PacketSize, PacketType};
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 use std::path::PathBuf;
@@ -22,6 +23,9 @@ pub const MISSING_VALUE: &str = "MISSING VALUE";
 const DEFAULT_ACK_WAIT_MULTIPLIER: f64 = 1.5;
 
 const DEFAULT_ACK_WAIT_ADDITION: Duration = Duration::from_millis(1_500);
+// with the default ack timeouts, a packet of a tracked message is retried for about 20s
+// before the message is given up on. untracked messages are not subject to this limit.
+const DEFAULT_MAXIMUM_RETRANSMISSIONS: u32 = 10;
 const DEFAULT_LOOP_COVER_STREAM_AVERAGE_DELAY: Duration = Duration::from_millis(200);
 const DEFAULT_MESSAGE_STREAM_AVERAGE_DELAY: Duration = Duration::from_millis(20);
 const DEFAULT_AVERAGE_PACKET_DELAY: Duration = Duration::from_millis(50);
@@ -32,6 +36,11 @@ const DEFAULT_TOPOLOGY_RESOLUTION_TIMEOUT: Duration = Duration::from_millis(5_00
 // bandwidth bridging protocol, we can come back to a smaller timeout value
 const DEFAULT_GATEWAY_RESPONSE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
 
+// gateway failover related:
+// clients/client-core/src/client/gateway_failover.rs
+const DEFAULT_MAXIMUM_FALLBACK_GATEWAYS: usize = 3;
+const DEFAULT_GATEWAY_FAILOVER_THRESHOLD: usize = 3;
+
 const DEFAULT_COVER_TRAFFIC_PRIMARY_SIZE_RATIO: f64 = 0.70;
 
 // reply-surbs related:
@@ -57,6 +66,14 @@ const DEFAULT_MAXIMUM_REPLY_SURB_AGE: Duration = Duration::from_secs(12 * 60 * 6
 // 24 hours
 const DEFAULT_MAXIMUM_REPLY_KEY_AGE: Duration = Duration::from_secs(24 * 60 * 60);
 
+const DEFAULT_REPLY_STORAGE_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+// message reconstruction related:
+// clients/client-core/src/client/received_buffer.rs
+const DEFAULT_MAXIMUM_PARTIAL_MESSAGE_AGE: Duration = Duration::from_secs(10 * 60);
+const DEFAULT_MAXIMUM_BUFFERED_PARTIAL_MESSAGE_BYTES: usize = 128 * 1024 * 1024;
+const DEFAULT_STALE_PARTIAL_MESSAGE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
 pub fn missing_string_value() -> String {
     MISSING_VALUE.to_string()
 }
@@ -404,6 +421,10 @@ impl<T> Config<T> {
     pub fn get_maximum_reply_key_age(&self) -> Duration {
         self.debug.reply_surbs.maximum_reply_key_age
     }
+
+    pub fn get_reply_storage_flush_interval(&self) -> Duration {
+        self.debug.reply_surbs.reply_storage_flush_interval
+    }
 }
 
 impl<T: NymConfig> Default for Config<T> {
@@ -627,12 +648,34 @@ pub struct Traffic {
     /// Note that its use decreases overall anonymity.
     /// Do not set it it unless you understand the consequences of that change.
     pub secondary_packet_size: Option<PacketSize>,
+
+    /// Specifies the format of the packets used for sent messages, i.e. either Sphinx ("mix")
+    /// or Outfox ("outfox"). Note that Outfox is only used for messages sent with the regular packet size
+    /// and the acknowledgements, replies and cover traffic always use Sphinx.
+    pub packet_type: PacketType,
+
+    /// Specifies the optional forward error correction applied to sent messages (and replies).
+    /// If enabled, additional Reed-Solomon parity packets are sent alongside the message
+    /// so that it could be reconstructed even if some of its packets got lost, without waiting
+    /// for their retransmission. It is only applied to messages that do not need to be split
+    /// into multiple sets, i.e. ones shorter than 255 packets. Longer messages are sent
+    /// without any parity packets, which is reported with a warning.
+    /// It can be overridden for individual messages.
+    pub erasure_coding: Option<ErasureCoding>,
 }
 
 impl Traffic {
     pub fn validate(&self) -> bool {
+        // the packet sizes are defined in terms of sphinx packets, the actual format is
+        // determined by the `packet_type`
+        if self.primary_packet_size == PacketSize::OutfoxRegularPacket
+            || self.packet_type.is_old_vpn()
+        {
+            return false;
+        }
         if let Some(secondary_packet_size) = self.secondary_packet_size {
             if secondary_packet_size == PacketSize::AckPacket
+                || secondary_packet_size == PacketSize::OutfoxRegularPacket
                 || secondary_packet_size == self.primary_packet_size
             {
                 return false;
@@ -650,6 +693,8 @@ impl Default for Traffic {
             disable_main_poisson_packet_distribution: false,
             primary_packet_size: PacketSize::RegularPacket,
             secondary_packet_size: None,
+            packet_type: PacketType::Mix,
+            erasure_coding: None,
         }
     }
 }
@@ -688,12 +733,34 @@ pub struct GatewayConnection {
     /// before giving up on it.
     #[serde(with = "humantime_serde")]
     pub gateway_response_timeout: Duration,
+
+    /// Controls whether the client should attempt to re-register with a different gateway,
+    /// chosen from the current network topology, once its current gateway keeps on failing.
+    /// It's disabled by default as doing so changes the address of the client,
+    /// so anyone wishing to reach it has to learn about the new one.
+    pub enable_gateway_failover: bool,
+
+    /// Maximum number of fallback gateways, ranked by their latency, that are going to be
+    /// considered during a failover attempt.
+    pub maximum_fallback_gateways: usize,
+
+    /// Number of consecutive failures to send packets to the gateway (after its own reconnection
+    /// attempts have already been exhausted) after which the gateway is considered to be dead.
+    pub gateway_failover_threshold: usize,
+
+    /// Controls whether the client should keep an authenticated connection to a secondary gateway
+    /// at all times, so that the failover could happen without having to register first.
+    pub enable_secondary_gateway: bool,
 }
 
 impl Default for GatewayConnection {
     fn default() -> Self {
         GatewayConnection {
             gateway_response_timeout: DEFAULT_GATEWAY_RESPONSE_TIMEOUT,
+            enable_gateway_failover: false,
+            maximum_fallback_gateways: DEFAULT_MAXIMUM_FALLBACK_GATEWAYS,
+            gateway_failover_threshold: DEFAULT_GATEWAY_FAILOVER_THRESHOLD,
+            enable_secondary_gateway: false,
         }
     }
 }
@@ -718,6 +785,12 @@ pub struct Acknowledgements {
     /// In an ideal network with 0 latency, this value would have been 0.
     #[serde(with = "humantime_serde")]
     pub ack_wait_addition: Duration,
+
+    /// Maximum number of times a packet of a tracked message, i.e. one sent with a message id,
+    /// is going to get retransmitted before the client gives up on delivering the message.
+    /// If set to 0, the packets are going to get retransmitted until they're acknowledged.
+    /// Packets of untracked messages are always retransmitted until they're acknowledged.
+    pub maximum_retransmissions: u32,
 }
 
 impl Default for Acknowledgements {
@@ -726,6 +799,7 @@ impl Default for Acknowledgements {
             average_ack_delay: DEFAULT_AVERAGE_PACKET_DELAY,
             ack_wait_multiplier: DEFAULT_ACK_WAIT_MULTIPLIER,
             ack_wait_addition: DEFAULT_ACK_WAIT_ADDITION,
+            maximum_retransmissions: DEFAULT_MAXIMUM_RETRANSMISSIONS,
         }
     }
 }
@@ -798,6 +872,12 @@ pub struct ReplySurbs {
     /// This is going to be superseded by key rotation once implemented.
     #[serde(with = "humantime_serde")]
     pub maximum_reply_key_age: Duration,
+
+    /// Defines how often the changes made to the reply storage, such as received reply surbs or sent reply keys,
+    /// are written to the persistent storage, so that they would survive an unclean shutdown.
+    /// It's only applicable to storage backends capable of incremental writes.
+    #[serde(with = "humantime_serde")]
+    pub reply_storage_flush_interval: Duration,
 }
 
 impl Default for ReplySurbs {
@@ -813,6 +893,34 @@ impl Default for ReplySurbs {
             maximum_reply_surb_drop_waiting_period: DEFAULT_MAXIMUM_REPLY_SURB_DROP_WAITING_PERIOD,
             maximum_reply_surb_age: DEFAULT_MAXIMUM_REPLY_SURB_AGE,
             maximum_reply_key_age: DEFAULT_MAXIMUM_REPLY_KEY_AGE,
+            reply_storage_flush_interval: DEFAULT_REPLY_STORAGE_FLUSH_INTERVAL,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MessageReconstruction {
+    /// Defines maximum amount of time, since receiving its first fragment, an incomplete message
+    /// is going to be kept in memory before it's assumed its remaining fragments got lost.
+    #[serde(with = "humantime_serde")]
+    pub maximum_partial_message_age: Duration,
+
+    /// Defines maximum number of bytes the client is going to keep in memory for all incomplete messages.
+    /// Once exceeded, the oldest incomplete messages are going to be dropped.
+    pub maximum_buffered_bytes: usize,
+
+    /// Defines how often the client is going to check for incomplete messages that have become stale.
+    #[serde(with = "humantime_serde")]
+    pub stale_message_check_interval: Duration,
+}
+
+impl Default for MessageReconstruction {
+    fn default() -> Self {
+        MessageReconstruction {
+            maximum_partial_message_age: DEFAULT_MAXIMUM_PARTIAL_MESSAGE_AGE,
+            maximum_buffered_bytes: DEFAULT_MAXIMUM_BUFFERED_PARTIAL_MESSAGE_BYTES,
+            stale_message_check_interval: DEFAULT_STALE_PARTIAL_MESSAGE_CHECK_INTERVAL,
         }
     }
 }
@@ -837,6 +945,9 @@ pub struct DebugConfig {
 
     /// Defines all configuration options related to reply SURBs.
     pub reply_surbs: ReplySurbs,
+
+    /// Defines all configuration options related to reconstruction of received messages.
+    pub message_reconstruction: MessageReconstruction,
 }
 
 impl DebugConfig {
@@ -858,6 +969,7 @@ impl Default for DebugConfig {
             acknowledgements: Default::default(),
             topology: Default::default(),
             reply_surbs: Default::default(),
+            message_reconstruction: Default::default(),
         }
     }
 }
@@ -14,7 +14,7 @@ use crate::config::{
     DEFAULT_TOPOLOGY_REFRESH_RATE, DEFAULT_TOPOLOGY_RESOLUTION_TIMEOUT,
 };
 use nym_config::NymConfig;
-use nym_sphinx::params::PacketSize;
+use nym_sphinx::params::{PacketSize, PacketType};
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 use std::time::Duration;
@@ -125,6 +125,8 @@ impl From<OldDebugConfigV1_1_13> for DebugConfig {
                     .disable_main_poisson_packet_distribution,
                 primary_packet_size: PacketSize::RegularPacket,
                 secondary_packet_size: value.use_extended_packet_size.map(Into::into),
+                packet_type: PacketType::Mix,
+                erasure_coding: None,
             },
             cover_traffic: CoverTraffic {
                 loop_cover_traffic_average_delay: value.loop_cover_traffic_average_delay,
@@ -133,11 +135,13 @@ impl From<OldDebugConfigV1_1_13> for DebugConfig {
             },
             gateway_connection: GatewayConnection {
                 gateway_response_timeout: value.gateway_response_timeout,
+                ..GatewayConnection::default()
             },
             acknowledgements: Acknowledgements {
                 average_ack_delay: value.average_ack_delay,
                 ack_wait_multiplier: value.ack_wait_multiplier,
                 ack_wait_addition: value.ack_wait_addition,
+                ..Acknowledgements::default()
             },
             topology: Topology {
                 topology_refresh_rate: value.topology_refresh_rate,
@@ -157,7 +161,9 @@ impl From<OldDebugConfigV1_1_13> for DebugConfig {
                     .maximum_reply_surb_drop_waiting_period,
                 maximum_reply_surb_age: value.maximum_reply_surb_age,
                 maximum_reply_key_age: value.maximum_reply_key_age,
+                ..ReplySurbs::default()
             },
+            message_reconstruction: Default::default(),
         }
     }
 }
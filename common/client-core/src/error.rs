@@ -3,6 +3,7 @@
 
 use nym_crypto::asymmetric::identity::Ed25519RecoveryError;
 use nym_gateway_client::error::GatewayClientError;
+use nym_sphinx::addressing::clients::Recipient;
 use nym_topology::gateway::GatewayConversionError;
 use nym_topology::NymTopologyError;
 use nym_validator_client::ValidatorClientError;
@@ -90,6 +91,9 @@ pub enum ClientCoreError {
     #[error("failed to register receiver for reconstructed mixnet messages")]
     FailedToRegisterReceiver,
 
+    #[error("failed to register listener for message delivery events")]
+    FailedToRegisterDeliveryListener,
+
     #[error("Unexpected exit")]
     UnexpectedExit,
 }
@@ -103,4 +107,10 @@ pub enum ClientCoreStatusMessage {
     // NOTE: The nym-connect frontend listens for these strings, so don't change them until we have a more robust mechanism in place
     #[error("The connected gateway is very slow, or the connection to it is very slow")]
     GatewayIsVerySlow,
+
+    #[error("Dropped {sets} incomplete message set(s) ({bytes} bytes) as their remaining fragments have not been received in time")]
+    DroppedIncompleteMessages { sets: usize, bytes: usize },
+
+    #[error("Failed over to gateway {gateway} - the address of this client is now {address}")]
+    GatewayFailover { gateway: String, address: Recipient },
 }
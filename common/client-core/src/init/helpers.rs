@@ -160,12 +160,7 @@ async fn measure_latency(gateway: gateway::Node) -> Result<GatewayWithLatency, C
     Ok(GatewayWithLatency::new(gateway, avg))
 }
 
-async fn choose_gateway_by_latency<R: Rng>(
-    rng: &mut R,
-    gateways: Vec<gateway::Node>,
-) -> Result<gateway::Node, ClientCoreError> {
-    info!("choosing gateway by latency...");
-
+async fn measure_gateways(gateways: Vec<gateway::Node>) -> Vec<GatewayWithLatency> {
     let mut gateways_with_latency = Vec::new();
     for gateway in gateways {
         let id = *gateway.identity();
@@ -180,6 +175,16 @@ async fn choose_gateway_by_latency<R: Rng>(
         debug!("{id}: {:?}", with_latency.latency);
         gateways_with_latency.push(with_latency)
     }
+    gateways_with_latency
+}
+
+async fn choose_gateway_by_latency<R: Rng>(
+    rng: &mut R,
+    gateways: Vec<gateway::Node>,
+) -> Result<gateway::Node, ClientCoreError> {
+    info!("choosing gateway by latency...");
+
+    let gateways_with_latency = measure_gateways(gateways).await;
 
     let chosen = gateways_with_latency
         .choose_weighted(rng, |item| 1. / item.latency.as_secs_f32())
@@ -193,6 +198,22 @@ async fn choose_gateway_by_latency<R: Rng>(
     Ok(chosen.gateway.clone())
 }
 
+/// Measures latency to all the provided gateways and returns (at most) `limit` of the responsive ones,
+/// ordered from the fastest.
+pub(crate) async fn rank_gateways_by_latency(
+    gateways: Vec<gateway::Node>,
+    limit: usize,
+) -> Vec<gateway::Node> {
+    let mut gateways_with_latency = measure_gateways(gateways).await;
+    gateways_with_latency.sort_by_key(|gateway| gateway.latency);
+
+    gateways_with_latency
+        .into_iter()
+        .take(limit)
+        .map(|with_latency| with_latency.gateway)
+        .collect()
+}
+
 fn uniformly_random_gateway<R: Rng>(
     rng: &mut R,
     gateways: Vec<gateway::Node>,
@@ -252,10 +273,10 @@ where
 
 // TODO: make it generic
 #[cfg(not(target_arch = "wasm32"))]
-pub(super) fn on_disk_key_store<T>(config: &Config<T>) -> OnDiskKeys
+pub(super) fn on_disk_key_store<T>(config: &Config<T>, key_passphrase: Option<&str>) -> OnDiskKeys
 where
     T: NymConfig,
 {
     let pathfinder = ClientKeyPathfinder::new_from_config(config);
-    OnDiskKeys::new(pathfinder)
+    OnDiskKeys::new(pathfinder).with_key_passphrase(key_passphrase.map(ToOwned::to_owned))
 }
@@ -22,7 +22,7 @@ use std::sync::Arc;
 use tap::TapFallible;
 use url::Url;
 
-mod helpers;
+pub(crate) mod helpers;
 
 /// Struct describing the results of the client initialization procedure.
 #[derive(Debug, Serialize)]
@@ -94,12 +94,15 @@ where
 /// b. Create a new gateway configuration but keep existing keys. This assumes that the caller
 ///    knows what they are doing and that the keys match the requested gateway.
 /// c. Create a new gateway configuration with a newly registered gateway and keys.
+///
+/// If the `key_passphrase` is provided, the stored private keys are going to be encrypted with it.
 #[cfg(not(target_arch = "wasm32"))]
 pub async fn setup_gateway_from_config<C, T, St>(
     register_gateway: bool,
     user_chosen_gateway_id: Option<identity::PublicKey>,
     config: &Config<T>,
     by_latency: bool,
+    key_passphrase: Option<&str>,
 ) -> Result<GatewayEndpointConfig, ClientCoreError>
 where
     C: NymConfig + ClientCoreConfigTrait,
@@ -132,7 +135,7 @@ where
         return Ok(gateway.into());
     }
 
-    let key_store = helpers::on_disk_key_store(config);
+    let key_store = helpers::on_disk_key_store(config, key_passphrase);
     let mut rng = rand::thread_rng();
     let mut managed_keys =
         crate::client::key_manager::ManagedKeys::load_or_generate(&mut rng, &key_store).await;
@@ -187,39 +190,47 @@ pub fn get_client_address(
 }
 
 /// Get the client address by loading the keys from stored files.
+/// The passphrase is only required if the private keys have been stored encrypted.
 pub fn get_client_address_from_stored_keys<T>(
     config: &Config<T>,
+    key_passphrase: Option<&str>,
 ) -> Result<Recipient, ClientCoreError>
 where
     T: nym_config::NymConfig,
 {
     fn load_identity_keys(
         pathfinder: &ClientKeyPathfinder,
+        key_passphrase: Option<&str>,
     ) -> Result<identity::KeyPair, ClientCoreError> {
-        let identity_keypair: identity::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+        let identity_keypair: identity::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_identity_key().to_owned(),
                 pathfinder.public_identity_key().to_owned(),
-            ))
-            .tap_err(|_| log::error!("Failed to read stored identity key files"))?;
+            ),
+            key_passphrase.map(str::as_bytes),
+        )
+        .tap_err(|_| log::error!("Failed to read stored identity key files"))?;
         Ok(identity_keypair)
     }
 
     fn load_sphinx_keys(
         pathfinder: &ClientKeyPathfinder,
+        key_passphrase: Option<&str>,
     ) -> Result<encryption::KeyPair, ClientCoreError> {
-        let sphinx_keypair: encryption::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+        let sphinx_keypair: encryption::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_encryption_key().to_owned(),
                 pathfinder.public_encryption_key().to_owned(),
-            ))
-            .tap_err(|_| log::error!("Failed to read stored sphinx key files"))?;
+            ),
+            key_passphrase.map(str::as_bytes),
+        )
+        .tap_err(|_| log::error!("Failed to read stored sphinx key files"))?;
         Ok(sphinx_keypair)
     }
 
     let pathfinder = ClientKeyPathfinder::new_from_config(config);
-    let identity_keypair = load_identity_keys(&pathfinder)?;
-    let sphinx_keypair = load_sphinx_keys(&pathfinder)?;
+    let identity_keypair = load_identity_keys(&pathfinder, key_passphrase)?;
+    let sphinx_keypair = load_sphinx_keys(&pathfinder, key_passphrase)?;
 
     let client_recipient = Recipient::new(
         *identity_keypair.public_key(),
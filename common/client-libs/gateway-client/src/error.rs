@@ -95,4 +95,20 @@ impl GatewayClientError {
             _ => false,
         }
     }
+
+    /// Checks whether the error was caused by the connection to the gateway itself
+    /// (as opposed to, for example, the client running out of bandwidth).
+    pub fn is_connection_failure(&self) -> bool {
+        match self {
+            GatewayClientError::ConnectionNotEstablished
+            | GatewayClientError::NetworkError(_)
+            | GatewayClientError::ConnectionAbruptlyClosed
+            | GatewayClientError::ConnectionClosedGatewayShutdown
+            | GatewayClientError::ConnectionInInvalidState
+            | GatewayClientError::Timeout => true,
+            #[cfg(target_arch = "wasm32")]
+            GatewayClientError::NetworkErrorWasm(_) => true,
+            _ => false,
+        }
+    }
 }
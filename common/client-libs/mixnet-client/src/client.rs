@@ -1,13 +1,15 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::noise::NoiseConfig;
 use futures::channel::mpsc;
 use futures::StreamExt;
 use log::*;
 use nym_sphinx::framing::codec::SphinxCodec;
+use nym_sphinx::framing::noise::LinkCodec;
 use nym_sphinx::framing::packet::FramedSphinxPacket;
-use nym_sphinx::params::PacketMode;
-use nym_sphinx::{addressing::nodes::NymNodeRoutingAddress, SphinxPacket};
+use nym_sphinx::params::PacketType;
+use nym_sphinx::{addressing::nodes::NymNodeRoutingAddress, NymPacket};
 use std::collections::HashMap;
 use std::io;
 use std::net::SocketAddr;
@@ -24,6 +26,9 @@ pub struct Config {
     initial_connection_timeout: Duration,
     maximum_connection_buffer_size: usize,
     use_legacy_version: bool,
+
+    /// If specified, the connections are going to be encrypted using noise whenever possible.
+    noise: Option<NoiseConfig>,
 }
 
 impl Config {
@@ -33,6 +38,7 @@ impl Config {
         initial_connection_timeout: Duration,
         maximum_connection_buffer_size: usize,
         use_legacy_version: bool,
+        noise: Option<NoiseConfig>,
     ) -> Self {
         Config {
             initial_reconnection_backoff,
@@ -40,6 +46,7 @@ impl Config {
             initial_connection_timeout,
             maximum_connection_buffer_size,
             use_legacy_version,
+            noise,
         }
     }
 }
@@ -50,8 +57,8 @@ pub trait SendWithoutResponse {
     fn send_without_response(
         &mut self,
         address: NymNodeRoutingAddress,
-        packet: SphinxPacket,
-        packet_mode: PacketMode,
+        packet: NymPacket,
+        packet_type: PacketType,
     ) -> io::Result<()>;
 }
 
@@ -87,16 +94,17 @@ impl Client {
         receiver: mpsc::Receiver<FramedSphinxPacket>,
         connection_timeout: Duration,
         current_reconnection: &AtomicU32,
+        noise_config: Option<NoiseConfig>,
     ) {
         let connection_fut = TcpStream::connect(address);
 
-        let conn = match tokio::time::timeout(connection_timeout, connection_fut).await {
+        let stream = match tokio::time::timeout(connection_timeout, connection_fut).await {
             Ok(stream_res) => match stream_res {
                 Ok(stream) => {
                     debug!("Managed to establish connection to {}", address);
                     // if we managed to connect, reset the reconnection count (whatever it might have been)
                     current_reconnection.store(0, Ordering::Release);
-                    Framed::new(stream, SphinxCodec)
+                    stream
                 }
                 Err(err) => {
                     debug!(
@@ -118,6 +126,17 @@ impl Client {
             }
         };
 
+        let conn = match noise_config {
+            Some(noise_config) => match noise_config.initiate_connection(stream, address).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    warn!("failed to establish noise connection to {address} - {err}");
+                    return;
+                }
+            },
+            None => Framed::new(stream, LinkCodec::Plain(SphinxCodec)),
+        };
+
         // Take whatever the receiver channel produces and put it on the connection.
         // We could have as well used conn.send_all(receiver.map(Ok)), but considering we don't care
         // about neither receiver nor the connection, it doesn't matter which one gets consumed
@@ -177,6 +196,7 @@ impl Client {
 
         // copy the value before moving into another task
         let initial_connection_timeout = self.config.initial_connection_timeout;
+        let noise_config = self.config.noise.clone();
 
         tokio::spawn(async move {
             // before executing the manager, wait for what was specified, if anything
@@ -190,6 +210,7 @@ impl Client {
                 receiver,
                 initial_connection_timeout,
                 &current_reconnection_attempt,
+                noise_config,
             )
             .await
         });
@@ -200,12 +221,12 @@ impl SendWithoutResponse for Client {
     fn send_without_response(
         &mut self,
         address: NymNodeRoutingAddress,
-        packet: SphinxPacket,
-        packet_mode: PacketMode,
+        packet: NymPacket,
+        packet_type: PacketType,
     ) -> io::Result<()> {
         trace!("Sending packet to {:?}", address);
         let framed_packet =
-            FramedSphinxPacket::new(packet, packet_mode, self.config.use_legacy_version);
+            FramedSphinxPacket::new(packet, packet_type, self.config.use_legacy_version);
 
         if let Some(sender) = self.conn_new.get_mut(&address) {
             if let Err(err) = sender.channel.try_send(framed_packet) {
@@ -264,6 +285,7 @@ mod tests {
             initial_connection_timeout: Duration::from_millis(1_500),
             maximum_connection_buffer_size: 128,
             use_legacy_version: false,
+            noise: None,
         })
     }
 
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::client::{Client, Config, SendWithoutResponse};
+use crate::noise::NoiseConfig;
 use futures::channel::mpsc;
 use futures::StreamExt;
 use log::*;
@@ -26,6 +27,7 @@ impl PacketForwarder {
         initial_connection_timeout: Duration,
         maximum_connection_buffer_size: usize,
         use_legacy_version: bool,
+        noise: Option<NoiseConfig>,
         shutdown: nym_task::TaskClient,
     ) -> (PacketForwarder, MixForwardingSender) {
         let client_config = Config::new(
@@ -34,6 +36,7 @@ impl PacketForwarder {
             initial_connection_timeout,
             maximum_connection_buffer_size,
             use_legacy_version,
+            noise,
         );
 
         let (packet_sender, packet_receiver) = mpsc::unbounded();
@@ -59,14 +62,14 @@ impl PacketForwarder {
                      trace!("Going to forward packet to {:?}", mix_packet.next_hop());
 
                     let next_hop = mix_packet.next_hop();
-                    let packet_mode = mix_packet.packet_mode();
-                    let sphinx_packet = mix_packet.into_sphinx_packet();
+                    let packet_type = mix_packet.packet_type();
+                    let packet = mix_packet.into_packet();
                     // we don't care about responses, we just want to fire packets
                     // as quickly as possible
 
                     if let Err(err) =
                         self.mixnet_client
-                            .send_without_response(next_hop, sphinx_packet, packet_mode)
+                            .send_without_response(next_hop, packet, packet_type)
                     {
                         debug!("failed to forward the packet - {err}")
                     }
@@ -3,5 +3,7 @@
 
 pub mod client;
 pub mod forwarder;
+pub mod noise;
 
 pub use client::{Client, Config, SendWithoutResponse};
+pub use noise::{NoiseConfig, NoiseNodeKeys};
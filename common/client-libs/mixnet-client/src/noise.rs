@@ -0,0 +1,192 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use log::*;
+use nym_crypto::asymmetric::encryption;
+use nym_sphinx::framing::codec::SphinxCodec;
+use nym_sphinx::framing::noise::{self, LinkCodec, NoiseError};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_util::codec::Framed;
+
+/// x25519 keys of the nodes in the network used for authenticating the noise connections.
+#[derive(Clone, Default)]
+pub struct NoiseNodeKeys {
+    inner: Arc<RwLock<NoiseNodeKeysInner>>,
+}
+
+#[derive(Default)]
+struct NoiseNodeKeysInner {
+    by_address: HashMap<SocketAddr, encryption::PublicKey>,
+    known_keys: HashSet<encryption::PublicKey>,
+
+    /// Addresses of the nodes that have failed to complete the noise handshake
+    /// and thus are assumed to only support the legacy connections.
+    legacy_addresses: HashSet<SocketAddr>,
+}
+
+impl NoiseNodeKeys {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Replaces all the known keys. Note that it also clears the information about the nodes
+    /// that only supported the legacy connections so that they'd be retried in case they got upgraded.
+    pub fn update(&self, keys: HashMap<SocketAddr, encryption::PublicKey>) {
+        let known_keys = keys.values().copied().collect();
+
+        let mut guard = self.inner.write().expect("noise keys lock got poisoned");
+        guard.by_address = keys;
+        guard.known_keys = known_keys;
+        guard.legacy_addresses.clear();
+    }
+
+    fn is_known(&self, key: &[u8]) -> bool {
+        let key = match encryption::PublicKey::from_bytes(key) {
+            Ok(key) => key,
+            Err(_) => return false,
+        };
+        self.inner
+            .read()
+            .expect("noise keys lock got poisoned")
+            .known_keys
+            .contains(&key)
+    }
+
+    fn remote_key(&self, address: SocketAddr) -> Option<encryption::PublicKey> {
+        let guard = self.inner.read().expect("noise keys lock got poisoned");
+        if guard.legacy_addresses.contains(&address) {
+            return None;
+        }
+        guard.by_address.get(&address).copied()
+    }
+
+    fn mark_legacy(&self, address: SocketAddr) {
+        self.inner
+            .write()
+            .expect("noise keys lock got poisoned")
+            .legacy_addresses
+            .insert(address);
+    }
+}
+
+#[derive(Clone)]
+pub struct NoiseConfig {
+    local_key: Arc<encryption::PrivateKey>,
+    node_keys: NoiseNodeKeys,
+
+    /// Specifies whether unencrypted connections are still accepted from the nodes that did not
+    /// initiate the noise handshake, and whether we could fall back to them ourselves
+    /// if the remote does not support noise.
+    accept_legacy: bool,
+
+    handshake_timeout: Duration,
+}
+
+impl NoiseConfig {
+    pub fn new(
+        local_key: Arc<encryption::PrivateKey>,
+        node_keys: NoiseNodeKeys,
+        accept_legacy: bool,
+        handshake_timeout: Duration,
+    ) -> Self {
+        NoiseConfig {
+            local_key,
+            node_keys,
+            accept_legacy,
+            handshake_timeout,
+        }
+    }
+
+    pub(crate) async fn initiate_connection(
+        &self,
+        conn: TcpStream,
+        address: SocketAddr,
+    ) -> io::Result<Framed<TcpStream, LinkCodec>> {
+        let remote_key = match self.node_keys.remote_key(address) {
+            Some(remote_key) => remote_key,
+            None if self.accept_legacy => {
+                trace!("using legacy connection to {address}");
+                return Ok(Framed::new(conn, LinkCodec::Plain(SphinxCodec)));
+            }
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "the noise key of the remote is not known and the legacy connections are not allowed",
+                ))
+            }
+        };
+
+        let local_key = self.local_key.to_bytes();
+        let remote_key = remote_key.to_bytes();
+        let handshake = noise::initiate(conn, &local_key, &remote_key);
+        let res = match tokio::time::timeout(self.handshake_timeout, handshake).await {
+            Ok(res) => res,
+            Err(_) => Err(NoiseError::IoError(io::ErrorKind::TimedOut.into())),
+        };
+
+        res.map_err(|err| {
+            if self.accept_legacy {
+                // the remote is most likely yet to support noise. the next connection attempt is going
+                // to use the legacy mode (until our keys get refreshed)
+                debug!("noise handshake with {address} has failed - {err}. Falling back to the legacy connection");
+                self.node_keys.mark_legacy(address);
+            }
+            err.into()
+        })
+    }
+}
+
+/// Accepts the connection received from another node, performing the noise handshake
+/// if the remote has initiated it.
+pub async fn accept_connection(
+    conn: TcpStream,
+    noise_config: Option<&NoiseConfig>,
+) -> Result<Framed<TcpStream, LinkCodec>, NoiseError> {
+    let config = match noise_config {
+        Some(config) => config,
+        None => return Ok(Framed::new(conn, LinkCodec::Plain(SphinxCodec))),
+    };
+
+    let local_key = config.local_key.to_bytes();
+    let handshake = noise::accept(conn, &local_key, config.accept_legacy, |key| {
+        config.node_keys.is_known(key)
+    });
+    match tokio::time::timeout(config.handshake_timeout, handshake).await {
+        Ok(res) => res,
+        Err(_) => Err(NoiseError::IoError(io::ErrorKind::TimedOut.into())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dummy_key(byte: u8) -> encryption::PublicKey {
+        encryption::PublicKey::from_bytes(&[byte; encryption::PUBLIC_KEY_SIZE]).unwrap()
+    }
+
+    #[test]
+    fn legacy_addresses_are_reset_on_key_update() {
+        let address: SocketAddr = "1.2.3.4:1789".parse().unwrap();
+        let node_keys = NoiseNodeKeys::new();
+        node_keys.update(HashMap::from([(address, dummy_key(1))]));
+
+        assert_eq!(node_keys.remote_key(address), Some(dummy_key(1)));
+        assert!(node_keys.is_known(&dummy_key(1).to_bytes()));
+        assert!(!node_keys.is_known(&dummy_key(2).to_bytes()));
+
+        node_keys.mark_legacy(address);
+        assert!(node_keys.remote_key(address).is_none());
+        // the node is still known if it decided to connect to us using noise
+        assert!(node_keys.is_known(&dummy_key(1).to_bytes()));
+
+        node_keys.update(HashMap::from([(address, dummy_key(2))]));
+        assert_eq!(node_keys.remote_key(address), Some(dummy_key(2)));
+        assert!(!node_keys.is_known(&dummy_key(1).to_bytes()));
+    }
+}
@@ -6,14 +6,19 @@ use nym_api_requests::coconut::{
     BlindSignRequestBody, BlindedSignatureResponse, VerifyCredentialBody, VerifyCredentialResponse,
 };
 use nym_api_requests::models::{
-    GatewayCoreStatusResponse, MixnodeCoreStatusResponse, MixnodeStatusResponse,
-    RewardEstimationResponse, StakeSaturationResponse,
+    GatewayBondWithSphinxKeys, GatewayCoreStatusResponse, GatewayLatencyResponse,
+    MixNodeDetailsWithSphinxKeys, MixnodeCoreStatusResponse, MixnodeLatencyResponse,
+    MixnodeStatusResponse, RewardEstimationResponse, SignedSphinxKeyAnnouncement,
+    SignedTopologySnapshot, SphinxKeyAnnouncementResponse, StakeSaturationResponse,
 };
 use nym_coconut_dkg_common::types::NodeIndex;
 use nym_coconut_interface::VerificationKey;
+use nym_mixnet_contract_common::Interval;
 pub use nym_mixnet_contract_common::{mixnode::MixNodeDetails, GatewayBond, IdentityKeyRef, MixId};
 use url::Url;
 
+#[cfg(feature = "nyxd-client")]
+use crate::nyxd::error::NyxdError;
 #[cfg(feature = "nyxd-client")]
 use crate::nyxd::traits::{DkgQueryClient, MixnetQueryClient};
 #[cfg(feature = "nyxd-client")]
@@ -21,6 +26,8 @@ use crate::nyxd::{self, CosmWasmClient, NyxdClient, QueryNyxdClient, SigningNyxd
 #[cfg(feature = "nyxd-client")]
 use crate::signing::direct_wallet::DirectSecp256k1HdWallet;
 #[cfg(feature = "nyxd-client")]
+use crate::signing::signer::OfflineSigner;
+#[cfg(feature = "nyxd-client")]
 use nym_api_requests::models::MixNodeBondAnnotated;
 #[cfg(feature = "nyxd-client")]
 use nym_coconut_dkg_common::{types::EpochId, verification_key::ContractVKShare};
@@ -135,11 +142,27 @@ impl Client<SigningNyxdClient<DirectSecp256k1HdWallet>> {
         config: Config,
         mnemonic: bip39::Mnemonic,
     ) -> Result<Client<SigningNyxdClient<DirectSecp256k1HdWallet>>, ValidatorClientError> {
+        let prefix = &config.nyxd_config.chain_details.bech32_account_prefix;
+        let wallet = DirectSecp256k1HdWallet::from_mnemonic(prefix, mnemonic);
+        Self::new_signing_with_signer(config, wallet)
+    }
+}
+
+#[cfg(feature = "nyxd-client")]
+impl<S> Client<SigningNyxdClient<S>>
+where
+    S: OfflineSigner,
+    NyxdError: From<S::Error>,
+{
+    pub fn new_signing_with_signer(
+        config: Config,
+        signer: S,
+    ) -> Result<Client<SigningNyxdClient<S>>, ValidatorClientError> {
         let nym_api_client = nym_api::Client::new(config.api_url.clone());
-        let nyxd_client = NyxdClient::connect_with_mnemonic(
+        let nyxd_client = NyxdClient::connect_with_signer(
             config.nyxd_config.clone(),
             config.nyxd_url.as_str(),
-            mnemonic,
+            signer,
             None,
         )?;
 
@@ -711,6 +734,41 @@ impl NymApiClient {
         Ok(self.nym_api_client.get_gateways().await?)
     }
 
+    pub async fn get_cached_active_mixnodes_with_sphinx_keys(
+        &self,
+    ) -> Result<Vec<MixNodeDetailsWithSphinxKeys>, ValidatorClientError> {
+        Ok(self
+            .nym_api_client
+            .get_active_mixnodes_with_sphinx_keys()
+            .await?)
+    }
+
+    pub async fn get_cached_gateways_with_sphinx_keys(
+        &self,
+    ) -> Result<Vec<GatewayBondWithSphinxKeys>, ValidatorClientError> {
+        Ok(self.nym_api_client.get_gateways_with_sphinx_keys().await?)
+    }
+
+    pub async fn get_topology_snapshot(
+        &self,
+    ) -> Result<SignedTopologySnapshot, ValidatorClientError> {
+        Ok(self.nym_api_client.get_topology_snapshot().await?)
+    }
+
+    pub async fn announce_sphinx_key(
+        &self,
+        announcement: &SignedSphinxKeyAnnouncement,
+    ) -> Result<SphinxKeyAnnouncementResponse, ValidatorClientError> {
+        Ok(self
+            .nym_api_client
+            .announce_sphinx_key(announcement)
+            .await?)
+    }
+
+    pub async fn get_current_epoch(&self) -> Result<Interval, ValidatorClientError> {
+        Ok(self.nym_api_client.get_current_epoch().await?)
+    }
+
     pub async fn get_gateway_core_status_count(
         &self,
         identity: IdentityKeyRef<'_>,
@@ -733,6 +791,28 @@ impl NymApiClient {
             .await?)
     }
 
+    pub async fn get_gateway_latency(
+        &self,
+        identity: IdentityKeyRef<'_>,
+        since: Option<i64>,
+    ) -> Result<GatewayLatencyResponse, ValidatorClientError> {
+        Ok(self
+            .nym_api_client
+            .get_gateway_latency(identity, since)
+            .await?)
+    }
+
+    pub async fn get_mixnode_latency(
+        &self,
+        mix_id: MixId,
+        since: Option<i64>,
+    ) -> Result<MixnodeLatencyResponse, ValidatorClientError> {
+        Ok(self
+            .nym_api_client
+            .get_mixnode_latency(mix_id, since)
+            .await?)
+    }
+
     pub async fn get_mixnode_status(
         &self,
         mix_id: MixId,
@@ -2,19 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::nym_api::error::NymAPIError;
-use crate::nym_api::routes::{CORE_STATUS_COUNT, SINCE_ARG};
+use crate::nym_api::routes::{CORE_STATUS_COUNT, LATENCY, SINCE_ARG};
 use nym_api_requests::coconut::{
     BlindSignRequestBody, BlindedSignatureResponse, VerifyCredentialBody, VerifyCredentialResponse,
 };
 use nym_api_requests::models::{
-    ComputeRewardEstParam, GatewayCoreStatusResponse, GatewayStatusReportResponse,
-    GatewayUptimeHistoryResponse, InclusionProbabilityResponse, MixNodeBondAnnotated,
-    MixnodeCoreStatusResponse, MixnodeStatusReportResponse, MixnodeStatusResponse,
-    MixnodeUptimeHistoryResponse, RequestError, RewardEstimationResponse, StakeSaturationResponse,
-    UptimeResponse,
+    ComputeRewardEstParam, GatewayBondWithSphinxKeys, GatewayCoreStatusResponse,
+    GatewayLatencyResponse, GatewayStatusReportResponse, GatewayUptimeHistoryResponse,
+    InclusionProbabilityResponse, MixNodeBondAnnotated, MixNodeDetailsWithSphinxKeys,
+    MixnodeCoreStatusResponse, MixnodeLatencyResponse,
+    MixnodeStatusReportResponse, MixnodeStatusResponse, MixnodeUptimeHistoryResponse, RequestError,
+    RewardEstimationResponse, SignedSphinxKeyAnnouncement, SignedTopologySnapshot,
+    SphinxKeyAnnouncementResponse, StakeSaturationResponse, UptimeResponse,
 };
 use nym_mixnet_contract_common::mixnode::MixNodeDetails;
-use nym_mixnet_contract_common::{GatewayBond, IdentityKeyRef, MixId};
+use nym_mixnet_contract_common::{GatewayBond, IdentityKeyRef, Interval, MixId};
 use nym_service_provider_directory_common::ServiceInfo;
 use reqwest::{Response, StatusCode};
 use serde::{Deserialize, Serialize};
@@ -167,6 +169,41 @@ impl Client {
             .await
     }
 
+    pub async fn get_gateways_with_sphinx_keys(
+        &self,
+    ) -> Result<Vec<GatewayBondWithSphinxKeys>, NymAPIError> {
+        self.query_nym_api(&[routes::API_VERSION, routes::GATEWAYS], NO_PARAMS)
+            .await
+    }
+
+    pub async fn get_topology_snapshot(&self) -> Result<SignedTopologySnapshot, NymAPIError> {
+        self.query_nym_api(
+            &[routes::API_VERSION, routes::TOPOLOGY, routes::SNAPSHOT],
+            NO_PARAMS,
+        )
+        .await
+    }
+
+    pub async fn announce_sphinx_key(
+        &self,
+        announcement: &SignedSphinxKeyAnnouncement,
+    ) -> Result<SphinxKeyAnnouncementResponse, NymAPIError> {
+        self.post_nym_api(
+            &[routes::API_VERSION, routes::TOPOLOGY, routes::SPHINX_KEY],
+            NO_PARAMS,
+            announcement,
+        )
+        .await
+    }
+
+    pub async fn get_current_epoch(&self) -> Result<Interval, NymAPIError> {
+        self.query_nym_api(
+            &[routes::API_VERSION, routes::EPOCH, routes::CURRENT],
+            NO_PARAMS,
+        )
+        .await
+    }
+
     pub async fn get_active_mixnodes(&self) -> Result<Vec<MixNodeDetails>, NymAPIError> {
         self.query_nym_api(
             &[routes::API_VERSION, routes::MIXNODES, routes::ACTIVE],
@@ -175,6 +212,16 @@ impl Client {
         .await
     }
 
+    pub async fn get_active_mixnodes_with_sphinx_keys(
+        &self,
+    ) -> Result<Vec<MixNodeDetailsWithSphinxKeys>, NymAPIError> {
+        self.query_nym_api(
+            &[routes::API_VERSION, routes::MIXNODES, routes::ACTIVE],
+            NO_PARAMS,
+        )
+        .await
+    }
+
     pub async fn get_active_mixnodes_detailed(
         &self,
     ) -> Result<Vec<MixNodeBondAnnotated>, NymAPIError> {
@@ -346,6 +393,49 @@ impl Client {
         }
     }
 
+    pub async fn get_gateway_latency(
+        &self,
+        identity: IdentityKeyRef<'_>,
+        since: Option<i64>,
+    ) -> Result<GatewayLatencyResponse, NymAPIError> {
+        let path = [
+            routes::API_VERSION,
+            routes::STATUS_ROUTES,
+            routes::GATEWAY,
+            identity,
+            LATENCY,
+        ];
+
+        if let Some(since) = since {
+            self.query_nym_api(&path, &[(SINCE_ARG, since.to_string())])
+                .await
+        } else {
+            self.query_nym_api(&path, NO_PARAMS).await
+        }
+    }
+
+    pub async fn get_mixnode_latency(
+        &self,
+        mix_id: MixId,
+        since: Option<i64>,
+    ) -> Result<MixnodeLatencyResponse, NymAPIError> {
+        let mix_id = mix_id.to_string();
+        let path = [
+            routes::API_VERSION,
+            routes::STATUS_ROUTES,
+            routes::MIXNODE,
+            &mix_id,
+            LATENCY,
+        ];
+
+        if let Some(since) = since {
+            self.query_nym_api(&path, &[(SINCE_ARG, since.to_string())])
+                .await
+        } else {
+            self.query_nym_api(&path, NO_PARAMS).await
+        }
+    }
+
     pub async fn get_mixnode_status(
         &self,
         mix_id: MixId,
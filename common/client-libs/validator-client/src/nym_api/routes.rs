@@ -11,6 +11,8 @@ pub const DETAILED: &str = "detailed";
 pub const DETAILED_UNFILTERED: &str = "detailed-unfiltered";
 pub const ACTIVE: &str = "active";
 pub const REWARDED: &str = "rewarded";
+pub const EPOCH: &str = "epoch";
+pub const CURRENT: &str = "current";
 pub const COCONUT_ROUTES: &str = "coconut";
 pub const BANDWIDTH: &str = "bandwidth";
 
@@ -22,6 +24,7 @@ pub const MIXNODE: &str = "mixnode";
 pub const GATEWAY: &str = "gateway";
 
 pub const CORE_STATUS_COUNT: &str = "core-status-count";
+pub const LATENCY: &str = "latency";
 pub const SINCE_ARG: &str = "since";
 
 pub const STATUS: &str = "status";
@@ -34,3 +37,7 @@ pub const STAKE_SATURATION: &str = "stake-saturation";
 pub const INCLUSION_CHANCE: &str = "inclusion-probability";
 
 pub const SERVICE_PROVIDERS: &str = "service-providers";
+
+pub const TOPOLOGY: &str = "topology";
+pub const SNAPSHOT: &str = "snapshot";
+pub const SPHINX_KEY: &str = "sphinx-key";
@@ -8,7 +8,7 @@ use crate::nyxd::cosmwasm_client::types::*;
 use crate::nyxd::error::NyxdError;
 use crate::nyxd::fee::{Fee, DEFAULT_SIMULATED_GAS_MULTIPLIER};
 use crate::nyxd::{Coin, GasAdjustable, GasPrice, TxResponse};
-use crate::signing::signer::OfflineSigner;
+use crate::signing::signer::{OfflineSigner, SignerType};
 use crate::signing::tx_signer::TxSigner;
 use crate::signing::SignerData;
 use async_trait::async_trait;
@@ -687,7 +687,12 @@ pub trait SigningCosmWasmClient: CosmWasmClient {
             }
         };
 
-        self.sign_direct(signer_address, messages, fee, memo, signer_data)
+        match self.signer().preferred_signer_type() {
+            SignerType::Amino => self.sign_amino(signer_address, messages, fee, memo, signer_data),
+            SignerType::Direct => {
+                self.sign_direct(signer_address, messages, fee, memo, signer_data)
+            }
+        }
     }
 
     fn sign_amino(
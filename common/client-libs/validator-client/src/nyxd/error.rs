@@ -13,6 +13,9 @@ use cosmrs::{
 use thiserror::Error;
 
 use crate::signing::direct_wallet::DirectSecp256k1HdWalletError;
+#[cfg(feature = "ledger")]
+use crate::signing::ledger::LedgerSignerError;
+use crate::signing::signer::SigningError;
 pub use cosmrs::rpc::{
     error::{Error as TendermintRpcError, ErrorDetail as TendermintRpcErrorDetail},
     response_error::{Code, ResponseError},
@@ -27,6 +30,13 @@ pub enum NyxdError {
     #[error(transparent)]
     WalletError(#[from] DirectSecp256k1HdWalletError),
 
+    #[cfg(feature = "ledger")]
+    #[error(transparent)]
+    LedgerSignerError(#[from] LedgerSignerError),
+
+    #[error(transparent)]
+    SigningError(#[from] SigningError),
+
     #[error("There was an issue on the cosmrs side - {0}")]
     CosmrsError(#[from] cosmrs::Error),
 
@@ -126,6 +136,9 @@ pub enum NyxdError {
     #[error("{coin_representation} is not a valid Cosmos Coin")]
     MalformedCoin { coin_representation: String },
 
+    #[error("{event_type} contract event is malformed: {reason}")]
+    MalformedContractEvent { event_type: String, reason: String },
+
     #[error("This account does not have BaseAccount information available to it")]
     NoBaseAccountInformationAvailable,
 
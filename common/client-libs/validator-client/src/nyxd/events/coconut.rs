@@ -0,0 +1,40 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nyxd::error::NyxdError;
+use crate::nyxd::events::{parse_attribute, required_attribute};
+use cosmwasm_std::{Event, Uint128};
+use nym_coconut_bandwidth_contract_common::events::{
+    DEPOSITED_FUNDS_EVENT_TYPE, DEPOSIT_ENCRYPTION_KEY, DEPOSIT_IDENTITY_KEY, DEPOSIT_INFO,
+    DEPOSIT_VALUE,
+};
+
+/// Event emitted by the coconut bandwidth contract.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CoconutBandwidthEvent {
+    /// Funds deposited in exchange for a bandwidth credential.
+    /// Note that the value is expressed in the base denomination of the chain.
+    DepositedFunds {
+        value: Uint128,
+        info: String,
+        identity_key: String,
+        encryption_key: String,
+    },
+    Other(Event),
+}
+
+impl CoconutBandwidthEvent {
+    pub(crate) fn try_from_event(event: Event) -> Result<Self, NyxdError> {
+        let decoded = match event.ty.as_str() {
+            DEPOSITED_FUNDS_EVENT_TYPE => CoconutBandwidthEvent::DepositedFunds {
+                value: parse_attribute(&event, DEPOSIT_VALUE)?,
+                info: required_attribute(&event, DEPOSIT_INFO)?,
+                identity_key: required_attribute(&event, DEPOSIT_IDENTITY_KEY)?,
+                encryption_key: required_attribute(&event, DEPOSIT_ENCRYPTION_KEY)?,
+            },
+            _ => CoconutBandwidthEvent::Other(event),
+        };
+
+        Ok(decoded)
+    }
+}
@@ -0,0 +1,338 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nyxd::error::NyxdError;
+use crate::nyxd::events::{
+    address_attribute, coin_attribute, malformed_event, optional_address_attribute,
+    optional_coin_attribute, optional_parsed_attribute, parse_attribute, required_attribute,
+};
+use cosmwasm_std::{Addr, Coin, Event};
+use nym_mixnet_contract_common::events::{
+    MixnetEventType, ACTIVE_SET_SIZE_KEY, AMOUNT_KEY, ASSIGNED_LAYER_KEY, DELEGATES_REWARD_KEY,
+    DELEGATION_TARGET_KEY, DELEGATOR_KEY, INTERVAL_KEY, MIX_ID_KEY, NEW_CURRENT_EPOCH_KEY,
+    NODE_IDENTITY_KEY, OPERATOR_REWARD_KEY, OWNER_KEY, PROXY_KEY, REWARDED_SET_NODES_KEY,
+};
+use nym_mixnet_contract_common::rewarding::RewardDistribution;
+use nym_mixnet_contract_common::{EpochId, IdentityKey, Layer, MixId};
+
+/// Event emitted by the mixnet contract.
+///
+/// Only the events that result in a change of the contract state are fully decoded.
+/// The remaining ones, such as announcements of pending changes, are kept in their raw form.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MixnetEvent {
+    MixnodeBonding {
+        mix_id: MixId,
+        identity: IdentityKey,
+        owner: Addr,
+        proxy: Option<Addr>,
+        assigned_layer: Layer,
+        amount: Coin,
+    },
+    PendingMixnodeUnbonding {
+        mix_id: MixId,
+        identity: IdentityKey,
+        owner: Addr,
+        proxy: Option<Addr>,
+    },
+    MixnodeUnbonding {
+        mix_id: MixId,
+    },
+    MixnodeConfigUpdate {
+        mix_id: MixId,
+        owner: Addr,
+        proxy: Option<Addr>,
+    },
+    MixnodeCostParamsUpdate {
+        mix_id: MixId,
+    },
+    PledgeIncrease {
+        mix_id: MixId,
+        amount: Coin,
+    },
+    PledgeDecrease {
+        mix_id: MixId,
+        amount: Coin,
+    },
+    GatewayBonding {
+        identity: IdentityKey,
+        owner: Addr,
+        proxy: Option<Addr>,
+        amount: Coin,
+    },
+    GatewayUnbonding {
+        identity: IdentityKey,
+        owner: Addr,
+        proxy: Option<Addr>,
+        amount: Coin,
+    },
+    GatewayConfigUpdate {
+        owner: Addr,
+        proxy: Option<Addr>,
+    },
+    /// Note that the amount is not known for delegations made towards nodes that have unbonded
+    /// in the meantime, as the tokens got returned to the delegator.
+    Delegation {
+        delegator: Addr,
+        proxy: Option<Addr>,
+        mix_id: MixId,
+        amount: Option<Coin>,
+    },
+    Undelegation {
+        delegator: Addr,
+        proxy: Option<Addr>,
+        mix_id: MixId,
+    },
+    WithdrawOperatorReward {
+        owner: Addr,
+        proxy: Option<Addr>,
+        mix_id: MixId,
+        amount: Coin,
+    },
+    WithdrawDelegatorReward {
+        delegator: Addr,
+        proxy: Option<Addr>,
+        mix_id: MixId,
+        amount: Coin,
+    },
+    /// The reward is not present if the node could not have been rewarded, for example,
+    /// because it has unbonded before the end of the epoch.
+    MixnodeRewarding {
+        epoch_id: EpochId,
+        mix_id: MixId,
+        reward: Option<RewardDistribution>,
+    },
+    ActiveSetUpdate {
+        active_set_size: u32,
+    },
+    AdvanceEpoch {
+        epoch_id: EpochId,
+        rewarded_set_nodes: u32,
+    },
+    Other(Event),
+}
+
+impl MixnetEvent {
+    pub(crate) fn try_from_event(event: Event) -> Result<Self, NyxdError> {
+        let Some(typ) = MixnetEventType::from_event_name(&event.ty) else {
+            return Ok(MixnetEvent::Other(event));
+        };
+
+        let decoded = match typ {
+            MixnetEventType::MixnodeBonding => MixnetEvent::MixnodeBonding {
+                mix_id: parse_attribute(&event, MIX_ID_KEY)?,
+                identity: required_attribute(&event, NODE_IDENTITY_KEY)?,
+                owner: address_attribute(&event, OWNER_KEY)?,
+                proxy: optional_address_attribute(&event, PROXY_KEY),
+                assigned_layer: parse_layer(&event)?,
+                amount: coin_attribute(&event, AMOUNT_KEY)?,
+            },
+            MixnetEventType::PendingMixnodeUnbonding => MixnetEvent::PendingMixnodeUnbonding {
+                mix_id: parse_attribute(&event, MIX_ID_KEY)?,
+                identity: required_attribute(&event, NODE_IDENTITY_KEY)?,
+                owner: address_attribute(&event, OWNER_KEY)?,
+                proxy: optional_address_attribute(&event, PROXY_KEY),
+            },
+            MixnetEventType::MixnodeUnbonding => MixnetEvent::MixnodeUnbonding {
+                mix_id: parse_attribute(&event, MIX_ID_KEY)?,
+            },
+            MixnetEventType::MixnodeConfigUpdate => MixnetEvent::MixnodeConfigUpdate {
+                mix_id: parse_attribute(&event, MIX_ID_KEY)?,
+                owner: address_attribute(&event, OWNER_KEY)?,
+                proxy: optional_address_attribute(&event, PROXY_KEY),
+            },
+            MixnetEventType::MixnodeCostParamsUpdate => MixnetEvent::MixnodeCostParamsUpdate {
+                mix_id: parse_attribute(&event, MIX_ID_KEY)?,
+            },
+            MixnetEventType::PledgeIncrease => MixnetEvent::PledgeIncrease {
+                mix_id: parse_attribute(&event, MIX_ID_KEY)?,
+                amount: coin_attribute(&event, AMOUNT_KEY)?,
+            },
+            MixnetEventType::PledgeDecrease => MixnetEvent::PledgeDecrease {
+                mix_id: parse_attribute(&event, MIX_ID_KEY)?,
+                amount: coin_attribute(&event, AMOUNT_KEY)?,
+            },
+            MixnetEventType::GatewayBonding => MixnetEvent::GatewayBonding {
+                identity: required_attribute(&event, NODE_IDENTITY_KEY)?,
+                owner: address_attribute(&event, OWNER_KEY)?,
+                proxy: optional_address_attribute(&event, PROXY_KEY),
+                amount: coin_attribute(&event, AMOUNT_KEY)?,
+            },
+            MixnetEventType::GatewayUnbonding => MixnetEvent::GatewayUnbonding {
+                identity: required_attribute(&event, NODE_IDENTITY_KEY)?,
+                owner: address_attribute(&event, OWNER_KEY)?,
+                proxy: optional_address_attribute(&event, PROXY_KEY),
+                amount: coin_attribute(&event, AMOUNT_KEY)?,
+            },
+            MixnetEventType::GatewayConfigUpdate => MixnetEvent::GatewayConfigUpdate {
+                owner: address_attribute(&event, OWNER_KEY)?,
+                proxy: optional_address_attribute(&event, PROXY_KEY),
+            },
+            MixnetEventType::Delegation => MixnetEvent::Delegation {
+                delegator: address_attribute(&event, DELEGATOR_KEY)?,
+                proxy: optional_address_attribute(&event, PROXY_KEY),
+                mix_id: parse_attribute(&event, DELEGATION_TARGET_KEY)?,
+                amount: optional_coin_attribute(&event, AMOUNT_KEY)?,
+            },
+            MixnetEventType::Undelegation => MixnetEvent::Undelegation {
+                delegator: address_attribute(&event, DELEGATOR_KEY)?,
+                proxy: optional_address_attribute(&event, PROXY_KEY),
+                mix_id: parse_attribute(&event, MIX_ID_KEY)?,
+            },
+            MixnetEventType::WithdrawOperatorReward => MixnetEvent::WithdrawOperatorReward {
+                owner: address_attribute(&event, OWNER_KEY)?,
+                proxy: optional_address_attribute(&event, PROXY_KEY),
+                mix_id: parse_attribute(&event, MIX_ID_KEY)?,
+                amount: coin_attribute(&event, AMOUNT_KEY)?,
+            },
+            MixnetEventType::WithdrawDelegatorReward => MixnetEvent::WithdrawDelegatorReward {
+                delegator: address_attribute(&event, DELEGATOR_KEY)?,
+                proxy: optional_address_attribute(&event, PROXY_KEY),
+                mix_id: parse_attribute(&event, DELEGATION_TARGET_KEY)?,
+                amount: coin_attribute(&event, AMOUNT_KEY)?,
+            },
+            MixnetEventType::MixnodeRewarding => {
+                let operator = optional_parsed_attribute(&event, OPERATOR_REWARD_KEY)?;
+                let delegates = optional_parsed_attribute(&event, DELEGATES_REWARD_KEY)?;
+
+                MixnetEvent::MixnodeRewarding {
+                    epoch_id: parse_attribute(&event, INTERVAL_KEY)?,
+                    mix_id: parse_attribute(&event, MIX_ID_KEY)?,
+                    reward: operator.zip(delegates).map(|(operator, delegates)| {
+                        RewardDistribution {
+                            operator,
+                            delegates,
+                        }
+                    }),
+                }
+            }
+            MixnetEventType::ActiveSetUpdate => MixnetEvent::ActiveSetUpdate {
+                active_set_size: parse_attribute(&event, ACTIVE_SET_SIZE_KEY)?,
+            },
+            MixnetEventType::AdvanceEpoch => MixnetEvent::AdvanceEpoch {
+                epoch_id: parse_attribute(&event, NEW_CURRENT_EPOCH_KEY)?,
+                rewarded_set_nodes: parse_attribute(&event, REWARDED_SET_NODES_KEY)?,
+            },
+            _ => MixnetEvent::Other(event),
+        };
+
+        Ok(decoded)
+    }
+}
+
+fn parse_layer(event: &Event) -> Result<Layer, NyxdError> {
+    let raw: u8 = parse_attribute(event, ASSIGNED_LAYER_KEY)?;
+    Layer::try_from(raw).map_err(|err| malformed_event(event, err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nyxd::events::into_contract_event;
+    use crate::nyxd::events::tests::{raw_event, MIXNET_CONTRACT};
+    use cosmwasm_std::Decimal;
+    use std::str::FromStr;
+
+    fn decode(typ: MixnetEventType, attributes: &[(&str, &str)]) -> Result<MixnetEvent, NyxdError> {
+        let raw = raw_event(MIXNET_CONTRACT, &typ.to_string(), attributes);
+        MixnetEvent::try_from_event(into_contract_event(&raw).unwrap())
+    }
+
+    #[test]
+    fn decoding_mixnode_bonding() {
+        let event = decode(
+            MixnetEventType::MixnodeBonding,
+            &[
+                (MIX_ID_KEY, "123"),
+                (
+                    NODE_IDENTITY_KEY,
+                    "7kXLMZmW4kLvFhCpBRMFoZ3GHWzmsNYPjkdqwy9CBWQx",
+                ),
+                (OWNER_KEY, "n1owner"),
+                (ASSIGNED_LAYER_KEY, "2"),
+                (AMOUNT_KEY, "100000000unym"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            event,
+            MixnetEvent::MixnodeBonding {
+                mix_id: 123,
+                identity: "7kXLMZmW4kLvFhCpBRMFoZ3GHWzmsNYPjkdqwy9CBWQx".to_string(),
+                owner: Addr::unchecked("n1owner"),
+                proxy: None,
+                assigned_layer: Layer::Two,
+                amount: Coin::new(100000000, "unym"),
+            }
+        );
+
+        let invalid_layer = decode(
+            MixnetEventType::MixnodeBonding,
+            &[
+                (MIX_ID_KEY, "123"),
+                (
+                    NODE_IDENTITY_KEY,
+                    "7kXLMZmW4kLvFhCpBRMFoZ3GHWzmsNYPjkdqwy9CBWQx",
+                ),
+                (OWNER_KEY, "n1owner"),
+                (ASSIGNED_LAYER_KEY, "4"),
+                (AMOUNT_KEY, "100000000unym"),
+            ],
+        );
+        assert!(invalid_layer.is_err());
+    }
+
+    #[test]
+    fn decoding_rewarding() {
+        let rewarded = decode(
+            MixnetEventType::MixnodeRewarding,
+            &[
+                (INTERVAL_KEY, "100"),
+                (MIX_ID_KEY, "5"),
+                (OPERATOR_REWARD_KEY, "1234.5"),
+                (DELEGATES_REWARD_KEY, "42"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            rewarded,
+            MixnetEvent::MixnodeRewarding {
+                epoch_id: 100,
+                mix_id: 5,
+                reward: Some(RewardDistribution {
+                    operator: Decimal::from_str("1234.5").unwrap(),
+                    delegates: Decimal::from_str("42").unwrap(),
+                }),
+            }
+        );
+
+        let not_rewarded = decode(
+            MixnetEventType::MixnodeRewarding,
+            &[(INTERVAL_KEY, "100"), (MIX_ID_KEY, "5")],
+        )
+        .unwrap();
+        assert_eq!(
+            not_rewarded,
+            MixnetEvent::MixnodeRewarding {
+                epoch_id: 100,
+                mix_id: 5,
+                reward: None,
+            }
+        );
+    }
+
+    #[test]
+    fn pending_events_are_kept_raw() {
+        let event = decode(
+            MixnetEventType::PendingDelegation,
+            &[(DELEGATOR_KEY, "n1delegator"), (DELEGATION_TARGET_KEY, "5")],
+        )
+        .unwrap();
+
+        let MixnetEvent::Other(raw) = event else {
+            panic!("unexpected event: {event:?}")
+        };
+        assert_eq!(raw.ty, MixnetEventType::PendingDelegation.to_string());
+    }
+}
@@ -0,0 +1,330 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed representation of the events emitted by the Nym contracts alongside a websocket subscription
+//! that allows following them block by block.
+
+use crate::nyxd::error::NyxdError;
+use crate::nyxd::{Config, DeliverTx, Event, Height};
+use cosmrs::AccountId;
+use cosmwasm_std::{Addr, Coin};
+use nym_contracts_common::events::may_find_attribute;
+use std::fmt::Display;
+use std::str::FromStr;
+
+pub use coconut::CoconutBandwidthEvent;
+pub use mixnet::MixnetEvent;
+pub use subscription::{websocket_url, ContractEventsSubscriber};
+pub use vesting::VestingEvent;
+
+pub mod coconut;
+pub mod mixnet;
+pub mod subscription;
+pub mod vesting;
+
+// cosmwasm prefixes types of all custom events emitted by the contracts
+const WASM_EVENT_PREFIX: &str = "wasm-";
+const CONTRACT_ADDRESS_KEY: &str = "_contract_address";
+
+/// Addresses of the contracts whose events should be decoded.
+#[derive(Debug, Clone, Default)]
+pub struct EventContracts {
+    pub mixnet: Option<AccountId>,
+    pub vesting: Option<AccountId>,
+    pub coconut_bandwidth: Option<AccountId>,
+}
+
+impl From<&Config> for EventContracts {
+    fn from(config: &Config) -> Self {
+        EventContracts {
+            mixnet: config.mixnet_contract_address.clone(),
+            vesting: config.vesting_contract_address.clone(),
+            coconut_bandwidth: config.coconut_bandwidth_contract_address.clone(),
+        }
+    }
+}
+
+impl EventContracts {
+    fn matches(contract: &Option<AccountId>, address: &str) -> bool {
+        contract
+            .as_ref()
+            .map(|contract| contract.as_ref() == address)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContractEventKind {
+    Mixnet(MixnetEvent),
+    Vesting(VestingEvent),
+    CoconutBandwidth(CoconutBandwidthEvent),
+}
+
+/// An event emitted by one of the watched contracts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractEvent {
+    /// Index of the transaction within the block that has emitted the event.
+    pub tx_index: usize,
+    pub kind: ContractEventKind,
+}
+
+impl ContractEvent {
+    /// Attempts to decode the raw event emitted as part of a transaction.
+    /// Returns `Ok(None)` if the event has not been emitted by any of the watched contracts.
+    pub fn decode(
+        contracts: &EventContracts,
+        tx_index: usize,
+        raw: &Event,
+    ) -> Result<Option<Self>, NyxdError> {
+        let Some(event) = into_contract_event(raw) else {
+            return Ok(None);
+        };
+        let Some(contract) = may_find_attribute(&event, CONTRACT_ADDRESS_KEY) else {
+            return Ok(None);
+        };
+
+        let kind = if EventContracts::matches(&contracts.mixnet, &contract) {
+            ContractEventKind::Mixnet(MixnetEvent::try_from_event(event)?)
+        } else if EventContracts::matches(&contracts.vesting, &contract) {
+            ContractEventKind::Vesting(VestingEvent::try_from_event(event)?)
+        } else if EventContracts::matches(&contracts.coconut_bandwidth, &contract) {
+            ContractEventKind::CoconutBandwidth(CoconutBandwidthEvent::try_from_event(event)?)
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(ContractEvent { tx_index, kind }))
+    }
+}
+
+/// All the events emitted by the watched contracts in the particular block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockEvents {
+    pub height: Height,
+    pub events: Vec<ContractEvent>,
+}
+
+impl BlockEvents {
+    pub fn decode(
+        contracts: &EventContracts,
+        height: Height,
+        txs_results: &[DeliverTx],
+    ) -> Result<Self, NyxdError> {
+        let mut events = Vec::new();
+        // failed transactions don't emit any events, but let's be explicit about it
+        for (tx_index, tx_result) in txs_results
+            .iter()
+            .enumerate()
+            .filter(|(_, tx_result)| tx_result.code.is_ok())
+        {
+            for raw in &tx_result.events {
+                if let Some(event) = ContractEvent::decode(contracts, tx_index, raw)? {
+                    events.push(event)
+                }
+            }
+        }
+
+        Ok(BlockEvents { height, events })
+    }
+}
+
+// strips the cosmwasm prefix from the event type so that it would match the type emitted by the contract
+fn into_contract_event(raw: &Event) -> Option<cosmwasm_std::Event> {
+    let typ = raw.type_str.strip_prefix(WASM_EVENT_PREFIX)?;
+
+    let mut event = cosmwasm_std::Event::new(typ);
+    // note: attributes have to be constructed directly as `Attribute::new` panics on reserved keys,
+    // such as the address of the contract
+    event.attributes = raw
+        .attributes
+        .iter()
+        .map(|tag| cosmwasm_std::Attribute {
+            key: tag.key.to_string(),
+            value: tag.value.to_string(),
+        })
+        .collect();
+    Some(event)
+}
+
+fn malformed_event<S: Into<String>>(event: &cosmwasm_std::Event, reason: S) -> NyxdError {
+    NyxdError::MalformedContractEvent {
+        event_type: event.ty.clone(),
+        reason: reason.into(),
+    }
+}
+
+fn required_attribute(event: &cosmwasm_std::Event, key: &str) -> Result<String, NyxdError> {
+    may_find_attribute(event, key)
+        .ok_or_else(|| malformed_event(event, format!("the '{key}' attribute is missing")))
+}
+
+fn parse_value<T>(event: &cosmwasm_std::Event, key: &str, raw: &str) -> Result<T, NyxdError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    raw.parse()
+        .map_err(|err| malformed_event(event, format!("the '{key}' attribute is invalid: {err}")))
+}
+
+fn parse_attribute<T>(event: &cosmwasm_std::Event, key: &str) -> Result<T, NyxdError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    parse_value(event, key, &required_attribute(event, key)?)
+}
+
+fn optional_parsed_attribute<T>(
+    event: &cosmwasm_std::Event,
+    key: &str,
+) -> Result<Option<T>, NyxdError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    may_find_attribute(event, key)
+        .map(|raw| parse_value(event, key, &raw))
+        .transpose()
+}
+
+// note: the addresses have already been validated by the contract itself
+fn address_attribute(event: &cosmwasm_std::Event, key: &str) -> Result<Addr, NyxdError> {
+    required_attribute(event, key).map(Addr::unchecked)
+}
+
+fn optional_address_attribute(event: &cosmwasm_std::Event, key: &str) -> Option<Addr> {
+    may_find_attribute(event, key).map(Addr::unchecked)
+}
+
+// coins are emitted in their display representation, i.e. `<amount><denom>`
+fn parse_coin(raw: &str) -> Option<Coin> {
+    let denom_start = raw.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, denom) = raw.split_at(denom_start);
+    if amount.is_empty() {
+        return None;
+    }
+
+    Some(Coin::new(amount.parse().ok()?, denom))
+}
+
+fn coin_attribute(event: &cosmwasm_std::Event, key: &str) -> Result<Coin, NyxdError> {
+    let raw = required_attribute(event, key)?;
+    parse_coin(&raw).ok_or_else(|| malformed_event(event, format!("'{raw}' is not a valid coin")))
+}
+
+fn optional_coin_attribute(
+    event: &cosmwasm_std::Event,
+    key: &str,
+) -> Result<Option<Coin>, NyxdError> {
+    if may_find_attribute(event, key).is_none() {
+        return Ok(None);
+    }
+    coin_attribute(event, key).map(Some)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::nyxd::Tag;
+    use nym_mixnet_contract_common::events::{
+        MixnetEventType, AMOUNT_KEY, DELEGATION_TARGET_KEY, DELEGATOR_KEY,
+    };
+
+    pub(crate) const MIXNET_CONTRACT: &str =
+        "n14hj2tavq8fpesdwxxcu44rty3hh90vhujrvcmstl4zr3txmfvw9sjyvg3g";
+    const VESTING_CONTRACT: &str = "n1nc5tatafv6eyq7llkr2gv50ff9e22mnf70qgjlv737ktmt4eswrq73f2nw";
+
+    pub(crate) fn raw_event(contract: &str, typ: &str, attributes: &[(&str, &str)]) -> Event {
+        let mut tags = vec![Tag {
+            key: CONTRACT_ADDRESS_KEY.parse().unwrap(),
+            value: contract.parse().unwrap(),
+        }];
+        tags.extend(attributes.iter().map(|(key, value)| Tag {
+            key: key.parse().unwrap(),
+            value: value.parse().unwrap(),
+        }));
+
+        Event {
+            type_str: format!("{WASM_EVENT_PREFIX}{typ}"),
+            attributes: tags,
+        }
+    }
+
+    fn contracts() -> EventContracts {
+        EventContracts {
+            mixnet: Some(MIXNET_CONTRACT.parse().unwrap()),
+            vesting: Some(VESTING_CONTRACT.parse().unwrap()),
+            coconut_bandwidth: None,
+        }
+    }
+
+    #[test]
+    fn parsing_coins() {
+        assert_eq!(parse_coin("1000unym"), Some(Coin::new(1000, "unym")));
+        assert_eq!(
+            parse_coin("42ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"),
+            Some(Coin::new(
+                42,
+                "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+            ))
+        );
+        assert!(parse_coin("unym").is_none());
+        assert!(parse_coin("1000").is_none());
+        assert!(parse_coin("").is_none());
+    }
+
+    #[test]
+    fn events_are_attributed_to_the_emitting_contract() {
+        let delegation = [
+            (DELEGATOR_KEY, "n1jw6mp7d5xqc7w6xm79lha27glmd0vdt3l9artf"),
+            (AMOUNT_KEY, "1000unym"),
+            (DELEGATION_TARGET_KEY, "42"),
+        ];
+        let typ = MixnetEventType::Delegation.to_string();
+
+        let event = ContractEvent::decode(
+            &contracts(),
+            3,
+            &raw_event(MIXNET_CONTRACT, &typ, &delegation),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(event.tx_index, 3);
+        assert!(matches!(
+            event.kind,
+            ContractEventKind::Mixnet(MixnetEvent::Delegation { mix_id: 42, .. })
+        ));
+
+        // the same event coming from some other contract is ignored
+        let unknown = "n1qg5ega6dykkxc307y25pecuufrjkxkaggkkxh7nad0vhyhtuhw3sp6zgz7";
+        let res = ContractEvent::decode(&contracts(), 3, &raw_event(unknown, &typ, &delegation));
+        assert!(res.unwrap().is_none());
+
+        // as are non-wasm events
+        let mut bank_event = raw_event(MIXNET_CONTRACT, "transfer", &[]);
+        bank_event.type_str = "transfer".to_string();
+        assert!(ContractEvent::decode(&contracts(), 0, &bank_event)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn malformed_events_are_rejected() {
+        let typ = MixnetEventType::Delegation.to_string();
+        let delegation = [
+            (DELEGATOR_KEY, "n1jw6mp7d5xqc7w6xm79lha27glmd0vdt3l9artf"),
+            (DELEGATION_TARGET_KEY, "not-a-mix-id"),
+        ];
+
+        let res = ContractEvent::decode(
+            &contracts(),
+            0,
+            &raw_event(MIXNET_CONTRACT, &typ, &delegation),
+        );
+        assert!(matches!(
+            res,
+            Err(NyxdError::MalformedContractEvent { event_type, .. }) if event_type == typ
+        ));
+    }
+}
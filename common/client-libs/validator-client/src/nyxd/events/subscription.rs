@@ -0,0 +1,192 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nyxd::error::NyxdError;
+use crate::nyxd::events::{BlockEvents, EventContracts};
+use crate::nyxd::Height;
+use cosmrs::rpc::event::{Event as RpcEvent, EventData};
+use cosmrs::rpc::query::EventType;
+use cosmrs::rpc::{Client, Subscription, SubscriptionClient, WebSocketClient};
+use futures::{Stream, StreamExt};
+use log::{debug, warn};
+use url::Url;
+
+/// Derives the address of the websocket endpoint from the url of the RPC endpoint of the validator,
+/// i.e. `http://localhost:26657` becomes `ws://localhost:26657/websocket`.
+pub fn websocket_url(nyxd_url: &Url) -> Url {
+    let mut url = nyxd_url.clone();
+    let scheme = if nyxd_url.scheme() == "https" {
+        "wss"
+    } else {
+        "ws"
+    };
+    // the only possible failure is changing between 'special' and 'non-special' schemes,
+    // such as from `http` to `foo`, which is not the case here
+    let _ = url.set_scheme(scheme);
+    url.set_path("websocket");
+    url
+}
+
+/// Follows the chain block by block and decodes events emitted by the watched contracts,
+/// so that the caches of the contract state could be updated incrementally rather than
+/// re-querying the whole state on every refresh.
+pub struct ContractEventsSubscriber {
+    client: WebSocketClient,
+    contracts: EventContracts,
+}
+
+impl ContractEventsSubscriber {
+    /// Establishes a websocket connection to the validator, for example with the url obtained via [`websocket_url`].
+    pub async fn connect(url: Url, contracts: EventContracts) -> Result<Self, NyxdError> {
+        let (client, driver) = WebSocketClient::new(url.as_str()).await?;
+        tokio::spawn(async move {
+            if let Err(err) = driver.run().await {
+                warn!("the websocket connection to the validator has failed - {err}")
+            }
+        });
+
+        Ok(ContractEventsSubscriber { client, contracts })
+    }
+
+    /// Returns stream of events emitted in every subsequent block.
+    ///
+    /// If `start_height` is specified, all the blocks since that height are processed first
+    /// before the stream catches up with the chain. Otherwise it starts with the first new block.
+    /// A consumer that wishes to resume processing after a restart should thus pass the height
+    /// following the last block it has processed.
+    ///
+    /// Blocks containing malformed events result in an error, but the stream carries on with the subsequent ones.
+    /// However, the stream terminates upon any failure of the connection to the validator.
+    pub async fn subscribe(
+        self,
+        start_height: Option<Height>,
+    ) -> Result<impl Stream<Item = Result<BlockEvents, NyxdError>>, NyxdError> {
+        // subscribe before checking the current height so that we wouldn't miss any blocks in between
+        let new_blocks = self.client.subscribe(EventType::NewBlock.into()).await?;
+
+        let latest_height = if start_height.is_some() {
+            Some(self.client.status().await?.sync_info.latest_block_height)
+        } else {
+            None
+        };
+
+        let state = SubscriptionState {
+            client: self.client,
+            contracts: self.contracts,
+            new_blocks,
+            next_height: start_height,
+            latest_height,
+            finished: false,
+        };
+
+        Ok(futures::stream::unfold(state, |mut state| async move {
+            state.next_block_events().await.map(|item| (item, state))
+        }))
+    }
+}
+
+struct SubscriptionState {
+    client: WebSocketClient,
+    contracts: EventContracts,
+    new_blocks: Subscription,
+
+    /// Height of the next block that is going to be processed.
+    next_height: Option<Height>,
+
+    /// Height of the most recent block that we know of.
+    latest_height: Option<Height>,
+
+    finished: bool,
+}
+
+impl SubscriptionState {
+    async fn next_block_events(&mut self) -> Option<Result<BlockEvents, NyxdError>> {
+        if self.finished {
+            return None;
+        }
+
+        loop {
+            if let (Some(next), Some(latest)) = (self.next_height, self.latest_height) {
+                if next <= latest {
+                    self.next_height = Some(next.increment());
+                    return Some(self.block_events(next).await);
+                }
+            }
+
+            match self.new_blocks.next().await {
+                Some(Ok(event)) => {
+                    let Some(height) = new_block_height(&event) else {
+                        debug!("received a new block notification without the block itself");
+                        continue;
+                    };
+                    self.latest_height = Some(height);
+                    if self.next_height.is_none() {
+                        self.next_height = Some(height)
+                    }
+                }
+                Some(Err(err)) => {
+                    self.finished = true;
+                    return Some(Err(err.into()));
+                }
+                None => {
+                    self.finished = true;
+                    return None;
+                }
+            }
+        }
+    }
+
+    async fn block_events(&mut self, height: Height) -> Result<BlockEvents, NyxdError> {
+        let results = match self.client.block_results(height).await {
+            Ok(results) => results,
+            Err(err) => {
+                self.finished = true;
+                return Err(err.into());
+            }
+        };
+
+        BlockEvents::decode(
+            &self.contracts,
+            height,
+            results.txs_results.as_deref().unwrap_or_default(),
+        )
+    }
+}
+
+impl Drop for SubscriptionState {
+    fn drop(&mut self) {
+        // make sure to also terminate the connection driver
+        if let Err(err) = self.client.clone().close() {
+            debug!("failed to close the websocket connection - {err}")
+        }
+    }
+}
+
+fn new_block_height(event: &RpcEvent) -> Option<Height> {
+    match &event.data {
+        EventData::NewBlock {
+            block: Some(block), ..
+        } => Some(block.header.height),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deriving_websocket_url() {
+        let http: Url = "http://localhost:26657".parse().unwrap();
+        assert_eq!(
+            websocket_url(&http).as_str(),
+            "ws://localhost:26657/websocket"
+        );
+
+        let https: Url = "https://rpc.nymtech.net".parse().unwrap();
+        assert_eq!(
+            websocket_url(&https).as_str(),
+            "wss://rpc.nymtech.net/websocket"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nyxd::error::NyxdError;
+use crate::nyxd::events::{
+    address_attribute, coin_attribute, malformed_event, optional_address_attribute,
+    required_attribute,
+};
+use cosmwasm_std::{Addr, Coin, Event, Timestamp};
+use nym_vesting_contract_common::events::{
+    AMOUNT_KEY, FROM_ACCOUNT_KEY, NEW_PERIODIC_VESTING_ACCOUNT_EVENT_TYPE, NO_VALUE_VALUE,
+    OWNERSHIP_TRANSFER_EVENT_TYPE, OWNER_KEY, REMAINING_SPENDABLE_KEY, STAKING_ADDRESS_KEY,
+    START_TIME_KEY, TO_ACCOUNT_KEY, WITHDRAW_EVENT_TYPE,
+};
+
+/// Event emitted by the vesting contract.
+///
+/// Most of the events emitted when staking with vesting tokens don't carry any attributes
+/// as the relevant information is already part of the accompanying mixnet contract event,
+/// so they are kept in their raw form.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VestingEvent {
+    NewPeriodicVestingAccount {
+        owner: Addr,
+        amount: Coin,
+        staking_address: Option<Addr>,
+        start_time: Timestamp,
+    },
+    VestedCoinsWithdraw {
+        owner: Addr,
+        amount: Coin,
+        remaining_spendable: Coin,
+    },
+    /// Emitted when either the ownership of the account or its staking address got changed.
+    OwnershipTransfer {
+        from: Option<Addr>,
+        to: Option<Addr>,
+    },
+    Other(Event),
+}
+
+impl VestingEvent {
+    pub(crate) fn try_from_event(event: Event) -> Result<Self, NyxdError> {
+        let decoded = match event.ty.as_str() {
+            NEW_PERIODIC_VESTING_ACCOUNT_EVENT_TYPE => VestingEvent::NewPeriodicVestingAccount {
+                owner: address_attribute(&event, OWNER_KEY)?,
+                amount: coin_attribute(&event, AMOUNT_KEY)?,
+                staking_address: optional_address_attribute(&event, STAKING_ADDRESS_KEY),
+                start_time: timestamp_attribute(&event, START_TIME_KEY)?,
+            },
+            WITHDRAW_EVENT_TYPE => VestingEvent::VestedCoinsWithdraw {
+                owner: address_attribute(&event, OWNER_KEY)?,
+                amount: coin_attribute(&event, AMOUNT_KEY)?,
+                remaining_spendable: coin_attribute(&event, REMAINING_SPENDABLE_KEY)?,
+            },
+            OWNERSHIP_TRANSFER_EVENT_TYPE => VestingEvent::OwnershipTransfer {
+                from: account_attribute(&event, FROM_ACCOUNT_KEY)?,
+                to: account_attribute(&event, TO_ACCOUNT_KEY)?,
+            },
+            _ => VestingEvent::Other(event),
+        };
+
+        Ok(decoded)
+    }
+}
+
+// timestamps are emitted as `<seconds>.<nanoseconds>`
+fn timestamp_attribute(event: &Event, key: &str) -> Result<Timestamp, NyxdError> {
+    let raw = required_attribute(event, key)?;
+    let invalid = || malformed_event(event, format!("'{raw}' is not a valid timestamp"));
+
+    let (seconds, nanos) = raw.split_once('.').ok_or_else(invalid)?;
+    let seconds: u64 = seconds.parse().map_err(|_| invalid())?;
+    let nanos: u64 = nanos.parse().map_err(|_| invalid())?;
+
+    Ok(Timestamp::from_seconds(seconds).plus_nanos(nanos))
+}
+
+// the account is explicitly set to 'none' if it's not present
+fn account_attribute(event: &Event, key: &str) -> Result<Option<Addr>, NyxdError> {
+    let raw = required_attribute(event, key)?;
+    if raw == NO_VALUE_VALUE {
+        Ok(None)
+    } else {
+        Ok(Some(Addr::unchecked(raw)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nyxd::events::into_contract_event;
+    use crate::nyxd::events::tests::raw_event;
+    use nym_vesting_contract_common::events::{
+        new_ownership_transfer_event, new_periodic_vesting_account_event,
+        VESTING_DELEGATION_EVENT_TYPE,
+    };
+
+    // makes sure we can decode whatever the contract itself emits
+    fn emitted(event: Event) -> Result<VestingEvent, NyxdError> {
+        let attributes = event
+            .attributes
+            .iter()
+            .map(|attr| (attr.key.as_str(), attr.value.as_str()))
+            .collect::<Vec<_>>();
+        let raw = raw_event("n1vesting", &event.ty, &attributes);
+        VestingEvent::try_from_event(into_contract_event(&raw).unwrap())
+    }
+
+    #[test]
+    fn decoding_new_vesting_account() {
+        let owner = Addr::unchecked("n1owner");
+        let start_time = Timestamp::from_seconds(1666666666).plus_nanos(123);
+        let amount = Coin::new(1000000, "unym");
+
+        let event = new_periodic_vesting_account_event(&owner, &amount, &None, start_time);
+        assert_eq!(
+            emitted(event).unwrap(),
+            VestingEvent::NewPeriodicVestingAccount {
+                owner,
+                amount,
+                staking_address: None,
+                start_time,
+            }
+        );
+    }
+
+    #[test]
+    fn decoding_ownership_transfer() {
+        let from = Addr::unchecked("n1from");
+        let to = Addr::unchecked("n1to");
+
+        let event = new_ownership_transfer_event(&from, &to);
+        assert_eq!(
+            emitted(event).unwrap(),
+            VestingEvent::OwnershipTransfer {
+                from: Some(from),
+                to: Some(to),
+            }
+        );
+    }
+
+    #[test]
+    fn barebone_events_are_kept_raw() {
+        let event = emitted(Event::new(VESTING_DELEGATION_EVENT_TYPE)).unwrap();
+        assert!(
+            matches!(event, VestingEvent::Other(raw) if raw.ty == VESTING_DELEGATION_EVENT_TYPE)
+        );
+    }
+}
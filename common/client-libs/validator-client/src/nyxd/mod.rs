@@ -1,26 +1,33 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::nyxd::cosmwasm_client::helpers::CheckResponse;
 use crate::nyxd::cosmwasm_client::signing_client;
 use crate::nyxd::cosmwasm_client::types::{
     Account, ChangeAdminResult, ContractCodeId, ExecuteResult, InstantiateOptions,
     InstantiateResult, MigrateResult, SequenceResponse, SimulateResponse, UploadResult,
 };
 use crate::nyxd::error::NyxdError;
+use crate::nyxd::events::{ContractEventsSubscriber, EventContracts};
 use crate::nyxd::fee::DEFAULT_SIMULATED_GAS_MULTIPLIER;
 use crate::signing::direct_wallet::DirectSecp256k1HdWallet;
+#[cfg(feature = "ledger")]
+use crate::signing::ledger::LedgerSigner;
 use crate::signing::signer::OfflineSigner;
+use crate::signing::unsigned_tx::UnsignedTx;
 use cosmrs::cosmwasm;
 use cosmrs::rpc::endpoint::block::Response as BlockResponse;
 use cosmrs::rpc::query::Query;
 use cosmrs::rpc::Error as TendermintRpcError;
 use cosmrs::rpc::HttpClientUrl;
+use cosmrs::tendermint::chain;
 use cosmrs::tx::Msg;
 use log::debug;
 use nym_network_defaults::{ChainDetails, NymNetworkDetails};
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
 use std::time::SystemTime;
+use url::Url;
 
 pub use crate::nyxd::cosmwasm_client::client::CosmWasmClient;
 pub use crate::nyxd::cosmwasm_client::signing_client::SigningCosmWasmClient;
@@ -47,10 +54,13 @@ pub use signing_client::Client as SigningNyxdClient;
 pub use traits::{VestingQueryClient, VestingSigningClient};
 
 pub type DirectSigningNyxdClient = SigningNyxdClient<DirectSecp256k1HdWallet>;
+#[cfg(feature = "ledger")]
+pub type LedgerSigningNyxdClient = SigningNyxdClient<LedgerSigner>;
 
 pub mod coin;
 pub mod cosmwasm_client;
 pub mod error;
+pub mod events;
 pub mod fee;
 pub mod traits;
 
@@ -187,6 +197,22 @@ impl NyxdClient<SigningNyxdClient<DirectSecp256k1HdWallet>> {
     }
 }
 
+#[cfg(feature = "ledger")]
+impl NyxdClient<SigningNyxdClient<LedgerSigner>> {
+    pub fn connect_with_ledger<U: Clone>(
+        config: Config,
+        endpoint: U,
+        gas_price: Option<GasPrice>,
+    ) -> Result<NyxdClient<SigningNyxdClient<LedgerSigner>>, NyxdError>
+    where
+        U: TryInto<HttpClientUrl, Error = TendermintRpcError>,
+    {
+        let prefix = &config.chain_details.bech32_account_prefix;
+        let signer = LedgerSigner::new(prefix)?;
+        Self::connect_with_signer(config, endpoint, signer, gas_price)
+    }
+}
+
 impl<S> NyxdClient<SigningNyxdClient<S>>
 where
     S: OfflineSigner,
@@ -239,6 +265,15 @@ impl<C> NyxdClient<C> {
         &self.config.chain_details
     }
 
+    /// Connects to the websocket endpoint of the validator in order to follow the events
+    /// emitted by the mixnet, vesting and coconut bandwidth contracts.
+    pub async fn contract_events_subscriber(
+        &self,
+        websocket_url: Url,
+    ) -> Result<ContractEventsSubscriber, NyxdError> {
+        ContractEventsSubscriber::connect(websocket_url, EventContracts::from(&self.config)).await
+    }
+
     pub fn set_mixnet_contract_address(&mut self, address: AccountId) {
         self.config.mixnet_contract_address = Some(address);
     }
@@ -435,6 +470,23 @@ impl<C> NyxdClient<C> {
         self.client.get_account(address).await
     }
 
+    pub async fn get_account_sequence(
+        &self,
+        address: &AccountId,
+    ) -> Result<SequenceResponse, NyxdError>
+    where
+        C: CosmWasmClient + Sync,
+    {
+        self.client.get_sequence(address).await
+    }
+
+    pub async fn get_chain_id(&self) -> Result<chain::Id, NyxdError>
+    where
+        C: CosmWasmClient + Sync,
+    {
+        self.client.get_chain_id().await
+    }
+
     pub async fn get_account_public_key(
         &self,
         address: &AccountId,
@@ -567,6 +619,33 @@ impl<C> NyxdClient<C> {
             .await
     }
 
+    /// Simulates the execution of the unsigned transaction in order to estimate its gas cost.
+    pub async fn simulate_unsigned_tx(&self, tx: &UnsignedTx) -> Result<SimulateResponse, NyxdError>
+    where
+        C: CosmWasmClient + Sync,
+    {
+        self.client
+            .query_simulate(Some(tx.simulation_tx()?), Vec::new())
+            .await
+    }
+
+    /// Broadcasts the transaction that has been signed by all of its signers
+    /// and waits for its inclusion in a block.
+    pub async fn broadcast_signed_tx(&self, tx: &UnsignedTx) -> Result<TxResponse, NyxdError>
+    where
+        C: CosmWasmClient + Sync,
+    {
+        let tx_bytes = tx
+            .to_raw()?
+            .to_bytes()
+            .map_err(|_| NyxdError::SerializationError("Tx".to_owned()))?;
+
+        self.client
+            .broadcast_tx(tx_bytes.into())
+            .await?
+            .check_response()
+    }
+
     /// Send funds from one address to another
     pub async fn send(
         &self,
@@ -27,6 +27,12 @@ pub trait CoconutBandwidthSigningClient {
         gateway_cosmos_address: String,
         fee: Option<Fee>,
     ) -> Result<ExecuteResult, NyxdError>;
+    async fn spend_credentials(
+        &self,
+        credentials: Vec<(Coin, String)>,
+        gateway_cosmos_address: String,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError>;
 }
 
 #[async_trait]
@@ -80,4 +86,37 @@ impl<C: SigningCosmWasmClient + Sync + Send> CoconutBandwidthSigningClient for N
             )
             .await
     }
+
+    async fn spend_credentials(
+        &self,
+        credentials: Vec<(Coin, String)>,
+        gateway_cosmos_address: String,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let reqs = credentials
+            .into_iter()
+            .map(|(funds, blinded_serial_number)| {
+                (
+                    ExecuteMsg::SpendCredential {
+                        data: SpendCredentialData::new(
+                            funds.into(),
+                            blinded_serial_number,
+                            gateway_cosmos_address.clone(),
+                        ),
+                    },
+                    vec![],
+                )
+            })
+            .collect::<Vec<_>>();
+        self.client
+            .execute_multiple(
+                self.address(),
+                self.coconut_bandwidth_contract_address(),
+                reqs,
+                fee,
+                "CoconutBandwidth::SpendCredential::Multiple",
+            )
+            .await
+    }
 }
@@ -35,6 +35,12 @@ pub trait MultisigSigningClient {
         proposal_id: u64,
         fee: Option<Fee>,
     ) -> Result<ExecuteResult, NyxdError>;
+
+    async fn execute_proposals(
+        &self,
+        proposal_ids: Vec<u64>,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError>;
 }
 
 #[async_trait]
@@ -115,4 +121,25 @@ impl<C: SigningCosmWasmClient + Sync + Send> MultisigSigningClient for NyxdClien
             )
             .await
     }
+
+    async fn execute_proposals(
+        &self,
+        proposal_ids: Vec<u64>,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let reqs = proposal_ids
+            .into_iter()
+            .map(|proposal_id| (ExecuteMsg::Execute { proposal_id }, vec![]))
+            .collect::<Vec<_>>();
+        self.client
+            .execute_multiple(
+                self.address(),
+                self.multisig_contract_address(),
+                reqs,
+                fee,
+                "Multisig::Execute::Multiple",
+            )
+            .await
+    }
 }
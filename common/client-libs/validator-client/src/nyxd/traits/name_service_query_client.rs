@@ -1,10 +1,10 @@
 use async_trait::async_trait;
 use cosmrs::AccountId;
-use nym_contracts_common::ContractBuildInformation;
+use nym_contracts_common::{signing::Nonce, ContractBuildInformation};
 use nym_name_service_common::{
     msg::QueryMsg as NameQueryMsg,
     response::{ConfigResponse, NamesListResponse, PagedNamesListResponse},
-    Address, NameEntry, NameId,
+    Address, NameEntry, NameId, NymName,
 };
 use serde::Deserialize;
 
@@ -42,6 +42,21 @@ pub trait NameServiceQueryClient {
         .await
     }
 
+    async fn get_name_entry_by_name(&self, name: NymName) -> Result<NameEntry, NyxdError> {
+        self.query_name_service_contract(NameQueryMsg::ByName { name })
+            .await
+    }
+
+    async fn get_name_service_signing_nonce(
+        &self,
+        address: &AccountId,
+    ) -> Result<Nonce, NyxdError> {
+        self.query_name_service_contract(NameQueryMsg::GetSigningNonce {
+            address: address.to_string(),
+        })
+        .await
+    }
+
     async fn get_names_by_address(&self, address: Address) -> Result<NamesListResponse, NyxdError> {
         self.query_name_service_contract(NameQueryMsg::ByAddress { address })
             .await
@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_trait::async_trait;
+use cosmrs::AccountId;
+use nym_contracts_common::signing::MessageSignature;
 use nym_name_service_common::{msg::ExecuteMsg as NameExecuteMsg, Address, NameId, NymName};
 
 use crate::nyxd::{
@@ -22,12 +24,17 @@ pub trait NameServiceSigningClient {
         &self,
         name: NymName,
         address: Address,
+        owner_signature: MessageSignature,
         deposit: Coin,
         fee: Option<Fee>,
     ) -> Result<ExecuteResult, NyxdError> {
         self.execute_name_service_contract(
             fee,
-            NameExecuteMsg::Register { name, address },
+            NameExecuteMsg::Register {
+                name,
+                address,
+                owner_signature,
+            },
             vec![deposit],
         )
         .await
@@ -51,6 +58,32 @@ pub trait NameServiceSigningClient {
             .await
     }
 
+    async fn renew_name(
+        &self,
+        name_id: NameId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_name_service_contract(fee, NameExecuteMsg::Renew { name_id }, vec![])
+            .await
+    }
+
+    async fn transfer_name_ownership(
+        &self,
+        name_id: NameId,
+        new_owner: AccountId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_name_service_contract(
+            fee,
+            NameExecuteMsg::TransferOwnership {
+                name_id,
+                new_owner: new_owner.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
     async fn update_deposit_required(
         &self,
         deposit_required: Coin,
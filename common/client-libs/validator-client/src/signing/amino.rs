@@ -0,0 +1,291 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::signing::signer::SigningError;
+use crate::signing::SignerData;
+use cosmrs::bank::MsgSend;
+use cosmrs::distribution::MsgWithdrawDelegatorReward;
+use cosmrs::staking::{MsgDelegate, MsgUndelegate};
+use cosmrs::tx::{self, AccountNumber, Msg, SequenceNumber};
+use cosmrs::{cosmwasm, Any, Coin};
+use serde_json::{json, Value};
+
+const MSG_EXECUTE_CONTRACT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+const MSG_SEND_TYPE_URL: &str = "/cosmos.bank.v1beta1.MsgSend";
+const MSG_DELEGATE_TYPE_URL: &str = "/cosmos.staking.v1beta1.MsgDelegate";
+const MSG_UNDELEGATE_TYPE_URL: &str = "/cosmos.staking.v1beta1.MsgUndelegate";
+const MSG_WITHDRAW_DELEGATOR_REWARD_TYPE_URL: &str =
+    "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward";
+
+/// A message in its amino json representation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AminoMsg {
+    pub typ: String,
+    pub value: Value,
+}
+
+impl AminoMsg {
+    pub fn new<S: Into<String>>(typ: S, value: Value) -> Self {
+        AminoMsg {
+            typ: typ.into(),
+            value,
+        }
+    }
+
+    /// Attempts to convert the protobuf-encoded message into its amino json representation.
+    /// Only the subset of messages used by our clients is supported.
+    pub fn from_any(msg: &Any) -> Result<Self, SigningError> {
+        let sign_doc_failure = |source| SigningError::SignDocFailure { source };
+
+        match msg.type_url.as_str() {
+            MSG_EXECUTE_CONTRACT_TYPE_URL => {
+                let msg = cosmwasm::MsgExecuteContract::from_any(msg).map_err(sign_doc_failure)?;
+                let contract_msg: Value =
+                    serde_json::from_slice(&msg.msg).map_err(|err| sign_doc_failure(err.into()))?;
+                Ok(AminoMsg::new(
+                    "wasm/MsgExecuteContract",
+                    json!({
+                        "sender": msg.sender.to_string(),
+                        "contract": msg.contract.to_string(),
+                        "msg": contract_msg,
+                        "funds": coins_json(&msg.funds),
+                    }),
+                ))
+            }
+            MSG_SEND_TYPE_URL => {
+                let msg = MsgSend::from_any(msg).map_err(sign_doc_failure)?;
+                Ok(AminoMsg::new(
+                    "cosmos-sdk/MsgSend",
+                    json!({
+                        "from_address": msg.from_address.to_string(),
+                        "to_address": msg.to_address.to_string(),
+                        "amount": coins_json(&msg.amount),
+                    }),
+                ))
+            }
+            MSG_DELEGATE_TYPE_URL => {
+                let msg = MsgDelegate::from_any(msg).map_err(sign_doc_failure)?;
+                Ok(AminoMsg::new(
+                    "cosmos-sdk/MsgDelegate",
+                    json!({
+                        "delegator_address": msg.delegator_address.to_string(),
+                        "validator_address": msg.validator_address.to_string(),
+                        "amount": coin_json(&msg.amount),
+                    }),
+                ))
+            }
+            MSG_UNDELEGATE_TYPE_URL => {
+                let msg = MsgUndelegate::from_any(msg).map_err(sign_doc_failure)?;
+                Ok(AminoMsg::new(
+                    "cosmos-sdk/MsgUndelegate",
+                    json!({
+                        "delegator_address": msg.delegator_address.to_string(),
+                        "validator_address": msg.validator_address.to_string(),
+                        "amount": coin_json(&msg.amount),
+                    }),
+                ))
+            }
+            MSG_WITHDRAW_DELEGATOR_REWARD_TYPE_URL => {
+                let msg = MsgWithdrawDelegatorReward::from_any(msg).map_err(sign_doc_failure)?;
+                Ok(AminoMsg::new(
+                    "cosmos-sdk/MsgWithdrawDelegationReward",
+                    json!({
+                        "delegator_address": msg.delegator_address.to_string(),
+                        "validator_address": msg.validator_address.to_string(),
+                    }),
+                ))
+            }
+            type_url => Err(SigningError::UnsupportedAminoMessage {
+                type_url: type_url.to_string(),
+            }),
+        }
+    }
+
+    fn to_json_value(&self) -> Value {
+        json!({
+            "type": self.typ,
+            "value": self.value,
+        })
+    }
+}
+
+/// The document that gets signed in the legacy amino json signing mode.
+#[derive(Debug, Clone)]
+pub struct StdSignDoc {
+    pub chain_id: String,
+    pub account_number: AccountNumber,
+    pub sequence: SequenceNumber,
+    pub fee: tx::Fee,
+    pub msgs: Vec<AminoMsg>,
+    pub memo: String,
+}
+
+impl StdSignDoc {
+    pub fn new(
+        messages: &[Any],
+        fee: tx::Fee,
+        memo: String,
+        signer_data: &SignerData,
+    ) -> Result<Self, SigningError> {
+        let msgs = messages
+            .iter()
+            .map(AminoMsg::from_any)
+            .collect::<Result<_, _>>()?;
+
+        Ok(StdSignDoc {
+            chain_id: signer_data.chain_id.to_string(),
+            account_number: signer_data.account_number,
+            sequence: signer_data.sequence,
+            fee,
+            msgs,
+            memo,
+        })
+    }
+
+    fn fee_json(&self) -> Value {
+        let mut fee = json!({
+            "amount": coins_json(&self.fee.amount),
+            "gas": self.fee.gas_limit.value().to_string(),
+        });
+        if let Some(payer) = &self.fee.payer {
+            fee["payer"] = Value::String(payer.to_string());
+        }
+        if let Some(granter) = &self.fee.granter {
+            fee["granter"] = Value::String(granter.to_string());
+        }
+        fee
+    }
+
+    fn to_json_value(&self) -> Value {
+        json!({
+            "chain_id": self.chain_id,
+            "account_number": self.account_number.to_string(),
+            "sequence": self.sequence.to_string(),
+            "fee": self.fee_json(),
+            "msgs": self.msgs.iter().map(AminoMsg::to_json_value).collect::<Vec<_>>(),
+            "memo": self.memo,
+        })
+    }
+
+    /// Returns the canonical json representation of the document, i.e. the exact string that has to be signed:
+    /// compact, with all object keys sorted and with `&`, `<` and `>` characters escaped.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        write_sorted_json(&self.to_json_value(), &mut out);
+        out.replace('&', "\\u0026")
+            .replace('<', "\\u003c")
+            .replace('>', "\\u003e")
+    }
+
+    pub fn to_sign_bytes(&self) -> Vec<u8> {
+        self.to_json().into_bytes()
+    }
+}
+
+fn coin_json(coin: &Coin) -> Value {
+    json!({
+        "amount": coin.amount.to_string(),
+        "denom": coin.denom.to_string(),
+    })
+}
+
+fn coins_json(coins: &[Coin]) -> Value {
+    Value::Array(coins.iter().map(coin_json).collect())
+}
+
+// we can't rely on the ordering of `serde_json::Map` as it changes with the `preserve_order` feature
+fn write_sorted_json(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_sorted_json(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_sorted_json(value, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmrs::tx::Gas;
+
+    fn signer_data() -> SignerData {
+        SignerData::new(42, 7, "nyx".parse().unwrap())
+    }
+
+    fn fee(amount: u64, gas_limit: u64) -> tx::Fee {
+        let amount = Coin {
+            denom: "unym".parse().unwrap(),
+            amount: amount.into(),
+        };
+        tx::Fee::from_amount_and_gas(amount, Gas::from(gas_limit))
+    }
+
+    #[test]
+    fn sign_doc_json_is_sorted_and_compact() {
+        let sender = "n1jw6mp7d5xqc7w6xm79lha27glmd0vdt3l9artf".parse().unwrap();
+        let contract = "n14hj2tavq8fpesdwxxcu44rty3hh90vhujrvcmstl4zr3txmfvw9sjyvg3g"
+            .parse()
+            .unwrap();
+        let msg = cosmwasm::MsgExecuteContract {
+            sender,
+            contract,
+            msg: br#"{"delegate_to_mixnode":{"mix_id":5,"note":"<a&b>"}}"#.to_vec(),
+            funds: vec![],
+        }
+        .to_any()
+        .unwrap();
+
+        let sign_doc = StdSignDoc::new(
+            &[msg],
+            fee(5000, 200000),
+            "memo".to_string(),
+            &signer_data(),
+        )
+        .unwrap();
+
+        let expected = concat!(
+            r#"{"account_number":"42","chain_id":"nyx","#,
+            r#""fee":{"amount":[{"amount":"5000","denom":"unym"}],"gas":"200000"},"memo":"memo","#,
+            r#""msgs":[{"type":"wasm/MsgExecuteContract","value":{"contract":"n14hj2tavq8fpesdwxxcu44rty3hh90vhujrvcmstl4zr3txmfvw9sjyvg3g","#,
+            r#""funds":[],"msg":{"delegate_to_mixnode":{"mix_id":5,"note":"\u003ca\u0026b\u003e"}},"#,
+            r#""sender":"n1jw6mp7d5xqc7w6xm79lha27glmd0vdt3l9artf"}}],"sequence":"7"}"#
+        );
+        assert_eq!(sign_doc.to_json(), expected);
+    }
+
+    #[test]
+    fn unsupported_messages_are_rejected() {
+        let msg = Any {
+            type_url: "/cosmos.gov.v1beta1.MsgVote".to_string(),
+            value: vec![],
+        };
+
+        let res = StdSignDoc::new(&[msg], fee(0, 0), String::new(), &signer_data());
+        assert!(matches!(
+            res,
+            Err(SigningError::UnsupportedAminoMessage { type_url }) if type_url == "/cosmos.gov.v1beta1.MsgVote"
+        ));
+    }
+}
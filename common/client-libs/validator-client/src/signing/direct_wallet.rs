@@ -65,7 +65,7 @@ impl OfflineSigner for DirectSecp256k1HdWallet {
         sign_doc: SignDoc,
     ) -> Result<tx::Raw, Self::Error> {
         sign_doc
-            .sign(&signer.private_key)
+            .sign(signer.signing_key()?)
             .map_err(|source| SigningError::SigningFailure { source }.into())
     }
 }
@@ -113,7 +113,7 @@ impl DirectSecp256k1HdWallet {
             accounts.push(AccountData {
                 address,
                 public_key: keypair.1,
-                private_key: keypair.0,
+                private_key: Some(keypair.0),
             })
         }
 
@@ -0,0 +1,183 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::signing::amino::StdSignDoc;
+use crate::signing::signer::{OfflineSigner, Signature, SignerType, SigningError};
+use crate::signing::AccountData;
+use cosmrs::bip32::DerivationPath;
+use cosmrs::crypto::secp256k1::VerifyingKey;
+use cosmrs::crypto::PublicKey;
+use cosmrs::AccountId;
+use nym_config::defaults;
+use nym_ledger::error::LedgerError;
+use nym_ledger::transport::LedgerTransport;
+use nym_ledger::{CosmosLedger, TransportNativeHID};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum LedgerSignerError {
+    #[error(transparent)]
+    SigningFailure(#[from] SigningError),
+
+    #[error("failed to communicate with the ledger device: {source}")]
+    LedgerFailure {
+        #[from]
+        source: LedgerError,
+    },
+
+    #[error("the ledger device has returned an invalid address: {source}")]
+    InvalidAddress { source: eyre::Report },
+}
+
+/// Signer backed by a Ledger hardware wallet running the Cosmos application.
+///
+/// The private key never leaves the device and thus only the amino json signing mode is supported.
+/// Each transaction has to be reviewed and approved on the device itself.
+pub struct LedgerSigner<T = TransportNativeHID> {
+    ledger: CosmosLedger<T>,
+}
+
+impl LedgerSigner {
+    /// Connects to the first Ledger device that could be found and uses the default derivation path.
+    pub fn new(prefix: &str) -> Result<Self, LedgerSignerError> {
+        // the default derivation path is a valid constant
+        let path = defaults::COSMOS_DERIVATION_PATH.parse().unwrap();
+        Self::new_with_path(path, prefix)
+    }
+
+    /// Connects to the first Ledger device that could be found and uses the provided derivation path.
+    pub fn new_with_path(path: DerivationPath, prefix: &str) -> Result<Self, LedgerSignerError> {
+        Ok(LedgerSigner {
+            ledger: CosmosLedger::new(path, prefix.to_string())?,
+        })
+    }
+}
+
+impl<T: LedgerTransport> LedgerSigner<T> {
+    /// Uses the provided transport, such as a software emulator, for communicating with the device.
+    pub fn with_transport(path: DerivationPath, prefix: &str, transport: T) -> Self {
+        LedgerSigner {
+            ledger: CosmosLedger::with_transport(path, prefix.to_string(), transport),
+        }
+    }
+
+    fn account(&self, display: bool) -> Result<AccountData, LedgerSignerError> {
+        let response = self.ledger.get_addr_secp256k1(display)?;
+
+        let public_key = PublicKey::from(VerifyingKey::from(&response.public_key));
+        let address = response
+            .address
+            .parse()
+            .map_err(|source| LedgerSignerError::InvalidAddress { source })?;
+
+        Ok(AccountData {
+            address,
+            public_key,
+            private_key: None,
+        })
+    }
+
+    /// Displays the address on the device so that the user could confirm it's the expected one.
+    pub fn show_address(&self) -> Result<AccountId, LedgerSignerError> {
+        self.account(true).map(|account| account.address)
+    }
+}
+
+impl<T: LedgerTransport> OfflineSigner for LedgerSigner<T> {
+    type Error = LedgerSignerError;
+
+    fn preferred_signer_type(&self) -> SignerType {
+        SignerType::Amino
+    }
+
+    fn get_accounts(&self) -> Result<Vec<AccountData>, Self::Error> {
+        Ok(vec![self.account(false)?])
+    }
+
+    fn sign_amino_with_account(
+        &self,
+        _signer: &AccountData,
+        sign_doc: &StdSignDoc,
+    ) -> Result<Signature, Self::Error> {
+        let response = self.ledger.sign_secp256k1(sign_doc.to_json())?;
+
+        // the chain only accepts signatures with the low S value
+        let signature = response.signature;
+        Ok(signature.normalize_s().unwrap_or(signature))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::direct_wallet::DirectSecp256k1HdWallet;
+    use crate::signing::SignerData;
+    use cosmrs::bank::MsgSend;
+    use cosmrs::bip32::XPrv;
+    use cosmrs::tx::{self, Gas, Msg};
+    use cosmrs::Coin;
+    use nym_ledger::emulator::LedgerEmulator;
+
+    const MNEMONIC: &str = "crush minute paddle tobacco message debate cabin peace bar jacket execute twenty winner view sure mask popular couch penalty fragile demise fresh pizza stove";
+
+    fn wallet_and_emulated_ledger() -> (DirectSecp256k1HdWallet, LedgerSigner<LedgerEmulator>) {
+        let mnemonic = bip39::Mnemonic::parse(MNEMONIC).unwrap();
+        let path: DerivationPath = defaults::COSMOS_DERIVATION_PATH.parse().unwrap();
+
+        let wallet = DirectSecp256k1HdWallet::from_mnemonic("n", mnemonic.clone());
+        let address = wallet.try_derive_accounts().unwrap()[0].address.to_string();
+
+        let key = XPrv::derive_from_path(mnemonic.to_seed(""), &path).unwrap();
+        let emulator = LedgerEmulator::new(key.private_key().clone(), address);
+
+        (wallet, LedgerSigner::with_transport(path, "n", emulator))
+    }
+
+    #[test]
+    fn ledger_account_matches_the_wallet() {
+        let (wallet, ledger) = wallet_and_emulated_ledger();
+
+        let wallet_account = &wallet.get_accounts().unwrap()[0];
+        let ledger_account = &ledger.get_accounts().unwrap()[0];
+
+        assert_eq!(wallet_account.address, ledger_account.address);
+        assert_eq!(wallet_account.public_key, ledger_account.public_key);
+        assert!(ledger_account.private_key().is_none());
+    }
+
+    #[test]
+    fn ledger_produces_the_same_amino_signatures_as_the_wallet() {
+        let (wallet, ledger) = wallet_and_emulated_ledger();
+        let address = ledger.get_accounts().unwrap()[0].address.clone();
+
+        let send = MsgSend {
+            from_address: address.clone(),
+            to_address: address.clone(),
+            amount: vec![Coin {
+                denom: "unym".parse().unwrap(),
+                amount: 1000u64.into(),
+            }],
+        }
+        .to_any()
+        .unwrap();
+        let fee = tx::Fee::from_amount_and_gas(
+            Coin {
+                denom: "unym".parse().unwrap(),
+                amount: 5000u64.into(),
+            },
+            Gas::from(200000u64),
+        );
+        let signer_data = SignerData::new(1, 2, "nyx".parse().unwrap());
+
+        // make sure the document spans multiple apdu chunks
+        let memo = "a".repeat(600);
+        let sign_doc = StdSignDoc::new(&[send], fee, memo, &signer_data).unwrap();
+
+        let ledger_signature = ledger.sign_amino(&address, &sign_doc).unwrap();
+        let wallet_signature = wallet.sign_amino(&address, &sign_doc).unwrap();
+        assert_eq!(ledger_signature, wallet_signature);
+
+        // but it can't sign arbitrary data
+        assert!(ledger.sign_raw(&address, b"foomp").is_err());
+    }
+}
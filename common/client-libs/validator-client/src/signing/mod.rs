@@ -1,6 +1,7 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::signing::signer::SigningError;
 use cosmrs::bip32::DerivationPath;
 use cosmrs::crypto::secp256k1::SigningKey;
 use cosmrs::crypto::PublicKey;
@@ -8,9 +9,13 @@ use cosmrs::tendermint::chain;
 use cosmrs::tx::{AccountNumber, SequenceNumber};
 use cosmrs::AccountId;
 
+pub mod amino;
 pub mod direct_wallet;
+#[cfg(feature = "ledger")]
+pub mod ledger;
 pub mod signer;
 pub mod tx_signer;
+pub mod unsigned_tx;
 
 /// Derivation information required to derive a keypair and an address from a mnemonic.
 #[derive(Debug, Clone)]
@@ -19,13 +24,14 @@ struct Secp256k1Derivation {
     prefix: String,
 }
 
-// TODO: is this struct going to be derivable with other signer types?
 pub struct AccountData {
     pub address: AccountId,
 
     pub(crate) public_key: PublicKey,
 
-    pub(crate) private_key: SigningKey,
+    /// The private key of the account. It's not available if the key never leaves
+    /// the external device, like in the case of hardware wallets.
+    pub(crate) private_key: Option<SigningKey>,
 }
 
 impl AccountData {
@@ -37,8 +43,16 @@ impl AccountData {
         self.public_key
     }
 
-    pub fn private_key(&self) -> &SigningKey {
-        &self.private_key
+    pub fn private_key(&self) -> Option<&SigningKey> {
+        self.private_key.as_ref()
+    }
+
+    pub(crate) fn signing_key(&self) -> Result<&SigningKey, SigningError> {
+        self.private_key
+            .as_ref()
+            .ok_or_else(|| SigningError::UnavailablePrivateKey {
+                account: self.address.clone(),
+            })
     }
 }
 
@@ -1,6 +1,7 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::signing::amino::StdSignDoc;
 use crate::signing::AccountData;
 pub use cosmrs::crypto::secp256k1::Signature;
 use cosmrs::tx::SignDoc;
@@ -20,6 +21,27 @@ pub enum SigningError {
 
     #[error("failed to construct the sign doc: {source}")]
     SignDocFailure { source: eyre::Report },
+
+    #[error("the private key of account {account} is not available to this signer")]
+    UnavailablePrivateKey { account: AccountId },
+
+    #[error("message of type {type_url} has no amino json representation")]
+    UnsupportedAminoMessage { type_url: String },
+
+    #[error("the transaction has already been signed and can no longer be modified")]
+    TransactionAlreadySigned,
+
+    #[error("{address} is not one of the signers of the transaction")]
+    NotATransactionSigner { address: AccountId },
+
+    #[error("the transaction is still missing signatures of: {}", .signers.join(", "))]
+    MissingSignatures { signers: Vec<String> },
+
+    #[error("the transactions differ and thus their signatures can't be combined")]
+    MismatchedTransactions,
+
+    #[error("the transaction is malformed: {reason}")]
+    MalformedTransaction { reason: String },
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -28,11 +50,14 @@ pub enum SignerType {
     Direct,
 }
 
-// TODO: check if this trait needs to be async
-// (I guess it depends on future ledger requirements)
 pub trait OfflineSigner {
     type Error: From<SigningError>;
 
+    /// The signing mode that should be used for transactions created with this signer.
+    fn preferred_signer_type(&self) -> SignerType {
+        SignerType::Direct
+    }
+
     fn get_accounts(&self) -> Result<Vec<AccountData>, Self::Error>;
 
     fn find_account(&self, signer_address: &AccountId) -> Result<AccountData, Self::Error> {
@@ -55,7 +80,7 @@ pub trait OfflineSigner {
         message: M,
     ) -> Result<Signature, Self::Error> {
         signer
-            .private_key
+            .signing_key()?
             .sign(message.as_ref())
             .map_err(|source| SigningError::SigningFailure { source }.into())
     }
@@ -90,7 +115,21 @@ pub trait OfflineSigner {
         .into())
     }
 
-    // fn sign_amino(&self, signer_address: &AccountId, sign_doc: AminoSignDoc) -> Result<tx::Raw, Self::Error>;
+    fn sign_amino(
+        &self,
+        signer_address: &AccountId,
+        sign_doc: &StdSignDoc,
+    ) -> Result<Signature, Self::Error> {
+        let signer = self.find_account(signer_address)?;
+        self.sign_amino_with_account(&signer, sign_doc)
+    }
 
-    // fn sign_amino_with_account(&self, signer: &AccountData, sign_doc: AminoSignDoc) -> Result<tx::Raw, Self::Error>;
+    // signing the canonical json representation is equivalent to signing in the amino mode
+    fn sign_amino_with_account(
+        &self,
+        signer: &AccountData,
+        sign_doc: &StdSignDoc,
+    ) -> Result<Signature, Self::Error> {
+        self.sign_raw_with_account(signer, sign_doc.to_sign_bytes())
+    }
 }
@@ -1,9 +1,12 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::signing::amino::StdSignDoc;
 use crate::signing::signer::{OfflineSigner, SigningError};
 use crate::signing::SignerData;
-use cosmrs::tx::{SignDoc, SignerInfo};
+use cosmrs::proto::cosmos::tx::signing::v1beta1::SignMode;
+use cosmrs::proto::cosmos::tx::v1beta1::TxRaw;
+use cosmrs::tx::{mode_info, ModeInfo, SignDoc, SignerInfo};
 use cosmrs::{tx, AccountId, Any};
 
 #[derive(Debug)]
@@ -28,16 +31,50 @@ impl<S> TxSigner<S> {
 
     pub fn sign_amino(
         &self,
-        _signer_address: &AccountId,
-        _messages: Vec<Any>,
-        _fee: tx::Fee,
-        _memo: impl Into<String> + Send + 'static,
-        _signer_data: SignerData,
+        signer_address: &AccountId,
+        messages: Vec<Any>,
+        fee: tx::Fee,
+        memo: impl Into<String> + Send + 'static,
+        signer_data: SignerData,
     ) -> Result<tx::Raw, S::Error>
     where
         S: OfflineSigner,
     {
-        unimplemented!()
+        let account_from_signer = self.signer.find_account(signer_address)?;
+        let memo = memo.into();
+
+        // TODO: experiment with this field
+        let timeout_height = 0u32;
+
+        let sign_doc = StdSignDoc::new(&messages, fee.clone(), memo.clone(), &signer_data)?;
+        let signature = self
+            .signer
+            .sign_amino_with_account(&account_from_signer, &sign_doc)?;
+
+        // the body and auth info have to contain exactly the same data as the signed amino document
+        let tx_body = tx::Body::new(messages, memo, timeout_height);
+        let signer_info = SignerInfo {
+            public_key: Some(account_from_signer.public_key.into()),
+            mode_info: ModeInfo::Single(mode_info::Single {
+                mode: SignMode::LegacyAminoJson,
+            }),
+            sequence: signer_data.sequence,
+        };
+        let auth_info = signer_info.auth_info(fee);
+
+        let body_bytes = tx_body
+            .into_bytes()
+            .map_err(|source| SigningError::SignDocFailure { source })?;
+        let auth_info_bytes = auth_info
+            .into_bytes()
+            .map_err(|source| SigningError::SignDocFailure { source })?;
+
+        Ok(TxRaw {
+            body_bytes,
+            auth_info_bytes,
+            signatures: vec![signature.as_ref().to_vec()],
+        }
+        .into())
     }
 
     // TODO: change this sucker to use the trait better
@@ -0,0 +1,566 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transactions that are assembled, signed and broadcast in separate steps,
+//! possibly on different machines, for example if the signing keys are kept offline.
+//!
+//! Every signer uses the legacy amino json signing mode, so that each of them could sign the transaction
+//! independently of the others, including with a hardware wallet.
+
+use crate::signing::amino::StdSignDoc;
+use crate::signing::signer::{OfflineSigner, SigningError};
+use crate::signing::SignerData;
+use cosmrs::crypto::PublicKey;
+use cosmrs::proto::cosmos::tx::signing::v1beta1::SignMode;
+use cosmrs::proto::cosmos::tx::v1beta1::TxRaw;
+use cosmrs::tx::{self, mode_info, AccountNumber, Gas, ModeInfo, SequenceNumber, SignerInfo};
+use cosmrs::{AccountId, Any, Coin};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+fn malformed<S: Into<String>>(reason: S) -> SigningError {
+    SigningError::MalformedTransaction {
+        reason: reason.into(),
+    }
+}
+
+/// Protobuf-encoded message included in the transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncodedMessage {
+    pub type_url: String,
+
+    /// Base64-encoded value of the message.
+    pub value: String,
+}
+
+impl EncodedMessage {
+    fn from_any(msg: Any) -> Self {
+        EncodedMessage {
+            type_url: msg.type_url,
+            value: base64::encode(msg.value),
+        }
+    }
+
+    fn to_any(&self) -> Result<Any, SigningError> {
+        let value = base64::decode(&self.value).map_err(|err| {
+            malformed(format!(
+                "the value of {} is not valid base64: {err}",
+                self.type_url
+            ))
+        })?;
+
+        Ok(Any {
+            type_url: self.type_url.clone(),
+            value,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeCoin {
+    pub denom: String,
+    pub amount: String,
+}
+
+/// Fee paid by the first signer of the transaction. Note that fee grants are not supported.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedFee {
+    pub amount: Vec<FeeCoin>,
+    pub gas_limit: u64,
+}
+
+impl From<&tx::Fee> for UnsignedFee {
+    fn from(fee: &tx::Fee) -> Self {
+        UnsignedFee {
+            amount: fee
+                .amount
+                .iter()
+                .map(|coin| FeeCoin {
+                    denom: coin.denom.to_string(),
+                    amount: coin.amount.to_string(),
+                })
+                .collect(),
+            gas_limit: fee.gas_limit.value(),
+        }
+    }
+}
+
+impl UnsignedFee {
+    fn to_fee(&self) -> Result<tx::Fee, SigningError> {
+        let amount =
+            self.amount
+                .iter()
+                .map(|coin| {
+                    Ok(Coin {
+                        denom: coin.denom.parse().map_err(|_| {
+                            malformed(format!("'{}' is not a valid denom", coin.denom))
+                        })?,
+                        amount: coin.amount.parse().map_err(|_| {
+                            malformed(format!("'{}' is not a valid amount", coin.amount))
+                        })?,
+                    })
+                })
+                .collect::<Result<_, SigningError>>()?;
+
+        Ok(tx::Fee {
+            amount,
+            gas_limit: Gas::from(self.gas_limit),
+            payer: None,
+            granter: None,
+        })
+    }
+}
+
+/// Account that has to sign the transaction alongside its signature, once it's available.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedTxSigner {
+    pub address: String,
+    pub account_number: AccountNumber,
+    pub sequence: SequenceNumber,
+
+    /// Public key of the account in its json representation. It's only known once the account has signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<Value>,
+
+    /// Base64-encoded signature of the amino sign doc.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl UnsignedTxSigner {
+    fn same_account(&self, other: &UnsignedTxSigner) -> bool {
+        self.address == other.address
+            && self.account_number == other.account_number
+            && self.sequence == other.sequence
+    }
+
+    fn public_key(&self) -> Result<Option<PublicKey>, SigningError> {
+        self.public_key
+            .as_ref()
+            .map(|key| {
+                PublicKey::from_json(&key.to_string()).map_err(|err| {
+                    malformed(format!("invalid public key of {}: {err}", self.address))
+                })
+            })
+            .transpose()
+    }
+}
+
+/// A transaction whose messages are collected ahead of time and which is then signed
+/// by all of its signers before it gets broadcast.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedTx {
+    pub chain_id: String,
+    pub memo: String,
+    pub fee: UnsignedFee,
+    pub messages: Vec<EncodedMessage>,
+
+    /// Accounts required to sign the transaction in the order they appear as the senders of the messages.
+    /// The first one pays the fee.
+    pub signers: Vec<UnsignedTxSigner>,
+}
+
+impl UnsignedTx {
+    pub fn new<S: Into<String>>(chain_id: S, memo: S) -> Self {
+        UnsignedTx {
+            chain_id: chain_id.into(),
+            memo: memo.into(),
+            fee: UnsignedFee::default(),
+            messages: Vec::new(),
+            signers: Vec::new(),
+        }
+    }
+
+    /// Checks whether any of the signers has already signed the transaction.
+    pub fn is_signed(&self) -> bool {
+        self.signers.iter().any(|signer| signer.signature.is_some())
+    }
+
+    // any modification would invalidate existing signatures
+    fn ensure_unsigned(&self) -> Result<(), SigningError> {
+        if self.is_signed() {
+            Err(SigningError::TransactionAlreadySigned)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn has_signer(&self, address: &AccountId) -> bool {
+        self.find_signer(address).is_some()
+    }
+
+    fn find_signer(&self, address: &AccountId) -> Option<&UnsignedTxSigner> {
+        self.signers
+            .iter()
+            .find(|signer| signer.address == address.as_ref())
+    }
+
+    /// Adds another required signer to the transaction, unless it's already present.
+    /// Signers have to be added in the same order as they first appear as the senders of the messages.
+    pub fn add_signer(
+        &mut self,
+        address: &AccountId,
+        account_number: AccountNumber,
+        sequence: SequenceNumber,
+    ) -> Result<(), SigningError> {
+        self.ensure_unsigned()?;
+        if !self.has_signer(address) {
+            self.signers.push(UnsignedTxSigner {
+                address: address.to_string(),
+                account_number,
+                sequence,
+                public_key: None,
+                signature: None,
+            })
+        }
+        Ok(())
+    }
+
+    pub fn push_message(&mut self, message: Any) -> Result<(), SigningError> {
+        self.ensure_unsigned()?;
+        self.messages.push(EncodedMessage::from_any(message));
+        Ok(())
+    }
+
+    pub fn set_fee(&mut self, fee: &tx::Fee) -> Result<(), SigningError> {
+        self.ensure_unsigned()?;
+        self.fee = fee.into();
+        Ok(())
+    }
+
+    pub fn decoded_messages(&self) -> Result<Vec<Any>, SigningError> {
+        self.messages.iter().map(EncodedMessage::to_any).collect()
+    }
+
+    /// Returns addresses of all the signers that haven't signed the transaction yet.
+    pub fn missing_signatures(&self) -> Vec<&str> {
+        self.signers
+            .iter()
+            .filter(|signer| signer.signature.is_none())
+            .map(|signer| signer.address.as_str())
+            .collect()
+    }
+
+    /// Constructs the document that has to be signed by the particular signer.
+    pub fn sign_doc(&self, address: &AccountId) -> Result<StdSignDoc, SigningError> {
+        let signer =
+            self.find_signer(address)
+                .ok_or_else(|| SigningError::NotATransactionSigner {
+                    address: address.clone(),
+                })?;
+        let chain_id = self
+            .chain_id
+            .parse()
+            .map_err(|_| malformed(format!("'{}' is not a valid chain id", self.chain_id)))?;
+
+        let signer_data = SignerData::new(signer.account_number, signer.sequence, chain_id);
+        StdSignDoc::new(
+            &self.decoded_messages()?,
+            self.fee.to_fee()?,
+            self.memo.clone(),
+            &signer_data,
+        )
+    }
+
+    /// Signs the transaction with the specified account of the signer.
+    pub fn sign<S>(&mut self, signer: &S, address: &AccountId) -> Result<(), S::Error>
+    where
+        S: OfflineSigner,
+    {
+        let account = signer.find_account(address)?;
+        let sign_doc = self.sign_doc(address)?;
+        let signature = signer.sign_amino_with_account(&account, &sign_doc)?;
+
+        let public_key = serde_json::from_str(&account.public_key().to_json())
+            .map_err(|err| malformed(format!("failed to encode the public key: {err}")))?;
+
+        // the signer must exist since we have just created its sign doc
+        if let Some(entry) = self
+            .signers
+            .iter_mut()
+            .find(|signer| signer.address == address.as_ref())
+        {
+            entry.public_key = Some(public_key);
+            entry.signature = Some(base64::encode(signature.as_ref()));
+        }
+        Ok(())
+    }
+
+    /// Copies over signatures from another copy of the same transaction that has been signed independently.
+    pub fn merge_signatures(&mut self, other: &UnsignedTx) -> Result<(), SigningError> {
+        if self.chain_id != other.chain_id
+            || self.memo != other.memo
+            || self.fee != other.fee
+            || self.messages != other.messages
+            || self.signers.len() != other.signers.len()
+            || self
+                .signers
+                .iter()
+                .zip(&other.signers)
+                .any(|(ours, theirs)| !ours.same_account(theirs))
+        {
+            return Err(SigningError::MismatchedTransactions);
+        }
+
+        for (ours, theirs) in self.signers.iter_mut().zip(&other.signers) {
+            if ours.signature.is_none() && theirs.signature.is_some() {
+                ours.public_key = theirs.public_key.clone();
+                ours.signature = theirs.signature.clone();
+            }
+        }
+        Ok(())
+    }
+
+    /// Constructs the transaction without any signatures so that its gas cost could be simulated.
+    pub fn simulation_tx(&self) -> Result<tx::Tx, SigningError> {
+        let signer_infos = self
+            .signers
+            .iter()
+            .map(|signer| {
+                Ok(SignerInfo {
+                    public_key: signer.public_key()?.map(Into::into),
+                    mode_info: ModeInfo::Single(mode_info::Single {
+                        mode: SignMode::Unspecified,
+                    }),
+                    sequence: signer.sequence,
+                })
+            })
+            .collect::<Result<_, SigningError>>()?;
+
+        Ok(tx::Tx {
+            body: tx::Body::new(self.decoded_messages()?, self.memo.clone(), 0u32),
+            auth_info: tx::AuthInfo {
+                signer_infos,
+                fee: self.fee.to_fee()?,
+            },
+            signatures: vec![Vec::new(); self.signers.len()],
+        })
+    }
+
+    /// Assembles the final transaction once all of its signers have signed it.
+    pub fn to_raw(&self) -> Result<tx::Raw, SigningError> {
+        let missing = self.missing_signatures();
+        if !missing.is_empty() {
+            return Err(SigningError::MissingSignatures {
+                signers: missing.into_iter().map(ToString::to_string).collect(),
+            });
+        }
+
+        let mut signer_infos = Vec::with_capacity(self.signers.len());
+        let mut signatures = Vec::with_capacity(self.signers.len());
+        for signer in &self.signers {
+            let public_key = signer.public_key()?.ok_or_else(|| {
+                malformed(format!("the public key of {} is missing", signer.address))
+            })?;
+            signer_infos.push(SignerInfo {
+                public_key: Some(public_key.into()),
+                mode_info: ModeInfo::Single(mode_info::Single {
+                    mode: SignMode::LegacyAminoJson,
+                }),
+                sequence: signer.sequence,
+            });
+
+            // the signature must be present as we have checked for missing ones
+            let signature = signer.signature.as_deref().unwrap_or_default();
+            signatures.push(base64::decode(signature).map_err(|err| {
+                malformed(format!("invalid signature of {}: {err}", signer.address))
+            })?);
+        }
+
+        // the body and auth info have to contain exactly the same data as the signed amino documents
+        let tx_body = tx::Body::new(self.decoded_messages()?, self.memo.clone(), 0u32);
+        let auth_info = tx::AuthInfo {
+            signer_infos,
+            fee: self.fee.to_fee()?,
+        };
+
+        let body_bytes = tx_body
+            .into_bytes()
+            .map_err(|source| SigningError::SignDocFailure { source })?;
+        let auth_info_bytes = auth_info
+            .into_bytes()
+            .map_err(|source| SigningError::SignDocFailure { source })?;
+
+        Ok(TxRaw {
+            body_bytes,
+            auth_info_bytes,
+            signatures,
+        }
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::direct_wallet::{DirectSecp256k1HdWallet, DirectSecp256k1HdWalletError};
+    use cosmrs::bank::MsgSend;
+    use cosmrs::tx::Msg;
+
+    fn wallet(mnemonic: &str) -> (DirectSecp256k1HdWallet, AccountId) {
+        let wallet = DirectSecp256k1HdWallet::from_mnemonic("n", mnemonic.parse().unwrap());
+        let address = wallet.try_derive_accounts().unwrap()[0].address.clone();
+        (wallet, address)
+    }
+
+    fn wallets() -> [(DirectSecp256k1HdWallet, AccountId); 2] {
+        [
+            wallet("crush minute paddle tobacco message debate cabin peace bar jacket execute twenty winner view sure mask popular couch penalty fragile demise fresh pizza stove"),
+            wallet("acquire rebel spot skin gun such erupt pull swear must define ill chief turtle today flower chunk truth battle claw rigid detail gym feel"),
+        ]
+    }
+
+    fn send(from: &AccountId, to: &AccountId, amount: u64) -> Any {
+        MsgSend {
+            from_address: from.clone(),
+            to_address: to.clone(),
+            amount: vec![Coin {
+                denom: "unym".parse().unwrap(),
+                amount: amount.into(),
+            }],
+        }
+        .to_any()
+        .unwrap()
+    }
+
+    fn two_signer_tx(first: &AccountId, second: &AccountId) -> UnsignedTx {
+        let mut tx = UnsignedTx::new("nyx", "batch");
+        tx.add_signer(first, 42, 7).unwrap();
+        tx.push_message(send(first, second, 1000)).unwrap();
+        tx.push_message(send(first, second, 2000)).unwrap();
+        tx.add_signer(second, 43, 0).unwrap();
+        tx.push_message(send(second, first, 3000)).unwrap();
+
+        let fee_amount = Coin {
+            denom: "unym".parse().unwrap(),
+            amount: 5000u64.into(),
+        };
+        tx.set_fee(&tx::Fee::from_amount_and_gas(
+            fee_amount,
+            Gas::from(300000u64),
+        ))
+        .unwrap();
+        tx
+    }
+
+    #[test]
+    fn signers_are_only_added_once() {
+        let [(_, first), (_, second)] = wallets();
+        let tx = two_signer_tx(&first, &second);
+
+        assert_eq!(tx.signers.len(), 2);
+        assert_eq!(tx.signers[0].address, first.to_string());
+        assert_eq!(tx.signers[1].address, second.to_string());
+        assert_eq!(tx.messages.len(), 3);
+    }
+
+    #[test]
+    fn transaction_survives_json_roundtrip() {
+        let [(first_wallet, first), (_, second)] = wallets();
+        let mut tx = two_signer_tx(&first, &second);
+        tx.sign(&first_wallet, &first).unwrap();
+
+        let json = serde_json::to_string_pretty(&tx).unwrap();
+        let recovered: UnsignedTx = serde_json::from_str(&json).unwrap();
+        assert_eq!(tx, recovered);
+        assert_eq!(
+            tx.decoded_messages().unwrap(),
+            recovered.decoded_messages().unwrap()
+        );
+    }
+
+    #[test]
+    fn signed_transaction_can_not_be_modified() {
+        let [(first_wallet, first), (_, second)] = wallets();
+        let mut tx = two_signer_tx(&first, &second);
+        tx.sign(&first_wallet, &first).unwrap();
+
+        assert!(matches!(
+            tx.push_message(send(&first, &second, 1)),
+            Err(SigningError::TransactionAlreadySigned)
+        ));
+        assert!(matches!(
+            tx.add_signer(&second, 43, 0),
+            Err(SigningError::TransactionAlreadySigned)
+        ));
+    }
+
+    #[test]
+    fn only_listed_signers_can_sign() {
+        let [(first_wallet, first), (second_wallet, second)] = wallets();
+        let mut tx = UnsignedTx::new("nyx", "");
+        tx.add_signer(&first, 42, 7).unwrap();
+        tx.push_message(send(&first, &second, 1000)).unwrap();
+
+        assert!(matches!(
+            tx.sign(&second_wallet, &second),
+            Err(DirectSecp256k1HdWalletError::SigningFailure(SigningError::NotATransactionSigner { address })) if address == second
+        ));
+        // and the wallet must actually hold the key
+        assert!(matches!(
+            tx.sign(&second_wallet, &first),
+            Err(DirectSecp256k1HdWalletError::SigningFailure(
+                SigningError::AccountNotFound { .. }
+            ))
+        ));
+        tx.sign(&first_wallet, &first).unwrap();
+    }
+
+    #[test]
+    fn independently_signed_copies_are_combined() {
+        let [(first_wallet, first), (second_wallet, second)] = wallets();
+        let unsigned = two_signer_tx(&first, &second);
+
+        let mut signed_by_first = unsigned.clone();
+        signed_by_first.sign(&first_wallet, &first).unwrap();
+        let mut signed_by_second = unsigned.clone();
+        signed_by_second.sign(&second_wallet, &second).unwrap();
+
+        assert!(matches!(
+            signed_by_first.to_raw(),
+            Err(SigningError::MissingSignatures { signers }) if signers == vec![second.to_string()]
+        ));
+
+        let mut combined = signed_by_first.clone();
+        combined.merge_signatures(&signed_by_second).unwrap();
+        assert!(combined.missing_signatures().is_empty());
+
+        // each signature covers the amino document of its own signer
+        let expected_first = first_wallet
+            .sign_amino(&first, &unsigned.sign_doc(&first).unwrap())
+            .unwrap();
+        let expected_second = second_wallet
+            .sign_amino(&second, &unsigned.sign_doc(&second).unwrap())
+            .unwrap();
+
+        let raw = combined.to_raw().unwrap().to_bytes().unwrap();
+        let tx = tx::Tx::from_bytes(&raw).unwrap();
+        assert_eq!(
+            tx.signatures,
+            vec![
+                expected_first.as_ref().to_vec(),
+                expected_second.as_ref().to_vec()
+            ]
+        );
+        assert_eq!(tx.auth_info.signer_infos.len(), 2);
+        assert_eq!(tx.auth_info.signer_infos[0].sequence, 7);
+        assert_eq!(tx.auth_info.signer_infos[1].sequence, 0);
+        assert_eq!(tx.body.messages, unsigned.decoded_messages().unwrap());
+    }
+
+    #[test]
+    fn signatures_of_different_transactions_are_not_combined() {
+        let [(_, first), (second_wallet, second)] = wallets();
+        let mut tx = two_signer_tx(&first, &second);
+
+        let mut other = two_signer_tx(&first, &second);
+        other.memo = "something else".to_string();
+        other.sign(&second_wallet, &second).unwrap();
+
+        assert!(matches!(
+            tx.merge_signatures(&other),
+            Err(SigningError::MismatchedTransactions)
+        ));
+        assert_eq!(tx.missing_signatures().len(), 2);
+    }
+}
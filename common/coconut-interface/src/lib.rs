@@ -7,9 +7,14 @@ use getset::{CopyGetters, Getters};
 use serde::{Deserialize, Serialize};
 
 use error::CoconutInterfaceError;
+use nym_network_defaults::{TICKETBOOK_SIZE, TICKETBOOK_VOUCHER_INFO};
 
 pub use nym_coconut::*;
 
+// the most significant bit of the serialized number of parameters marks a ticket,
+// in which case the index of the ticket directly follows the epoch id
+const TICKET_FLAG: u32 = 1 << 31;
+
 #[derive(Debug, Serialize, Deserialize, Getters, CopyGetters, Clone, PartialEq, Eq)]
 pub struct Credential {
     #[getset(get = "pub")]
@@ -20,6 +25,10 @@ pub struct Credential {
     voucher_info: String,
     #[getset(get = "pub")]
     epoch_id: u64,
+    /// Index of the spent ticket if this credential represents only a part of a ticketbook.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub")]
+    ticket_index: Option<TicketIndex>,
 }
 impl Credential {
     pub fn new(
@@ -35,9 +44,34 @@ impl Credential {
             voucher_value,
             voucher_info,
             epoch_id,
+            ticket_index: None,
         }
     }
 
+    /// Creates a credential spending a single ticket of a ticketbook.
+    /// The `voucher_value` and `voucher_info` are the public attributes of the whole ticketbook.
+    pub fn new_ticket(
+        n_params: u32,
+        theta: Theta,
+        voucher_value: u64,
+        voucher_info: String,
+        epoch_id: u64,
+        ticket_index: TicketIndex,
+    ) -> Credential {
+        Credential {
+            n_params,
+            theta,
+            voucher_value,
+            voucher_info,
+            epoch_id,
+            ticket_index: Some(ticket_index),
+        }
+    }
+
+    pub fn is_ticket(&self) -> bool {
+        self.ticket_index.is_some()
+    }
+
     pub fn blinded_serial_number(&self) -> String {
         self.theta.blinded_serial_number_bs58()
     }
@@ -51,7 +85,17 @@ impl Credential {
             .has_blinded_serial_number(blinded_serial_number_bs58)?)
     }
 
+    /// The value that's being spent with this credential,
+    /// i.e. either the value of the whole voucher or of a single ticket.
     pub fn voucher_value(&self) -> u64 {
+        match self.ticket_index {
+            None => self.voucher_value,
+            Some(_) => self.voucher_value / TICKETBOOK_SIZE,
+        }
+    }
+
+    /// The value of the voucher that was originally issued, i.e. of the whole ticketbook in case of tickets.
+    pub fn issued_voucher_value(&self) -> u64 {
         self.voucher_value
     }
 
@@ -64,11 +108,37 @@ impl Credential {
         .iter()
         .map(hash_to_scalar)
         .collect::<Vec<Attribute>>();
-        nym_coconut::verify_credential(&params, verification_key, &self.theta, &public_attributes)
+
+        let is_ticketbook = self.voucher_info == TICKETBOOK_VOUCHER_INFO;
+        match self.ticket_index {
+            // ticketbooks can't be spent in full, as otherwise they could be spent again through their tickets
+            None if is_ticketbook => false,
+            None => nym_coconut::verify_credential(
+                &params,
+                verification_key,
+                &self.theta,
+                &public_attributes,
+            ),
+            Some(ticket_index) => {
+                is_ticketbook
+                    && ticket_index < TICKETBOOK_SIZE
+                    && nym_coconut::verify_bandwidth_ticket(
+                        &params,
+                        verification_key,
+                        &self.theta,
+                        &public_attributes,
+                        ticket_index,
+                    )
+            }
+        }
     }
 
     pub fn as_bytes(&self) -> Vec<u8> {
-        let n_params_bytes = self.n_params.to_be_bytes();
+        let n_params = match self.ticket_index {
+            None => self.n_params,
+            Some(_) => self.n_params | TICKET_FLAG,
+        };
+        let n_params_bytes = n_params.to_be_bytes();
         let theta_bytes = self.theta.to_bytes();
         let theta_bytes_len = theta_bytes.len();
         let voucher_value_bytes = self.voucher_value.to_be_bytes();
@@ -76,12 +146,15 @@ impl Credential {
         let voucher_info_bytes = self.voucher_info.as_bytes();
         let voucher_info_len = voucher_info_bytes.len();
 
-        let mut bytes = Vec::with_capacity(28 + theta_bytes_len + voucher_info_len);
+        let mut bytes = Vec::with_capacity(36 + theta_bytes_len + voucher_info_len);
         bytes.extend_from_slice(&n_params_bytes);
         bytes.extend_from_slice(&(theta_bytes_len as u64).to_be_bytes());
         bytes.extend_from_slice(&theta_bytes);
         bytes.extend_from_slice(&voucher_value_bytes);
         bytes.extend_from_slice(&epoch_id_bytes);
+        if let Some(ticket_index) = self.ticket_index {
+            bytes.extend_from_slice(&ticket_index.to_be_bytes());
+        }
         bytes.extend_from_slice(voucher_info_bytes);
 
         bytes
@@ -98,9 +171,12 @@ impl Credential {
 
         four_byte.copy_from_slice(&bytes[..4]);
         let n_params = u32::from_be_bytes(four_byte);
+        let is_ticket = n_params & TICKET_FLAG != 0;
+        let n_params = n_params & !TICKET_FLAG;
+        let fixed_len = if is_ticket { 36 } else { 28 };
         eight_byte.copy_from_slice(&bytes[4..12]);
         let theta_len = u64::from_be_bytes(eight_byte);
-        if bytes.len() < 28 + theta_len as usize {
+        if bytes.len() < fixed_len + theta_len as usize {
             return Err(CoconutError::Deserialization(String::from(
                 "To few bytes in credential",
             )));
@@ -111,7 +187,13 @@ impl Credential {
         let voucher_value = u64::from_be_bytes(eight_byte);
         eight_byte.copy_from_slice(&bytes[20 + theta_len as usize..28 + theta_len as usize]);
         let epoch_id = u64::from_be_bytes(eight_byte);
-        let voucher_info = String::from_utf8(bytes[28 + theta_len as usize..].to_vec())
+        let ticket_index = if is_ticket {
+            eight_byte.copy_from_slice(&bytes[28 + theta_len as usize..36 + theta_len as usize]);
+            Some(u64::from_be_bytes(eight_byte))
+        } else {
+            None
+        };
+        let voucher_info = String::from_utf8(bytes[fixed_len + theta_len as usize..].to_vec())
             .map_err(|e| CoconutError::Deserialization(e.to_string()))?;
 
         Ok(Credential {
@@ -120,6 +202,7 @@ impl Credential {
             voucher_value,
             voucher_info,
             epoch_id,
+            ticket_index,
         })
     }
 }
@@ -175,11 +258,33 @@ mod tests {
             binding_number,
         )
         .unwrap();
-        let credential = Credential::new(4, theta, voucher_value, voucher_info, 42);
+        let credential = Credential::new(4, theta, voucher_value, voucher_info.clone(), 42);
 
         let serialized_credential = credential.as_bytes();
         let deserialized_credential = Credential::from_bytes(&serialized_credential).unwrap();
 
         assert_eq!(credential, deserialized_credential);
+
+        let theta = prove_bandwidth_ticket(
+            &params,
+            &verification_key,
+            &signature,
+            serial_number,
+            binding_number,
+            7,
+        )
+        .unwrap();
+        let ticket = Credential::new_ticket(4, theta, voucher_value, voucher_info, 42, 7);
+
+        let serialized_ticket = ticket.as_bytes();
+        let deserialized_ticket = Credential::from_bytes(&serialized_ticket).unwrap();
+
+        assert_eq!(ticket, deserialized_ticket);
+        assert_eq!(deserialized_ticket.ticket_index(), Some(7));
+        assert_eq!(*deserialized_ticket.n_params(), 4);
+        assert_eq!(
+            deserialized_ticket.voucher_value(),
+            voucher_value / TICKETBOOK_SIZE
+        );
     }
 }
@@ -11,6 +11,9 @@ pub enum ContextError {
     #[error("failed to parse mnemonic - {0}")]
     Bip39Error(#[from] bip39::Error),
 
+    #[error("failed to use the ledger device - {0}")]
+    LedgerError(#[from] nym_validator_client::signing::ledger::LedgerSignerError),
+
     // there are lots of error that can occur in the nyxd client, so just pass through their display details
     // TODO: improve this to return known errors
     #[error("failed to create client - {0}")]
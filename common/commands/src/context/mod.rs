@@ -2,22 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::context::errors::ContextError;
+use crate::context::signer::CliSigner;
 use nym_network_defaults::{
     setup_env,
     var_names::{MIXNET_CONTRACT_ADDRESS, NYM_API, NYXD, VESTING_CONTRACT_ADDRESS},
     NymNetworkDetails,
 };
 pub use nym_validator_client::nym_api::Client as NymApiClient;
-use nym_validator_client::nyxd::{
-    self, AccountId, DirectSigningNyxdClient, NyxdClient, QueryNyxdClient,
-};
+use nym_validator_client::nyxd::{self, AccountId, NyxdClient, QueryNyxdClient, SigningNyxdClient};
+use nym_validator_client::signing::direct_wallet::DirectSecp256k1HdWallet;
+use nym_validator_client::signing::ledger::LedgerSigner;
 use tap::prelude::*;
 
 pub mod errors;
+pub mod signer;
 
-pub type SigningClient = nym_validator_client::nyxd::NyxdClient<DirectSigningNyxdClient>;
+pub type SigningClient = nym_validator_client::nyxd::NyxdClient<SigningNyxdClient<CliSigner>>;
 pub type QueryClient = nym_validator_client::nyxd::NyxdClient<QueryNyxdClient>;
-pub type SigningClientWithNyxd = nym_validator_client::Client<DirectSigningNyxdClient>;
+pub type SigningClientWithNyxd = nym_validator_client::Client<SigningNyxdClient<CliSigner>>;
 pub type QueryClientWithNyxd = nym_validator_client::Client<QueryNyxdClient>;
 
 #[derive(Debug)]
@@ -26,6 +28,7 @@ pub struct ClientArgs {
     pub nyxd_url: Option<String>,
     pub nym_api_url: Option<String>,
     pub mnemonic: Option<bip39::Mnemonic>,
+    pub ledger: bool,
     pub mixnet_contract_address: Option<AccountId>,
     pub vesting_contract_address: Option<AccountId>,
 }
@@ -55,12 +58,15 @@ pub fn get_network_details(args: &ClientArgs) -> Result<NymNetworkDetails, Conte
     Ok(NymNetworkDetails::new_from_env())
 }
 
-pub fn create_signing_client(
+pub fn create_signer(
     args: ClientArgs,
     network_details: &NymNetworkDetails,
-) -> Result<SigningClient, ContextError> {
-    let client_config = nyxd::Config::try_from_nym_network_details(network_details)
-        .tap_err(|err| log::error!("Failed to get client config - {err}"))?;
+) -> Result<CliSigner, ContextError> {
+    let prefix = &network_details.chain_details.bech32_account_prefix;
+
+    if args.ledger {
+        return Ok(CliSigner::Ledger(LedgerSigner::new(prefix)?));
+    }
 
     // get mnemonic
     let mnemonic = match std::env::var("MNEMONIC") {
@@ -72,6 +78,20 @@ pub fn create_signing_client(
         },
     };
 
+    Ok(CliSigner::Mnemonic(DirectSecp256k1HdWallet::from_mnemonic(
+        prefix, mnemonic,
+    )))
+}
+
+pub fn create_signing_client(
+    args: ClientArgs,
+    network_details: &NymNetworkDetails,
+) -> Result<SigningClient, ContextError> {
+    let client_config = nyxd::Config::try_from_nym_network_details(network_details)
+        .tap_err(|err| log::error!("Failed to get client config - {err}"))?;
+
+    let signer = create_signer(args, network_details)?;
+
     let nyxd_url = network_details
         .endpoints
         .first()
@@ -79,7 +99,7 @@ pub fn create_signing_client(
         .nyxd_url
         .as_str();
 
-    match NyxdClient::connect_with_mnemonic(client_config, nyxd_url, mnemonic, None) {
+    match NyxdClient::connect_with_signer(client_config, nyxd_url, signer, None) {
         Ok(client) => Ok(client),
         Err(e) => Err(ContextError::NyxdError(format!("{e}"))),
     }
@@ -111,17 +131,9 @@ pub fn create_signing_client_with_nym_api(
     let client_config = nym_validator_client::Config::try_from_nym_network_details(network_details)
         .tap_err(|err| log::error!("Failed to get client config - {err}"))?;
 
-    // get mnemonic
-    let mnemonic = match std::env::var("MNEMONIC") {
-        Ok(value) => bip39::Mnemonic::parse(value)?,
-        // env var MNEMONIC is not present, so try to fall back to arg --mnemonic ...
-        Err(_) => match args.mnemonic {
-            Some(value) => value,
-            None => return Err(ContextError::MnemonicNotProvided), // no env var or arg provided
-        },
-    };
+    let signer = create_signer(args, network_details)?;
 
-    match nym_validator_client::client::Client::new_signing(client_config, mnemonic) {
+    match nym_validator_client::client::Client::new_signing_with_signer(client_config, signer) {
         Ok(client) => Ok(client),
         Err(e) => Err(ContextError::NyxdError(format!("{e}"))),
     }
@@ -0,0 +1,68 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use cosmrs::tx::{self, SignDoc};
+use nym_validator_client::nyxd::error::NyxdError;
+use nym_validator_client::signing::amino::StdSignDoc;
+use nym_validator_client::signing::direct_wallet::DirectSecp256k1HdWallet;
+use nym_validator_client::signing::ledger::LedgerSigner;
+use nym_validator_client::signing::signer::{OfflineSigner, Signature, SignerType};
+use nym_validator_client::signing::AccountData;
+
+/// Signer used by the transaction commands. The account is either derived from a mnemonic
+/// or is kept on a Ledger device, so that the mnemonic never has to be exposed.
+pub enum CliSigner {
+    Mnemonic(DirectSecp256k1HdWallet),
+    Ledger(LedgerSigner),
+}
+
+impl OfflineSigner for CliSigner {
+    type Error = NyxdError;
+
+    fn preferred_signer_type(&self) -> SignerType {
+        match self {
+            CliSigner::Mnemonic(wallet) => wallet.preferred_signer_type(),
+            CliSigner::Ledger(ledger) => ledger.preferred_signer_type(),
+        }
+    }
+
+    fn get_accounts(&self) -> Result<Vec<AccountData>, Self::Error> {
+        match self {
+            CliSigner::Mnemonic(wallet) => Ok(wallet.get_accounts()?),
+            CliSigner::Ledger(ledger) => Ok(ledger.get_accounts()?),
+        }
+    }
+
+    fn sign_raw_with_account<M: AsRef<[u8]>>(
+        &self,
+        signer: &AccountData,
+        message: M,
+    ) -> Result<Signature, Self::Error> {
+        match self {
+            CliSigner::Mnemonic(wallet) => Ok(wallet.sign_raw_with_account(signer, message)?),
+            CliSigner::Ledger(ledger) => Ok(ledger.sign_raw_with_account(signer, message)?),
+        }
+    }
+
+    fn sign_direct_with_account(
+        &self,
+        signer: &AccountData,
+        sign_doc: SignDoc,
+    ) -> Result<tx::Raw, Self::Error> {
+        match self {
+            CliSigner::Mnemonic(wallet) => Ok(wallet.sign_direct_with_account(signer, sign_doc)?),
+            CliSigner::Ledger(ledger) => Ok(ledger.sign_direct_with_account(signer, sign_doc)?),
+        }
+    }
+
+    fn sign_amino_with_account(
+        &self,
+        signer: &AccountData,
+        sign_doc: &StdSignDoc,
+    ) -> Result<Signature, Self::Error> {
+        match self {
+            CliSigner::Mnemonic(wallet) => Ok(wallet.sign_amino_with_account(signer, sign_doc)?),
+            CliSigner::Ledger(ledger) => Ok(ledger.sign_amino_with_account(signer, sign_doc)?),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use clap::{Args, Subcommand};
+
+pub mod delete;
+pub mod name_register_sign_payload;
+pub mod register;
+pub mod renew;
+pub mod transfer;
+
+#[derive(Debug, Args)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_required = true)]
+pub struct MixnetOperatorsName {
+    #[clap(subcommand)]
+    pub command: MixnetOperatorsNameCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MixnetOperatorsNameCommands {
+    /// Register a name pointing to a nym address
+    Register(register::Args),
+    /// Delete a registered name
+    Delete(delete::Args),
+    /// Extend the registration of a name by another registration period
+    Renew(renew::Args),
+    /// Transfer the ownership of a name to another account
+    Transfer(transfer::Args),
+    /// Create base58-encoded payload that has to be signed by the identity key of the nym address
+    CreateNameRegisterSignPayload(name_register_sign_payload::Args),
+}
@@ -0,0 +1,62 @@
+use clap::Parser;
+use cosmwasm_std::Coin;
+use nym_bin_common::output_format::OutputFormat;
+use nym_name_service_common::{construct_name_register_sign_payload, Address, NymName};
+use nym_validator_client::nyxd::traits::NameServiceQueryClient;
+
+use crate::context::SigningClient;
+use crate::utils::{account_id_to_cw_addr, DataWrapper};
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long)]
+    pub name: String,
+
+    #[clap(long)]
+    pub nym_address: String,
+
+    /// Deposit to be made to the name service, in curent DENOMINATION (e.g. 'unym')
+    #[clap(long)]
+    pub deposit: u128,
+
+    #[clap(short, long, default_value_t = OutputFormat::default())]
+    output: OutputFormat,
+}
+
+pub async fn create_payload(args: Args, client: SigningClient) {
+    let denom = client.current_chain_details().mix_denom.base.as_str();
+    let deposit = Coin::new(args.deposit, denom);
+
+    let name = match NymName::new(&args.name) {
+        Ok(name) => name,
+        Err(_) => {
+            eprint!("{} is not a valid name", args.name);
+            return;
+        }
+    };
+
+    let nonce = match client
+        .get_name_service_signing_nonce(client.address())
+        .await
+    {
+        Ok(nonce) => nonce,
+        Err(err) => {
+            eprint!(
+                "failed to query for the signing nonce of {}: {err}",
+                client.address()
+            );
+            return;
+        }
+    };
+
+    let address = account_id_to_cw_addr(client.address());
+    let payload = construct_name_register_sign_payload(
+        nonce,
+        address,
+        deposit,
+        name,
+        Address::new(&args.nym_address),
+    );
+    let wrapper = DataWrapper::new(payload.to_base58_string().unwrap());
+    println!("{}", args.output.format(&wrapper))
+}
@@ -0,0 +1,42 @@
+use clap::Parser;
+use log::info;
+use nym_contracts_common::signing::MessageSignature;
+use nym_name_service_common::{Address, NymName};
+use nym_validator_client::nyxd::{traits::NameServiceSigningClient, Coin};
+
+use crate::context::SigningClient;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long)]
+    pub name: String,
+
+    #[clap(long)]
+    pub nym_address: String,
+
+    /// Signature of the payload created with `create-name-register-sign-payload`, made with
+    /// the identity key of the nym address
+    #[clap(long)]
+    pub signature: MessageSignature,
+
+    /// Deposit to be made to the name service, in curent DENOMINATION (e.g. 'unym')
+    #[clap(long)]
+    pub deposit: u128,
+}
+
+pub async fn register(args: Args, client: SigningClient) {
+    info!("Registering name {}", args.name);
+
+    let name = NymName::new(&args.name).expect("Invalid name");
+    let nym_address = Address::new(&args.nym_address);
+
+    let denom = client.current_chain_details().mix_denom.base.as_str();
+    let deposit = Coin::new(args.deposit, denom);
+
+    let res = client
+        .register_name(name, nym_address, args.signature, deposit, None)
+        .await
+        .expect("Failed to register name");
+
+    info!("Registered name: {res:?}");
+}
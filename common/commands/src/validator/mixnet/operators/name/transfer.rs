@@ -0,0 +1,30 @@
+use clap::Parser;
+use log::info;
+use nym_name_service_common::NameId;
+use nym_validator_client::nyxd::{traits::NameServiceSigningClient, AccountId};
+
+use crate::context::SigningClient;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long)]
+    pub id: NameId,
+
+    /// The account that is going to become the new owner of the name
+    #[clap(long)]
+    pub new_owner: AccountId,
+}
+
+pub async fn transfer(args: Args, client: SigningClient) {
+    info!(
+        "Transferring name with id {} to {}",
+        args.id, args.new_owner
+    );
+
+    let res = client
+        .transfer_name_ownership(args.id, args.new_owner, None)
+        .await
+        .expect("Failed to transfer name");
+
+    info!("Transferred: {res:?}");
+}
@@ -5,6 +5,7 @@ use clap::{Args, Subcommand};
 
 pub mod query_all_gateways;
 pub mod query_all_mixnodes;
+pub mod query_all_names;
 pub mod query_all_service_providers;
 
 #[derive(Debug, Args)]
@@ -22,4 +23,6 @@ pub enum MixnetQueryCommands {
     Gateways(query_all_gateways::Args),
     /// Query announced service-providers
     ServiceProviders(query_all_service_providers::Args),
+    /// Query registered names
+    Names(query_all_names::Args),
 }
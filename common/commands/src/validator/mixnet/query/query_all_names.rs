@@ -0,0 +1,47 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Parser;
+use comfy_table::Table;
+use nym_validator_client::nyxd::traits::NameServiceQueryClient;
+
+use crate::context::QueryClientWithNyxd;
+use crate::utils::show_error;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(value_parser)]
+    #[clap(help = "Optionally, the name to display")]
+    pub name: Option<String>,
+}
+
+pub async fn query(args: Args, client: &QueryClientWithNyxd) {
+    match client.get_all_names().await {
+        Ok(res) => {
+            if let Some(name) = args.name {
+                let entry = res.iter().find(|entry| entry.name.name.as_str() == name);
+                println!(
+                    "{}",
+                    ::serde_json::to_string_pretty(&entry).expect("json formatting error")
+                );
+            } else {
+                let mut table = Table::new();
+
+                table.set_header(vec!["Name Id", "Name", "Nym Address", "Owner", "Expires"]);
+                for entry in res {
+                    table.add_row(vec![
+                        entry.name_id.to_string(),
+                        entry.name.name.to_string(),
+                        entry.name.address.to_string(),
+                        entry.name.owner.to_string(),
+                        entry.name.expires_at.to_string(),
+                    ]);
+                }
+
+                println!("The registered names are:");
+                println!("{table}");
+            }
+        }
+        Err(e) => show_error(e),
+    }
+}
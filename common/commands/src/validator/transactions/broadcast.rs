@@ -0,0 +1,34 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::QueryClient;
+use crate::utils::show_error;
+use crate::validator::transactions::load_unsigned_tx;
+use clap::Parser;
+use log::info;
+use serde_json::json;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long, help = "The fully signed transaction file")]
+    pub tx_file: PathBuf,
+}
+
+pub async fn broadcast(args: Args, client: &QueryClient) {
+    let signed_tx = load_unsigned_tx(&args.tx_file);
+
+    info!(
+        "Broadcasting transaction with {} message(s)...",
+        signed_tx.messages.len()
+    );
+
+    match client.broadcast_signed_tx(&signed_tx).await {
+        Ok(res) => {
+            info!("Broadcast result: {}", json!(res));
+            println!("Transaction result code: {}", &res.tx_result.code.value());
+            println!("Transaction hash: {}", &res.hash);
+        }
+        Err(e) => show_error(e),
+    }
+}
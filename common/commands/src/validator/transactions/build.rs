@@ -0,0 +1,252 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::QueryClient;
+use crate::validator::mixnet::delegators::rewards::claim_delegator_reward;
+use crate::validator::mixnet::delegators::{delegate_to_mixnode, undelegate_from_mixnode};
+use crate::validator::transactions::{load_unsigned_tx, save_unsigned_tx};
+use clap::{Parser, Subcommand};
+use cosmrs::tx::Msg;
+use cosmrs::{cosmwasm, AccountId, Any};
+use log::{error, info};
+use nym_mixnet_contract_common::{ExecuteMsg as MixnetExecuteMsg, MixId};
+use nym_validator_client::nyxd::fee::DEFAULT_SIMULATED_GAS_MULTIPLIER;
+use nym_validator_client::nyxd::traits::MixnetQueryClient;
+use nym_validator_client::nyxd::{tx, Coin, Gas, GasAdjustable, GasPrice, MsgSend};
+use nym_validator_client::signing::unsigned_tx::UnsignedTx;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(
+        long,
+        help = "The unsigned transaction file. If it already exists, the message is appended to it"
+    )]
+    pub tx_file: PathBuf,
+
+    #[clap(
+        long,
+        help = "The address of the account sending the message. It will have to sign the transaction"
+    )]
+    pub from: AccountId,
+
+    #[clap(long, help = "Set the memo of the transaction")]
+    pub memo: Option<String>,
+
+    #[clap(
+        long,
+        help = "Gas limit of the whole transaction. If not set, it's estimated by simulating the transaction"
+    )]
+    pub gas_limit: Option<u64>,
+
+    #[clap(subcommand)]
+    pub message: BuildMessage,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BuildMessage {
+    /// Send tokens to another account
+    Send(SendArgs),
+    /// Delegate to a mixnode
+    DelegateToMixnode(delegate_to_mixnode::Args),
+    /// Undelegate from a mixnode
+    UndelegateFromMixnode(undelegate_from_mixnode::Args),
+    /// Claim rewards accumulated during the delegation
+    ClaimDelegatorReward(claim_delegator_reward::Args),
+    /// Execute an arbitrary contract method
+    ExecuteContract(ExecuteContractArgs),
+}
+
+#[derive(Debug, Parser)]
+pub struct SendArgs {
+    #[clap(value_parser, help = "The recipient account address")]
+    pub recipient: AccountId,
+
+    #[clap(
+        value_parser,
+        help = "Amount to transfer in micro denomination (e.g. unym or unyx)"
+    )]
+    pub amount: u128,
+
+    #[clap(long, help = "Override the denomination")]
+    pub denom: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct ExecuteContractArgs {
+    #[clap(value_parser)]
+    #[clap(help = "The address of contract to execute")]
+    pub contract_address: AccountId,
+
+    #[clap(value_parser)]
+    #[clap(help = "JSON encoded method arguments")]
+    pub json_args: String,
+
+    #[clap(
+        long,
+        requires = "funds_denom",
+        help = "Amount to supply as funds in micro denomination (e.g. unym or unyx)"
+    )]
+    pub funds: Option<u128>,
+
+    #[clap(long, requires = "funds", help = "Set the denomination for the funds")]
+    pub funds_denom: Option<String>,
+}
+
+fn execute_contract_msg<M: Serialize>(
+    sender: &AccountId,
+    contract: &AccountId,
+    msg: &M,
+    funds: Vec<Coin>,
+) -> Any {
+    cosmwasm::MsgExecuteContract {
+        sender: sender.clone(),
+        contract: contract.clone(),
+        msg: serde_json::to_vec(msg).expect("failed to serialize the contract message"),
+        funds: funds.into_iter().map(Into::into).collect(),
+    }
+    .to_any()
+    .expect("failed to encode the contract message")
+}
+
+async fn resolve_mix_id(
+    client: &QueryClient,
+    mix_id: Option<MixId>,
+    identity_key: Option<String>,
+) -> MixId {
+    match mix_id {
+        Some(mix_id) => mix_id,
+        None => {
+            let identity_key =
+                identity_key.expect("either mix_id or mix_identity has to be specified");
+            client
+                .get_mixnode_details_by_identity(identity_key)
+                .await
+                .expect("contract query failed")
+                .expect("mixnode with the specified identity doesnt exist")
+                .mix_id()
+        }
+    }
+}
+
+async fn build_message(from: &AccountId, message: BuildMessage, client: &QueryClient) -> Any {
+    let denom = client.current_chain_details().mix_denom.base.clone();
+    let mixnet_contract = client.mixnet_contract_address();
+
+    match message {
+        BuildMessage::Send(args) => MsgSend {
+            from_address: from.clone(),
+            to_address: args.recipient,
+            amount: vec![Coin::new(args.amount, args.denom.unwrap_or(denom)).into()],
+        }
+        .to_any()
+        .expect("failed to encode the send message"),
+        BuildMessage::DelegateToMixnode(args) => {
+            let mix_id = resolve_mix_id(client, args.mix_id, args.identity_key).await;
+            execute_contract_msg(
+                from,
+                mixnet_contract,
+                &MixnetExecuteMsg::DelegateToMixnode { mix_id },
+                vec![Coin::new(args.amount, denom)],
+            )
+        }
+        BuildMessage::UndelegateFromMixnode(args) => {
+            let mix_id = resolve_mix_id(client, args.mix_id, args.identity_key).await;
+            execute_contract_msg(
+                from,
+                mixnet_contract,
+                &MixnetExecuteMsg::UndelegateFromMixnode { mix_id },
+                vec![],
+            )
+        }
+        BuildMessage::ClaimDelegatorReward(args) => {
+            let mix_id = resolve_mix_id(client, args.mix_id, args.identity_key).await;
+            execute_contract_msg(
+                from,
+                mixnet_contract,
+                &MixnetExecuteMsg::WithdrawDelegatorReward { mix_id },
+                vec![],
+            )
+        }
+        BuildMessage::ExecuteContract(args) => {
+            let json_args: Value =
+                serde_json::from_str(&args.json_args).expect("Unable to parse JSON args");
+            let funds = match args.funds {
+                Some(funds) => vec![Coin::new(
+                    funds,
+                    args.funds_denom.expect("denom for funds not set"),
+                )],
+                None => vec![],
+            };
+            execute_contract_msg(from, &args.contract_address, &json_args, funds)
+        }
+    }
+}
+
+pub async fn build(args: Args, client: &QueryClient) {
+    let mut unsigned_tx = if args.tx_file.exists() {
+        load_unsigned_tx(&args.tx_file)
+    } else {
+        let chain_id = client
+            .get_chain_id()
+            .await
+            .expect("failed to obtain the chain id");
+        UnsignedTx::new(chain_id.to_string(), String::new())
+    };
+
+    if unsigned_tx.is_signed() {
+        error!("the transaction has already been signed and can no longer be modified");
+        return;
+    }
+
+    let message = build_message(&args.from, args.message, client).await;
+
+    if !unsigned_tx.has_signer(&args.from) {
+        let account = client
+            .get_account_sequence(&args.from)
+            .await
+            .expect("failed to obtain the account details - does the account exist?");
+        unsigned_tx
+            .add_signer(&args.from, account.account_number, account.sequence)
+            .expect("failed to add the signer");
+    }
+    unsigned_tx
+        .push_message(message)
+        .expect("failed to add the message");
+    if let Some(memo) = args.memo {
+        unsigned_tx.memo = memo;
+    }
+
+    let gas_limit = match args.gas_limit {
+        Some(gas_limit) => Gas::from(gas_limit),
+        None => client
+            .simulate_unsigned_tx(&unsigned_tx)
+            .await
+            .expect("failed to simulate the transaction")
+            .gas_info
+            .expect("the simulation has not returned the gas estimation")
+            .gas_used
+            .adjust_gas(DEFAULT_SIMULATED_GAS_MULTIPLIER),
+    };
+    let gas_price =
+        GasPrice::new_with_default_price(&client.current_chain_details().mix_denom.base)
+            .expect("failed to determine the gas price");
+    let fee = tx::Fee::from_amount_and_gas(&gas_price * gas_limit, gas_limit);
+    unsigned_tx.set_fee(&fee).expect("failed to set the fee");
+
+    save_unsigned_tx(&args.tx_file, &unsigned_tx);
+
+    info!(
+        "The transaction now contains {} message(s) and requires signatures of: {}",
+        unsigned_tx.messages.len(),
+        unsigned_tx.missing_signatures().join(", ")
+    );
+    println!(
+        "Fee: {} {} (gas limit: {})",
+        fee.amount[0].amount,
+        fee.amount[0].denom,
+        fee.gas_limit.value()
+    );
+}
@@ -2,9 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use clap::{Args, Subcommand};
+use nym_validator_client::signing::unsigned_tx::UnsignedTx;
+use std::path::Path;
 
+pub mod broadcast;
+pub mod build;
 pub mod get_transaction;
+pub mod multisign;
 pub mod query_transactions;
+pub mod sign;
 
 #[derive(Debug, Args)]
 #[clap(args_conflicts_with_subcommands = true, subcommand_required = true)]
@@ -19,4 +25,22 @@ pub enum TransactionsCommands {
     Get(crate::validator::transactions::get_transaction::Args),
     /// Query for transactions
     Query(crate::validator::transactions::query_transactions::Args),
+    /// Append a message to an unsigned transaction file, creating it if it doesn't exist yet
+    Build(crate::validator::transactions::build::Args),
+    /// Sign an unsigned transaction file offline with a mnemonic or a Ledger device
+    Sign(crate::validator::transactions::sign::Args),
+    /// Combine signatures from separately signed copies of the same transaction
+    Multisign(crate::validator::transactions::multisign::Args),
+    /// Broadcast a transaction once it has been signed by all of its signers
+    Broadcast(crate::validator::transactions::broadcast::Args),
+}
+
+pub(crate) fn load_unsigned_tx(path: &Path) -> UnsignedTx {
+    let raw = std::fs::read_to_string(path).expect("failed to read the transaction file");
+    serde_json::from_str(&raw).expect("the transaction file is malformed")
+}
+
+pub(crate) fn save_unsigned_tx(path: &Path, tx: &UnsignedTx) {
+    let raw = serde_json::to_string_pretty(tx).expect("failed to serialize the transaction");
+    std::fs::write(path, raw).expect("failed to write the transaction file")
 }
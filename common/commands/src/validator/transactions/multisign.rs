@@ -0,0 +1,50 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::validator::transactions::{load_unsigned_tx, save_unsigned_tx};
+use clap::Parser;
+use log::info;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long, help = "The transaction file the signatures are added to")]
+    pub tx_file: PathBuf,
+
+    #[clap(
+        value_parser,
+        required = true,
+        help = "Files with copies of the same transaction signed by other signers"
+    )]
+    pub signed: Vec<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Write the combined transaction to this file instead of updating the original one"
+    )]
+    pub output: Option<PathBuf>,
+}
+
+pub fn multisign(args: Args) {
+    let mut unsigned_tx = load_unsigned_tx(&args.tx_file);
+
+    for path in &args.signed {
+        let signed = load_unsigned_tx(path);
+        unsigned_tx
+            .merge_signatures(&signed)
+            .unwrap_or_else(|err| panic!("failed to combine signatures from {path:?}: {err}"));
+    }
+
+    let output = args.output.unwrap_or(args.tx_file);
+    save_unsigned_tx(&output, &unsigned_tx);
+
+    let missing = unsigned_tx.missing_signatures();
+    if missing.is_empty() {
+        info!("The transaction is fully signed and can be broadcast");
+    } else {
+        info!(
+            "The transaction is still missing signatures of: {}",
+            missing.join(", ")
+        );
+    }
+}
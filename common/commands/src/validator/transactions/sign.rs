@@ -0,0 +1,72 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::signer::CliSigner;
+use crate::validator::transactions::{load_unsigned_tx, save_unsigned_tx};
+use clap::Parser;
+use log::{error, info};
+use nym_validator_client::signing::signer::OfflineSigner;
+use serde_json::Value;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long, help = "The unsigned transaction file")]
+    pub tx_file: PathBuf,
+
+    #[clap(
+        long,
+        help = "Write the signed transaction to this file instead of updating the original one"
+    )]
+    pub output: Option<PathBuf>,
+}
+
+pub fn sign(args: Args, signer: CliSigner) {
+    let mut unsigned_tx = load_unsigned_tx(&args.tx_file);
+
+    let accounts = signer
+        .get_accounts()
+        .expect("failed to obtain the accounts of the signer");
+
+    let mut signed = false;
+    for account in accounts {
+        if !unsigned_tx.has_signer(&account.address) {
+            continue;
+        }
+
+        // show exactly what is about to be signed, so that it could be reviewed (and compared with the Ledger display)
+        let sign_doc = unsigned_tx
+            .sign_doc(&account.address)
+            .expect("failed to construct the sign doc");
+        let sign_doc: Value =
+            serde_json::from_str(&sign_doc.to_json()).expect("the sign doc is not valid json");
+        println!("Signing the following document with {}:", account.address);
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&sign_doc).expect("failed to display the sign doc")
+        );
+
+        unsigned_tx
+            .sign(&signer, &account.address)
+            .expect("failed to sign the transaction");
+        signed = true;
+    }
+
+    if !signed {
+        error!("none of the accounts of the signer is required to sign this transaction");
+        return;
+    }
+
+    let output = args.output.unwrap_or(args.tx_file);
+    save_unsigned_tx(&output, &unsigned_tx);
+
+    let missing = unsigned_tx.missing_signatures();
+    if missing.is_empty() {
+        info!("The transaction is fully signed and can be broadcast");
+    } else {
+        info!(
+            "The transaction is still missing signatures of: {}",
+            missing.join(", ")
+        );
+    }
+}
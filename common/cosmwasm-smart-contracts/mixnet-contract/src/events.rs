@@ -99,6 +99,53 @@ impl ToString for MixnetEventType {
     }
 }
 
+impl MixnetEventType {
+    /// Attempts to recover the event type from its emitted (versioned) name, e.g. `"v2_delegation"`.
+    pub fn from_event_name(name: &str) -> Option<Self> {
+        let event_name = name.strip_prefix(EVENT_VERSION_PREFIX)?;
+
+        Some(match event_name {
+            "mixnode_bonding" => MixnetEventType::MixnodeBonding,
+            "pending_pledge_increase" => MixnetEventType::PendingPledgeIncrease,
+            "pledge_increase" => MixnetEventType::PledgeIncrease,
+            "pending_pledge_decrease" => MixnetEventType::PendingPledgeDecrease,
+            "pledge_decrease" => MixnetEventType::PledgeDecrease,
+            "gateway_bonding" => MixnetEventType::GatewayBonding,
+            "gateway_unbonding" => MixnetEventType::GatewayUnbonding,
+            "pending_mixnode_unbonding" => MixnetEventType::PendingMixnodeUnbonding,
+            "mixnode_config_update" => MixnetEventType::MixnodeConfigUpdate,
+            "mixnode_unbonding" => MixnetEventType::MixnodeUnbonding,
+            "pending_mixnode_cost_params_update" => MixnetEventType::PendingMixnodeCostParamsUpdate,
+            "mixnode_cost_params_update" => MixnetEventType::MixnodeCostParamsUpdate,
+            "mix_rewarding" => MixnetEventType::MixnodeRewarding,
+            "withdraw_delegator_reward" => MixnetEventType::WithdrawDelegatorReward,
+            "withdraw_operator_reward" => MixnetEventType::WithdrawOperatorReward,
+            "pending_active_set_update" => MixnetEventType::PendingActiveSetUpdate,
+            "active_set_update" => MixnetEventType::ActiveSetUpdate,
+            "pending_interval_rewarding_params_update" => {
+                MixnetEventType::PendingIntervalRewardingParamsUpdate
+            }
+            "interval_rewarding_params_update" => MixnetEventType::IntervalRewardingParamsUpdate,
+            "pending_delegation" => MixnetEventType::PendingDelegation,
+            "pending_undelegation" => MixnetEventType::PendingUndelegation,
+            "delegation" => MixnetEventType::Delegation,
+            "undelegation" => MixnetEventType::Undelegation,
+            "settings_update" => MixnetEventType::ContractSettingsUpdate,
+            "rewarding_validator_address_update" => MixnetEventType::RewardingValidatorUpdate,
+            "beginning_epoch_transition" => MixnetEventType::BeginEpochTransition,
+            "advance_epoch" => MixnetEventType::AdvanceEpoch,
+            "execute_pending_epoch_events" => MixnetEventType::ExecutePendingEpochEvents,
+            "execute_pending_interval_events" => MixnetEventType::ExecutePendingIntervalEvents,
+            "reconcile_pending_events" => MixnetEventType::ReconcilePendingEvents,
+            "pending_interval_config_update" => MixnetEventType::PendingIntervalConfigUpdate,
+            "interval_config_update" => MixnetEventType::IntervalConfigUpdate,
+            "delegation_on_unbonding_node" => MixnetEventType::DelegationOnUnbonding,
+            "gateway_config_update" => MixnetEventType::GatewayConfigUpdate,
+            _ => return None,
+        })
+    }
+}
+
 // attributes that are used in multiple places
 pub const OWNER_KEY: &str = "owner";
 pub const AMOUNT_KEY: &str = "amount";
@@ -600,3 +647,57 @@ pub fn new_pending_interval_config_update_event(
             approximate_time_remaining_secs.to_string(),
         )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_names_can_be_parsed_back() {
+        let all = vec![
+            MixnetEventType::MixnodeBonding,
+            MixnetEventType::PendingPledgeIncrease,
+            MixnetEventType::PledgeIncrease,
+            MixnetEventType::PendingPledgeDecrease,
+            MixnetEventType::PledgeDecrease,
+            MixnetEventType::GatewayBonding,
+            MixnetEventType::GatewayUnbonding,
+            MixnetEventType::PendingMixnodeUnbonding,
+            MixnetEventType::MixnodeConfigUpdate,
+            MixnetEventType::MixnodeUnbonding,
+            MixnetEventType::PendingMixnodeCostParamsUpdate,
+            MixnetEventType::MixnodeCostParamsUpdate,
+            MixnetEventType::MixnodeRewarding,
+            MixnetEventType::WithdrawDelegatorReward,
+            MixnetEventType::WithdrawOperatorReward,
+            MixnetEventType::PendingActiveSetUpdate,
+            MixnetEventType::ActiveSetUpdate,
+            MixnetEventType::PendingIntervalRewardingParamsUpdate,
+            MixnetEventType::IntervalRewardingParamsUpdate,
+            MixnetEventType::PendingDelegation,
+            MixnetEventType::PendingUndelegation,
+            MixnetEventType::Delegation,
+            MixnetEventType::Undelegation,
+            MixnetEventType::ContractSettingsUpdate,
+            MixnetEventType::RewardingValidatorUpdate,
+            MixnetEventType::BeginEpochTransition,
+            MixnetEventType::AdvanceEpoch,
+            MixnetEventType::ExecutePendingEpochEvents,
+            MixnetEventType::ExecutePendingIntervalEvents,
+            MixnetEventType::ReconcilePendingEvents,
+            MixnetEventType::PendingIntervalConfigUpdate,
+            MixnetEventType::IntervalConfigUpdate,
+            MixnetEventType::DelegationOnUnbonding,
+            MixnetEventType::GatewayConfigUpdate,
+        ];
+
+        for typ in all {
+            let name = typ.to_string();
+            let parsed = MixnetEventType::from_event_name(&name).unwrap();
+            assert_eq!(parsed.to_string(), name);
+        }
+
+        assert!(MixnetEventType::from_event_name("delegation").is_none());
+        assert!(MixnetEventType::from_event_name("v2_foomp").is_none());
+    }
+}
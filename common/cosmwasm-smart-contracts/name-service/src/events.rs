@@ -1,4 +1,4 @@
-use cosmwasm_std::{Coin, Event};
+use cosmwasm_std::{Addr, Coin, Event};
 
 use crate::{NameId, RegisteredName};
 
@@ -6,6 +6,9 @@ pub enum NameEventType {
     Register,
     DeleteId,
     DeleteName,
+    Renew,
+    TransferOwnership,
+    ReclaimExpired,
     UpdateDepositRequired,
 }
 
@@ -15,6 +18,9 @@ impl std::fmt::Display for NameEventType {
             NameEventType::Register => write!(f, "register"),
             NameEventType::DeleteId => write!(f, "delete_id"),
             NameEventType::DeleteName => write!(f, "delete_name"),
+            NameEventType::Renew => write!(f, "renew"),
+            NameEventType::TransferOwnership => write!(f, "transfer_ownership"),
+            NameEventType::ReclaimExpired => write!(f, "reclaim_expired"),
             NameEventType::UpdateDepositRequired => write!(f, "update_deposit_required"),
         }
     }
@@ -31,6 +37,8 @@ pub const ACTION: &str = "action";
 pub const NAME_ID: &str = "name_id";
 pub const NAME: &str = "name";
 pub const OWNER: &str = "owner";
+pub const NEW_OWNER: &str = "new_owner";
+pub const EXPIRES_AT: &str = "expires_at";
 
 pub const DEPOSIT_REQUIRED: &str = "deposit_required";
 
@@ -41,6 +49,7 @@ pub fn new_register_event(name_id: NameId, name: RegisteredName) -> Event {
         .add_attribute(NAME, name.name.to_string())
         .add_attribute(name.address.event_tag(), name.address.to_string())
         .add_attribute(OWNER, name.owner.to_string())
+        .add_attribute(EXPIRES_AT, name.expires_at.to_string())
 }
 
 pub fn new_delete_id_event(name_id: NameId, name: RegisteredName) -> Event {
@@ -59,6 +68,35 @@ pub fn new_delete_name_event(name_id: NameId, name: RegisteredName) -> Event {
         .add_attribute(name.address.event_tag(), name.address.to_string())
 }
 
+pub fn new_renew_event(name_id: NameId, name: RegisteredName) -> Event {
+    Event::new(NameEventType::Renew)
+        .add_attribute(ACTION, NameEventType::Renew)
+        .add_attribute(NAME_ID, name_id.to_string())
+        .add_attribute(NAME, name.name.to_string())
+        .add_attribute(EXPIRES_AT, name.expires_at.to_string())
+}
+
+pub fn new_transfer_ownership_event(
+    name_id: NameId,
+    previous_owner: Addr,
+    name: RegisteredName,
+) -> Event {
+    Event::new(NameEventType::TransferOwnership)
+        .add_attribute(ACTION, NameEventType::TransferOwnership)
+        .add_attribute(NAME_ID, name_id.to_string())
+        .add_attribute(NAME, name.name.to_string())
+        .add_attribute(OWNER, previous_owner.to_string())
+        .add_attribute(NEW_OWNER, name.owner.to_string())
+}
+
+pub fn new_reclaim_expired_event(name_id: NameId, name: RegisteredName) -> Event {
+    Event::new(NameEventType::ReclaimExpired)
+        .add_attribute(ACTION, NameEventType::ReclaimExpired)
+        .add_attribute(NAME_ID, name_id.to_string())
+        .add_attribute(NAME, name.name.to_string())
+        .add_attribute(OWNER, name.owner.to_string())
+}
+
 pub fn new_update_deposit_required_event(deposit_required: Coin) -> Event {
     Event::new(NameEventType::UpdateDepositRequired)
         .add_attribute(ACTION, NameEventType::UpdateDepositRequired)
@@ -1,7 +1,9 @@
 pub mod events;
 pub mod msg;
 pub mod response;
+pub mod signing_types;
 pub mod types;
 
 // Re-export all types at the top-level
+pub use signing_types::*;
 pub use types::*;
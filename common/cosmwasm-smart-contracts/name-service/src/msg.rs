@@ -1,16 +1,23 @@
 use crate::{Address, NameId, NymName};
 use cosmwasm_std::Coin;
+use cw_utils::Duration;
+use nym_contracts_common::signing::MessageSignature;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct InstantiateMsg {
     pub deposit_required: Coin,
+    /// For how long a name stays registered before it has to be renewed.
+    pub registration_period: Duration,
 }
 
 impl InstantiateMsg {
-    pub fn new(deposit_required: Coin) -> Self {
-        Self { deposit_required }
+    pub fn new(deposit_required: Coin, registration_period: Duration) -> Self {
+        Self {
+            deposit_required,
+            registration_period,
+        }
     }
 }
 
@@ -21,12 +28,22 @@ pub struct MigrateMsg {}
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    /// Announcing a name pointing to a nym-address
-    Register { name: NymName, address: Address },
+    /// Announcing a name pointing to a nym-address. The signature has to be made with the
+    /// identity key of the nym-address, over the payload constructed with
+    /// [`crate::construct_name_register_sign_payload`].
+    Register {
+        name: NymName,
+        address: Address,
+        owner_signature: MessageSignature,
+    },
     /// Delete a name entry by id
     DeleteId { name_id: NameId },
     /// Delete a name entry by name
     DeleteName { name: NymName },
+    /// Extend the registration of a name by another registration period
+    Renew { name_id: NameId },
+    /// Hand over a name entry, together with its deposit, to a new owner
+    TransferOwnership { name_id: NameId, new_owner: String },
     /// Change the deposit required for announcing a name
     UpdateDepositRequired { deposit_required: Coin },
 }
@@ -38,7 +55,7 @@ impl ExecuteMsg {
 
     pub fn default_memo(&self) -> String {
         match self {
-            ExecuteMsg::Register { name, address } => {
+            ExecuteMsg::Register { name, address, .. } => {
                 format!("registering {address} as name: {name}")
             }
             ExecuteMsg::DeleteId { name_id } => {
@@ -47,6 +64,12 @@ impl ExecuteMsg {
             ExecuteMsg::DeleteName { name } => {
                 format!("deleting name: {name}")
             }
+            ExecuteMsg::Renew { name_id } => {
+                format!("renewing name with id {name_id}")
+            }
+            ExecuteMsg::TransferOwnership { name_id, new_owner } => {
+                format!("transferring ownership of name with id {name_id} to {new_owner}")
+            }
             ExecuteMsg::UpdateDepositRequired { deposit_required } => {
                 format!("updating the deposit required to {deposit_required}")
             }
@@ -76,6 +99,9 @@ pub enum QueryMsg {
         start_after: Option<NameId>,
     },
     Config {},
+    GetSigningNonce {
+        address: String,
+    },
     GetContractVersion {},
     #[serde(rename = "get_cw2_contract_version")]
     GetCW2ContractVersion {},
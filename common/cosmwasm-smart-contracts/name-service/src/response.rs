@@ -1,5 +1,6 @@
-use crate::{msg::ExecuteMsg, NameEntry, NameId, RegisteredName};
+use crate::{NameEntry, NameId, RegisteredName};
 use cosmwasm_std::Coin;
+use cw_utils::Duration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -67,13 +68,5 @@ impl PagedNamesListResponse {
 #[serde(rename_all = "snake_case")]
 pub struct ConfigResponse {
     pub deposit_required: Coin,
-}
-
-impl From<RegisteredName> for ExecuteMsg {
-    fn from(name: RegisteredName) -> Self {
-        ExecuteMsg::Register {
-            name: name.name,
-            address: name.address,
-        }
-    }
+    pub registration_period: Duration,
 }
@@ -0,0 +1,41 @@
+use crate::{Address, NymName};
+use cosmwasm_std::{Addr, Coin};
+use nym_contracts_common::signing::{
+    ContractMessageContent, MessageType, Nonce, SignableMessage, SigningPurpose,
+};
+use serde::Serialize;
+
+pub type SignableNameRegisterMsg = SignableMessage<ContractMessageContent<NameRegistrationPayload>>;
+
+/// The data signed with the identity key of the nym address being registered, proving
+/// that the registrant is in control of that address.
+#[derive(Serialize)]
+pub struct NameRegistrationPayload {
+    name: NymName,
+    address: Address,
+}
+
+impl NameRegistrationPayload {
+    pub fn new(name: NymName, address: Address) -> Self {
+        Self { name, address }
+    }
+}
+
+impl SigningPurpose for NameRegistrationPayload {
+    fn message_type() -> MessageType {
+        MessageType::new("name-registration")
+    }
+}
+
+pub fn construct_name_register_sign_payload(
+    nonce: Nonce,
+    sender: Addr,
+    deposit: Coin,
+    name: NymName,
+    address: Address,
+) -> SignableNameRegisterMsg {
+    let payload = NameRegistrationPayload::new(name, address);
+    let content = ContractMessageContent::new(sender, None, vec![deposit], payload);
+
+    SignableMessage::new(nonce, content)
+}
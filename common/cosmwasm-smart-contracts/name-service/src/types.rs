@@ -1,6 +1,7 @@
 use std::fmt::{Display, Formatter};
 
-use cosmwasm_std::{Addr, Coin};
+use cosmwasm_std::{Addr, BlockInfo, Coin};
+use cw_utils::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -19,6 +20,16 @@ pub struct RegisteredName {
     pub block_height: u64,
     /// The deposit used to announce the service.
     pub deposit: Coin,
+    /// The point after which the registration expires and the name can be claimed by anyone.
+    /// Names registered before expiry was introduced never expire, until migrated.
+    #[serde(default)]
+    pub expires_at: Expiration,
+}
+
+impl RegisteredName {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires_at.is_expired(block)
+    }
 }
 
 /// String representation of a nym address, which is of the form
@@ -44,6 +55,17 @@ impl Address {
         }
     }
 
+    /// The client identity part of the address, i.e. the base58-encoded ed25519 public key
+    /// that has to sign any registration pointing to this address.
+    pub fn client_id(&self) -> &str {
+        match self {
+            Address::NymAddress(address) => address
+                .split_once('.')
+                .map(|(client_id, _)| client_id)
+                .unwrap_or(address),
+        }
+    }
+
     pub fn event_tag(&self) -> &str {
         match self {
             Address::NymAddress(_) => "nym_address",
@@ -114,7 +136,16 @@ impl NameEntry {
 
 #[cfg(test)]
 mod tests {
-    use super::NymName;
+    use super::{Address, NymName};
+
+    #[test]
+    fn address_client_id() {
+        assert_eq!(
+            Address::new("client_id.client_enc@gateway_id").client_id(),
+            "client_id"
+        );
+        assert_eq!(Address::new("client_id").client_id(), "client_id");
+    }
 
     #[test]
     fn parse_nym_name() {
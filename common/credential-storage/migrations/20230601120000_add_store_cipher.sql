@@ -0,0 +1,12 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- information required for re-deriving the key used for encrypting the credentials.
+-- if the table is empty, the stored credentials are not encrypted.
+CREATE TABLE store_cipher
+(
+    id              INTEGER NOT NULL PRIMARY KEY CHECK (id = 0),
+    exported_cipher TEXT    NOT NULL
+);
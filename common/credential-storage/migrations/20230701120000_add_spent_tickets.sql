@@ -0,0 +1,12 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- indices of the tickets that have already been spent from the ticketbook credentials
+CREATE TABLE spent_tickets
+(
+    credential_id INTEGER NOT NULL REFERENCES coconut_credentials (id),
+    ticket_index  INTEGER NOT NULL,
+    PRIMARY KEY (credential_id, ticket_index)
+);
@@ -2,12 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::models::CoconutCredential;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 #[derive(Clone)]
 pub struct CoconutCredentialManager {
     inner: Arc<RwLock<Vec<CoconutCredential>>>,
+    spent_tickets: Arc<RwLock<HashMap<i64, Vec<u64>>>>,
 }
 
 impl CoconutCredentialManager {
@@ -15,6 +17,7 @@ impl CoconutCredentialManager {
     pub fn new() -> Self {
         CoconutCredentialManager {
             inner: Arc::new(RwLock::new(Vec::new())),
+            spent_tickets: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -67,4 +70,28 @@ impl CoconutCredentialManager {
             cred.consumed = true;
         }
     }
+
+    /// Retrieves indices of the already spent tickets of the specified ticketbook credential.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: Database id of the ticketbook.
+    pub async fn get_spent_tickets(&self, id: i64) -> Vec<u64> {
+        let spent_tickets = self.spent_tickets.read().await;
+        spent_tickets.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Marks as spent the ticket at the specified index of the ticketbook credential.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: Database id of the ticketbook.
+    /// * `ticket_index`: Index of the spent ticket.
+    pub async fn insert_spent_ticket(&self, id: i64, ticket_index: u64) {
+        let mut spent_tickets = self.spent_tickets.write().await;
+        let spent = spent_tickets.entry(id).or_default();
+        if !spent.contains(&ticket_index) {
+            spent.push(ticket_index)
+        }
+    }
 }
@@ -71,4 +71,107 @@ impl CoconutCredentialManager {
         .await?;
         Ok(())
     }
+
+    /// Retrieves indices of the already spent tickets of the specified ticketbook credential.
+    ///
+    /// # Arguments
+    ///
+    /// * `credential_id`: Database id of the ticketbook.
+    pub async fn get_spent_tickets(&self, credential_id: i64) -> Result<Vec<i64>, sqlx::Error> {
+        let spent = sqlx::query!(
+            "SELECT ticket_index FROM spent_tickets WHERE credential_id = ?",
+            credential_id
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+        Ok(spent.into_iter().map(|row| row.ticket_index).collect())
+    }
+
+    /// Marks as spent the ticket at the specified index of the ticketbook credential.
+    ///
+    /// # Arguments
+    ///
+    /// * `credential_id`: Database id of the ticketbook.
+    /// * `ticket_index`: Index of the spent ticket.
+    pub async fn insert_spent_ticket(
+        &self,
+        credential_id: i64,
+        ticket_index: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO spent_tickets(credential_id, ticket_index) VALUES (?, ?)",
+            credential_id,
+            ticket_index
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns all the stored credentials, including the consumed ones.
+    pub async fn get_all_coconut_credentials(&self) -> Result<Vec<CoconutCredential>, sqlx::Error> {
+        sqlx::query_as!(CoconutCredential, "SELECT * FROM coconut_credentials")
+            .fetch_all(&self.connection_pool)
+            .await
+    }
+}
+
+#[derive(Clone)]
+pub struct StoreCipherManager {
+    connection_pool: sqlx::SqlitePool,
+}
+
+impl StoreCipherManager {
+    /// Creates new instance of the `StoreCipherManager` with the provided sqlite connection pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection_pool`: database connection pool to use.
+    pub fn new(connection_pool: sqlx::SqlitePool) -> Self {
+        StoreCipherManager { connection_pool }
+    }
+
+    /// Tries to retrieve the serialized information about the cipher used for encrypting the credentials.
+    pub async fn get_exported_cipher(&self) -> Result<Option<String>, sqlx::Error> {
+        let exported = sqlx::query!("SELECT exported_cipher FROM store_cipher WHERE id = 0")
+            .fetch_optional(&self.connection_pool)
+            .await?;
+        Ok(exported.map(|row| row.exported_cipher))
+    }
+
+    /// Stores the serialized information about the cipher used for encrypting the credentials
+    /// alongside the re-encrypted secret attributes of all the existing credentials.
+    /// It's done within a single transaction, so that the database is never left with
+    /// a mix of plaintext and encrypted data.
+    ///
+    /// # Arguments
+    ///
+    /// * `exported_cipher`: JSON representation of the exported store cipher.
+    /// * `encrypted_credentials`: all existing credentials with their serial numbers, binding numbers and signatures encrypted.
+    pub async fn insert_exported_cipher(
+        &self,
+        exported_cipher: String,
+        encrypted_credentials: Vec<CoconutCredential>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.connection_pool.begin().await?;
+        sqlx::query!(
+            "INSERT INTO store_cipher(id, exported_cipher) VALUES (0, ?)",
+            exported_cipher
+        )
+        .execute(&mut tx)
+        .await?;
+
+        for credential in encrypted_credentials {
+            sqlx::query!(
+                "UPDATE coconut_credentials SET serial_number = ?, binding_number = ?, signature = ? WHERE id = ?",
+                credential.serial_number,
+                credential.binding_number,
+                credential.signature,
+                credential.id
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await
+    }
 }
@@ -66,4 +66,16 @@ impl Storage for EphemeralStorage {
 
         Ok(())
     }
+
+    async fn get_spent_tickets(&self, id: i64) -> Result<Vec<u64>, StorageError> {
+        Ok(self.coconut_credential_manager.get_spent_tickets(id).await)
+    }
+
+    async fn insert_spent_ticket(&self, id: i64, ticket_index: u64) -> Result<(), StorageError> {
+        self.coconut_credential_manager
+            .insert_spent_ticket(id, ticket_index)
+            .await;
+
+        Ok(())
+    }
 }
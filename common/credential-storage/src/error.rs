@@ -18,4 +18,15 @@ pub enum StorageError {
 
     #[error("No unused credential in database. You need to buy at least one")]
     NoCredential,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error("Failed to use the store cipher - {0}")]
+    StoreCipherError(#[from] nym_store_cipher::Error),
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error("Failed to (de)serialize encrypted data - {0}")]
+    MalformedEncryptedData(#[from] serde_json::Error),
+
+    #[error("The credentials in the database are encrypted, but no passphrase was provided")]
+    NoPassphraseProvided,
 }
@@ -23,6 +23,17 @@ pub async fn initialise_persistent_storage(path: std::path::PathBuf) -> Persiste
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn initialise_encrypted_persistent_storage(
+    path: std::path::PathBuf,
+    passphrase: &str,
+) -> PersistentStorage {
+    match persistent_storage::PersistentStorage::init_encrypted(path, passphrase.as_bytes()).await {
+        Err(err) => panic!("failed to initialise encrypted credential storage - {err}"),
+        Ok(storage) => storage,
+    }
+}
+
 pub fn initialise_ephemeral_storage() -> EphemeralStorage {
     ephemeral_storage::EphemeralStorage::default()
 }
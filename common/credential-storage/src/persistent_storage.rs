@@ -1,29 +1,55 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::backends::sqlite::CoconutCredentialManager;
+use crate::backends::sqlite::{CoconutCredentialManager, StoreCipherManager};
 use crate::error::StorageError;
 use crate::storage::Storage;
 
 use crate::models::CoconutCredential;
 use async_trait::async_trait;
-use log::{debug, error};
+use log::{debug, error, info};
+use nym_store_cipher::{Aes256Gcm, EncryptedData, ExportedStoreCipher, StoreCipher};
 use sqlx::ConnectOptions;
 use std::path::Path;
+use std::sync::Arc;
 
 // note that clone here is fine as upon cloning the same underlying pool will be used
 #[derive(Clone)]
 pub struct PersistentStorage {
     coconut_credential_manager: CoconutCredentialManager,
+    store_cipher: Option<Arc<StoreCipher<Aes256Gcm>>>,
 }
 
 impl PersistentStorage {
     /// Initialises `PersistentStorage` using the provided path.
+    /// It will fail if the underlying database has previously been encrypted.
     ///
     /// # Arguments
     ///
     /// * `database_path`: path to the database.
     pub async fn init<P: AsRef<Path> + Send>(database_path: P) -> Result<Self, StorageError> {
+        Self::init_with_passphrase(database_path, None).await
+    }
+
+    /// Initialises `PersistentStorage` using the provided path, encrypting the serial numbers,
+    /// binding numbers and signatures of the stored credentials with a key derived from the passphrase.
+    /// If the underlying database contains any plaintext credentials, they will get encrypted.
+    ///
+    /// # Arguments
+    ///
+    /// * `database_path`: path to the database.
+    /// * `passphrase`: passphrase used for deriving the encryption key.
+    pub async fn init_encrypted<P: AsRef<Path> + Send>(
+        database_path: P,
+        passphrase: &[u8],
+    ) -> Result<Self, StorageError> {
+        Self::init_with_passphrase(database_path, Some(passphrase)).await
+    }
+
+    async fn init_with_passphrase<P: AsRef<Path> + Send>(
+        database_path: P,
+        passphrase: Option<&[u8]>,
+    ) -> Result<Self, StorageError> {
         debug!(
             "Attempting to connect to database {:?}",
             database_path.as_ref().as_os_str()
@@ -48,10 +74,120 @@ impl PersistentStorage {
             return Err(err.into());
         }
 
+        let coconut_credential_manager = CoconutCredentialManager::new(connection_pool.clone());
+        let store_cipher_manager = StoreCipherManager::new(connection_pool);
+        let store_cipher = Self::setup_store_cipher(
+            &store_cipher_manager,
+            &coconut_credential_manager,
+            passphrase,
+        )
+        .await?;
+
         Ok(PersistentStorage {
-            coconut_credential_manager: CoconutCredentialManager::new(connection_pool.clone()),
+            coconut_credential_manager,
+            store_cipher: store_cipher.map(Arc::new),
         })
     }
+
+    async fn setup_store_cipher(
+        store_cipher_manager: &StoreCipherManager,
+        coconut_credential_manager: &CoconutCredentialManager,
+        passphrase: Option<&[u8]>,
+    ) -> Result<Option<StoreCipher<Aes256Gcm>>, StorageError> {
+        // we have few options of proceeding from here:
+        // no passphrase + no existing info => the credentials are not encrypted, so just use them as they are
+        // no passphrase + existing info => the credentials are encrypted, so reject
+        // passphrase + no existing info => derive new key and encrypt any credentials that might already be stored
+        // passphrase + existing info => try to re-derive the key
+        let existing = store_cipher_manager.get_exported_cipher().await?;
+        match (existing, passphrase) {
+            (None, None) => Ok(None),
+            (Some(_), None) => Err(StorageError::NoPassphraseProvided),
+            (Some(exported), Some(passphrase)) => {
+                debug!("attempting to use previously derived encryption key");
+                let exported: ExportedStoreCipher = serde_json::from_str(&exported)?;
+                Ok(Some(StoreCipher::import_aes256gcm(passphrase, exported)?))
+            }
+            (None, Some(passphrase)) => {
+                debug!("attempting to derive new encryption key");
+                let store_cipher = StoreCipher::<Aes256Gcm>::new_with_default_kdf(passphrase)?;
+                let exported = serde_json::to_string(&store_cipher.export_aes256gcm()?)?;
+
+                let existing_credentials = coconut_credential_manager
+                    .get_all_coconut_credentials()
+                    .await?;
+                if !existing_credentials.is_empty() {
+                    info!(
+                        "encrypting {} previously stored credentials",
+                        existing_credentials.len()
+                    );
+                }
+
+                let encrypted_credentials = existing_credentials
+                    .into_iter()
+                    .map(|credential| encrypt_credential(&store_cipher, credential))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                store_cipher_manager
+                    .insert_exported_cipher(exported, encrypted_credentials)
+                    .await?;
+
+                Ok(Some(store_cipher))
+            }
+        }
+    }
+
+    fn encrypt_field(&self, value: String) -> Result<String, StorageError> {
+        match &self.store_cipher {
+            Some(store_cipher) => encrypt_field(store_cipher, value),
+            None => Ok(value),
+        }
+    }
+
+    fn decrypt_credential(
+        &self,
+        credential: CoconutCredential,
+    ) -> Result<CoconutCredential, StorageError> {
+        let Some(store_cipher) = &self.store_cipher else {
+            return Ok(credential);
+        };
+
+        Ok(CoconutCredential {
+            serial_number: decrypt_field(store_cipher, &credential.serial_number)?,
+            binding_number: decrypt_field(store_cipher, &credential.binding_number)?,
+            signature: decrypt_field(store_cipher, &credential.signature)?,
+            ..credential
+        })
+    }
+}
+
+fn encrypt_field(
+    store_cipher: &StoreCipher<Aes256Gcm>,
+    value: String,
+) -> Result<String, StorageError> {
+    let encrypted = store_cipher.encrypt_data(value.into_bytes())?;
+    Ok(serde_json::to_string(&encrypted)?)
+}
+
+fn decrypt_field(
+    store_cipher: &StoreCipher<Aes256Gcm>,
+    value: &str,
+) -> Result<String, StorageError> {
+    let encrypted: EncryptedData = serde_json::from_str(value)?;
+    let plaintext = store_cipher.decrypt_data(encrypted)?;
+    String::from_utf8(plaintext).map_err(|_| StorageError::InconsistentData)
+}
+
+fn encrypt_credential(
+    store_cipher: &StoreCipher<Aes256Gcm>,
+    credential: CoconutCredential,
+) -> Result<CoconutCredential, StorageError> {
+    Ok(CoconutCredential {
+        serial_number: encrypt_field(store_cipher, credential.serial_number)?,
+        binding_number: encrypt_field(store_cipher, credential.binding_number)?,
+        signature: encrypt_field(store_cipher, credential.signature)?,
+        ..credential
+    })
 }
 
 #[async_trait]
@@ -71,9 +207,9 @@ impl Storage for PersistentStorage {
             .insert_coconut_credential(
                 voucher_value,
                 voucher_info,
-                serial_number,
-                binding_number,
-                signature,
+                self.encrypt_field(serial_number)?,
+                self.encrypt_field(binding_number)?,
+                self.encrypt_field(signature)?,
                 epoch_id,
             )
             .await?;
@@ -88,7 +224,7 @@ impl Storage for PersistentStorage {
             .await?
             .ok_or(StorageError::NoCredential)?;
 
-        Ok(credential)
+        self.decrypt_credential(credential)
     }
 
     async fn consume_coconut_credential(&self, id: i64) -> Result<(), StorageError> {
@@ -98,4 +234,195 @@ impl Storage for PersistentStorage {
 
         Ok(())
     }
+
+    async fn get_spent_tickets(&self, id: i64) -> Result<Vec<u64>, StorageError> {
+        let spent = self
+            .coconut_credential_manager
+            .get_spent_tickets(id)
+            .await?
+            .into_iter()
+            .map(|ticket_index| ticket_index as u64)
+            .collect();
+
+        Ok(spent)
+    }
+
+    async fn insert_spent_ticket(&self, id: i64, ticket_index: u64) -> Result<(), StorageError> {
+        self.coconut_credential_manager
+            .insert_spent_ticket(id, ticket_index as i64)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const PASSPHRASE: &[u8] = b"my-super-secret-passphrase";
+
+    fn database_path(dir: &TempDir) -> std::path::PathBuf {
+        dir.path().join("credentials.sqlite")
+    }
+
+    // note: the signatures have to be unique
+    async fn insert_dummy_credential(storage: &PersistentStorage, signature: &str) {
+        storage
+            .insert_coconut_credential(
+                "1000".to_string(),
+                "voucher info".to_string(),
+                "serial number".to_string(),
+                "binding number".to_string(),
+                signature.to_string(),
+                "42".to_string(),
+            )
+            .await
+            .unwrap();
+    }
+
+    fn assert_is_dummy_credential(credential: &CoconutCredential, signature: &str) {
+        assert_eq!(credential.voucher_value, "1000");
+        assert_eq!(credential.voucher_info, "voucher info");
+        assert_eq!(credential.serial_number, "serial number");
+        assert_eq!(credential.binding_number, "binding number");
+        assert_eq!(credential.signature, signature);
+        assert_eq!(credential.epoch_id, "42");
+    }
+
+    async fn raw_credentials(storage: &PersistentStorage) -> Vec<CoconutCredential> {
+        storage
+            .coconut_credential_manager
+            .get_all_coconut_credentials()
+            .await
+            .unwrap()
+    }
+
+    fn assert_secrets_are_encrypted(credential: &CoconutCredential) {
+        for field in [
+            &credential.serial_number,
+            &credential.binding_number,
+            &credential.signature,
+        ] {
+            assert!(serde_json::from_str::<EncryptedData>(field).is_ok());
+        }
+    }
+
+    #[test]
+    fn encrypted_fields_can_be_decrypted() {
+        let store_cipher = StoreCipher::<Aes256Gcm>::new_with_default_kdf(PASSPHRASE).unwrap();
+
+        let encrypted = encrypt_field(&store_cipher, "foomp".to_string()).unwrap();
+        assert_ne!(encrypted, "foomp");
+        assert_eq!(decrypt_field(&store_cipher, &encrypted).unwrap(), "foomp");
+
+        // the same value is never encrypted to the same ciphertext
+        let encrypted2 = encrypt_field(&store_cipher, "foomp".to_string()).unwrap();
+        assert_ne!(encrypted, encrypted2);
+    }
+
+    #[test]
+    fn fields_cannot_be_decrypted_with_different_key() {
+        let store_cipher = StoreCipher::<Aes256Gcm>::new_with_default_kdf(PASSPHRASE).unwrap();
+        let other_cipher = StoreCipher::<Aes256Gcm>::new_with_default_kdf(PASSPHRASE).unwrap();
+
+        let encrypted = encrypt_field(&store_cipher, "foomp".to_string()).unwrap();
+        assert!(decrypt_field(&other_cipher, &encrypted).is_err());
+        assert!(decrypt_field(&store_cipher, "foomp").is_err());
+    }
+
+    #[tokio::test]
+    async fn plaintext_storage_is_left_unencrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = PersistentStorage::init(database_path(&dir)).await.unwrap();
+        insert_dummy_credential(&storage, "signature").await;
+
+        assert!(storage.store_cipher.is_none());
+        assert_is_dummy_credential(&raw_credentials(&storage).await[0], "signature");
+        assert_is_dummy_credential(
+            &storage.get_next_coconut_credential().await.unwrap(),
+            "signature",
+        );
+    }
+
+    #[tokio::test]
+    async fn credentials_are_stored_encrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = PersistentStorage::init_encrypted(database_path(&dir), PASSPHRASE)
+            .await
+            .unwrap();
+        insert_dummy_credential(&storage, "signature").await;
+
+        let raw = raw_credentials(&storage).await;
+        assert_secrets_are_encrypted(&raw[0]);
+        assert_eq!(raw[0].voucher_value, "1000");
+        assert_eq!(raw[0].epoch_id, "42");
+        assert_is_dummy_credential(
+            &storage.get_next_coconut_credential().await.unwrap(),
+            "signature",
+        );
+
+        // and they can be read after reopening the storage with the same passphrase
+        drop(storage);
+        let storage = PersistentStorage::init_encrypted(database_path(&dir), PASSPHRASE)
+            .await
+            .unwrap();
+        assert_is_dummy_credential(
+            &storage.get_next_coconut_credential().await.unwrap(),
+            "signature",
+        );
+    }
+
+    #[tokio::test]
+    async fn plaintext_storage_gets_encrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = PersistentStorage::init(database_path(&dir)).await.unwrap();
+        insert_dummy_credential(&storage, "signature").await;
+        insert_dummy_credential(&storage, "another signature").await;
+        drop(storage);
+
+        let storage = PersistentStorage::init_encrypted(database_path(&dir), PASSPHRASE)
+            .await
+            .unwrap();
+        let raw = raw_credentials(&storage).await;
+        assert_eq!(raw.len(), 2);
+        for credential in &raw {
+            assert_secrets_are_encrypted(credential);
+        }
+        assert_is_dummy_credential(
+            &storage.get_next_coconut_credential().await.unwrap(),
+            "signature",
+        );
+    }
+
+    #[tokio::test]
+    async fn wrong_passphrase_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = PersistentStorage::init_encrypted(database_path(&dir), PASSPHRASE)
+            .await
+            .unwrap();
+        insert_dummy_credential(&storage, "signature").await;
+        drop(storage);
+
+        assert!(
+            PersistentStorage::init_encrypted(database_path(&dir), b"wrong-passphrase")
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn encrypted_storage_cannot_be_opened_without_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = PersistentStorage::init_encrypted(database_path(&dir), PASSPHRASE)
+            .await
+            .unwrap();
+        drop(storage);
+
+        assert!(matches!(
+            PersistentStorage::init(database_path(&dir)).await,
+            Err(StorageError::NoPassphraseProvided)
+        ));
+    }
 }
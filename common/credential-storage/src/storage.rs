@@ -38,4 +38,23 @@ pub trait Storage: Send + Sync {
     ///
     /// * `id`: Id of the credential to be consumed.
     async fn consume_coconut_credential(&self, id: i64) -> Result<(), Self::StorageError>;
+
+    /// Retrieves indices of the already spent tickets of the specified ticketbook credential.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: Id of the ticketbook credential.
+    async fn get_spent_tickets(&self, id: i64) -> Result<Vec<u64>, Self::StorageError>;
+
+    /// Marks as spent the ticket at the specified index of the ticketbook credential.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: Id of the ticketbook credential.
+    /// * `ticket_index`: Index of the spent ticket.
+    async fn insert_spent_ticket(
+        &self,
+        id: i64,
+        ticket_index: u64,
+    ) -> Result<(), Self::StorageError>;
 }
@@ -8,13 +8,13 @@
 
 use nym_coconut_interface::{
     hash_to_scalar, prepare_blind_sign, Attribute, BlindSignRequest, Credential, Parameters,
-    PrivateAttribute, PublicAttribute, Signature, VerificationKey,
+    PrivateAttribute, PublicAttribute, Signature, TicketIndex, VerificationKey,
 };
 use nym_crypto::asymmetric::{encryption, identity};
 
 use cosmrs::tx::Hash;
 
-use super::utils::prepare_credential_for_spending;
+use super::utils::{self, prepare_credential_for_spending};
 use crate::error::Error;
 
 pub const PUBLIC_ATTRIBUTES: u32 = 2;
@@ -238,6 +238,10 @@ impl BandwidthVoucher {
         self.voucher_value_plain.clone()
     }
 
+    pub fn get_voucher_info(&self) -> String {
+        self.voucher_info_plain.clone()
+    }
+
     pub fn get_public_attributes_plain(&self) -> Vec<String> {
         vec![
             self.voucher_value_plain.clone(),
@@ -279,6 +283,34 @@ pub fn prepare_for_spending(
     )
 }
 
+/// Prepares a single ticket of a ticketbook for spending. Ticketbooks are issued as regular vouchers
+/// (with the `TicketBook` voucher info), but each of their tickets can be spent independently.
+#[allow(clippy::too_many_arguments)]
+pub fn prepare_ticket_for_spending(
+    voucher_value: u64,
+    voucher_info: String,
+    serial_number: PrivateAttribute,
+    binding_number: PrivateAttribute,
+    ticket_index: TicketIndex,
+    epoch_id: u64,
+    signature: &Signature,
+    verification_key: &VerificationKey,
+) -> Result<Credential, Error> {
+    let params = Parameters::new(TOTAL_ATTRIBUTES)?;
+
+    utils::prepare_ticket_for_spending(
+        &params,
+        voucher_value,
+        voucher_info,
+        serial_number,
+        binding_number,
+        ticket_index,
+        epoch_id,
+        signature,
+        verification_key,
+    )
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
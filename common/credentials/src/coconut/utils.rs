@@ -3,8 +3,9 @@
 
 use nym_api_requests::coconut::BlindSignRequestBody;
 use nym_coconut_interface::{
-    aggregate_signature_shares, aggregate_verification_keys, prove_bandwidth_credential, Attribute,
-    BlindedSignature, Credential, Parameters, Signature, SignatureShare, VerificationKey,
+    aggregate_signature_shares, aggregate_verification_keys, prove_bandwidth_credential,
+    prove_bandwidth_ticket, Attribute, BlindedSignature, Credential, Parameters, Signature,
+    SignatureShare, TicketIndex, VerificationKey,
 };
 use nym_crypto::asymmetric::encryption::PublicKey;
 use nym_crypto::shared_key::recompute_shared_key;
@@ -159,3 +160,35 @@ pub fn prepare_credential_for_spending(
         epoch_id,
     ))
 }
+
+// the serial number of a ticketbook is the secret all of its tickets are derived from
+#[allow(clippy::too_many_arguments)]
+pub fn prepare_ticket_for_spending(
+    params: &Parameters,
+    voucher_value: u64,
+    voucher_info: String,
+    serial_number: Attribute,
+    binding_number: Attribute,
+    ticket_index: TicketIndex,
+    epoch_id: u64,
+    signature: &Signature,
+    verification_key: &VerificationKey,
+) -> Result<Credential, Error> {
+    let theta = prove_bandwidth_ticket(
+        params,
+        verification_key,
+        signature,
+        serial_number,
+        binding_number,
+        ticket_index,
+    )?;
+
+    Ok(Credential::new_ticket(
+        PUBLIC_ATTRIBUTES + PRIVATE_ATTRIBUTES,
+        theta,
+        voucher_value,
+        voucher_info,
+        epoch_id,
+        ticket_index,
+    ))
+}
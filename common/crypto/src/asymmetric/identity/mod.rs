@@ -1,6 +1,8 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::asymmetric::encryption;
+use curve25519_dalek::edwards::CompressedEdwardsY;
 pub use ed25519_dalek::ed25519::signature::Signature as SignatureTrait;
 pub use ed25519_dalek::SignatureError;
 pub use ed25519_dalek::{Verifier, PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH, SIGNATURE_LENGTH};
@@ -9,7 +11,7 @@ use nym_sphinx_types::{DestinationAddressBytes, DESTINATION_ADDRESS_LENGTH};
 use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
 use thiserror::Error;
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 #[cfg(feature = "rand")]
 use rand::{CryptoRng, RngCore};
@@ -146,6 +148,16 @@ impl PublicKey {
     pub fn verify(&self, message: &[u8], signature: &Signature) -> Result<(), SignatureError> {
         self.0.verify(message, &signature.0)
     }
+
+    /// Converts this key into the x25519 key of the same keypair, i.e. the Montgomery form
+    /// of the same curve point, so that it could be used for the Diffie-Hellman key exchange.
+    pub fn to_x25519(&self) -> encryption::PublicKey {
+        // the point has already been validated when the key got constructed
+        let point = CompressedEdwardsY(self.0.to_bytes())
+            .decompress()
+            .expect("ed25519 public key is not a valid curve point");
+        encryption::PublicKey::from_bytes(point.to_montgomery().as_bytes()).unwrap()
+    }
 }
 
 impl FromStr for PublicKey {
@@ -237,6 +249,15 @@ impl PrivateKey {
         Signature(sig)
     }
 
+    /// Converts this key into the x25519 key of the same keypair so that it could be used
+    /// for the Diffie-Hellman key exchange. The resulting key corresponds to [`PublicKey::to_x25519`].
+    pub fn to_x25519(&self) -> encryption::PrivateKey {
+        // the first half of the expanded key is the (already clamped) scalar
+        let expanded = ed25519_dalek::ExpandedSecretKey::from(&self.0);
+        let expanded_bytes = Zeroizing::new(expanded.to_bytes());
+        encryption::PrivateKey::from_bytes(&expanded_bytes[..encryption::PRIVATE_KEY_SIZE]).unwrap()
+    }
+
     /// Signs text with the provided Ed25519 private key, returning a base58 signature
     pub fn sign_text(&self, text: &str) -> String {
         let signature_bytes = self.sign(text.as_ref()).to_bytes();
@@ -327,3 +348,46 @@ impl<'d> Deserialize<'d> for Signature {
         Signature::from_bytes(bytes.as_ref()).map_err(SerdeError::custom)
     }
 }
+
+#[cfg(test)]
+mod x25519_conversion {
+    use super::*;
+    use rand_chacha::rand_core::{RngCore, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    fn random_private_key(rng: &mut ChaCha20Rng) -> PrivateKey {
+        let mut seed = [0u8; SECRET_KEY_LENGTH];
+        rng.fill_bytes(&mut seed);
+        PrivateKey::from_bytes(&seed).unwrap()
+    }
+
+    #[test]
+    fn converted_keys_form_a_valid_keypair() {
+        let mut rng = ChaCha20Rng::from_seed([42u8; 32]);
+
+        for _ in 0..100 {
+            let private = random_private_key(&mut rng);
+            let public = PublicKey::from(&private);
+
+            let x25519_private = private.to_x25519();
+            let derived_public = encryption::PublicKey::from(&x25519_private);
+            assert_eq!(derived_public, public.to_x25519());
+        }
+    }
+
+    #[test]
+    fn converted_keys_can_be_used_for_key_exchange() {
+        let mut rng = ChaCha20Rng::from_seed([42u8; 32]);
+
+        let alice = random_private_key(&mut rng);
+        let bob = random_private_key(&mut rng);
+
+        let alice_shared = alice
+            .to_x25519()
+            .diffie_hellman(&PublicKey::from(&bob).to_x25519());
+        let bob_shared = bob
+            .to_x25519()
+            .diffie_hellman(&PublicKey::from(&alice).to_x25519());
+        assert_eq!(alice_shared, bob_shared);
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::Result;
+use crate::transport::LedgerTransport;
+use crate::{
+    CLA, INS_GET_ADDR_SECP256K1, INS_GET_VERSION, INS_SIGN_SECP256K1, PAYLOAD_TYPE_ADD,
+    PAYLOAD_TYPE_INIT, PAYLOAD_TYPE_LAST,
+};
+use k256::ecdsa::signature::Signer;
+use k256::ecdsa::{Signature, SigningKey};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use ledger_transport::{APDUAnswer, APDUCommand, APDUErrorCode};
+use std::sync::Mutex;
+
+// version reported by the emulated cosmos application
+const EMULATED_VERSION: (u8, u8, u8) = (2, 34, 0);
+
+/// Software emulation of a Ledger device running the Cosmos application.
+///
+/// It holds a single, in-memory, key which is used regardless of the requested derivation path
+/// and it never waits for any user confirmation. It must never be used outside of tests.
+pub struct LedgerEmulator {
+    signing_key: SigningKey,
+    address: String,
+
+    /// Message that is currently being signed, as the device receives it in multiple chunks.
+    pending_message: Mutex<Option<Vec<u8>>>,
+}
+
+impl LedgerEmulator {
+    /// Create the emulator using the provided key. The address is reported as is.
+    pub fn new(signing_key: SigningKey, address: String) -> Self {
+        LedgerEmulator {
+            signing_key,
+            address,
+            pending_message: Mutex::new(None),
+        }
+    }
+
+    fn version(&self) -> Vec<u8> {
+        let (major, minor, patch) = EMULATED_VERSION;
+        vec![0, major, minor, patch, 0]
+    }
+
+    fn address(&self) -> Vec<u8> {
+        let public_key = self.signing_key.verifying_key().to_encoded_point(true);
+        let mut data = public_key.as_bytes().to_vec();
+        data.extend_from_slice(self.address.as_bytes());
+        data
+    }
+
+    fn sign(&self, payload_type: u8, chunk: &[u8]) -> std::result::Result<Vec<u8>, APDUErrorCode> {
+        let mut pending = self.pending_message.lock().unwrap();
+        match payload_type {
+            // the first chunk only contains the derivation path
+            PAYLOAD_TYPE_INIT => {
+                *pending = Some(Vec::new());
+                Ok(Vec::new())
+            }
+            PAYLOAD_TYPE_ADD => {
+                let message = pending
+                    .as_mut()
+                    .ok_or(APDUErrorCode::ConditionsNotSatisfied)?;
+                message.extend_from_slice(chunk);
+                Ok(Vec::new())
+            }
+            PAYLOAD_TYPE_LAST => {
+                let mut message = pending
+                    .take()
+                    .ok_or(APDUErrorCode::ConditionsNotSatisfied)?;
+                message.extend_from_slice(chunk);
+
+                let signature: Signature = self.signing_key.sign(&message);
+                Ok(signature.to_der().as_bytes().to_vec())
+            }
+            _ => Err(APDUErrorCode::InvalidP1P2),
+        }
+    }
+}
+
+fn answer(response: std::result::Result<Vec<u8>, APDUErrorCode>) -> APDUAnswer<Vec<u8>> {
+    let (mut data, code) = match response {
+        Ok(data) => (data, APDUErrorCode::NoError),
+        Err(code) => (Vec::new(), code),
+    };
+    data.extend_from_slice(&u16::from(code).to_be_bytes());
+
+    // the answer is always at least 2 bytes long as it contains the return code
+    APDUAnswer::from_answer(data).expect("the answer contains the return code")
+}
+
+impl LedgerTransport for LedgerEmulator {
+    fn exchange(&self, command: &APDUCommand<Vec<u8>>) -> Result<APDUAnswer<Vec<u8>>> {
+        if command.cla != CLA {
+            return Ok(answer(Err(APDUErrorCode::ClaNotSupported)));
+        }
+
+        let response = match command.ins {
+            INS_GET_VERSION => Ok(self.version()),
+            INS_GET_ADDR_SECP256K1 => Ok(self.address()),
+            INS_SIGN_SECP256K1 => self.sign(command.p1, &command.data),
+            _ => Err(APDUErrorCode::InsNotSupported),
+        };
+        Ok(answer(response))
+    }
+}
@@ -2,25 +2,30 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod addr_secp256k1;
+#[cfg(feature = "emulator")]
+pub mod emulator;
 pub mod error;
 pub(crate) mod helpers;
-mod sign_secp256k1;
+pub mod sign_secp256k1;
+pub mod transport;
 pub mod version;
 
 use crate::addr_secp256k1::AddrSecp256k1Response;
 use crate::error::LedgerError;
 use crate::helpers::path_bytes;
 use crate::sign_secp256k1::SignSecp256k1Response;
+use crate::transport::LedgerTransport;
 use crate::version::VersionResponse;
 use bip32::DerivationPath;
 use error::Result;
 use ledger_transport::APDUCommand;
 use ledger_transport_hid::hidapi::HidApi;
-use ledger_transport_hid::TransportNativeHID;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::sync::Arc;
 
+pub use ledger_transport_hid::TransportNativeHID;
+
 const CLA: u8 = 0x55;
 const INS_GET_VERSION: u8 = 0x00;
 const INS_SIGN_SECP256K1: u8 = 0x02;
@@ -33,14 +38,23 @@ const CHUNK_SIZE: usize = 250;
 
 /// Manage hardware Ledger device with Cosmos specific operations, as described in the
 /// specification: https://github.com/cosmos/ledger-cosmos/blob/main/docs/APDUSPEC.md
-#[derive(Clone)]
-pub struct CosmosLedger {
+pub struct CosmosLedger<T = TransportNativeHID> {
     path: DerivationPath,
     prefix: String,
-    transport: Arc<TransportNativeHID>,
+    transport: Arc<T>,
+}
+
+impl<T> Clone for CosmosLedger<T> {
+    fn clone(&self) -> Self {
+        CosmosLedger {
+            path: self.path.clone(),
+            prefix: self.prefix.clone(),
+            transport: Arc::clone(&self.transport),
+        }
+    }
 }
 
-impl Debug for CosmosLedger {
+impl<T> Debug for CosmosLedger<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
         write!(f, "()")
     }
@@ -50,13 +64,20 @@ impl CosmosLedger {
     /// Create the connection to the first Ledger device that we can find.
     pub fn new(path: DerivationPath, prefix: String) -> Result<Self> {
         let api = HidApi::new()?;
-        let transport = Arc::new(TransportNativeHID::new(&api)?);
+        let transport = TransportNativeHID::new(&api)?;
 
-        Ok(CosmosLedger {
+        Ok(Self::with_transport(path, prefix, transport))
+    }
+}
+
+impl<T: LedgerTransport> CosmosLedger<T> {
+    /// Use the provided transport for communicating with the device.
+    pub fn with_transport(path: DerivationPath, prefix: String, transport: T) -> Self {
+        CosmosLedger {
             path,
             prefix,
-            transport,
-        })
+            transport: Arc::new(transport),
+        }
     }
 
     /// Get the version of the device.
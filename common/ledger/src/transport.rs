@@ -0,0 +1,19 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::Result;
+use ledger_transport::{APDUAnswer, APDUCommand};
+use ledger_transport_hid::TransportNativeHID;
+
+/// Means of exchanging APDU messages with a device running the Cosmos application.
+///
+/// Apart from the physical device connected over HID, it could also be implemented by a software emulator.
+pub trait LedgerTransport {
+    fn exchange(&self, command: &APDUCommand<Vec<u8>>) -> Result<APDUAnswer<Vec<u8>>>;
+}
+
+impl LedgerTransport for TransportNativeHID {
+    fn exchange(&self, command: &APDUCommand<Vec<u8>>) -> Result<APDUAnswer<Vec<u8>>> {
+        Ok(TransportNativeHID::exchange(self, command)?)
+    }
+}
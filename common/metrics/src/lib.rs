@@ -0,0 +1,150 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Minimal encoder for the Prometheus text exposition format, as described in
+//! https://prometheus.io/docs/instrumenting/exposition_formats/#text-based-format
+//!
+//! The binaries are expected to keep track of their own state and only render it into the
+//! text format whenever the `/metrics` endpoint gets scraped.
+
+use std::fmt::{Display, Write};
+
+/// The value of the `Content-Type` header that should accompany the encoded metrics.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricType {
+    Counter,
+    Gauge,
+}
+
+impl Display for MetricType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricType::Counter => write!(f, "counter"),
+            MetricType::Gauge => write!(f, "gauge"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MetricsEncoder {
+    output: String,
+}
+
+impl MetricsEncoder {
+    pub fn new() -> Self {
+        MetricsEncoder::default()
+    }
+
+    /// Writes the `HELP` and `TYPE` lines of a metric family. It must be called before
+    /// any samples of that family are written.
+    pub fn describe(&mut self, name: &str, help: &str, metric_type: MetricType) -> &mut Self {
+        // writing to a `String` can't fail
+        let _ = writeln!(self.output, "# HELP {name} {}", escape_help(help));
+        let _ = writeln!(self.output, "# TYPE {name} {metric_type}");
+        self
+    }
+
+    /// Writes a single sample of a metric family with the provided labels.
+    pub fn sample<V: Display>(
+        &mut self,
+        name: &str,
+        labels: &[(&str, &str)],
+        value: V,
+    ) -> &mut Self {
+        self.output.push_str(name);
+        if !labels.is_empty() {
+            self.output.push('{');
+            for (i, (label, label_value)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.output.push(',');
+                }
+                let _ = write!(
+                    self.output,
+                    "{label}=\"{}\"",
+                    escape_label_value(label_value)
+                );
+            }
+            self.output.push('}');
+        }
+        let _ = writeln!(self.output, " {value}");
+        self
+    }
+
+    /// Convenience method for writing an unlabelled counter.
+    pub fn counter<V: Display>(&mut self, name: &str, help: &str, value: V) -> &mut Self {
+        self.describe(name, help, MetricType::Counter)
+            .sample(name, &[], value)
+    }
+
+    /// Convenience method for writing an unlabelled gauge.
+    pub fn gauge<V: Display>(&mut self, name: &str, help: &str, value: V) -> &mut Self {
+        self.describe(name, help, MetricType::Gauge)
+            .sample(name, &[], value)
+    }
+
+    pub fn finish(self) -> String {
+        self.output
+    }
+}
+
+fn escape_help(help: &str) -> String {
+    help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_unlabelled_metrics() {
+        let mut encoder = MetricsEncoder::new();
+        encoder
+            .counter("packets_total", "Total number of packets.", 42)
+            .gauge("queue_length", "Current length of the queue.", 3);
+
+        let expected = "# HELP packets_total Total number of packets.\n\
+                        # TYPE packets_total counter\n\
+                        packets_total 42\n\
+                        # HELP queue_length Current length of the queue.\n\
+                        # TYPE queue_length gauge\n\
+                        queue_length 3\n";
+        assert_eq!(expected, encoder.finish());
+    }
+
+    #[test]
+    fn encoding_labelled_metrics() {
+        let mut encoder = MetricsEncoder::new();
+        encoder
+            .describe("sent_total", "Sent packets.", MetricType::Counter)
+            .sample("sent_total", &[("peer", "1.2.3.4:1789")], 1)
+            .sample("sent_total", &[("peer", "5.6.7.8:1789"), ("layer", "2")], 2);
+
+        let expected = "# HELP sent_total Sent packets.\n\
+                        # TYPE sent_total counter\n\
+                        sent_total{peer=\"1.2.3.4:1789\"} 1\n\
+                        sent_total{peer=\"5.6.7.8:1789\",layer=\"2\"} 2\n";
+        assert_eq!(expected, encoder.finish());
+    }
+
+    #[test]
+    fn special_characters_are_escaped() {
+        let mut encoder = MetricsEncoder::new();
+        encoder
+            .describe("foo", "multi\nline \\ help", MetricType::Gauge)
+            .sample("foo", &[("bar", "\"quoted\"\n\\")], 1.5);
+
+        let expected = "# HELP foo multi\\nline \\\\ help\n\
+                        # TYPE foo gauge\n\
+                        foo{bar=\"\\\"quoted\\\"\\n\\\\\"} 1.5\n";
+        assert_eq!(expected, encoder.finish());
+    }
+}
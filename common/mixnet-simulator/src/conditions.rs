@@ -0,0 +1,48 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+/// Conditions applied to every packet sent between the nodes of the simulated mixnet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkConditions {
+    /// Probability, within the `[0, 1]` range, of a packet getting lost in transit.
+    pub loss_probability: f64,
+
+    /// Time it takes for a packet to reach the next hop.
+    pub latency: Duration,
+
+    /// Upper bound on the uniformly distributed delay added on top of the `latency`.
+    pub jitter: Duration,
+}
+
+impl Default for LinkConditions {
+    fn default() -> Self {
+        LinkConditions {
+            loss_probability: 0.0,
+            latency: Duration::ZERO,
+            jitter: Duration::ZERO,
+        }
+    }
+}
+
+impl LinkConditions {
+    pub fn with_loss_probability(mut self, loss_probability: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&loss_probability),
+            "loss probability has to be within the [0, 1] range"
+        );
+        self.loss_probability = loss_probability;
+        self
+    }
+
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::network::{PacketReceiver, SimulatedNetwork};
+use async_trait::async_trait;
+use futures::StreamExt;
+use log::*;
+use nym_client_core::client::local_gateway::LocalGateway;
+use nym_crypto::asymmetric::{encryption, identity};
+use nym_gateway_client::error::GatewayClientError;
+use nym_gateway_client::packet_router::PacketRouter;
+use nym_mixnode_common::packet_processor::processor::{
+    MixProcessingResult, ProcessedFinalHop, SphinxPacketProcessor,
+};
+use nym_sphinx::forwarding::packet::MixPacket;
+use nym_sphinx::framing::packet::FramedSphinxPacket;
+use nym_sphinx::DestinationAddressBytes;
+use nym_task::TaskClient;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Clients registered with the gateway alongside the messages of the ones that are not.
+#[derive(Default)]
+struct Inbox {
+    active_clients: HashMap<DestinationAddressBytes, PacketRouter>,
+    stored_messages: HashMap<DestinationAddressBytes, Vec<Vec<u8>>>,
+}
+
+/// Gateway of the simulated mixnet. Clients use it in place of the websocket connection
+/// through the `LocalGateway` trait.
+#[derive(Clone)]
+pub struct SimulatedGateway {
+    identity: identity::PublicKey,
+    address: SocketAddr,
+    network: SimulatedNetwork,
+    inbox: Arc<Mutex<Inbox>>,
+}
+
+impl SimulatedGateway {
+    pub(crate) fn start(
+        identity: identity::PublicKey,
+        address: SocketAddr,
+        sphinx_key: &encryption::PrivateKey,
+        network: SimulatedNetwork,
+        mut shutdown: TaskClient,
+    ) -> Self {
+        // the simulation is torn down all at once, so there's no point in reporting the halt
+        shutdown.mark_as_success();
+
+        let gateway = SimulatedGateway {
+            identity,
+            address,
+            inbox: Default::default(),
+            network,
+        };
+
+        let packet_receiver = gateway.network.register_node(address);
+        let packet_processor = SphinxPacketProcessor::new(sphinx_key.into());
+        let handler = gateway.clone();
+        tokio::spawn(async move {
+            handler
+                .run(packet_processor, packet_receiver, shutdown)
+                .await
+        });
+
+        gateway
+    }
+
+    pub fn identity(&self) -> identity::PublicKey {
+        self.identity
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    fn lock_inbox(&self) -> MutexGuard<'_, Inbox> {
+        self.inbox.lock().expect("gateway inbox lock got poisoned")
+    }
+
+    fn forward_packet(&self, mix_packet: MixPacket) {
+        let next_hop = mix_packet.next_hop();
+        let packet_type = mix_packet.packet_type();
+        self.network.send(
+            self.address,
+            next_hop,
+            mix_packet.into_packet(),
+            packet_type,
+        )
+    }
+
+    // mirrors the behaviour of `ConnectionHandler` of the gateway: the message is pushed to the
+    // client if it's online or otherwise stored until it connects. either way the ack is forwarded
+    fn handle_processed_packet(&self, processed_final_hop: ProcessedFinalHop) {
+        let client_address = processed_final_hop.destination;
+        let message = processed_final_hop.message;
+
+        let mut inbox = self.lock_inbox();
+        match inbox.active_clients.get_mut(&client_address) {
+            Some(packet_router) => {
+                if let Err(err) = packet_router.route_received(vec![message]) {
+                    warn!("failed to push received packet to {client_address} - {err}");
+                    inbox.active_clients.remove(&client_address);
+                } else {
+                    trace!("Pushed received packet to {client_address}")
+                }
+            }
+            None => {
+                trace!("Stored packet for {client_address}");
+                inbox
+                    .stored_messages
+                    .entry(client_address)
+                    .or_default()
+                    .push(message)
+            }
+        }
+        drop(inbox);
+
+        if let Some(forward_ack) = processed_final_hop.forward_ack {
+            self.forward_packet(forward_ack)
+        }
+    }
+
+    fn handle_received_packet(
+        &self,
+        packet_processor: &SphinxPacketProcessor,
+        framed_sphinx_packet: FramedSphinxPacket,
+    ) {
+        if self.network.is_failed(self.address) {
+            trace!("{} has failed - dropping received packet", self.address);
+            return;
+        }
+
+        match packet_processor.process_received(framed_sphinx_packet) {
+            Err(err) => debug!("We failed to process received sphinx packet - {err}"),
+            Ok(MixProcessingResult::ForwardHop(..)) => {
+                debug!("{} has received a forward hop mix packet", self.address)
+            }
+            Ok(MixProcessingResult::FinalHop(processed_final_hop)) => {
+                self.handle_processed_packet(processed_final_hop)
+            }
+        }
+    }
+
+    async fn run(
+        &self,
+        packet_processor: SphinxPacketProcessor,
+        mut packet_receiver: PacketReceiver,
+        mut shutdown: TaskClient,
+    ) {
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = shutdown.recv() => {
+                    log::trace!("SimulatedGateway: received shutdown");
+                }
+                packet = packet_receiver.next() => match packet {
+                    Some(packet) => self.handle_received_packet(&packet_processor, packet),
+                    None => break,
+                }
+            }
+        }
+        log::trace!("SimulatedGateway: Exiting");
+    }
+}
+
+#[async_trait]
+impl LocalGateway for SimulatedGateway {
+    async fn register_client(
+        &mut self,
+        client: identity::PublicKey,
+        mut packet_router: PacketRouter,
+    ) -> Result<(), GatewayClientError> {
+        let client_address = client.derive_destination_address();
+
+        let mut inbox = self.lock_inbox();
+        // just like the real gateway, push everything that was stored while the client was offline
+        if let Some(stored) = inbox.stored_messages.remove(&client_address) {
+            debug!(
+                "pushing {} stored messages to {client_address}",
+                stored.len()
+            );
+            packet_router.route_received(stored)?;
+        }
+        inbox.active_clients.insert(client_address, packet_router);
+
+        Ok(())
+    }
+
+    async fn send_mix_packets(
+        &mut self,
+        packets: Vec<MixPacket>,
+    ) -> Result<(), GatewayClientError> {
+        // if the gateway has failed, the packets are going to be silently dropped by the network
+        for packet in packets {
+            self.forward_packet(packet)
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! In-process mixnet for end-to-end tests of the clients and the nodes.
+//!
+//! The mixnodes and gateways run the same sphinx processing and packet delaying as the real ones,
+//! but exchange the packets through in-memory channels instead of TCP connections.
+//! Clients are regular `BaseClient`s with a hardcoded topology that talk to their gateway directly.
+//!
+//! The simulation is meant to be run on a tokio runtime with paused clock, e.g. by using
+//! `#[tokio::test(start_paused = true)]`, so that any packet delays, retransmission timeouts
+//! or injected link latency do not slow the tests down. Packet loss, latency jitter and the keys
+//! of all the nodes are derived from the provided seed.
+
+pub mod conditions;
+pub mod gateway;
+pub(crate) mod mixnode;
+pub mod network;
+pub mod simulator;
+
+pub use conditions::LinkConditions;
+pub use network::NetworkStats;
+pub use simulator::{MixnetSimulator, MixnetSimulatorBuilder};
@@ -0,0 +1,120 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::network::{PacketReceiver, SimulatedNetwork};
+use futures::StreamExt;
+use log::*;
+use nym_crypto::asymmetric::encryption;
+use nym_mixnode_common::delay_forwarder::{DelayForwarder, PacketDelayForwardSender};
+use nym_mixnode_common::packet_processor::processor::{MixProcessingResult, SphinxPacketProcessor};
+use nym_sphinx::addressing::nodes::NymNodeRoutingAddress;
+use nym_sphinx::framing::packet::FramedSphinxPacket;
+use nym_sphinx::params::PacketType;
+use nym_sphinx::NymPacket;
+use nym_task::TaskClient;
+use std::io;
+use std::net::SocketAddr;
+use tokio::time::Instant;
+
+/// Replacement of the TCP mixnet client pushing the packets through the simulated network instead.
+pub(crate) struct SimulatedMixnetClient {
+    address: SocketAddr,
+    network: SimulatedNetwork,
+}
+
+impl SimulatedMixnetClient {
+    pub(crate) fn new(address: SocketAddr, network: SimulatedNetwork) -> Self {
+        SimulatedMixnetClient { address, network }
+    }
+}
+
+impl nym_mixnet_client::SendWithoutResponse for SimulatedMixnetClient {
+    fn send_without_response(
+        &mut self,
+        address: NymNodeRoutingAddress,
+        packet: NymPacket,
+        packet_type: PacketType,
+    ) -> io::Result<()> {
+        self.network
+            .send(self.address, address, packet, packet_type);
+        Ok(())
+    }
+}
+
+/// Mixnode using the same packet processing and delaying as the real one,
+/// but with all of its connections going through the simulated network.
+pub(crate) struct SimulatedMixnode {
+    address: SocketAddr,
+    network: SimulatedNetwork,
+    packet_processor: SphinxPacketProcessor,
+    delay_forwarding_channel: PacketDelayForwardSender,
+    packet_receiver: PacketReceiver,
+    shutdown: TaskClient,
+}
+
+impl SimulatedMixnode {
+    pub(crate) fn start(
+        address: SocketAddr,
+        sphinx_key: &encryption::PrivateKey,
+        network: SimulatedNetwork,
+        mut shutdown: TaskClient,
+    ) {
+        // the simulation is torn down all at once, so there's no point in reporting the halt
+        shutdown.mark_as_success();
+
+        let mut delay_forwarder = DelayForwarder::new(
+            SimulatedMixnetClient::new(address, network.clone()),
+            (),
+            shutdown.clone(),
+        );
+
+        let mut mixnode = SimulatedMixnode {
+            address,
+            packet_receiver: network.register_node(address),
+            network,
+            packet_processor: SphinxPacketProcessor::new(sphinx_key.into()),
+            delay_forwarding_channel: delay_forwarder.sender(),
+            shutdown,
+        };
+
+        tokio::spawn(async move { delay_forwarder.run().await });
+        tokio::spawn(async move { mixnode.run().await });
+    }
+
+    // mirrors the behaviour of `ConnectionHandler` of the mixnode
+    fn handle_received_packet(&self, framed_sphinx_packet: FramedSphinxPacket) {
+        if self.network.is_failed(self.address) {
+            trace!("{} has failed - dropping received packet", self.address);
+            return;
+        }
+
+        match self.packet_processor.process_received(framed_sphinx_packet) {
+            Err(err) => debug!("We failed to process received sphinx packet - {err}"),
+            Ok(MixProcessingResult::ForwardHop(forward_packet, delay)) => {
+                let forward_instant = delay.map(|delay| Instant::now() + delay.to_duration());
+                self.delay_forwarding_channel
+                    .unbounded_send((forward_packet, forward_instant))
+                    .expect("the delay-forwarder has died!");
+            }
+            Ok(MixProcessingResult::FinalHop(..)) => {
+                warn!("{} has received a final hop packet", self.address)
+            }
+        }
+    }
+
+    async fn run(&mut self) {
+        while !self.shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = self.shutdown.recv() => {
+                    log::trace!("SimulatedMixnode: received shutdown");
+                }
+                packet = self.packet_receiver.next() => match packet {
+                    Some(packet) => self.handle_received_packet(packet),
+                    None => break,
+                }
+            }
+        }
+        log::trace!("SimulatedMixnode: Exiting");
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::conditions::LinkConditions;
+use futures::channel::mpsc;
+use log::*;
+use nym_sphinx::addressing::nodes::NymNodeRoutingAddress;
+use nym_sphinx::framing::packet::FramedSphinxPacket;
+use nym_sphinx::params::PacketType;
+use nym_sphinx::NymPacket;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+pub(crate) type PacketSender = mpsc::UnboundedSender<FramedSphinxPacket>;
+pub(crate) type PacketReceiver = mpsc::UnboundedReceiver<FramedSphinxPacket>;
+
+/// Counters of what happened to the packets sent through the simulated network.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkStats {
+    /// Packets that have been handed over to their next hop.
+    pub transmitted: u64,
+
+    /// Packets that got lost due to the configured loss probability.
+    pub lost: u64,
+
+    /// Packets that were sent either from or to a failed (or unknown) node.
+    pub dropped: u64,
+}
+
+struct NetworkState {
+    nodes: HashMap<SocketAddr, PacketSender>,
+    failed_nodes: HashSet<SocketAddr>,
+    conditions: LinkConditions,
+    rng: StdRng,
+    stats: NetworkStats,
+}
+
+/// In-memory replacement of the TCP connections between the nodes.
+/// All the randomness, i.e. the packet loss and the jitter, is derived from the seeded rng,
+/// so that the same sequence of packets is always affected in the same way.
+#[derive(Clone)]
+pub struct SimulatedNetwork {
+    state: Arc<Mutex<NetworkState>>,
+}
+
+impl SimulatedNetwork {
+    pub(crate) fn new(seed: u64, conditions: LinkConditions) -> Self {
+        SimulatedNetwork {
+            state: Arc::new(Mutex::new(NetworkState {
+                nodes: HashMap::new(),
+                failed_nodes: HashSet::new(),
+                conditions,
+                rng: StdRng::seed_from_u64(seed),
+                stats: NetworkStats::default(),
+            })),
+        }
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, NetworkState> {
+        self.state.lock().expect("network state lock got poisoned")
+    }
+
+    pub(crate) fn register_node(&self, address: SocketAddr) -> PacketReceiver {
+        let (packet_sender, packet_receiver) = mpsc::unbounded();
+        self.lock_state().nodes.insert(address, packet_sender);
+        packet_receiver
+    }
+
+    pub fn set_link_conditions(&self, conditions: LinkConditions) {
+        self.lock_state().conditions = conditions
+    }
+
+    /// Makes the node stop receiving and sending any packets. Packets that are currently delayed
+    /// by the node are going to be dropped once they get forwarded.
+    pub fn fail_node(&self, address: SocketAddr) {
+        debug!("failing node {address}");
+        self.lock_state().failed_nodes.insert(address);
+    }
+
+    pub fn restore_node(&self, address: SocketAddr) {
+        debug!("restoring node {address}");
+        self.lock_state().failed_nodes.remove(&address);
+    }
+
+    pub fn is_failed(&self, address: SocketAddr) -> bool {
+        self.lock_state().failed_nodes.contains(&address)
+    }
+
+    pub fn stats(&self) -> NetworkStats {
+        self.lock_state().stats
+    }
+
+    fn packet_delay(rng: &mut StdRng, conditions: &LinkConditions) -> Duration {
+        if conditions.jitter.is_zero() {
+            conditions.latency
+        } else {
+            conditions.latency + conditions.jitter.mul_f64(rng.gen())
+        }
+    }
+
+    /// Sends the packet from the node at `source` to its next hop,
+    /// subject to the current link conditions.
+    pub(crate) fn send(
+        &self,
+        source: SocketAddr,
+        next_hop: NymNodeRoutingAddress,
+        packet: NymPacket,
+        packet_type: PacketType,
+    ) {
+        let destination = SocketAddr::from(next_hop);
+
+        let mut guard = self.lock_state();
+        if guard.failed_nodes.contains(&source) || guard.failed_nodes.contains(&destination) {
+            trace!("dropping packet from {source} to {destination} as one of them has failed");
+            guard.stats.dropped += 1;
+            return;
+        }
+
+        let Some(sender) = guard.nodes.get(&destination).cloned() else {
+            debug!("dropping packet from {source} to unknown node {destination}");
+            guard.stats.dropped += 1;
+            return;
+        };
+
+        let conditions = guard.conditions;
+        if conditions.loss_probability > 0.0 && guard.rng.gen_bool(conditions.loss_probability) {
+            trace!("packet from {source} to {destination} got lost");
+            guard.stats.lost += 1;
+            return;
+        }
+
+        let delay = Self::packet_delay(&mut guard.rng, &conditions);
+        guard.stats.transmitted += 1;
+        drop(guard);
+
+        let framed_packet = FramedSphinxPacket::new(packet, packet_type, false);
+        if delay.is_zero() {
+            // the receiver is only gone if the simulation is being torn down
+            sender.unbounded_send(framed_packet).ok();
+        } else {
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                sender.unbounded_send(framed_packet).ok();
+            });
+        }
+    }
+}
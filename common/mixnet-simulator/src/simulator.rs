@@ -0,0 +1,239 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::conditions::LinkConditions;
+use crate::gateway::SimulatedGateway;
+use crate::mixnode::SimulatedMixnode;
+use crate::network::{NetworkStats, SimulatedNetwork};
+use nym_client_core::client::base_client::storage::{Ephemeral, MixnetClientStorage};
+use nym_client_core::client::base_client::{BaseClient, BaseClientBuilder, CredentialsToggle};
+use nym_client_core::config::{DebugConfig, GatewayEndpointConfig};
+use nym_client_core::error::ClientCoreError;
+use nym_crypto::asymmetric::{encryption, identity};
+use nym_task::TaskManager;
+use nym_topology::mix::Layer;
+use nym_topology::provider_trait::HardcodedTopologyProvider;
+use nym_topology::{gateway, mix, NetworkAddress, NymTopology};
+use nym_validator_client::nyxd::QueryNyxdClient;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+const MIX_PORT: u16 = 1789;
+const CLIENTS_PORT: u16 = 9000;
+const SIMULATED_OWNER: &str = "n1simulated";
+
+// the simulated clients never talk to the chain, this is only required to satisfy the bounds
+type Client = nym_validator_client::Client<QueryNyxdClient>;
+
+pub struct MixnetSimulatorBuilder {
+    seed: u64,
+    mixnodes_per_layer: u8,
+    gateways: u8,
+    link_conditions: LinkConditions,
+}
+
+impl MixnetSimulatorBuilder {
+    /// Creates a builder of a network with a single mixnode per layer and a single gateway.
+    /// The `seed` determines the keys of all the nodes as well as the behaviour of the links.
+    pub fn new(seed: u64) -> Self {
+        MixnetSimulatorBuilder {
+            seed,
+            mixnodes_per_layer: 1,
+            gateways: 1,
+            link_conditions: Default::default(),
+        }
+    }
+
+    pub fn mixnodes_per_layer(mut self, mixnodes_per_layer: u8) -> Self {
+        assert!(mixnodes_per_layer > 0, "every layer needs a mixnode");
+        self.mixnodes_per_layer = mixnodes_per_layer;
+        self
+    }
+
+    pub fn gateways(mut self, gateways: u8) -> Self {
+        assert!(gateways > 0, "the network needs a gateway");
+        self.gateways = gateways;
+        self
+    }
+
+    pub fn link_conditions(mut self, link_conditions: LinkConditions) -> Self {
+        self.link_conditions = link_conditions;
+        self
+    }
+
+    /// Starts all the nodes. It has to be called from within the tokio runtime,
+    /// which for reproducible results should have its clock paused.
+    pub fn build(self) -> MixnetSimulator {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let network = SimulatedNetwork::new(rng.next_u64(), self.link_conditions);
+        let task_manager = TaskManager::default();
+
+        let mut mixes = BTreeMap::new();
+        for layer in 1..=3 {
+            let mut layer_nodes = Vec::with_capacity(self.mixnodes_per_layer as usize);
+            for i in 0..self.mixnodes_per_layer {
+                let identity_keys = identity::KeyPair::new(&mut rng);
+                let sphinx_keys = encryption::KeyPair::new(&mut rng);
+                let ip = IpAddr::V4(Ipv4Addr::new(10, layer, 0, i + 1));
+                let mix_host = SocketAddr::new(ip, MIX_PORT);
+
+                SimulatedMixnode::start(
+                    mix_host,
+                    sphinx_keys.private_key(),
+                    network.clone(),
+                    task_manager.subscribe(),
+                );
+
+                layer_nodes.push(mix::Node {
+                    mix_id: (layer as u32 - 1) * self.mixnodes_per_layer as u32 + i as u32 + 1,
+                    owner: SIMULATED_OWNER.to_string(),
+                    host: NetworkAddress::IpAddr(ip),
+                    mix_host,
+                    identity_key: *identity_keys.public_key(),
+                    sphinx_key: *sphinx_keys.public_key(),
+                    sphinx_key_validity: None,
+                    announced_sphinx_keys: None,
+                    layer: Layer::try_from(layer).unwrap(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                });
+            }
+            mixes.insert(layer, layer_nodes);
+        }
+
+        let mut gateways = Vec::with_capacity(self.gateways as usize);
+        let mut gateway_nodes = Vec::with_capacity(self.gateways as usize);
+        for i in 0..self.gateways {
+            let identity_keys = identity::KeyPair::new(&mut rng);
+            let sphinx_keys = encryption::KeyPair::new(&mut rng);
+            let ip = IpAddr::V4(Ipv4Addr::new(10, 100, 0, i + 1));
+            let mix_host = SocketAddr::new(ip, MIX_PORT);
+
+            gateways.push(SimulatedGateway::start(
+                *identity_keys.public_key(),
+                mix_host,
+                sphinx_keys.private_key(),
+                network.clone(),
+                task_manager.subscribe(),
+            ));
+
+            gateway_nodes.push(gateway::Node {
+                owner: SIMULATED_OWNER.to_string(),
+                host: NetworkAddress::IpAddr(ip),
+                mix_host,
+                clients_port: CLIENTS_PORT,
+                identity_key: *identity_keys.public_key(),
+                sphinx_key: *sphinx_keys.public_key(),
+                sphinx_key_validity: None,
+                announced_sphinx_keys: None,
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            });
+        }
+
+        MixnetSimulator {
+            topology: NymTopology::new(mixes, gateway_nodes),
+            network,
+            gateways,
+            _task_manager: task_manager,
+        }
+    }
+}
+
+/// Mixnet consisting of the nodes running within the current process that are connected
+/// through the in-memory network. All the nodes get stopped once it is dropped.
+pub struct MixnetSimulator {
+    topology: NymTopology,
+    network: SimulatedNetwork,
+    gateways: Vec<SimulatedGateway>,
+
+    // dropping the manager signals the shutdown to all the nodes
+    _task_manager: TaskManager,
+}
+
+impl MixnetSimulator {
+    pub fn builder(seed: u64) -> MixnetSimulatorBuilder {
+        MixnetSimulatorBuilder::new(seed)
+    }
+
+    pub fn topology(&self) -> &NymTopology {
+        &self.topology
+    }
+
+    pub fn gateways(&self) -> &[SimulatedGateway] {
+        &self.gateways
+    }
+
+    pub fn set_link_conditions(&self, link_conditions: LinkConditions) {
+        self.network.set_link_conditions(link_conditions)
+    }
+
+    /// Makes the node at the provided address (as specified in the topology) unreachable.
+    pub fn fail_node(&self, address: SocketAddr) {
+        self.network.fail_node(address)
+    }
+
+    pub fn restore_node(&self, address: SocketAddr) {
+        self.network.restore_node(address)
+    }
+
+    pub fn network_stats(&self) -> NetworkStats {
+        self.network.stats()
+    }
+
+    /// Configuration of the simulated clients. The cover traffic and the poisson distribution of
+    /// the real packets are disabled, as otherwise, under the paused clock, the clients would
+    /// keep on generating the packets whenever the runtime is idle.
+    pub fn default_client_debug_config() -> DebugConfig {
+        let mut debug_config = DebugConfig::default();
+        debug_config
+            .traffic
+            .disable_main_poisson_packet_distribution = true;
+        debug_config.cover_traffic.disable_loop_cover_traffic_stream = true;
+        debug_config.topology.disable_refreshing = true;
+        debug_config
+    }
+
+    /// Starts a client registered with the gateway at the specified index
+    /// using the default simulation configuration.
+    pub async fn start_client(&self, gateway: usize) -> Result<BaseClient, ClientCoreError> {
+        self.start_client_with_config(gateway, &Self::default_client_debug_config())
+            .await
+    }
+
+    /// Starts a client registered with the gateway at the specified index.
+    /// It uses ephemeral storage and the topology of the simulated network.
+    pub async fn start_client_with_config(
+        &self,
+        gateway: usize,
+        debug_config: &DebugConfig,
+    ) -> Result<BaseClient, ClientCoreError> {
+        let local_gateway = self.gateways[gateway].clone();
+        let gateway_node = &self.topology.gateways()[gateway];
+        let gateway_config = GatewayEndpointConfig {
+            gateway_id: local_gateway.identity().to_base58_string(),
+            gateway_owner: gateway_node.owner.clone(),
+            gateway_listener: gateway_node.clients_address(),
+        };
+
+        let (key_store, reply_storage_backend, _) = Ephemeral::default().into_split();
+        let base_builder: BaseClientBuilder<Client, Ephemeral> = BaseClientBuilder::new(
+            &gateway_config,
+            debug_config,
+            key_store,
+            None,
+            reply_storage_backend,
+            CredentialsToggle::Disabled,
+            Vec::new(),
+        );
+
+        base_builder
+            .with_topology_provider(Box::new(HardcodedTopologyProvider::new(
+                self.topology.clone(),
+            )))
+            .with_local_gateway(Box::new(local_gateway))
+            .start_base()
+            .await
+    }
+}
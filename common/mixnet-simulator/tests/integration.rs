@@ -0,0 +1,192 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::StreamExt;
+use nym_client_core::client::base_client::{BaseClient, ClientInput};
+use nym_client_core::client::inbound_messages::InputMessage;
+use nym_client_core::client::received_buffer::ReconstructedMessagesReceiver;
+use nym_mixnet_simulator::{LinkConditions, MixnetSimulator};
+use nym_sphinx::receiver::ReconstructedMessage;
+use nym_task::connections::TransmissionLane;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+// the clock is paused, so this only gets hit if the message is never going to arrive
+const RECEIVE_TIMEOUT: Duration = Duration::from_secs(3600);
+
+struct TestClient {
+    // keeps all the client tasks alive
+    client: BaseClient,
+    input: ClientInput,
+    receiver: ReconstructedMessagesReceiver,
+    received: VecDeque<ReconstructedMessage>,
+}
+
+impl TestClient {
+    async fn start(simulator: &MixnetSimulator, gateway: usize) -> Self {
+        let mut client = simulator.start_client(gateway).await.unwrap();
+        let input = client.client_input.register_producer();
+        let receiver = client
+            .client_output
+            .register_consumer()
+            .register_receiver()
+            .unwrap();
+
+        TestClient {
+            client,
+            input,
+            receiver,
+            received: VecDeque::new(),
+        }
+    }
+
+    async fn send(&self, message: InputMessage) {
+        self.input.send(message).await.unwrap()
+    }
+
+    async fn receive(&mut self) -> ReconstructedMessage {
+        while self.received.is_empty() {
+            let messages = tokio::time::timeout(RECEIVE_TIMEOUT, self.receiver.next())
+                .await
+                .expect("the message has not been delivered")
+                .expect("the client has shut down");
+            self.received.extend(messages)
+        }
+        self.received.pop_front().unwrap()
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn messages_are_delivered_between_clients() {
+    let simulator = MixnetSimulator::builder(42).gateways(2).build();
+    let sender = TestClient::start(&simulator, 0).await;
+    let mut recipient = TestClient::start(&simulator, 1).await;
+
+    sender
+        .send(InputMessage::new_regular(
+            recipient.client.address,
+            b"hello".to_vec(),
+            TransmissionLane::General,
+        ))
+        .await;
+
+    let received = recipient.receive().await;
+    assert_eq!(received.message, b"hello");
+    assert!(received.sender_tag.is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn lost_packets_are_retransmitted() {
+    let link_conditions = LinkConditions::default()
+        .with_loss_probability(0.2)
+        .with_latency(Duration::from_millis(20))
+        .with_jitter(Duration::from_millis(10));
+    let simulator = MixnetSimulator::builder(42)
+        .link_conditions(link_conditions)
+        .build();
+    let sender = TestClient::start(&simulator, 0).await;
+    let mut recipient = TestClient::start(&simulator, 0).await;
+
+    // big enough to get split into multiple packets
+    let message = vec![42u8; 10_000];
+    sender
+        .send(InputMessage::new_regular(
+            recipient.client.address,
+            message.clone(),
+            TransmissionLane::General,
+        ))
+        .await;
+
+    assert_eq!(recipient.receive().await.message, message);
+    assert!(simulator.network_stats().lost > 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn replies_are_sent_using_surbs() {
+    let simulator = MixnetSimulator::builder(42).gateways(2).build();
+    let mut sender = TestClient::start(&simulator, 0).await;
+    let mut recipient = TestClient::start(&simulator, 1).await;
+
+    sender
+        .send(InputMessage::new_anonymous(
+            recipient.client.address,
+            b"who am I?".to_vec(),
+            10,
+            TransmissionLane::General,
+        ))
+        .await;
+
+    let received = recipient.receive().await;
+    assert_eq!(received.message, b"who am I?");
+    let sender_tag = received
+        .sender_tag
+        .expect("anonymous message without the sender tag");
+
+    recipient
+        .send(InputMessage::new_reply(
+            sender_tag,
+            b"no idea".to_vec(),
+            TransmissionLane::General,
+        ))
+        .await;
+
+    assert_eq!(sender.receive().await.message, b"no idea");
+}
+
+#[tokio::test(start_paused = true)]
+async fn messages_are_routed_around_failed_mixnode() {
+    let simulator = MixnetSimulator::builder(42).mixnodes_per_layer(2).build();
+    let failed = simulator.topology().mixes_in_layer(2)[0].mix_host;
+    simulator.fail_node(failed);
+
+    let sender = TestClient::start(&simulator, 0).await;
+    let mut recipient = TestClient::start(&simulator, 0).await;
+
+    for i in 0..5u8 {
+        sender
+            .send(InputMessage::new_regular(
+                recipient.client.address,
+                vec![i; 100],
+                TransmissionLane::General,
+            ))
+            .await;
+    }
+
+    let mut received = Vec::new();
+    while received.len() < 5 {
+        let message = recipient.receive().await.message;
+        if !received.contains(&message) {
+            received.push(message)
+        }
+    }
+    received.sort();
+    assert_eq!(received, (0..5u8).map(|i| vec![i; 100]).collect::<Vec<_>>());
+}
+
+#[tokio::test(start_paused = true)]
+async fn messages_are_delivered_once_gateway_is_restored() {
+    let simulator = MixnetSimulator::builder(42).gateways(2).build();
+    let sender = TestClient::start(&simulator, 0).await;
+    let mut recipient = TestClient::start(&simulator, 1).await;
+
+    let recipient_gateway = simulator.gateways()[1].address();
+    simulator.fail_node(recipient_gateway);
+
+    sender
+        .send(InputMessage::new_regular(
+            recipient.client.address,
+            b"are you there?".to_vec(),
+            TransmissionLane::General,
+        ))
+        .await;
+
+    // nothing can get through while the gateway is down
+    assert!(
+        tokio::time::timeout(Duration::from_secs(30), recipient.receiver.next())
+            .await
+            .is_err()
+    );
+
+    simulator.restore_node(recipient_gateway);
+    assert_eq!(recipient.receive().await.message, b"are you there?");
+}
@@ -1,69 +1,100 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::node::node_statistics::UpdateSender;
 use futures::channel::mpsc;
 use futures::StreamExt;
 use nym_nonexhaustive_delayqueue::{Expired, NonExhaustiveDelayQueue};
-use nym_sphinx::forwarding::packet::MixPacket;
+use nym_sphinx_forwarding::packet::MixPacket;
+use nym_task::TaskClient;
 use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use tokio::time::Instant;
 
-use super::TaskClient;
-
 // Delay + MixPacket vs Instant + MixPacket
 
 // rather than using Duration directly, we use an Instant, this way we minimise skew due to
 // time packet spent waiting in the queue to get delayed
-pub(crate) type PacketDelayForwardSender = mpsc::UnboundedSender<(MixPacket, Option<Instant>)>;
+pub type PacketDelayForwardSender = mpsc::UnboundedSender<(MixPacket, Option<Instant>)>;
 type PacketDelayForwardReceiver = mpsc::UnboundedReceiver<(MixPacket, Option<Instant>)>;
 
+/// Number of packets that are currently being delayed by the `DelayForwarder`.
+#[derive(Clone, Default)]
+pub struct DelayQueueLength(Arc<AtomicUsize>);
+
+impl DelayQueueLength {
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn set(&self, length: usize) {
+        self.0.store(length, Ordering::Relaxed)
+    }
+}
+
+/// Receiver of the results of forwarding the delayed packets, such as the node statistics.
+pub trait ForwardingStatsReporter {
+    fn report_sent(&self, destination: String);
+
+    fn report_dropped(&self, destination: String);
+}
+
+impl ForwardingStatsReporter for () {
+    fn report_sent(&self, _destination: String) {}
+
+    fn report_dropped(&self, _destination: String) {}
+}
+
 /// Entity responsible for delaying received sphinx packet and forwarding it to next node.
-pub(crate) struct DelayForwarder<C>
+pub struct DelayForwarder<C, R>
 where
     C: nym_mixnet_client::SendWithoutResponse,
+    R: ForwardingStatsReporter,
 {
     delay_queue: NonExhaustiveDelayQueue<MixPacket>,
     mixnet_client: C,
     packet_sender: PacketDelayForwardSender,
     packet_receiver: PacketDelayForwardReceiver,
-    node_stats_update_sender: UpdateSender,
+    node_stats_update_sender: R,
+    delay_queue_length: DelayQueueLength,
     shutdown: TaskClient,
 }
 
-impl<C> DelayForwarder<C>
+impl<C, R> DelayForwarder<C, R>
 where
     C: nym_mixnet_client::SendWithoutResponse,
+    R: ForwardingStatsReporter,
 {
-    pub(crate) fn new(
-        client: C,
-        node_stats_update_sender: UpdateSender,
-        shutdown: TaskClient,
-    ) -> DelayForwarder<C> {
+    pub fn new(client: C, node_stats_update_sender: R, shutdown: TaskClient) -> Self {
         let (packet_sender, packet_receiver) = mpsc::unbounded();
 
-        DelayForwarder::<C> {
+        DelayForwarder {
             delay_queue: NonExhaustiveDelayQueue::new(),
             mixnet_client: client,
             packet_sender,
             packet_receiver,
             node_stats_update_sender,
+            delay_queue_length: DelayQueueLength::default(),
             shutdown,
         }
     }
 
-    pub(crate) fn sender(&self) -> PacketDelayForwardSender {
+    pub fn sender(&self) -> PacketDelayForwardSender {
         self.packet_sender.clone()
     }
 
+    pub fn delay_queue_length(&self) -> DelayQueueLength {
+        self.delay_queue_length.clone()
+    }
+
     fn forward_packet(&mut self, packet: MixPacket) {
         let next_hop = packet.next_hop();
-        let packet_mode = packet.packet_mode();
-        let sphinx_packet = packet.into_sphinx_packet();
+        let packet_type = packet.packet_type();
+        let nym_packet = packet.into_packet();
 
         if let Err(err) =
             self.mixnet_client
-                .send_without_response(next_hop, sphinx_packet, packet_mode)
+                .send_without_response(next_hop, nym_packet, packet_type)
         {
             if err.kind() == io::ErrorKind::WouldBlock {
                 // we only know for sure if we dropped a packet if our sending queue was full
@@ -84,6 +115,7 @@ where
 
     /// Upon packet being finished getting delayed, forward it to the mixnet.
     fn handle_done_delaying(&mut self, packet: Expired<MixPacket>) {
+        self.delay_queue_length.set(self.delay_queue.len());
         let delayed_packet = packet.into_inner();
         self.forward_packet(delayed_packet)
     }
@@ -98,13 +130,14 @@ where
                 self.forward_packet(new_packet.0)
             } else {
                 self.delay_queue.insert_at(new_packet.0, instant);
+                self.delay_queue_length.set(self.delay_queue.len());
             }
         } else {
             self.forward_packet(new_packet.0)
         }
     }
 
-    pub(crate) async fn run(&mut self) {
+    pub async fn run(&mut self) {
         log::trace!("Starting DelayForwarder");
         loop {
             tokio::select! {
@@ -136,36 +169,51 @@ mod tests {
 
     use nym_task::TaskManager;
 
-    use nym_sphinx::addressing::nodes::NymNodeRoutingAddress;
+    use nym_sphinx_addressing::nodes::NymNodeRoutingAddress;
     use nym_sphinx_params::packet_sizes::PacketSize;
-    use nym_sphinx_params::PacketMode;
+    use nym_sphinx_params::PacketType;
     use nym_sphinx_types::builder::SphinxPacketBuilder;
     use nym_sphinx_types::{
         crypto, Delay as SphinxDelay, Destination, DestinationAddressBytes, Node, NodeAddressBytes,
-        SphinxPacket, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
+        NymPacket, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
     };
 
+    #[derive(Default)]
+    struct TestReporter {
+        sent: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl ForwardingStatsReporter for TestReporter {
+        fn report_sent(&self, destination: String) {
+            self.sent.lock().unwrap().push(destination)
+        }
+
+        fn report_dropped(&self, _destination: String) {
+            panic!("no packets should have been dropped")
+        }
+    }
+
     #[derive(Default)]
     struct TestClient {
-        pub packets_sent: Arc<Mutex<Vec<(NymNodeRoutingAddress, SphinxPacket, PacketMode)>>>,
+        pub packets_sent: Arc<Mutex<Vec<(NymNodeRoutingAddress, NymPacket, PacketType)>>>,
     }
 
     impl nym_mixnet_client::SendWithoutResponse for TestClient {
         fn send_without_response(
             &mut self,
             address: NymNodeRoutingAddress,
-            packet: SphinxPacket,
-            packet_mode: PacketMode,
+            packet: NymPacket,
+            packet_type: PacketType,
         ) -> io::Result<()> {
             self.packets_sent
                 .lock()
                 .unwrap()
-                .push((address, packet, packet_mode));
+                .push((address, packet, packet_type));
             Ok(())
         }
     }
 
-    fn make_valid_sphinx_packet(size: PacketSize) -> SphinxPacket {
+    fn make_valid_sphinx_packet(size: PacketSize) -> NymPacket {
         let (_, node1_pk) = crypto::keygen();
         let node1 = Node::new(
             NodeAddressBytes::from_bytes([5u8; NODE_ADDRESS_LENGTH]),
@@ -196,13 +244,14 @@ mod tests {
             .with_payload_size(size.payload_size())
             .build_packet(b"foomp", &route, &destination, &delays)
             .unwrap()
+            .into()
     }
 
     #[tokio::test]
     async fn packets_received_are_forwarded() {
         // Wire up the DelayForwarder
-        let (stats_sender, _stats_receiver) = mpsc::unbounded();
-        let node_stats_update_sender = UpdateSender::new(stats_sender);
+        let node_stats_update_sender = TestReporter::default();
+        let reported_sent = node_stats_update_sender.sent.clone();
         let client = TestClient::default();
         let client_packets_sent = client.packets_sent.clone();
         let shutdown = TaskManager::default();
@@ -219,7 +268,7 @@ mod tests {
         let mix_packet = MixPacket::new(
             next_hop,
             make_valid_sphinx_packet(PacketSize::default()),
-            PacketMode::default(),
+            PacketType::default(),
         );
         let forward_instant = None;
         packet_sender
@@ -239,5 +288,6 @@ mod tests {
                 .collect::<Vec<_>>(),
             vec![next_hop]
         );
+        assert_eq!(*reported_sent.lock().unwrap(), vec![next_hop.to_string()]);
     }
 }
@@ -1,6 +1,9 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
+pub mod delay_forwarder;
+pub mod noise_keys;
 pub mod packet_processor;
+pub mod sphinx_key_rotation;
 pub mod verloc;
 
 pub fn cpu_cycles() -> Result<i64, Box<dyn std::error::Error>> {
@@ -0,0 +1,175 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Periodic refresh of the keys used for authenticating the noise connections between the nodes.
+//! They are derived from the identity keys of all the bonded mixnodes and gateways,
+//! so that no additional information has to be published by the nodes.
+
+use log::*;
+use nym_crypto::asymmetric::{encryption, identity};
+use nym_mixnet_client::NoiseNodeKeys;
+use nym_task::TaskClient;
+use nym_validator_client::NymApiClient;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::time::sleep;
+use url::Url;
+
+/// Publicly reachable mix address of a node alongside its identity key.
+struct NodeEntry {
+    host: String,
+    mix_port: u16,
+    identity_key: String,
+}
+
+pub struct NoiseKeysRefresher {
+    node_keys: NoiseNodeKeys,
+    nym_api_clients: Vec<NymApiClient>,
+    refresh_interval: Duration,
+    shutdown_listener: TaskClient,
+}
+
+impl NoiseKeysRefresher {
+    pub fn new(
+        node_keys: NoiseNodeKeys,
+        nym_api_urls: Vec<Url>,
+        refresh_interval: Duration,
+        shutdown_listener: TaskClient,
+    ) -> Self {
+        NoiseKeysRefresher {
+            node_keys,
+            nym_api_clients: nym_api_urls.into_iter().map(NymApiClient::new).collect(),
+            refresh_interval,
+            shutdown_listener,
+        }
+    }
+
+    async fn fetch_nodes(&self) -> Option<Vec<NodeEntry>> {
+        for client in &self.nym_api_clients {
+            let mixnodes = match client.get_cached_mixnodes().await {
+                Ok(mixnodes) => mixnodes,
+                Err(err) => {
+                    warn!(
+                        "failed to obtain the mixnodes from {} - {err}",
+                        client.nym_api_client.current_url()
+                    );
+                    continue;
+                }
+            };
+            let gateways = match client.get_cached_gateways().await {
+                Ok(gateways) => gateways,
+                Err(err) => {
+                    warn!(
+                        "failed to obtain the gateways from {} - {err}",
+                        client.nym_api_client.current_url()
+                    );
+                    continue;
+                }
+            };
+
+            let mixnodes = mixnodes.into_iter().map(|details| {
+                let mix_node = details.bond_information.mix_node;
+                NodeEntry {
+                    host: mix_node.host,
+                    mix_port: mix_node.mix_port,
+                    identity_key: mix_node.identity_key,
+                }
+            });
+            let gateways = gateways.into_iter().map(|bond| NodeEntry {
+                host: bond.gateway.host,
+                mix_port: bond.gateway.mix_port,
+                identity_key: bond.gateway.identity_key,
+            });
+
+            return Some(mixnodes.chain(gateways).collect());
+        }
+        None
+    }
+
+    async fn refresh(&self) {
+        let Some(nodes) = self.fetch_nodes().await else {
+            warn!(
+                "could not obtain the list of nodes - the noise keys are not going to be refreshed"
+            );
+            return;
+        };
+
+        let mut keys = HashMap::with_capacity(nodes.len());
+        for node in nodes {
+            let Some(noise_key) = noise_key(&node.identity_key) else {
+                debug!("node {} has a malformed identity key", node.identity_key);
+                continue;
+            };
+            match resolve_mix_address(&node.host, node.mix_port).await {
+                Some(address) => {
+                    keys.insert(address, noise_key);
+                }
+                None => debug!(
+                    "failed to resolve the mix address of {} ({}:{})",
+                    node.identity_key, node.host, node.mix_port
+                ),
+            }
+        }
+
+        debug!("refreshed noise keys of {} nodes", keys.len());
+        self.node_keys.update(keys);
+    }
+
+    pub async fn run(&mut self) {
+        info!("Starting noise keys refresher");
+
+        while !self.shutdown_listener.is_shutdown() {
+            self.refresh().await;
+
+            tokio::select! {
+                _ = sleep(self.refresh_interval) => {},
+                _ = self.shutdown_listener.recv() => {
+                    log::trace!("Shutdown received while sleeping");
+                }
+            }
+        }
+
+        log::trace!("NoiseKeysRefresher: Exiting");
+    }
+}
+
+fn noise_key(identity_key: &str) -> Option<encryption::PublicKey> {
+    identity::PublicKey::from_base58_string(identity_key)
+        .ok()
+        .map(|identity_key| identity_key.to_x25519())
+}
+
+async fn resolve_mix_address(host: &str, mix_port: u16) -> Option<SocketAddr> {
+    if let Ok(ip) = host.parse() {
+        return Some(SocketAddr::new(ip, mix_port));
+    }
+    tokio::net::lookup_host((host, mix_port)).await.ok()?.next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn noise_key_is_derived_from_valid_identity() {
+        let private = identity::PrivateKey::from_bytes(&[42u8; 32]).unwrap();
+        let public = identity::PublicKey::from(&private);
+
+        let derived = noise_key(&public.to_base58_string()).unwrap();
+        assert_eq!(derived, encryption::PublicKey::from(&private.to_x25519()));
+        assert!(noise_key("definitely not a valid key").is_none());
+    }
+
+    #[tokio::test]
+    async fn ip_hosts_are_not_resolved() {
+        assert_eq!(
+            resolve_mix_address("1.2.3.4", 1789).await,
+            Some("1.2.3.4:1789".parse().unwrap())
+        );
+        assert_eq!(
+            resolve_mix_address("::1", 1789).await,
+            Some("[::1]:1789".parse().unwrap())
+        );
+    }
+}
@@ -3,7 +3,7 @@
 
 use nym_sphinx_acknowledgements::surb_ack::SurbAckRecoveryError;
 use nym_sphinx_addressing::nodes::NymNodeRoutingAddressError;
-use nym_sphinx_types::Error as SphinxError;
+use nym_sphinx_types::{Error as SphinxError, NymPacketError, OutfoxError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -11,6 +11,9 @@ pub enum MixProcessingError {
     #[error("failed to process received packet: {0}")]
     SphinxProcessingError(#[from] SphinxError),
 
+    #[error("failed to process received outfox packet: {0}")]
+    OutfoxProcessingError(#[from] OutfoxError),
+
     #[error("the forward hop address was malformed: {0}")]
     InvalidForwardHopAddress(#[from] NymNodeRoutingAddressError),
 
@@ -22,4 +25,22 @@ pub enum MixProcessingError {
 
     #[error("the received packet was set to use the very old and very much deprecated 'VPN' mode")]
     ReceivedOldTypeVpnPacket,
+
+    #[error("the received packet has already been processed before")]
+    ReplayedPacket,
+}
+
+impl From<NymPacketError> for MixProcessingError {
+    fn from(err: NymPacketError) -> Self {
+        match err {
+            NymPacketError::Sphinx(err) => MixProcessingError::SphinxProcessingError(err),
+            NymPacketError::Outfox(err) => MixProcessingError::OutfoxProcessingError(err),
+        }
+    }
+}
+
+impl MixProcessingError {
+    pub fn is_replay(&self) -> bool {
+        matches!(self, MixProcessingError::ReplayedPacket)
+    }
 }
@@ -3,18 +3,20 @@
 
 use crate::measure;
 use crate::packet_processor::error::MixProcessingError;
+use crate::packet_processor::replay::{ReplayDetector, ReplayTag};
 use log::*;
 use nym_sphinx_acknowledgements::surb_ack::SurbAck;
 use nym_sphinx_addressing::nodes::NymNodeRoutingAddress;
 use nym_sphinx_forwarding::packet::MixPacket;
 use nym_sphinx_framing::packet::FramedSphinxPacket;
-use nym_sphinx_params::{PacketMode, PacketSize};
+use nym_sphinx_params::{PacketSize, PacketType};
 use nym_sphinx_types::{
-    Delay as SphinxDelay, DestinationAddressBytes, NodeAddressBytes, Payload, PrivateKey,
-    ProcessedPacket, SphinxPacket,
+    Delay as SphinxDelay, DestinationAddressBytes, NodeAddressBytes, NymPacket, NymProcessedPacket,
+    OutfoxPacket, OutfoxRoutingInformation, Payload, PrivateKey, ProcessedPacket,
 };
 use std::convert::TryFrom;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 #[cfg(feature = "cpucycles")]
 use tracing::instrument;
 
@@ -34,18 +36,155 @@ pub enum MixProcessingResult {
     FinalHop(ProcessedFinalHop),
 }
 
+/// Sphinx key of this node alongside the tags of all packets that were processed using it.
+struct SphinxKey {
+    private_key: Arc<PrivateKey>,
+    replay_detector: ReplayDetector,
+
+    /// Instant after which packets are no longer accepted under this key.
+    /// It is only set for keys that got rotated out.
+    expiration: Option<Instant>,
+}
+
+impl SphinxKey {
+    fn new(private_key: PrivateKey, replay_detector: ReplayDetector) -> Self {
+        SphinxKey {
+            private_key: Arc::new(private_key),
+            replay_detector,
+            expiration: None,
+        }
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expiration
+            .map(|expiration| expiration <= now)
+            .unwrap_or_default()
+    }
+
+    fn expire_at(&mut self, expiration: Instant) {
+        self.expiration = Some(
+            self.expiration
+                .map(|current| current.min(expiration))
+                .unwrap_or(expiration),
+        )
+    }
+}
+
 #[derive(Clone)]
 pub struct SphinxPacketProcessor {
-    /// Private sphinx key of this node required to unwrap received sphinx packet.
-    sphinx_key: Arc<PrivateKey>,
+    /// Private sphinx keys of this node required to unwrap received sphinx packets.
+    /// The first key is the primary one, i.e. the key currently announced to the network,
+    /// while the remaining ones are either upcoming keys or old keys still within their grace period.
+    // note: each key has its own replay detector as tags are only meaningful within the context
+    // of a single key. This way the tags are also forgotten alongside the key itself.
+    sphinx_keys: Arc<RwLock<Vec<SphinxKey>>>,
 }
 
 impl SphinxPacketProcessor {
     /// Creates new instance of `CachedPacketProcessor`
     pub fn new(sphinx_key: PrivateKey) -> Self {
+        Self::new_with_replay_detector(sphinx_key, ReplayDetector::default())
+    }
+
+    /// Creates new instance of `CachedPacketProcessor` using the provided replay detector.
+    /// Replay detectors of any subsequently added keys are going to use the same parameters.
+    pub fn new_with_replay_detector(
+        sphinx_key: PrivateKey,
+        replay_detector: ReplayDetector,
+    ) -> Self {
         SphinxPacketProcessor {
-            sphinx_key: Arc::new(sphinx_key),
+            sphinx_keys: Arc::new(RwLock::new(vec![SphinxKey::new(
+                sphinx_key,
+                replay_detector,
+            )])),
+        }
+    }
+
+    fn fresh_replay_detector(keys: &[SphinxKey]) -> ReplayDetector {
+        // there's always at least a single key present
+        keys[0].replay_detector.fresh()
+    }
+
+    /// Starts accepting packets encrypted under the provided key, alongside all the existing keys.
+    /// It allows processing packets of clients that have already learned about the upcoming key
+    /// before this node has switched to it.
+    pub fn add_upcoming_key(&self, sphinx_key: PrivateKey) {
+        let mut keys = self.sphinx_keys.write().unwrap();
+        if keys
+            .iter()
+            .any(|key| key.private_key.to_bytes() == sphinx_key.to_bytes())
+        {
+            return;
+        }
+
+        let replay_detector = Self::fresh_replay_detector(&keys);
+        keys.push(SphinxKey::new(sphinx_key, replay_detector));
+    }
+
+    /// Makes the provided key the primary key of this node. All other keys are still going to be accepted
+    /// for the duration of the grace period so that packets of clients using slightly outdated
+    /// topology would not get dropped.
+    pub fn rotate_key(&self, sphinx_key: PrivateKey, grace_period: Duration) {
+        let expiration = Instant::now() + grace_period;
+
+        let mut keys = self.sphinx_keys.write().unwrap();
+        let new_primary = match keys
+            .iter()
+            .position(|key| key.private_key.to_bytes() == sphinx_key.to_bytes())
+        {
+            // if the key has been announced as upcoming, make sure to keep the tags it has already seen
+            Some(position) => keys.remove(position),
+            None => SphinxKey::new(sphinx_key, Self::fresh_replay_detector(&keys)),
+        };
+
+        for key in keys.iter_mut() {
+            key.expire_at(expiration)
+        }
+        keys.insert(0, new_primary);
+    }
+
+    /// Removes all keys (and their replay detectors) that are past their grace period.
+    pub fn remove_expired_keys(&self) {
+        let now = Instant::now();
+        self.sphinx_keys
+            .write()
+            .unwrap()
+            .retain(|key| !key.is_expired(now))
+    }
+
+    /// Returns the number of keys under which packets are currently accepted.
+    pub fn active_keys(&self) -> usize {
+        let now = Instant::now();
+        self.sphinx_keys
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|key| !key.is_expired(now))
+            .count()
+    }
+
+    fn process_with_key(
+        key: &SphinxKey,
+        packet: NymPacket,
+    ) -> Result<NymProcessedPacket, MixProcessingError> {
+        let replay_tag = ReplayTag::new(&packet);
+        let processed = packet.process(&key.private_key).map_err(|err| {
+            debug!("Failed to unwrap the packet: {err}");
+            MixProcessingError::from(err)
+        })?;
+
+        // only mark the tag as seen once we know the packet was valid (i.e. its integrity was
+        // verified), otherwise anyone observing the traffic could preemptively 'burn'
+        // tags of legitimate packets by sending garbage with the same header
+        // (note: packets without any tag material would have failed to get processed)
+        if let Some(replay_tag) = replay_tag {
+            if key.replay_detector.check_and_insert(&replay_tag) {
+                debug!("Received a replayed packet");
+                return Err(MixProcessingError::ReplayedPacket);
+            }
         }
+
+        Ok(processed)
     }
 
     /// Performs a fresh sphinx unwrapping using no cache.
@@ -55,13 +194,55 @@ impl SphinxPacketProcessor {
     )]
     fn perform_initial_sphinx_packet_processing(
         &self,
-        packet: SphinxPacket,
-    ) -> Result<ProcessedPacket, MixProcessingError> {
+        packet: NymPacket,
+        raw_packet: Option<&[u8]>,
+    ) -> Result<NymProcessedPacket, MixProcessingError> {
         measure!({
-            packet.process(&self.sphinx_key).map_err(|err| {
-                debug!("Failed to unwrap Sphinx packet: {err}");
-                MixProcessingError::SphinxProcessingError(err)
-            })
+            // the lock can only be poisoned if another thread panicked while holding it,
+            // at which point we're in an unrecoverable state anyway
+            let keys = self.sphinx_keys.read().unwrap();
+            let now = Instant::now();
+            let mut valid_keys = keys.iter().filter(|key| !key.is_expired(now));
+
+            // there's always the primary key present and it never expires
+            let primary = valid_keys.next().unwrap();
+            let mut fallback_keys = valid_keys.peekable();
+            if fallback_keys.peek().is_none() {
+                return Self::process_with_key(primary, packet);
+            }
+
+            // processing consumes the packet, so if it had to be retried with any of the other keys,
+            // it's parsed again out of the bytes it has been received as. Only the packets that
+            // have not come from the wire have to be serialised for that purpose.
+            let is_outfox = matches!(packet, NymPacket::Outfox(_));
+            let serialised;
+            let packet_bytes = match raw_packet {
+                Some(raw_packet) => raw_packet,
+                None => {
+                    serialised = packet.to_bytes();
+                    &serialised
+                }
+            };
+            let reconstruct = || {
+                if is_outfox {
+                    NymPacket::outfox_from_bytes(packet_bytes)
+                } else {
+                    NymPacket::sphinx_from_bytes(packet_bytes)
+                }
+            };
+
+            let mut result = Self::process_with_key(primary, packet);
+            for key in fallback_keys {
+                // if the packet got replayed or processed successfully, there's no point in
+                // trying any other key
+                match &result {
+                    Err(MixProcessingError::SphinxProcessingError(_))
+                    | Err(MixProcessingError::OutfoxProcessingError(_)) => (),
+                    _ => return result,
+                }
+                result = Self::process_with_key(key, reconstruct()?);
+            }
+            result
         })
     }
 
@@ -73,16 +254,16 @@ impl SphinxPacketProcessor {
     fn perform_initial_unwrapping(
         &self,
         received: FramedSphinxPacket,
-    ) -> Result<ProcessedPacket, MixProcessingError> {
+    ) -> Result<NymProcessedPacket, MixProcessingError> {
         measure!({
-            let packet_mode = received.packet_mode();
-            let sphinx_packet = received.into_inner();
+            let packet_type = received.packet_type();
+            let (packet, raw_packet) = received.into_inner_with_raw_packet();
 
-            if packet_mode.is_old_vpn() {
+            if packet_type.is_old_vpn() {
                 return Err(MixProcessingError::ReceivedOldTypeVpnPacket);
             }
 
-            self.perform_initial_sphinx_packet_processing(sphinx_packet)
+            self.perform_initial_sphinx_packet_processing(packet, raw_packet.as_deref())
         })
     }
 
@@ -90,14 +271,14 @@ impl SphinxPacketProcessor {
     /// and packs all the data in a way that can be easily sent to the next hop.
     fn process_forward_hop(
         &self,
-        packet: SphinxPacket,
+        packet: NymPacket,
         forward_address: NodeAddressBytes,
         delay: SphinxDelay,
-        packet_mode: PacketMode,
+        packet_type: PacketType,
     ) -> Result<MixProcessingResult, MixProcessingError> {
         let next_hop_address = NymNodeRoutingAddress::try_from(forward_address)?;
 
-        let mix_packet = MixPacket::new(next_hop_address, packet, packet_mode);
+        let mix_packet = MixPacket::new(next_hop_address, packet, packet_type);
         Ok(MixProcessingResult::ForwardHop(mix_packet, Some(delay)))
     }
 
@@ -124,7 +305,7 @@ impl SphinxPacketProcessor {
         &self,
         data: Vec<u8>,
         packet_size: PacketSize,
-        packet_mode: PacketMode,
+        packet_type: PacketType,
     ) -> Result<(Option<MixPacket>, Vec<u8>), MixProcessingError> {
         match packet_size {
             PacketSize::AckPacket => {
@@ -134,11 +315,19 @@ impl SphinxPacketProcessor {
             PacketSize::RegularPacket
             | PacketSize::ExtendedPacket8
             | PacketSize::ExtendedPacket16
-            | PacketSize::ExtendedPacket32 => {
+            | PacketSize::ExtendedPacket32
+            | PacketSize::OutfoxRegularPacket => {
                 trace!("received a normal packet!");
                 let (ack_data, message) = self.split_hop_data_into_ack_and_message(data)?;
                 let (ack_first_hop, ack_packet) = SurbAck::try_recover_first_hop_packet(&ack_data)?;
-                let forward_ack = MixPacket::new(ack_first_hop, ack_packet, packet_mode);
+
+                // SURB-Acks are always sphinx packets, regardless of the format of the packet carrying them
+                let ack_packet_type = if packet_type.is_outfox() {
+                    PacketType::Mix
+                } else {
+                    packet_type
+                };
+                let forward_ack = MixPacket::new(ack_first_hop, ack_packet.into(), ack_packet_type);
                 Ok((Some(forward_ack), message))
             }
         }
@@ -152,12 +341,34 @@ impl SphinxPacketProcessor {
         destination: DestinationAddressBytes,
         payload: Payload,
         packet_size: PacketSize,
-        packet_mode: PacketMode,
+        packet_type: PacketType,
     ) -> Result<MixProcessingResult, MixProcessingError> {
         let packet_message = payload.recover_plaintext()?;
+        self.split_final_hop_message(destination, packet_message, packet_size, packet_type)
+    }
+
+    /// Processed received outfox packet that has had its final layer decoded.
+    fn process_outfox_final_hop(
+        &self,
+        packet: OutfoxPacket,
+        routing_information: OutfoxRoutingInformation,
+        packet_size: PacketSize,
+        packet_type: PacketType,
+    ) -> Result<MixProcessingResult, MixProcessingError> {
+        let destination = DestinationAddressBytes::from_bytes(routing_information.next_address);
+        let packet_message = packet.recover_plaintext()?.to_vec();
+        self.split_final_hop_message(destination, packet_message, packet_size, packet_type)
+    }
 
+    fn split_final_hop_message(
+        &self,
+        destination: DestinationAddressBytes,
+        packet_message: Vec<u8>,
+        packet_size: PacketSize,
+        packet_type: PacketType,
+    ) -> Result<MixProcessingResult, MixProcessingError> {
         let (forward_ack, message) =
-            self.split_into_ack_and_message(packet_message, packet_size, packet_mode)?;
+            self.split_into_ack_and_message(packet_message, packet_size, packet_type)?;
 
         Ok(MixProcessingResult::FinalHop(ProcessedFinalHop {
             destination,
@@ -170,18 +381,36 @@ impl SphinxPacketProcessor {
     /// or a final hop.
     fn perform_final_processing(
         &self,
-        packet: ProcessedPacket,
+        packet: NymProcessedPacket,
         packet_size: PacketSize,
-        packet_mode: PacketMode,
+        packet_type: PacketType,
     ) -> Result<MixProcessingResult, MixProcessingError> {
         match packet {
-            ProcessedPacket::ForwardHop(packet, address, delay) => {
-                self.process_forward_hop(*packet, address, delay, packet_mode)
+            NymProcessedPacket::Sphinx(ProcessedPacket::ForwardHop(packet, address, delay)) => {
+                self.process_forward_hop((*packet).into(), address, delay, packet_type)
             }
             // right now there's no use for the surb_id included in the header - probably it should get removed from the
             // sphinx all together?
-            ProcessedPacket::FinalHop(destination, _, payload) => {
-                self.process_final_hop(destination, payload, packet_size, packet_mode)
+            NymProcessedPacket::Sphinx(ProcessedPacket::FinalHop(destination, _, payload)) => {
+                self.process_final_hop(destination, payload, packet_size, packet_type)
+            }
+            NymProcessedPacket::Outfox(packet, routing_information) => {
+                if packet.remaining_layers() == 0 {
+                    self.process_outfox_final_hop(
+                        packet,
+                        routing_information,
+                        packet_size,
+                        packet_type,
+                    )
+                } else {
+                    let address = NodeAddressBytes::from_bytes(routing_information.next_address);
+                    self.process_forward_hop(
+                        packet.into(),
+                        address,
+                        routing_information.delay,
+                        packet_type,
+                    )
+                }
             }
         }
     }
@@ -197,14 +426,14 @@ impl SphinxPacketProcessor {
         // explicit packet size will help to correctly parse final hop
         measure!({
             let packet_size = received.packet_size();
-            let packet_mode = received.packet_mode();
+            let packet_type = received.packet_type();
 
             // unwrap the sphinx packet and if possible and appropriate, cache keys
             let processed_packet = self.perform_initial_unwrapping(received)?;
 
             // for forward packets, extract next hop and set delay (but do NOT delay here)
             // for final packets, extract SURBAck
-            self.perform_final_processing(processed_packet, packet_size, packet_mode)
+            self.perform_final_processing(processed_packet, packet_size, packet_type)
         })
     }
 }
@@ -213,13 +442,52 @@ impl SphinxPacketProcessor {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use nym_sphinx_types::crypto::keygen;
+    use bytes::BytesMut;
+    use nym_sphinx_framing::codec::SphinxCodec;
+    use nym_sphinx_types::builder::SphinxPacketBuilder;
+    use nym_sphinx_types::crypto::{keygen, PublicKey};
+    use nym_sphinx_types::{
+        Destination, Node, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
+    };
+    use tokio_util::codec::{Decoder, Encoder};
 
     fn fixture() -> SphinxPacketProcessor {
         let local_keys = keygen();
         SphinxPacketProcessor::new(local_keys.0)
     }
 
+    fn make_sphinx_packet_bytes(first_hop_key: PublicKey) -> Vec<u8> {
+        let node1 = Node::new(
+            NodeAddressBytes::from_bytes([5u8; NODE_ADDRESS_LENGTH]),
+            first_hop_key,
+        );
+        let node2 = Node::new(
+            NodeAddressBytes::from_bytes([4u8; NODE_ADDRESS_LENGTH]),
+            keygen().1,
+        );
+        let destination = Destination::new(
+            DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]),
+            [4u8; IDENTIFIER_LENGTH],
+        );
+        let delays = vec![
+            SphinxDelay::new_from_nanos(42),
+            SphinxDelay::new_from_nanos(42),
+        ];
+        SphinxPacketBuilder::new()
+            .with_payload_size(PacketSize::default().payload_size())
+            .build_packet(b"foomp", &[node1, node2], &destination, &delays)
+            .unwrap()
+            .to_bytes()
+    }
+
+    fn framed_packet(bytes: &[u8]) -> FramedSphinxPacket {
+        FramedSphinxPacket::new(
+            NymPacket::sphinx_from_bytes(bytes).unwrap(),
+            Default::default(),
+            false,
+        )
+    }
+
     #[tokio::test]
     async fn splitting_hop_data_works_for_sufficiently_long_payload() {
         let processor = fixture();
@@ -255,4 +523,201 @@ mod tests {
         assert!(ack.is_none());
         assert_eq!(data, message)
     }
+
+    #[tokio::test]
+    async fn replayed_packets_are_rejected() {
+        let (private_key, public_key) = keygen();
+        let processor = SphinxPacketProcessor::new(private_key);
+
+        let packet_bytes = make_sphinx_packet_bytes(public_key);
+        assert!(processor
+            .process_received(framed_packet(&packet_bytes))
+            .is_ok());
+
+        let err = processor
+            .process_received(framed_packet(&packet_bytes))
+            .err()
+            .unwrap();
+        assert!(err.is_replay());
+
+        // but a different packet is still processed just fine
+        let other_packet_bytes = make_sphinx_packet_bytes(public_key);
+        assert!(processor
+            .process_received(framed_packet(&other_packet_bytes))
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn outfox_packets_are_forwarded_as_outfox() {
+        let (private_key, public_key) = keygen();
+        let processor = SphinxPacketProcessor::new(private_key);
+
+        let route = [
+            Node::new(
+                NodeAddressBytes::from_bytes([5u8; NODE_ADDRESS_LENGTH]),
+                public_key,
+            ),
+            Node::new(
+                NodeAddressBytes::from_bytes([4u8; NODE_ADDRESS_LENGTH]),
+                keygen().1,
+            ),
+        ];
+        let destination = DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]);
+        let delays = vec![SphinxDelay::new_from_nanos(42); 2];
+        let payload = vec![42u8; PacketSize::OutfoxRegularPacket.payload_size()];
+        let packet = OutfoxPacket::build(&payload, &route, &destination, &delays).unwrap();
+
+        let framed = FramedSphinxPacket::new(packet.into(), PacketType::Outfox, false);
+        match processor.process_received(framed).unwrap() {
+            MixProcessingResult::ForwardHop(mix_packet, delay) => {
+                assert_eq!(mix_packet.packet_type(), PacketType::Outfox);
+                assert_eq!(
+                    mix_packet.next_hop(),
+                    NymNodeRoutingAddress::try_from(route[1].address).unwrap()
+                );
+                assert_eq!(delay, Some(SphinxDelay::new_from_nanos(42)));
+            }
+            MixProcessingResult::FinalHop(_) => panic!("expected forward hop"),
+        }
+    }
+
+    #[tokio::test]
+    async fn packets_under_upcoming_key_are_accepted() {
+        let (old_private, old_public) = keygen();
+        let (new_private, new_public) = keygen();
+        let processor = SphinxPacketProcessor::new(old_private);
+
+        let new_packet = make_sphinx_packet_bytes(new_public);
+        assert!(processor
+            .process_received(framed_packet(&new_packet))
+            .is_err());
+
+        processor.add_upcoming_key(new_private);
+        assert_eq!(processor.active_keys(), 2);
+        assert!(processor
+            .process_received(framed_packet(&new_packet))
+            .is_ok());
+        assert!(processor
+            .process_received(framed_packet(&make_sphinx_packet_bytes(old_public)))
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn old_key_is_accepted_during_grace_period() {
+        let (old_private, old_public) = keygen();
+        let (new_private, new_public) = keygen();
+        let processor = SphinxPacketProcessor::new(old_private);
+
+        let old_packet = make_sphinx_packet_bytes(old_public);
+        assert!(processor
+            .process_received(framed_packet(&old_packet))
+            .is_ok());
+
+        processor.rotate_key(new_private, Duration::from_secs(60));
+        assert_eq!(processor.active_keys(), 2);
+
+        assert!(processor
+            .process_received(framed_packet(&make_sphinx_packet_bytes(new_public)))
+            .is_ok());
+        assert!(processor
+            .process_received(framed_packet(&make_sphinx_packet_bytes(old_public)))
+            .is_ok());
+
+        // the tags seen under the old key are still remembered
+        assert!(processor
+            .process_received(framed_packet(&old_packet))
+            .err()
+            .unwrap()
+            .is_replay());
+    }
+
+    #[tokio::test]
+    async fn received_packets_are_retried_with_other_keys() {
+        let (old_private, old_public) = keygen();
+        let (new_private, _) = keygen();
+        let processor = SphinxPacketProcessor::new(old_private);
+        processor.rotate_key(new_private, Duration::from_secs(60));
+
+        // go through the codec, so that the packet would carry the bytes it has been received as
+        let mut bytes = BytesMut::new();
+        SphinxCodec
+            .encode(
+                framed_packet(&make_sphinx_packet_bytes(old_public)),
+                &mut bytes,
+            )
+            .unwrap();
+        let received = SphinxCodec.decode(&mut bytes).unwrap().unwrap();
+
+        assert!(processor.process_received(received).is_ok());
+    }
+
+    #[tokio::test]
+    async fn old_key_is_rejected_after_grace_period() {
+        let (old_private, old_public) = keygen();
+        let (new_private, _) = keygen();
+        let processor = SphinxPacketProcessor::new(old_private);
+
+        processor.rotate_key(new_private, Duration::ZERO);
+        assert_eq!(processor.active_keys(), 1);
+
+        let err = processor
+            .process_received(framed_packet(&make_sphinx_packet_bytes(old_public)))
+            .err()
+            .unwrap();
+        assert!(matches!(err, MixProcessingError::SphinxProcessingError(_)));
+
+        processor.remove_expired_keys();
+        assert_eq!(processor.sphinx_keys.read().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn promoted_upcoming_key_keeps_its_replay_tags() {
+        let (old_private, _) = keygen();
+        let (new_private, new_public) = keygen();
+        let processor = SphinxPacketProcessor::new(old_private);
+        processor.add_upcoming_key(PrivateKey::from(new_private.to_bytes()));
+
+        let new_packet = make_sphinx_packet_bytes(new_public);
+        assert!(processor
+            .process_received(framed_packet(&new_packet))
+            .is_ok());
+
+        processor.rotate_key(new_private, Duration::from_secs(60));
+        assert_eq!(processor.active_keys(), 2);
+        assert!(processor
+            .process_received(framed_packet(&new_packet))
+            .err()
+            .unwrap()
+            .is_replay());
+    }
+
+    #[tokio::test]
+    async fn key_rotation_is_shared_between_clones() {
+        let (old_private, _) = keygen();
+        let (new_private, new_public) = keygen();
+        let processor = SphinxPacketProcessor::new(old_private);
+        let cloned = processor.clone();
+
+        processor.rotate_key(new_private, Duration::ZERO);
+        assert!(cloned
+            .process_received(framed_packet(&make_sphinx_packet_bytes(new_public)))
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn replay_detection_is_shared_between_clones() {
+        let (private_key, public_key) = keygen();
+        let processor = SphinxPacketProcessor::new(private_key);
+        let cloned = processor.clone();
+
+        let packet_bytes = make_sphinx_packet_bytes(public_key);
+        assert!(processor
+            .process_received(framed_packet(&packet_bytes))
+            .is_ok());
+        assert!(cloned
+            .process_received(framed_packet(&packet_bytes))
+            .err()
+            .unwrap()
+            .is_replay());
+    }
 }
@@ -0,0 +1,251 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nym_crypto::blake3;
+use nym_sphinx_types::NymPacket;
+use std::sync::{Arc, Mutex};
+
+/// Default number of tags each generation of the replay filter is going to hold before it gets rotated.
+pub const DEFAULT_REPLAY_FILTER_CAPACITY: usize = 2_000_000;
+
+/// Default false positive rate of each generation of the replay filter.
+pub const DEFAULT_REPLAY_FILTER_FALSE_POSITIVE_RATE: f64 = 1e-5;
+
+const REPLAY_TAG_SIZE: usize = 32;
+
+/// Tag uniquely identifying a sphinx packet at this particular hop. It is derived by hashing the
+/// shared secret (the blinded group element) included in the packet header. Since the blinding
+/// is different at every hop, the same tag can never legitimately be seen twice by a node
+/// for as long as its sphinx key does not change.
+///
+/// For outfox packets the fresh group element of the current layer is used instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayTag([u8; REPLAY_TAG_SIZE]);
+
+impl ReplayTag {
+    pub fn new(packet: &NymPacket) -> Option<Self> {
+        let material = packet.replay_tag_material()?;
+        Some(ReplayTag(*blake3::hash(&material).as_bytes()))
+    }
+
+    // the tag itself is an output of a cryptographic hash function, so rather than hashing it
+    // again for each of the bloom filter indices, just use two of its words for double hashing
+    fn index_seeds(&self) -> (u64, u64) {
+        let mut h1 = [0u8; 8];
+        let mut h2 = [0u8; 8];
+        h1.copy_from_slice(&self.0[..8]);
+        h2.copy_from_slice(&self.0[8..16]);
+        (u64::from_le_bytes(h1), u64::from_le_bytes(h2) | 1)
+    }
+}
+
+impl From<[u8; REPLAY_TAG_SIZE]> for ReplayTag {
+    fn from(bytes: [u8; REPLAY_TAG_SIZE]) -> Self {
+        ReplayTag(bytes)
+    }
+}
+
+struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+    items: usize,
+}
+
+impl BloomFilter {
+    fn new(num_bits: u64, num_hashes: u32) -> Self {
+        let words = num_bits.div_ceil(64) as usize;
+        BloomFilter {
+            bits: vec![0; words],
+            num_bits: words as u64 * 64,
+            num_hashes,
+            items: 0,
+        }
+    }
+
+    fn bit_positions(&self, tag: &ReplayTag) -> impl Iterator<Item = u64> {
+        let (h1, h2) = tag.index_seeds();
+        let num_bits = self.num_bits;
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+
+    fn contains(&self, tag: &ReplayTag) -> bool {
+        self.bit_positions(tag)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    fn insert(&mut self, tag: &ReplayTag) {
+        for bit in self.bit_positions(tag) {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+        self.items += 1;
+    }
+
+    fn clear(&mut self) {
+        self.bits.iter_mut().for_each(|word| *word = 0);
+        self.items = 0;
+    }
+}
+
+/// Pair of bloom filters, where the older one gets discarded once the current one reaches its capacity.
+/// This way the memory usage is bounded while each seen tag is remembered for at least `capacity`
+/// subsequent insertions.
+struct RotatingBloomFilter {
+    capacity: usize,
+    current: BloomFilter,
+    previous: BloomFilter,
+}
+
+impl RotatingBloomFilter {
+    fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let capacity = capacity.max(1);
+        let ln2 = std::f64::consts::LN_2;
+
+        // standard optimal bloom filter parameters for `n` items with `p` false positive rate:
+        // m = -n * ln(p) / ln(2)^2
+        // k = m / n * ln(2)
+        let num_bits = (-(capacity as f64) * false_positive_rate.ln() / (ln2 * ln2)).ceil();
+        let num_hashes = ((num_bits / capacity as f64) * ln2).round().max(1.0);
+
+        RotatingBloomFilter {
+            capacity,
+            current: BloomFilter::new(num_bits as u64, num_hashes as u32),
+            previous: BloomFilter::new(num_bits as u64, num_hashes as u32),
+        }
+    }
+
+    /// Inserts the tag into the filter and returns whether it has (probably) been seen before.
+    fn check_and_insert(&mut self, tag: &ReplayTag) -> bool {
+        if self.current.contains(tag) || self.previous.contains(tag) {
+            return true;
+        }
+
+        if self.current.items >= self.capacity {
+            std::mem::swap(&mut self.current, &mut self.previous);
+            self.current.clear();
+        }
+        self.current.insert(tag);
+        false
+    }
+
+    fn reset(&mut self) {
+        self.current.clear();
+        self.previous.clear();
+    }
+
+    fn empty_copy(&self) -> Self {
+        RotatingBloomFilter {
+            capacity: self.capacity,
+            current: BloomFilter::new(self.current.num_bits, self.current.num_hashes),
+            previous: BloomFilter::new(self.previous.num_bits, self.previous.num_hashes),
+        }
+    }
+}
+
+/// Memory-bounded store of tags of all packets processed with the current sphinx key.
+/// Note that it is only valid for as long as the key remains unchanged and it should get reset
+/// whenever the key is rotated.
+#[derive(Clone)]
+pub struct ReplayDetector {
+    filter: Arc<Mutex<RotatingBloomFilter>>,
+}
+
+impl Default for ReplayDetector {
+    fn default() -> Self {
+        ReplayDetector::new(
+            DEFAULT_REPLAY_FILTER_CAPACITY,
+            DEFAULT_REPLAY_FILTER_FALSE_POSITIVE_RATE,
+        )
+    }
+}
+
+impl ReplayDetector {
+    pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        ReplayDetector {
+            filter: Arc::new(Mutex::new(RotatingBloomFilter::new(
+                capacity,
+                false_positive_rate,
+            ))),
+        }
+    }
+
+    /// Marks the provided tag as seen and returns whether it has already been seen before,
+    /// i.e. whether the associated packet is a replay.
+    pub fn check_and_insert(&self, tag: &ReplayTag) -> bool {
+        // the lock can only be poisoned if another thread panicked while holding it,
+        // at which point we're in an unrecoverable state anyway
+        self.filter.lock().unwrap().check_and_insert(tag)
+    }
+
+    /// Forgets all previously seen tags. It should be called whenever the sphinx key changes.
+    pub fn reset(&self) {
+        self.filter.lock().unwrap().reset()
+    }
+
+    /// Creates a new detector, with the same parameters as this one, that hasn't seen any tags.
+    /// It's meant to be used alongside a freshly generated sphinx key.
+    pub fn fresh(&self) -> Self {
+        ReplayDetector {
+            filter: Arc::new(Mutex::new(self.filter.lock().unwrap().empty_copy())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(i: u64) -> ReplayTag {
+        ReplayTag(*blake3::hash(&i.to_le_bytes()).as_bytes())
+    }
+
+    #[test]
+    fn detects_duplicate_tags() {
+        let detector = ReplayDetector::new(1000, 1e-6);
+        for i in 0..100 {
+            assert!(!detector.check_and_insert(&tag(i)));
+        }
+        for i in 0..100 {
+            assert!(detector.check_and_insert(&tag(i)));
+        }
+    }
+
+    #[test]
+    fn remembers_tags_from_previous_generation() {
+        let detector = ReplayDetector::new(10, 1e-6);
+        for i in 0..15 {
+            assert!(!detector.check_and_insert(&tag(i)));
+        }
+        // the filter has been rotated once, but the first generation is still there
+        assert!(detector.check_and_insert(&tag(0)));
+        assert!(detector.check_and_insert(&tag(14)));
+    }
+
+    #[test]
+    fn forgets_tags_after_two_rotations() {
+        let detector = ReplayDetector::new(10, 1e-6);
+        for i in 0..25 {
+            assert!(!detector.check_and_insert(&tag(i)));
+        }
+        assert!(!detector.check_and_insert(&tag(0)));
+    }
+
+    #[test]
+    fn fresh_detector_is_independent() {
+        let detector = ReplayDetector::new(100, 1e-6);
+        detector.check_and_insert(&tag(1));
+
+        let fresh = detector.fresh();
+        assert!(!fresh.check_and_insert(&tag(1)));
+        assert!(!fresh.check_and_insert(&tag(2)));
+        assert!(!detector.check_and_insert(&tag(2)));
+    }
+
+    #[test]
+    fn reset_clears_all_tags() {
+        let detector = ReplayDetector::new(100, 1e-6);
+        detector.check_and_insert(&tag(1));
+        detector.reset();
+        assert!(!detector.check_and_insert(&tag(1)));
+    }
+}
@@ -0,0 +1,563 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Periodic rotation of the sphinx key of a node. Every new key is generated in advance,
+//! announced to the nym apis, so that the clients would learn about it via the topology endpoints
+//! and the signed snapshots, and becomes the primary key of the packet processor once its first epoch begins.
+//! The previous key is kept around for a short grace period for the packets that are still in flight.
+//!
+//! The rotated keys, alongside their announcements, are persisted on disk so that after a restart
+//! the node keeps on using the keys the clients already know about.
+
+use crate::packet_processor::processor::SphinxPacketProcessor;
+use log::*;
+use nym_crypto::asymmetric::{encryption, identity};
+use nym_mixnet_contract_common::EpochId;
+use nym_network_defaults::mainnet::NYM_API;
+use nym_sphinx_types::crypto::keygen;
+use nym_sphinx_types::PrivateKey;
+use nym_task::TaskClient;
+use nym_validator_client::models::{
+    SignedSphinxKeyAnnouncement, SphinxKeyAnnouncement, SphinxKeyValidity,
+};
+use nym_validator_client::NymApiClient;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::sleep;
+use url::Url;
+
+const DEFAULT_EPOCHS_PER_KEY: u32 = 24;
+const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(15 * 60);
+const DEFAULT_EPOCH_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Number of epochs in advance the next key is announced.
+const ANNOUNCEMENT_LEAD_EPOCHS: EpochId = 1;
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Number of epochs during which a single sphinx key is used.
+    epochs_per_key: u32,
+
+    /// Specifies for how long the previous key is still accepted after it got replaced.
+    grace_period: Duration,
+
+    /// Specifies how often the current epoch is checked.
+    epoch_check_interval: Duration,
+
+    /// URLs to the nym apis to which the keys are announced.
+    nym_api_urls: Vec<Url>,
+}
+
+impl Config {
+    pub fn build() -> ConfigBuilder {
+        ConfigBuilder::new()
+    }
+}
+
+#[must_use]
+pub struct ConfigBuilder(Config);
+
+impl ConfigBuilder {
+    pub fn new() -> ConfigBuilder {
+        Self::default()
+    }
+
+    pub fn epochs_per_key(mut self, epochs_per_key: u32) -> Self {
+        self.0.epochs_per_key = epochs_per_key;
+        self
+    }
+
+    pub fn grace_period(mut self, grace_period: Duration) -> Self {
+        self.0.grace_period = grace_period;
+        self
+    }
+
+    pub fn epoch_check_interval(mut self, epoch_check_interval: Duration) -> Self {
+        self.0.epoch_check_interval = epoch_check_interval;
+        self
+    }
+
+    pub fn nym_api_urls(mut self, nym_api_urls: Vec<Url>) -> Self {
+        self.0.nym_api_urls = nym_api_urls;
+        self
+    }
+
+    pub fn build(self) -> Config {
+        // panics here are fine as those are only ever constructed at the initial setup
+        assert!(
+            !self.0.nym_api_urls.is_empty(),
+            "at least one validator endpoint must be provided",
+        );
+        assert!(
+            self.0.epochs_per_key > 0,
+            "sphinx keys must be used for at least a single epoch"
+        );
+        self.0
+    }
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        ConfigBuilder(Config {
+            epochs_per_key: DEFAULT_EPOCHS_PER_KEY,
+            grace_period: DEFAULT_GRACE_PERIOD,
+            epoch_check_interval: DEFAULT_EPOCH_CHECK_INTERVAL,
+            nym_api_urls: vec![NYM_API.parse().expect("Invalid default API URL")],
+        })
+    }
+}
+
+/// Sphinx key generated by this node alongside its signed announcement.
+struct RotatedKey {
+    private_key: encryption::PrivateKey,
+    announcement: SignedSphinxKeyAnnouncement,
+}
+
+impl RotatedKey {
+    fn validity(&self) -> SphinxKeyValidity {
+        self.announcement.announcement.validity
+    }
+
+    fn sphinx_key(&self) -> PrivateKey {
+        (&self.private_key).into()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum KeySlot {
+    Current,
+    Upcoming,
+}
+
+impl KeySlot {
+    fn name(&self) -> &'static str {
+        match self {
+            KeySlot::Current => "current",
+            KeySlot::Upcoming => "upcoming",
+        }
+    }
+}
+
+/// Persists the rotated keys on disk, so that they would survive restarts of the node.
+/// The private keys are stored in the same way as the rest of the node keys, i.e. encrypted
+/// with the provided passphrase, if any.
+pub struct RotatedKeysStorage {
+    directory: PathBuf,
+    passphrase: Option<String>,
+}
+
+impl RotatedKeysStorage {
+    pub fn new(directory: PathBuf, passphrase: Option<String>) -> Self {
+        RotatedKeysStorage {
+            directory,
+            passphrase,
+        }
+    }
+
+    fn private_key_path(&self, slot: KeySlot) -> PathBuf {
+        self.directory
+            .join(format!("{}_private_sphinx.pem", slot.name()))
+    }
+
+    fn announcement_path(&self, slot: KeySlot) -> PathBuf {
+        self.directory
+            .join(format!("{}_sphinx_announcement.json", slot.name()))
+    }
+
+    fn passphrase(&self) -> Option<&[u8]> {
+        self.passphrase.as_deref().map(str::as_bytes)
+    }
+
+    fn load(&self, slot: KeySlot) -> io::Result<Option<RotatedKey>> {
+        let announcement_path = self.announcement_path(slot);
+        if !announcement_path.exists() {
+            return Ok(None);
+        }
+
+        let announcement = serde_json::from_slice(&fs::read(announcement_path)?)?;
+        let private_key =
+            nym_pemstore::load_key_with_passphrase(self.private_key_path(slot), self.passphrase())?;
+
+        Ok(Some(RotatedKey {
+            private_key,
+            announcement,
+        }))
+    }
+
+    fn store(&self, slot: KeySlot, key: Option<&RotatedKey>) -> io::Result<()> {
+        let Some(key) = key else {
+            return self.remove(slot);
+        };
+
+        fs::create_dir_all(&self.directory)?;
+        // the private key is written first so that the announcement never points to a missing key
+        nym_pemstore::store_key_with_passphrase(
+            &key.private_key,
+            self.private_key_path(slot),
+            self.passphrase(),
+        )?;
+        fs::write(
+            self.announcement_path(slot),
+            serde_json::to_vec(&key.announcement)?,
+        )
+    }
+
+    fn remove(&self, slot: KeySlot) -> io::Result<()> {
+        for path in [self.announcement_path(slot), self.private_key_path(slot)] {
+            match fs::remove_file(path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct SphinxKeyRotator {
+    config: Config,
+    identity: Arc<identity::KeyPair>,
+    packet_processor: SphinxPacketProcessor,
+    nym_api_clients: Vec<NymApiClient>,
+    keys_storage: RotatedKeysStorage,
+    shutdown_listener: TaskClient,
+
+    /// Key currently used by the packet processor. It's `None` until the bonded key gets replaced.
+    current: Option<RotatedKey>,
+
+    /// Key that has already been announced, but whose validity has not started yet.
+    upcoming: Option<RotatedKey>,
+}
+
+impl SphinxKeyRotator {
+    pub fn new(
+        config: Config,
+        identity: Arc<identity::KeyPair>,
+        packet_processor: SphinxPacketProcessor,
+        keys_storage: RotatedKeysStorage,
+        shutdown_listener: TaskClient,
+    ) -> Self {
+        let nym_api_clients = config
+            .nym_api_urls
+            .iter()
+            .map(|url| NymApiClient::new(url.clone()))
+            .collect();
+
+        let mut rotator = SphinxKeyRotator {
+            config,
+            identity,
+            packet_processor,
+            nym_api_clients,
+            keys_storage,
+            shutdown_listener,
+            current: None,
+            upcoming: None,
+        };
+        rotator.restore_keys();
+        rotator
+    }
+
+    fn load_key(&self, slot: KeySlot) -> Option<RotatedKey> {
+        match self.keys_storage.load(slot) {
+            Ok(Some(key)) => {
+                let identity = self.identity.public_key().to_base58_string();
+                if key.announcement.announcement.identity_key == identity {
+                    Some(key)
+                } else {
+                    warn!(
+                        "the stored {} sphinx key has been announced by a different node - ignoring it",
+                        slot.name()
+                    );
+                    None
+                }
+            }
+            Ok(None) => None,
+            Err(err) => {
+                error!(
+                    "failed to load the stored {} sphinx key - {err}",
+                    slot.name()
+                );
+                None
+            }
+        }
+    }
+
+    /// Restores the keys from before the restart. Any of them that might have expired in the meantime
+    /// are going to get replaced during the first rotation check.
+    fn restore_keys(&mut self) {
+        if let Some(current) = self.load_key(KeySlot::Current) {
+            info!(
+                "restored the sphinx key valid in epochs {}..{}",
+                current.validity().valid_from,
+                current.validity().valid_until
+            );
+            self.packet_processor
+                .rotate_key(current.sphinx_key(), self.config.grace_period);
+            self.current = Some(current);
+        }
+
+        if let Some(upcoming) = self.load_key(KeySlot::Upcoming) {
+            info!(
+                "restored the upcoming sphinx key valid in epochs {}..{}",
+                upcoming.validity().valid_from,
+                upcoming.validity().valid_until
+            );
+            self.packet_processor
+                .add_upcoming_key(upcoming.sphinx_key());
+            self.upcoming = Some(upcoming);
+        }
+    }
+
+    fn persist_keys(&self) {
+        for (slot, key) in [
+            (KeySlot::Current, &self.current),
+            (KeySlot::Upcoming, &self.upcoming),
+        ] {
+            if let Err(err) = self.keys_storage.store(slot, key.as_ref()) {
+                error!("failed to persist the {} sphinx key - {err}", slot.name());
+            }
+        }
+    }
+
+    async fn current_epoch(&self) -> Option<EpochId> {
+        for client in &self.nym_api_clients {
+            match client.get_current_epoch().await {
+                Ok(interval) => return Some(interval.current_epoch_absolute_id()),
+                Err(err) => warn!(
+                    "failed to obtain the current epoch from {} - {err}",
+                    client.nym_api_client.current_url()
+                ),
+            }
+        }
+        None
+    }
+
+    fn generate_key(&self, valid_from: EpochId) -> RotatedKey {
+        let (private_key, public_key) = keygen();
+        let generated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("the system clock is set before the unix epoch")
+            .as_secs() as i64;
+
+        let announcement = SphinxKeyAnnouncement {
+            identity_key: self.identity.public_key().to_base58_string(),
+            sphinx_key: encryption::PublicKey::from(public_key).to_base58_string(),
+            validity: SphinxKeyValidity {
+                valid_from,
+                valid_until: valid_from + self.config.epochs_per_key,
+            },
+            generated_at,
+        };
+        let signature = self
+            .identity
+            .private_key()
+            .sign(&announcement.signable_bytes())
+            .to_base58_string();
+
+        RotatedKey {
+            private_key: private_key.into(),
+            announcement: SignedSphinxKeyAnnouncement {
+                announcement,
+                signature,
+            },
+        }
+    }
+
+    // the announcements are repeated on every check so that the apis that were unavailable
+    // or got restarted would eventually learn about our keys
+    async fn announce(&self, key: &RotatedKey) {
+        for client in &self.nym_api_clients {
+            match client.announce_sphinx_key(&key.announcement).await {
+                Ok(response) if response.newly_announced => info!(
+                    "announced sphinx key valid in epochs {}..{} to {}",
+                    key.validity().valid_from,
+                    key.validity().valid_until,
+                    client.nym_api_client.current_url()
+                ),
+                Ok(_) => trace!(
+                    "{} already knows our sphinx key",
+                    client.nym_api_client.current_url()
+                ),
+                Err(err) => warn!(
+                    "failed to announce our sphinx key to {} - {err}",
+                    client.nym_api_client.current_url()
+                ),
+            }
+        }
+    }
+
+    async fn check_rotation(&mut self, current_epoch: EpochId) {
+        self.packet_processor.remove_expired_keys();
+        let mut keys_changed = false;
+
+        // promote the upcoming key once its first epoch begins
+        if let Some(upcoming) = self.upcoming.take() {
+            if upcoming.validity().valid_from <= current_epoch {
+                info!("rotating the sphinx key in epoch {current_epoch}");
+                self.packet_processor
+                    .rotate_key(upcoming.sphinx_key(), self.config.grace_period);
+                self.current = Some(upcoming);
+                keys_changed = true;
+            } else {
+                self.upcoming = Some(upcoming);
+            }
+        }
+
+        if self.upcoming.is_none() {
+            // the bonded key is replaced starting from the next epoch
+            let next_key_from = match &self.current {
+                Some(current) => current.validity().valid_until.max(current_epoch),
+                None => current_epoch + 1,
+            };
+            if next_key_from <= current_epoch + ANNOUNCEMENT_LEAD_EPOCHS {
+                let next_key = self.generate_key(next_key_from);
+                if next_key_from == current_epoch {
+                    // we must have missed the rotation (say, the apis were unavailable)
+                    // so the new key has to be used straight away
+                    warn!("our sphinx key has expired - rotating it immediately");
+                    self.packet_processor
+                        .rotate_key(next_key.sphinx_key(), self.config.grace_period);
+                    self.current = Some(next_key);
+                } else {
+                    self.packet_processor
+                        .add_upcoming_key(next_key.sphinx_key());
+                    self.upcoming = Some(next_key);
+                }
+                keys_changed = true;
+            }
+        }
+
+        if keys_changed {
+            self.persist_keys();
+        }
+
+        if let Some(current) = &self.current {
+            self.announce(current).await;
+        }
+        if let Some(upcoming) = &self.upcoming {
+            self.announce(upcoming).await;
+        }
+    }
+
+    pub async fn run(&mut self) {
+        info!(
+            "Starting sphinx key rotation. Every key is going to be used for {} epochs",
+            self.config.epochs_per_key
+        );
+
+        while !self.shutdown_listener.is_shutdown() {
+            match self.current_epoch().await {
+                Some(current_epoch) => self.check_rotation(current_epoch).await,
+                None => warn!("could not determine the current epoch - will try again later"),
+            }
+
+            tokio::select! {
+                _ = sleep(self.config.epoch_check_interval) => {},
+                _ = self.shutdown_listener.recv() => {
+                    log::trace!("Shutdown received while sleeping");
+                }
+            }
+        }
+
+        log::trace!("SphinxKeyRotator: Exiting");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_07::rngs::OsRng;
+
+    fn rotator(directory: PathBuf, identity: Arc<identity::KeyPair>) -> SphinxKeyRotator {
+        let (bonded_key, _) = keygen();
+        SphinxKeyRotator::new(
+            Config::build().build(),
+            identity,
+            SphinxPacketProcessor::new(bonded_key),
+            RotatedKeysStorage::new(directory, Some("passphrase".to_string())),
+            TaskClient::dummy(),
+        )
+    }
+
+    fn assert_same_key(a: &RotatedKey, b: &RotatedKey) {
+        assert_eq!(a.private_key.to_bytes(), b.private_key.to_bytes());
+        assert_eq!(a.announcement, b.announcement);
+    }
+
+    #[test]
+    fn stored_keys_can_be_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let identity = Arc::new(identity::KeyPair::new(&mut OsRng));
+        let rotator = rotator(dir.path().to_path_buf(), identity);
+        let storage = RotatedKeysStorage::new(dir.path().join("keys"), None);
+
+        assert!(storage.load(KeySlot::Current).unwrap().is_none());
+
+        let key = rotator.generate_key(42);
+        storage.store(KeySlot::Current, Some(&key)).unwrap();
+        assert_same_key(&storage.load(KeySlot::Current).unwrap().unwrap(), &key);
+        assert!(storage.load(KeySlot::Upcoming).unwrap().is_none());
+
+        storage.store(KeySlot::Current, None).unwrap();
+        assert!(storage.load(KeySlot::Current).unwrap().is_none());
+    }
+
+    #[test]
+    fn stored_keys_are_encrypted_with_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let identity = Arc::new(identity::KeyPair::new(&mut OsRng));
+        let rotator = rotator(dir.path().to_path_buf(), identity);
+        let key = rotator.generate_key(42);
+
+        let storage = RotatedKeysStorage::new(dir.path().to_path_buf(), Some("foomp".to_string()));
+        storage.store(KeySlot::Upcoming, Some(&key)).unwrap();
+        assert_same_key(&storage.load(KeySlot::Upcoming).unwrap().unwrap(), &key);
+
+        let without_passphrase = RotatedKeysStorage::new(dir.path().to_path_buf(), None);
+        assert!(without_passphrase.load(KeySlot::Upcoming).is_err());
+
+        let wrong_passphrase =
+            RotatedKeysStorage::new(dir.path().to_path_buf(), Some("bar".to_string()));
+        assert!(wrong_passphrase.load(KeySlot::Upcoming).is_err());
+    }
+
+    #[test]
+    fn keys_are_restored_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let identity = Arc::new(identity::KeyPair::new(&mut OsRng));
+
+        let mut rotator = rotator(dir.path().to_path_buf(), Arc::clone(&identity));
+        rotator.current = Some(rotator.generate_key(10));
+        rotator.upcoming = Some(rotator.generate_key(34));
+        rotator.persist_keys();
+
+        let restored = self::rotator(dir.path().to_path_buf(), identity);
+        assert_same_key(
+            restored.current.as_ref().unwrap(),
+            rotator.current.as_ref().unwrap(),
+        );
+        assert_same_key(
+            restored.upcoming.as_ref().unwrap(),
+            rotator.upcoming.as_ref().unwrap(),
+        );
+
+        // the bonded key (within its grace period), the current and the upcoming ones
+        assert_eq!(restored.packet_processor.active_keys(), 3);
+    }
+
+    #[test]
+    fn keys_of_different_node_are_not_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let identity = Arc::new(identity::KeyPair::new(&mut OsRng));
+        let mut rotator = rotator(dir.path().to_path_buf(), identity);
+        rotator.current = Some(rotator.generate_key(10));
+        rotator.persist_keys();
+
+        let other_identity = Arc::new(identity::KeyPair::new(&mut OsRng));
+        let restored = self::rotator(dir.path().to_path_buf(), other_identity);
+        assert!(restored.current.is_none());
+        assert_eq!(restored.packet_processor.active_keys(), 1);
+    }
+}
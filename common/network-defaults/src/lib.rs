@@ -406,6 +406,14 @@ pub const BANDWIDTH_VALUE: u64 = UTOKENS_TO_BURN * BYTES_PER_UTOKEN;
 
 pub const VOUCHER_INFO: &str = "BandwidthVoucher";
 
+/// Voucher info of the credentials that get spent in multiple parts (tickets)
+pub const TICKETBOOK_VOUCHER_INFO: &str = "TicketBook";
+/// Number of tickets in a single ticketbook
+pub const TICKETBOOK_SIZE: u64 = 50;
+/// How many utokens get deposited for a single ticketbook. All ticketbooks have the same value,
+/// as otherwise their tickets could be linked together through it
+pub const TICKETBOOK_VALUE: u64 = UTOKENS_TO_BURN;
+
 pub const ETH_MIN_BLOCK_DEPTH: usize = 7;
 
 /// Defaults Cosmos Hub/ATOM path
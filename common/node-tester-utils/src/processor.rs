@@ -64,8 +64,21 @@ where
 
     pub fn process_mixnet_message(
         &mut self,
-        mut raw_message: Vec<u8>,
+        raw_message: Vec<u8>,
     ) -> Result<TestMessage<T>, NetworkTestingError>
+    where
+        T: DeserializeOwned,
+    {
+        self.process_identified_mixnet_message(raw_message)
+            .map(|(message, _)| message)
+    }
+
+    /// Processes the received mixnet message and alongside the recovered test message returns
+    /// the identifier of the fragment it was sent as, so that it could be matched against the sent packet.
+    pub fn process_identified_mixnet_message(
+        &mut self,
+        mut raw_message: Vec<u8>,
+    ) -> Result<(TestMessage<T>, FragmentIdentifier), NetworkTestingError>
     where
         T: DeserializeOwned,
     {
@@ -76,6 +89,7 @@ where
                 &mut raw_message,
             )?;
         let fragment = self.message_receiver.recover_fragment(plaintext)?;
+        let fragment_id = fragment.fragment_identifier();
 
         // test messages must consist of a single fragment
         let (serialized, _) = self
@@ -83,7 +97,7 @@ where
             .insert_new_fragment(fragment)?
             .ok_or(NetworkTestingError::NonReconstructablePacket)?;
 
-        TestMessage::try_recover(serialized)
+        Ok((TestMessage::try_recover(serialized)?, fragment_id))
     }
 
     pub fn process_ack(
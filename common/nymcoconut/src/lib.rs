@@ -21,6 +21,10 @@ pub use scheme::keygen::SecretKey;
 pub use scheme::keygen::VerificationKey;
 pub use scheme::setup::setup;
 pub use scheme::setup::Parameters;
+pub use scheme::ticketbook::compute_ticket_zeta;
+pub use scheme::ticketbook::prove_bandwidth_ticket;
+pub use scheme::ticketbook::verify_bandwidth_ticket;
+pub use scheme::ticketbook::TicketIndex;
 pub use scheme::verification::check_vk_pairing;
 pub use scheme::verification::prove_bandwidth_credential;
 pub use scheme::verification::verify_credential;
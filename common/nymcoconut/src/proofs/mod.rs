@@ -6,7 +6,7 @@
 use std::borrow::Borrow;
 use std::convert::TryInto;
 
-use bls12_381::{G1Projective, G2Projective, Scalar};
+use bls12_381::{G1Projective, G2Affine, G2Projective, Scalar};
 use digest::generic_array::typenum::Unsigned;
 use digest::Digest;
 use group::GroupEncoding;
@@ -349,6 +349,31 @@ impl ProofKappaZeta {
         blinding_factor: &Scalar,
         blinded_message: &G2Projective,
         blinded_serial_number: &G2Projective,
+    ) -> Self {
+        Self::construct_with_zeta_base(
+            params,
+            verification_key,
+            params.gen2(),
+            serial_number,
+            binding_number,
+            blinding_factor,
+            blinded_message,
+            blinded_serial_number,
+        )
+    }
+
+    /// Constructs the proof for zeta computed over an arbitrary base rather than over the generator of G2,
+    /// such as the base of particular ticket within a ticketbook.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn construct_with_zeta_base(
+        params: &Parameters,
+        verification_key: &VerificationKey,
+        zeta_base: &G2Affine,
+        serial_number: &Attribute,
+        binding_number: &Attribute,
+        blinding_factor: &Scalar,
+        blinded_message: &G2Projective,
+        blinded_serial_number: &G2Projective,
     ) -> Self {
         // create the witnesses
         let witness_blinder = params.random_scalar();
@@ -356,12 +381,6 @@ impl ProofKappaZeta {
         let witness_binding_number = params.random_scalar();
         let witness_attributes = vec![witness_serial_number, witness_binding_number];
 
-        let beta_bytes = verification_key
-            .beta_g2
-            .iter()
-            .map(|beta_i| beta_i.to_bytes())
-            .collect::<Vec<_>>();
-
         // witnesses commitments
         // Aw = g2 * wt + alpha + beta[0] * wm[0] + ... + beta[i] * wm[i]
         let commitment_kappa = params.gen2() * witness_blinder
@@ -373,16 +392,16 @@ impl ProofKappaZeta {
                 .sum::<G2Projective>();
 
         // zeta is the public value associated with the serial number
-        let commitment_zeta = params.gen2() * witness_serial_number;
-
-        let challenge = compute_challenge::<ChallengeDigest, _, _>(
-            std::iter::once(params.gen2().to_bytes().as_ref())
-                .chain(std::iter::once(blinded_message.to_bytes().as_ref()))
-                .chain(std::iter::once(blinded_serial_number.to_bytes().as_ref()))
-                .chain(std::iter::once(verification_key.alpha.to_bytes().as_ref()))
-                .chain(beta_bytes.iter().map(|b| b.as_ref()))
-                .chain(std::iter::once(commitment_kappa.to_bytes().as_ref()))
-                .chain(std::iter::once(commitment_zeta.to_bytes().as_ref())),
+        let commitment_zeta = zeta_base * witness_serial_number;
+
+        let challenge = compute_kappa_zeta_challenge(
+            params,
+            verification_key,
+            zeta_base,
+            blinded_message,
+            blinded_serial_number,
+            &commitment_kappa,
+            &commitment_zeta,
         );
 
         // responses
@@ -404,19 +423,14 @@ impl ProofKappaZeta {
         2
     }
 
-    pub(crate) fn verify(
+    pub(crate) fn verify_with_zeta_base(
         &self,
         params: &Parameters,
         verification_key: &VerificationKey,
+        zeta_base: &G2Affine,
         kappa: &G2Projective,
         zeta: &G2Projective,
     ) -> bool {
-        let beta_bytes = verification_key
-            .beta_g2
-            .iter()
-            .map(|beta_i| beta_i.to_bytes())
-            .collect::<Vec<_>>();
-
         let response_attributes = vec![self.response_serial_number, self.response_binding_number];
         // re-compute witnesses commitments
         // Aw = (c * kappa) + (rt * g2) + ((1 - c) * alpha) + (rm[0] * beta[0]) + ... + (rm[i] * beta[i])
@@ -430,17 +444,17 @@ impl ProofKappaZeta {
                 .sum::<G2Projective>();
 
         // zeta is the public value associated with the serial number
-        let commitment_zeta = zeta * self.challenge + params.gen2() * self.response_serial_number;
+        let commitment_zeta = zeta * self.challenge + zeta_base * self.response_serial_number;
 
         // compute the challenge
-        let challenge = compute_challenge::<ChallengeDigest, _, _>(
-            std::iter::once(params.gen2().to_bytes().as_ref())
-                .chain(std::iter::once(kappa.to_bytes().as_ref()))
-                .chain(std::iter::once(zeta.to_bytes().as_ref()))
-                .chain(std::iter::once(verification_key.alpha.to_bytes().as_ref()))
-                .chain(beta_bytes.iter().map(|b| b.as_ref()))
-                .chain(std::iter::once(commitment_kappa.to_bytes().as_ref()))
-                .chain(std::iter::once(commitment_zeta.to_bytes().as_ref())),
+        let challenge = compute_kappa_zeta_challenge(
+            params,
+            verification_key,
+            zeta_base,
+            kappa,
+            zeta,
+            &commitment_kappa,
+            &commitment_zeta,
         );
 
         challenge == self.challenge
@@ -505,6 +519,37 @@ impl ProofKappaZeta {
     }
 }
 
+fn compute_kappa_zeta_challenge(
+    params: &Parameters,
+    verification_key: &VerificationKey,
+    zeta_base: &G2Affine,
+    kappa: &G2Projective,
+    zeta: &G2Projective,
+    commitment_kappa: &G2Projective,
+    commitment_zeta: &G2Projective,
+) -> Scalar {
+    let beta_bytes = verification_key
+        .beta_g2
+        .iter()
+        .map(|beta_i| beta_i.to_bytes())
+        .collect::<Vec<_>>();
+
+    // the base of zeta is only included if it's not the generator of G2 so that the proofs
+    // of the regular credentials remain unchanged
+    let zeta_base_bytes = (zeta_base != params.gen2()).then(|| zeta_base.to_bytes());
+
+    compute_challenge::<ChallengeDigest, _, _>(
+        std::iter::once(params.gen2().to_bytes().as_ref())
+            .chain(zeta_base_bytes.iter().map(|b| b.as_ref()))
+            .chain(std::iter::once(kappa.to_bytes().as_ref()))
+            .chain(std::iter::once(zeta.to_bytes().as_ref()))
+            .chain(std::iter::once(verification_key.alpha.to_bytes().as_ref()))
+            .chain(beta_bytes.iter().map(|b| b.as_ref()))
+            .chain(std::iter::once(commitment_kappa.to_bytes().as_ref()))
+            .chain(std::iter::once(commitment_zeta.to_bytes().as_ref())),
+    )
+}
+
 // proof builder:
 // - commitment
 // - challenge
@@ -23,6 +23,7 @@ pub mod double_use;
 pub mod issuance;
 pub mod keygen;
 pub mod setup;
+pub mod ticketbook;
 pub mod verification;
 
 pub type SignerIndex = u64;
@@ -0,0 +1,244 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+// A ticketbook is a regular credential that gets spent in multiple, fixed-size parts (tickets).
+// Its serial number attribute acts as the secret of the whole book, while each ticket gets its own
+// blinded serial number (zeta) computed over a base derived from the index of the ticket rather than
+// over the generator of G2. Zetas of different tickets can't be linked to each other or to the book
+// (under the DDH assumption in G2), but spending the same ticket twice always results in the same value,
+// so the existing double spending detection keeps on working.
+//
+// Note that the index of the ticket is revealed upon spending it.
+
+use bls12_381::G2Projective;
+use group::Curve;
+
+use crate::error::{CoconutError, Result};
+use crate::proofs::ProofKappaZeta;
+use crate::scheme::setup::Parameters;
+use crate::scheme::verification::{compute_kappa, verify_theta, Theta};
+use crate::scheme::Signature;
+use crate::scheme::VerificationKey;
+use crate::utils::hash_g2;
+use crate::Attribute;
+
+pub type TicketIndex = u64;
+
+const TICKET_BASE_DOMAIN: &[u8] = b"coconut-ticketbook-ticket";
+
+/// Derives the base used for computing the blinded serial number of the ticket at the specified index.
+pub fn compute_ticket_base(ticket_index: TicketIndex) -> G2Projective {
+    let mut msg = TICKET_BASE_DOMAIN.to_vec();
+    msg.extend_from_slice(&ticket_index.to_be_bytes());
+    hash_g2(msg)
+}
+
+pub fn compute_ticket_zeta(wallet_secret: Attribute, ticket_index: TicketIndex) -> G2Projective {
+    compute_ticket_base(ticket_index) * wallet_secret
+}
+
+pub fn prove_bandwidth_ticket(
+    params: &Parameters,
+    verification_key: &VerificationKey,
+    signature: &Signature,
+    wallet_secret: Attribute,
+    binding_number: Attribute,
+    ticket_index: TicketIndex,
+) -> Result<Theta> {
+    if verification_key.beta_g2.len() < 2 {
+        return Err(
+            CoconutError::Verification(
+                format!("Tried to prove a ticket for higher than supported by the provided verification key number of attributes (max: {}, requested: 2)",
+                        verification_key.beta_g2.len()
+                )));
+    }
+
+    // the same as for the regular credentials, apart from zeta which is bound to the particular ticket
+    let (signature_prime, sign_blinding_factor) = signature.randomise(params);
+
+    let private_attributes = vec![wallet_secret, binding_number];
+    let blinded_message = compute_kappa(
+        params,
+        verification_key,
+        &private_attributes,
+        sign_blinding_factor,
+    );
+
+    let ticket_base = compute_ticket_base(ticket_index).to_affine();
+    let blinded_serial_number = ticket_base * wallet_secret;
+
+    let pi_v = ProofKappaZeta::construct_with_zeta_base(
+        params,
+        verification_key,
+        &ticket_base,
+        &wallet_secret,
+        &binding_number,
+        &sign_blinding_factor,
+        &blinded_message,
+        &blinded_serial_number,
+    );
+
+    Ok(Theta {
+        blinded_message,
+        blinded_serial_number,
+        credential: signature_prime,
+        pi_v,
+    })
+}
+
+pub fn verify_bandwidth_ticket(
+    params: &Parameters,
+    verification_key: &VerificationKey,
+    theta: &Theta,
+    public_attributes: &[Attribute],
+    ticket_index: TicketIndex,
+) -> bool {
+    verify_theta(
+        params,
+        verification_key,
+        &compute_ticket_base(ticket_index).to_affine(),
+        theta,
+        public_attributes,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scheme::issuance::sign;
+    use crate::scheme::keygen::keygen;
+    use crate::scheme::setup::setup;
+    use crate::scheme::verification::{prove_bandwidth_credential, verify_credential};
+
+    use super::*;
+
+    struct Ticketbook {
+        params: Parameters,
+        verification_key: VerificationKey,
+        signature: Signature,
+        wallet_secret: Attribute,
+        binding_number: Attribute,
+        public_attributes: Vec<Attribute>,
+    }
+
+    fn ticketbook_fixture() -> Ticketbook {
+        let mut params = setup(4).unwrap();
+        let keypair = keygen(&params);
+
+        let wallet_secret = params.random_scalar();
+        let binding_number = params.random_scalar();
+        let public_attributes = params.n_random_scalars(2);
+
+        let mut attributes = vec![wallet_secret, binding_number];
+        attributes.extend_from_slice(&public_attributes);
+        let signature = sign(&mut params, &keypair.secret_key(), &attributes).unwrap();
+
+        Ticketbook {
+            params,
+            verification_key: keypair.verification_key(),
+            signature,
+            wallet_secret,
+            binding_number,
+            public_attributes,
+        }
+    }
+
+    impl Ticketbook {
+        fn prove(&self, ticket_index: TicketIndex) -> Theta {
+            prove_bandwidth_ticket(
+                &self.params,
+                &self.verification_key,
+                &self.signature,
+                self.wallet_secret,
+                self.binding_number,
+                ticket_index,
+            )
+            .unwrap()
+        }
+
+        fn verify(&self, theta: &Theta, ticket_index: TicketIndex) -> bool {
+            verify_bandwidth_ticket(
+                &self.params,
+                &self.verification_key,
+                theta,
+                &self.public_attributes,
+                ticket_index,
+            )
+        }
+    }
+
+    #[test]
+    fn tickets_only_verify_for_their_index() {
+        let ticketbook = ticketbook_fixture();
+
+        let theta = ticketbook.prove(3);
+        assert!(ticketbook.verify(&theta, 3));
+        assert!(!ticketbook.verify(&theta, 2));
+        assert!(!ticketbook.verify(&theta, 4));
+        assert!(!verify_credential(
+            &ticketbook.params,
+            &ticketbook.verification_key,
+            &theta,
+            &ticketbook.public_attributes,
+        ));
+    }
+
+    #[test]
+    fn regular_credential_does_not_verify_as_ticket() {
+        let ticketbook = ticketbook_fixture();
+
+        let theta = prove_bandwidth_credential(
+            &ticketbook.params,
+            &ticketbook.verification_key,
+            &ticketbook.signature,
+            ticketbook.wallet_secret,
+            ticketbook.binding_number,
+        )
+        .unwrap();
+        assert!(verify_credential(
+            &ticketbook.params,
+            &ticketbook.verification_key,
+            &theta,
+            &ticketbook.public_attributes,
+        ));
+        assert!(!ticketbook.verify(&theta, 0));
+    }
+
+    #[test]
+    fn ticket_requires_valid_signature() {
+        let ticketbook = ticketbook_fixture();
+        let other = ticketbook_fixture();
+
+        let theta = prove_bandwidth_ticket(
+            &ticketbook.params,
+            &ticketbook.verification_key,
+            &other.signature,
+            ticketbook.wallet_secret,
+            ticketbook.binding_number,
+            1,
+        )
+        .unwrap();
+        assert!(!ticketbook.verify(&theta, 1));
+    }
+
+    #[test]
+    fn blinded_serial_numbers_are_unique_per_ticket() {
+        let ticketbook = ticketbook_fixture();
+
+        let first = ticketbook.prove(1);
+        let first_again = ticketbook.prove(1);
+        let second = ticketbook.prove(2);
+
+        // the same ticket always results in the same serial number, even though the proofs differ
+        assert_eq!(
+            first.blinded_serial_number,
+            first_again.blinded_serial_number
+        );
+        assert_ne!(first.blinded_message, first_again.blinded_message);
+        assert_eq!(
+            first.blinded_serial_number,
+            compute_ticket_zeta(ticketbook.wallet_secret, 1)
+        );
+
+        assert_ne!(first.blinded_serial_number, second.blinded_serial_number);
+    }
+}
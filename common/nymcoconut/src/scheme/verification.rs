@@ -5,7 +5,7 @@ use core::ops::Neg;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 
-use bls12_381::{multi_miller_loop, G1Affine, G2Prepared, G2Projective, Scalar};
+use bls12_381::{multi_miller_loop, G1Affine, G2Affine, G2Prepared, G2Projective, Scalar};
 use group::{Curve, Group};
 
 use crate::error::{CoconutError, Result};
@@ -72,10 +72,16 @@ impl TryFrom<&[u8]> for Theta {
 }
 
 impl Theta {
-    fn verify_proof(&self, params: &Parameters, verification_key: &VerificationKey) -> bool {
-        self.pi_v.verify(
+    fn verify_proof(
+        &self,
+        params: &Parameters,
+        verification_key: &VerificationKey,
+        zeta_base: &G2Affine,
+    ) -> bool {
+        self.pi_v.verify_with_zeta_base(
             params,
             verification_key,
+            zeta_base,
             &self.blinded_message,
             &self.blinded_serial_number,
         )
@@ -250,6 +256,23 @@ pub fn verify_credential(
     verification_key: &VerificationKey,
     theta: &Theta,
     public_attributes: &[Attribute],
+) -> bool {
+    verify_theta(
+        params,
+        verification_key,
+        params.gen2(),
+        theta,
+        public_attributes,
+    )
+}
+
+/// Verifies the provided theta with its zeta (the blinded serial number) computed over the specified base.
+pub(crate) fn verify_theta(
+    params: &Parameters,
+    verification_key: &VerificationKey,
+    zeta_base: &G2Affine,
+    theta: &Theta,
+    public_attributes: &[Attribute],
 ) -> bool {
     if public_attributes.len() + theta.pi_v.private_attributes_len()
         > verification_key.beta_g2.len()
@@ -257,7 +280,7 @@ pub fn verify_credential(
         return false;
     }
 
-    if !theta.verify_proof(params, verification_key) {
+    if !theta.verify_proof(params, verification_key, zeta_base) {
         return false;
     }
 
@@ -0,0 +1,140 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional forward error correction of `FragmentSet`s.
+//!
+//! When enabled, the sender appends a number of Reed-Solomon parity `Fragment`s to the set,
+//! so that the recipient is able to reconstruct the message from any `k` out of the `n` sent
+//! `Fragment`s, where `k` is the number of `Fragment`s containing the actual data.
+//! It means a lost packet no longer has to wait for the retransmission timeout,
+//! which is particularly important for replies sent with a limited number of reply SURBs.
+//!
+//! Note that erasure coding is only applied to messages fitting in a single `FragmentSet`.
+
+use reed_solomon_erasure::galois_8::ReedSolomon;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of parity `Fragment`s in a single `FragmentSet`. It's restricted by the
+/// header encoding, where the most significant bit of the byte holding the value
+/// is reserved for the linking flag.
+pub const MAX_PARITY_FRAGMENTS: u8 = 127;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ErasureCoding {
+    /// Number of parity `Fragment`s, relative to the number of data `Fragment`s, expressed in percent.
+    /// The resultant value is always rounded up, so that at least a single parity `Fragment` is created.
+    pub parity_percentage: u8,
+}
+
+impl ErasureCoding {
+    pub fn new(parity_percentage: u8) -> Self {
+        ErasureCoding { parity_percentage }
+    }
+
+    /// Determines number of parity `Fragment`s that should be created for the given number of
+    /// data `Fragment`s. Returns `None` if the set would not have space for any of them.
+    pub fn parity_fragments(&self, data_fragments: usize) -> Option<u8> {
+        if data_fragments == 0 {
+            return None;
+        }
+
+        let available = (u8::MAX as usize)
+            .checked_sub(data_fragments)?
+            .min(MAX_PARITY_FRAGMENTS as usize);
+        if available == 0 {
+            return None;
+        }
+
+        let wanted = (data_fragments * self.parity_percentage as usize).div_ceil(100);
+        Some(wanted.clamp(1, available) as u8)
+    }
+}
+
+/// Creates the specified number of parity shards for the provided data shards.
+/// All data shards must have identical, non-zero, length.
+pub(crate) fn generate_parity_shards(data_shards: &[Vec<u8>], parity_shards: u8) -> Vec<Vec<u8>> {
+    let encoder = ReedSolomon::new(data_shards.len(), parity_shards as usize)
+        .expect("the number of data and parity shards is always within the valid range");
+
+    let shard_len = data_shards[0].len();
+    let mut shards = data_shards.to_vec();
+    shards.resize(
+        data_shards.len() + parity_shards as usize,
+        vec![0; shard_len],
+    );
+
+    encoder
+        .encode(&mut shards)
+        .expect("all shards have the same length");
+    shards.split_off(data_shards.len())
+}
+
+/// Recovers any missing data shards, in place, using the remaining data and parity shards.
+pub(crate) fn reconstruct_data_shards(
+    shards: &mut [Option<Vec<u8>>],
+    parity_shards: u8,
+) -> Result<(), reed_solomon_erasure::Error> {
+    let data_shards = shards.len() - parity_shards as usize;
+    ReedSolomon::new(data_shards, parity_shards as usize)?.reconstruct_data(shards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_of_parity_fragments_is_rounded_up() {
+        let erasure_coding = ErasureCoding::new(20);
+        assert_eq!(Some(1), erasure_coding.parity_fragments(1));
+        assert_eq!(Some(1), erasure_coding.parity_fragments(5));
+        assert_eq!(Some(2), erasure_coding.parity_fragments(6));
+        assert_eq!(Some(1), ErasureCoding::new(0).parity_fragments(10));
+    }
+
+    #[test]
+    fn number_of_parity_fragments_is_bounded() {
+        assert_eq!(Some(127), ErasureCoding::new(200).parity_fragments(100));
+        assert_eq!(Some(5), ErasureCoding::new(100).parity_fragments(250));
+        assert_eq!(None, ErasureCoding::new(100).parity_fragments(255));
+        assert_eq!(None, ErasureCoding::new(100).parity_fragments(0));
+    }
+
+    #[test]
+    fn data_can_be_recovered_from_any_subset_of_sufficient_size() {
+        let data_shards: Vec<_> = (0..4u8).map(|i| vec![i; 32]).collect();
+        let parity_shards = generate_parity_shards(&data_shards, 2);
+        assert_eq!(2, parity_shards.len());
+
+        let mut shards: Vec<_> = data_shards
+            .iter()
+            .cloned()
+            .chain(parity_shards)
+            .map(Some)
+            .collect();
+        shards[0] = None;
+        shards[2] = None;
+
+        reconstruct_data_shards(&mut shards, 2).unwrap();
+        for (i, shard) in data_shards.into_iter().enumerate() {
+            assert_eq!(Some(shard), shards[i]);
+        }
+    }
+
+    #[test]
+    fn data_cant_be_recovered_with_too_many_missing_shards() {
+        let data_shards: Vec<_> = (0..4u8).map(|i| vec![i; 32]).collect();
+        let parity_shards = generate_parity_shards(&data_shards, 2);
+
+        let mut shards: Vec<_> = data_shards
+            .into_iter()
+            .chain(parity_shards)
+            .map(Some)
+            .collect();
+        shards[0] = None;
+        shards[1] = None;
+        shards[5] = None;
+
+        assert!(reconstruct_data_shards(&mut shards, 2).is_err());
+    }
+}
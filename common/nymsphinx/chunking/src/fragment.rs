@@ -1,6 +1,7 @@
 // Copyright 2021-2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::erasure_coding::MAX_PARITY_FRAGMENTS;
 use crate::ChunkingError;
 use nym_sphinx_params::{SerializedFragmentIdentifier, FRAG_ID_LEN};
 use std::convert::TryInto;
@@ -191,6 +192,41 @@ impl Fragment {
         })
     }
 
+    /// Tries to encapsulate provided payload slice and metadata into a `Fragment` belonging to
+    /// an erasure coded set, i.e. one whose last `parity_fragments` fragments contain
+    /// Reed-Solomon parity data rather than the message itself.
+    /// All fragments of such set must be full, i.e. their payloads must have the maximum unlinked length.
+    pub(crate) fn try_new_erasure_coded(
+        payload: &[u8],
+        id: i32,
+        total_fragments: u8,
+        current_fragment: u8,
+        parity_fragments: u8,
+        max_plaintext_size: usize,
+    ) -> Result<Self, ChunkingError> {
+        let header = FragmentHeader::try_new_with_parity(
+            id,
+            total_fragments,
+            current_fragment,
+            None,
+            None,
+            parity_fragments,
+        )?;
+
+        let max_unlinked_len = unlinked_fragment_payload_max_len(max_plaintext_size);
+        if payload.len() != max_unlinked_len {
+            return Err(ChunkingError::InvalidPayloadLengthError {
+                received: payload.len(),
+                expected: max_unlinked_len,
+            });
+        }
+
+        Ok(Fragment {
+            header,
+            payload: payload.to_vec(),
+        })
+    }
+
     /// based on the size of the embedded data, determines which predefined `PacketSize`
     /// was used for construction of this `Fragment`
     pub fn serialized_size(&self) -> usize {
@@ -247,6 +283,23 @@ impl Fragment {
         self.header.next_fragments_set_id
     }
 
+    /// Extracts number of Reed-Solomon parity `Fragment`s in the `FragmentSet`
+    /// this `Fragment` belongs to. It's 0 if the set is not erasure coded.
+    pub fn parity_fragments(&self) -> u8 {
+        self.header.parity_fragments
+    }
+
+    /// Extracts number of `Fragment`s in the `FragmentSet` that contain the actual message data,
+    /// i.e. the minimum number of `Fragment`s required for reconstructing the set.
+    pub fn data_fragments(&self) -> u8 {
+        self.header.total_fragments - self.header.parity_fragments
+    }
+
+    /// Checks whether this `Fragment` contains Reed-Solomon parity data rather than part of the message.
+    pub fn is_parity(&self) -> bool {
+        self.header.current_fragment > self.data_fragments()
+    }
+
     /// Consumes `self` to obtain payload (i.e. part of original message) associated with this
     /// `Fragment`.
     pub(crate) fn extract_payload(self) -> Vec<u8> {
@@ -281,6 +334,11 @@ impl Fragment {
 /// further note if LID is not set,
 /// then the Linked ID bytes in the header are used as payload.
 ///
+/// Finally, if the set is erasure coded, which is only allowed for unlinked sets,
+/// the byte following CurrentFragment, rather than being '0', holds the number of
+/// Reed-Solomon parity `Fragment`s (PF) located at the end of the set:
+/// '1'bit || 31-bit ID || 1-byte TF || 1 byte CF || '0'bit || 7-bit PF
+///
 /// Hence after marshaling `FragmentHeader` into bytes,
 /// the following three alternatives are possible:
 ///
@@ -321,6 +379,10 @@ pub(crate) struct FragmentHeader {
     /// Optional ID of next `FragmentSet` into which the original message was split.
     /// Note, this option is only valid of `current_fragment == total_fragments == u8::max_value()`
     next_fragments_set_id: Option<i32>,
+
+    /// Number of Reed-Solomon parity `Fragment`s at the end of the `FragmentSet`.
+    /// Note, it must be 0 if the set is linked.
+    parity_fragments: u8,
 }
 
 impl FragmentHeader {
@@ -333,6 +395,26 @@ impl FragmentHeader {
         current_fragment: u8,
         previous_fragments_set_id: Option<i32>,
         next_fragments_set_id: Option<i32>,
+    ) -> Result<Self, ChunkingError> {
+        Self::try_new_with_parity(
+            id,
+            total_fragments,
+            current_fragment,
+            previous_fragments_set_id,
+            next_fragments_set_id,
+            0,
+        )
+    }
+
+    /// Like `try_new`, but additionally allows specifying the number of parity `Fragment`s
+    /// in the erasure coded set.
+    fn try_new_with_parity(
+        id: i32,
+        total_fragments: u8,
+        current_fragment: u8,
+        previous_fragments_set_id: Option<i32>,
+        next_fragments_set_id: Option<i32>,
+        parity_fragments: u8,
     ) -> Result<Self, ChunkingError> {
         if id <= 0 {
             return Err(ChunkingError::MalformedHeaderError);
@@ -356,6 +438,17 @@ impl FragmentHeader {
                 return Err(ChunkingError::MalformedHeaderError);
             }
         }
+        if parity_fragments > 0 {
+            // there must be at least a single data fragment, the linked sets can't be erasure coded
+            // and the number must fit in 7 bits
+            if parity_fragments >= total_fragments
+                || parity_fragments > MAX_PARITY_FRAGMENTS
+                || previous_fragments_set_id.is_some()
+                || next_fragments_set_id.is_some()
+            {
+                return Err(ChunkingError::MalformedHeaderError);
+            }
+        }
 
         Ok(FragmentHeader {
             id,
@@ -363,6 +456,7 @@ impl FragmentHeader {
             current_fragment,
             previous_fragments_set_id,
             next_fragments_set_id,
+            parity_fragments,
         })
     }
 
@@ -393,9 +487,10 @@ impl FragmentHeader {
 
         let mut previous_fragments_set_id = None;
         let mut next_fragments_set_id = None;
+        let mut parity_fragments = 0;
 
         // check if the linking id flag might be set
-        let read_bytes = if b[6] != 0 {
+        let read_bytes = if b[6] & (1 << 7) != 0 {
             // there's linking ID supposedly attached, make sure we have enough bytes to parse
             if b.len() < LINKED_FRAGMENTED_HEADER_LEN {
                 return Err(ChunkingError::TooShortFragmentHeader {
@@ -422,16 +517,19 @@ impl FragmentHeader {
 
             10
         } else {
+            // if the set is not linked, the byte holds the number of parity fragments instead
+            parity_fragments = b[6];
             7
         };
 
         Ok((
-            Self::try_new(
+            Self::try_new_with_parity(
                 id,
                 total_fragments,
                 current_fragment,
                 previous_fragments_set_id,
                 next_fragments_set_id,
+                parity_fragments,
             )?,
             read_bytes,
         ))
@@ -458,7 +556,9 @@ impl FragmentHeader {
                 .chain(linked_id_bytes.iter().cloned())
                 .collect()
         } else {
-            bytes_prefix_iter.chain(std::iter::once(0)).collect()
+            bytes_prefix_iter
+                .chain(std::iter::once(self.parity_fragments))
+                .collect()
         }
     }
 }
@@ -913,6 +1013,30 @@ mod fragment_tests {
         )
         .is_err());
     }
+
+    #[test]
+    fn erasure_coded_fragment_can_be_converted_to_and_from_bytes() {
+        let mut rng = thread_rng();
+        let mut msg = vec![0u8; unlinked_fragment_payload_max_len(max_plaintext_size())];
+        rng.fill_bytes(&mut msg);
+
+        let fragment =
+            Fragment::try_new_erasure_coded(&msg, 12345, 10, 10, 3, max_plaintext_size()).unwrap();
+        assert!(fragment.is_parity());
+        assert_eq!(7, fragment.data_fragments());
+
+        let recovered = Fragment::try_from_bytes(&fragment.clone().into_bytes()).unwrap();
+        assert!(fragment == recovered);
+        assert_eq!(3, recovered.parity_fragments());
+    }
+
+    #[test]
+    fn erasure_coded_fragment_returns_error_when_created_with_non_full_payload() {
+        let msg = vec![0u8; unlinked_fragment_payload_max_len(max_plaintext_size()) - 1];
+        assert!(
+            Fragment::try_new_erasure_coded(&msg, 12345, 10, 1, 3, max_plaintext_size()).is_err()
+        );
+    }
 }
 
 #[cfg(test)]
@@ -1027,6 +1151,7 @@ mod fragment_header {
                 current_fragment: 11,
                 previous_fragments_set_id: None,
                 next_fragments_set_id: None,
+                parity_fragments: 0,
             };
             let header_bytes = header.to_bytes();
             assert!(FragmentHeader::try_from_bytes(&header_bytes).is_err());
@@ -1041,6 +1166,7 @@ mod fragment_header {
                 current_fragment: 0,
                 previous_fragments_set_id: None,
                 next_fragments_set_id: None,
+                parity_fragments: 0,
             };
             let header_bytes = header.to_bytes();
             assert!(FragmentHeader::try_from_bytes(&header_bytes).is_err());
@@ -1142,4 +1268,56 @@ mod fragment_header {
             assert_eq!(LINKED_FRAGMENTED_HEADER_LEN, bytes_used);
         }
     }
+
+    #[cfg(test)]
+    mod erasure_coded_fragmented_payload {
+        use super::*;
+
+        #[test]
+        fn can_be_converted_to_and_from_bytes() {
+            let fragmented_header =
+                FragmentHeader::try_new_with_parity(12345, 10, 9, None, None, 2).unwrap();
+
+            let header_bytes = fragmented_header.to_bytes();
+            let (recovered_header, bytes_used) =
+                FragmentHeader::try_from_bytes(&header_bytes).unwrap();
+            assert_eq!(fragmented_header, recovered_header);
+            assert_eq!(UNLINKED_FRAGMENTED_HEADER_LEN, bytes_used);
+        }
+
+        #[test]
+        fn creation_of_header_fails_without_any_data_fragments() {
+            assert!(FragmentHeader::try_new_with_parity(12345, 10, 5, None, None, 10).is_err());
+        }
+
+        #[test]
+        fn creation_of_header_fails_for_too_many_parity_fragments() {
+            assert!(FragmentHeader::try_new_with_parity(12345, 255, 5, None, None, 128).is_err());
+        }
+
+        #[test]
+        fn creation_of_header_fails_for_linked_set() {
+            assert!(
+                FragmentHeader::try_new_with_parity(12345, 10, 1, Some(1234), None, 2).is_err()
+            );
+            assert!(
+                FragmentHeader::try_new_with_parity(12345, 10, 10, None, Some(1234), 2).is_err()
+            );
+        }
+
+        #[test]
+        fn retrieval_from_bytes_fail_without_any_data_fragments() {
+            // manually create header to overwrite any constructor checks
+            let header = FragmentHeader {
+                id: 1234,
+                total_fragments: 10,
+                current_fragment: 5,
+                previous_fragments_set_id: None,
+                next_fragments_set_id: None,
+                parity_fragments: 10,
+            };
+            let header_bytes = header.to_bytes();
+            assert!(FragmentHeader::try_from_bytes(&header_bytes).is_err());
+        }
+    }
 }
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::fragment::{linked_fragment_payload_max_len, unlinked_fragment_payload_max_len};
-pub use set::split_into_sets;
+pub use erasure_coding::ErasureCoding;
+pub use set::{split_into_sets, split_into_sets_with_erasure_coding};
 use thiserror::Error;
 
 pub const MIN_PADDING_OVERHEAD: usize = 1;
@@ -18,6 +19,7 @@ pub const MIN_PADDING_OVERHEAD: usize = 1;
 // they should definitely be revisited.
 // For instance there are not tests for the cases when we are padding the message
 
+pub mod erasure_coding;
 pub mod fragment;
 pub mod reconstruction;
 pub mod set;
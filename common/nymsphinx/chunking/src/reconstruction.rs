@@ -1,9 +1,24 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
+use crate::erasure_coding;
 use crate::fragment::Fragment;
 use crate::ChunkingError;
 use log::*;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use wasm_timer::Instant;
+
+/// Default maximum amount of time a partially received set is kept around before it's assumed
+/// its remaining fragments got lost and it's not going to be possible to reconstruct it.
+pub const DEFAULT_MAXIMUM_PARTIAL_MESSAGE_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Default maximum number of payload bytes that can be buffered across all partially received sets.
+pub const DEFAULT_MAXIMUM_BUFFERED_BYTES: usize = 128 * 1024 * 1024;
 
 // TODO: perhaps a more sophisticated approach with writing to disk periodically in case
 // we're receiving fast & furious in uncompressed 4K - we don't want to keep that in memory;
@@ -31,6 +46,18 @@ struct ReconstructionBuffer {
     /// appropriately resized and all missing fragments are set to a `None`, thus keeping
     /// everything in order the whole time, allowing for O(1) insertions and O(n) reconstruction.
     fragments: Vec<Option<Fragment>>,
+
+    /// Time at which the first `Fragment` of this set has been received.
+    /// It is used for determining whether the set has become stale.
+    first_received: Instant,
+
+    /// Total number of payload bytes currently held by the `fragments` of this buffer.
+    buffered_bytes: usize,
+
+    /// Number of Reed-Solomon parity `Fragment`s at the end of the set.
+    /// If non-zero, the set can be reconstructed from any `fragments.len() - parity_fragments`
+    /// of its `Fragment`s.
+    parity_fragments: u8,
 }
 
 /// Type alias representing fully reconstructed message - its original data and list of all
@@ -56,9 +83,34 @@ impl ReconstructionBuffer {
             previous_fragments_set_id: None,
             next_fragments_set_id: None,
             fragments: fragments_buffer,
+            first_received: Instant::now(),
+            buffered_bytes: 0,
+            parity_fragments: 0,
         }
     }
 
+    /// Initialises new instance of a `ReconstructionBuffer` for an erasure coded set,
+    /// whose final `parity_fragments` `Fragment`s contain Reed-Solomon parity data.
+    fn new_erasure_coded(size: u8, parity_fragments: u8) -> Self {
+        debug_assert!(parity_fragments < size);
+
+        ReconstructionBuffer {
+            parity_fragments,
+            ..ReconstructionBuffer::new(size)
+        }
+    }
+
+    /// Returns the number of `Fragment`s containing the actual set data, i.e. the number
+    /// of `Fragment`s that have to be received in order to reconstruct the set.
+    fn data_fragments(&self) -> usize {
+        self.fragments.len() - self.parity_fragments as usize
+    }
+
+    /// Returns the number of `Fragment`s that have already been received for this set.
+    fn received_fragments(&self) -> usize {
+        self.fragments.iter().filter(|frag| frag.is_some()).count()
+    }
+
     /// After receiving all data, consumes `self` in order to recover original data
     /// encapsulated in this particular set.
     fn reconstruct_set_data(self) -> Vec<u8> {
@@ -66,10 +118,33 @@ impl ReconstructionBuffer {
         // if the set is complete.
         debug_assert!(self.is_complete);
 
-        self.fragments
+        if self.parity_fragments == 0 {
+            return self
+                .fragments
+                .into_iter()
+                .map(|fragment| fragment.unwrap().extract_payload())
+                .flat_map(|fragment_data| fragment_data.into_iter())
+                .collect();
+        }
+
+        let data_fragments = self.data_fragments();
+        let mut shards: Vec<_> = self
+            .fragments
             .into_iter()
-            .map(|fragment| fragment.unwrap().extract_payload())
-            .flat_map(|fragment_data| fragment_data.into_iter())
+            .map(|fragment| fragment.map(Fragment::extract_payload))
+            .collect();
+
+        if shards[..data_fragments].contains(&None) {
+            // the shard lengths were validated on insertion and we have at least `data_fragments`
+            // of them, so the reconstruction can't fail
+            erasure_coding::reconstruct_data_shards(&mut shards, self.parity_fragments)
+                .expect("failed to recover missing data fragments of the erasure coded set");
+        }
+
+        shards
+            .into_iter()
+            .take(data_fragments)
+            .flat_map(|shard| shard.unwrap().into_iter())
             .collect()
     }
 
@@ -78,8 +153,34 @@ impl ReconstructionBuffer {
     // we might have false positives if somehow we receive a duplicate
     /// Checks if `self` is done receiving `Fragment` data by checking if there are still
     /// any `None` elements in the `fragments` vector.
+    /// If the set is erasure coded, it's sufficient to have received any `data_fragments` of them.
     fn is_done_receiving(&self) -> bool {
-        !self.fragments.contains(&None)
+        if self.parity_fragments == 0 {
+            !self.fragments.contains(&None)
+        } else {
+            self.received_fragments() >= self.data_fragments()
+        }
+    }
+
+    /// Checks whether the `Fragment` is consistent with the ones already in the buffer,
+    /// so that it would not later break the set reconstruction.
+    fn is_consistent(&self, fragment: &Fragment) -> bool {
+        if fragment.total_fragments() as usize != self.fragments.len()
+            || fragment.parity_fragments() != self.parity_fragments
+        {
+            return false;
+        }
+
+        // all shards of an erasure coded set must have the same, non-zero, length
+        if self.parity_fragments > 0 {
+            if fragment.payload_size() == 0 {
+                return false;
+            }
+            if let Some(present) = self.fragments.iter().flatten().next() {
+                return present.payload_size() == fragment.payload_size();
+            }
+        }
+        true
     }
 
     /// Inserts new `Fragment` data into an appropriate position in the buffer.
@@ -104,6 +205,15 @@ impl ReconstructionBuffer {
             }
         });
 
+        if !self.is_consistent(&fragment) {
+            warn!(
+                "received fragment {} (set id: {}) is inconsistent with the rest of its set - it's going to be ignored",
+                fragment.current_fragment(),
+                fragment.id()
+            );
+            return;
+        }
+
         let fragment_index = fragment.current_fragment() as usize - 1;
         if self.fragments[fragment_index].is_some() {
             // TODO: what to do in that case? give up on the message? overwrite it? panic?
@@ -115,9 +225,16 @@ impl ReconstructionBuffer {
                 fragment.id()
             );
         }
-        self.fragments[fragment_index] = Some(fragment);
+        self.buffered_bytes += fragment.payload_size();
+        if let Some(replaced) = self.fragments[fragment_index].replace(fragment) {
+            self.buffered_bytes -= replaced.payload_size();
+        }
         if self.is_done_receiving() {
             self.is_complete = true;
+            if self.parity_fragments > 0 {
+                // erasure coded sets are never linked
+                return;
+            }
             self.previous_fragments_set_id = self.fragments[0]
                 .as_ref()
                 .unwrap()
@@ -134,19 +251,71 @@ impl ReconstructionBuffer {
     }
 }
 
+/// Limits imposed on the data buffered by the `MessageReconstructor` so that a misbehaving
+/// (or malicious) sender could not make us keep incomplete messages in memory indefinitely.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct ReconstructionLimits {
+    /// Maximum amount of time, counting from the arrival of its first fragment,
+    /// a set is kept around before it's dropped if the message it belongs to has not been
+    /// reconstructed.
+    pub maximum_partial_message_age: Duration,
+
+    /// Maximum number of payload bytes that can be buffered across all sets.
+    /// Once exceeded, the oldest sets are dropped until we're back within the budget.
+    pub maximum_buffered_bytes: usize,
+}
+
+impl Default for ReconstructionLimits {
+    fn default() -> Self {
+        ReconstructionLimits {
+            maximum_partial_message_age: DEFAULT_MAXIMUM_PARTIAL_MESSAGE_AGE,
+            maximum_buffered_bytes: DEFAULT_MAXIMUM_BUFFERED_BYTES,
+        }
+    }
+}
+
+/// Information about a set of an incomplete message that was dropped by the `MessageReconstructor`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct DroppedSet {
+    /// Id of the dropped set.
+    pub set_id: i32,
+
+    /// Number of `Fragment`s of the set that had been received before it got dropped.
+    pub received_fragments: usize,
+
+    /// Number of `Fragment`s the set was expected to consist of.
+    pub total_fragments: usize,
+
+    /// Number of payload bytes that were buffered for the set.
+    pub buffered_bytes: usize,
+}
+
+impl DroppedSet {
+    fn new(set_id: i32, buf: &ReconstructionBuffer) -> Self {
+        DroppedSet {
+            set_id,
+            received_fragments: buf.received_fragments(),
+            total_fragments: buf.fragments.len(),
+            buffered_bytes: buf.buffered_bytes,
+        }
+    }
+}
+
 /// High level public structure used to buffer all received data `Fragment`s and eventually
 /// returning original messages that they encapsulate.
 #[derive(Default, PartialEq, Debug, Clone)]
 pub struct MessageReconstructor {
-    // TODO: some cleaner thread/routine that if message is incomplete and
-    // we haven't received any fragments in X time, we assume they
-    // were lost and message can't be restored.
-    // Perhaps add 'latest_fragment_timestamp' to each buffer
-    // and after N fragments received globally, check all of buffer timestamps.
-    // otherwise we are vulnerable to heap overflow attacks -> somebody can keep on sending
-    // maximum sized sets but without one of required fragments. All of the received
-    // data will be kept on the heap indefinitely in the current implementation.
     reconstructed_sets: HashMap<i32, ReconstructionBuffer>,
+
+    /// Limits on the amount of time and memory the incomplete messages can take.
+    limits: ReconstructionLimits,
+
+    /// Total number of payload bytes currently buffered across all sets.
+    buffered_bytes: usize,
+
+    /// Sets that got dropped due to exceeding the byte budget since the last call
+    /// to `remove_stale_sets`.
+    dropped_over_budget: Vec<DroppedSet>,
 }
 
 impl MessageReconstructor {
@@ -155,6 +324,78 @@ impl MessageReconstructor {
         Default::default()
     }
 
+    /// Changes the limits imposed on the buffered incomplete messages.
+    pub fn set_limits(&mut self, limits: ReconstructionLimits) {
+        self.limits = limits;
+    }
+
+    /// Returns the total number of payload bytes currently buffered across all sets.
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffered_bytes
+    }
+
+    /// Returns the number of sets currently held by the `MessageReconstructor`.
+    pub fn buffered_sets(&self) -> usize {
+        self.reconstructed_sets.len()
+    }
+
+    /// Removes the set of given `id` without attempting to reconstruct it.
+    fn drop_set(&mut self, set_id: i32) -> Option<DroppedSet> {
+        let buf = self.reconstructed_sets.remove(&set_id)?;
+        self.buffered_bytes = self.buffered_bytes.saturating_sub(buf.buffered_bytes);
+        Some(DroppedSet::new(set_id, &buf))
+    }
+
+    /// Drops the oldest sets until the total number of buffered bytes is within the configured budget.
+    fn enforce_byte_budget(&mut self) {
+        if self.buffered_bytes <= self.limits.maximum_buffered_bytes {
+            return;
+        }
+
+        let mut by_age: Vec<_> = self
+            .reconstructed_sets
+            .iter()
+            .map(|(id, buf)| (buf.first_received, *id))
+            .collect();
+        // note: `Instant` used in wasm only implements `PartialOrd`
+        by_age.sort_unstable_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        for (_, set_id) in by_age {
+            if self.buffered_bytes <= self.limits.maximum_buffered_bytes {
+                break;
+            }
+            if let Some(dropped) = self.drop_set(set_id) {
+                warn!(
+                    "exceeded the reconstruction buffer budget - dropping set {set_id} ({}/{} fragments received)",
+                    dropped.received_fragments, dropped.total_fragments
+                );
+                self.dropped_over_budget.push(dropped);
+            }
+        }
+    }
+
+    /// Removes all sets whose first fragment has been received more than the maximum partial
+    /// message age ago, as it's assumed the remaining fragments got lost.
+    /// It returns information on all dropped sets, including the ones that got removed
+    /// since the last call due to exceeding the byte budget.
+    pub fn remove_stale_sets(&mut self) -> Vec<DroppedSet> {
+        self.remove_stale_sets_at(Instant::now())
+    }
+
+    fn remove_stale_sets_at(&mut self, now: Instant) -> Vec<DroppedSet> {
+        let max_age = self.limits.maximum_partial_message_age;
+        let stale: Vec<_> = self
+            .reconstructed_sets
+            .iter()
+            .filter(|(_, buf)| buf.first_received + max_age < now)
+            .map(|(id, _)| *id)
+            .collect();
+
+        let mut dropped = std::mem::take(&mut self.dropped_over_budget);
+        dropped.extend(stale.into_iter().filter_map(|id| self.drop_set(id)));
+        dropped
+    }
+
     /// Given fully received set of given `id`, if it has any post-linked sets, recursively
     /// checks if all of them were also fully received.
     fn check_front_chain(&self, id: i32) -> bool {
@@ -237,10 +478,9 @@ impl MessageReconstructor {
     /// Note, before you call this method, you *must* ensure set was fully received
     fn extract_set_payload(&mut self, set_id: i32) -> Vec<u8> {
         debug_assert!(self.is_set_fully_received(set_id));
-        self.reconstructed_sets
-            .remove(&set_id)
-            .unwrap()
-            .reconstruct_set_data()
+        let buf = self.reconstructed_sets.remove(&set_id).unwrap();
+        self.buffered_bytes = self.buffered_bytes.saturating_sub(buf.buffered_bytes);
+        buf.reconstruct_set_data()
     }
 
     // Future consideration: perhaps for long messages, rather than return whole data allocated
@@ -267,19 +507,29 @@ impl MessageReconstructor {
     /// If a buffer does not exist, a new instance is created.
     /// If it was last remaining `Fragment` for the original message, the message is reconstructed
     /// and returned alongside all (if applicable) set ids used in the message.
+    /// Otherwise, if the buffered data exceeds the byte budget, the oldest sets are dropped.
     pub fn insert_new_fragment(&mut self, fragment: Fragment) -> Option<ReconstructedMessage> {
         let set_id = fragment.id();
         let set_len = fragment.total_fragments();
+        let parity_fragments = fragment.parity_fragments();
 
-        let buf = self
-            .reconstructed_sets
-            .entry(set_id)
-            .or_insert_with(|| ReconstructionBuffer::new(set_len));
+        let buf = self.reconstructed_sets.entry(set_id).or_insert_with(|| {
+            if parity_fragments > 0 {
+                ReconstructionBuffer::new_erasure_coded(set_len, parity_fragments)
+            } else {
+                ReconstructionBuffer::new(set_len)
+            }
+        });
 
+        let bytes_before = buf.buffered_bytes;
         buf.insert_fragment(fragment);
+        self.buffered_bytes =
+            (self.buffered_bytes + buf.buffered_bytes).saturating_sub(bytes_before);
+
         if self.is_message_fully_received(set_id) {
             Some(self.reconstruct_message(set_id))
         } else {
+            self.enforce_byte_budget();
             None
         }
     }
@@ -865,6 +1115,9 @@ mod message_reconstructor {
                 previous_fragments_set_id: None,
                 next_fragments_set_id: None,
                 fragments: vec![],
+                first_received: Instant::now(),
+                buffered_bytes: 0,
+                parity_fragments: 0,
             },
         );
 
@@ -875,6 +1128,9 @@ mod message_reconstructor {
                 previous_fragments_set_id: None,
                 next_fragments_set_id: None,
                 fragments: vec![],
+                first_received: Instant::now(),
+                buffered_bytes: 0,
+                parity_fragments: 0,
             },
         );
 
@@ -1002,6 +1258,9 @@ mod message_reconstructor {
                 previous_fragments_set_id: None,
                 next_fragments_set_id: Some(1234),
                 fragments: vec![],
+                first_received: Instant::now(),
+                buffered_bytes: 0,
+                parity_fragments: 0,
             },
         );
 
@@ -1012,6 +1271,9 @@ mod message_reconstructor {
                 previous_fragments_set_id: Some(12345),
                 next_fragments_set_id: Some(123),
                 fragments: vec![],
+                first_received: Instant::now(),
+                buffered_bytes: 0,
+                parity_fragments: 0,
             },
         );
 
@@ -1022,6 +1284,9 @@ mod message_reconstructor {
                 previous_fragments_set_id: Some(1234),
                 next_fragments_set_id: Some(12),
                 fragments: vec![],
+                first_received: Instant::now(),
+                buffered_bytes: 0,
+                parity_fragments: 0,
             },
         );
 
@@ -1032,6 +1297,9 @@ mod message_reconstructor {
                 previous_fragments_set_id: Some(123),
                 next_fragments_set_id: None,
                 fragments: vec![],
+                first_received: Instant::now(),
+                buffered_bytes: 0,
+                parity_fragments: 0,
             },
         );
 
@@ -1079,6 +1347,9 @@ mod message_reconstructor {
                 previous_fragments_set_id: None,
                 next_fragments_set_id: Some(1234),
                 fragments: vec![],
+                first_received: Instant::now(),
+                buffered_bytes: 0,
+                parity_fragments: 0,
             },
         );
         reconstructor.reconstructed_sets.insert(
@@ -1088,6 +1359,9 @@ mod message_reconstructor {
                 previous_fragments_set_id: Some(12345),
                 next_fragments_set_id: None,
                 fragments: vec![],
+                first_received: Instant::now(),
+                buffered_bytes: 0,
+                parity_fragments: 0,
             },
         );
         assert_eq!(reconstructor.previous_linked_set_id(12345), None);
@@ -1135,6 +1409,9 @@ mod message_reconstructor {
                 previous_fragments_set_id: None,
                 next_fragments_set_id: Some(1234),
                 fragments: vec![],
+                first_received: Instant::now(),
+                buffered_bytes: 0,
+                parity_fragments: 0,
             },
         );
         reconstructor.reconstructed_sets.insert(
@@ -1144,6 +1421,9 @@ mod message_reconstructor {
                 previous_fragments_set_id: Some(12345),
                 next_fragments_set_id: None,
                 fragments: vec![],
+                first_received: Instant::now(),
+                buffered_bytes: 0,
+                parity_fragments: 0,
             },
         );
         assert_eq!(reconstructor.next_linked_set_id(12345), Some(1234));
@@ -1332,6 +1612,110 @@ mod message_reconstructor {
             .is_err());
         assert_eq!(reconstructor_with_data, reconstructor_clone);
     }
+
+    fn split_message(message_len: usize) -> Vec<Fragment> {
+        crate::split_into_sets(
+            &mut rand::rngs::OsRng,
+            &vec![42u8; message_len],
+            AVAILABLE_PLAINTEXT_SIZE,
+        )
+        .into_iter()
+        .flat_map(|fragment_set| fragment_set.into_iter())
+        .collect()
+    }
+
+    #[test]
+    fn buffered_bytes_are_tracked_until_message_is_reconstructed() {
+        let mut reconstructor = MessageReconstructor::default();
+        let mut fragments =
+            split_message(unlinked_fragment_payload_max_len(AVAILABLE_PLAINTEXT_SIZE) * 3);
+        let last = fragments.pop().unwrap();
+
+        let mut expected_bytes = 0;
+        for fragment in fragments {
+            expected_bytes += fragment.payload_size();
+            assert!(reconstructor.insert_new_fragment(fragment).is_none());
+            assert_eq!(reconstructor.buffered_bytes(), expected_bytes);
+        }
+        assert_eq!(reconstructor.buffered_sets(), 1);
+
+        assert!(reconstructor.insert_new_fragment(last).is_some());
+        assert_eq!(reconstructor.buffered_bytes(), 0);
+        assert_eq!(reconstructor.buffered_sets(), 0);
+    }
+
+    #[test]
+    fn duplicate_fragments_are_not_double_counted() {
+        let mut reconstructor = MessageReconstructor::default();
+        let fragments =
+            split_message(unlinked_fragment_payload_max_len(AVAILABLE_PLAINTEXT_SIZE) * 3);
+
+        reconstructor.insert_new_fragment(fragments[0].clone());
+        reconstructor.insert_new_fragment(fragments[0].clone());
+        assert_eq!(reconstructor.buffered_bytes(), fragments[0].payload_size());
+    }
+
+    #[test]
+    fn removing_stale_sets_only_drops_sets_older_than_maximum_age() {
+        let mut reconstructor = MessageReconstructor::default();
+        let fragments =
+            split_message(unlinked_fragment_payload_max_len(AVAILABLE_PLAINTEXT_SIZE) * 3);
+        let set_id = fragments[0].id();
+        let payload_size = fragments[0].payload_size();
+        reconstructor.insert_new_fragment(fragments[0].clone());
+
+        assert!(reconstructor.remove_stale_sets().is_empty());
+        assert_eq!(reconstructor.buffered_sets(), 1);
+
+        let later = Instant::now() + DEFAULT_MAXIMUM_PARTIAL_MESSAGE_AGE + Duration::from_secs(1);
+        let dropped = reconstructor.remove_stale_sets_at(later);
+        assert_eq!(
+            dropped,
+            vec![DroppedSet {
+                set_id,
+                received_fragments: 1,
+                total_fragments: 3,
+                buffered_bytes: payload_size,
+            }]
+        );
+        assert_eq!(reconstructor.buffered_sets(), 0);
+        assert_eq!(reconstructor.buffered_bytes(), 0);
+    }
+
+    #[test]
+    fn exceeding_byte_budget_drops_oldest_sets() {
+        let mut reconstructor = MessageReconstructor::default();
+        let message_len = unlinked_fragment_payload_max_len(AVAILABLE_PLAINTEXT_SIZE) * 3;
+        let old_fragments = split_message(message_len);
+        let new_fragments = split_message(message_len);
+        reconstructor.set_limits(ReconstructionLimits {
+            maximum_partial_message_age: DEFAULT_MAXIMUM_PARTIAL_MESSAGE_AGE,
+            maximum_buffered_bytes: old_fragments[0].payload_size(),
+        });
+
+        let old_id = old_fragments[0].id();
+        let new_id = new_fragments[0].id();
+        reconstructor.insert_new_fragment(old_fragments[0].clone());
+        reconstructor
+            .reconstructed_sets
+            .get_mut(&old_id)
+            .unwrap()
+            .first_received -= Duration::from_secs(1);
+
+        reconstructor.insert_new_fragment(new_fragments[0].clone());
+        assert_eq!(reconstructor.buffered_sets(), 1);
+        assert!(reconstructor.reconstructed_sets.contains_key(&new_id));
+        assert_eq!(
+            reconstructor.buffered_bytes(),
+            new_fragments[0].payload_size()
+        );
+
+        // sets dropped due to the budget are reported alongside the stale ones
+        let dropped = reconstructor.remove_stale_sets();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].set_id, old_id);
+        assert!(reconstructor.remove_stale_sets().is_empty());
+    }
 }
 
 #[cfg(test)]
@@ -1662,6 +2046,144 @@ mod message_reconstruction {
         }
     }
 
+    #[cfg(test)]
+    mod erasure_coded_split {
+        use super::*;
+        use crate::fragment::unlinked_fragment_payload_max_len;
+        use crate::ErasureCoding;
+
+        fn erasure_coded_fragments(message: &[u8]) -> Vec<Fragment> {
+            crate::split_into_sets_with_erasure_coding(
+                &mut rand::rngs::OsRng,
+                message,
+                AVAILABLE_PLAINTEXT_SIZE,
+                Some(ErasureCoding::new(25)),
+            )
+            .0
+            .into_iter()
+            .flat_map(|fragment_set| fragment_set.into_iter())
+            .collect()
+        }
+
+        #[test]
+        fn it_reconstructs_message_with_lost_fragments() {
+            let mut rng = thread_rng();
+            let max_unlinked_len = unlinked_fragment_payload_max_len(AVAILABLE_PLAINTEXT_SIZE);
+
+            let mut message = vec![0u8; max_unlinked_len * 8];
+            rng.fill_bytes(&mut message);
+
+            let mut fragments = erasure_coded_fragments(&message);
+            assert_eq!(fragments.len(), 10);
+
+            // lose two random fragments and shuffle the rest
+            fragments.shuffle(&mut rng);
+            fragments.truncate(8);
+
+            let mut message_reconstructor = MessageReconstructor::default();
+            for (i, fragment) in fragments.into_iter().enumerate() {
+                let res = message_reconstructor.insert_new_fragment(
+                    message_reconstructor
+                        .recover_fragment(fragment.into_bytes())
+                        .unwrap(),
+                );
+                if i == 7 {
+                    let reconstructed_message = res.unwrap();
+                    assert_eq!(reconstructed_message.0, message);
+                    assert_eq!(reconstructed_message.1.len(), 1);
+                } else {
+                    assert!(res.is_none());
+                }
+            }
+            assert_eq!(0, message_reconstructor.buffered_bytes());
+        }
+
+        #[test]
+        fn it_does_not_reconstruct_message_with_too_many_lost_fragments() {
+            let mut rng = thread_rng();
+            let max_unlinked_len = unlinked_fragment_payload_max_len(AVAILABLE_PLAINTEXT_SIZE);
+
+            let mut message = vec![0u8; max_unlinked_len * 8];
+            rng.fill_bytes(&mut message);
+
+            let mut fragments = erasure_coded_fragments(&message);
+            fragments.shuffle(&mut rng);
+            fragments.truncate(7);
+
+            let mut message_reconstructor = MessageReconstructor::default();
+            for fragment in fragments {
+                assert!(message_reconstructor
+                    .insert_new_fragment(fragment)
+                    .is_none());
+            }
+        }
+
+        #[test]
+        fn it_ignores_fragments_inconsistent_with_the_set() {
+            let mut rng = thread_rng();
+            let max_unlinked_len = unlinked_fragment_payload_max_len(AVAILABLE_PLAINTEXT_SIZE);
+
+            let mut message = vec![0u8; max_unlinked_len * 8];
+            rng.fill_bytes(&mut message);
+
+            let fragments = erasure_coded_fragments(&message);
+            let set_id = fragments[0].id();
+
+            let mut message_reconstructor = MessageReconstructor::default();
+            assert!(message_reconstructor
+                .insert_new_fragment(fragments[0].clone())
+                .is_none());
+
+            // fragment of the same set that claims to have no parity data
+            let malformed = Fragment::try_new(
+                &vec![1u8; max_unlinked_len],
+                set_id,
+                10,
+                2,
+                None,
+                None,
+                AVAILABLE_PLAINTEXT_SIZE,
+            )
+            .unwrap();
+            assert!(message_reconstructor
+                .insert_new_fragment(malformed)
+                .is_none());
+            assert_eq!(
+                1,
+                message_reconstructor
+                    .reconstructed_sets
+                    .get(&set_id)
+                    .unwrap()
+                    .received_fragments()
+            );
+        }
+
+        #[test]
+        fn it_reconstructs_message_with_partial_final_fragment() {
+            let mut rng = thread_rng();
+            let max_unlinked_len = unlinked_fragment_payload_max_len(AVAILABLE_PLAINTEXT_SIZE);
+
+            let mut message = vec![0u8; max_unlinked_len * 3 + 42];
+            rng.fill_bytes(&mut message);
+
+            let mut fragments = erasure_coded_fragments(&message);
+            assert_eq!(fragments.len(), 5);
+            // lose the final, padded, data fragment
+            fragments.remove(3);
+
+            let mut message_reconstructor = MessageReconstructor::default();
+            let mut reconstructed = None;
+            for fragment in fragments {
+                reconstructed = message_reconstructor.insert_new_fragment(fragment);
+            }
+            let reconstructed_message = reconstructed.unwrap().0;
+            assert_eq!(&reconstructed_message[..message.len()], message);
+            assert!(reconstructed_message[message.len()..]
+                .iter()
+                .all(|b| *b == 0));
+        }
+    }
+
     #[cfg(test)]
     mod multiple_sets_split {
         use super::*;
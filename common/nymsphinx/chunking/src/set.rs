@@ -1,6 +1,7 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::erasure_coding::{self, ErasureCoding};
 use crate::fragment::{
     linked_fragment_payload_max_len, unlinked_fragment_payload_max_len, Fragment,
     LINKED_FRAGMENTED_HEADER_LEN, UNLINKED_FRAGMENTED_HEADER_LEN,
@@ -295,6 +296,85 @@ pub fn split_into_sets<R: Rng>(
     }
 }
 
+/// Splits underlying message into data `Fragment`s of a single `FragmentSet` followed by
+/// the specified number of Reed-Solomon parity `Fragment`s, such that the set could be
+/// reconstructed using any of its data-length-many `Fragment`s.
+/// The final data `Fragment` is zero-padded to the full length so that all the shards
+/// used for the encoding are of equal size.
+fn prepare_erasure_coded_set(
+    message: &[u8],
+    id: i32,
+    parity_fragments: u8,
+    max_plaintext_size: usize,
+) -> FragmentSet {
+    let max_unlinked_len = unlinked_fragment_payload_max_len(max_plaintext_size);
+
+    let data_shards: Vec<_> = message
+        .chunks(max_unlinked_len)
+        .map(|chunk| {
+            let mut shard = chunk.to_vec();
+            shard.resize(max_unlinked_len, 0);
+            shard
+        })
+        .collect();
+    let parity_shards = erasure_coding::generate_parity_shards(&data_shards, parity_fragments);
+
+    let total_fragments = data_shards.len() + parity_shards.len();
+    debug_assert!(total_fragments <= u8::MAX as usize);
+
+    data_shards
+        .iter()
+        .chain(parity_shards.iter())
+        .enumerate()
+        .map(|(i, shard)| {
+            Fragment::try_new_erasure_coded(
+                shard,
+                id,
+                total_fragments as u8,
+                (i + 1) as u8,
+                parity_fragments,
+                max_plaintext_size,
+            )
+            .unwrap()
+        })
+        .collect()
+}
+
+/// Entry point for splitting whole message into possibly multiple [`Set`]s with optional
+/// forward error correction. Alongside the [`Set`]s, it returns whether erasure coding
+/// has actually been applied to them.
+///
+/// Erasure coding is only applied if the message fits in a single [`Set`] that leaves space
+/// for at least a single parity `Fragment`, as parity `Fragment`s can't be part of linked [`Set`]s.
+/// Otherwise, the message is split as in [`split_into_sets`] and it's up to the caller to decide
+/// how to deal with the lack of forward error correction.
+pub fn split_into_sets_with_erasure_coding<R: Rng>(
+    rng: &mut R,
+    message: &[u8],
+    max_plaintext_size: usize,
+    erasure_coding: Option<ErasureCoding>,
+) -> (Vec<FragmentSet>, bool) {
+    let Some(erasure_coding) = erasure_coding else {
+        return (split_into_sets(rng, message, max_plaintext_size), false);
+    };
+
+    if total_number_of_sets(message.len(), max_plaintext_size) != 1 {
+        return (split_into_sets(rng, message, max_plaintext_size), false);
+    }
+
+    let max_unlinked_len = unlinked_fragment_payload_max_len(max_plaintext_size);
+    let data_fragments = message.len().div_ceil(max_unlinked_len);
+    match erasure_coding.parity_fragments(data_fragments) {
+        Some(parity_fragments) => {
+            let set_id = generate_set_id(rng);
+            let set =
+                prepare_erasure_coded_set(message, set_id, parity_fragments, max_plaintext_size);
+            (vec![set], true)
+        }
+        None => (split_into_sets(rng, message, max_plaintext_size), false),
+    }
+}
+
 // reason for top level tests module is to be able to use the helper functions to verify sets payloads
 #[cfg(test)]
 mod tests {
@@ -799,6 +879,85 @@ mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod splitting_with_erasure_coding {
+        use super::*;
+        use rand::{thread_rng, RngCore};
+
+        #[test]
+        fn appends_parity_fragments_to_single_set() {
+            let mut rng = thread_rng();
+            let max_unlinked_len = unlinked_fragment_payload_max_len(max_plaintext_size());
+            let mut message = vec![0u8; max_unlinked_len * 9 + 123];
+            rng.fill_bytes(&mut message);
+
+            let (sets, erasure_coded) = split_into_sets_with_erasure_coding(
+                &mut rng,
+                &message,
+                max_plaintext_size(),
+                Some(ErasureCoding::new(20)),
+            );
+            assert!(erasure_coded);
+            assert_eq!(1, sets.len());
+
+            let set = &sets[0];
+            assert_eq!(12, set.len());
+            for (i, fragment) in set.iter().enumerate() {
+                assert_eq!(2, fragment.parity_fragments());
+                assert_eq!(10, fragment.data_fragments());
+                assert_eq!(i >= 10, fragment.is_parity());
+                assert_eq!(max_unlinked_len, fragment.payload_size());
+                assert!(fragment.previous_fragments_set_id().is_none());
+                assert!(fragment.next_fragments_set_id().is_none());
+            }
+
+            let data: Vec<_> = set[..10]
+                .iter()
+                .flat_map(|fragment| fragment.clone().extract_payload())
+                .collect();
+            assert_eq!(message, data[..message.len()]);
+            assert!(data[message.len()..].iter().all(|b| *b == 0));
+        }
+
+        #[test]
+        fn falls_back_to_regular_splitting_for_linked_sets() {
+            let mut rng = thread_rng();
+            let mut message =
+                vec![0u8; max_unlinked_set_payload_length(max_plaintext_size()) + 2345];
+            rng.fill_bytes(&mut message);
+
+            let (sets, erasure_coded) = split_into_sets_with_erasure_coding(
+                &mut rng,
+                &message,
+                max_plaintext_size(),
+                Some(ErasureCoding::new(20)),
+            );
+            assert!(!erasure_coded);
+            assert_eq!(2, sets.len());
+            assert!(sets
+                .iter()
+                .flatten()
+                .all(|fragment| fragment.parity_fragments() == 0));
+        }
+
+        #[test]
+        fn falls_back_to_regular_splitting_for_full_set() {
+            let mut rng = thread_rng();
+            let mut message = vec![0u8; max_unlinked_set_payload_length(max_plaintext_size())];
+            rng.fill_bytes(&mut message);
+
+            let (mut sets, erasure_coded) = split_into_sets_with_erasure_coding(
+                &mut rng,
+                &message,
+                max_plaintext_size(),
+                Some(ErasureCoding::new(20)),
+            );
+            assert!(!erasure_coded);
+            assert_eq!(1, sets.len());
+            verify_unlinked_set_payload(sets.pop().unwrap(), &message);
+        }
+    }
+
     #[cfg(test)]
     mod helpers {
         use super::*;
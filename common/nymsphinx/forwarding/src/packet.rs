@@ -2,15 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use nym_sphinx_addressing::nodes::{NymNodeRoutingAddress, NymNodeRoutingAddressError};
-use nym_sphinx_params::{PacketMode, PacketSize};
-use nym_sphinx_types::SphinxPacket;
+use nym_sphinx_params::{PacketSize, PacketType};
+use nym_sphinx_types::NymPacket;
 use std::convert::TryFrom;
 use std::fmt::{self, Debug, Display, Formatter};
 
 #[derive(Debug)]
 pub enum MixPacketFormattingError {
     TooFewBytesProvided,
-    InvalidPacketMode,
+    InvalidPacketType,
     InvalidPacketSize(usize),
     InvalidAddress,
     MalformedSphinxPacket,
@@ -25,13 +25,13 @@ impl Display for MixPacketFormattingError {
             InvalidPacketSize(actual) =>
                 write!(
                     f,
-                    "received request had invalid size. (actual: {}, but expected one of: {} (ACK), {} (REGULAR), {}, {}, {} (EXTENDED))",
+                    "received request had invalid size. (actual: {}, but expected one of: {} (ACK), {} (REGULAR), {}, {}, {} (EXTENDED), {} (OUTFOX REGULAR))",
                     actual, PacketSize::AckPacket.size(), PacketSize::RegularPacket.size(),
                     PacketSize::ExtendedPacket8.size(), PacketSize::ExtendedPacket16.size(),
-                    PacketSize::ExtendedPacket32.size()
+                    PacketSize::ExtendedPacket32.size(), PacketSize::OutfoxRegularPacket.size()
                 ),
-            MalformedSphinxPacket => write!(f, "received sphinx packet was malformed"),
-            InvalidPacketMode => write!(f, "provided packet mode is invalid")
+            MalformedSphinxPacket => write!(f, "received packet was malformed"),
+            InvalidPacketType => write!(f, "provided packet type is invalid")
         }
     }
 }
@@ -46,19 +46,16 @@ impl From<NymNodeRoutingAddressError> for MixPacketFormattingError {
 
 pub struct MixPacket {
     next_hop: NymNodeRoutingAddress,
-    sphinx_packet: SphinxPacket,
-    packet_mode: PacketMode,
+    packet: NymPacket,
+    packet_type: PacketType,
 }
 
 impl Debug for MixPacket {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "MixPacket to {:?} with packet_mode {:?}. Sphinx header: {:?}, payload length: {}",
-            self.next_hop,
-            self.packet_mode,
-            self.sphinx_packet.header,
-            self.sphinx_packet.payload.len()
+            "MixPacket to {:?} with packet_type {:?}. {:?}",
+            self.next_hop, self.packet_type, self.packet
         )
     }
 }
@@ -66,13 +63,13 @@ impl Debug for MixPacket {
 impl MixPacket {
     pub fn new(
         next_hop: NymNodeRoutingAddress,
-        sphinx_packet: SphinxPacket,
-        packet_mode: PacketMode,
+        packet: NymPacket,
+        packet_type: PacketType,
     ) -> Self {
         MixPacket {
             next_hop,
-            sphinx_packet,
-            packet_mode,
+            packet,
+            packet_type,
         }
     }
 
@@ -80,51 +77,53 @@ impl MixPacket {
         self.next_hop
     }
 
-    pub fn sphinx_packet(&self) -> &SphinxPacket {
-        &self.sphinx_packet
+    pub fn packet(&self) -> &NymPacket {
+        &self.packet
     }
 
-    pub fn into_sphinx_packet(self) -> SphinxPacket {
-        self.sphinx_packet
+    pub fn into_packet(self) -> NymPacket {
+        self.packet
     }
 
-    pub fn packet_mode(&self) -> PacketMode {
-        self.packet_mode
+    pub fn packet_type(&self) -> PacketType {
+        self.packet_type
     }
 
     // the message is formatted as follows:
-    // PACKET_MODE || FIRST_HOP || SPHINX_PACKET
+    // PACKET_TYPE || FIRST_HOP || PACKET
     pub fn try_from_bytes(b: &[u8]) -> Result<Self, MixPacketFormattingError> {
-        let packet_mode = match PacketMode::try_from(b[0]) {
+        let packet_type = match PacketType::try_from(b[0]) {
             Ok(mode) => mode,
-            Err(_) => return Err(MixPacketFormattingError::InvalidPacketMode),
+            Err(_) => return Err(MixPacketFormattingError::InvalidPacketType),
         };
 
         let next_hop = NymNodeRoutingAddress::try_from_bytes(&b[1..])?;
         let addr_offset = next_hop.bytes_min_len();
 
-        let sphinx_packet_data = &b[addr_offset + 1..];
-        let packet_size = sphinx_packet_data.len();
+        let packet_data = &b[addr_offset + 1..];
+        let packet_size = packet_data.len();
         if PacketSize::get_type(packet_size).is_err() {
             Err(MixPacketFormattingError::InvalidPacketSize(packet_size))
         } else {
-            let sphinx_packet = match SphinxPacket::from_bytes(sphinx_packet_data) {
-                Ok(packet) => packet,
-                Err(_) => return Err(MixPacketFormattingError::MalformedSphinxPacket),
-            };
+            let packet = if packet_type.is_outfox() {
+                NymPacket::outfox_from_bytes(packet_data)
+            } else {
+                NymPacket::sphinx_from_bytes(packet_data)
+            }
+            .map_err(|_| MixPacketFormattingError::MalformedSphinxPacket)?;
 
             Ok(MixPacket {
                 next_hop,
-                sphinx_packet,
-                packet_mode,
+                packet,
+                packet_type,
             })
         }
     }
 
     pub fn into_bytes(self) -> Vec<u8> {
-        std::iter::once(self.packet_mode as u8)
+        std::iter::once(self.packet_type as u8)
             .chain(self.next_hop.as_bytes().into_iter())
-            .chain(self.sphinx_packet.to_bytes().into_iter())
+            .chain(self.packet.to_bytes().into_iter())
             .collect()
     }
 }
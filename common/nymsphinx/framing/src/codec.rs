@@ -1,12 +1,12 @@
 // Copyright 2021-2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::noise::NoiseError;
 use crate::packet::{FramedSphinxPacket, Header};
 use bytes::{Buf, BufMut, BytesMut};
-use nym_sphinx_params::packet_modes::InvalidPacketMode;
 use nym_sphinx_params::packet_sizes::{InvalidPacketSize, PacketSize};
-use nym_sphinx_types::Error as SphinxError;
-use nym_sphinx_types::SphinxPacket;
+use nym_sphinx_params::packet_types::InvalidPacketType;
+use nym_sphinx_types::{NymPacket, NymPacketError};
 use std::io;
 use thiserror::Error;
 use tokio_util::codec::{Decoder, Encoder};
@@ -16,11 +16,14 @@ pub enum SphinxCodecError {
     #[error("the packet size information was malformed - {0}")]
     InvalidPacketSize(#[from] InvalidPacketSize),
 
-    #[error("the packet mode information was malformed - {0}")]
-    InvalidPacketMode(#[from] InvalidPacketMode),
+    #[error("the packet type information was malformed - {0}")]
+    InvalidPacketType(#[from] InvalidPacketType),
 
     #[error("the actual sphinx packet was malformed - {0}")]
-    MalformedSphinxPacket(#[from] SphinxError),
+    MalformedSphinxPacket(#[from] NymPacketError),
+
+    #[error("failed to encrypt or decrypt the noise frame - {0}")]
+    Noise(#[from] NoiseError),
 
     #[error("encountered an IO error - {0}")]
     IoError(#[from] io::Error),
@@ -32,19 +35,20 @@ impl From<SphinxCodecError> for io::Error {
             SphinxCodecError::InvalidPacketSize(source) => {
                 io::Error::new(io::ErrorKind::InvalidInput, source)
             }
-            SphinxCodecError::InvalidPacketMode(source) => {
+            SphinxCodecError::InvalidPacketType(source) => {
                 io::Error::new(io::ErrorKind::InvalidInput, source)
             }
             SphinxCodecError::MalformedSphinxPacket(source) => {
                 io::Error::new(io::ErrorKind::InvalidData, source)
             }
+            SphinxCodecError::Noise(source) => io::Error::new(io::ErrorKind::InvalidData, source),
             SphinxCodecError::IoError(err) => err,
         }
     }
 }
 
-// TODO: in the future it could be extended to have state containing symmetric encryption key
-// so that all data could be encrypted easily (alternatively we could just slap TLS)
+// note: the encryption of the links between the nodes is handled by wrapping this codec
+// in `crate::noise::NoiseCodec`
 pub struct SphinxCodec;
 
 impl Encoder<FramedSphinxPacket> for SphinxCodec {
@@ -91,8 +95,16 @@ impl Decoder for SphinxCodec {
 
         // here it could be debatable whether stream is corrupt or not,
         // but let's go with the safer approach and assume it is.
-        let packet = SphinxPacket::from_bytes(&sphinx_packet_bytes)?;
-        let nymsphinx_packet = FramedSphinxPacket { header, packet };
+        let packet = if header.packet_type.is_outfox() {
+            NymPacket::outfox_from_bytes(&sphinx_packet_bytes)?
+        } else {
+            NymPacket::sphinx_from_bytes(&sphinx_packet_bytes)?
+        };
+        let nymsphinx_packet = FramedSphinxPacket {
+            header,
+            packet,
+            raw_packet: Some(sphinx_packet_bytes.freeze()),
+        };
 
         // As per docs:
         // Before returning from the function, implementations should ensure that the buffer
@@ -126,15 +138,16 @@ impl Decoder for SphinxCodec {
 }
 
 #[cfg(test)]
-mod packet_encoding {
+pub(crate) mod packet_encoding {
     use super::*;
+    use nym_sphinx_params::PacketType;
     use nym_sphinx_types::builder::SphinxPacketBuilder;
     use nym_sphinx_types::{
         crypto, Delay as SphinxDelay, Destination, DestinationAddressBytes, Node, NodeAddressBytes,
-        DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
+        OutfoxPacket, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
     };
 
-    fn make_valid_sphinx_packet(size: PacketSize) -> SphinxPacket {
+    pub(crate) fn make_valid_sphinx_packet(size: PacketSize) -> NymPacket {
         let (_, node1_pk) = crypto::keygen();
         let node1 = Node::new(
             NodeAddressBytes::from_bytes([5u8; NODE_ADDRESS_LENGTH]),
@@ -165,6 +178,23 @@ mod packet_encoding {
             .with_payload_size(size.payload_size())
             .build_packet(b"foomp", &route, &destination, &delays)
             .unwrap()
+            .into()
+    }
+
+    fn make_valid_outfox_packet(size: PacketSize) -> NymPacket {
+        let route = (0..4)
+            .map(|i| {
+                let (_, pk) = crypto::keygen();
+                Node::new(NodeAddressBytes::from_bytes([i; NODE_ADDRESS_LENGTH]), pk)
+            })
+            .collect::<Vec<_>>();
+        let destination = DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]);
+        let delays = vec![SphinxDelay::new_from_nanos(42); route.len()];
+        let payload = vec![42u8; size.payload_size()];
+
+        OutfoxPacket::build(&payload, &route, &destination, &delays)
+            .unwrap()
+            .into()
     }
 
     #[test]
@@ -176,6 +206,7 @@ mod packet_encoding {
         let packet = FramedSphinxPacket {
             header,
             packet: sphinx_packet,
+            raw_packet: None,
         };
 
         let mut bytes = BytesMut::new();
@@ -186,11 +217,29 @@ mod packet_encoding {
         assert_eq!(decoded.packet.to_bytes(), sphinx_bytes)
     }
 
+    #[test]
+    fn whole_outfox_packet_can_be_decoded_from_a_valid_encoded_instance() {
+        let outfox_packet = make_valid_outfox_packet(PacketSize::OutfoxRegularPacket);
+        let outfox_bytes = outfox_packet.to_bytes();
+
+        let packet = FramedSphinxPacket::new(outfox_packet, PacketType::Outfox, false);
+        let header = packet.header;
+
+        let mut bytes = BytesMut::new();
+        SphinxCodec.encode(packet, &mut bytes).unwrap();
+        let decoded = SphinxCodec.decode(&mut bytes).unwrap().unwrap();
+
+        assert_eq!(decoded.header, header);
+        assert_eq!(decoded.packet_size(), PacketSize::OutfoxRegularPacket);
+        assert!(matches!(decoded.packet, NymPacket::Outfox(_)));
+        assert_eq!(decoded.packet.to_bytes(), outfox_bytes)
+    }
+
     #[cfg(test)]
     mod decode_will_allocate_enough_bytes_for_next_call {
         use super::*;
         use nym_sphinx_params::packet_version::PacketVersion;
-        use nym_sphinx_params::PacketMode;
+        use nym_sphinx_params::PacketType;
 
         #[test]
         fn for_empty_bytes() {
@@ -212,12 +261,13 @@ mod packet_encoding {
                 PacketSize::ExtendedPacket8,
                 PacketSize::ExtendedPacket16,
                 PacketSize::ExtendedPacket32,
+                PacketSize::OutfoxRegularPacket,
             ];
             for packet_size in packet_sizes {
                 let header = Header {
                     packet_version: PacketVersion::Legacy,
                     packet_size,
-                    packet_mode: Default::default(),
+                    packet_type: Default::default(),
                 };
                 let mut bytes = BytesMut::new();
                 header.encode(&mut bytes);
@@ -236,12 +286,13 @@ mod packet_encoding {
                 PacketSize::ExtendedPacket8,
                 PacketSize::ExtendedPacket16,
                 PacketSize::ExtendedPacket32,
+                PacketSize::OutfoxRegularPacket,
             ];
             for packet_size in packet_sizes {
                 let header = Header {
                     packet_version: PacketVersion::Versioned(123),
                     packet_size,
-                    packet_mode: Default::default(),
+                    packet_type: Default::default(),
                 };
                 let mut bytes = BytesMut::new();
                 header.encode(&mut bytes);
@@ -261,9 +312,10 @@ mod packet_encoding {
                 header: Header {
                     packet_version: PacketVersion::Legacy,
                     packet_size: Default::default(),
-                    packet_mode: Default::default(),
+                    packet_type: Default::default(),
                 },
                 packet: make_valid_sphinx_packet(Default::default()),
+                raw_packet: None,
             };
 
             let mut bytes = BytesMut::new();
@@ -281,6 +333,7 @@ mod packet_encoding {
             let packet = FramedSphinxPacket {
                 header: Header::default(),
                 packet: make_valid_sphinx_packet(Default::default()),
+                raw_packet: None,
             };
 
             let mut bytes = BytesMut::new();
@@ -301,6 +354,7 @@ mod packet_encoding {
                 PacketSize::ExtendedPacket8,
                 PacketSize::ExtendedPacket16,
                 PacketSize::ExtendedPacket32,
+                PacketSize::OutfoxRegularPacket,
             ];
 
             for packet_size in packet_sizes {
@@ -308,15 +362,16 @@ mod packet_encoding {
                     header: Header {
                         packet_version: PacketVersion::Legacy,
                         packet_size: Default::default(),
-                        packet_mode: Default::default(),
+                        packet_type: Default::default(),
                     },
                     packet: make_valid_sphinx_packet(Default::default()),
+                    raw_packet: None,
                 };
 
                 let mut bytes = BytesMut::new();
                 SphinxCodec.encode(first_packet, &mut bytes).unwrap();
                 bytes.put_u8(packet_size as u8);
-                bytes.put_u8(PacketMode::default() as u8);
+                bytes.put_u8(PacketType::default() as u8);
                 assert!(SphinxCodec.decode(&mut bytes).unwrap().is_some());
 
                 assert!(bytes.capacity() >= Header::LEGACY_SIZE + packet_size.size())
@@ -332,19 +387,21 @@ mod packet_encoding {
                 PacketSize::ExtendedPacket8,
                 PacketSize::ExtendedPacket16,
                 PacketSize::ExtendedPacket32,
+                PacketSize::OutfoxRegularPacket,
             ];
 
             for packet_size in packet_sizes {
                 let first_packet = FramedSphinxPacket {
                     header: Header::default(),
                     packet: make_valid_sphinx_packet(Default::default()),
+                    raw_packet: None,
                 };
 
                 let mut bytes = BytesMut::new();
                 SphinxCodec.encode(first_packet, &mut bytes).unwrap();
                 bytes.put_u8(PacketVersion::new_versioned(123).as_u8().unwrap());
                 bytes.put_u8(packet_size as u8);
-                bytes.put_u8(PacketMode::default() as u8);
+                bytes.put_u8(PacketType::default() as u8);
                 assert!(SphinxCodec.decode(&mut bytes).unwrap().is_some());
 
                 assert!(bytes.capacity() >= Header::VERSIONED_SIZE + packet_size.size())
@@ -357,11 +414,13 @@ mod packet_encoding {
         let packet1 = FramedSphinxPacket {
             header: Header::default(),
             packet: make_valid_sphinx_packet(Default::default()),
+            raw_packet: None,
         };
 
         let packet2 = FramedSphinxPacket {
             header: Header::default(),
             packet: make_valid_sphinx_packet(Default::default()),
+            raw_packet: None,
         };
 
         let mut bytes = BytesMut::new();
@@ -379,11 +438,13 @@ mod packet_encoding {
         let packet1 = FramedSphinxPacket {
             header: Header::default(),
             packet: make_valid_sphinx_packet(Default::default()),
+            raw_packet: None,
         };
 
         let packet2 = FramedSphinxPacket {
             header: Header::default(),
             packet: make_valid_sphinx_packet(Default::default()),
+            raw_packet: None,
         };
 
         let mut bytes = BytesMut::new();
@@ -2,4 +2,5 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod codec;
+pub mod noise;
 pub mod packet;
@@ -0,0 +1,463 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional encryption of the links between the mixnet nodes using the Noise `XK` handshake.
+//!
+//! The initiator of the connection announces the Noise handshake by sending [`NOISE_HANDSHAKE_FLAG`]
+//! as the very first byte. It can never be confused with a legacy connection, as the first byte
+//! of a framed sphinx packet is always either the packet size or the packet version, neither of which
+//! can ever get anywhere near this value. This allows the receivers to accept both kinds of connections
+//! during the transition period.
+//!
+//! The static keys used in the handshake are the x25519 forms of the node identity keys,
+//! so that the nodes could be authenticated using the keys already present in the topology.
+
+use crate::codec::{SphinxCodec, SphinxCodecError};
+use crate::packet::FramedSphinxPacket;
+use bytes::{Buf, BufMut, BytesMut};
+use snow::{HandshakeState, TransportState};
+use std::io;
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_util::codec::{Decoder, Encoder, Framed, FramedParts};
+
+/// The first byte sent by the initiator of a connection that wishes to perform the Noise handshake.
+pub const NOISE_HANDSHAKE_FLAG: u8 = 0xFF;
+
+const NOISE_PATTERN: &str = "Noise_XK_25519_ChaChaPoly_BLAKE2s";
+
+// as defined by the Noise specification
+const MAX_NOISE_MESSAGE_LEN: usize = 65535;
+const NOISE_TAG_LEN: usize = 16;
+const MAX_NOISE_PAYLOAD_LEN: usize = MAX_NOISE_MESSAGE_LEN - NOISE_TAG_LEN;
+
+// every noise message is prefixed with its length encoded as big endian u16
+const LENGTH_PREFIX_LEN: usize = 2;
+
+#[derive(Error, Debug)]
+pub enum NoiseError {
+    #[error("the noise protocol has failed - {0}")]
+    ProtocolFailure(#[from] snow::Error),
+
+    #[error("encountered an IO error during the noise handshake - {0}")]
+    IoError(#[from] io::Error),
+
+    #[error("the remote has not initiated the noise handshake and the legacy connections are not accepted")]
+    UnexpectedLegacyConnection,
+
+    #[error("the remote has not revealed its static key during the handshake")]
+    MissingRemoteKey,
+
+    #[error("the remote has authenticated itself with an unknown static key")]
+    UnknownRemoteKey,
+}
+
+impl From<NoiseError> for io::Error {
+    fn from(err: NoiseError) -> Self {
+        match err {
+            NoiseError::IoError(err) => err,
+            other => io::Error::new(io::ErrorKind::InvalidData, other),
+        }
+    }
+}
+
+/// Codec encrypting everything produced by the inner codec (and decrypting everything before passing
+/// it to the inner codec) using the transport state established during the Noise handshake.
+pub struct NoiseCodec<C> {
+    inner: C,
+    transport: TransportState,
+
+    /// Decrypted bytes that have not yet been consumed by the inner codec.
+    plaintext: BytesMut,
+}
+
+impl<C> NoiseCodec<C> {
+    pub fn new(inner: C, transport: TransportState) -> Self {
+        NoiseCodec {
+            inner,
+            transport,
+            plaintext: BytesMut::new(),
+        }
+    }
+
+    /// Returns the static key the remote has authenticated itself with.
+    pub fn remote_static_key(&self) -> Option<&[u8]> {
+        self.transport.get_remote_static()
+    }
+
+    fn decrypt_available_messages(&mut self, src: &mut BytesMut) -> Result<(), NoiseError> {
+        let mut buf = [0u8; MAX_NOISE_MESSAGE_LEN];
+
+        while src.len() >= LENGTH_PREFIX_LEN {
+            let message_len = u16::from_be_bytes([src[0], src[1]]) as usize;
+            if src.len() < LENGTH_PREFIX_LEN + message_len {
+                src.reserve(LENGTH_PREFIX_LEN + message_len - src.len());
+                break;
+            }
+
+            src.advance(LENGTH_PREFIX_LEN);
+            let message = src.split_to(message_len);
+            let payload_len = self.transport.read_message(&message, &mut buf)?;
+            self.plaintext.put_slice(&buf[..payload_len]);
+        }
+
+        Ok(())
+    }
+}
+
+impl<C, I> Encoder<I> for NoiseCodec<C>
+where
+    C: Encoder<I>,
+    C::Error: From<NoiseError>,
+{
+    type Error = C::Error;
+
+    fn encode(&mut self, item: I, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let mut plaintext = BytesMut::new();
+        self.inner.encode(item, &mut plaintext)?;
+
+        let mut buf = [0u8; MAX_NOISE_MESSAGE_LEN];
+        for chunk in plaintext.chunks(MAX_NOISE_PAYLOAD_LEN) {
+            let message_len = self
+                .transport
+                .write_message(chunk, &mut buf)
+                .map_err(NoiseError::from)?;
+
+            dst.reserve(LENGTH_PREFIX_LEN + message_len);
+            dst.put_u16(message_len as u16);
+            dst.put_slice(&buf[..message_len]);
+        }
+        Ok(())
+    }
+}
+
+impl<C> Decoder for NoiseCodec<C>
+where
+    C: Decoder,
+    C::Error: From<NoiseError>,
+{
+    type Item = C::Item;
+    type Error = C::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.decrypt_available_messages(src)?;
+        self.inner.decode(&mut self.plaintext)
+    }
+}
+
+/// Codec used on the links between the mixnet nodes, which might or might not be encrypted
+/// depending on whether the Noise handshake took place.
+pub enum LinkCodec {
+    Plain(SphinxCodec),
+    Noise(NoiseCodec<SphinxCodec>),
+}
+
+impl LinkCodec {
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self, LinkCodec::Noise(_))
+    }
+}
+
+impl Encoder<FramedSphinxPacket> for LinkCodec {
+    type Error = SphinxCodecError;
+
+    fn encode(&mut self, item: FramedSphinxPacket, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        match self {
+            LinkCodec::Plain(codec) => codec.encode(item, dst),
+            LinkCodec::Noise(codec) => codec.encode(item, dst),
+        }
+    }
+}
+
+impl Decoder for LinkCodec {
+    type Item = FramedSphinxPacket;
+    type Error = SphinxCodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self {
+            LinkCodec::Plain(codec) => codec.decode(src),
+            LinkCodec::Noise(codec) => codec.decode(src),
+        }
+    }
+}
+
+async fn write_handshake_message<S>(
+    stream: &mut S,
+    handshake: &mut HandshakeState,
+) -> Result<(), NoiseError>
+where
+    S: AsyncWrite + Unpin,
+{
+    let mut buf = vec![0u8; MAX_NOISE_MESSAGE_LEN];
+    let message_len = handshake.write_message(&[], &mut buf)?;
+
+    stream.write_u16(message_len as u16).await?;
+    stream.write_all(&buf[..message_len]).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+async fn read_handshake_message<S>(
+    stream: &mut S,
+    handshake: &mut HandshakeState,
+) -> Result<(), NoiseError>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut message = vec![0u8; MAX_NOISE_MESSAGE_LEN];
+    let message_len = stream.read_u16().await? as usize;
+    stream.read_exact(&mut message[..message_len]).await?;
+
+    // no payloads are ever sent during the handshake
+    let mut payload = vec![0u8; MAX_NOISE_MESSAGE_LEN];
+    handshake.read_message(&message[..message_len], &mut payload)?;
+    Ok(())
+}
+
+/// Performs the Noise handshake as the initiator of the connection, authenticating the remote
+/// using its already known static key.
+pub async fn initiate<S>(
+    mut stream: S,
+    local_private_key: &[u8],
+    remote_public_key: &[u8],
+) -> Result<Framed<S, LinkCodec>, NoiseError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut handshake = snow::Builder::new(NOISE_PATTERN.parse()?)
+        .local_private_key(local_private_key)
+        .remote_public_key(remote_public_key)
+        .build_initiator()?;
+
+    stream.write_u8(NOISE_HANDSHAKE_FLAG).await?;
+
+    // -> e, es
+    write_handshake_message(&mut stream, &mut handshake).await?;
+    // <- e, ee
+    read_handshake_message(&mut stream, &mut handshake).await?;
+    // -> s, se
+    write_handshake_message(&mut stream, &mut handshake).await?;
+
+    let transport = handshake.into_transport_mode()?;
+    Ok(Framed::new(
+        stream,
+        LinkCodec::Noise(NoiseCodec::new(SphinxCodec, transport)),
+    ))
+}
+
+/// Accepts the connection as its responder. If the remote has initiated the Noise handshake,
+/// its static key must be recognised by `is_known_key`. Otherwise, the unencrypted connection
+/// is only accepted if `accept_legacy` is set.
+pub async fn accept<S, F>(
+    mut stream: S,
+    local_private_key: &[u8],
+    accept_legacy: bool,
+    is_known_key: F,
+) -> Result<Framed<S, LinkCodec>, NoiseError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    F: FnOnce(&[u8]) -> bool,
+{
+    let first_byte = stream.read_u8().await?;
+    if first_byte != NOISE_HANDSHAKE_FLAG {
+        if !accept_legacy {
+            return Err(NoiseError::UnexpectedLegacyConnection);
+        }
+
+        // the byte we have just read is already part of the first sphinx frame
+        let mut parts =
+            FramedParts::new::<FramedSphinxPacket>(stream, LinkCodec::Plain(SphinxCodec));
+        parts.read_buf.put_u8(first_byte);
+        return Ok(Framed::from_parts(parts));
+    }
+
+    let mut handshake = snow::Builder::new(NOISE_PATTERN.parse()?)
+        .local_private_key(local_private_key)
+        .build_responder()?;
+
+    // -> e, es
+    read_handshake_message(&mut stream, &mut handshake).await?;
+    // <- e, ee
+    write_handshake_message(&mut stream, &mut handshake).await?;
+    // -> s, se
+    read_handshake_message(&mut stream, &mut handshake).await?;
+
+    let remote_key = handshake
+        .get_remote_static()
+        .ok_or(NoiseError::MissingRemoteKey)?;
+    if !is_known_key(remote_key) {
+        return Err(NoiseError::UnknownRemoteKey);
+    }
+
+    let transport = handshake.into_transport_mode()?;
+    Ok(Framed::new(
+        stream,
+        LinkCodec::Noise(NoiseCodec::new(SphinxCodec, transport)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::packet_encoding::make_valid_sphinx_packet;
+    use futures::{SinkExt, StreamExt};
+    use nym_sphinx_params::PacketType;
+    use snow::Keypair;
+
+    fn keypair() -> Keypair {
+        snow::Builder::new(NOISE_PATTERN.parse().unwrap())
+            .generate_keypair()
+            .unwrap()
+    }
+
+    fn test_packet() -> (FramedSphinxPacket, Vec<u8>) {
+        let packet = make_valid_sphinx_packet(Default::default());
+        let bytes = packet.to_bytes();
+        (
+            FramedSphinxPacket::new(packet, PacketType::Mix, false),
+            bytes,
+        )
+    }
+
+    #[tokio::test]
+    async fn packets_can_be_sent_over_noise_link() {
+        let initiator_keys = keypair();
+        let responder_keys = keypair();
+        let (initiator_stream, responder_stream) = tokio::io::duplex(MAX_NOISE_MESSAGE_LEN);
+
+        let (initiator, responder) = tokio::join!(
+            initiate(
+                initiator_stream,
+                &initiator_keys.private,
+                &responder_keys.public
+            ),
+            accept(responder_stream, &responder_keys.private, false, |key| key
+                == initiator_keys.public)
+        );
+        let mut initiator = initiator.unwrap();
+        let mut responder = responder.unwrap();
+        assert!(initiator.codec().is_encrypted());
+        assert!(responder.codec().is_encrypted());
+
+        for _ in 0..3 {
+            let (packet, packet_bytes) = test_packet();
+            initiator.send(packet).await.unwrap();
+
+            let received = responder.next().await.unwrap().unwrap();
+            assert_eq!(received.into_inner().to_bytes(), packet_bytes);
+        }
+    }
+
+    #[tokio::test]
+    async fn legacy_connection_is_accepted_in_transitional_mode() {
+        let responder_keys = keypair();
+        let (initiator_stream, responder_stream) = tokio::io::duplex(MAX_NOISE_MESSAGE_LEN);
+
+        let mut initiator = Framed::new(initiator_stream, SphinxCodec);
+        let (packet, packet_bytes) = test_packet();
+        initiator.send(packet).await.unwrap();
+
+        let mut responder = accept(responder_stream, &responder_keys.private, true, |_| false)
+            .await
+            .unwrap();
+        assert!(!responder.codec().is_encrypted());
+
+        let received = responder.next().await.unwrap().unwrap();
+        assert_eq!(received.into_inner().to_bytes(), packet_bytes);
+    }
+
+    #[tokio::test]
+    async fn legacy_connection_is_rejected_if_not_allowed() {
+        let responder_keys = keypair();
+        let (initiator_stream, responder_stream) = tokio::io::duplex(MAX_NOISE_MESSAGE_LEN);
+
+        let mut initiator = Framed::new(initiator_stream, SphinxCodec);
+        let (packet, _) = test_packet();
+        initiator.send(packet).await.unwrap();
+
+        let res = accept(responder_stream, &responder_keys.private, false, |_| true).await;
+        assert!(matches!(res, Err(NoiseError::UnexpectedLegacyConnection)));
+    }
+
+    #[tokio::test]
+    async fn unknown_initiator_is_rejected() {
+        let initiator_keys = keypair();
+        let responder_keys = keypair();
+        let (initiator_stream, responder_stream) = tokio::io::duplex(MAX_NOISE_MESSAGE_LEN);
+
+        let (_, responder) = tokio::join!(
+            initiate(
+                initiator_stream,
+                &initiator_keys.private,
+                &responder_keys.public
+            ),
+            accept(responder_stream, &responder_keys.private, true, |_| false)
+        );
+        assert!(matches!(responder, Err(NoiseError::UnknownRemoteKey)));
+    }
+
+    #[tokio::test]
+    async fn handshake_fails_if_responder_key_is_different_than_expected() {
+        let initiator_keys = keypair();
+        let responder_keys = keypair();
+        let expected_responder_keys = keypair();
+        let (initiator_stream, responder_stream) = tokio::io::duplex(MAX_NOISE_MESSAGE_LEN);
+
+        let (initiator, responder) = tokio::join!(
+            initiate(
+                initiator_stream,
+                &initiator_keys.private,
+                &expected_responder_keys.public
+            ),
+            accept(responder_stream, &responder_keys.private, true, |_| true)
+        );
+        assert!(initiator.is_err() || responder.is_err());
+    }
+
+    #[test]
+    fn large_payloads_are_split_into_multiple_noise_messages() {
+        let initiator_keys = keypair();
+        let responder_keys = keypair();
+
+        let mut initiator = snow::Builder::new(NOISE_PATTERN.parse().unwrap())
+            .local_private_key(&initiator_keys.private)
+            .remote_public_key(&responder_keys.public)
+            .build_initiator()
+            .unwrap();
+        let mut responder = snow::Builder::new(NOISE_PATTERN.parse().unwrap())
+            .local_private_key(&responder_keys.private)
+            .build_responder()
+            .unwrap();
+
+        let mut message = [0u8; MAX_NOISE_MESSAGE_LEN];
+        let mut payload = [0u8; MAX_NOISE_MESSAGE_LEN];
+        for _ in 0..3 {
+            let (writer, reader) = if initiator.is_my_turn() {
+                (&mut initiator, &mut responder)
+            } else {
+                (&mut responder, &mut initiator)
+            };
+            let len = writer.write_message(&[], &mut message).unwrap();
+            reader.read_message(&message[..len], &mut payload).unwrap();
+        }
+
+        let mut sender = NoiseCodec::new(
+            tokio_util::codec::BytesCodec::new(),
+            initiator.into_transport_mode().unwrap(),
+        );
+        let mut receiver = NoiseCodec::new(
+            tokio_util::codec::BytesCodec::new(),
+            responder.into_transport_mode().unwrap(),
+        );
+
+        let data = vec![42u8; 3 * MAX_NOISE_MESSAGE_LEN];
+        let mut encrypted = BytesMut::new();
+        sender
+            .encode(bytes::Bytes::from(data.clone()), &mut encrypted)
+            .unwrap();
+        assert!(encrypted.len() > data.len() + 3 * (LENGTH_PREFIX_LEN + NOISE_TAG_LEN));
+
+        let decrypted = receiver.decode(&mut encrypted).unwrap().unwrap();
+        assert_eq!(decrypted.to_vec(), data);
+    }
+}
@@ -2,23 +2,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::codec::SphinxCodecError;
-use bytes::{BufMut, BytesMut};
+use bytes::{BufMut, Bytes, BytesMut};
 use nym_sphinx_params::packet_sizes::PacketSize;
 use nym_sphinx_params::packet_version::PacketVersion;
-use nym_sphinx_params::PacketMode;
-use nym_sphinx_types::SphinxPacket;
+use nym_sphinx_params::PacketType;
+use nym_sphinx_types::NymPacket;
 use std::convert::TryFrom;
 
 pub struct FramedSphinxPacket {
     /// Contains any metadata helping receiver to handle the underlying packet.
     pub(crate) header: Header,
 
-    /// The actual SphinxPacket being sent.
-    pub(crate) packet: SphinxPacket,
+    /// The actual packet being sent. Despite the name of this struct, depending on the packet type
+    /// specified in the header, it might use either Sphinx or Outfox format.
+    pub(crate) packet: NymPacket,
+
+    /// Bytes the packet has been decoded from, if it has been received from the wire.
+    /// They allow parsing the packet again without having to serialise it first.
+    pub(crate) raw_packet: Option<Bytes>,
 }
 
 impl FramedSphinxPacket {
-    pub fn new(packet: SphinxPacket, packet_mode: PacketMode, use_legacy_version: bool) -> Self {
+    pub fn new(packet: NymPacket, packet_type: PacketType, use_legacy_version: bool) -> Self {
         // If this fails somebody is using the library in a super incorrect way, because they
         // already managed to somehow create a sphinx packet
         let packet_size = PacketSize::get_type(packet.len()).unwrap();
@@ -27,9 +32,10 @@ impl FramedSphinxPacket {
             header: Header {
                 packet_version: PacketVersion::new(use_legacy_version),
                 packet_size,
-                packet_mode,
+                packet_type,
             },
             packet,
+            raw_packet: None,
         }
     }
 
@@ -37,13 +43,19 @@ impl FramedSphinxPacket {
         self.header.packet_size
     }
 
-    pub fn packet_mode(&self) -> PacketMode {
-        self.header.packet_mode
+    pub fn packet_type(&self) -> PacketType {
+        self.header.packet_type
     }
 
-    pub fn into_inner(self) -> SphinxPacket {
+    pub fn into_inner(self) -> NymPacket {
         self.packet
     }
+
+    /// Returns the packet alongside the bytes it has been decoded from, if it has been received
+    /// from the wire rather than constructed locally.
+    pub fn into_inner_with_raw_packet(self) -> (NymPacket, Option<Bytes>) {
+        (self.packet, self.raw_packet)
+    }
 }
 
 // Contains any metadata that might be useful for sending between mix nodes.
@@ -54,7 +66,7 @@ pub struct Header {
     /// Represents the wire format version used to construct this packet.
     pub(crate) packet_version: PacketVersion,
 
-    /// Represents type and consequently size of the included SphinxPacket.
+    /// Represents type and consequently size of the included packet.
     pub(crate) packet_size: PacketSize,
 
     /// Represents whether this packet is sent in a `vpn_mode` meaning it should not get delayed
@@ -64,9 +76,10 @@ pub struct Header {
     ///
     /// TODO: ask @AP whether this can be sent like this - could it introduce some anonymity issues?
     /// (note: this will be behind some encryption, either something implemented by us or some SSL action)
-    // Note: currently packet_mode is deprecated but is still left as a concept behind to not break
-    // compatibility with existing network
-    pub(crate) packet_mode: PacketMode,
+    // Note: the vpn mode is deprecated but is still left as a concept behind to not break
+    // compatibility with existing network. However, the field is also used to indicate
+    // whether the included packet uses the Outfox rather than Sphinx format.
+    pub(crate) packet_type: PacketType,
 }
 
 impl Header {
@@ -90,7 +103,7 @@ impl Header {
         }
 
         dst.put_u8(self.packet_size as u8);
-        dst.put_u8(self.packet_mode as u8);
+        dst.put_u8(self.packet_type as u8);
         // reserve bytes for the actual packet
         dst.reserve(self.packet_size.size());
     }
@@ -107,7 +120,7 @@ impl Header {
             Ok(Some(Header {
                 packet_version,
                 packet_size: PacketSize::try_from(src[0])?,
-                packet_mode: PacketMode::try_from(src[1])?,
+                packet_type: PacketType::try_from(src[1])?,
             }))
         } else if src.len() < Self::VERSIONED_SIZE {
             // we're missing that 1 byte to read the full header...
@@ -117,7 +130,7 @@ impl Header {
             Ok(Some(Header {
                 packet_version,
                 packet_size: PacketSize::try_from(src[1])?,
-                packet_mode: PacketMode::try_from(src[2])?,
+                packet_type: PacketType::try_from(src[2])?,
             }))
         }
     }
@@ -148,7 +161,7 @@ mod header_encoding {
             [
                 PacketVersion::new_versioned(123).as_u8().unwrap(),
                 unknown_packet_size,
-                PacketMode::default() as u8,
+                PacketType::default() as u8,
             ]
             .as_ref(),
         );
@@ -156,12 +169,12 @@ mod header_encoding {
     }
 
     #[test]
-    fn decoding_will_fail_for_unknown_packet_mode() {
-        let unknown_packet_mode: u8 = 255;
+    fn decoding_will_fail_for_unknown_packet_type() {
+        let unknown_packet_type: u8 = 255;
         // make sure this is still 'unknown' for if we make changes in the future
-        assert!(PacketMode::try_from(unknown_packet_mode).is_err());
+        assert!(PacketType::try_from(unknown_packet_type).is_err());
 
-        let mut bytes = BytesMut::from([PacketSize::default() as u8, unknown_packet_mode].as_ref());
+        let mut bytes = BytesMut::from([PacketSize::default() as u8, unknown_packet_type].as_ref());
         assert!(Header::decode(&mut bytes).is_err())
     }
 
@@ -186,12 +199,13 @@ mod header_encoding {
             PacketSize::ExtendedPacket8,
             PacketSize::ExtendedPacket16,
             PacketSize::ExtendedPacket32,
+            PacketSize::OutfoxRegularPacket,
         ];
         for packet_size in packet_sizes {
             let header = Header {
                 packet_version: PacketVersion::Legacy,
                 packet_size,
-                packet_mode: Default::default(),
+                packet_type: Default::default(),
             };
             let mut bytes = BytesMut::new();
             header.encode(&mut bytes);
@@ -207,12 +221,13 @@ mod header_encoding {
             PacketSize::ExtendedPacket8,
             PacketSize::ExtendedPacket16,
             PacketSize::ExtendedPacket32,
+            PacketSize::OutfoxRegularPacket,
         ];
         for packet_size in packet_sizes {
             let header = Header {
                 packet_version: PacketVersion::Versioned(123),
                 packet_size,
-                packet_mode: Default::default(),
+                packet_type: Default::default(),
             };
             let mut bytes = BytesMut::new();
             header.encode(&mut bytes);
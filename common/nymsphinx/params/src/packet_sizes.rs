@@ -1,9 +1,9 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::FRAG_ID_LEN;
+use crate::{PacketType, FRAG_ID_LEN};
 use nym_sphinx_types::header::HEADER_SIZE;
-use nym_sphinx_types::PAYLOAD_OVERHEAD_SIZE;
+use nym_sphinx_types::{OUTFOX_PACKET_OVERHEAD, PAYLOAD_OVERHEAD_SIZE};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::convert::TryFrom;
@@ -27,6 +27,9 @@ const EXTENDED_PACKET_SIZE_8: usize = 8 * 1024 + PACKET_OVERHEAD;
 const EXTENDED_PACKET_SIZE_16: usize = 16 * 1024 + PACKET_OVERHEAD;
 const EXTENDED_PACKET_SIZE_32: usize = 32 * 1024 + PACKET_OVERHEAD;
 
+// outfox packets do not have any payload overhead, the entire header is included in OUTFOX_PACKET_OVERHEAD
+const OUTFOX_REGULAR_PACKET_SIZE: usize = 2 * 1024 + OUTFOX_PACKET_OVERHEAD;
+
 #[derive(Debug, Error)]
 pub enum InvalidPacketSize {
     #[error("{received} is not a valid packet size tag")]
@@ -62,6 +65,10 @@ pub enum PacketSize {
     // for example for streaming fast and furious in compressed XviD quality
     #[serde(rename = "extended16")]
     ExtendedPacket16 = 5,
+
+    // regular packet using the outfox format
+    #[serde(rename = "outfox_regular")]
+    OutfoxRegularPacket = 6,
 }
 
 impl PartialOrd for PacketSize {
@@ -88,6 +95,7 @@ impl FromStr for PacketSize {
             "extended8" => Ok(Self::ExtendedPacket8),
             "extended16" => Ok(Self::ExtendedPacket16),
             "extended32" => Ok(Self::ExtendedPacket32),
+            "outfox_regular" => Ok(Self::OutfoxRegularPacket),
             s => Err(InvalidPacketSize::UnknownExtendedPacketVariant {
                 received: s.to_string(),
             }),
@@ -103,6 +111,7 @@ impl Display for PacketSize {
             PacketSize::ExtendedPacket32 => write!(f, "extended32"),
             PacketSize::ExtendedPacket8 => write!(f, "extended8"),
             PacketSize::ExtendedPacket16 => write!(f, "extended16"),
+            PacketSize::OutfoxRegularPacket => write!(f, "outfox_regular"),
         }
     }
 }
@@ -127,6 +136,7 @@ impl TryFrom<u8> for PacketSize {
             _ if value == (PacketSize::ExtendedPacket8 as u8) => Ok(Self::ExtendedPacket8),
             _ if value == (PacketSize::ExtendedPacket16 as u8) => Ok(Self::ExtendedPacket16),
             _ if value == (PacketSize::ExtendedPacket32 as u8) => Ok(Self::ExtendedPacket32),
+            _ if value == (PacketSize::OutfoxRegularPacket as u8) => Ok(Self::OutfoxRegularPacket),
             v => Err(InvalidPacketSize::UnknownPacketTag { received: v }),
         }
     }
@@ -140,15 +150,30 @@ impl PacketSize {
             PacketSize::ExtendedPacket8 => EXTENDED_PACKET_SIZE_8,
             PacketSize::ExtendedPacket16 => EXTENDED_PACKET_SIZE_16,
             PacketSize::ExtendedPacket32 => EXTENDED_PACKET_SIZE_32,
+            PacketSize::OutfoxRegularPacket => OUTFOX_REGULAR_PACKET_SIZE,
         }
     }
 
     pub const fn plaintext_size(self) -> usize {
-        self.size() - HEADER_SIZE - PAYLOAD_OVERHEAD_SIZE
+        match self {
+            PacketSize::OutfoxRegularPacket => self.size() - OUTFOX_PACKET_OVERHEAD,
+            _ => self.size() - HEADER_SIZE - PAYLOAD_OVERHEAD_SIZE,
+        }
     }
 
     pub const fn payload_size(self) -> usize {
-        self.size() - HEADER_SIZE
+        match self {
+            PacketSize::OutfoxRegularPacket => self.size() - OUTFOX_PACKET_OVERHEAD,
+            _ => self.size() - HEADER_SIZE,
+        }
+    }
+
+    /// Returns the type of packets that can be of this size.
+    pub const fn packet_type(self) -> PacketType {
+        match self {
+            PacketSize::OutfoxRegularPacket => PacketType::Outfox,
+            _ => PacketType::Mix,
+        }
     }
 
     pub fn get_type(size: usize) -> Result<Self, InvalidPacketSize> {
@@ -162,6 +187,8 @@ impl PacketSize {
             Ok(PacketSize::ExtendedPacket16)
         } else if PacketSize::ExtendedPacket32.size() == size {
             Ok(PacketSize::ExtendedPacket32)
+        } else if PacketSize::OutfoxRegularPacket.size() == size {
+            Ok(PacketSize::OutfoxRegularPacket)
         } else {
             Err(InvalidPacketSize::UnknownPacketSize { received: size })
         }
@@ -169,7 +196,9 @@ impl PacketSize {
 
     pub fn is_extended_size(&self) -> bool {
         match self {
-            PacketSize::RegularPacket | PacketSize::AckPacket => false,
+            PacketSize::RegularPacket | PacketSize::AckPacket | PacketSize::OutfoxRegularPacket => {
+                false
+            }
             PacketSize::ExtendedPacket8
             | PacketSize::ExtendedPacket16
             | PacketSize::ExtendedPacket32 => true,
@@ -184,9 +213,23 @@ impl PacketSize {
         }
     }
 
-    pub fn get_type_from_plaintext(plaintext_size: usize) -> Result<Self, InvalidPacketSize> {
-        let packet_size = plaintext_size + PACKET_OVERHEAD;
-        Self::get_type(packet_size)
+    pub fn get_type_from_plaintext(
+        plaintext_size: usize,
+        packet_type: PacketType,
+    ) -> Result<Self, InvalidPacketSize> {
+        let packet_size = match packet_type {
+            PacketType::Outfox => plaintext_size + OUTFOX_PACKET_OVERHEAD,
+            PacketType::Mix | PacketType::Vpn => plaintext_size + PACKET_OVERHEAD,
+        };
+        let size = Self::get_type(packet_size)?;
+
+        // make sure we haven't accidentally matched a packet size of a different format
+        if size.packet_type().is_outfox() != packet_type.is_outfox() {
+            return Err(InvalidPacketSize::UnknownPacketSize {
+                received: packet_size,
+            });
+        }
+        Ok(size)
     }
 }
 
@@ -201,4 +244,24 @@ mod tests {
         let iv_size = AckEncryptionAlgorithm::iv_size();
         assert_eq!(iv_size, ACK_IV_SIZE);
     }
+
+    #[test]
+    fn packet_sizes_are_unique() {
+        let sizes = [
+            PacketSize::RegularPacket,
+            PacketSize::AckPacket,
+            PacketSize::ExtendedPacket8,
+            PacketSize::ExtendedPacket16,
+            PacketSize::ExtendedPacket32,
+            PacketSize::OutfoxRegularPacket,
+        ];
+        for size in sizes {
+            assert_eq!(PacketSize::get_type(size.size()).unwrap(), size);
+            assert_eq!(
+                PacketSize::get_type_from_plaintext(size.plaintext_size(), size.packet_type())
+                    .unwrap(),
+                size
+            );
+        }
+    }
 }
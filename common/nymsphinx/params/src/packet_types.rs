@@ -0,0 +1,58 @@
+// Copyright 2021-2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("{received} is not a valid packet type tag")]
+pub struct InvalidPacketType {
+    received: u8,
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PacketType {
+    /// Represents 'normal' packet sent through the network that should be delayed by an appropriate
+    /// value at each hop.
+    #[default]
+    #[serde(rename = "mix")]
+    Mix = 0,
+
+    /// Represents a VPN packet that should not be delayed and ideally cached pre-computed keys
+    /// should be used for unwrapping data. Note that it does not offer the same level of anonymity.
+    #[serde(rename = "vpn")]
+    Vpn = 1,
+
+    /// Represents a 'normal' packet that uses the Outfox format rather than Sphinx.
+    #[serde(rename = "outfox")]
+    Outfox = 2,
+}
+
+impl PacketType {
+    pub fn is_mix(self) -> bool {
+        self == PacketType::Mix
+    }
+
+    pub fn is_old_vpn(self) -> bool {
+        self == PacketType::Vpn
+    }
+
+    pub fn is_outfox(self) -> bool {
+        self == PacketType::Outfox
+    }
+}
+
+impl TryFrom<u8> for PacketType {
+    type Error = InvalidPacketType;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            _ if value == (PacketType::Mix as u8) => Ok(Self::Mix),
+            _ if value == (PacketType::Vpn as u8) => Ok(Self::Vpn),
+            _ if value == (PacketType::Outfox as u8) => Ok(Self::Outfox),
+            v => Err(InvalidPacketType { received: v }),
+        }
+    }
+}
@@ -11,6 +11,7 @@ use nym_sphinx_anonymous_replies::requests::{
     ReplyMessageContent,
 };
 use nym_sphinx_chunking::fragment::Fragment;
+use nym_sphinx_chunking::ErasureCoding;
 use nym_sphinx_params::{PacketSize, ReplySurbKeyDigestAlgorithm};
 use rand::Rng;
 use std::fmt::{Display, Formatter};
@@ -256,10 +257,31 @@ impl PaddedMessage {
         rng: &mut R,
         plaintext_per_packet: usize,
     ) -> Vec<Fragment> {
-        chunking::split_into_sets(rng, &self.0, plaintext_per_packet)
+        self.split_into_fragments_with_erasure_coding(rng, plaintext_per_packet, None)
+            .0
+    }
+
+    /// Splits the padded message into [`Fragment`] that when serialized are going to become
+    /// sphinx packet payloads. If erasure coding is specified, and applicable for the message,
+    /// additional parity [`Fragment`]s are going to get created. The returned flag indicates
+    /// whether that has been the case.
+    pub fn split_into_fragments_with_erasure_coding<R: Rng>(
+        self,
+        rng: &mut R,
+        plaintext_per_packet: usize,
+        erasure_coding: Option<ErasureCoding>,
+    ) -> (Vec<Fragment>, bool) {
+        let (sets, erasure_coded) = chunking::split_into_sets_with_erasure_coding(
+            rng,
+            &self.0,
+            plaintext_per_packet,
+            erasure_coding,
+        );
+        let fragments = sets
             .into_iter()
             .flat_map(|fragment_set| fragment_set.into_iter())
-            .collect()
+            .collect();
+        (fragments, erasure_coded)
     }
 
     // reverse of NymMessage::pad_to_full_packet_lengths
@@ -11,11 +11,12 @@ use nym_sphinx_addressing::clients::Recipient;
 use nym_sphinx_addressing::nodes::NymNodeRoutingAddress;
 use nym_sphinx_anonymous_replies::reply_surb::ReplySurb;
 use nym_sphinx_chunking::fragment::{Fragment, FragmentIdentifier};
+use nym_sphinx_chunking::ErasureCoding;
 use nym_sphinx_forwarding::packet::MixPacket;
 use nym_sphinx_params::packet_sizes::PacketSize;
-use nym_sphinx_params::{ReplySurbKeyDigestAlgorithm, DEFAULT_NUM_MIX_HOPS};
+use nym_sphinx_params::{PacketType, ReplySurbKeyDigestAlgorithm, DEFAULT_NUM_MIX_HOPS};
 use nym_sphinx_types::builder::SphinxPacketBuilder;
-use nym_sphinx_types::{delays, Delay};
+use nym_sphinx_types::{delays, Delay, NymPacket, OutfoxPacket};
 use nym_topology::{NymTopology, NymTopologyError};
 use rand::{CryptoRng, Rng};
 use std::convert::TryFrom;
@@ -31,7 +32,7 @@ pub struct PreparedFragment {
 
     /// Indicates all data required to serialize and forward the data. It contains the actual
     /// address of the node to which the message should be sent, the actual 'chunk' of the message
-    /// going through the mix network and also the 'mode' of the packet, i.e. VPN or Mix.
+    /// going through the mix network and also the 'type' of the packet, i.e. Mix or Outfox.
     pub mix_packet: MixPacket,
 
     /// Identifier to uniquely identify a fragment.
@@ -54,6 +55,12 @@ pub trait FragmentPreparer {
     fn average_packet_delay(&self) -> Duration;
     fn average_ack_delay(&self) -> Duration;
 
+    /// Format of the packets used for sending 'real' data. Note that acknowledgements, replies
+    /// and cover traffic always use Sphinx.
+    fn packet_type(&self) -> PacketType {
+        PacketType::Mix
+    }
+
     fn generate_reply_surbs(
         &mut self,
         amount: usize,
@@ -115,7 +122,7 @@ pub trait FragmentPreparer {
 
         // the reason we're unwrapping (or rather 'expecting') here rather than handling the error
         // more gracefully is that this error should never be reached as it implies incorrect chunking
-        let packet_size = PacketSize::get_type_from_plaintext(expected_plaintext)
+        let packet_size = PacketSize::get_type_from_plaintext(expected_plaintext, PacketType::Mix)
             .expect("the message has been incorrectly fragmented");
 
         // this is not going to be accurate by any means. but that's the best estimation we can do
@@ -143,7 +150,7 @@ pub trait FragmentPreparer {
             // well as the total delay of the ack packet.
             // we don't know the delays inside the reply surbs so we use best-effort estimation from our poisson distribution
             total_delay: expected_forward_delay + ack_delay,
-            mix_packet: MixPacket::new(first_hop_address, sphinx_packet, Default::default()),
+            mix_packet: MixPacket::new(first_hop_address, sphinx_packet.into(), PacketType::Mix),
             fragment_identifier,
         })
     }
@@ -165,6 +172,9 @@ pub trait FragmentPreparer {
     /// - compute vk_b = g^x || v_b
     /// - compute sphinx_plaintext = SURB_ACK || g^x || v_b
     /// - compute sphinx_packet = Sphinx(recipient, sphinx_plaintext)
+    ///
+    /// If the preparer is set to use Outfox, the final step uses the Outfox format instead,
+    /// assuming the resultant packet is of regular size and goes through the default number of hops.
     fn prepare_chunk_for_sending(
         &mut self,
         fragment: Fragment,
@@ -179,9 +189,18 @@ pub trait FragmentPreparer {
         let non_reply_overhead = encryption::PUBLIC_KEY_SIZE;
         let expected_plaintext = fragment.serialized_size() + ACK_OVERHEAD + non_reply_overhead;
 
+        // outfox packets are only defined for the default number of hops
+        let packet_type = if self.num_mix_hops() == DEFAULT_NUM_MIX_HOPS {
+            self.packet_type()
+        } else {
+            PacketType::Mix
+        };
+
         // the reason we're unwrapping (or rather 'expecting') here rather than handling the error
         // more gracefully is that this error should never be reached as it implies incorrect chunking
-        let packet_size = PacketSize::get_type_from_plaintext(expected_plaintext)
+        // (note: outfox is only defined for the regular packet size, so fallback to sphinx for anything else)
+        let packet_size = PacketSize::get_type_from_plaintext(expected_plaintext, packet_type)
+            .or_else(|_| PacketSize::get_type_from_plaintext(expected_plaintext, PacketType::Mix))
             .expect("the message has been incorrectly fragmented");
 
         let fragment_identifier = fragment.fragment_identifier();
@@ -204,12 +223,24 @@ pub trait FragmentPreparer {
         let delays =
             delays::generate_from_average_duration(route.len(), self.average_packet_delay());
 
-        // create the actual sphinx packet here. With valid route and correct payload size,
+        // create the actual packet here. With valid route and correct payload size,
         // there's absolutely no reason for this call to fail.
-        let sphinx_packet = SphinxPacketBuilder::new()
-            .with_payload_size(packet_size.payload_size())
-            .build_packet(packet_payload, &route, &destination, &delays)
-            .unwrap();
+        let packet: NymPacket = if packet_size.packet_type().is_outfox() {
+            OutfoxPacket::build(
+                packet_payload.as_ref(),
+                &route,
+                &destination.address,
+                &delays,
+            )
+            .unwrap()
+            .into()
+        } else {
+            SphinxPacketBuilder::new()
+                .with_payload_size(packet_size.payload_size())
+                .build_packet(packet_payload, &route, &destination, &delays)
+                .unwrap()
+                .into()
+        };
 
         // from the previously constructed route extract the first hop
         let first_hop_address =
@@ -220,7 +251,7 @@ pub trait FragmentPreparer {
             // well as the total delay of the ack packet.
             // note that the last hop of the packet is a gateway that does not do any delays
             total_delay: delays.iter().take(delays.len() - 1).sum::<Delay>() + ack_delay,
-            mix_packet: MixPacket::new(first_hop_address, sphinx_packet, Default::default()),
+            mix_packet: MixPacket::new(first_hop_address, packet, packet_size.packet_type()),
             fragment_identifier,
         })
     }
@@ -230,11 +261,27 @@ pub trait FragmentPreparer {
         message: NymMessage,
         packet_size: PacketSize,
     ) -> Vec<Fragment> {
+        self.pad_and_split_message_with_erasure_coding(message, packet_size, None)
+            .0
+    }
+
+    /// Pads and splits the message into [`Fragment`]s, applying the erasure coding if it's
+    /// possible for the message. The returned flag indicates whether it has been applied.
+    fn pad_and_split_message_with_erasure_coding(
+        &mut self,
+        message: NymMessage,
+        packet_size: PacketSize,
+        erasure_coding: Option<ErasureCoding>,
+    ) -> (Vec<Fragment>, bool) {
         let plaintext_per_packet = message.available_sphinx_plaintext_per_packet(packet_size);
 
         message
             .pad_to_full_packet_lengths(plaintext_per_packet)
-            .split_into_fragments(self.rng(), plaintext_per_packet)
+            .split_into_fragments_with_erasure_coding(
+                self.rng(),
+                plaintext_per_packet,
+                erasure_coding,
+            )
     }
 }
 
@@ -260,6 +307,9 @@ pub struct MessagePreparer<R> {
     /// Number of mix hops each packet ('real' message, ack, reply) is expected to take.
     /// Note that it does not include gateway hops.
     num_mix_hops: u8,
+
+    /// Format of the packets used for sending 'real' messages.
+    packet_type: PacketType,
 }
 
 impl<R> MessagePreparer<R>
@@ -278,6 +328,7 @@ where
             average_packet_delay,
             average_ack_delay,
             num_mix_hops: DEFAULT_NUM_MIX_HOPS,
+            packet_type: PacketType::Mix,
         }
     }
 
@@ -287,6 +338,12 @@ where
         self
     }
 
+    /// Allows setting non-default format of the packets used for sending 'real' messages.
+    pub fn with_packet_type(mut self, packet_type: PacketType) -> Self {
+        self.packet_type = packet_type;
+        self
+    }
+
     /// Overwrites existing sender address with the provided value.
     pub fn set_sender_address(&mut self, sender_address: Recipient) {
         self.sender_address = sender_address;
@@ -362,6 +419,20 @@ where
     ) -> Vec<Fragment> {
         <Self as FragmentPreparer>::pad_and_split_message(self, message, packet_size)
     }
+
+    pub fn pad_and_split_message_with_erasure_coding(
+        &mut self,
+        message: NymMessage,
+        packet_size: PacketSize,
+        erasure_coding: Option<ErasureCoding>,
+    ) -> (Vec<Fragment>, bool) {
+        <Self as FragmentPreparer>::pad_and_split_message_with_erasure_coding(
+            self,
+            message,
+            packet_size,
+            erasure_coding,
+        )
+    }
 }
 
 impl<R: CryptoRng + Rng> FragmentPreparer for MessagePreparer<R> {
@@ -382,6 +453,10 @@ impl<R: CryptoRng + Rng> FragmentPreparer for MessagePreparer<R> {
     fn average_ack_delay(&self) -> Duration {
         self.average_ack_delay
     }
+
+    fn packet_type(&self) -> PacketType {
+        self.packet_type
+    }
 }
 
 /*
@@ -287,6 +287,8 @@ mod message_receiver {
                     "B3GzG62aXAZNg14RoMCp3BhELNBrySLr2JqrwyfYFzRc",
                 )
                 .unwrap(),
+                sphinx_key_validity: None,
+                announced_sphinx_keys: None,
                 layer: Layer::One,
                 version: "0.8.0-dev".to_string(),
             }],
@@ -307,6 +309,8 @@ mod message_receiver {
                     "5Z1VqYwM2xeKxd8H7fJpGWasNiDFijYBAee7MErkZ5QT",
                 )
                 .unwrap(),
+                sphinx_key_validity: None,
+                announced_sphinx_keys: None,
                 layer: Layer::Two,
                 version: "0.8.0-dev".to_string(),
             }],
@@ -327,6 +331,8 @@ mod message_receiver {
                     "9EyjhCggr2QEA2nakR88YHmXgpy92DWxoe2draDRkYof",
                 )
                 .unwrap(),
+                sphinx_key_validity: None,
+                announced_sphinx_keys: None,
                 layer: Layer::Three,
                 version: "0.8.0-dev".to_string(),
             }],
@@ -348,6 +354,8 @@ mod message_receiver {
                     "EB42xvMFMD5rUCstE2CDazgQQJ22zLv8SPm1Luxni44c",
                 )
                 .unwrap(),
+                sphinx_key_validity: None,
+                announced_sphinx_keys: None,
                 version: "0.8.0-dev".to_string(),
             }],
         )
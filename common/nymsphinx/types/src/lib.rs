@@ -15,3 +15,119 @@ pub use sphinx_packet::{
     surb::{SURBMaterial, SURB},
     Error, ProcessedPacket, Result, SphinxPacket,
 };
+
+pub use nym_outfox::{
+    error::OutfoxError,
+    packet::{OutfoxPacket, OutfoxRoutingInformation, OUTFOX_PACKET_OVERHEAD},
+};
+
+use std::fmt::{self, Debug, Formatter};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum NymPacketError {
+    #[error("Sphinx error: {0}")]
+    Sphinx(#[from] Error),
+
+    #[error("Outfox error: {0}")]
+    Outfox(#[from] OutfoxError),
+}
+
+/// Packet travelling through the mix network using either of the supported wire formats.
+#[allow(clippy::large_enum_variant)]
+pub enum NymPacket {
+    Sphinx(SphinxPacket),
+    Outfox(OutfoxPacket),
+}
+
+/// Result of processing a single layer of a [`NymPacket`].
+pub enum NymProcessedPacket {
+    Sphinx(ProcessedPacket),
+
+    /// Outfox packet with its next layer decoded alongside the routing information
+    /// recovered out of it.
+    Outfox(OutfoxPacket, OutfoxRoutingInformation),
+}
+
+impl Debug for NymPacket {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            NymPacket::Sphinx(packet) => write!(
+                f,
+                "Sphinx packet. header: {:?}, payload length: {}",
+                packet.header,
+                packet.payload.len()
+            ),
+            NymPacket::Outfox(packet) => write!(
+                f,
+                "Outfox packet. remaining layers: {}, length: {}",
+                packet.remaining_layers(),
+                packet.len()
+            ),
+        }
+    }
+}
+
+impl NymPacket {
+    pub fn sphinx_from_bytes(bytes: &[u8]) -> std::result::Result<NymPacket, NymPacketError> {
+        Ok(NymPacket::Sphinx(SphinxPacket::from_bytes(bytes)?))
+    }
+
+    pub fn outfox_from_bytes(bytes: &[u8]) -> std::result::Result<NymPacket, NymPacketError> {
+        Ok(NymPacket::Outfox(OutfoxPacket::try_from_bytes(bytes)?))
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            NymPacket::Sphinx(packet) => packet.len(),
+            NymPacket::Outfox(packet) => packet.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            NymPacket::Sphinx(packet) => packet.to_bytes(),
+            NymPacket::Outfox(packet) => packet.to_bytes(),
+        }
+    }
+
+    /// Bytes uniquely identifying this packet at the current hop that can be used for replay detection.
+    pub fn replay_tag_material(&self) -> Option<[u8; 32]> {
+        match self {
+            NymPacket::Sphinx(packet) => Some(*packet.header.shared_secret.as_bytes()),
+            NymPacket::Outfox(packet) => packet.current_group_element(),
+        }
+    }
+
+    /// Unwraps a single layer of encryption of this packet using the provided private key.
+    pub fn process(
+        self,
+        node_secret_key: &PrivateKey,
+    ) -> std::result::Result<NymProcessedPacket, NymPacketError> {
+        match self {
+            NymPacket::Sphinx(packet) => {
+                Ok(NymProcessedPacket::Sphinx(packet.process(node_secret_key)?))
+            }
+            NymPacket::Outfox(mut packet) => {
+                let routing_information = packet.decode_next_layer(&node_secret_key.to_bytes())?;
+                Ok(NymProcessedPacket::Outfox(packet, routing_information))
+            }
+        }
+    }
+}
+
+impl From<SphinxPacket> for NymPacket {
+    fn from(packet: SphinxPacket) -> Self {
+        NymPacket::Sphinx(packet)
+    }
+}
+
+impl From<OutfoxPacket> for NymPacket {
+    fn from(packet: OutfoxPacket) -> Self {
+        NymPacket::Outfox(packet)
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nym_store_cipher::{Aes256Gcm, EncryptedData, ExportedStoreCipher, StoreCipher};
+use serde::{Deserialize, Serialize};
+use std::io;
+
+const ENCRYPTED_TAG_PREFIX: &str = "ENCRYPTED ";
+
+/// Similarly to PKCS#8 `EncryptedPrivateKeyInfo`, alongside the ciphertext itself,
+/// it contains all the information required for re-deriving the encryption key from the passphrase.
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeyInfo {
+    /// Argon2 parameters and salt used for the key derivation alongside a known ciphertext
+    /// used for verifying the passphrase.
+    cipher: ExportedStoreCipher,
+
+    /// The actual encrypted key material.
+    encrypted_key: EncryptedData,
+}
+
+pub(crate) fn encrypted_pem_tag(tag: &str) -> String {
+    format!("{ENCRYPTED_TAG_PREFIX}{tag}")
+}
+
+pub(crate) fn is_encrypted_pem_tag(tag: &str) -> bool {
+    tag.starts_with(ENCRYPTED_TAG_PREFIX)
+}
+
+fn cipher_error(err: nym_store_cipher::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+pub(crate) fn encrypt_key_bytes(passphrase: &[u8], key_bytes: Vec<u8>) -> io::Result<Vec<u8>> {
+    let cipher =
+        StoreCipher::<Aes256Gcm>::new_with_default_kdf(passphrase).map_err(cipher_error)?;
+    let info = EncryptedKeyInfo {
+        cipher: cipher.export_aes256gcm().map_err(cipher_error)?,
+        encrypted_key: cipher.encrypt_data(key_bytes).map_err(cipher_error)?,
+    };
+
+    serde_json::to_vec(&info).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+pub(crate) fn decrypt_key_bytes(passphrase: &[u8], contents: &[u8]) -> io::Result<Vec<u8>> {
+    let info: EncryptedKeyInfo = serde_json::from_slice(contents)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    let cipher = StoreCipher::<Aes256Gcm>::import_aes256gcm(passphrase, info.cipher)
+        .map_err(cipher_error)?;
+    cipher
+        .decrypt_data(info.encrypted_key)
+        .map_err(cipher_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypted_key_can_be_decrypted_with_the_same_passphrase() {
+        let key = vec![42u8; 32];
+        let encrypted = encrypt_key_bytes(b"passphrase", key.clone()).unwrap();
+        assert!(!encrypted.windows(key.len()).any(|window| window == key));
+
+        assert_eq!(decrypt_key_bytes(b"passphrase", &encrypted).unwrap(), key);
+    }
+
+    #[test]
+    fn decryption_fails_with_wrong_passphrase() {
+        let encrypted = encrypt_key_bytes(b"passphrase", vec![42u8; 32]).unwrap();
+        let err = decrypt_key_bytes(b"wrong passphrase", &encrypted).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn decryption_fails_for_malformed_data() {
+        let err = decrypt_key_bytes(b"passphrase", &[42u8; 32]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn encrypted_tags_are_recognised() {
+        let tag = encrypted_pem_tag("ED25519 PRIVATE KEY");
+        assert_eq!(tag, "ENCRYPTED ED25519 PRIVATE KEY");
+        assert!(is_encrypted_pem_tag(&tag));
+        assert!(!is_encrypted_pem_tag("ED25519 PRIVATE KEY"));
+    }
+}
@@ -7,6 +7,7 @@ use std::fs::File;
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 
+mod encryption;
 pub mod traits;
 
 #[derive(Debug)]
@@ -28,34 +29,86 @@ pub fn load_keypair<T>(paths: &KeyPairPath) -> io::Result<T>
 where
     T: PemStorableKeyPair,
 {
-    let private: T::PrivatePemKey = load_key(&paths.private_key_path)?;
+    load_keypair_with_passphrase(paths, None)
+}
+
+/// Loads the keypair, decrypting the private key with the provided passphrase if it was stored encrypted.
+/// Note that plaintext private keys are still going to be loaded even if the passphrase is provided.
+pub fn load_keypair_with_passphrase<T>(
+    paths: &KeyPairPath,
+    passphrase: Option<&[u8]>,
+) -> io::Result<T>
+where
+    T: PemStorableKeyPair,
+{
+    let private: T::PrivatePemKey = load_key_with_passphrase(&paths.private_key_path, passphrase)?;
     let public: T::PublicPemKey = load_key(&paths.public_key_path)?;
     Ok(T::from_keys(private, public))
 }
 
 pub fn store_keypair<T>(keypair: &T, paths: &KeyPairPath) -> io::Result<()>
+where
+    T: PemStorableKeyPair,
+{
+    store_keypair_with_passphrase(keypair, paths, None)
+}
+
+/// Stores the keypair, encrypting the private key with the provided passphrase (if any).
+/// The public key is always stored in plaintext.
+pub fn store_keypair_with_passphrase<T>(
+    keypair: &T,
+    paths: &KeyPairPath,
+    passphrase: Option<&[u8]>,
+) -> io::Result<()>
 where
     T: PemStorableKeyPair,
 {
     store_key(keypair.public_key(), &paths.public_key_path)?;
-    store_key(keypair.private_key(), &paths.private_key_path)
+    store_key_with_passphrase(keypair.private_key(), &paths.private_key_path, passphrase)
 }
 
 pub fn load_key<T, P>(path: P) -> io::Result<T>
+where
+    T: PemStorableKey,
+    P: AsRef<Path>,
+{
+    load_key_with_passphrase(path, None)
+}
+
+/// Loads the key, decrypting it with the provided passphrase if it was stored encrypted.
+/// Note that plaintext keys are still going to be loaded even if the passphrase is provided.
+pub fn load_key_with_passphrase<T, P>(path: P, passphrase: Option<&[u8]>) -> io::Result<T>
 where
     T: PemStorableKey,
     P: AsRef<Path>,
 {
     let key_pem = read_pem_file(path)?;
 
-    if T::pem_type() != key_pem.tag {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "unexpected key pem tag",
-        ));
-    }
+    let key_bytes = if encryption::is_encrypted_pem_tag(&key_pem.tag) {
+        if encryption::encrypted_pem_tag(T::pem_type()) != key_pem.tag {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "unexpected key pem tag",
+            ));
+        }
+        let Some(passphrase) = passphrase else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the key is encrypted, but no passphrase was provided",
+            ));
+        };
+        encryption::decrypt_key_bytes(passphrase, &key_pem.contents)?
+    } else {
+        if T::pem_type() != key_pem.tag {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "unexpected key pem tag",
+            ));
+        }
+        key_pem.contents
+    };
 
-    let key = match T::from_bytes(&key_pem.contents) {
+    let key = match T::from_bytes(&key_bytes) {
         Ok(key) => key,
         Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err.to_string())),
     };
@@ -68,7 +121,28 @@ where
     T: PemStorableKey,
     P: AsRef<Path>,
 {
-    write_pem_file(path, key.to_bytes(), T::pem_type())
+    store_key_with_passphrase(key, path, None)
+}
+
+/// Stores the key, encrypting it with the provided passphrase (if any).
+/// The encryption key is derived with argon2 and the key material is encrypted with AES-GCM.
+pub fn store_key_with_passphrase<T, P>(
+    key: &T,
+    path: P,
+    passphrase: Option<&[u8]>,
+) -> io::Result<()>
+where
+    T: PemStorableKey,
+    P: AsRef<Path>,
+{
+    match passphrase {
+        Some(passphrase) => write_pem_file(
+            path,
+            encryption::encrypt_key_bytes(passphrase, key.to_bytes())?,
+            &encryption::encrypted_pem_tag(T::pem_type()),
+        ),
+        None => write_pem_file(path, key.to_bytes(), T::pem_type()),
+    }
 }
 
 fn read_pem_file<P: AsRef<Path>>(filepath: P) -> io::Result<Pem> {
@@ -109,3 +183,166 @@ fn write_pem_file<P: AsRef<Path>>(filepath: P, data: Vec<u8>, tag: &str) -> io::
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::{self, Display, Formatter};
+    use tempfile::tempdir;
+
+    const PASSPHRASE: &[u8] = b"my-super-secret-passphrase";
+
+    #[derive(Debug, PartialEq)]
+    struct DummyKey(Vec<u8>);
+
+    #[derive(Debug)]
+    struct DummyKeyError;
+
+    impl Display for DummyKeyError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "invalid dummy key")
+        }
+    }
+
+    impl std::error::Error for DummyKeyError {}
+
+    impl PemStorableKey for DummyKey {
+        type Error = DummyKeyError;
+
+        fn pem_type() -> &'static str {
+            "DUMMY PRIVATE KEY"
+        }
+
+        fn to_bytes(&self) -> Vec<u8> {
+            self.0.clone()
+        }
+
+        fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error> {
+            if bytes.len() != 32 {
+                return Err(DummyKeyError);
+            }
+            Ok(DummyKey(bytes.to_vec()))
+        }
+    }
+
+    fn dummy_key() -> DummyKey {
+        DummyKey((0..32).collect())
+    }
+
+    #[test]
+    fn plaintext_key_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("key.pem");
+
+        store_key(&dummy_key(), &path).unwrap();
+        assert_eq!(read_pem_file(&path).unwrap().tag, "DUMMY PRIVATE KEY");
+        assert_eq!(load_key::<DummyKey, _>(&path).unwrap(), dummy_key());
+    }
+
+    #[test]
+    fn encrypted_key_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("key.pem");
+
+        store_key_with_passphrase(&dummy_key(), &path, Some(PASSPHRASE)).unwrap();
+        let pem = read_pem_file(&path).unwrap();
+        assert_eq!(pem.tag, "ENCRYPTED DUMMY PRIVATE KEY");
+        assert_ne!(pem.contents, dummy_key().0);
+
+        let loaded: DummyKey = load_key_with_passphrase(&path, Some(PASSPHRASE)).unwrap();
+        assert_eq!(loaded, dummy_key());
+    }
+
+    #[test]
+    fn encrypted_key_is_not_loaded_with_wrong_passphrase() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("key.pem");
+
+        store_key_with_passphrase(&dummy_key(), &path, Some(PASSPHRASE)).unwrap();
+        let err =
+            load_key_with_passphrase::<DummyKey, _>(&path, Some(b"wrong-passphrase")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn encrypted_key_is_not_loaded_without_passphrase() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("key.pem");
+
+        store_key_with_passphrase(&dummy_key(), &path, Some(PASSPHRASE)).unwrap();
+        let err = load_key::<DummyKey, _>(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn legacy_plaintext_key_is_loaded_with_passphrase() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("key.pem");
+
+        store_key(&dummy_key(), &path).unwrap();
+        let loaded: DummyKey = load_key_with_passphrase(&path, Some(PASSPHRASE)).unwrap();
+        assert_eq!(loaded, dummy_key());
+
+        // and it can then get encrypted
+        store_key_with_passphrase(&loaded, &path, Some(PASSPHRASE)).unwrap();
+        assert!(load_key::<DummyKey, _>(&path).is_err());
+        let loaded: DummyKey = load_key_with_passphrase(&path, Some(PASSPHRASE)).unwrap();
+        assert_eq!(loaded, dummy_key());
+    }
+
+    #[test]
+    fn key_with_unexpected_tag_is_rejected() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("key.pem");
+
+        write_pem_file(&path, dummy_key().0, "OTHER PRIVATE KEY").unwrap();
+        assert!(load_key::<DummyKey, _>(&path).is_err());
+
+        let encrypted = encryption::encrypt_key_bytes(PASSPHRASE, dummy_key().0).unwrap();
+        write_pem_file(&path, encrypted, "ENCRYPTED OTHER PRIVATE KEY").unwrap();
+        assert!(load_key_with_passphrase::<DummyKey, _>(&path, Some(PASSPHRASE)).is_err());
+    }
+
+    #[test]
+    fn keypair_round_trip_only_encrypts_private_key() {
+        struct DummyKeyPair {
+            private: DummyKey,
+            public: DummyKey,
+        }
+
+        impl PemStorableKeyPair for DummyKeyPair {
+            type PrivatePemKey = DummyKey;
+            type PublicPemKey = DummyKey;
+
+            fn private_key(&self) -> &Self::PrivatePemKey {
+                &self.private
+            }
+
+            fn public_key(&self) -> &Self::PublicPemKey {
+                &self.public
+            }
+
+            fn from_keys(private: Self::PrivatePemKey, public: Self::PublicPemKey) -> Self {
+                DummyKeyPair { private, public }
+            }
+        }
+
+        let dir = tempdir().unwrap();
+        let paths = KeyPairPath::new(
+            dir.path().join("private.pem"),
+            dir.path().join("public.pem"),
+        );
+        let keypair = DummyKeyPair {
+            private: dummy_key(),
+            public: DummyKey(vec![1; 32]),
+        };
+
+        store_keypair_with_passphrase(&keypair, &paths, Some(PASSPHRASE)).unwrap();
+        assert!(load_key::<DummyKey, _>(&paths.public_key_path).is_ok());
+        assert!(load_keypair::<DummyKeyPair>(&paths).is_err());
+
+        let loaded: DummyKeyPair = load_keypair_with_passphrase(&paths, Some(PASSPHRASE)).unwrap();
+        assert_eq!(loaded.private, keypair.private);
+        assert_eq!(loaded.public, keypair.public);
+    }
+}
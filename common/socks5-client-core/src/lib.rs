@@ -63,11 +63,33 @@ pub struct NymClient {
     /// Client configuration options, including, among other things, packet sending rates,
     /// key filepaths, etc.
     config: Config,
+
+    /// Optional passphrase used for encrypting the stored bandwidth credentials.
+    credentials_passphrase: Option<String>,
+
+    /// Optional passphrase used for encrypting the private keys stored on disk.
+    key_passphrase: Option<String>,
 }
 
 impl NymClient {
     pub fn new(config: Config) -> Self {
-        NymClient { config }
+        NymClient {
+            config,
+            credentials_passphrase: None,
+            key_passphrase: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_credentials_passphrase(mut self, credentials_passphrase: Option<String>) -> Self {
+        self.credentials_passphrase = credentials_passphrase;
+        self
+    }
+
+    #[must_use]
+    pub fn with_key_passphrase(mut self, key_passphrase: Option<String>) -> Self {
+        self.key_passphrase = key_passphrase;
+        self
     }
 
     pub fn start_socks5_listener(
@@ -90,6 +112,7 @@ impl NymClient {
 
         let ClientOutput {
             received_buffer_request_sender,
+            ..
         } = client_output;
 
         let ClientState {
@@ -222,16 +245,23 @@ impl NymClient {
 impl NymClient {
     fn key_store(&self) -> OnDiskKeys {
         let pathfinder = ClientKeyPathfinder::new_from_config(self.config.get_base());
-        OnDiskKeys::new(pathfinder)
+        OnDiskKeys::new(pathfinder).with_key_passphrase(self.key_passphrase.clone())
     }
 
     async fn create_bandwidth_controller(
         &self,
     ) -> BandwidthController<Client<QueryNyxdClient>, PersistentStorage> {
-        let storage = nym_credential_storage::initialise_persistent_storage(
-            self.config.get_base().get_database_path(),
-        )
-        .await;
+        let database_path = self.config.get_base().get_database_path();
+        let storage = match &self.credentials_passphrase {
+            Some(passphrase) => {
+                nym_credential_storage::initialise_encrypted_persistent_storage(
+                    database_path,
+                    passphrase,
+                )
+                .await
+            }
+            None => nym_credential_storage::initialise_persistent_storage(database_path).await,
+        };
 
         non_wasm_helpers::create_bandwidth_controller(self.config.get_base(), storage)
     }
@@ -3,9 +3,11 @@
 use super::authentication::{AuthenticationMethods, Authenticator, User};
 use super::request::{SocksCommand, SocksRequest};
 use super::types::{ResponseCodeV4, ResponseCodeV5, SocksProxyError};
+use super::udp::{self, UdpAssociations};
 use super::{SocksVersion, RESERVED, SOCKS4_VERSION, SOCKS5_VERSION};
 use futures::channel::mpsc;
 use futures::task::{Context, Poll};
+use futures::StreamExt;
 use log::*;
 use nym_client_core::client::inbound_messages::{InputMessage, InputMessageSender};
 use nym_service_providers_common::interface::{ProviderInterfaceVersion, RequestVersion};
@@ -26,8 +28,12 @@ use std::io;
 use std::net::SocketAddr;
 use std::pin::Pin;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio::net::UdpSocket;
 use tokio::{self, net::TcpStream};
 
+/// Maximum size of a UDP datagram we might receive from the local application.
+const MAX_DATAGRAM_SIZE: usize = 65535;
+
 #[pin_project(project = StateProject)]
 enum StreamState {
     Available(TcpStream),
@@ -174,6 +180,7 @@ impl Config {
 pub(crate) struct SocksClient {
     config: Config,
     controller_sender: ControllerSender,
+    udp_associations: UdpAssociations,
     stream: StreamState,
     auth_nmethods: u8,
     authenticator: Authenticator,
@@ -210,6 +217,7 @@ impl SocksClient {
         input_sender: InputMessageSender,
         service_provider: &Recipient,
         controller_sender: ControllerSender,
+        udp_associations: UdpAssociations,
         self_address: &Recipient,
         lane_queue_lengths: LaneQueueLengths,
         mut shutdown_listener: TaskClient,
@@ -222,6 +230,7 @@ impl SocksClient {
         SocksClient {
             config,
             controller_sender,
+            udp_associations,
             connection_id,
             stream: StreamState::Available(stream),
             auth_nmethods: 0,
@@ -450,6 +459,116 @@ impl SocksClient {
         self.stream.finish_proxy(stream)
     }
 
+    async fn send_datagram_to_mixnet(&mut self, remote_address: RemoteAddress, data: Vec<u8>) {
+        let anonymous = self.config.use_surbs_for_responses;
+        let return_address = if anonymous {
+            None
+        } else {
+            Some(self.self_address)
+        };
+
+        // TODO: simplify by using `request_version`
+        let req = Socks5Request::new_send_datagram(
+            self.config.socks5_protocol_version,
+            self.connection_id,
+            remote_address,
+            data,
+            return_address,
+        );
+        let msg =
+            Socks5ProviderRequest::new_provider_data(self.config.provider_interface_version, req);
+
+        let lane = TransmissionLane::ConnectionId(self.connection_id);
+        let input_message = if anonymous {
+            InputMessage::new_anonymous(
+                self.service_provider,
+                msg.into_bytes(),
+                self.config.per_request_surbs,
+                lane,
+            )
+        } else {
+            InputMessage::new_regular(self.service_provider, msg.into_bytes(), lane)
+        };
+        self.input_sender
+            .send(input_message)
+            .await
+            .expect("InputMessageReceiver has stopped receiving!");
+    }
+
+    /// Relays datagrams between the local application and the service provider for as long
+    /// as the TCP connection the association was requested on stays open.
+    async fn run_udp_relay(&mut self) -> Result<(), SocksProxyError> {
+        // same as the main socks server, we only ever want to listen locally
+        let socket = UdpSocket::bind("127.0.0.1:0")
+            .await
+            .map_err(|source| SocksProxyError::UdpRelayBindFailure { source })?;
+        let relay_address = socket
+            .local_addr()
+            .map_err(|source| SocksProxyError::UdpRelayBindFailure { source })?;
+        self.acknowledge_socks5_udp_associate(relay_address).await?;
+
+        let (datagram_sender, mut datagram_receiver) = mpsc::unbounded();
+        self.udp_associations
+            .insert(self.connection_id, datagram_sender);
+
+        // the application address is only learned once it sends us its first datagram
+        let mut application_address = None;
+        let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+        let mut control_buf = [0u8; 64];
+
+        loop {
+            tokio::select! {
+                read = self.stream.read(&mut control_buf) => {
+                    // the association terminates when the TCP connection it arrived on terminates
+                    if !matches!(read, Ok(n) if n > 0) {
+                        debug!("UDP association {} control stream is closed", self.connection_id);
+                        break;
+                    }
+                }
+                received = socket.recv_from(&mut buf) => {
+                    let (n, source) = match received {
+                        Ok(received) => received,
+                        Err(err) => {
+                            debug!("failed to receive local datagram: {err}");
+                            continue;
+                        }
+                    };
+                    if *application_address.get_or_insert(source) != source {
+                        debug!("ignoring datagram from unexpected source {source}");
+                        continue;
+                    }
+                    let Some((remote_address, data)) = udp::parse_udp_request(&buf[..n]) else {
+                        debug!("received malformed udp request from {source}");
+                        continue;
+                    };
+                    let data = data.to_vec();
+                    self.send_datagram_to_mixnet(remote_address, data).await;
+                }
+                datagram = datagram_receiver.next() => {
+                    let Some(datagram) = datagram else {
+                        break;
+                    };
+                    let Some(application_address) = application_address else {
+                        debug!("received a datagram before the application has sent anything");
+                        continue;
+                    };
+                    if let Some(response) = udp::encode_udp_response(datagram) {
+                        if let Err(err) = socket.send_to(&response, application_address).await {
+                            debug!("failed to send datagram to {application_address}: {err}");
+                        }
+                    }
+                }
+                _ = self.shutdown_listener.recv() => {
+                    log::trace!("SocksClient UDP relay: Received shutdown");
+                    break;
+                }
+            }
+        }
+
+        self.udp_associations.remove(self.connection_id);
+        Ok(())
+    }
+
     /// Handles a client request.
     async fn handle_request(&mut self) -> Result<(), SocksProxyError> {
         debug!("Handling CONNECT Command");
@@ -498,8 +617,17 @@ impl SocksClient {
                 );
             }
 
+            SocksCommand::UdpAssociate => {
+                if *version != SocksVersion::V5 {
+                    return Err(ResponseCodeV5::CommandNotSupported.into());
+                }
+
+                info!("Starting UDP relay (id: {})", self.connection_id);
+                self.run_udp_relay().await?;
+                info!("UDP relay is finished (id: {})", self.connection_id);
+            }
+
             SocksCommand::Bind => unimplemented!(), // not handled
-            SocksCommand::UdpAssociate => unimplemented!(), // not handled
         };
 
         Ok(())
@@ -525,6 +653,27 @@ impl SocksClient {
             .unwrap();
     }
 
+    /// Writes a Socks5 header back to the requesting client's TCP stream containing
+    /// the address of the udp relay the client should be sending its datagrams to.
+    async fn acknowledge_socks5_udp_associate(
+        &mut self,
+        relay_address: SocketAddr,
+    ) -> Result<(), SocksProxyError> {
+        let SocketAddr::V4(relay_address) = relay_address else {
+            // we have explicitly bound to an ipv4 address
+            return Err(ResponseCodeV5::Failure.into());
+        };
+
+        let mut response = vec![SOCKS5_VERSION, ResponseCodeV5::Success as u8, RESERVED, 1];
+        response.extend_from_slice(&relay_address.ip().octets());
+        response.extend_from_slice(&relay_address.port().to_be_bytes());
+
+        self.stream
+            .write_all(&response)
+            .await
+            .map_err(|source| SocksProxyError::SocketWriteError { source })
+    }
+
     /// Writes a Socks4 header back to the requesting client's TCP stream,
     async fn acknowledge_socks4(&mut self) {
         self.stream
@@ -13,11 +13,13 @@ use nym_sphinx::receiver::ReconstructedMessage;
 use nym_task::TaskClient;
 
 use crate::error::Socks5ClientCoreError;
+use crate::socks::udp::UdpAssociations;
 
 pub(crate) struct MixnetResponseListener {
     buffer_requester: ReceivedBufferRequestSender,
     mix_response_receiver: ReconstructedMessagesReceiver,
     controller_sender: ControllerSender,
+    udp_associations: UdpAssociations,
     shutdown: TaskClient,
 }
 
@@ -40,6 +42,7 @@ impl MixnetResponseListener {
     pub(crate) fn new(
         buffer_requester: ReceivedBufferRequestSender,
         controller_sender: ControllerSender,
+        udp_associations: UdpAssociations,
         shutdown: TaskClient,
     ) -> Self {
         let (mix_response_sender, mix_response_receiver) = mpsc::unbounded();
@@ -51,6 +54,7 @@ impl MixnetResponseListener {
             buffer_requester,
             mix_response_receiver,
             controller_sender,
+            udp_associations,
             shutdown,
         }
     }
@@ -85,6 +89,10 @@ impl MixnetResponseListener {
                     .unwrap();
                 Ok(())
             }
+            Socks5ResponseContent::Datagram(response) => {
+                self.udp_associations.forward(response);
+                Ok(())
+            }
         }
     }
 
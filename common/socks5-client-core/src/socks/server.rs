@@ -4,6 +4,7 @@ use super::{
     authentication::Authenticator, client::SocksClient, mixnet_responses::MixnetResponseListener,
 };
 use crate::socks::client;
+use crate::socks::udp::UdpAssociations;
 use log::*;
 use nym_client_core::client::{
     inbound_messages::InputMessageSender, received_buffer::ReceivedBufferRequestSender,
@@ -76,10 +77,14 @@ impl SphinxSocksServer {
             active_streams_controller.run().await;
         });
 
+        // all udp associations currently relayed by the clients
+        let udp_associations = UdpAssociations::default();
+
         // listener for mix messages
         let mut mixnet_response_listener = MixnetResponseListener::new(
             buffer_requester,
             controller_sender.clone(),
+            udp_associations.clone(),
             self.shutdown.clone(),
         );
         tokio::spawn(async move {
@@ -101,6 +106,7 @@ impl SphinxSocksServer {
                         input_sender.clone(),
                         &self.service_provider,
                         controller_sender.clone(),
+                        udp_associations.clone(),
                         &self.self_address,
                         self.lane_queue_lengths.clone(),
                         self.shutdown.clone(),
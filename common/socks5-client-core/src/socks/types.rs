@@ -57,6 +57,12 @@ pub enum SocksProxyError {
         source: std::io::Error,
     },
 
+    #[error("failed to bind the udp relay socket: {source}")]
+    UdpRelayBindFailure {
+        #[source]
+        source: std::io::Error,
+    },
+
     #[error("failed to extract ip address of the connected peer: {source}")]
     PeerAddrExtractionFailure {
         #[source]
@@ -0,0 +1,170 @@
+#![forbid(unsafe_code)]
+
+use super::types::AddrType;
+use super::utils as socks_utils;
+use super::RESERVED;
+use futures::channel::mpsc;
+use log::*;
+use nym_socks5_requests::{ConnectionId, Datagram, RemoteAddress};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+/// Channel responsible for forwarding datagrams received from the mix network
+/// to the relay of the particular udp association.
+pub(crate) type DatagramSender = mpsc::UnboundedSender<Datagram>;
+
+/// All udp associations that are currently being relayed by this client.
+#[derive(Clone, Default)]
+pub(crate) struct UdpAssociations {
+    inner: Arc<Mutex<HashMap<ConnectionId, DatagramSender>>>,
+}
+
+impl UdpAssociations {
+    pub(crate) fn insert(&self, connection_id: ConnectionId, datagram_sender: DatagramSender) {
+        self.inner
+            .lock()
+            .unwrap()
+            .insert(connection_id, datagram_sender);
+    }
+
+    pub(crate) fn remove(&self, connection_id: ConnectionId) {
+        self.inner.lock().unwrap().remove(&connection_id);
+    }
+
+    /// Forwards the received datagram to the relevant relay. If the association no longer exists,
+    /// the datagram is silently dropped, as it would have been by any other udp hop.
+    pub(crate) fn forward(&self, datagram: Datagram) {
+        let guard = self.inner.lock().unwrap();
+        match guard.get(&datagram.connection_id) {
+            Some(datagram_sender) => {
+                if datagram_sender.unbounded_send(datagram).is_err() {
+                    debug!("the udp relay has already stopped");
+                }
+            }
+            None => debug!(
+                "received a datagram for association {} which no longer exists",
+                datagram.connection_id
+            ),
+        }
+    }
+}
+
+/// Parses a datagram received from the local application, that, as described in
+/// https://www.rfc-editor.org/rfc/rfc1928#section-7, looks like this:
+///
+/// +----+------+------+----------+----------+----------+
+/// |RSV | FRAG | ATYP | DST.ADDR | DST.PORT |   DATA   |
+/// +----+------+------+----------+----------+----------+
+/// | 2  |  1   |  1   | Variable |    2     | Variable |
+/// +----+------+------+----------+----------+----------+
+///
+/// Fragmentation is not supported, so any fragmented datagrams are rejected.
+pub(crate) fn parse_udp_request(datagram: &[u8]) -> Option<(RemoteAddress, &[u8])> {
+    if datagram.len() < 4 {
+        return None;
+    }
+
+    if datagram[2] != 0 {
+        debug!("dropping fragmented datagram");
+        return None;
+    }
+
+    let addr_type = AddrType::from(datagram[3] as usize)?;
+    let (addr, rest) = match addr_type {
+        AddrType::V4 => split_checked(&datagram[4..], 4)?,
+        AddrType::V6 => split_checked(&datagram[4..], 16)?,
+        AddrType::Domain => {
+            let domain_length = *datagram.get(4)? as usize;
+            split_checked(&datagram[5..], domain_length)?
+        }
+    };
+    let (port, data) = split_checked(rest, 2)?;
+    let port = u16::from_be_bytes([port[0], port[1]]);
+
+    let address = socks_utils::pretty_print_addr(&addr_type, addr);
+    Some((format!("{address}:{port}"), data))
+}
+
+/// Wraps the datagram received from the mix network with the udp request header, so that it
+/// could be returned to the local application.
+pub(crate) fn encode_udp_response(datagram: Datagram) -> Option<Vec<u8>> {
+    let source: SocketAddr = match datagram.remote_addr.parse() {
+        Ok(source) => source,
+        Err(err) => {
+            warn!(
+                "received datagram from an invalid address {}: {err}",
+                datagram.remote_addr
+            );
+            return None;
+        }
+    };
+
+    let mut header = vec![RESERVED, RESERVED, 0];
+    match source {
+        SocketAddr::V4(addr) => {
+            header.push(AddrType::V4 as u8);
+            header.extend_from_slice(&addr.ip().octets());
+        }
+        SocketAddr::V6(addr) => {
+            header.push(AddrType::V6 as u8);
+            header.extend_from_slice(&addr.ip().octets());
+        }
+    }
+    header.extend_from_slice(&source.port().to_be_bytes());
+    header.extend(datagram.data);
+
+    Some(header)
+}
+
+fn split_checked(data: &[u8], at: usize) -> Option<(&[u8], &[u8])> {
+    if data.len() < at {
+        None
+    } else {
+        Some(data.split_at(at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_ipv4_request() {
+        let datagram = [0, 0, 0, 1, 1, 1, 1, 1, 0, 53, 42, 42];
+        let (address, data) = parse_udp_request(&datagram).unwrap();
+        assert_eq!("1.1.1.1:53", address);
+        assert_eq!(&[42, 42], data);
+    }
+
+    #[test]
+    fn parsing_domain_request() {
+        let datagram = [0, 0, 0, 3, 7, 102, 111, 111, 46, 99, 111, 109, 1, 187, 42];
+        let (address, data) = parse_udp_request(&datagram).unwrap();
+        assert_eq!("foo.com:443", address);
+        assert_eq!(&[42], data);
+    }
+
+    #[test]
+    fn parsing_rejects_fragmented_and_truncated_requests() {
+        assert!(parse_udp_request(&[0, 0, 1, 1, 1, 1, 1, 1, 0, 53]).is_none());
+        assert!(parse_udp_request(&[0, 0, 0, 1, 1, 1, 1]).is_none());
+        assert!(parse_udp_request(&[0, 0, 0, 3, 7, 102, 111]).is_none());
+        assert!(parse_udp_request(&[0, 0, 0, 1, 1, 1, 1, 1, 0]).is_none());
+    }
+
+    #[test]
+    fn encoded_response_can_be_parsed_back() {
+        let datagram = Datagram::new(42, "1.2.3.4:53".to_string(), vec![1, 2, 3]);
+        let encoded = encode_udp_response(datagram).unwrap();
+        let (address, data) = parse_udp_request(&encoded).unwrap();
+        assert_eq!("1.2.3.4:53", address);
+        assert_eq!(&[1, 2, 3], data);
+    }
+
+    #[test]
+    fn encoding_rejects_invalid_source_address() {
+        let datagram = Datagram::new(42, "foo.com:53".to_string(), vec![1, 2, 3]);
+        assert!(encode_udp_response(datagram).is_none());
+    }
+}
@@ -15,6 +15,7 @@ pub type RemoteAddress = String;
 pub enum RequestFlag {
     Connect = 0,
     Send = 1,
+    SendDatagram = 2,
 }
 
 impl TryFrom<u8> for RequestFlag {
@@ -24,6 +25,7 @@ impl TryFrom<u8> for RequestFlag {
         match value {
             _ if value == (RequestFlag::Connect as u8) => Ok(Self::Connect),
             _ if value == (RequestFlag::Send as u8) => Ok(Self::Send),
+            _ if value == (RequestFlag::SendDatagram as u8) => Ok(Self::SendDatagram),
             value => Err(RequestDeserializationError::UnknownRequestFlag { value }),
         }
     }
@@ -51,6 +53,9 @@ pub enum RequestDeserializationError {
 
     #[error("malformed return address - {0}")]
     MalformedReturnAddress(RecipientFormattingError),
+
+    #[error("not enough bytes to recover the return address flag")]
+    ReturnAddressFlagTooShort,
 }
 
 impl RequestDeserializationError {
@@ -74,6 +79,15 @@ pub struct SendRequest {
     pub local_closed: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct SendDatagramRequest {
+    /// Identifier of the UDP association this datagram belongs to.
+    pub conn_id: ConnectionId,
+    pub remote_addr: RemoteAddress,
+    pub data: Vec<u8>,
+    pub return_address: Option<Recipient>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Socks5Request {
     pub protocol_version: Socks5ProtocolVersion,
@@ -166,6 +180,24 @@ impl Socks5Request {
             content: Socks5RequestContent::new_send(conn_id, data, local_closed),
         }
     }
+
+    pub fn new_send_datagram(
+        protocol_version: Socks5ProtocolVersion,
+        conn_id: ConnectionId,
+        remote_addr: RemoteAddress,
+        data: Vec<u8>,
+        return_address: Option<Recipient>,
+    ) -> Socks5Request {
+        Socks5Request {
+            protocol_version,
+            content: Socks5RequestContent::new_send_datagram(
+                conn_id,
+                remote_addr,
+                data,
+                return_address,
+            ),
+        }
+    }
 }
 
 /// A request from a SOCKS5 client that a Nym Socks5 service provider should
@@ -179,6 +211,11 @@ pub enum Socks5RequestContent {
 
     /// Re-use an existing TCP connection, sending more request data up it.
     Send(SendRequest),
+
+    /// Send a single UDP datagram to the specified `RemoteAddress` as part of the UDP association
+    /// identified by the `ConnectionId`. Any datagrams received back from that address
+    /// should be sent to the specified `Recipient`.
+    SendDatagram(Box<SendDatagramRequest>),
 }
 
 impl Socks5RequestContent {
@@ -208,6 +245,21 @@ impl Socks5RequestContent {
         })
     }
 
+    /// Construct a new Request::SendDatagram instance
+    pub fn new_send_datagram(
+        conn_id: ConnectionId,
+        remote_addr: RemoteAddress,
+        data: Vec<u8>,
+        return_address: Option<Recipient>,
+    ) -> Socks5RequestContent {
+        Socks5RequestContent::SendDatagram(Box::new(SendDatagramRequest {
+            conn_id,
+            remote_addr,
+            data,
+            return_address,
+        }))
+    }
+
     /// Deserialize the request type, connection id, destination address and port,
     /// and the request body from bytes.
     ///
@@ -288,6 +340,58 @@ impl Socks5RequestContent {
                     local_closed,
                 }))
             }
+            RequestFlag::SendDatagram => {
+                let datagram_request_bytes = &b[9..];
+
+                if datagram_request_bytes.len() < 2 {
+                    return Err(RequestDeserializationError::AddressLengthTooShort);
+                }
+
+                let address_length =
+                    u16::from_be_bytes([datagram_request_bytes[0], datagram_request_bytes[1]])
+                        as usize;
+
+                if datagram_request_bytes.len() < 2 + address_length {
+                    return Err(RequestDeserializationError::AddressTooShort);
+                }
+
+                let address_start = 2;
+                let address_end = address_start + address_length;
+                let address_bytes = &datagram_request_bytes[address_start..address_end];
+                let remote_address = String::from_utf8_lossy(address_bytes).to_string();
+
+                // unlike the connect request, the datagram is followed by the actual data,
+                // so we need an explicit flag to tell whether the return address is present
+                let Some(&has_return) = datagram_request_bytes.get(address_end) else {
+                    return Err(RequestDeserializationError::ReturnAddressFlagTooShort);
+                };
+                let mut data_start = address_end + 1;
+
+                let return_address = if has_return == 0 {
+                    None
+                } else {
+                    if datagram_request_bytes.len() < data_start + Recipient::LEN {
+                        return Err(RequestDeserializationError::ReturnAddressTooShort);
+                    }
+
+                    let mut return_bytes = [0u8; Recipient::LEN];
+                    return_bytes.copy_from_slice(
+                        &datagram_request_bytes[data_start..data_start + Recipient::LEN],
+                    );
+                    data_start += Recipient::LEN;
+                    Some(
+                        Recipient::try_from_bytes(return_bytes)
+                            .map_err(RequestDeserializationError::MalformedReturnAddress)?,
+                    )
+                };
+
+                Ok(Socks5RequestContent::new_send_datagram(
+                    conn_id,
+                    remote_address,
+                    datagram_request_bytes[data_start..].to_vec(),
+                    return_address,
+                ))
+            }
         }
     }
 
@@ -317,6 +421,27 @@ impl Socks5RequestContent {
                 .chain(std::iter::once(req.local_closed as u8))
                 .chain(req.data.into_iter())
                 .collect(),
+            // datagram is: DATAGRAM_FLAG || CONN_ID || REMOTE_LEN || REMOTE || HAS_RETURN || [RETURN] || DATA
+            Socks5RequestContent::SendDatagram(req) => {
+                let remote_address_bytes = req.remote_addr.into_bytes();
+                let remote_address_bytes_len = remote_address_bytes.len() as u16;
+
+                let iter = std::iter::once(RequestFlag::SendDatagram as u8)
+                    .chain(req.conn_id.to_be_bytes().into_iter())
+                    .chain(remote_address_bytes_len.to_be_bytes().into_iter())
+                    .chain(remote_address_bytes.into_iter());
+
+                if let Some(return_address) = req.return_address {
+                    iter.chain(std::iter::once(1))
+                        .chain(return_address.to_bytes().into_iter())
+                        .chain(req.data.into_iter())
+                        .collect()
+                } else {
+                    iter.chain(std::iter::once(0))
+                        .chain(req.data.into_iter())
+                        .collect()
+                }
+            }
         }
     }
 }
@@ -611,4 +736,84 @@ mod request_deserialization_tests {
             }
         }
     }
+
+    #[cfg(test)]
+    mod sending_datagrams {
+        use super::*;
+
+        #[test]
+        fn returns_error_when_return_address_flag_is_missing() {
+            // correct 8 bytes of connection_id and "foo.com" remote address, but no return flag
+            let request_bytes = [
+                RequestFlag::SendDatagram as u8,
+                1,
+                2,
+                3,
+                4,
+                5,
+                6,
+                7,
+                8,
+                0,
+                7,
+                102,
+                111,
+                111,
+                46,
+                99,
+                111,
+                109,
+            ]
+            .to_vec();
+
+            match Socks5RequestContent::try_from_bytes(&request_bytes).unwrap_err() {
+                RequestDeserializationError::ReturnAddressFlagTooShort => {}
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn works_without_return_address() {
+            let request = Socks5RequestContent::new_send_datagram(
+                42,
+                "foo.com:53".to_string(),
+                vec![1, 2, 3],
+                None,
+            );
+            let deserialized = Socks5RequestContent::try_from_bytes(&request.into_bytes()).unwrap();
+            match deserialized {
+                Socks5RequestContent::SendDatagram(req) => {
+                    assert_eq!(42, req.conn_id);
+                    assert_eq!("foo.com:53".to_string(), req.remote_addr);
+                    assert_eq!(vec![1, 2, 3], req.data);
+                    assert!(req.return_address.is_none());
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn works_with_return_address() {
+            let recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+            let request = Socks5RequestContent::new_send_datagram(
+                42,
+                "foo.com:53".to_string(),
+                vec![255, 255, 255],
+                Some(recipient),
+            );
+            let deserialized = Socks5RequestContent::try_from_bytes(&request.into_bytes()).unwrap();
+            match deserialized {
+                Socks5RequestContent::SendDatagram(req) => {
+                    assert_eq!(42, req.conn_id);
+                    assert_eq!("foo.com:53".to_string(), req.remote_addr);
+                    assert_eq!(vec![255, 255, 255], req.data);
+                    assert_eq!(
+                        req.return_address.unwrap().to_bytes().to_vec(),
+                        recipient.to_bytes().to_vec()
+                    );
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
 }
@@ -1,7 +1,7 @@
 // Copyright 2020-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{ConnectionId, Socks5ProtocolVersion, Socks5RequestError};
+use crate::{ConnectionId, RemoteAddress, Socks5ProtocolVersion, Socks5RequestError};
 use nym_service_providers_common::interface::{Serializable, ServiceProviderResponse};
 use thiserror::Error;
 
@@ -13,6 +13,7 @@ use thiserror::Error;
 pub enum ResponseFlag {
     NetworkData = 1,
     ConnectionError = 2,
+    Datagram = 3,
 }
 
 impl TryFrom<u8> for ResponseFlag {
@@ -22,6 +23,7 @@ impl TryFrom<u8> for ResponseFlag {
         match value {
             _ if value == (ResponseFlag::NetworkData as u8) => Ok(Self::NetworkData),
             _ if value == (ResponseFlag::ConnectionError as u8) => Ok(Self::ConnectionError),
+            _ if value == (ResponseFlag::Datagram as u8) => Ok(Self::Datagram),
             value => Err(ResponseDeserializationError::UnknownResponseFlag { value }),
         }
     }
@@ -32,6 +34,12 @@ pub enum ResponseDeserializationError {
     #[error("not enough bytes to recover the connection id")]
     ConnectionIdTooShort,
 
+    #[error("not enough bytes to recover the length of the address")]
+    AddressLengthTooShort,
+
+    #[error("not enough bytes to recover the address")]
+    AddressTooShort,
+
     #[error("{value} is not a valid response flag")]
     UnknownResponseFlag { value: u8 },
 
@@ -135,12 +143,25 @@ impl Socks5Response {
             content: Socks5ResponseContent::new_connection_error(connection_id, error_message),
         }
     }
+
+    pub fn new_datagram(
+        protocol_version: Socks5ProtocolVersion,
+        connection_id: ConnectionId,
+        remote_addr: RemoteAddress,
+        data: Vec<u8>,
+    ) -> Socks5Response {
+        Socks5Response {
+            protocol_version,
+            content: Socks5ResponseContent::new_datagram(connection_id, remote_addr, data),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum Socks5ResponseContent {
     NetworkData(NetworkData),
     ConnectionError(ConnectionError),
+    Datagram(Datagram),
 }
 
 impl Socks5ResponseContent {
@@ -163,6 +184,14 @@ impl Socks5ResponseContent {
         Socks5ResponseContent::ConnectionError(ConnectionError::new(connection_id, error_message))
     }
 
+    pub fn new_datagram(
+        connection_id: ConnectionId,
+        remote_addr: RemoteAddress,
+        data: Vec<u8>,
+    ) -> Socks5ResponseContent {
+        Socks5ResponseContent::Datagram(Datagram::new(connection_id, remote_addr, data))
+    }
+
     pub fn into_bytes(self) -> Vec<u8> {
         match self {
             Socks5ResponseContent::NetworkData(res) => {
@@ -175,6 +204,9 @@ impl Socks5ResponseContent {
                     .chain(res.into_bytes().into_iter())
                     .collect()
             }
+            Socks5ResponseContent::Datagram(res) => std::iter::once(ResponseFlag::Datagram as u8)
+                .chain(res.into_bytes().into_iter())
+                .collect(),
         }
     }
 
@@ -193,6 +225,9 @@ impl Socks5ResponseContent {
             ResponseFlag::ConnectionError => Ok(Socks5ResponseContent::ConnectionError(
                 ConnectionError::try_from_bytes(&b[1..])?,
             )),
+            ResponseFlag::Datagram => Ok(Socks5ResponseContent::Datagram(
+                Datagram::try_from_bytes(&b[1..])?,
+            )),
         }
     }
 }
@@ -318,6 +353,67 @@ impl ConnectionError {
     }
 }
 
+/// A UDP datagram received by the Socks5 service provider from the remote address
+/// as part of an existing UDP association.
+#[derive(Debug)]
+pub struct Datagram {
+    pub connection_id: ConnectionId,
+    pub remote_addr: RemoteAddress,
+    pub data: Vec<u8>,
+}
+
+impl Datagram {
+    pub fn new(connection_id: ConnectionId, remote_addr: RemoteAddress, data: Vec<u8>) -> Self {
+        Datagram {
+            connection_id,
+            remote_addr,
+            data,
+        }
+    }
+
+    pub fn try_from_bytes(b: &[u8]) -> Result<Datagram, ResponseDeserializationError> {
+        if b.is_empty() {
+            return Err(ResponseDeserializationError::NoData);
+        }
+
+        if b.len() < 8 {
+            return Err(ResponseDeserializationError::ConnectionIdTooShort);
+        }
+        let connection_id = u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]);
+
+        if b.len() < 10 {
+            return Err(ResponseDeserializationError::AddressLengthTooShort);
+        }
+        let address_length = u16::from_be_bytes([b[8], b[9]]) as usize;
+
+        let address_end = 10 + address_length;
+        if b.len() < address_end {
+            return Err(ResponseDeserializationError::AddressTooShort);
+        }
+        let remote_addr = String::from_utf8_lossy(&b[10..address_end]).to_string();
+
+        Ok(Datagram {
+            connection_id,
+            remote_addr,
+            data: b[address_end..].to_vec(),
+        })
+    }
+
+    // datagram is: CONN_ID || REMOTE_LEN || REMOTE || DATA
+    pub fn into_bytes(self) -> Vec<u8> {
+        let remote_address_bytes = self.remote_addr.into_bytes();
+        let remote_address_bytes_len = remote_address_bytes.len() as u16;
+
+        self.connection_id
+            .to_be_bytes()
+            .into_iter()
+            .chain(remote_address_bytes_len.to_be_bytes().into_iter())
+            .chain(remote_address_bytes.into_iter())
+            .chain(self.data.into_iter())
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -414,4 +510,45 @@ mod tests {
             ));
         }
     }
+
+    #[cfg(test)]
+    mod datagram_response_serde_tests {
+        use super::*;
+
+        #[test]
+        fn simple_serde() {
+            let response = Socks5ResponseContent::new_datagram(
+                42,
+                "1.1.1.1:53".to_string(),
+                vec![255, 255, 255],
+            );
+            let bytes = response.into_bytes();
+            match Socks5ResponseContent::try_from_bytes(&bytes).unwrap() {
+                Socks5ResponseContent::Datagram(datagram) => {
+                    assert_eq!(42, datagram.connection_id);
+                    assert_eq!("1.1.1.1:53".to_string(), datagram.remote_addr);
+                    assert_eq!(vec![255, 255, 255], datagram.data);
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn deserialization_errors() {
+            let err = Datagram::try_from_bytes(&[]).err().unwrap();
+            assert_eq!(err, ResponseDeserializationError::NoData);
+
+            let bytes: [u8; 5] = [1, 2, 3, 4, 5];
+            let err = Datagram::try_from_bytes(&bytes).err().unwrap();
+            assert_eq!(err, ResponseDeserializationError::ConnectionIdTooShort);
+
+            let bytes: [u8; 9] = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+            let err = Datagram::try_from_bytes(&bytes).err().unwrap();
+            assert_eq!(err, ResponseDeserializationError::AddressLengthTooShort);
+
+            let bytes: [u8; 12] = [1, 2, 3, 4, 5, 6, 7, 8, 0, 7, 102, 111];
+            let err = Datagram::try_from_bytes(&bytes).err().unwrap();
+            assert_eq!(err, ResponseDeserializationError::AddressTooShort);
+        }
+    }
 }
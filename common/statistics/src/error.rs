@@ -10,4 +10,7 @@ pub enum StatsError {
 
     #[error("Reqwest error: {0}")]
     ReqwestError(#[from] reqwest::Error),
+
+    #[error("Invalid privacy budget: {0}. It has to be a positive number")]
+    InvalidPrivacyBudget(f64),
 }
@@ -8,6 +8,7 @@ use error::StatsError;
 pub mod api;
 pub mod collector;
 pub mod error;
+pub mod privacy;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct StatsMessage {
@@ -36,6 +37,16 @@ pub enum StatsData {
 pub struct StatsGatewayData {
     pub gateway_id: String,
     pub inbox_count: u32,
+    #[serde(default)]
+    pub stored_messages: u64,
+    #[serde(default)]
+    pub stored_bytes: u64,
+    /// Number of messages evicted within the reporting interval due to their clients exceeding their quotas.
+    #[serde(default)]
+    pub evicted_messages: u64,
+    /// Number of messages removed within the reporting interval due to exceeding their time to live.
+    #[serde(default)]
+    pub expired_messages: u64,
 }
 
 impl StatsGatewayData {
@@ -43,8 +54,27 @@ impl StatsGatewayData {
         StatsGatewayData {
             gateway_id,
             inbox_count,
+            stored_messages: 0,
+            stored_bytes: 0,
+            evicted_messages: 0,
+            expired_messages: 0,
         }
     }
+
+    #[must_use]
+    pub fn with_stored_messages(
+        mut self,
+        stored_messages: u64,
+        stored_bytes: u64,
+        evicted_messages: u64,
+        expired_messages: u64,
+    ) -> Self {
+        self.stored_messages = stored_messages;
+        self.stored_bytes = stored_bytes;
+        self.evicted_messages = evicted_messages;
+        self.expired_messages = expired_messages;
+        self
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -0,0 +1,287 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use rand::rngs::OsRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::error::StatsError;
+
+pub const DEFAULT_EPSILON_PER_INTERVAL: f64 = 1.0;
+pub const DEFAULT_SMALL_COUNT_THRESHOLD: u64 = 5;
+pub const DEFAULT_ROUNDING_GRANULARITY: u64 = 5;
+
+/// Name under which all the values that are too small to be reported on their own get aggregated.
+pub const SMALL_COUNTS_BUCKET: &str = "other";
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrivacyConfig {
+    /// Specifies whether the statistics should be made differentially private before being sent.
+    pub enabled: bool,
+
+    /// Privacy budget (epsilon) spent on all the values reported within a single interval.
+    /// It's split equally between all of them, so the more values are reported,
+    /// the more noise gets added to each. Lower values result in stronger privacy guarantees.
+    pub epsilon_per_interval: f64,
+
+    /// Values which, after adding the noise, are lower than this threshold are not reported individually.
+    /// It's expressed in the units of sensitivity of the particular value.
+    pub small_count_threshold: u64,
+
+    /// Granularity to which the reported values are rounded.
+    /// It's expressed in the units of sensitivity of the particular value.
+    pub rounding_granularity: u64,
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        PrivacyConfig {
+            enabled: false,
+            epsilon_per_interval: DEFAULT_EPSILON_PER_INTERVAL,
+            small_count_threshold: DEFAULT_SMALL_COUNT_THRESHOLD,
+            rounding_granularity: DEFAULT_ROUNDING_GRANULARITY,
+        }
+    }
+}
+
+/// Value about to be reported alongside its sensitivity, i.e. the maximum amount by which
+/// a single user could have influenced it within the reporting interval.
+#[derive(Clone, Copy, Debug)]
+pub struct SensitiveValue {
+    pub value: u64,
+    pub sensitivity: u64,
+}
+
+impl SensitiveValue {
+    pub fn new(value: u64, sensitivity: u64) -> Self {
+        SensitiveValue {
+            value,
+            sensitivity: sensitivity.max(1),
+        }
+    }
+}
+
+/// Applies the laplace mechanism to the statistics collected within a single reporting interval.
+#[derive(Clone, Copy, Debug)]
+pub struct PrivacyFilter {
+    config: PrivacyConfig,
+}
+
+impl PrivacyFilter {
+    /// Creates the filter out of the provided config, unless the privacy layer is disabled.
+    pub fn from_config(config: PrivacyConfig) -> Result<Option<Self>, StatsError> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        if !config.epsilon_per_interval.is_finite() || config.epsilon_per_interval <= 0.0 {
+            return Err(StatsError::InvalidPrivacyBudget(
+                config.epsilon_per_interval,
+            ));
+        }
+
+        Ok(Some(PrivacyFilter { config }))
+    }
+
+    fn sample_laplace(&self, scale: f64) -> f64 {
+        // inverse transform sampling: u is uniform over (-0.5, 0.5)
+        let u = OsRng.gen::<f64>() - 0.5;
+        let tail = (1.0 - 2.0 * u.abs()).max(f64::MIN_POSITIVE);
+        -scale * u.signum() * tail.ln()
+    }
+
+    /// Adds noise to all the values that are going to be reported within a single interval.
+    /// The whole privacy budget of the interval is spent in the process.
+    pub fn add_noise(&self, values: &[SensitiveValue]) -> Vec<f64> {
+        if values.is_empty() {
+            return Vec::new();
+        }
+
+        let epsilon = self.config.epsilon_per_interval / values.len() as f64;
+        values
+            .iter()
+            .map(|value| {
+                let scale = value.sensitivity as f64 / epsilon;
+                value.value as f64 + self.sample_laplace(scale)
+            })
+            .collect()
+    }
+
+    /// Rounds the noisy value to the configured granularity. Returns `None` if it's too small
+    /// to be reported on its own. Note that it's pure post-processing, so it doesn't use any budget.
+    pub fn postprocess(&self, noisy_value: f64, sensitivity: u64) -> Option<u64> {
+        let sensitivity = sensitivity.max(1) as f64;
+        let units = noisy_value / sensitivity;
+        if units < self.config.small_count_threshold as f64 || units <= 0.0 {
+            return None;
+        }
+
+        let granularity = self.config.rounding_granularity.max(1) as f64;
+        let rounded = (units / granularity).round() * granularity * sensitivity;
+        Some(rounded as u64)
+    }
+
+    /// Adds noise to all the provided values and post-processes them,
+    /// with the values that are too small being reported as zero.
+    pub fn privatize(&self, values: &[SensitiveValue]) -> Vec<u64> {
+        self.add_noise(values)
+            .into_iter()
+            .zip(values)
+            .map(|(noisy_value, value)| {
+                self.postprocess(noisy_value, value.sensitivity)
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(config: PrivacyConfig) -> PrivacyFilter {
+        PrivacyFilter::from_config(PrivacyConfig {
+            enabled: true,
+            ..config
+        })
+        .unwrap()
+        .unwrap()
+    }
+
+    fn filter_with_budget(epsilon_per_interval: f64) -> PrivacyFilter {
+        filter(PrivacyConfig {
+            epsilon_per_interval,
+            ..Default::default()
+        })
+    }
+
+    // the mean absolute deviation of the laplace distribution is equal to its scale
+    fn mean_absolute_noise(filter: &PrivacyFilter, values: &[SensitiveValue]) -> Vec<f64> {
+        let samples = 20000;
+        let mut total = vec![0.; values.len()];
+        for _ in 0..samples {
+            for (i, noisy) in filter.add_noise(values).into_iter().enumerate() {
+                total[i] += (noisy - values[i].value as f64).abs();
+            }
+        }
+        total
+            .into_iter()
+            .map(|total| total / samples as f64)
+            .collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < expected * 0.1,
+            "{actual} is not close to {expected}"
+        )
+    }
+
+    #[test]
+    fn filter_is_not_created_if_disabled() {
+        assert!(PrivacyFilter::from_config(PrivacyConfig::default())
+            .unwrap()
+            .is_none())
+    }
+
+    #[test]
+    fn invalid_budget_is_rejected() {
+        for epsilon_per_interval in [0., -1., f64::NAN, f64::INFINITY] {
+            let config = PrivacyConfig {
+                enabled: true,
+                epsilon_per_interval,
+                ..Default::default()
+            };
+            assert!(PrivacyFilter::from_config(config).is_err())
+        }
+    }
+
+    #[test]
+    fn sensitivity_is_at_least_one() {
+        assert_eq!(SensitiveValue::new(42, 0).sensitivity, 1);
+        assert_eq!(SensitiveValue::new(42, 10).sensitivity, 10);
+    }
+
+    #[test]
+    fn noise_scale_is_inversely_proportional_to_budget() {
+        let value = SensitiveValue::new(1000, 10);
+        for epsilon in [0.5, 1., 2.] {
+            let noise = mean_absolute_noise(&filter_with_budget(epsilon), &[value]);
+            assert_close(noise[0], 10. / epsilon)
+        }
+    }
+
+    #[test]
+    fn noise_scale_is_proportional_to_sensitivity() {
+        let values = [SensitiveValue::new(1000, 1), SensitiveValue::new(1000, 100)];
+        let noise = mean_absolute_noise(&filter_with_budget(2.), &values);
+
+        // the budget is split between both values
+        assert_close(noise[0], 1.);
+        assert_close(noise[1], 100.);
+    }
+
+    #[test]
+    fn budget_is_split_between_all_values() {
+        let values = [SensitiveValue::new(0, 1); 4];
+        let noise = mean_absolute_noise(&filter_with_budget(1.), &values);
+        for noise in noise {
+            assert_close(noise, 4.)
+        }
+    }
+
+    #[test]
+    fn small_values_are_not_reported() {
+        let filter = filter(PrivacyConfig {
+            small_count_threshold: 5,
+            rounding_granularity: 1,
+            ..Default::default()
+        });
+
+        assert_eq!(filter.postprocess(-3., 1), None);
+        assert_eq!(filter.postprocess(0., 1), None);
+        assert_eq!(filter.postprocess(4.9, 1), None);
+        assert_eq!(filter.postprocess(5., 1), Some(5));
+
+        // the threshold is expressed in the units of sensitivity
+        assert_eq!(filter.postprocess(499., 100), None);
+        assert_eq!(filter.postprocess(500., 100), Some(500));
+    }
+
+    #[test]
+    fn values_are_rounded_to_granularity() {
+        let filter = filter(PrivacyConfig {
+            small_count_threshold: 5,
+            rounding_granularity: 5,
+            ..Default::default()
+        });
+
+        assert_eq!(filter.postprocess(7.4, 1), Some(5));
+        assert_eq!(filter.postprocess(7.6, 1), Some(10));
+        assert_eq!(filter.postprocess(23., 1), Some(25));
+
+        // the granularity is expressed in the units of sensitivity
+        assert_eq!(filter.postprocess(740., 100), Some(500));
+        assert_eq!(filter.postprocess(760., 100), Some(1000));
+    }
+
+    #[test]
+    fn zero_granularity_rounds_to_integers() {
+        let filter = filter(PrivacyConfig {
+            small_count_threshold: 1,
+            rounding_granularity: 0,
+            ..Default::default()
+        });
+
+        assert_eq!(filter.postprocess(3.4, 1), Some(3));
+        assert_eq!(filter.postprocess(3.6, 1), Some(4));
+    }
+
+    #[test]
+    fn privatized_small_values_are_reported_as_zero() {
+        // with such a big budget the noise is negligible
+        let filter = filter_with_budget(1e9);
+        let values = [SensitiveValue::new(3, 1), SensitiveValue::new(1000, 1)];
+        assert_eq!(filter.privatize(&values), vec![0, 1000]);
+    }
+}
@@ -1,9 +1,9 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{filter, NetworkAddress};
+use crate::{filter, select_sphinx_key, AnnouncedSphinxKeys, NetworkAddress, SphinxKeyValidity};
 use nym_crypto::asymmetric::{encryption, identity};
-use nym_mixnet_contract_common::GatewayBond;
+use nym_mixnet_contract_common::{EpochId, GatewayBond};
 use nym_sphinx_addressing::nodes::{NodeIdentity, NymNodeRoutingAddress};
 use nym_sphinx_types::Node as SphinxNode;
 use std::convert::{TryFrom, TryInto};
@@ -38,6 +38,14 @@ pub struct Node {
     pub clients_port: u16,
     pub identity_key: identity::PublicKey,
     pub sphinx_key: encryption::PublicKey, // TODO: or nymsphinx::PublicKey? both are x25519
+
+    /// Epochs during which the gateway is going to be using the `sphinx_key`.
+    /// It is not set if the gateway still uses the key it has been bonded with.
+    pub sphinx_key_validity: Option<SphinxKeyValidity>,
+
+    /// Current and next sphinx keys announced by the gateway, if it has rotated away from its bonded key.
+    /// See [`crate::NymTopology::with_sphinx_keys_for_epoch`] for choosing the key meant for particular epoch.
+    pub announced_sphinx_keys: Option<AnnouncedSphinxKeys>,
     pub version: String,
 }
 
@@ -62,6 +70,19 @@ impl Node {
         })?[0])
     }
 
+    /// Returns the gateway using the sphinx key meant for the specified epoch
+    /// or `None` if it does not have any key valid in that epoch.
+    pub fn with_sphinx_key_for_epoch(&self, epoch_id: EpochId) -> Option<Self> {
+        let mut gateway = self.clone();
+        select_sphinx_key(
+            &mut gateway.sphinx_key,
+            &mut gateway.sphinx_key_validity,
+            self.announced_sphinx_keys.as_ref(),
+            epoch_id,
+        )
+        .then_some(gateway)
+    }
+
     pub fn identity(&self) -> &NodeIdentity {
         &self.identity_key
     }
@@ -114,6 +135,8 @@ impl<'a> TryFrom<&'a GatewayBond> for Node {
             clients_port: bond.gateway.clients_port,
             identity_key: identity::PublicKey::from_base58_string(&bond.gateway.identity_key)?,
             sphinx_key: encryption::PublicKey::from_base58_string(&bond.gateway.sphinx_key)?,
+            sphinx_key_validity: None,
+            announced_sphinx_keys: None,
             version: bond.gateway.version.clone(),
         })
     }
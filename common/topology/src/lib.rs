@@ -3,8 +3,9 @@
 
 use crate::filter::VersionFilterable;
 use log::warn;
+use nym_crypto::asymmetric::encryption;
 use nym_mixnet_contract_common::mixnode::MixNodeDetails;
-use nym_mixnet_contract_common::{GatewayBond, IdentityKeyRef, MixId};
+use nym_mixnet_contract_common::{EpochId, GatewayBond, IdentityKeyRef, MixId};
 use nym_sphinx_addressing::nodes::NodeIdentity;
 use nym_sphinx_types::Node as SphinxNode;
 use rand::{CryptoRng, Rng};
@@ -66,6 +67,66 @@ impl Display for NetworkAddress {
 
 pub type MixLayer = u8;
 
+/// Range of epochs during which a node is going to be using particular sphinx key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SphinxKeyValidity {
+    /// The first epoch in which the key is used.
+    pub valid_from: EpochId,
+
+    /// The first epoch in which the key is no longer used.
+    pub valid_until: EpochId,
+}
+
+impl SphinxKeyValidity {
+    pub fn is_valid_in(&self, epoch_id: EpochId) -> bool {
+        self.valid_from <= epoch_id && epoch_id < self.valid_until
+    }
+}
+
+/// Sphinx key announced by a node alongside the epochs during which it is going to be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnnouncedSphinxKey {
+    pub sphinx_key: encryption::PublicKey,
+    pub validity: SphinxKeyValidity,
+}
+
+/// Sphinx keys a node has announced in place of the one it has been bonded with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnouncedSphinxKeys {
+    /// The first epoch in which the node no longer uses the key it has been bonded with.
+    pub bonded_key_valid_until: EpochId,
+
+    /// The current and the next keys of the node.
+    pub keys: Vec<AnnouncedSphinxKey>,
+}
+
+/// Replaces the sphinx key with the one the node is using during the specified epoch.
+/// Returns `false` if the node does not have any key that is valid in that epoch.
+pub(crate) fn select_sphinx_key(
+    sphinx_key: &mut encryption::PublicKey,
+    sphinx_key_validity: &mut Option<SphinxKeyValidity>,
+    announced: Option<&AnnouncedSphinxKeys>,
+    epoch_id: EpochId,
+) -> bool {
+    if let Some(announced_key) = announced.and_then(|announced| {
+        announced
+            .keys
+            .iter()
+            .find(|key| key.validity.is_valid_in(epoch_id))
+    }) {
+        *sphinx_key = announced_key.sphinx_key;
+        *sphinx_key_validity = Some(announced_key.validity);
+        return true;
+    }
+
+    match (sphinx_key_validity, announced) {
+        (Some(validity), _) => validity.is_valid_in(epoch_id),
+        // nodes keep on using the keys they have been bonded with until their first announced key
+        (None, Some(announced)) => epoch_id < announced.bonded_key_valid_until,
+        (None, None) => true,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct NymTopology {
     mixes: BTreeMap<MixLayer, Vec<mix::Node>>,
@@ -308,6 +369,31 @@ impl NymTopology {
             gateways: self.gateways.clone(),
         }
     }
+
+    /// Makes all nodes use the sphinx keys meant for the specified epoch, so that the constructed
+    /// routes would only contain keys that the nodes are using at that time.
+    /// Nodes that do not have any key valid in that epoch are removed.
+    #[must_use]
+    pub fn with_sphinx_keys_for_epoch(&self, epoch_id: EpochId) -> Self {
+        NymTopology {
+            mixes: self
+                .mixes
+                .iter()
+                .map(|(layer, nodes)| {
+                    let valid = nodes
+                        .iter()
+                        .filter_map(|node| node.with_sphinx_key_for_epoch(epoch_id))
+                        .collect();
+                    (*layer, valid)
+                })
+                .collect(),
+            gateways: self
+                .gateways
+                .iter()
+                .filter_map(|node| node.with_sphinx_key_for_epoch(epoch_id))
+                .collect(),
+        }
+    }
 }
 
 pub fn nym_topology_from_detailed(
@@ -381,6 +467,8 @@ mod converting_mixes_to_vec {
                     "C7cown6dYCLZpLiMFC1PaBmhvLvmJmLDJGeRTbPD45bX",
                 )
                 .unwrap(),
+                sphinx_key_validity: None,
+                announced_sphinx_keys: None,
                 layer: Layer::One,
                 version: "0.x.0".to_string(),
             };
@@ -417,3 +505,114 @@ mod converting_mixes_to_vec {
         }
     }
 }
+
+#[cfg(test)]
+mod sphinx_key_validity {
+    use super::*;
+    use nym_crypto::asymmetric::{encryption, identity};
+    use nym_mixnet_contract_common::Layer;
+
+    fn mix(mix_id: MixId, sphinx_key_validity: Option<SphinxKeyValidity>) -> mix::Node {
+        mix::Node {
+            mix_id,
+            owner: "N/A".to_string(),
+            host: "3.3.3.3".parse().unwrap(),
+            mix_host: "3.3.3.3:1789".parse().unwrap(),
+            identity_key: identity::PublicKey::from_base58_string(
+                "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7",
+            )
+            .unwrap(),
+            sphinx_key: encryption::PublicKey::from_base58_string(
+                "C7cown6dYCLZpLiMFC1PaBmhvLvmJmLDJGeRTbPD45bX",
+            )
+            .unwrap(),
+            sphinx_key_validity,
+            announced_sphinx_keys: None,
+            layer: Layer::One,
+            version: "0.x.0".to_string(),
+        }
+    }
+
+    fn validity(valid_from: EpochId, valid_until: EpochId) -> Option<SphinxKeyValidity> {
+        Some(SphinxKeyValidity {
+            valid_from,
+            valid_until,
+        })
+    }
+
+    #[test]
+    fn validity_range_is_half_open() {
+        let validity = validity(10, 20).unwrap();
+        assert!(!validity.is_valid_in(9));
+        assert!(validity.is_valid_in(10));
+        assert!(validity.is_valid_in(19));
+        assert!(!validity.is_valid_in(20));
+    }
+
+    #[test]
+    fn nodes_with_keys_for_other_epochs_are_removed() {
+        let mut mixes = BTreeMap::new();
+        mixes.insert(
+            1,
+            vec![
+                mix(1, None),
+                mix(2, validity(10, 20)),
+                mix(3, validity(20, 30)),
+                mix(4, validity(5, 10)),
+            ],
+        );
+        let topology = NymTopology::new(mixes, vec![]).with_sphinx_keys_for_epoch(12);
+
+        let remaining = topology
+            .mixes_as_vec()
+            .into_iter()
+            .map(|node| node.mix_id)
+            .collect::<Vec<_>>();
+        assert_eq!(remaining, vec![1, 2]);
+    }
+
+    #[test]
+    fn announced_key_valid_in_the_epoch_is_used() {
+        let current = encryption::PublicKey::from_base58_string(
+            "BnLYqQjb8K6TmW5oFdNZrUTocGxa3rgzBvapQrf8XUbF",
+        )
+        .unwrap();
+        let next = encryption::PublicKey::from_base58_string(
+            "HSsnkkKmb3wg9yEiJJ9u1YNdRn2Bwuhv5Uu9dwpDFgRS",
+        )
+        .unwrap();
+
+        let mut rotated = mix(1, None);
+        let bonded = rotated.sphinx_key;
+        rotated.announced_sphinx_keys = Some(AnnouncedSphinxKeys {
+            bonded_key_valid_until: 10,
+            keys: vec![
+                AnnouncedSphinxKey {
+                    sphinx_key: current,
+                    validity: validity(10, 20).unwrap(),
+                },
+                AnnouncedSphinxKey {
+                    sphinx_key: next,
+                    validity: validity(20, 30).unwrap(),
+                },
+            ],
+        });
+
+        let mut mixes = BTreeMap::new();
+        mixes.insert(1, vec![rotated]);
+        let topology = NymTopology::new(mixes, vec![]);
+
+        let key_in_epoch = |epoch_id| {
+            topology
+                .with_sphinx_keys_for_epoch(epoch_id)
+                .mixes_as_vec()
+                .first()
+                .map(|node| node.sphinx_key)
+        };
+        assert_eq!(key_in_epoch(9), Some(bonded));
+        assert_eq!(key_in_epoch(10), Some(current));
+        assert_eq!(key_in_epoch(19), Some(current));
+        assert_eq!(key_in_epoch(20), Some(next));
+        assert_eq!(key_in_epoch(30), None);
+    }
+}
@@ -1,10 +1,10 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{filter, NetworkAddress};
+use crate::{filter, select_sphinx_key, AnnouncedSphinxKeys, NetworkAddress, SphinxKeyValidity};
 use nym_crypto::asymmetric::{encryption, identity};
 pub use nym_mixnet_contract_common::Layer;
-use nym_mixnet_contract_common::{MixId, MixNodeBond};
+use nym_mixnet_contract_common::{EpochId, MixId, MixNodeBond};
 use nym_sphinx_addressing::nodes::NymNodeRoutingAddress;
 use nym_sphinx_types::Node as SphinxNode;
 use std::convert::{TryFrom, TryInto};
@@ -38,6 +38,14 @@ pub struct Node {
     pub mix_host: SocketAddr,
     pub identity_key: identity::PublicKey,
     pub sphinx_key: encryption::PublicKey, // TODO: or nymsphinx::PublicKey? both are x25519
+
+    /// Epochs during which the node is going to be using the `sphinx_key`.
+    /// It is not set if the node still uses the key it has been bonded with.
+    pub sphinx_key_validity: Option<SphinxKeyValidity>,
+
+    /// Current and next sphinx keys announced by the node, if it has rotated away from its bonded key.
+    /// See [`crate::NymTopology::with_sphinx_keys_for_epoch`] for choosing the key meant for particular epoch.
+    pub announced_sphinx_keys: Option<AnnouncedSphinxKeys>,
     pub layer: Layer,
     pub version: String,
 }
@@ -62,6 +70,19 @@ impl Node {
             }
         })?[0])
     }
+
+    /// Returns the node using the sphinx key meant for the specified epoch
+    /// or `None` if it does not have any key valid in that epoch.
+    pub fn with_sphinx_key_for_epoch(&self, epoch_id: EpochId) -> Option<Self> {
+        let mut node = self.clone();
+        select_sphinx_key(
+            &mut node.sphinx_key,
+            &mut node.sphinx_key_validity,
+            self.announced_sphinx_keys.as_ref(),
+            epoch_id,
+        )
+        .then_some(node)
+    }
 }
 
 impl filter::Versioned for Node {
@@ -97,6 +118,8 @@ impl<'a> TryFrom<&'a MixNodeBond> for Node {
             mix_host,
             identity_key: identity::PublicKey::from_base58_string(&bond.mix_node.identity_key)?,
             sphinx_key: encryption::PublicKey::from_base58_string(&bond.mix_node.sphinx_key)?,
+            sphinx_key_validity: None,
+            announced_sphinx_keys: None,
             layer: bond.layer,
             version: bond.mix_node.version.clone(),
         })
@@ -23,6 +23,7 @@ pub fn instantiate(
 
     let config = Config {
         deposit_required: msg.deposit_required,
+        registration_period: msg.registration_period,
     };
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     state::save_config(deps.storage, &config)?;
@@ -34,7 +35,7 @@ pub fn instantiate(
 
 pub fn migrate(
     deps: DepsMut<'_>,
-    _env: Env,
+    env: Env,
     _msg: MigrateMsg,
 ) -> Result<Response, NameServiceError> {
     // Note: don't remove this particular bit of code as we have to ALWAYS check whether we have to
@@ -62,6 +63,11 @@ pub fn migrate(
         // should occur here, for example anything from `crate::queued_migrations::`
     }
 
+    // names registered before they could expire get a full registration period from now on.
+    // This is a no-op once all of them have been migrated.
+    let registration_period = state::registration_period(deps.storage)?;
+    state::names::expire_legacy_names(deps.storage, registration_period.after(&env.block))?;
+
     Ok(Response::new())
 }
 
@@ -72,9 +78,17 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, NameServiceError> {
     match msg {
-        ExecuteMsg::Register { name, address } => execute::register(deps, env, info, name, address),
+        ExecuteMsg::Register {
+            name,
+            address,
+            owner_signature,
+        } => execute::register(deps, env, info, name, address, owner_signature),
         ExecuteMsg::DeleteId { name_id } => execute::delete_id(deps, info, name_id),
         ExecuteMsg::DeleteName { name } => execute::delete_name(deps, info, name),
+        ExecuteMsg::Renew { name_id } => execute::renew(deps, env, info, name_id),
+        ExecuteMsg::TransferOwnership { name_id, new_owner } => {
+            execute::transfer_ownership(deps, env, info, name_id, new_owner)
+        }
         ExecuteMsg::UpdateDepositRequired { deposit_required } => {
             execute::update_deposit_required(deps, info, deposit_required)
         }
@@ -91,6 +105,9 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary> {
             to_binary(&query::query_all_paged(deps, limit, start_after)?)
         }
         QueryMsg::Config {} => to_binary(&query::query_config(deps)?),
+        QueryMsg::GetSigningNonce { address } => {
+            to_binary(&query::query_current_signing_nonce(deps, address)?)
+        }
         QueryMsg::GetContractVersion {} => to_binary(&query::query_contract_version()),
         QueryMsg::GetCW2ContractVersion {} => to_binary(&cw2::get_contract_version(deps.storage)?),
     };
@@ -104,14 +121,20 @@ mod tests {
     use crate::test_helpers::{
         assert::{assert_config, assert_empty, assert_name, assert_names, assert_not_found},
         fixture::name_fixture,
-        helpers::{get_attribute, nyms},
+        helpers::{
+            get_attribute, instantiate_test_contract, nyms, register_msg, register_name,
+            sign_register, REGISTRATION_PERIOD,
+        },
     };
 
     use cosmwasm_std::{
-        testing::{mock_dependencies, mock_env, mock_info},
-        Addr, Coin,
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier},
+        Addr, BankMsg, Coin, CosmosMsg, MemoryStorage, OwnedDeps,
     };
+    use cw_utils::Expiration;
+    use nym_crypto::asymmetric::identity;
     use nym_name_service_common::{msg::ExecuteMsg, NameEntry, NameId};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
 
     const DENOM: &str = "unym";
 
@@ -120,6 +143,7 @@ mod tests {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             deposit_required: Coin::new(100u128, DENOM),
+            registration_period: REGISTRATION_PERIOD,
         };
         let info = mock_info("creator", &[]);
         let admin = info.sender.clone();
@@ -130,21 +154,26 @@ mod tests {
 
         // Check that it worked by querying the config, and checking that the list of names is
         // empty
-        assert_config(deps.as_ref(), &admin, Coin::new(100u128, DENOM));
+        assert_config(
+            deps.as_ref(),
+            &admin,
+            Coin::new(100u128, DENOM),
+            REGISTRATION_PERIOD,
+        );
         assert_empty(deps.as_ref());
     }
 
     #[test]
     fn register_fails_incorrect_deposit() {
         let mut deps = mock_dependencies();
-        let msg = InstantiateMsg::new(nyms(100));
+        let msg = InstantiateMsg::new(nyms(100), REGISTRATION_PERIOD);
         let info = mock_info("creator", &[]);
         let admin = info.sender.clone();
         let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
         assert_eq!(res.messages.len(), 0);
 
         // Register
-        let msg: ExecuteMsg = name_fixture().into();
+        let msg = register_msg(deps.as_ref(), &name_fixture());
         let owner = name_fixture().owner.to_string();
 
         assert_eq!(
@@ -175,20 +204,25 @@ mod tests {
             }
         );
 
-        assert_config(deps.as_ref(), &admin, Coin::new(100, DENOM));
+        assert_config(
+            deps.as_ref(),
+            &admin,
+            Coin::new(100, DENOM),
+            REGISTRATION_PERIOD,
+        );
         assert_empty(deps.as_ref());
     }
 
     #[test]
     fn register_success() {
         let mut deps = mock_dependencies();
-        let msg = InstantiateMsg::new(nyms(100));
+        let msg = InstantiateMsg::new(nyms(100), REGISTRATION_PERIOD);
         let info = mock_info("creator", &[]);
         let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
         assert_eq!(res.messages.len(), 0);
 
         // Register
-        let msg: ExecuteMsg = name_fixture().into();
+        let msg = register_msg(deps.as_ref(), &name_fixture());
         let info = mock_info("steve", &[nyms(100)]);
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -202,7 +236,7 @@ mod tests {
         );
         assert_eq!(
             get_attribute(&res, "register", "nym_address"),
-            "client_id.client_key@gateway_id".to_string()
+            name_fixture().address.to_string()
         );
 
         // The expected registered name
@@ -217,13 +251,13 @@ mod tests {
     #[test]
     fn delete() {
         let mut deps = mock_dependencies();
-        let msg = InstantiateMsg::new(Coin::new(100, "unym"));
+        let msg = InstantiateMsg::new(Coin::new(100, "unym"), REGISTRATION_PERIOD);
         let info = mock_info("creator", &[]);
         let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
         assert_eq!(res.messages.len(), 0);
 
         // Register
-        let msg: ExecuteMsg = name_fixture().into();
+        let msg = register_msg(deps.as_ref(), &name_fixture());
         let info_steve = mock_info("steve", &[nyms(100)]);
         assert_eq!(info_steve.sender, name_fixture().owner);
         execute(deps.as_mut(), mock_env(), info_steve, msg).unwrap();
@@ -269,4 +303,206 @@ mod tests {
         assert_names(deps.as_ref(), &[]);
         assert_not_found(deps.as_ref(), expected_id);
     }
+
+    fn setup_with_registered_fixture() -> (OwnedDeps<MemoryStorage, MockApi, MockQuerier>, NameId) {
+        let mut deps = instantiate_test_contract();
+        let name_id = register_name(deps.as_mut(), &name_fixture());
+        (deps, name_id)
+    }
+
+    fn expired_env() -> Env {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        env
+    }
+
+    #[test]
+    fn register_fails_with_signature_from_another_key() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::new(nyms(100), REGISTRATION_PERIOD);
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let name = name_fixture();
+        let another_identity = identity::KeyPair::new(&mut ChaCha20Rng::from_seed([1u8; 32]));
+        let msg = ExecuteMsg::Register {
+            name: name.name.clone(),
+            address: name.address.clone(),
+            owner_signature: sign_register(0, &name, &another_identity),
+        };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("steve", &[nyms(100)]),
+                msg
+            )
+            .unwrap_err(),
+            NameServiceError::InvalidEd25519Signature
+        );
+        assert_empty(deps.as_ref());
+    }
+
+    #[test]
+    fn register_signature_cant_be_replayed() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::new(nyms(100), REGISTRATION_PERIOD);
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = register_msg(deps.as_ref(), &name_fixture());
+        let info = mock_info("steve", &[nyms(100)]);
+        execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::delete_id(1),
+        )
+        .unwrap();
+
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap_err(),
+            NameServiceError::InvalidEd25519Signature
+        );
+        assert_empty(deps.as_ref());
+    }
+
+    #[test]
+    fn renew_extends_expiration() {
+        let (mut deps, name_id) = setup_with_registered_fixture();
+
+        let msg = ExecuteMsg::Renew { name_id };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("timmy", &[]),
+                msg.clone()
+            )
+            .unwrap_err(),
+            NameServiceError::Unauthorized {
+                sender: Addr::unchecked("timmy")
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("steve", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        let expected_expiration = Expiration::AtTime(mock_env().block.time.plus_seconds(2000));
+        let mut expected_name = NameEntry::new(name_id, name_fixture());
+        expected_name.name.expires_at = expected_expiration;
+        assert_name(deps.as_ref(), &expected_name);
+
+        // renewing an already expired name starts a new registration period from now
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(5000);
+        execute(deps.as_mut(), env.clone(), mock_info("steve", &[]), msg).unwrap();
+        expected_name.name.expires_at = REGISTRATION_PERIOD.after(&env.block);
+        assert_name(deps.as_ref(), &expected_name);
+    }
+
+    #[test]
+    fn transfer_ownership() {
+        let (mut deps, name_id) = setup_with_registered_fixture();
+
+        let msg = ExecuteMsg::TransferOwnership {
+            name_id,
+            new_owner: "timmy".to_string(),
+        };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("timmy", &[]),
+                msg.clone()
+            )
+            .unwrap_err(),
+            NameServiceError::Unauthorized {
+                sender: Addr::unchecked("timmy")
+            }
+        );
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("steve", &[]), msg).unwrap();
+        assert_eq!(get_attribute(&res, "transfer_ownership", "owner"), "steve");
+        assert_eq!(
+            get_attribute(&res, "transfer_ownership", "new_owner"),
+            "timmy"
+        );
+
+        let mut expected_name = NameEntry::new(name_id, name_fixture());
+        expected_name.name.owner = Addr::unchecked("timmy");
+        assert_name(deps.as_ref(), &expected_name);
+
+        // the deposit is now returned to the new owner
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("timmy", &[]),
+            ExecuteMsg::delete_id(name_id),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "timmy".to_string(),
+                amount: vec![nyms(100)],
+            })
+        );
+    }
+
+    #[test]
+    fn cant_transfer_expired_name() {
+        let (mut deps, name_id) = setup_with_registered_fixture();
+
+        let msg = ExecuteMsg::TransferOwnership {
+            name_id,
+            new_owner: "timmy".to_string(),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), expired_env(), mock_info("steve", &[]), msg).unwrap_err(),
+            NameServiceError::NameExpired { name_id }
+        );
+    }
+
+    #[test]
+    fn expired_name_can_be_claimed() {
+        let (mut deps, name_id) = setup_with_registered_fixture();
+
+        let mut new_name = name_fixture();
+        new_name.owner = Addr::unchecked("timmy");
+        let msg = register_msg(deps.as_ref(), &new_name);
+        let info = mock_info("timmy", &[nyms(100)]);
+
+        // not possible while the registration is still valid
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err(),
+            NameServiceError::NameAlreadyRegistered {
+                name: new_name.name.clone()
+            }
+        );
+
+        let env = expired_env();
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // the previous owner gets the deposit back
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "steve".to_string(),
+                amount: vec![nyms(100)],
+            })
+        );
+        assert_eq!(
+            get_attribute(&res, "reclaim_expired", "name_id"),
+            name_id.to_string()
+        );
+
+        new_name.expires_at = REGISTRATION_PERIOD.after(&env.block);
+        let expected_name = NameEntry::new(name_id + 1, new_name);
+        assert_names(deps.as_ref(), &[expected_name]);
+        assert_not_found(deps.as_ref(), name_id);
+    }
 }
@@ -3,11 +3,16 @@ use crate::{
     error::{NameServiceError, Result},
     state,
 };
-use cosmwasm_std::{Addr, BankMsg, Coin, Deps, DepsMut, Env, MessageInfo, Response, Uint128};
+use cosmwasm_std::{
+    Addr, BankMsg, BlockInfo, Coin, Deps, DepsMut, Env, MessageInfo, Response, Uint128,
+};
+use cw_utils::{Duration, Expiration};
+use nym_contracts_common::signing::{MessageSignature, Verifier};
 use nym_name_service_common::{
+    construct_name_register_sign_payload,
     events::{
-        new_delete_id_event, new_delete_name_event, new_register_event,
-        new_update_deposit_required_event,
+        new_delete_id_event, new_delete_name_event, new_reclaim_expired_event, new_register_event,
+        new_renew_event, new_transfer_ownership_event, new_update_deposit_required_event,
     },
     Address, NameId, NymName, RegisteredName,
 };
@@ -79,6 +84,65 @@ fn ensure_sender_authorized(info: MessageInfo, names: &RegisteredName) -> Result
     }
 }
 
+fn ensure_not_expired(block: &BlockInfo, name_id: NameId, name: &RegisteredName) -> Result<()> {
+    if name.is_expired(block) {
+        Err(NameServiceError::NameExpired { name_id })
+    } else {
+        Ok(())
+    }
+}
+
+fn decode_ed25519_identity_key(encoded: &str) -> Result<[u8; 32]> {
+    let mut public_key = [0u8; 32];
+    let used = bs58::decode(encoded)
+        .into(&mut public_key)
+        .map_err(|err| NameServiceError::MalformedEd25519IdentityKey(err.to_string()))?;
+
+    if used != 32 {
+        return Err(NameServiceError::MalformedEd25519IdentityKey(
+            "Too few bytes provided for the public key".into(),
+        ));
+    }
+
+    Ok(public_key)
+}
+
+fn verify_register_signature(
+    deps: Deps,
+    sender: Addr,
+    deposit: Coin,
+    name: NymName,
+    address: Address,
+    signature: MessageSignature,
+) -> Result<()> {
+    // the registration has to be signed by the identity key of the client the name points to
+    let public_key = decode_ed25519_identity_key(address.client_id())?;
+
+    // reconstruct the payload
+    let nonce = state::signing::get_signing_nonce(deps.storage, sender.clone())?;
+    let msg = construct_name_register_sign_payload(nonce, sender, deposit, name, address);
+
+    if deps.api.verify_message(msg, signature, &public_key)? {
+        Ok(())
+    } else {
+        Err(NameServiceError::InvalidEd25519Signature)
+    }
+}
+
+/// Compute the expiration of a renewed name. Names that are still valid get extended by the
+/// registration period, while for the already expired ones it starts from the current block.
+fn renewed_expiration(
+    block: &BlockInfo,
+    expires_at: Expiration,
+    registration_period: Duration,
+) -> Expiration {
+    if expires_at.is_expired(block) {
+        return registration_period.after(block);
+    }
+    // the addition fails if the registration period has changed between height and time
+    (expires_at + registration_period).unwrap_or_else(|_| registration_period.after(block))
+}
+
 fn return_deposit(name_to_delete: &RegisteredName) -> BankMsg {
     BankMsg::Send {
         to_address: name_to_delete.owner.to_string(),
@@ -86,14 +150,34 @@ fn return_deposit(name_to_delete: &RegisteredName) -> BankMsg {
     }
 }
 
-/// Register a new name. It will be assigned a new name id.
+/// If the name has been registered before but has since expired, remove it so that it could be
+/// claimed again. The deposit is returned to the previous owner.
+fn reclaim_if_expired(deps: DepsMut, block: &BlockInfo, name: &NymName) -> Result<Response> {
+    let response = Response::new();
+    let Ok((name_id, expired_name)) = state::names::load_name_entry(deps.storage, name) else {
+        return Ok(response);
+    };
+    if !expired_name.is_expired(block) {
+        return Ok(response);
+    }
+
+    state::names::remove_id(deps.storage, name_id)?;
+    Ok(response
+        .add_message(return_deposit(&expired_name))
+        .add_event(new_reclaim_expired_event(name_id, expired_name)))
+}
+
+/// Register a new name. It will be assigned a new name id. If the name has previously been
+/// registered, but the registration has expired, it is claimed by the new owner.
 pub fn register(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     name: NymName,
     address: Address,
+    owner_signature: MessageSignature,
 ) -> Result<Response> {
+    let response = reclaim_if_expired(deps.branch(), &env.block, &name)?;
     ensure_name_not_exists(deps.as_ref(), &name)?;
     ensure_max_names_per_owner(deps.as_ref(), info.sender.clone())?;
     ensure_max_names_per_address(deps.as_ref(), address.clone())?;
@@ -103,17 +187,30 @@ pub fn register(
     let will_deposit = cw_utils::must_pay(&info, &denom)
         .map_err(|err| NameServiceError::DepositRequired { source: err })?;
     ensure_correct_deposit(will_deposit, deposit_required.amount)?;
+    let deposit = Coin::new(will_deposit.u128(), denom);
+
+    verify_register_signature(
+        deps.as_ref(),
+        info.sender.clone(),
+        deposit.clone(),
+        name.clone(),
+        address.clone(),
+        owner_signature,
+    )?;
+    state::signing::increment_signing_nonce(deps.storage, info.sender.clone())?;
 
+    let registration_period = state::registration_period(deps.storage)?;
     let new_name = RegisteredName {
         address,
         name,
         owner: info.sender,
         block_height: env.block.height,
-        deposit: Coin::new(will_deposit.u128(), denom),
+        deposit,
+        expires_at: registration_period.after(&env.block),
     };
     let name_id = state::names::save(deps.storage, &new_name)?;
 
-    Ok(Response::new().add_event(new_register_event(name_id, new_name)))
+    Ok(response.add_event(new_register_event(name_id, new_name)))
 }
 
 /// Delete an exsisting name.
@@ -146,6 +243,48 @@ pub(crate) fn delete_name(deps: DepsMut, info: MessageInfo, name: NymName) -> Re
         )))
 }
 
+/// Extend the registration of a name by another registration period. The owner can still renew
+/// an expired name as long as nobody else has claimed it in the meantime.
+pub(crate) fn renew(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name_id: NameId,
+) -> Result<Response> {
+    ensure_name_exists(deps.as_ref(), name_id)?;
+    let mut name = state::names::load_id(deps.storage, name_id)?;
+    ensure_sender_authorized(info, &name)?;
+
+    let registration_period = state::registration_period(deps.storage)?;
+    name.expires_at = renewed_expiration(&env.block, name.expires_at, registration_period);
+    state::names::update(deps.storage, name_id, &name)?;
+
+    Ok(Response::new().add_event(new_renew_event(name_id, name)))
+}
+
+/// Hand over a name to a new owner. The deposit stays with the name, so it will be returned to
+/// the new owner once the name gets deleted.
+pub(crate) fn transfer_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name_id: NameId,
+    new_owner: String,
+) -> Result<Response> {
+    ensure_name_exists(deps.as_ref(), name_id)?;
+    let mut name = state::names::load_id(deps.storage, name_id)?;
+    ensure_sender_authorized(info, &name)?;
+    ensure_not_expired(&env.block, name_id, &name)?;
+
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    ensure_max_names_per_owner(deps.as_ref(), new_owner.clone())?;
+
+    let previous_owner = std::mem::replace(&mut name.owner, new_owner);
+    state::names::update(deps.storage, name_id, &name)?;
+
+    Ok(Response::new().add_event(new_transfer_ownership_event(name_id, previous_owner, name)))
+}
+
 /// Update the deposit required to register new names
 pub(crate) fn update_deposit_required(
     deps: DepsMut,
@@ -1,5 +1,5 @@
 use cosmwasm_std::Deps;
-use nym_contracts_common::ContractBuildInformation;
+use nym_contracts_common::{signing::Nonce, ContractBuildInformation};
 use nym_name_service_common::{
     response::{ConfigResponse, NamesListResponse, PagedNamesListResponse},
     Address, NameEntry, NameId, NymName,
@@ -49,6 +49,11 @@ pub fn query_config(deps: Deps) -> Result<ConfigResponse> {
     Ok(config.into())
 }
 
+pub fn query_current_signing_nonce(deps: Deps, address: String) -> Result<Nonce> {
+    let address = deps.api.addr_validate(&address)?;
+    state::signing::get_signing_nonce(deps.storage, address)
+}
+
 pub fn query_contract_version() -> ContractBuildInformation {
     // as per docs
     // env! macro will expand to the value of the named environment variable at
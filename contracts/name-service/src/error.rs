@@ -1,5 +1,6 @@
 use cosmwasm_std::{Addr, StdError};
 use cw_controllers::AdminError;
+use nym_contracts_common::signing::verifier::ApiVerifierError;
 use nym_name_service_common::{Address, NameId, NymName};
 use thiserror::Error;
 
@@ -52,6 +53,21 @@ pub enum NameServiceError {
 
     #[error("name already registered: {name}")]
     NameAlreadyRegistered { name: NymName },
+
+    #[error("name with id {name_id} has expired")]
+    NameExpired { name_id: NameId },
+
+    #[error("failed to recover ed25519 public key from its base58 representation - {0}")]
+    MalformedEd25519IdentityKey(String),
+
+    #[error("provided ed25519 signature did not verify correctly")]
+    InvalidEd25519Signature,
+
+    #[error("failed to verify message signature: {source}")]
+    SignatureVerificationFailure {
+        #[from]
+        source: ApiVerifierError,
+    },
 }
 
 pub(crate) type Result<T, E = NameServiceError> = std::result::Result<T, E>;
@@ -1,15 +1,20 @@
 //! Integration tests using cw-multi-test.
 
-use cosmwasm_std::Addr;
+use cosmwasm_std::{testing::mock_env, Addr};
+use cw_utils::Expiration;
 use nym_name_service_common::{
     response::{ConfigResponse, PagedNamesListResponse},
-    Address, NameEntry, NymName, RegisteredName,
+    NameEntry, NymName, RegisteredName,
 };
 
 use crate::{
     constants::NAME_DEFAULT_RETRIEVAL_LIMIT,
     error::NameServiceError,
-    test_helpers::{fixture::name_entry, helpers::nyms, test_setup::TestSetup},
+    test_helpers::{
+        fixture::name_entry,
+        helpers::{nyms, REGISTRATION_PERIOD},
+        test_setup::TestSetup,
+    },
 };
 
 #[test]
@@ -23,6 +28,7 @@ fn query_config() {
         TestSetup::new().query_config(),
         ConfigResponse {
             deposit_required: nyms(100),
+            registration_period: REGISTRATION_PERIOD,
         }
     );
 }
@@ -42,7 +48,7 @@ fn register_and_query_name() {
     // Register a first name
     let owner = Addr::unchecked("owner");
     let name = NymName::new("steves-server").unwrap();
-    let nym_address = Address::new("nym-address");
+    let nym_address = setup.new_address();
     assert_eq!(setup.contract_balance(), nyms(0));
     assert_eq!(setup.balance(&owner), nyms(250));
     setup.register(name.clone(), nym_address.clone(), owner.clone());
@@ -52,6 +58,7 @@ fn register_and_query_name() {
     assert_eq!(setup.balance(&owner), nyms(150));
 
     // We can query the full name list
+    let expires_at = REGISTRATION_PERIOD.after(&mock_env().block);
     assert_eq!(
         setup.query_all(),
         PagedNamesListResponse {
@@ -63,6 +70,7 @@ fn register_and_query_name() {
                     owner: owner.clone(),
                     block_height: 12345,
                     deposit: nyms(100),
+                    expires_at,
                 },
             }],
             per_page: NAME_DEFAULT_RETRIEVAL_LIMIT as usize,
@@ -81,6 +89,7 @@ fn register_and_query_name() {
                 owner: owner.clone(),
                 block_height: 12345,
                 deposit: nyms(100),
+                expires_at,
             },
         }
     );
@@ -88,7 +97,7 @@ fn register_and_query_name() {
     // Register a second name
     let owner2 = Addr::unchecked("owner2");
     let name2 = NymName::new("another_server").unwrap();
-    let nym_address2 = Address::new("nymAddress2");
+    let nym_address2 = setup.new_address();
     setup.register(name2.clone(), nym_address2.clone(), owner2.clone());
 
     assert_eq!(setup.contract_balance(), nyms(200));
@@ -108,18 +117,19 @@ fn register_and_query_name() {
 #[test]
 fn cant_register_a_name_without_funds() {
     let mut setup = TestSetup::new();
+    let nym_address = setup.new_address();
     assert_eq!(setup.contract_balance(), nyms(0));
     assert_eq!(setup.balance("owner"), nyms(250));
     setup.register(
         NymName::new("my_name").unwrap(),
-        Address::new("nymAddress"),
+        nym_address.clone(),
         Addr::unchecked("owner"),
     );
     assert_eq!(setup.contract_balance(), nyms(100));
     assert_eq!(setup.balance("owner"), nyms(150));
     setup.register(
         NymName::new("my_name2").unwrap(),
-        Address::new("nymAddress"),
+        nym_address.clone(),
         Addr::unchecked("owner"),
     );
     assert_eq!(setup.contract_balance(), nyms(200));
@@ -127,7 +137,7 @@ fn cant_register_a_name_without_funds() {
     let res = setup
         .try_register(
             NymName::new("my_name3").unwrap(),
-            Address::new("nymAddress"),
+            nym_address.clone(),
             Addr::unchecked("owner"),
         )
         .unwrap_err();
@@ -146,9 +156,10 @@ fn cant_register_a_name_without_funds() {
 #[test]
 fn delete_name() {
     let mut setup = TestSetup::new();
+    let nym_address = setup.new_address();
     setup.register(
         NymName::new("my_name").unwrap(),
-        Address::new("nymAddress"),
+        nym_address,
         Addr::unchecked("owner"),
     );
     assert_eq!(setup.contract_balance(), nyms(100));
@@ -165,10 +176,11 @@ fn delete_name() {
 #[test]
 fn only_owner_can_delete_name() {
     let mut setup = TestSetup::new();
+    let nym_address = setup.new_address();
     assert_eq!(setup.contract_balance(), nyms(0));
     setup.register(
         NymName::new("name").unwrap(),
-        Address::new("nymAddress"),
+        nym_address,
         Addr::unchecked("owner"),
     );
     assert_eq!(setup.contract_balance(), nyms(100));
@@ -190,9 +202,10 @@ fn only_owner_can_delete_name() {
 #[test]
 fn cant_delete_name_that_does_not_exist() {
     let mut setup = TestSetup::new();
+    let nym_address = setup.new_address();
     setup.register(
         NymName::new("foo").unwrap(),
-        Address::new("nymAddress"),
+        nym_address,
         Addr::unchecked("owner"),
     );
     assert_eq!(setup.contract_balance(), nyms(100));
@@ -221,16 +234,17 @@ fn cant_delete_name_that_does_not_exist() {
 #[test]
 fn cant_register_the_same_name_multiple_times() {
     let mut setup = TestSetup::new();
+    let nym_address = setup.new_address();
 
     setup.register(
         NymName::new("name").unwrap(),
-        Address::new("nymAddress"),
+        nym_address.clone(),
         Addr::unchecked("owner"),
     );
     let resp = setup
         .try_register(
             NymName::new("name").unwrap(),
-            Address::new("nymAddress"),
+            nym_address.clone(),
             Addr::unchecked("owner"),
         )
         .unwrap_err();
@@ -248,7 +262,7 @@ fn can_register_multiple_names_for_the_same_nym_address() {
     let mut setup = TestSetup::new();
     let name1 = NymName::new("name1").unwrap();
     let name2 = NymName::new("name2").unwrap();
-    let address = Address::new("nymaddress");
+    let address = setup.new_address();
     let owner = Addr::unchecked("owner");
 
     setup.register(name1.clone(), address.clone(), owner.clone());
@@ -268,8 +282,8 @@ fn register_multiple_names_and_deleting_by_name() {
     let mut setup = TestSetup::new();
     let owner1 = Addr::unchecked("wealthy_owner_1");
     let owner2 = Addr::unchecked("wealthy_owner_2");
-    let nym_address1 = Address::new("nymaddress1");
-    let nym_address2 = Address::new("nymaddress2");
+    let nym_address1 = setup.new_address();
+    let nym_address2 = setup.new_address();
     let name1 = NymName::new("name1").unwrap();
     let name2 = NymName::new("name2").unwrap();
     let name3 = NymName::new("name3").unwrap();
@@ -326,8 +340,8 @@ fn check_paging() {
     let mut setup = TestSetup::new();
     let owner1 = Addr::unchecked("wealthy_owner_1");
     let owner2 = Addr::unchecked("wealthy_owner_2");
-    let nym_address1 = Address::new("nymAddress1");
-    let nym_address2 = Address::new("nymAddress2");
+    let nym_address1 = setup.new_address();
+    let nym_address2 = setup.new_address();
     let name1 = NymName::new("name1").unwrap();
     let name2 = NymName::new("name2").unwrap();
     let name3 = NymName::new("name3").unwrap();
@@ -390,19 +404,23 @@ fn check_paging() {
 #[test]
 fn name_id_is_not_resused_when_deleting_and_then_adding_a_new_names() {
     let mut setup = TestSetup::new();
+    let nym_address1 = setup.new_address();
+    let nym_address2 = setup.new_address();
+    let nym_address3 = setup.new_address();
+    let nym_address4 = setup.new_address();
     setup.register(
         NymName::new("myname1").unwrap(),
-        Address::new("nymAddress1"),
+        nym_address1,
         Addr::unchecked("owner1"),
     );
     setup.register(
         NymName::new("myname2").unwrap(),
-        Address::new("nymAddress2"),
+        nym_address2.clone(),
         Addr::unchecked("owner2"),
     );
     setup.register(
         NymName::new("myname3").unwrap(),
-        Address::new("nymAddress3"),
+        nym_address3,
         Addr::unchecked("owner3"),
     );
 
@@ -414,14 +432,14 @@ fn name_id_is_not_resused_when_deleting_and_then_adding_a_new_names() {
         vec![name_entry(
             2,
             NymName::new("myname2").unwrap(),
-            Address::new("nymAddress2"),
+            nym_address2.clone(),
             Addr::unchecked("owner2")
         )]
     );
 
     setup.register(
         NymName::new("myname4").unwrap(),
-        Address::new("nymAddress4"),
+        nym_address4.clone(),
         Addr::unchecked("owner4"),
     );
 
@@ -431,15 +449,183 @@ fn name_id_is_not_resused_when_deleting_and_then_adding_a_new_names() {
             name_entry(
                 2,
                 NymName::new("myname2").unwrap(),
-                Address::new("nymAddress2"),
+                nym_address2.clone(),
                 Addr::unchecked("owner2")
             ),
             name_entry(
                 4,
                 NymName::new("myname4").unwrap(),
-                Address::new("nymAddress4"),
+                nym_address4.clone(),
                 Addr::unchecked("owner4")
             )
         ]
     );
 }
+
+#[test]
+fn cant_register_a_name_without_a_valid_signature() {
+    let mut setup = TestSetup::new();
+    let nym_address = setup.new_address();
+    let another_address = setup.new_address();
+    let name = NymName::new("name").unwrap();
+    let owner = Addr::unchecked("owner");
+
+    // signed by the identity key of another client
+    let signature = setup.sign_register(name.clone(), another_address, owner.clone());
+    let resp = setup
+        .try_register_with_signature(name.clone(), nym_address.clone(), owner.clone(), signature)
+        .unwrap_err();
+    assert_eq!(
+        resp.downcast::<NameServiceError>().unwrap(),
+        NameServiceError::InvalidEd25519Signature
+    );
+
+    // signed for another owner
+    let signature = setup.sign_register(name.clone(), nym_address.clone(), Addr::unchecked("user"));
+    let resp = setup
+        .try_register_with_signature(name, nym_address, owner, signature)
+        .unwrap_err();
+    assert_eq!(
+        resp.downcast::<NameServiceError>().unwrap(),
+        NameServiceError::InvalidEd25519Signature
+    );
+
+    assert_eq!(setup.contract_balance(), nyms(0));
+    assert!(setup.query_all().names.is_empty());
+}
+
+#[test]
+fn signing_nonce_is_incremented_on_registration() {
+    let mut setup = TestSetup::new();
+    let nym_address = setup.new_address();
+    let owner = Addr::unchecked("owner");
+    let name = NymName::new("name").unwrap();
+
+    assert_eq!(setup.query_signing_nonce(&owner), 0);
+    let signature = setup.sign_register(name.clone(), nym_address.clone(), owner.clone());
+    setup
+        .try_register_with_signature(
+            name.clone(),
+            nym_address.clone(),
+            owner.clone(),
+            signature.clone(),
+        )
+        .unwrap();
+    assert_eq!(setup.query_signing_nonce(&owner), 1);
+
+    // the same signature can't be used again
+    setup.delete(1, owner.clone());
+    let resp = setup
+        .try_register_with_signature(name, nym_address, owner, signature)
+        .unwrap_err();
+    assert_eq!(
+        resp.downcast::<NameServiceError>().unwrap(),
+        NameServiceError::InvalidEd25519Signature
+    );
+}
+
+#[test]
+fn renew_name() {
+    let mut setup = TestSetup::new();
+    let nym_address = setup.new_address();
+    let owner = Addr::unchecked("owner");
+    setup.register(NymName::new("name").unwrap(), nym_address, owner.clone());
+
+    let registered_at = mock_env().block.time;
+    assert_eq!(
+        setup.query_id(1).name.expires_at,
+        Expiration::AtTime(registered_at.plus_seconds(1000))
+    );
+
+    let resp = setup
+        .try_renew(1, Addr::unchecked("not_owner"))
+        .unwrap_err();
+    assert_eq!(
+        resp.downcast::<NameServiceError>().unwrap(),
+        NameServiceError::Unauthorized {
+            sender: Addr::unchecked("not_owner")
+        }
+    );
+
+    setup.advance_time(500);
+    setup.try_renew(1, owner).unwrap();
+    assert_eq!(
+        setup.query_id(1).name.expires_at,
+        Expiration::AtTime(registered_at.plus_seconds(2000))
+    );
+}
+
+#[test]
+fn transfer_name_ownership() {
+    let mut setup = TestSetup::new();
+    let nym_address = setup.new_address();
+    let owner = Addr::unchecked("owner");
+    let new_owner = Addr::unchecked("owner2");
+    setup.register(NymName::new("name").unwrap(), nym_address, owner.clone());
+
+    let resp = setup
+        .try_transfer_ownership(1, new_owner.clone(), new_owner.clone())
+        .unwrap_err();
+    assert_eq!(
+        resp.downcast::<NameServiceError>().unwrap(),
+        NameServiceError::Unauthorized {
+            sender: new_owner.clone()
+        }
+    );
+
+    setup
+        .try_transfer_ownership(1, owner.clone(), new_owner.clone())
+        .unwrap();
+    assert_eq!(setup.query_id(1).name.owner, new_owner);
+
+    // the previous owner no longer controls the name
+    assert!(setup.try_delete(1, owner.clone()).is_err());
+
+    // and the deposit goes to the new owner
+    setup.delete(1, new_owner.clone());
+    assert_eq!(setup.contract_balance(), nyms(0));
+    assert_eq!(setup.balance(&owner), nyms(150));
+    assert_eq!(setup.balance(&new_owner), nyms(350));
+}
+
+#[test]
+fn expired_name_can_be_claimed_by_someone_else() {
+    let mut setup = TestSetup::new();
+    let nym_address = setup.new_address();
+    let squatter_address = setup.new_address();
+    let owner = Addr::unchecked("owner");
+    let new_owner = Addr::unchecked("owner2");
+    let name = NymName::new("name").unwrap();
+    setup.register(name.clone(), squatter_address, owner.clone());
+
+    let resp = setup
+        .try_register(name.clone(), nym_address.clone(), new_owner.clone())
+        .unwrap_err();
+    assert_eq!(
+        resp.downcast::<NameServiceError>().unwrap(),
+        NameServiceError::NameAlreadyRegistered { name: name.clone() }
+    );
+
+    // expired names can't be transferred anymore
+    setup.advance_time(1000);
+    let resp = setup
+        .try_transfer_ownership(1, owner.clone(), new_owner.clone())
+        .unwrap_err();
+    assert_eq!(
+        resp.downcast::<NameServiceError>().unwrap(),
+        NameServiceError::NameExpired { name_id: 1 }
+    );
+
+    // but they can be claimed, in which case the previous owner gets the deposit back
+    setup.register(name.clone(), nym_address.clone(), new_owner.clone());
+    assert_eq!(setup.balance(&owner), nyms(250));
+    assert_eq!(setup.balance(&new_owner), nyms(150));
+    assert_eq!(setup.contract_balance(), nyms(100));
+
+    let entries = setup.query_all().names;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].name_id, 2);
+    assert_eq!(entries[0].name.name, name);
+    assert_eq!(entries[0].name.address, nym_address);
+    assert_eq!(entries[0].name.owner, new_owner);
+}
@@ -1,21 +1,32 @@
 use cosmwasm_std::{Coin, Storage};
 use cw_storage_plus::Item;
+use cw_utils::Duration;
 use nym_name_service_common::response::ConfigResponse;
 use serde::{Deserialize, Serialize};
 
-use crate::{constants::CONFIG_KEY, error::Result};
+use crate::{
+    constants::{CONFIG_KEY, DEFAULT_REGISTRATION_PERIOD},
+    error::Result,
+};
 
 const CONFIG: Item<Config> = Item::new(CONFIG_KEY);
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub(crate) struct Config {
     pub deposit_required: Coin,
+    #[serde(default = "default_registration_period")]
+    pub registration_period: Duration,
+}
+
+fn default_registration_period() -> Duration {
+    DEFAULT_REGISTRATION_PERIOD
 }
 
 impl From<Config> for ConfigResponse {
     fn from(config: Config) -> Self {
         ConfigResponse {
             deposit_required: config.deposit_required,
+            registration_period: config.registration_period,
         }
     }
 }
@@ -32,3 +43,10 @@ pub(crate) fn load_config(store: &dyn Storage) -> Result<Config> {
 pub(crate) fn deposit_required(store: &dyn Storage) -> Result<Coin> {
     Ok(CONFIG.load(store).map(|config| config.deposit_required)?)
 }
+
+/// Return for how long newly registered, or renewed, names stay valid.
+pub(crate) fn registration_period(store: &dyn Storage) -> Result<Duration> {
+    Ok(CONFIG
+        .load(store)
+        .map(|config| config.registration_period)?)
+}
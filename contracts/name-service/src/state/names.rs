@@ -1,5 +1,6 @@
 use cosmwasm_std::{Addr, Order, StdError, StdResult, Storage};
 use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, MultiIndex, UniqueIndex};
+use cw_utils::Expiration;
 use nym_name_service_common::{Address, NameId, NymName, RegisteredName};
 
 use crate::{
@@ -49,6 +50,29 @@ pub fn save(store: &mut dyn Storage, new_name: &RegisteredName) -> Result<NameId
     Ok(name_id)
 }
 
+/// Overwrite an existing name entry, keeping its name id.
+pub fn update(store: &mut dyn Storage, name_id: NameId, name: &RegisteredName) -> Result<()> {
+    Ok(names().save(store, name_id, name)?)
+}
+
+/// Set the expiration of all the names that were registered before names could expire.
+pub fn expire_legacy_names(store: &mut dyn Storage, expires_at: Expiration) -> Result<()> {
+    let legacy_names = names()
+        .range(store, None, None, Order::Ascending)
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .map_or(true, |(_, name)| name.expires_at == Expiration::Never {})
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (name_id, mut name) in legacy_names {
+        name.expires_at = expires_at;
+        update(store, name_id, &name)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 pub fn save_all(state: &mut dyn Storage, names: &[RegisteredName]) -> Result<Vec<NameId>> {
     let mut ids = vec![];
@@ -396,6 +420,25 @@ mod tests {
         assert!(!has_name(&deps.storage, &name_fixture().name));
     }
 
+    #[rstest]
+    fn expire_legacy_names_works(mut deps: TestDeps) {
+        let mut legacy_name = name_fixture_full("one", "address_one", "owner_one");
+        legacy_name.expires_at = Expiration::Never {};
+        save(deps.as_mut().storage, &legacy_name).unwrap();
+        save(deps.as_mut().storage, &name_fixture()).unwrap();
+
+        let expires_at = Expiration::AtHeight(100);
+        expire_legacy_names(deps.as_mut().storage, expires_at).unwrap();
+
+        legacy_name.expires_at = expires_at;
+        assert_eq!(load_id(deps.as_ref().storage, 1).unwrap(), legacy_name);
+        assert_eq!(load_id(deps.as_ref().storage, 2).unwrap(), name_fixture());
+        assert_eq!(
+            load_owner(deps.as_ref().storage, legacy_name.owner.clone()).unwrap(),
+            vec![(1, legacy_name)]
+        );
+    }
+
     #[rstest]
     fn remove_name_works(mut deps: TestDeps) {
         save(deps.as_mut().storage, &name_fixture()).unwrap();
@@ -0,0 +1,18 @@
+use cosmwasm_std::{Addr, Storage};
+use cw_storage_plus::Map;
+use nym_contracts_common::signing::Nonce;
+
+use crate::{constants::SIGNING_NONCES_NAMESPACE, error::Result};
+
+const NONCES: Map<'_, Addr, Nonce> = Map::new(SIGNING_NONCES_NAMESPACE);
+
+/// Return the nonce that has to be included in the next signed message of the given address.
+pub(crate) fn get_signing_nonce(store: &dyn Storage, address: Addr) -> Result<Nonce> {
+    Ok(NONCES.may_load(store, address)?.unwrap_or(0))
+}
+
+/// Increment the nonce so that the already used signature can't be replayed.
+pub(crate) fn increment_signing_nonce(store: &mut dyn Storage, address: Addr) -> Result<()> {
+    let nonce = get_signing_nonce(store, address.clone())?;
+    Ok(NONCES.save(store, address, &(nonce + 1))?)
+}
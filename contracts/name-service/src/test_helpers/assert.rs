@@ -1,4 +1,5 @@
 use cosmwasm_std::{from_binary, testing::mock_env, Addr, Coin, Deps};
+use cw_utils::Duration;
 use nym_name_service_common::{
     msg::QueryMsg,
     response::{ConfigResponse, PagedNamesListResponse},
@@ -7,11 +8,22 @@ use nym_name_service_common::{
 
 use crate::{constants::NAME_DEFAULT_RETRIEVAL_LIMIT, error::NameServiceError};
 
-pub fn assert_config(deps: Deps, admin: &Addr, deposit_required: Coin) {
+pub fn assert_config(
+    deps: Deps,
+    admin: &Addr,
+    deposit_required: Coin,
+    registration_period: Duration,
+) {
     crate::state::assert_admin(deps, admin).unwrap();
     let res = crate::contract::query(deps, mock_env(), QueryMsg::Config {}).unwrap();
     let config: ConfigResponse = from_binary(&res).unwrap();
-    assert_eq!(config, ConfigResponse { deposit_required });
+    assert_eq!(
+        config,
+        ConfigResponse {
+            deposit_required,
+            registration_period,
+        }
+    );
 }
 
 pub fn assert_names(deps: Deps, expected_names: &[NameEntry]) {
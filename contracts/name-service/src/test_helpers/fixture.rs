@@ -1,7 +1,20 @@
-use cosmwasm_std::Addr;
+use cosmwasm_std::{testing::mock_env, Addr};
+use nym_crypto::asymmetric::identity;
 use nym_name_service_common::{Address, NameEntry, NameId, NymName, RegisteredName};
 
-use super::helpers::nyms;
+use super::helpers::{nyms, test_rng, REGISTRATION_PERIOD};
+
+/// The identity key of the client behind the address used by the name fixtures.
+pub fn identity_fixture() -> identity::KeyPair {
+    identity::KeyPair::new(&mut test_rng())
+}
+
+pub fn nym_address_fixture() -> String {
+    format!(
+        "{}.client_key@gateway_id",
+        identity_fixture().public_key().to_base58_string()
+    )
+}
 
 pub fn name_fixture_full(name: &str, nym_address: &str, owner: &str) -> RegisteredName {
     RegisteredName {
@@ -10,15 +23,16 @@ pub fn name_fixture_full(name: &str, nym_address: &str, owner: &str) -> Register
         owner: Addr::unchecked(owner),
         block_height: 12345,
         deposit: nyms(100),
+        expires_at: REGISTRATION_PERIOD.after(&mock_env().block),
     }
 }
 
 pub fn name_fixture() -> RegisteredName {
-    name_fixture_full("my-service", "client_id.client_key@gateway_id", "steve")
+    name_fixture_full("my-service", &nym_address_fixture(), "steve")
 }
 
 pub fn name_fixture_name(name: &str) -> RegisteredName {
-    name_fixture_full(name, "client_id.client_key@gateway_id", "steve")
+    name_fixture_full(name, &nym_address_fixture(), "steve")
 }
 
 pub fn name_entry(name_id: NameId, name: NymName, address: Address, owner: Addr) -> NameEntry {
@@ -1,19 +1,62 @@
 use cosmwasm_std::{
     coin, coins,
     testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier},
-    Coin, DepsMut, Event, MemoryStorage, OwnedDeps, Response,
+    Coin, Deps, DepsMut, Event, MemoryStorage, OwnedDeps, Response,
 };
 use cw_multi_test::AppResponse;
+use cw_utils::Duration;
+use nym_contracts_common::signing::{MessageSignature, Nonce};
+use nym_crypto::asymmetric::identity;
 use nym_name_service_common::{
+    construct_name_register_sign_payload,
     events::{NameEventType, NAME_ID},
     msg::{ExecuteMsg, InstantiateMsg},
     NameId, NymName, RegisteredName,
 };
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+use super::fixture::identity_fixture;
+
+pub const REGISTRATION_PERIOD: Duration = Duration::Time(1000);
 
 pub fn nyms(amount: u64) -> Coin {
     Coin::new(amount.into(), "unym")
 }
 
+pub fn test_rng() -> ChaCha20Rng {
+    let dummy_seed = [42u8; 32];
+    ChaCha20Rng::from_seed(dummy_seed)
+}
+
+/// Sign the registration of the name with the identity key of the client it points to.
+pub fn sign_register(
+    nonce: Nonce,
+    name: &RegisteredName,
+    identity: &identity::KeyPair,
+) -> MessageSignature {
+    let payload = construct_name_register_sign_payload(
+        nonce,
+        name.owner.clone(),
+        name.deposit.clone(),
+        name.name.clone(),
+        name.address.clone(),
+    );
+    let signature = identity
+        .private_key()
+        .sign(&payload.to_plaintext().unwrap());
+    MessageSignature::from(signature.to_bytes().as_ref())
+}
+
+/// Construct the message for registering the name, signed with the identity of the fixtures.
+pub fn register_msg(deps: Deps<'_>, name: &RegisteredName) -> ExecuteMsg {
+    let nonce = crate::state::signing::get_signing_nonce(deps.storage, name.owner.clone()).unwrap();
+    ExecuteMsg::Register {
+        name: name.name.clone(),
+        address: name.address.clone(),
+        owner_signature: sign_register(nonce, name, &identity_fixture()),
+    }
+}
+
 pub fn get_event_types(response: &Response, event_type: &str) -> Vec<Event> {
     response
         .events
@@ -75,6 +118,7 @@ pub fn instantiate_test_contract() -> OwnedDeps<MemoryStorage, MockApi, MockQuer
     let mut deps = mock_dependencies();
     let msg = InstantiateMsg {
         deposit_required: coin(100, "unym"),
+        registration_period: REGISTRATION_PERIOD,
     };
     let env = mock_env();
     let info = mock_info("creator", &[]);
@@ -84,7 +128,7 @@ pub fn instantiate_test_contract() -> OwnedDeps<MemoryStorage, MockApi, MockQuer
 }
 
 pub fn register_name(deps: DepsMut<'_>, name: &RegisteredName) -> NameId {
-    let msg: ExecuteMsg = name.clone().into();
+    let msg = register_msg(deps.as_ref(), name);
     let info = mock_info(name.owner.as_str(), &coins(100, "unym"));
     let res = crate::execute(deps, mock_env(), info, msg).unwrap();
     let name_id: NameId = get_attribute(&res, &NameEventType::Register.to_string(), NAME_ID)
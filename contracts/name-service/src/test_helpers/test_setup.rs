@@ -1,13 +1,20 @@
+use std::collections::HashMap;
+
 use cosmwasm_std::{coins, Addr, Coin, Uint128};
 use cw_multi_test::{App, AppBuilder, AppResponse, ContractWrapper, Executor};
+use nym_contracts_common::signing::{MessageSignature, Nonce};
+use nym_crypto::asymmetric::identity;
 use nym_name_service_common::{
     msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
     response::{ConfigResponse, PagedNamesListResponse},
-    Address, NameEntry, NameId, NymName,
+    Address, NameEntry, NameId, NymName, RegisteredName,
 };
+use rand_chacha::ChaCha20Rng;
 use serde::de::DeserializeOwned;
 
-use crate::test_helpers::helpers::get_app_attribute;
+use crate::test_helpers::helpers::{
+    get_app_attribute, nyms, sign_register, test_rng, REGISTRATION_PERIOD,
+};
 
 const DENOM: &str = "unym";
 const ADDRESSES: &[&str] = &[
@@ -19,6 +26,9 @@ const WEALTHY_ADDRESSES: &[&str] = &["wealthy_owner_1", "wealthy_owner_2"];
 pub struct TestSetup {
     app: App,
     addr: Addr,
+    rng: ChaCha20Rng,
+    // identity keys of the clients behind the nym addresses created in the test
+    identities: HashMap<String, identity::KeyPair>,
 }
 
 impl Default for TestSetup {
@@ -44,7 +54,12 @@ impl TestSetup {
         let code = ContractWrapper::new(crate::execute, crate::instantiate, crate::query);
         let code_id = app.store_code(Box::new(code));
         let addr = Self::instantiate(&mut app, code_id);
-        TestSetup { app, addr }
+        TestSetup {
+            app,
+            addr,
+            rng: test_rng(),
+            identities: HashMap::new(),
+        }
     }
 
     fn instantiate(app: &mut App, code_id: u64) -> Addr {
@@ -53,6 +68,7 @@ impl TestSetup {
             Addr::unchecked("admin"),
             &InstantiateMsg {
                 deposit_required: Coin::new(100, DENOM),
+                registration_period: REGISTRATION_PERIOD,
             },
             &[],
             "contract_label",
@@ -61,6 +77,23 @@ impl TestSetup {
         .unwrap()
     }
 
+    /// Create a nym address of a new client, whose identity key can sign name registrations.
+    pub fn new_address(&mut self) -> Address {
+        let identity = identity::KeyPair::new(&mut self.rng);
+        let address = format!(
+            "{}.client_enc@gateway_id",
+            identity.public_key().to_base58_string()
+        );
+        self.identities.insert(address.clone(), identity);
+        Address::new(&address)
+    }
+
+    /// Move the chain forward by the given number of seconds.
+    pub fn advance_time(&mut self, seconds: u64) {
+        self.app
+            .update_block(|block| block.time = block.time.plus_seconds(seconds));
+    }
+
     pub fn contract_balance(&self) -> Coin {
         self.app.wrap().query_balance(&self.addr, DENOM).unwrap()
     }
@@ -80,6 +113,12 @@ impl TestSetup {
         self.query(&QueryMsg::NameId { name_id })
     }
 
+    pub fn query_signing_nonce(&self, address: &Addr) -> Nonce {
+        self.query(&QueryMsg::GetSigningNonce {
+            address: address.to_string(),
+        })
+    }
+
     pub fn query_all(&self) -> PagedNamesListResponse {
         self.query(&QueryMsg::all())
     }
@@ -92,16 +131,49 @@ impl TestSetup {
         self.query(&QueryMsg::All { limit, start_after })
     }
 
+    /// Sign the registration with the identity key of the client behind the address.
+    pub fn sign_register(&self, name: NymName, address: Address, owner: Addr) -> MessageSignature {
+        let nonce = self.query_signing_nonce(&owner);
+        let identity = self
+            .identities
+            .get(address.as_str())
+            .expect("the address has not been created in this test setup");
+        let name = RegisteredName {
+            name,
+            address,
+            owner,
+            block_height: self.app.block_info().height,
+            deposit: nyms(100),
+            expires_at: Default::default(),
+        };
+        sign_register(nonce, &name, identity)
+    }
+
     pub fn try_register(
         &mut self,
         name: NymName,
         address: Address,
         owner: Addr,
+    ) -> anyhow::Result<AppResponse> {
+        let owner_signature = self.sign_register(name.clone(), address.clone(), owner.clone());
+        self.try_register_with_signature(name, address, owner, owner_signature)
+    }
+
+    pub fn try_register_with_signature(
+        &mut self,
+        name: NymName,
+        address: Address,
+        owner: Addr,
+        owner_signature: MessageSignature,
     ) -> anyhow::Result<AppResponse> {
         self.app.execute_contract(
             owner,
             self.addr.clone(),
-            &ExecuteMsg::Register { name, address },
+            &ExecuteMsg::Register {
+                name,
+                address,
+                owner_signature,
+            },
             &[Coin {
                 denom: DENOM.to_string(),
                 amount: Uint128::new(100),
@@ -147,6 +219,32 @@ impl TestSetup {
             .unwrap()
     }
 
+    pub fn try_renew(&mut self, name_id: NameId, owner: Addr) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            owner,
+            self.addr.clone(),
+            &ExecuteMsg::Renew { name_id },
+            &[],
+        )
+    }
+
+    pub fn try_transfer_ownership(
+        &mut self,
+        name_id: NameId,
+        owner: Addr,
+        new_owner: Addr,
+    ) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            owner,
+            self.addr.clone(),
+            &ExecuteMsg::TransferOwnership {
+                name_id,
+                new_owner: new_owner.to_string(),
+            },
+            &[],
+        )
+    }
+
     pub fn balance(&self, address: impl Into<String>) -> Coin {
         self.app.wrap().query_balance(address, DENOM).unwrap()
     }
@@ -73,7 +73,7 @@ pub enum GatewayRequestsError {
     RequestOfInvalidSize(usize),
     MalformedSphinxPacket,
     MalformedEncryption,
-    InvalidPacketMode,
+    InvalidPacketType,
     InvalidMixPacket(MixPacketFormattingError),
 }
 
@@ -87,14 +87,14 @@ impl fmt::Display for GatewayRequestsError {
             RequestOfInvalidSize(actual) =>
                 write!(
                 f,
-                "received request had invalid size. (actual: {}, but expected one of: {} (ACK), {} (REGULAR), {}, {}, {} (EXTENDED))",
+                "received request had invalid size. (actual: {}, but expected one of: {} (ACK), {} (REGULAR), {}, {}, {} (EXTENDED), {} (OUTFOX REGULAR))",
                 actual, PacketSize::AckPacket.size(), PacketSize::RegularPacket.size(),
                 PacketSize::ExtendedPacket8.size(), PacketSize::ExtendedPacket16.size(),
-                PacketSize::ExtendedPacket32.size()
+                PacketSize::ExtendedPacket32.size(), PacketSize::OutfoxRegularPacket.size()
             ),
             MalformedSphinxPacket => write!(f, "received sphinx packet was malformed"),
             MalformedEncryption => write!(f, "the received encrypted data was malformed"),
-            InvalidPacketMode => write!(f, "provided packet mode is invalid"),
+            InvalidPacketType => write!(f, "provided packet type is invalid"),
             InvalidMixPacket(err) => write!(f, "provided mix packet was malformed - {err}")
         }
     }
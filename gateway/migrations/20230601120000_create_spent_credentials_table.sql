@@ -0,0 +1,14 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+CREATE TABLE spent_credential
+(
+    blinded_serial_number_bs58 TEXT    NOT NULL PRIMARY KEY UNIQUE,
+    credential                 BLOB    NOT NULL,
+    settled                    BOOLEAN NOT NULL DEFAULT FALSE,
+    failed_settlement_attempts INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX `spent_credential_settled_index` ON `spent_credential` (`settled`, `failed_settlement_attempts`);
@@ -0,0 +1,17 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- unix timestamp (in seconds) of when the message got stored
+ALTER TABLE message_store ADD COLUMN timestamp INTEGER NOT NULL DEFAULT 0;
+
+-- size of the message content in bytes
+ALTER TABLE message_store ADD COLUMN content_size INTEGER NOT NULL DEFAULT 0;
+
+-- treat any pre-existing messages as if they were just received so that they wouldn't get pruned straight away
+UPDATE message_store
+SET timestamp    = CAST(strftime('%s', 'now') AS INTEGER),
+    content_size = length(content);
+
+CREATE INDEX `message_store_timestamp_index` ON `message_store` (`timestamp`);
@@ -0,0 +1,10 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- credentials that can never get settled are kept around, but are no longer retried
+ALTER TABLE spent_credential ADD COLUMN settlement_failed BOOLEAN NOT NULL DEFAULT FALSE;
+
+DROP INDEX `spent_credential_settled_index`;
+CREATE INDEX `spent_credential_settled_index` ON `spent_credential` (`settled`, `settlement_failed`);
@@ -0,0 +1,7 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- allows checking the usage of the particular client's inbox without touching the message contents
+CREATE INDEX `message_store_client_size_index` ON `message_store` (`client_address_bs58`, `content_size`);
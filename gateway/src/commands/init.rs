@@ -2,11 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    commands::{override_config, OverrideConfig},
+    commands::{override_config, read_key_passphrase, OverrideConfig},
     config::{persistence::pathfinder::GatewayPathfinder, Config},
     OutputFormat,
 };
 use clap::Args;
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use nym_config::NymConfig;
 use nym_crypto::asymmetric::{encryption, identity};
 use nym_validator_client::nyxd;
@@ -36,6 +37,10 @@ pub struct Init {
     #[clap(long)]
     clients_port: Option<u16>,
 
+    /// The port on which the gateway will be serving its http API, such as the metrics endpoint
+    #[clap(long)]
+    http_api_port: Option<u16>,
+
     /// The host that will be reported to the directory server
     #[clap(long)]
     // TODO: could this be changed to `Option<url::Url>`?
@@ -80,6 +85,9 @@ pub struct Init {
 
     #[clap(short, long, default_value_t = OutputFormat::default())]
     output: OutputFormat,
+
+    #[clap(flatten)]
+    key_passphrase: ArgKeyPassphrase,
 }
 
 impl From<Init> for OverrideConfig {
@@ -89,6 +97,7 @@ impl From<Init> for OverrideConfig {
             wallet_address: Some(init_config.wallet_address),
             mix_port: init_config.mix_port,
             clients_port: init_config.clients_port,
+            http_api_port: init_config.http_api_port,
             datastore: init_config.datastore,
             announce_host: init_config.announce_host,
             nym_apis: init_config.nym_apis,
@@ -117,6 +126,7 @@ pub async fn execute(args: Init) -> Result<(), Box<dyn Error + Send + Sync>> {
         false
     };
 
+    let key_passphrase = read_key_passphrase(&args.key_passphrase)?;
     let override_config_fields = OverrideConfig::from(args.clone());
 
     // Initialising the config structure is just overriding a default constructed one
@@ -129,21 +139,23 @@ pub async fn execute(args: Init) -> Result<(), Box<dyn Error + Send + Sync>> {
         let identity_keys = identity::KeyPair::new(&mut rng);
         let sphinx_keys = encryption::KeyPair::new(&mut rng);
         let pathfinder = GatewayPathfinder::new_from_config(&config);
-        nym_pemstore::store_keypair(
+        nym_pemstore::store_keypair_with_passphrase(
             &sphinx_keys,
             &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_encryption_key().to_owned(),
                 pathfinder.public_encryption_key().to_owned(),
             ),
+            key_passphrase.as_deref().map(str::as_bytes),
         )
         .expect("Failed to save sphinx keys");
 
-        nym_pemstore::store_keypair(
+        nym_pemstore::store_keypair_with_passphrase(
             &identity_keys,
             &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_identity_key().to_owned(),
                 pathfinder.public_identity_key().to_owned(),
             ),
+            key_passphrase.as_deref().map(str::as_bytes),
         )
         .expect("Failed to save identity keys");
 
@@ -157,7 +169,7 @@ pub async fn execute(args: Init) -> Result<(), Box<dyn Error + Send + Sync>> {
     eprintln!("Saved configuration file to {:?}", config_save_location);
     eprintln!("Gateway configuration completed.\n\n\n");
 
-    crate::node::create_gateway(config)
+    crate::node::create_gateway(config, key_passphrase.as_deref())
         .await
         .print_node_details(args.output);
     Ok(())
@@ -179,6 +191,7 @@ mod tests {
             wallet_address: "n1z9egw0knv47nmur0p8vk4rcx59h9gg4zjx9ede".parse().unwrap(),
             mix_port: Some(42),
             clients_port: Some(43),
+            http_api_port: Some(44),
             announce_host: Some("foo-announce-host".to_string()),
             datastore: Some("/foo-datastore".parse().unwrap()),
             nym_apis: None,
@@ -188,6 +201,7 @@ mod tests {
             nyxd_urls: None,
             only_coconut_credentials: None,
             output: Default::default(),
+            key_passphrase: Default::default(),
         };
         std::env::set_var(BECH32_PREFIX, "n");
 
@@ -6,6 +6,7 @@ use crate::{config::Config, Cli};
 use clap::CommandFactory;
 use clap::Subcommand;
 use nym_bin_common::completions::{fig_generate, ArgShell};
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use nym_bin_common::version_checker;
 use nym_config::OptionalSet;
 use nym_network_defaults::var_names::NYXD;
@@ -52,6 +53,7 @@ pub(crate) struct OverrideConfig {
     wallet_address: Option<nyxd::AccountId>,
     mix_port: Option<u16>,
     clients_port: Option<u16>,
+    http_api_port: Option<u16>,
     datastore: Option<PathBuf>,
     announce_host: Option<String>,
     enabled_statistics: Option<bool>,
@@ -98,6 +100,7 @@ pub(crate) fn override_config(
     config = config
         .with_optional(Config::with_mix_port, args.mix_port)
         .with_optional(Config::with_clients_port, args.clients_port)
+        .with_optional(Config::with_http_api_port, args.http_api_port)
         .with_optional_custom_env(
             Config::with_custom_nym_apis,
             args.nym_apis,
@@ -169,3 +172,8 @@ pub(crate) fn ensure_config_version_compatibility(cfg: &Config) -> Result<(), Ga
         })
     }
 }
+
+pub(crate) fn read_key_passphrase(args: &ArgKeyPassphrase) -> Result<Option<String>, GatewayError> {
+    args.read_passphrase()
+        .map_err(|source| GatewayError::KeyPassphraseReadFailure { source })
+}
@@ -1,10 +1,11 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::commands::OverrideConfig;
+use crate::commands::{read_key_passphrase, OverrideConfig};
 use crate::support::config::build_config;
 use clap::Args;
 use nym_bin_common::output_format::OutputFormat;
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use std::error::Error;
 
 #[derive(Args, Clone)]
@@ -15,12 +16,16 @@ pub struct NodeDetails {
 
     #[clap(short, long, default_value_t = OutputFormat::default())]
     output: OutputFormat,
+
+    #[clap(flatten)]
+    key_passphrase: ArgKeyPassphrase,
 }
 
 pub async fn execute(args: NodeDetails) -> Result<(), Box<dyn Error + Send + Sync>> {
     let config = build_config(args.id.clone(), OverrideConfig::default())?;
+    let key_passphrase = read_key_passphrase(&args.key_passphrase)?;
 
-    crate::node::create_gateway(config)
+    crate::node::create_gateway(config, key_passphrase.as_deref())
         .await
         .print_node_details(args.output);
     Ok(())
@@ -1,10 +1,11 @@
 // Copyright 2020-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::commands::{ensure_config_version_compatibility, OverrideConfig};
+use crate::commands::{ensure_config_version_compatibility, read_key_passphrase, OverrideConfig};
 use crate::support::config::build_config;
 use clap::Args;
 use nym_bin_common::output_format::OutputFormat;
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use nym_validator_client::nyxd;
 use std::error::Error;
 use std::net::IpAddr;
@@ -32,6 +33,10 @@ pub struct Run {
     #[clap(long)]
     clients_port: Option<u16>,
 
+    /// The port on which the gateway will be serving its http API, such as the metrics endpoint
+    #[clap(long)]
+    http_api_port: Option<u16>,
+
     /// The host that will be reported to the directory server
     #[clap(long)]
     // TODO: could this be changed to `Option<url::Url>`?
@@ -76,6 +81,9 @@ pub struct Run {
 
     #[clap(short, long, default_value_t = OutputFormat::default())]
     output: OutputFormat,
+
+    #[clap(flatten)]
+    key_passphrase: ArgKeyPassphrase,
 }
 
 impl From<Run> for OverrideConfig {
@@ -85,6 +93,7 @@ impl From<Run> for OverrideConfig {
             wallet_address: run_config.wallet_address,
             mix_port: run_config.mix_port,
             clients_port: run_config.clients_port,
+            http_api_port: run_config.http_api_port,
             datastore: run_config.datastore,
             announce_host: run_config.announce_host,
             nym_apis: run_config.nym_apis,
@@ -118,6 +127,7 @@ pub async fn execute(args: Run) -> Result<(), Box<dyn Error + Send + Sync>> {
     eprintln!("Starting gateway {id}...");
 
     let output = args.output;
+    let key_passphrase = read_key_passphrase(&args.key_passphrase)?;
     let config = build_config(id, args)?;
     ensure_config_version_compatibility(&config)?;
 
@@ -125,7 +135,7 @@ pub async fn execute(args: Run) -> Result<(), Box<dyn Error + Send + Sync>> {
         show_binding_warning(config.get_listening_address().to_string());
     }
 
-    let mut gateway = crate::node::create_gateway(config).await;
+    let mut gateway = crate::node::create_gateway(config, key_passphrase.as_deref()).await;
     eprintln!(
         "\nTo bond your gateway you will need to install the Nym wallet, go to https://nymtech.net/get-involved and select the Download button.\n\
          Select the correct version and install it to your machine. You will need to provide the following: \n ");
@@ -1,7 +1,7 @@
 // Copyright 2020-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::commands::{ensure_correct_bech32_prefix, OverrideConfig};
+use crate::commands::{ensure_correct_bech32_prefix, read_key_passphrase, OverrideConfig};
 use crate::error::GatewayError;
 use crate::support::config::build_config;
 use crate::{
@@ -11,6 +11,7 @@ use crate::{
 use anyhow::{bail, Result};
 use clap::{ArgGroup, Args};
 use nym_bin_common::output_format::OutputFormat;
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use nym_crypto::asymmetric::identity;
 use nym_types::helpers::ConsoleSigningOutput;
 use nym_validator_client::nyxd;
@@ -38,6 +39,9 @@ pub struct Sign {
 
     #[clap(short, long, default_value_t = OutputFormat::default())]
     output: OutputFormat,
+
+    #[clap(flatten)]
+    key_passphrase: ArgKeyPassphrase,
 }
 
 enum SignedTarget {
@@ -65,13 +69,18 @@ impl TryFrom<Sign> for SignedTarget {
     }
 }
 
-pub fn load_identity_keys(pathfinder: &GatewayPathfinder) -> identity::KeyPair {
-    let identity_keypair: identity::KeyPair =
-        nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+pub fn load_identity_keys(
+    pathfinder: &GatewayPathfinder,
+    key_passphrase: Option<&str>,
+) -> identity::KeyPair {
+    let identity_keypair: identity::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+        &nym_pemstore::KeyPairPath::new(
             pathfinder.private_identity_key().to_owned(),
             pathfinder.public_identity_key().to_owned(),
-        ))
-        .expect("Failed to read stored identity key files");
+        ),
+        key_passphrase.map(str::as_bytes),
+    )
+    .expect("Failed to read stored identity key files");
     identity_keypair
 }
 
@@ -138,9 +147,10 @@ pub fn execute(args: Sign) -> Result<(), Box<dyn Error + Send + Sync>> {
     ensure_config_version_compatibility(&config)?;
 
     let output = args.output;
+    let key_passphrase = read_key_passphrase(&args.key_passphrase)?;
     let signed_target = SignedTarget::try_from(args)?;
     let pathfinder = GatewayPathfinder::new_from_config(&config);
-    let identity_keypair = load_identity_keys(&pathfinder);
+    let identity_keypair = load_identity_keys(&pathfinder, key_passphrase.as_deref());
 
     match signed_target {
         SignedTarget::Text(text) => {
@@ -1,10 +1,14 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::commands::read_key_passphrase;
+use crate::config::persistence::pathfinder::GatewayPathfinder;
 use crate::config::{Config, MISSING_VALUE};
 use clap::Args;
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use nym_bin_common::version_checker::Version;
 use nym_config::NymConfig;
+use nym_crypto::asymmetric::{encryption, identity};
 use std::fmt::Display;
 use std::process;
 
@@ -13,6 +17,12 @@ pub struct Upgrade {
     /// Id of the nym-gateway we want to upgrade
     #[clap(long)]
     id: String,
+
+    // if specified, any plaintext private keys will get encrypted with the provided passphrase.
+    // keys that are already encrypted must have been encrypted with the same passphrase,
+    // as changing it is not supported
+    #[clap(flatten)]
+    key_passphrase: ArgKeyPassphrase,
 }
 
 #[allow(dead_code)]
@@ -136,6 +146,48 @@ fn do_upgrade(mut config: Config, args: &Upgrade, package_version: Version) {
     }
 }
 
+fn encrypt_stored_keys(config: &Config, key_passphrase: &str) {
+    let pathfinder = GatewayPathfinder::new_from_config(config);
+    let identity_paths = nym_pemstore::KeyPairPath::new(
+        pathfinder.private_identity_key().to_owned(),
+        pathfinder.public_identity_key().to_owned(),
+    );
+    let sphinx_paths = nym_pemstore::KeyPairPath::new(
+        pathfinder.private_encryption_key().to_owned(),
+        pathfinder.public_encryption_key().to_owned(),
+    );
+    let passphrase = Some(key_passphrase.as_bytes());
+
+    // note: plaintext keys are going to be loaded regardless of the passphrase
+    let identity_keys: identity::KeyPair =
+        nym_pemstore::load_keypair_with_passphrase(&identity_paths, passphrase).unwrap_or_else(
+            |err| {
+                eprintln!("failed to load the identity keys! - {err}");
+                process::exit(1)
+            },
+        );
+    let sphinx_keys: encryption::KeyPair =
+        nym_pemstore::load_keypair_with_passphrase(&sphinx_paths, passphrase).unwrap_or_else(
+            |err| {
+                eprintln!("failed to load the sphinx keys! - {err}");
+                process::exit(1)
+            },
+        );
+
+    nym_pemstore::store_keypair_with_passphrase(&identity_keys, &identity_paths, passphrase)
+        .unwrap_or_else(|err| {
+            eprintln!("failed to encrypt the identity keys! - {err}");
+            process::exit(1)
+        });
+    nym_pemstore::store_keypair_with_passphrase(&sphinx_keys, &sphinx_paths, passphrase)
+        .unwrap_or_else(|err| {
+            eprintln!("failed to encrypt the sphinx keys! - {err}");
+            process::exit(1)
+        });
+
+    eprintln!("Encrypted the stored private keys");
+}
+
 pub async fn execute(args: &Upgrade) {
     let package_version = parse_package_version();
 
@@ -149,5 +201,13 @@ pub async fn execute(args: &Upgrade) {
         process::exit(1);
     }
 
+    let key_passphrase = read_key_passphrase(&args.key_passphrase).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        process::exit(1)
+    });
+    if let Some(key_passphrase) = key_passphrase {
+        encrypt_stored_keys(&existing_config, &key_passphrase);
+    }
+
     do_upgrade(existing_config, args, package_version)
 }
@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::template::config_template;
-use nym_config::defaults::{DEFAULT_CLIENT_LISTENING_PORT, DEFAULT_MIX_LISTENING_PORT};
+use nym_config::defaults::{
+    DEFAULT_CLIENT_LISTENING_PORT, DEFAULT_HTTP_API_LISTENING_PORT, DEFAULT_MIX_LISTENING_PORT,
+};
 use nym_config::NymConfig;
 use nym_network_defaults::mainnet::{NYM_API, NYXD_URL, STATISTICS_SERVICE_DOMAIN_ADDRESS};
+use nym_statistics_common::privacy::PrivacyConfig;
 use nym_validator_client::nyxd;
 use serde::{Deserialize, Serialize};
 use std::net::IpAddr;
@@ -18,6 +21,15 @@ mod template;
 
 pub(crate) const MISSING_VALUE: &str = "MISSING VALUE";
 
+// 'SPHINX KEY ROTATION'
+const DEFAULT_EPOCHS_PER_SPHINX_KEY: u32 = 24;
+const DEFAULT_SPHINX_KEY_GRACE_PERIOD: Duration = Duration::from_secs(15 * 60);
+const DEFAULT_EPOCH_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+// 'NOISE'
+const DEFAULT_NOISE_KEY_REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const DEFAULT_NOISE_HANDSHAKE_TIMEOUT: Duration = Duration::from_millis(5_000);
+
 // 'DEBUG'
 // where applicable, the below are defined in milliseconds
 const DEFAULT_PRESENCE_SENDING_DELAY: Duration = Duration::from_millis(10_000);
@@ -28,6 +40,14 @@ const DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE: usize = 128;
 
 const DEFAULT_STORED_MESSAGE_FILENAME_LENGTH: u16 = 16;
 const DEFAULT_MESSAGE_RETRIEVAL_LIMIT: i64 = 100;
+const DEFAULT_MAXIMUM_CLIENT_INBOX_MESSAGES: i64 = 50_000;
+const DEFAULT_MAXIMUM_CLIENT_INBOX_SIZE: i64 = 128 * 1024 * 1024; // 128MB
+const DEFAULT_STORED_MESSAGE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const DEFAULT_INBOX_PRUNING_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+const DEFAULT_CREDENTIAL_SETTLEMENT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_CREDENTIAL_SETTLEMENT_BATCH_SIZE: usize = 20;
+const DEFAULT_MAXIMUM_CREDENTIAL_SETTLEMENT_ATTEMPTS: u32 = 5;
 
 pub fn missing_string_value() -> String {
     MISSING_VALUE.to_string()
@@ -45,10 +65,20 @@ fn default_clients_port() -> u16 {
     DEFAULT_CLIENT_LISTENING_PORT
 }
 
+fn default_http_api_port() -> u16 {
+    DEFAULT_HTTP_API_LISTENING_PORT
+}
+
 #[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct Config {
     gateway: Gateway,
 
+    #[serde(default)]
+    sphinx_key_rotation: SphinxKeyRotation,
+    #[serde(default)]
+    noise: Noise,
+    #[serde(default)]
+    statistics_privacy: PrivacyConfig,
     #[serde(default)]
     logging: Logging,
     #[serde(default)]
@@ -178,6 +208,11 @@ impl Config {
         self
     }
 
+    pub fn with_http_api_port(mut self, port: u16) -> Self {
+        self.gateway.http_api_port = port;
+        self
+    }
+
     pub fn announce_host_from_listening_host(mut self) -> Self {
         self.gateway.announce_address = self.gateway.listening_address.to_string();
         self
@@ -223,6 +258,10 @@ impl Config {
         self.gateway.public_sphinx_key_file.clone()
     }
 
+    pub fn get_rotated_sphinx_keys_directory(&self) -> PathBuf {
+        Config::default_data_directory(&self.gateway.id).join("rotated_sphinx_keys")
+    }
+
     pub fn get_enabled_statistics(&self) -> bool {
         self.gateway.enabled_statistics
     }
@@ -231,6 +270,10 @@ impl Config {
         self.gateway.statistics_service_url.clone()
     }
 
+    pub fn get_statistics_privacy(&self) -> PrivacyConfig {
+        self.statistics_privacy
+    }
+
     pub fn get_nym_api_endpoints(&self) -> Vec<Url> {
         self.gateway.nym_api_urls.clone()
     }
@@ -259,6 +302,10 @@ impl Config {
         self.gateway.clients_port
     }
 
+    pub fn get_http_api_port(&self) -> u16 {
+        self.gateway.http_api_port
+    }
+
     pub fn get_persistent_store_path(&self) -> PathBuf {
         self.gateway.persistent_storage.clone()
     }
@@ -287,6 +334,66 @@ impl Config {
         self.debug.message_retrieval_limit
     }
 
+    pub fn get_maximum_client_inbox_messages(&self) -> i64 {
+        self.debug.maximum_client_inbox_messages
+    }
+
+    pub fn get_maximum_client_inbox_size(&self) -> i64 {
+        self.debug.maximum_client_inbox_size
+    }
+
+    pub fn get_stored_message_ttl(&self) -> Duration {
+        self.debug.stored_message_ttl
+    }
+
+    pub fn get_inbox_pruning_interval(&self) -> Duration {
+        self.debug.inbox_pruning_interval
+    }
+
+    pub fn get_credential_settlement_interval(&self) -> Duration {
+        self.debug.credential_settlement_interval
+    }
+
+    pub fn get_credential_settlement_batch_size(&self) -> usize {
+        self.debug.credential_settlement_batch_size
+    }
+
+    pub fn get_maximum_credential_settlement_attempts(&self) -> u32 {
+        self.debug.maximum_credential_settlement_attempts
+    }
+
+    pub fn get_sphinx_key_rotation_enabled(&self) -> bool {
+        self.sphinx_key_rotation.enabled
+    }
+
+    pub fn get_epochs_per_sphinx_key(&self) -> u32 {
+        self.sphinx_key_rotation.epochs_per_key
+    }
+
+    pub fn get_sphinx_key_grace_period(&self) -> Duration {
+        self.sphinx_key_rotation.grace_period
+    }
+
+    pub fn get_epoch_check_interval(&self) -> Duration {
+        self.sphinx_key_rotation.epoch_check_interval
+    }
+
+    pub fn get_noise_enabled(&self) -> bool {
+        self.noise.enabled
+    }
+
+    pub fn get_accept_legacy_connections(&self) -> bool {
+        self.noise.accept_legacy_connections
+    }
+
+    pub fn get_noise_key_refresh_interval(&self) -> Duration {
+        self.noise.key_refresh_interval
+    }
+
+    pub fn get_noise_handshake_timeout(&self) -> Duration {
+        self.noise.handshake_timeout
+    }
+
     pub fn get_version(&self) -> &str {
         &self.gateway.version
     }
@@ -331,6 +438,11 @@ pub struct Gateway {
     #[serde(default = "default_clients_port")]
     clients_port: u16,
 
+    /// Port used for serving the http API, such as the metrics endpoint.
+    /// (default: 8000)
+    #[serde(default = "default_http_api_port")]
+    http_api_port: u16,
+
     /// Path to file containing private identity key.
     private_identity_key_file: PathBuf,
 
@@ -405,6 +517,7 @@ impl Default for Gateway {
             announce_address: "127.0.0.1".to_string(),
             mix_port: DEFAULT_MIX_LISTENING_PORT,
             clients_port: DEFAULT_CLIENT_LISTENING_PORT,
+            http_api_port: DEFAULT_HTTP_API_LISTENING_PORT,
             private_identity_key_file: Default::default(),
             public_identity_key_file: Default::default(),
             private_sphinx_key_file: Default::default(),
@@ -426,6 +539,67 @@ impl Default for Gateway {
 #[serde(deny_unknown_fields)]
 struct Logging {}
 
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+struct SphinxKeyRotation {
+    /// Specifies whether the sphinx key should be periodically replaced with a freshly generated one.
+    /// Otherwise the key from the bond is used indefinitely.
+    enabled: bool,
+
+    /// Number of epochs during which a single sphinx key is used.
+    epochs_per_key: u32,
+
+    /// Specifies for how long packets created for the previous key are still accepted after the rotation.
+    #[serde(with = "humantime_serde")]
+    grace_period: Duration,
+
+    /// Specifies how often the gateway checks whether the epoch has advanced.
+    #[serde(with = "humantime_serde")]
+    epoch_check_interval: Duration,
+}
+
+impl Default for SphinxKeyRotation {
+    fn default() -> Self {
+        SphinxKeyRotation {
+            enabled: true,
+            epochs_per_key: DEFAULT_EPOCHS_PER_SPHINX_KEY,
+            grace_period: DEFAULT_SPHINX_KEY_GRACE_PERIOD,
+            epoch_check_interval: DEFAULT_EPOCH_CHECK_INTERVAL,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+struct Noise {
+    /// Specifies whether the connections to other nodes should be encrypted and authenticated
+    /// with the noise protocol using the identity keys from the topology.
+    enabled: bool,
+
+    /// Specifies whether unencrypted connections should still be used with the nodes that do not
+    /// support noise yet. It should only be disabled once the entire network got upgraded.
+    accept_legacy_connections: bool,
+
+    /// Specifies how often the noise keys of other nodes are refreshed from the topology.
+    #[serde(with = "humantime_serde")]
+    key_refresh_interval: Duration,
+
+    /// Maximum allowed duration of the noise handshake.
+    #[serde(with = "humantime_serde")]
+    handshake_timeout: Duration,
+}
+
+impl Default for Noise {
+    fn default() -> Self {
+        Noise {
+            enabled: false,
+            accept_legacy_connections: true,
+            key_refresh_interval: DEFAULT_NOISE_KEY_REFRESH_INTERVAL,
+            handshake_timeout: DEFAULT_NOISE_HANDSHAKE_TIMEOUT,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
 struct Debug {
@@ -456,6 +630,32 @@ struct Debug {
     /// Number of messages from offline client that can be pulled at once from the storage.
     message_retrieval_limit: i64,
 
+    /// Maximum number of messages that can be stored for a single offline client.
+    /// Once it's reached, the oldest messages are going to get evicted.
+    maximum_client_inbox_messages: i64,
+
+    /// Maximum total size (in bytes) of messages that can be stored for a single offline client.
+    /// Once it's reached, the oldest messages are going to get evicted.
+    maximum_client_inbox_size: i64,
+
+    /// Duration for which messages for offline clients are kept before getting removed.
+    #[serde(with = "humantime_serde")]
+    stored_message_ttl: Duration,
+
+    /// Delay between subsequent runs of the task removing expired client messages.
+    #[serde(with = "humantime_serde")]
+    inbox_pruning_interval: Duration,
+
+    /// Delay between subsequent attempts at settling accepted bandwidth credentials on chain.
+    #[serde(with = "humantime_serde")]
+    credential_settlement_interval: Duration,
+
+    /// Maximum number of bandwidth credentials that are going to be settled in a single batch.
+    credential_settlement_batch_size: usize,
+
+    /// Number of times the gateway is going to attempt to settle a bandwidth credential before giving up.
+    maximum_credential_settlement_attempts: u32,
+
     /// Specifies whether the mixnode should be using the legacy framing for the sphinx packets.
     // it's set to true by default. The reason for that decision is to preserve compatibility with the
     // existing nodes whilst everyone else is upgrading and getting the code for handling the new field.
@@ -473,6 +673,13 @@ impl Default for Debug {
             maximum_connection_buffer_size: DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE,
             stored_messages_filename_length: DEFAULT_STORED_MESSAGE_FILENAME_LENGTH,
             message_retrieval_limit: DEFAULT_MESSAGE_RETRIEVAL_LIMIT,
+            maximum_client_inbox_messages: DEFAULT_MAXIMUM_CLIENT_INBOX_MESSAGES,
+            maximum_client_inbox_size: DEFAULT_MAXIMUM_CLIENT_INBOX_SIZE,
+            stored_message_ttl: DEFAULT_STORED_MESSAGE_TTL,
+            inbox_pruning_interval: DEFAULT_INBOX_PRUNING_INTERVAL,
+            credential_settlement_interval: DEFAULT_CREDENTIAL_SETTLEMENT_INTERVAL,
+            credential_settlement_batch_size: DEFAULT_CREDENTIAL_SETTLEMENT_BATCH_SIZE,
+            maximum_credential_settlement_attempts: DEFAULT_MAXIMUM_CREDENTIAL_SETTLEMENT_ATTEMPTS,
             // TODO: remember to change it in one of future releases!!
             use_legacy_framed_packet_version: true,
         }
@@ -44,4 +44,10 @@ pub(crate) enum GatewayError {
         expected_prefix: String,
         actual_prefix: String,
     },
+
+    #[error("failed to read the passphrase for the private keys: {source}")]
+    KeyPassphraseReadFailure {
+        #[source]
+        source: io::Error,
+    },
 }
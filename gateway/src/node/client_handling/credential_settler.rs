@@ -0,0 +1,368 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::client_handling::websocket::connection_handler::coconut::{
+    CoconutVerifier, SettlementOutcome,
+};
+use crate::node::client_handling::websocket::connection_handler::RequestHandlingError;
+use crate::node::storage::error::StorageError;
+use crate::node::storage::Storage;
+use async_trait::async_trait;
+use log::*;
+use nym_coconut_interface::Credential;
+use nym_task::TaskClient;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Means of settling the accepted bandwidth credentials on chain.
+#[async_trait]
+pub(crate) trait CredentialSettlement: Send + Sync {
+    async fn settle_credentials(
+        &self,
+        credentials: Vec<Credential>,
+    ) -> Result<SettlementOutcome, RequestHandlingError>;
+}
+
+#[async_trait]
+impl CredentialSettlement for CoconutVerifier {
+    async fn settle_credentials(
+        &self,
+        credentials: Vec<Credential>,
+    ) -> Result<SettlementOutcome, RequestHandlingError> {
+        CoconutVerifier::settle_credentials(self, credentials).await
+    }
+}
+
+/// Periodically settles on chain all bandwidth credentials that were accepted by this gateway.
+///
+/// Clients are granted their bandwidth as soon as their credential has been verified locally,
+/// so the relatively slow process of spending the credential, collecting votes from the nym-apis
+/// and executing the resultant proposals happens in the background in batches.
+pub(crate) struct CredentialSettler<St: Storage, S = CoconutVerifier> {
+    storage: St,
+    settlement: Arc<S>,
+    settlement_interval: Duration,
+    batch_size: usize,
+    maximum_attempts: u32,
+}
+
+impl<St, S> CredentialSettler<St, S>
+where
+    St: Storage,
+    S: CredentialSettlement,
+{
+    pub(crate) fn new(
+        storage: St,
+        settlement: Arc<S>,
+        settlement_interval: Duration,
+        batch_size: usize,
+        maximum_attempts: u32,
+    ) -> Self {
+        CredentialSettler {
+            storage,
+            settlement,
+            settlement_interval,
+            batch_size,
+            maximum_attempts,
+        }
+    }
+
+    async fn record_failure(&self, serial_number: &str) -> Result<(), StorageError> {
+        if self
+            .storage
+            .record_failed_credential_settlement(serial_number, self.maximum_attempts as i64)
+            .await?
+        {
+            error!(
+                "failed to settle credential {serial_number} {} times. Giving up on it",
+                self.maximum_attempts
+            );
+        }
+        Ok(())
+    }
+
+    async fn settle_pending_credentials(&self) -> Result<(), StorageError> {
+        let pending = self
+            .storage
+            .get_unsettled_credentials(self.batch_size as i64)
+            .await?;
+
+        if pending.is_empty() {
+            trace!("there are no credentials to settle");
+            return Ok(());
+        }
+
+        let mut credentials = Vec::with_capacity(pending.len());
+        for unsettled in pending {
+            match Credential::from_bytes(&unsettled.credential) {
+                Ok(credential) => credentials.push(credential),
+                Err(err) => {
+                    // this should be impossible as we have serialized the credential ourselves,
+                    // but if it does happen, retrying is not going to change anything
+                    error!(
+                        "failed to deserialize stored credential {}: {err}. It will never get settled",
+                        unsettled.blinded_serial_number_bs58
+                    );
+                    self.storage
+                        .mark_credential_settlement_as_failed(&unsettled.blinded_serial_number_bs58)
+                        .await?;
+                }
+            }
+        }
+
+        if credentials.is_empty() {
+            return Ok(());
+        }
+
+        info!("attempting to settle {} credentials", credentials.len());
+        let serial_numbers = credentials
+            .iter()
+            .map(|credential| credential.blinded_serial_number())
+            .collect::<Vec<_>>();
+
+        let outcome = match self.settlement.settle_credentials(credentials).await {
+            Ok(outcome) => outcome,
+            Err(err) => {
+                warn!("failed to settle the batch of credentials: {err}");
+                SettlementOutcome::default()
+            }
+        };
+
+        for serial_number in serial_numbers {
+            if outcome.settled.contains(&serial_number) {
+                self.storage
+                    .mark_credential_as_settled(&serial_number)
+                    .await?;
+            } else if outcome.rejected.contains(&serial_number) {
+                error!("credential {serial_number} has been rejected on chain. It will never get settled");
+                self.storage
+                    .mark_credential_settlement_as_failed(&serial_number)
+                    .await?;
+            } else {
+                self.record_failure(&serial_number).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn run(&self, mut shutdown: TaskClient) {
+        info!("Starting credential settler");
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = shutdown.recv() => {
+                    trace!("CredentialSettler: received shutdown");
+                }
+                _ = tokio::time::sleep(self.settlement_interval) => {
+                    if let Err(err) = self.settle_pending_credentials().await {
+                        error!("failed to settle pending credentials: {err}")
+                    }
+                }
+            }
+        }
+        trace!("CredentialSettler: Exiting");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::storage::PersistentStorage;
+    use nym_coconut_interface::{
+        prove_bandwidth_credential, ttp_keygen, Base58, Parameters, Signature,
+    };
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    const MAXIMUM_ATTEMPTS: u32 = 3;
+
+    #[derive(Default)]
+    struct MockSettlement {
+        settled: Vec<String>,
+        rejected: Vec<String>,
+        batches: Mutex<Vec<Vec<String>>>,
+    }
+
+    impl MockSettlement {
+        fn batches(&self) -> Vec<Vec<String>> {
+            self.batches.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl CredentialSettlement for MockSettlement {
+        async fn settle_credentials(
+            &self,
+            credentials: Vec<Credential>,
+        ) -> Result<SettlementOutcome, RequestHandlingError> {
+            let batch = credentials
+                .iter()
+                .map(|credential| credential.blinded_serial_number())
+                .collect::<Vec<_>>();
+            let outcome = SettlementOutcome {
+                settled: batch
+                    .iter()
+                    .filter(|serial_number| self.settled.contains(serial_number))
+                    .cloned()
+                    .collect(),
+                rejected: batch
+                    .iter()
+                    .filter(|serial_number| self.rejected.contains(serial_number))
+                    .cloned()
+                    .collect(),
+            };
+            self.batches.lock().unwrap().push(batch);
+            Ok(outcome)
+        }
+    }
+
+    fn credential() -> Credential {
+        let params = Parameters::new(4).unwrap();
+        let verification_key = ttp_keygen(&params, 1, 1).unwrap()[0].verification_key();
+        // the credentials never get verified here, so the signature doesn't have to be valid
+        let signature = Signature::try_from_bs58(
+            "ta3pM9ffj5T6YGbwjSBp2W118rcwyP9PXStc\
+        7ssb91g5GQYMQHhuTNajbdZcjxUFBFL5rhED8EHpRzE8r432ss3qbPBfpNev4CdkfMkQ3wepyM7hy7q1W6Rn9WmFoZL\
+        ZR9j",
+        )
+        .unwrap();
+        let theta = prove_bandwidth_credential(
+            &params,
+            &verification_key,
+            &signature,
+            params.random_scalar(),
+            params.random_scalar(),
+        )
+        .unwrap();
+        Credential::new(4, theta, 1000000, String::from("BandwidthVoucher"), 42)
+    }
+
+    async fn storage_with(credentials: &[Credential]) -> (PersistentStorage, TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = PersistentStorage::init(dir.path().join("db.sqlite"), 100, 100, 1000000)
+            .await
+            .unwrap();
+        for credential in credentials {
+            storage
+                .insert_spent_credential(&credential.blinded_serial_number(), credential.as_bytes())
+                .await
+                .unwrap();
+        }
+        (storage, dir)
+    }
+
+    fn settler(
+        storage: PersistentStorage,
+        settlement: MockSettlement,
+    ) -> CredentialSettler<PersistentStorage, MockSettlement> {
+        CredentialSettler::new(
+            storage,
+            Arc::new(settlement),
+            Duration::from_secs(60),
+            10,
+            MAXIMUM_ATTEMPTS,
+        )
+    }
+
+    async fn unsettled(storage: &PersistentStorage) -> Vec<String> {
+        storage
+            .get_unsettled_credentials(100)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|credential| credential.blinded_serial_number_bs58)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn settled_credentials_are_not_retried() {
+        let credentials = vec![credential(), credential()];
+        let serial_numbers = credentials
+            .iter()
+            .map(|credential| credential.blinded_serial_number())
+            .collect::<Vec<_>>();
+        let (storage, _dir) = storage_with(&credentials).await;
+        let settler = settler(
+            storage.clone(),
+            MockSettlement {
+                settled: serial_numbers.clone(),
+                ..Default::default()
+            },
+        );
+
+        settler.settle_pending_credentials().await.unwrap();
+        assert!(unsettled(&storage).await.is_empty());
+
+        settler.settle_pending_credentials().await.unwrap();
+        assert_eq!(settler.settlement.batches(), vec![serial_numbers]);
+    }
+
+    #[tokio::test]
+    async fn failed_credentials_are_retried_until_maximum_attempts() {
+        let settled = credential();
+        let failing = credential();
+        let (storage, _dir) = storage_with(&[failing.clone(), settled.clone()]).await;
+        let settler = settler(
+            storage.clone(),
+            MockSettlement {
+                settled: vec![settled.blinded_serial_number()],
+                ..Default::default()
+            },
+        );
+
+        for _ in 0..MAXIMUM_ATTEMPTS {
+            settler.settle_pending_credentials().await.unwrap();
+        }
+        assert!(unsettled(&storage).await.is_empty());
+
+        // the failing credential has been part of every batch, the settled one only of the first
+        settler.settle_pending_credentials().await.unwrap();
+        let batches = settler.settlement.batches();
+        assert_eq!(batches.len(), MAXIMUM_ATTEMPTS as usize);
+        assert_eq!(
+            batches[0],
+            vec![
+                failing.blinded_serial_number(),
+                settled.blinded_serial_number()
+            ]
+        );
+        for batch in &batches[1..] {
+            assert_eq!(batch, &vec![failing.blinded_serial_number()]);
+        }
+    }
+
+    #[tokio::test]
+    async fn rejected_credentials_are_not_retried() {
+        let rejected = credential();
+        let failing = credential();
+        let (storage, _dir) = storage_with(&[rejected.clone(), failing.clone()]).await;
+        let settler = settler(
+            storage.clone(),
+            MockSettlement {
+                rejected: vec![rejected.blinded_serial_number()],
+                ..Default::default()
+            },
+        );
+
+        settler.settle_pending_credentials().await.unwrap();
+        assert_eq!(
+            unsettled(&storage).await,
+            vec![failing.blinded_serial_number()]
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_credentials_are_not_retried() {
+        let (storage, _dir) = storage_with(&[]).await;
+        storage
+            .insert_spent_credential("malformed", vec![1, 2, 3])
+            .await
+            .unwrap();
+        let settler = settler(storage.clone(), MockSettlement::default());
+
+        settler.settle_pending_credentials().await.unwrap();
+        assert!(unsettled(&storage).await.is_empty());
+        assert!(settler.settlement.batches().is_empty());
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::storage::Storage;
+use log::*;
+use nym_task::TaskClient;
+use std::time::Duration;
+
+/// Periodically removes messages for offline clients that have been stored for longer than
+/// the configured time to live, so that clients that never come back wouldn't be taking
+/// the gateway's disk space forever.
+pub(crate) struct InboxPruner<St: Storage> {
+    storage: St,
+    message_ttl: Duration,
+    pruning_interval: Duration,
+}
+
+impl<St> InboxPruner<St>
+where
+    St: Storage,
+{
+    pub(crate) fn new(storage: St, message_ttl: Duration, pruning_interval: Duration) -> Self {
+        InboxPruner {
+            storage,
+            message_ttl,
+            pruning_interval,
+        }
+    }
+
+    async fn prune_expired_messages(&self) {
+        match self.storage.remove_expired_messages(self.message_ttl).await {
+            Ok(0) => trace!("there were no expired messages to remove"),
+            Ok(removed) => info!("removed {removed} expired client messages"),
+            Err(err) => error!("failed to remove expired client messages: {err}"),
+        }
+    }
+
+    pub(crate) async fn run(&self, mut shutdown: TaskClient) {
+        info!("Starting inbox pruner");
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = shutdown.recv() => {
+                    trace!("InboxPruner: received shutdown");
+                }
+                _ = tokio::time::sleep(self.pruning_interval) => {
+                    self.prune_expired_messages().await
+                }
+            }
+        }
+        trace!("InboxPruner: Exiting");
+    }
+}
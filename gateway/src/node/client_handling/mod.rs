@@ -3,6 +3,8 @@
 
 pub(crate) mod active_clients;
 mod bandwidth;
+pub(crate) mod credential_settler;
+pub(crate) mod inbox_pruner;
 pub(crate) mod websocket;
 
 pub(crate) const FREE_TESTNET_BANDWIDTH_VALUE: i64 = 64 * 1024 * 1024 * 1024; // 64GB
@@ -46,6 +46,12 @@ pub(crate) enum RequestHandlingError {
     #[error("Provided bandwidth credential did not verify correctly on {0}")]
     InvalidBandwidthCredential(String),
 
+    #[error("Provided bandwidth credential has already been spent")]
+    BandwidthCredentialAlreadySpent,
+
+    #[error("Provided bandwidth ticket belongs to a ticketbook of non-standard value ({0})")]
+    NonStandardTicketbook(u64),
+
     #[error("This gateway is only accepting coconut credentials for bandwidth")]
     OnlyCoconutCredentials,
 
@@ -206,38 +212,15 @@ where
             iv,
         )?;
 
-        // Get the latest coconut signers and their VK
-        let credential_api_clients = self
-            .inner
-            .coconut_verifier
-            .all_coconut_api_clients(*credential.epoch_id())
-            .await?;
-        let current_api_clients = self
-            .inner
-            .coconut_verifier
-            .all_current_coconut_api_clients()
-            .await?;
-        if credential_api_clients.is_empty() || current_api_clients.is_empty() {
-            return Err(RequestHandlingError::NotEnoughNymAPIs {
-                received: 0,
-                needed: 1,
-            });
-        }
-
-        let aggregated_verification_key =
-            nym_credentials::obtain_aggregate_verification_key(&credential_api_clients).await?;
-
-        if !credential.verify(&aggregated_verification_key) {
-            return Err(RequestHandlingError::InvalidBandwidthCredential(
-                String::from("credential failed to verify on gateway"),
-            ));
-        }
-
+        // verify the credential locally against the aggregated verification key of its epoch
         self.inner
             .coconut_verifier
-            .release_funds(current_api_clients, &credential)
+            .verify_credential(&credential)
             .await?;
 
+        let blinded_serial_number = credential.blinded_serial_number();
+        let serialized_credential = credential.as_bytes();
+
         let bandwidth = Bandwidth::from(credential);
         let bandwidth_value = bandwidth.value();
 
@@ -251,6 +234,17 @@ where
             ));
         }
 
+        // mark the credential as spent so that it couldn't be used here again.
+        // the actual on-chain settlement is going to happen later as part of a batch
+        if !self
+            .inner
+            .storage
+            .insert_spent_credential(&blinded_serial_number, serialized_credential)
+            .await?
+        {
+            return Err(RequestHandlingError::BandwidthCredentialAlreadySpent);
+        }
+
         self.increase_bandwidth(bandwidth_value as i64).await?;
         let available_total = self.get_available_bandwidth().await?;
 
@@ -283,7 +277,7 @@ where
         &self,
         mix_packet: MixPacket,
     ) -> Result<ServerResponse, RequestHandlingError> {
-        let consumed_bandwidth = mix_packet.sphinx_packet().len() as i64;
+        let consumed_bandwidth = mix_packet.packet().len() as i64;
 
         let available_bandwidth = self.get_available_bandwidth().await?;
 
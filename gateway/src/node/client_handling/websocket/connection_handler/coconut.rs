@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::authenticated::RequestHandlingError;
+use cw3::{ProposalResponse, Status};
 use log::*;
-use nym_coconut_interface::Credential;
-use nym_validator_client::nyxd::traits::DkgQueryClient;
+use nym_coconut_bandwidth_contract_common::spend_credential::SpendCredentialStatus;
+use nym_coconut_interface::{Credential, VerificationKey};
+use nym_network_defaults::TICKETBOOK_VALUE;
+use nym_validator_client::nyxd::traits::{CoconutBandwidthQueryClient, DkgQueryClient};
 use nym_validator_client::{
     nyxd::{
         cosmwasm_client::logs::{find_attribute, BANDWIDTH_PROPOSAL_ID},
@@ -13,14 +16,29 @@ use nym_validator_client::{
     },
     Client, CoconutApiClient,
 };
+use std::collections::HashMap;
 use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
 
 const ONE_HOUR_SEC: u64 = 3600;
 const MAX_FEEGRANT_UNYM: u128 = 10000;
 
+/// Result of an attempt at settling a batch of credentials on chain.
+#[derive(Debug, Default)]
+pub(crate) struct SettlementOutcome {
+    /// Blinded serial numbers of credentials that got successfully settled.
+    pub(crate) settled: Vec<String>,
+
+    /// Blinded serial numbers of credentials that can never get settled as their proposals got rejected.
+    pub(crate) rejected: Vec<String>,
+}
+
 pub(crate) struct CoconutVerifier {
     nyxd_client: Client<DirectSigningNyxdClient>,
     mix_denom_base: String,
+
+    /// Aggregated verification keys of all epochs for which we have received credentials so far.
+    verification_keys: RwLock<HashMap<u64, VerificationKey>>,
 }
 
 impl CoconutVerifier {
@@ -35,6 +53,7 @@ impl CoconutVerifier {
         CoconutVerifier {
             nyxd_client,
             mix_denom_base,
+            verification_keys: RwLock::new(HashMap::new()),
         }
     }
 
@@ -52,50 +71,261 @@ impl CoconutVerifier {
         Ok(CoconutApiClient::all_coconut_api_clients(&self.nyxd_client, epoch_id).await?)
     }
 
-    pub async fn release_funds(
+    /// Obtains the aggregated verification key for the specified epoch. Since the key can't change
+    /// once the epoch has been established, it's only queried once and cached afterwards.
+    pub async fn aggregated_verification_key(
+        &self,
+        epoch_id: u64,
+    ) -> Result<VerificationKey, RequestHandlingError> {
+        if let Some(verification_key) = self.verification_keys.read().await.get(&epoch_id) {
+            return Ok(verification_key.clone());
+        }
+
+        let api_clients = self.all_coconut_api_clients(epoch_id).await?;
+        if api_clients.is_empty() {
+            return Err(RequestHandlingError::NotEnoughNymAPIs {
+                received: 0,
+                needed: 1,
+            });
+        }
+        let verification_key =
+            nym_credentials::obtain_aggregate_verification_key(&api_clients).await?;
+
+        self.verification_keys
+            .write()
+            .await
+            .insert(epoch_id, verification_key.clone());
+        Ok(verification_key)
+    }
+
+    /// Verifies the provided credential locally against the aggregated verification key of its epoch
+    /// and makes sure it has not already been spent on chain.
+    ///
+    /// Note that it's up to the caller to make sure the credential hasn't been already spent at this gateway.
+    pub async fn verify_credential(
         &self,
-        api_clients: Vec<CoconutApiClient>,
         credential: &Credential,
     ) -> Result<(), RequestHandlingError> {
-        // Use a custom multiplier for revoke, as the default one (1.3)
-        // isn't enough
-        let revoke_fee = Some(Fee::Auto(Some(1.5)));
+        // tickets of books of non-standard values would stand out and thus could be linked together
+        if credential.is_ticket() && credential.issued_voucher_value() != TICKETBOOK_VALUE {
+            return Err(RequestHandlingError::NonStandardTicketbook(
+                credential.issued_voucher_value(),
+            ));
+        }
+
+        let aggregated_verification_key = self
+            .aggregated_verification_key(*credential.epoch_id())
+            .await?;
+
+        if !credential.verify(&aggregated_verification_key) {
+            return Err(RequestHandlingError::InvalidBandwidthCredential(
+                String::from("credential failed to verify on gateway"),
+            ));
+        }
+
+        // this is just a single query (rather than a transaction) so it doesn't introduce any meaningful delays,
+        // but it allows us to catch credentials that were already spent (and settled) at different gateways
+        if self
+            .nyxd_client
+            .nyxd
+            .get_spent_credential(credential.blinded_serial_number())
+            .await?
+            .spend_credential
+            .is_some()
+        {
+            return Err(RequestHandlingError::BandwidthCredentialAlreadySpent);
+        }
+
+        Ok(())
+    }
+
+    /// Attempts to settle the provided, already accepted, credentials on chain in a single batch.
+    /// It sends a single transaction spending all of them, asks all nym-apis to vote on the resultant
+    /// proposals and finally executes them. Credentials that have been spent before, but whose proposals
+    /// haven't been executed (for example because we crashed mid-settlement), are voted on and executed as well.
+    pub async fn settle_credentials(
+        &self,
+        credentials: Vec<Credential>,
+    ) -> Result<SettlementOutcome, RequestHandlingError> {
+        let mut outcome = SettlementOutcome::default();
+        let mut to_spend = Vec::new();
+        let mut in_progress = Vec::new();
+
+        // if we crashed (or failed) mid-settlement last time, some credentials might already
+        // be present on chain, in which case we have to resume from wherever we stopped
+        for credential in credentials {
+            let blinded_serial_number = credential.blinded_serial_number();
+            match self
+                .nyxd_client
+                .nyxd
+                .get_spent_credential(blinded_serial_number.clone())
+                .await?
+                .spend_credential
+            {
+                None => to_spend.push(credential),
+                Some(spent) if spent.status() == SpendCredentialStatus::Spent => {
+                    warn!("credential {blinded_serial_number} has already been settled on chain");
+                    outcome.settled.push(blinded_serial_number);
+                }
+                Some(_) => {
+                    debug!("credential {blinded_serial_number} is already in the process of being settled on chain");
+                    in_progress.push(credential)
+                }
+            }
+        }
+
+        let mut to_vote = Vec::new();
+        let mut to_execute = Vec::new();
+        for (proposal, credential) in self.find_spending_proposals(&in_progress).await? {
+            match proposal.status {
+                Status::Pending | Status::Open => to_vote.push((proposal.id, credential)),
+                Status::Passed => to_execute.push((proposal.id, credential)),
+                Status::Executed => outcome.settled.push(credential.blinded_serial_number()),
+                Status::Rejected => {
+                    warn!(
+                        "proposal {} releasing funds of credential {} has been rejected",
+                        proposal.id,
+                        credential.blinded_serial_number()
+                    );
+                    outcome.rejected.push(credential.blinded_serial_number())
+                }
+            }
+        }
+
+        if !to_spend.is_empty() || !to_vote.is_empty() {
+            let api_clients = self.all_current_coconut_api_clients().await?;
+            if api_clients.is_empty() {
+                return Err(RequestHandlingError::NotEnoughNymAPIs {
+                    received: 0,
+                    needed: 1,
+                });
+            }
+
+            if !to_spend.is_empty() {
+                to_vote.append(&mut self.spend_credentials(&to_spend).await?);
+            }
+            self.request_proposal_votes(api_clients, &to_vote).await?;
+            to_execute.append(&mut to_vote);
+        }
+
+        if !to_execute.is_empty() {
+            outcome
+                .settled
+                .append(&mut self.execute_proposals(to_execute).await);
+        }
+
+        Ok(outcome)
+    }
+
+    /// Finds the release funds proposals that were created when the provided credentials got spent.
+    async fn find_spending_proposals<'a>(
+        &self,
+        credentials: &'a [Credential],
+    ) -> Result<Vec<(ProposalResponse, &'a Credential)>, RequestHandlingError> {
+        if credentials.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // the description of each release funds proposal is the blinded serial number of the spent credential
+        let by_serial_number = credentials
+            .iter()
+            .map(|credential| (credential.blinded_serial_number(), credential))
+            .collect::<HashMap<_, _>>();
+
+        let mut proposals = Vec::with_capacity(credentials.len());
+        for proposal in self.nyxd_client.nyxd.get_all_proposals().await? {
+            if let Some(credential) = by_serial_number.get(&proposal.description) {
+                proposals.push((proposal, *credential))
+            }
+        }
+
+        if proposals.len() != credentials.len() {
+            warn!(
+                "could not find the proposals of {} credentials that are in the process of being settled",
+                credentials.len() - proposals.len()
+            );
+        }
+
+        Ok(proposals)
+    }
+
+    /// Sends a single transaction spending all of the provided credentials and returns the ids
+    /// of the created release funds proposals alongside the credentials they correspond to.
+    async fn spend_credentials<'a>(
+        &self,
+        credentials: &'a [Credential],
+    ) -> Result<Vec<(u64, &'a Credential)>, RequestHandlingError> {
+        let spend_requests = credentials
+            .iter()
+            .map(|credential| {
+                (
+                    Coin::new(
+                        credential.voucher_value().into(),
+                        self.mix_denom_base.clone(),
+                    ),
+                    credential.blinded_serial_number(),
+                )
+            })
+            .collect();
 
         let res = self
             .nyxd_client
             .nyxd
-            .spend_credential(
-                Coin::new(
-                    credential.voucher_value().into(),
-                    self.mix_denom_base.clone(),
-                ),
-                credential.blinded_serial_number(),
+            .spend_credentials(
+                spend_requests,
                 self.nyxd_client.nyxd.address().to_string(),
                 None,
             )
             .await?;
-        let proposal_id = find_attribute(&res.logs, "wasm", BANDWIDTH_PROPOSAL_ID)
-            .ok_or(RequestHandlingError::ProposalIdError {
-                reason: String::from("proposal id not found"),
-            })?
-            .value
-            .parse::<u64>()
-            .map_err(|_| RequestHandlingError::ProposalIdError {
-                reason: String::from("proposal id could not be parsed to u64"),
-            })?;
-
-        let proposal = self.nyxd_client.nyxd.get_proposal(proposal_id).await?;
-        if !credential.has_blinded_serial_number(&proposal.description)? {
-            return Err(RequestHandlingError::ProposalIdError {
-                reason: String::from("proposal has different serial number"),
-            });
+
+        // each message in the transaction resulted in its own proposal
+        let mut proposals = Vec::with_capacity(credentials.len());
+        for log in &res.logs {
+            let proposal_id =
+                find_attribute(std::slice::from_ref(log), "wasm", BANDWIDTH_PROPOSAL_ID)
+                    .ok_or(RequestHandlingError::ProposalIdError {
+                        reason: String::from("proposal id not found"),
+                    })?
+                    .value
+                    .parse::<u64>()
+                    .map_err(|_| RequestHandlingError::ProposalIdError {
+                        reason: String::from("proposal id could not be parsed to u64"),
+                    })?;
+
+            let proposal = self.nyxd_client.nyxd.get_proposal(proposal_id).await?;
+            let mut matching = None;
+            for credential in credentials {
+                if credential.has_blinded_serial_number(&proposal.description)? {
+                    matching = Some(credential);
+                    break;
+                }
+            }
+
+            match matching {
+                Some(credential) => proposals.push((proposal_id, credential)),
+                None => {
+                    return Err(RequestHandlingError::ProposalIdError {
+                        reason: format!(
+                            "proposal {proposal_id} does not match any of the spent credentials"
+                        ),
+                    })
+                }
+            }
         }
 
-        let req = nym_api_requests::coconut::VerifyCredentialBody::new(
-            credential.clone(),
-            proposal_id,
-            self.nyxd_client.nyxd.address().clone(),
-        );
+        Ok(proposals)
+    }
+
+    /// Asks each of the provided nym-apis to verify the credentials and vote on the associated proposals.
+    async fn request_proposal_votes(
+        &self,
+        api_clients: Vec<CoconutApiClient>,
+        proposals: &[(u64, &Credential)],
+    ) -> Result<(), RequestHandlingError> {
+        // Use a custom multiplier for revoke, as the default one (1.3)
+        // isn't enough
+        let revoke_fee = Some(Fee::Auto(Some(1.5)));
+
         for client in api_clients {
             self.nyxd_client
                 .nyxd
@@ -109,7 +339,27 @@ impl CoconutVerifier {
                     None,
                 )
                 .await?;
-            let ret = client.api_client.verify_bandwidth_credential(&req).await;
+
+            for (proposal_id, credential) in proposals {
+                let req = nym_api_requests::coconut::VerifyCredentialBody::new(
+                    (*credential).clone(),
+                    *proposal_id,
+                    self.nyxd_client.nyxd.address().clone(),
+                );
+                match client.api_client.verify_bandwidth_credential(&req).await {
+                    Ok(res) if !res.verification_result => {
+                        debug!("Validator {} didn't accept the credential. It will probably vote No on the spending proposal", client.api_client.nym_api_client.current_url());
+                    }
+                    Err(err) => {
+                        warn!(
+                            "Validator {} failed to verify the credential: {err}",
+                            client.api_client.nym_api_client.current_url()
+                        )
+                    }
+                    _ => (),
+                }
+            }
+
             self.nyxd_client
                 .nyxd
                 .revoke_allowance(
@@ -118,16 +368,46 @@ impl CoconutVerifier {
                     revoke_fee.clone(),
                 )
                 .await?;
-            if !ret?.verification_result {
-                debug!("Validator {} didn't accept the credential. It will probably vote No on the spending proposal", client.api_client.nym_api_client.current_url());
-            }
         }
 
-        self.nyxd_client
+        Ok(())
+    }
+
+    /// Attempts to execute all provided proposals in a single transaction. If that fails,
+    /// falls back to executing them one by one.
+    ///
+    /// Returns blinded serial numbers of credentials associated with the successfully executed proposals.
+    async fn execute_proposals(&self, proposals: Vec<(u64, &Credential)>) -> Vec<String> {
+        let proposal_ids = proposals.iter().map(|(id, _)| *id).collect();
+        match self
+            .nyxd_client
             .nyxd
-            .execute_proposal(proposal_id, None)
-            .await?;
+            .execute_proposals(proposal_ids, None)
+            .await
+        {
+            Ok(_) => {
+                return proposals
+                    .into_iter()
+                    .map(|(_, credential)| credential.blinded_serial_number())
+                    .collect()
+            }
+            Err(err) => {
+                warn!("failed to execute the batch of {} proposals: {err}. Going to attempt to execute them individually", proposals.len())
+            }
+        }
 
-        Ok(())
+        let mut executed = Vec::new();
+        for (proposal_id, credential) in proposals {
+            match self
+                .nyxd_client
+                .nyxd
+                .execute_proposal(proposal_id, None)
+                .await
+            {
+                Ok(_) => executed.push(credential.blinded_serial_number()),
+                Err(err) => warn!("failed to execute proposal {proposal_id}: {err}"),
+            }
+        }
+        executed
     }
 }
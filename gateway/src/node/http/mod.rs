@@ -0,0 +1,70 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::client_handling::active_clients::ActiveClientsStore;
+use crate::node::storage::Storage;
+use log::warn;
+use nym_metrics::{MetricType, MetricsEncoder};
+use rocket::http::ContentType;
+use rocket::{get, State};
+
+pub(crate) struct MetricsState {
+    active_clients_store: ActiveClientsStore,
+    storage: Box<dyn Storage>,
+}
+
+impl MetricsState {
+    pub(crate) fn new(active_clients_store: ActiveClientsStore, storage: Box<dyn Storage>) -> Self {
+        MetricsState {
+            active_clients_store,
+            storage,
+        }
+    }
+}
+
+/// Returns the running stats of the gateway in the Prometheus text format.
+#[get("/metrics")]
+pub(crate) async fn metrics(state: &State<MetricsState>) -> (ContentType, String) {
+    let mut encoder = MetricsEncoder::new();
+    encoder.gauge(
+        "nym_gateway_active_clients",
+        "Number of clients currently connected via websocket.",
+        state.active_clients_store.size(),
+    );
+
+    match state.storage.get_inbox_statistics().await {
+        Ok(inbox) => {
+            encoder
+                .gauge(
+                    "nym_gateway_inbox_stored_messages",
+                    "Number of messages currently stored for all offline clients.",
+                    inbox.stored_messages,
+                )
+                .gauge(
+                    "nym_gateway_inbox_stored_bytes",
+                    "Total size of messages currently stored for all offline clients.",
+                    inbox.stored_bytes,
+                )
+                .describe(
+                    "nym_gateway_inbox_removed_messages_total",
+                    "Number of stored messages removed since startup.",
+                    MetricType::Counter,
+                )
+                .sample(
+                    "nym_gateway_inbox_removed_messages_total",
+                    &[("reason", "evicted")],
+                    inbox.evicted_messages,
+                )
+                .sample(
+                    "nym_gateway_inbox_removed_messages_total",
+                    &[("reason", "expired")],
+                    inbox.expired_messages,
+                );
+        }
+        Err(err) => warn!("failed to obtain inbox statistics: {err}"),
+    }
+
+    // the content type is a compile time constant, so it must be valid
+    let content_type = ContentType::parse_flexible(nym_metrics::CONTENT_TYPE).unwrap();
+    (content_type, encoder.finish())
+}
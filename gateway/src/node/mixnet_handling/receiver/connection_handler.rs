@@ -9,16 +9,16 @@ use crate::node::storage::Storage;
 use futures::StreamExt;
 use log::*;
 use nym_mixnet_client::forwarder::MixForwardingSender;
+use nym_mixnet_client::noise::accept_connection;
+use nym_mixnet_client::NoiseConfig;
 use nym_mixnode_common::packet_processor::processor::ProcessedFinalHop;
 use nym_sphinx::forwarding::packet::MixPacket;
-use nym_sphinx::framing::codec::SphinxCodec;
 use nym_sphinx::framing::packet::FramedSphinxPacket;
 use nym_sphinx::DestinationAddressBytes;
 use nym_task::TaskClient;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use tokio::net::TcpStream;
-use tokio_util::codec::Framed;
 
 pub(crate) struct ConnectionHandler<St: Storage> {
     packet_processor: PacketProcessor,
@@ -31,6 +31,7 @@ pub(crate) struct ConnectionHandler<St: Storage> {
     active_clients_store: ActiveClientsStore,
     storage: St,
     ack_sender: MixForwardingSender,
+    noise_config: Option<NoiseConfig>,
 }
 
 impl<St: Storage + Clone> Clone for ConnectionHandler<St> {
@@ -49,6 +50,7 @@ impl<St: Storage + Clone> Clone for ConnectionHandler<St> {
             active_clients_store: self.active_clients_store.clone(),
             storage: self.storage.clone(),
             ack_sender: self.ack_sender.clone(),
+            noise_config: self.noise_config.clone(),
         }
     }
 }
@@ -59,6 +61,7 @@ impl<St: Storage> ConnectionHandler<St> {
         storage: St,
         ack_sender: MixForwardingSender,
         active_clients_store: ActiveClientsStore,
+        noise_config: Option<NoiseConfig>,
     ) -> Self {
         ConnectionHandler {
             packet_processor,
@@ -66,6 +69,7 @@ impl<St: Storage> ConnectionHandler<St> {
             storage,
             active_clients_store,
             ack_sender,
+            noise_config,
         }
     }
 
@@ -156,12 +160,7 @@ impl<St: Storage> ConnectionHandler<St> {
     }
 
     async fn handle_received_packet(&mut self, framed_sphinx_packet: FramedSphinxPacket) {
-        //
-        // TODO: here be replay attack detection - it will require similar key cache to the one in
-        // packet processor for vpn packets,
-        // question: can it also be per connection vs global?
-        //
-
+        // note: replayed packets are rejected by the packet processor itself
         let processed_final_hop = match self.packet_processor.process_received(framed_sphinx_packet)
         {
             Err(err) => {
@@ -182,7 +181,13 @@ impl<St: Storage> ConnectionHandler<St> {
     ) {
         debug!("Starting connection handler for {:?}", remote);
         shutdown.mark_as_success();
-        let mut framed_conn = Framed::new(conn, SphinxCodec);
+        let mut framed_conn = match accept_connection(conn, self.noise_config.as_ref()).await {
+            Ok(framed_conn) => framed_conn,
+            Err(err) => {
+                debug!("Failed to accept the connection from {remote} - {err}");
+                return;
+            }
+        };
         while !shutdown.is_shutdown() {
             tokio::select! {
                 biased;
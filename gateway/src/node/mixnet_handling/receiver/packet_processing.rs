@@ -1,7 +1,6 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use nym_crypto::asymmetric::encryption;
 use nym_mixnode_common::packet_processor::error::MixProcessingError;
 pub use nym_mixnode_common::packet_processor::processor::MixProcessingResult;
 use nym_mixnode_common::packet_processor::processor::{ProcessedFinalHop, SphinxPacketProcessor};
@@ -24,10 +23,8 @@ pub struct PacketProcessor {
 }
 
 impl PacketProcessor {
-    pub(crate) fn new(encryption_key: &encryption::PrivateKey) -> Self {
-        PacketProcessor {
-            inner_processor: SphinxPacketProcessor::new(encryption_key.into()),
-        }
+    pub(crate) fn new(inner_processor: SphinxPacketProcessor) -> Self {
+        PacketProcessor { inner_processor }
     }
 
     pub(crate) fn process_received(
@@ -6,8 +6,11 @@ use crate::config::persistence::pathfinder::GatewayPathfinder;
 use crate::config::Config;
 use crate::error::GatewayError;
 use crate::node::client_handling::active_clients::ActiveClientsStore;
+use crate::node::client_handling::credential_settler::CredentialSettler;
+use crate::node::client_handling::inbox_pruner::InboxPruner;
 use crate::node::client_handling::websocket;
 use crate::node::client_handling::websocket::connection_handler::coconut::CoconutVerifier;
+use crate::node::http::MetricsState;
 use crate::node::mixnet_handling::receiver::connection_handler::ConnectionHandler;
 use crate::node::statistics::collector::GatewayStatisticsCollector;
 use crate::node::storage::Storage;
@@ -15,8 +18,13 @@ use log::*;
 use nym_bin_common::output_format::OutputFormat;
 use nym_crypto::asymmetric::{encryption, identity};
 use nym_mixnet_client::forwarder::{MixForwardingSender, PacketForwarder};
+use nym_mixnet_client::{NoiseConfig, NoiseNodeKeys};
+use nym_mixnode_common::noise_keys::NoiseKeysRefresher;
+use nym_mixnode_common::packet_processor::processor::SphinxPacketProcessor;
+use nym_mixnode_common::sphinx_key_rotation::{self, RotatedKeysStorage, SphinxKeyRotator};
 use nym_network_defaults::NymNetworkDetails;
 use nym_statistics_common::collector::StatisticsSender;
+use nym_statistics_common::privacy::PrivacyFilter;
 use nym_task::{TaskClient, TaskManager};
 use nym_validator_client::Client;
 use rand::seq::SliceRandom;
@@ -26,20 +34,33 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 
 pub(crate) mod client_handling;
+pub(crate) mod http;
 pub(crate) mod mixnet_handling;
 pub(crate) mod statistics;
 pub(crate) mod storage;
 
 /// Wire up and create Gateway instance
-pub(crate) async fn create_gateway(config: Config) -> Gateway<PersistentStorage> {
+pub(crate) async fn create_gateway(
+    config: Config,
+    key_passphrase: Option<&str>,
+) -> Gateway<PersistentStorage> {
     let storage = initialise_storage(&config).await;
-    Gateway::new(config, storage).await
+    Gateway::new(config, storage, key_passphrase).await
 }
 
 async fn initialise_storage(config: &Config) -> PersistentStorage {
     let path = config.get_persistent_store_path();
     let retrieval_limit = config.get_message_retrieval_limit();
-    match PersistentStorage::init(path, retrieval_limit).await {
+    let maximum_client_inbox_messages = config.get_maximum_client_inbox_messages();
+    let maximum_client_inbox_size = config.get_maximum_client_inbox_size();
+    match PersistentStorage::init(
+        path,
+        retrieval_limit,
+        maximum_client_inbox_messages,
+        maximum_client_inbox_size,
+    )
+    .await
+    {
         Err(err) => panic!("failed to initialise gateway storage - {err}"),
         Ok(storage) => storage,
     }
@@ -51,6 +72,8 @@ pub(crate) struct Gateway<St: Storage> {
     identity_keypair: Arc<identity::KeyPair>,
     /// x25519 keypair used for Diffie-Hellman. Currently only used for sphinx key derivation.
     sphinx_keypair: Arc<encryption::KeyPair>,
+    /// Passphrase the gateway keys are encrypted with, if any. It's also used for the rotated sphinx keys.
+    key_passphrase: Option<String>,
     storage: St,
 }
 
@@ -59,14 +82,15 @@ where
     St: Storage + Clone + 'static,
 {
     /// Construct from the given `Config` instance.
-    pub async fn new(config: Config, storage: St) -> Self {
+    pub async fn new(config: Config, storage: St, key_passphrase: Option<&str>) -> Self {
         let pathfinder = GatewayPathfinder::new_from_config(&config);
         // let storage = Self::initialise_storage(&config).await;
 
         Gateway {
             config,
-            identity_keypair: Arc::new(Self::load_identity_keys(&pathfinder)),
-            sphinx_keypair: Arc::new(Self::load_sphinx_keys(&pathfinder)),
+            identity_keypair: Arc::new(Self::load_identity_keys(&pathfinder, key_passphrase)),
+            sphinx_keypair: Arc::new(Self::load_sphinx_keys(&pathfinder, key_passphrase)),
+            key_passphrase: key_passphrase.map(ToOwned::to_owned),
             storage,
         }
     }
@@ -82,27 +106,38 @@ where
             config,
             identity_keypair: Arc::new(identity_keypair),
             sphinx_keypair: Arc::new(sphinx_keypair),
+            key_passphrase: None,
             storage,
         }
     }
 
-    fn load_identity_keys(pathfinder: &GatewayPathfinder) -> identity::KeyPair {
-        let identity_keypair: identity::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+    fn load_identity_keys(
+        pathfinder: &GatewayPathfinder,
+        key_passphrase: Option<&str>,
+    ) -> identity::KeyPair {
+        let identity_keypair: identity::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_identity_key().to_owned(),
                 pathfinder.public_identity_key().to_owned(),
-            ))
-            .expect("Failed to read stored identity key files");
+            ),
+            key_passphrase.map(str::as_bytes),
+        )
+        .expect("Failed to read stored identity key files");
         identity_keypair
     }
 
-    fn load_sphinx_keys(pathfinder: &GatewayPathfinder) -> encryption::KeyPair {
-        let sphinx_keypair: encryption::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+    fn load_sphinx_keys(
+        pathfinder: &GatewayPathfinder,
+        key_passphrase: Option<&str>,
+    ) -> encryption::KeyPair {
+        let sphinx_keypair: encryption::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_encryption_key().to_owned(),
                 pathfinder.public_encryption_key().to_owned(),
-            ))
-            .expect("Failed to read stored sphinx key files");
+            ),
+            key_passphrase.map(str::as_bytes),
+        )
+        .expect("Failed to read stored sphinx key files");
         sphinx_keypair
     }
 
@@ -128,20 +163,22 @@ where
 
     fn start_mix_socket_listener(
         &self,
+        sphinx_packet_processor: SphinxPacketProcessor,
         ack_sender: MixForwardingSender,
         active_clients_store: ActiveClientsStore,
+        noise_config: Option<NoiseConfig>,
         shutdown: TaskClient,
     ) {
         info!("Starting mix socket listener...");
 
-        let packet_processor =
-            mixnet_handling::PacketProcessor::new(self.sphinx_keypair.private_key());
+        let packet_processor = mixnet_handling::PacketProcessor::new(sphinx_packet_processor);
 
         let connection_handler = ConnectionHandler::new(
             packet_processor,
             self.storage.clone(),
             ack_sender,
             active_clients_store,
+            noise_config,
         );
 
         let listening_address = SocketAddr::new(
@@ -152,6 +189,60 @@ where
         mixnet_handling::Listener::new(listening_address, shutdown).start(connection_handler);
     }
 
+    fn start_sphinx_key_rotation(
+        &self,
+        sphinx_packet_processor: SphinxPacketProcessor,
+        shutdown: TaskClient,
+    ) {
+        if !self.config.get_sphinx_key_rotation_enabled() {
+            warn!("Sphinx key rotation is disabled - the key from the bond is going to be used indefinitely");
+            return;
+        }
+        info!("Starting the sphinx key rotator...");
+
+        let config = sphinx_key_rotation::ConfigBuilder::new()
+            .epochs_per_key(self.config.get_epochs_per_sphinx_key())
+            .grace_period(self.config.get_sphinx_key_grace_period())
+            .epoch_check_interval(self.config.get_epoch_check_interval())
+            .nym_api_urls(self.config.get_nym_api_endpoints())
+            .build();
+
+        let mut sphinx_key_rotator = SphinxKeyRotator::new(
+            config,
+            Arc::clone(&self.identity_keypair),
+            sphinx_packet_processor,
+            RotatedKeysStorage::new(
+                self.config.get_rotated_sphinx_keys_directory(),
+                self.key_passphrase.clone(),
+            ),
+            shutdown,
+        );
+        tokio::spawn(async move { sphinx_key_rotator.run().await });
+    }
+
+    fn start_noise_keys_refresher(&self, shutdown: TaskClient) -> Option<NoiseConfig> {
+        if !self.config.get_noise_enabled() {
+            return None;
+        }
+        info!("Starting the noise keys refresher...");
+
+        let node_keys = NoiseNodeKeys::new();
+        let mut noise_keys_refresher = NoiseKeysRefresher::new(
+            node_keys.clone(),
+            self.config.get_nym_api_endpoints(),
+            self.config.get_noise_key_refresh_interval(),
+            shutdown,
+        );
+        tokio::spawn(async move { noise_keys_refresher.run().await });
+
+        Some(NoiseConfig::new(
+            Arc::new(self.identity_keypair.private_key().to_x25519()),
+            node_keys,
+            self.config.get_accept_legacy_connections(),
+            self.config.get_noise_handshake_timeout(),
+        ))
+    }
+
     fn start_client_websocket_listener(
         &self,
         forwarding_channel: MixForwardingSender,
@@ -180,7 +271,62 @@ where
         );
     }
 
-    fn start_packet_forwarder(&self, shutdown: TaskClient) -> MixForwardingSender {
+    fn start_http_api(&self, active_clients_store: ActiveClientsStore) {
+        info!("Starting HTTP API...");
+
+        let mut config = rocket::config::Config::release_default();
+
+        // bind to the same address as we are using for the mix traffic
+        config.address = self.config.get_listening_address();
+        config.port = self.config.get_http_api_port();
+
+        let metrics_state = MetricsState::new(active_clients_store, Box::new(self.storage.clone()));
+
+        tokio::spawn(async move {
+            rocket::build()
+                .configure(config)
+                .mount("/", rocket::routes![http::metrics])
+                .manage(metrics_state)
+                .launch()
+                .await
+        });
+    }
+
+    fn start_credential_settler(
+        &self,
+        coconut_verifier: Arc<CoconutVerifier>,
+        shutdown: TaskClient,
+    ) {
+        info!("Starting bandwidth credential settler...");
+
+        let credential_settler = CredentialSettler::new(
+            self.storage.clone(),
+            coconut_verifier,
+            self.config.get_credential_settlement_interval(),
+            self.config.get_credential_settlement_batch_size(),
+            self.config.get_maximum_credential_settlement_attempts(),
+        );
+
+        tokio::spawn(async move { credential_settler.run(shutdown).await });
+    }
+
+    fn start_inbox_pruner(&self, shutdown: TaskClient) {
+        info!("Starting inbox pruner...");
+
+        let inbox_pruner = InboxPruner::new(
+            self.storage.clone(),
+            self.config.get_stored_message_ttl(),
+            self.config.get_inbox_pruning_interval(),
+        );
+
+        tokio::spawn(async move { inbox_pruner.run(shutdown).await });
+    }
+
+    fn start_packet_forwarder(
+        &self,
+        noise_config: Option<NoiseConfig>,
+        shutdown: TaskClient,
+    ) -> MixForwardingSender {
         info!("Starting mix packet forwarder...");
 
         let (mut packet_forwarder, packet_sender) = PacketForwarder::new(
@@ -189,6 +335,7 @@ where
             self.config.get_initial_connection_timeout(),
             self.config.get_maximum_connection_buffer_size(),
             self.config.get_use_legacy_sphinx_framing(),
+            noise_config,
             shutdown,
         );
 
@@ -283,36 +430,61 @@ where
 
         let coconut_verifier = {
             let nyxd_client = self.random_nyxd_client();
-            CoconutVerifier::new(nyxd_client)
+            Arc::new(CoconutVerifier::new(nyxd_client))
         };
+        self.start_credential_settler(Arc::clone(&coconut_verifier), shutdown.subscribe());
+        self.start_inbox_pruner(shutdown.subscribe());
 
-        let mix_forwarding_channel = self.start_packet_forwarder(shutdown.subscribe());
+        let noise_config = self.start_noise_keys_refresher(shutdown.subscribe());
+        let mix_forwarding_channel =
+            self.start_packet_forwarder(noise_config.clone(), shutdown.subscribe());
 
         let active_clients_store = ActiveClientsStore::new();
+        let sphinx_packet_processor =
+            SphinxPacketProcessor::new(self.sphinx_keypair.private_key().into());
         self.start_mix_socket_listener(
+            sphinx_packet_processor.clone(),
             mix_forwarding_channel.clone(),
             active_clients_store.clone(),
+            noise_config,
             shutdown.subscribe(),
         );
+        self.start_sphinx_key_rotation(sphinx_packet_processor, shutdown.subscribe());
 
         if self.config.get_enabled_statistics() {
             let statistics_service_url = self.config.get_statistics_service_url();
-            let stats_collector = GatewayStatisticsCollector::new(
+            let mut stats_collector = GatewayStatisticsCollector::new(
                 self.identity_keypair.public_key().to_base58_string(),
                 active_clients_store.clone(),
+                self.storage.clone(),
                 statistics_service_url,
             );
+            if let Some(privacy_filter) =
+                PrivacyFilter::from_config(self.config.get_statistics_privacy())?
+            {
+                info!("Statistics are going to be made differentially private before being sent");
+                stats_collector = stats_collector.with_privacy_filter(
+                    privacy_filter,
+                    self.config.get_maximum_client_inbox_messages() as u64,
+                    self.config.get_maximum_client_inbox_size() as u64,
+                );
+            }
             let mut stats_sender = StatisticsSender::new(stats_collector);
             tokio::spawn(async move {
                 stats_sender.run().await;
             });
         }
 
+        // Rocket handles shutdown on its own, but its shutdown handling should be incorporated
+        // with that of the rest of the tasks.
+        // Currently its runtime is forcefully terminated once the gateway exits.
+        self.start_http_api(active_clients_store.clone());
+
         self.start_client_websocket_listener(
             mix_forwarding_channel,
             active_clients_store,
             shutdown.subscribe(),
-            Arc::new(coconut_verifier),
+            coconut_verifier,
         );
 
         info!("Finished nym gateway startup procedure - it should now be able to receive mix and client traffic!");
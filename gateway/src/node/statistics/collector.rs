@@ -2,49 +2,143 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_trait::async_trait;
+use log::warn;
 use sqlx::types::chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use std::time::Duration;
 use url::Url;
 
 use nym_statistics_common::{
-    api::build_and_send_statistics_request, collector::StatisticsCollector, error::StatsError,
+    api::build_and_send_statistics_request,
+    collector::StatisticsCollector,
+    error::StatsError,
+    privacy::{PrivacyFilter, SensitiveValue},
     StatsData, StatsGatewayData, StatsMessage,
 };
 
 use crate::node::client_handling::active_clients::ActiveClientsStore;
+use crate::node::storage::Storage;
 
-pub(crate) struct GatewayStatisticsCollector {
+pub(crate) struct GatewayStatisticsCollector<St: Storage> {
     gateway_id: String,
     active_clients_store: ActiveClientsStore,
+    storage: St,
     statistics_service_url: Url,
+    privacy_filter: Option<PrivacyFilter>,
+
+    /// Maximum number of messages that can be stored for a single client.
+    max_client_messages: u64,
+
+    /// Maximum total size (in bytes) of messages that can be stored for a single client.
+    max_client_bytes: u64,
 }
 
-impl GatewayStatisticsCollector {
+impl<St: Storage> GatewayStatisticsCollector<St> {
     pub fn new(
         gateway_id: String,
         active_clients_store: ActiveClientsStore,
+        storage: St,
         statistics_service_url: Url,
     ) -> Self {
         GatewayStatisticsCollector {
             gateway_id,
             active_clients_store,
+            storage,
             statistics_service_url,
+            privacy_filter: None,
+            max_client_messages: 0,
+            max_client_bytes: 0,
         }
     }
+
+    /// Makes the collector add noise to all the values before they are sent. The inbox quotas
+    /// bound the amount by which a single client could influence the inbox statistics.
+    #[must_use]
+    pub fn with_privacy_filter(
+        mut self,
+        privacy_filter: PrivacyFilter,
+        max_client_messages: u64,
+        max_client_bytes: u64,
+    ) -> Self {
+        self.privacy_filter = Some(privacy_filter);
+        self.max_client_messages = max_client_messages;
+        self.max_client_bytes = max_client_bytes;
+        self
+    }
+
+    /// Sums the messages removed from all the inboxes. If the statistics are going to be made private,
+    /// the contribution of each client is bounded by its inbox quota.
+    fn total_removed(&self, removed_per_client: &HashMap<String, u64>) -> u64 {
+        removed_per_client
+            .values()
+            .map(|&removed| {
+                if self.privacy_filter.is_some() {
+                    removed.min(self.max_client_messages)
+                } else {
+                    removed
+                }
+            })
+            .sum()
+    }
+
+    fn privatize(
+        &self,
+        privacy_filter: &PrivacyFilter,
+        data: StatsGatewayData,
+    ) -> StatsGatewayData {
+        let values = [
+            SensitiveValue::new(data.inbox_count as u64, 1),
+            SensitiveValue::new(data.stored_messages, self.max_client_messages),
+            SensitiveValue::new(data.stored_bytes, self.max_client_bytes),
+            SensitiveValue::new(data.evicted_messages, self.max_client_messages),
+            SensitiveValue::new(data.expired_messages, self.max_client_messages),
+        ];
+        let private = privacy_filter.privatize(&values);
+
+        StatsGatewayData::new(data.gateway_id, private[0].min(u32::MAX as u64) as u32)
+            .with_stored_messages(private[1], private[2], private[3], private[4])
+    }
 }
 
 #[async_trait]
-impl StatisticsCollector for GatewayStatisticsCollector {
+impl<St: Storage> StatisticsCollector for GatewayStatisticsCollector<St> {
     async fn create_stats_message(
         &self,
         interval: Duration,
         timestamp: DateTime<Utc>,
     ) -> StatsMessage {
         let inbox_count = self.active_clients_store.size() as u32;
-        let stats_data = vec![StatsData::Gateway(StatsGatewayData::new(
-            self.gateway_id.clone(),
-            inbox_count,
-        ))];
+        let mut gateway_data = StatsGatewayData::new(self.gateway_id.clone(), inbox_count);
+
+        // the removals are reported per interval (rather than since startup),
+        // so that each removal only ever contributes to a single report
+        let (evicted_messages, expired_messages) = match self.storage.take_inbox_removals().await {
+            Ok(removals) => (
+                self.total_removed(&removals.evicted_messages),
+                self.total_removed(&removals.expired_messages),
+            ),
+            Err(err) => {
+                warn!("failed to obtain inbox removals: {err}");
+                (0, 0)
+            }
+        };
+        match self.storage.get_inbox_statistics().await {
+            Ok(inbox) => {
+                gateway_data = gateway_data.with_stored_messages(
+                    inbox.stored_messages,
+                    inbox.stored_bytes,
+                    evicted_messages,
+                    expired_messages,
+                )
+            }
+            Err(err) => warn!("failed to obtain inbox statistics: {err}"),
+        }
+
+        if let Some(privacy_filter) = &self.privacy_filter {
+            gateway_data = self.privatize(privacy_filter, gateway_data);
+        }
+
+        let stats_data = vec![StatsData::Gateway(gateway_data)];
         StatsMessage {
             stats_data,
             interval_seconds: interval.as_secs() as u32,
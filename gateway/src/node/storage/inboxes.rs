@@ -1,7 +1,17 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::node::storage::models::StoredMessage;
+use crate::node::storage::models::{InboxRemovals, InboxStatistics, StoredMessage};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn unix_timestamp(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default()
+}
 
 #[derive(Clone)]
 pub(crate) struct InboxManager {
@@ -10,6 +20,22 @@ pub(crate) struct InboxManager {
     /// It is used to prevent out of memory errors in the case of client receiving a lot of data while
     /// offline and then loading it all at once when he comes back online.
     retrieval_limit: i64,
+
+    /// Maximum number of messages that can be stored for a single client.
+    max_client_messages: i64,
+
+    /// Maximum total size (in bytes) of messages that can be stored for a single client.
+    max_client_bytes: i64,
+
+    /// Number of messages removed since startup due to their client exceeding its quota.
+    evicted_messages: Arc<AtomicU64>,
+
+    /// Number of messages removed since startup due to exceeding their time to live.
+    expired_messages: Arc<AtomicU64>,
+
+    /// Number of messages removed from each inbox since they were last taken. They're only tracked
+    /// once they have been taken for the first time, so that nothing accumulates if nobody is interested.
+    removals: Arc<Mutex<Option<InboxRemovals>>>,
 }
 
 impl InboxManager {
@@ -18,14 +44,51 @@ impl InboxManager {
     /// # Arguments
     ///
     /// * `connection_pool`: database connection pool to use.
-    pub(crate) fn new(connection_pool: sqlx::SqlitePool, retrieval_limit: i64) -> Self {
+    /// * `retrieval_limit`: maximum number of messages that can be retrieved at once.
+    /// * `max_client_messages`: maximum number of messages that can be stored for a single client.
+    /// * `max_client_bytes`: maximum total size of messages that can be stored for a single client.
+    pub(crate) fn new(
+        connection_pool: sqlx::SqlitePool,
+        retrieval_limit: i64,
+        max_client_messages: i64,
+        max_client_bytes: i64,
+    ) -> Self {
         InboxManager {
             connection_pool,
             retrieval_limit,
+            max_client_messages,
+            max_client_bytes,
+            evicted_messages: Arc::new(AtomicU64::new(0)),
+            expired_messages: Arc::new(AtomicU64::new(0)),
+            removals: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn is_tracking_removals(&self) -> bool {
+        self.removals.lock().expect("mutex got poisoned").is_some()
+    }
+
+    fn record_removals<F>(&self, update: F)
+    where
+        F: FnOnce(&mut InboxRemovals),
+    {
+        if let Some(removals) = self.removals.lock().expect("mutex got poisoned").as_mut() {
+            update(removals)
         }
     }
 
+    /// Takes the number of messages removed from each inbox since the previous call.
+    /// Note that the removals are only tracked after this has been called for the first time.
+    pub(crate) fn take_removals(&self) -> InboxRemovals {
+        self.removals
+            .lock()
+            .expect("mutex got poisoned")
+            .replace(InboxRemovals::default())
+            .unwrap_or_default()
+    }
+
     /// Inserts new message to the storage for an offline client for future retrieval.
+    /// If the client has exceeded its quota as the result, its oldest messages are evicted.
     ///
     /// # Arguments
     ///
@@ -36,16 +99,157 @@ impl InboxManager {
         client_address_bs58: &str,
         content: Vec<u8>,
     ) -> Result<(), sqlx::Error> {
+        let timestamp = unix_timestamp(SystemTime::now());
+        let content_size = content.len() as i64;
+
+        // the insertion and the eviction must happen atomically so that the inbox is never over its quota
+        let mut tx = self.connection_pool.begin().await?;
         sqlx::query!(
-            "INSERT INTO message_store(client_address_bs58, content) VALUES (?, ?)",
+            "INSERT INTO message_store(client_address_bs58, content, timestamp, content_size) VALUES (?, ?, ?, ?)",
             client_address_bs58,
             content,
+            timestamp,
+            content_size,
         )
-        .execute(&self.connection_pool)
+        .execute(&mut tx)
         .await?;
+
+        let evicted = self
+            .enforce_client_quota(&mut tx, client_address_bs58)
+            .await?;
+        tx.commit().await?;
+
+        if evicted > 0 {
+            self.evicted_messages.fetch_add(evicted, Ordering::Relaxed);
+            self.record_removals(|removals| {
+                *removals
+                    .evicted_messages
+                    .entry(client_address_bs58.to_string())
+                    .or_default() += evicted
+            });
+        }
         Ok(())
     }
 
+    /// Removes the oldest messages of the particular client until both the number and the total size
+    /// of its stored messages are within the limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx`: transaction within which the new message has been inserted.
+    /// * `client_address_bs58`: base58-encoded address of the client
+    ///
+    /// returns the number of evicted messages.
+    async fn enforce_client_quota(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        client_address_bs58: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let usage = sqlx::query!(
+            r#"
+                SELECT COUNT(*) AS "stored_messages!: i64", COALESCE(SUM(content_size), 0) AS "stored_bytes!: i64"
+                FROM message_store
+                WHERE client_address_bs58 = ?;
+            "#,
+            client_address_bs58
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if usage.stored_messages <= self.max_client_messages
+            && usage.stored_bytes <= self.max_client_bytes
+        {
+            return Ok(0);
+        }
+
+        // going from the newest message, keep everything until either of the limits is reached
+        let evicted = sqlx::query!(
+            r#"
+                DELETE FROM message_store WHERE id IN (
+                    SELECT id FROM (
+                        SELECT id,
+                               ROW_NUMBER() OVER (ORDER BY id DESC) AS position,
+                               SUM(content_size) OVER (ORDER BY id DESC) AS cumulative_size
+                        FROM message_store
+                        WHERE client_address_bs58 = ?
+                    )
+                    WHERE position > ? OR cumulative_size > ?
+                );
+            "#,
+            client_address_bs58,
+            self.max_client_messages,
+            self.max_client_bytes,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        Ok(evicted)
+    }
+
+    /// Removes all messages that have been stored for longer than the specified duration.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl`: maximum duration for which a message can be stored.
+    ///
+    /// returns the number of removed messages.
+    pub(crate) async fn remove_expired_messages(&self, ttl: Duration) -> Result<u64, sqlx::Error> {
+        let cutoff = unix_timestamp(SystemTime::now().checked_sub(ttl).unwrap_or(UNIX_EPOCH));
+        let mut tx = self.connection_pool.begin().await?;
+
+        let mut expired_per_client = HashMap::new();
+        if self.is_tracking_removals() {
+            let expiring = sqlx::query!(
+                r#"
+                    SELECT client_address_bs58, COUNT(*) AS "expired!: i64"
+                    FROM message_store
+                    WHERE timestamp < ?
+                    GROUP BY client_address_bs58;
+                "#,
+                cutoff
+            )
+            .fetch_all(&mut tx)
+            .await?;
+            for row in expiring {
+                expired_per_client.insert(row.client_address_bs58, row.expired as u64);
+            }
+        }
+
+        let expired = sqlx::query!("DELETE FROM message_store WHERE timestamp < ?", cutoff)
+            .execute(&mut tx)
+            .await?
+            .rows_affected();
+        tx.commit().await?;
+
+        self.expired_messages.fetch_add(expired, Ordering::Relaxed);
+        self.record_removals(|removals| {
+            for (client, expired) in expired_per_client {
+                *removals.expired_messages.entry(client).or_default() += expired
+            }
+        });
+        Ok(expired)
+    }
+
+    /// Obtains the current usage of the message store alongside the number of messages removed since startup.
+    pub(crate) async fn get_statistics(&self) -> Result<InboxStatistics, sqlx::Error> {
+        let usage = sqlx::query!(
+            r#"
+                SELECT COUNT(*) AS "stored_messages!: i64", COALESCE(SUM(content_size), 0) AS "stored_bytes!: i64"
+                FROM message_store;
+            "#
+        )
+        .fetch_one(&self.connection_pool)
+        .await?;
+
+        Ok(InboxStatistics {
+            stored_messages: usage.stored_messages as u64,
+            stored_bytes: usage.stored_bytes as u64,
+            evicted_messages: self.evicted_messages.load(Ordering::Relaxed),
+            expired_messages: self.expired_messages.load(Ordering::Relaxed),
+        })
+    }
+
     /// Retrieves messages stored for the particular client specified by the provided address.
     ///
     /// It also respects the specified retrieval limit. If there are more messages stored than allowed
@@ -70,7 +274,7 @@ impl InboxManager {
             sqlx::query_as!(
                 StoredMessage,
                 r#"
-                    SELECT * FROM message_store 
+                    SELECT id, client_address_bs58, content FROM message_store
                     WHERE client_address_bs58 = ? AND id > ?
                     ORDER BY id ASC
                     LIMIT ?;
@@ -85,7 +289,7 @@ impl InboxManager {
             sqlx::query_as!(
                 StoredMessage,
                 r#"
-                    SELECT * FROM message_store 
+                    SELECT id, client_address_bs58, content FROM message_store
                     WHERE client_address_bs58 = ?
                     ORDER BY id ASC
                     LIMIT ?;
@@ -120,3 +324,165 @@ impl InboxManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn manager(max_client_messages: i64, max_client_bytes: i64) -> InboxManager {
+        // every connection to an in-memory database gets its own instance of it
+        let connection_pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations")
+            .run(&connection_pool)
+            .await
+            .unwrap();
+        InboxManager::new(connection_pool, 100, max_client_messages, max_client_bytes)
+    }
+
+    async fn insert_old_message(manager: &InboxManager, client_address_bs58: &str) {
+        sqlx::query!(
+            "INSERT INTO message_store(client_address_bs58, content, timestamp, content_size) VALUES (?, x'00', 0, 1)",
+            client_address_bs58
+        )
+        .execute(&manager.connection_pool)
+        .await
+        .unwrap();
+    }
+
+    async fn stored_contents(manager: &InboxManager, client_address_bs58: &str) -> Vec<Vec<u8>> {
+        let (messages, _) = manager
+            .get_messages(client_address_bs58, None)
+            .await
+            .unwrap();
+        messages
+            .into_iter()
+            .map(|message| message.content)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn message_count_quota_is_enforced() {
+        let manager = manager(3, 1000).await;
+        for i in 0..3 {
+            manager.insert_message("client", vec![i]).await.unwrap();
+        }
+        assert_eq!(stored_contents(&manager, "client").await.len(), 3);
+        assert_eq!(manager.get_statistics().await.unwrap().evicted_messages, 0);
+
+        for i in 3..5 {
+            manager.insert_message("client", vec![i]).await.unwrap();
+        }
+        assert_eq!(stored_contents(&manager, "client").await.len(), 3);
+        assert_eq!(manager.get_statistics().await.unwrap().evicted_messages, 2);
+    }
+
+    #[tokio::test]
+    async fn message_size_quota_is_enforced() {
+        let manager = manager(100, 10).await;
+        manager.insert_message("client", vec![1; 4]).await.unwrap();
+        manager.insert_message("client", vec![2; 4]).await.unwrap();
+        assert_eq!(manager.get_statistics().await.unwrap().stored_bytes, 8);
+
+        manager.insert_message("client", vec![3; 4]).await.unwrap();
+        assert_eq!(
+            stored_contents(&manager, "client").await,
+            vec![vec![2; 4], vec![3; 4]]
+        );
+
+        let stats = manager.get_statistics().await.unwrap();
+        assert_eq!(stats.stored_bytes, 8);
+        assert_eq!(stats.evicted_messages, 1);
+    }
+
+    #[tokio::test]
+    async fn oldest_messages_are_evicted_first() {
+        let manager = manager(2, 1000).await;
+        for i in 0..5 {
+            manager.insert_message("client", vec![i]).await.unwrap();
+        }
+        assert_eq!(
+            stored_contents(&manager, "client").await,
+            vec![vec![3], vec![4]]
+        );
+    }
+
+    #[tokio::test]
+    async fn quota_is_enforced_per_client() {
+        let manager = manager(2, 1000).await;
+        for i in 0..3 {
+            manager.insert_message("client1", vec![i]).await.unwrap();
+        }
+        manager.insert_message("client2", vec![42]).await.unwrap();
+
+        assert_eq!(
+            stored_contents(&manager, "client1").await,
+            vec![vec![1], vec![2]]
+        );
+        assert_eq!(stored_contents(&manager, "client2").await, vec![vec![42]]);
+    }
+
+    #[tokio::test]
+    async fn only_expired_messages_are_removed() {
+        let manager = manager(100, 1000).await;
+        insert_old_message(&manager, "client").await;
+        manager.insert_message("client", vec![42]).await.unwrap();
+
+        let expired = manager
+            .remove_expired_messages(Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(expired, 1);
+        assert_eq!(stored_contents(&manager, "client").await, vec![vec![42]]);
+
+        // nothing else is old enough
+        let expired = manager
+            .remove_expired_messages(Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(expired, 0);
+        assert_eq!(manager.get_statistics().await.unwrap().expired_messages, 1);
+    }
+
+    #[tokio::test]
+    async fn removals_are_only_tracked_once_taken() {
+        let manager = manager(1, 1000).await;
+        manager.insert_message("client", vec![1]).await.unwrap();
+        manager.insert_message("client", vec![2]).await.unwrap();
+        assert!(manager.take_removals().evicted_messages.is_empty());
+
+        manager.insert_message("client", vec![3]).await.unwrap();
+        let removals = manager.take_removals();
+        assert_eq!(removals.evicted_messages.get("client"), Some(&1));
+
+        // the removals are reset once taken
+        assert!(manager.take_removals().evicted_messages.is_empty());
+        assert_eq!(manager.get_statistics().await.unwrap().evicted_messages, 2);
+    }
+
+    #[tokio::test]
+    async fn expired_messages_are_tracked_per_client() {
+        let manager = manager(100, 1000).await;
+        manager.take_removals();
+
+        insert_old_message(&manager, "client1").await;
+        insert_old_message(&manager, "client1").await;
+        insert_old_message(&manager, "client2").await;
+        manager.insert_message("client2", vec![1]).await.unwrap();
+
+        let expired = manager
+            .remove_expired_messages(Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(expired, 3);
+
+        let removals = manager.take_removals();
+        assert_eq!(removals.expired_messages.get("client1"), Some(&2));
+        assert_eq!(removals.expired_messages.get("client2"), Some(&1));
+        assert_eq!(manager.get_statistics().await.unwrap().stored_messages, 1);
+    }
+}
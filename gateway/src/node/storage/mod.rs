@@ -4,20 +4,25 @@
 use crate::node::storage::bandwidth::BandwidthManager;
 use crate::node::storage::error::StorageError;
 use crate::node::storage::inboxes::InboxManager;
-use crate::node::storage::models::{PersistedSharedKeys, StoredMessage};
+use crate::node::storage::models::{
+    InboxRemovals, InboxStatistics, PersistedSharedKeys, StoredMessage, UnsettledCredential,
+};
 use crate::node::storage::shared_keys::SharedKeysManager;
+use crate::node::storage::spent_credentials::SpentCredentialsManager;
 use async_trait::async_trait;
 use log::{debug, error};
 use nym_gateway_requests::registration::handshake::SharedKeys;
 use nym_sphinx::DestinationAddressBytes;
 use sqlx::ConnectOptions;
 use std::path::Path;
+use std::time::Duration;
 
 mod bandwidth;
 pub(crate) mod error;
 mod inboxes;
-mod models;
+pub(crate) mod models;
 mod shared_keys;
+mod spent_credentials;
 
 #[async_trait]
 pub(crate) trait Storage: Send + Sync {
@@ -91,6 +96,22 @@ pub(crate) trait Storage: Send + Sync {
     /// * `ids`: ids of the messages to remove
     async fn remove_messages(&self, ids: Vec<i64>) -> Result<(), StorageError>;
 
+    /// Removes all messages that have been stored for longer than the specified duration.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl`: maximum duration for which a message can be stored.
+    ///
+    /// returns the number of removed messages.
+    async fn remove_expired_messages(&self, ttl: Duration) -> Result<u64, StorageError>;
+
+    /// Retrieves statistics about messages stored for offline clients.
+    async fn get_inbox_statistics(&self) -> Result<InboxStatistics, StorageError>;
+
+    /// Takes the number of messages removed from each inbox since the previous call.
+    /// Note that the removals are only tracked after this has been called for the first time.
+    async fn take_inbox_removals(&self) -> Result<InboxRemovals, StorageError>;
+
     /// Creates a new bandwidth entry for the particular client.
     ///
     /// # Arguments
@@ -134,6 +155,64 @@ pub(crate) trait Storage: Send + Sync {
         client_address: DestinationAddressBytes,
         amount: i64,
     ) -> Result<(), StorageError>;
+
+    /// Marks the credential with the provided blinded serial number as spent at this gateway.
+    /// Returns `false` if the credential has already been spent here before.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number_bs58`: base58-encoded blinded serial number of the credential.
+    /// * `credential`: serialized credential that is going to be used for on-chain settlement.
+    async fn insert_spent_credential(
+        &self,
+        blinded_serial_number_bs58: &str,
+        credential: Vec<u8>,
+    ) -> Result<bool, StorageError>;
+
+    /// Retrieves credentials that have been accepted by this gateway, but were not yet settled on chain.
+    /// Credentials that have permanently failed to get settled are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit`: maximum number of credentials to retrieve.
+    async fn get_unsettled_credentials(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<UnsettledCredential>, StorageError>;
+
+    /// Marks the credential with the provided blinded serial number as settled on chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number_bs58`: base58-encoded blinded serial number of the credential.
+    async fn mark_credential_as_settled(
+        &self,
+        blinded_serial_number_bs58: &str,
+    ) -> Result<(), StorageError>;
+
+    /// Marks the credential with the provided blinded serial number as one that can never get settled.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number_bs58`: base58-encoded blinded serial number of the credential.
+    async fn mark_credential_settlement_as_failed(
+        &self,
+        blinded_serial_number_bs58: &str,
+    ) -> Result<(), StorageError>;
+
+    /// Records failure to settle the credential with the provided blinded serial number.
+    /// Returns whether the credential has reached the maximum number of attempts
+    /// and thus has been marked as failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number_bs58`: base58-encoded blinded serial number of the credential.
+    /// * `max_failed_attempts`: number of failed attempts after which no further attempts are made.
+    async fn record_failed_credential_settlement(
+        &self,
+        blinded_serial_number_bs58: &str,
+        max_failed_attempts: i64,
+    ) -> Result<bool, StorageError>;
 }
 
 // note that clone here is fine as upon cloning the same underlying pool will be used
@@ -142,6 +221,7 @@ pub(crate) struct PersistentStorage {
     shared_key_manager: SharedKeysManager,
     inbox_manager: InboxManager,
     bandwidth_manager: BandwidthManager,
+    spent_credentials_manager: SpentCredentialsManager,
 }
 
 impl PersistentStorage {
@@ -151,9 +231,13 @@ impl PersistentStorage {
     ///
     /// * `database_path`: path to the database.
     /// * `message_retrieval_limit`: maximum number of stored client messages that can be retrieved at once.
+    /// * `maximum_client_inbox_messages`: maximum number of messages that can be stored for a single client.
+    /// * `maximum_client_inbox_size`: maximum total size of messages that can be stored for a single client.
     pub async fn init<P: AsRef<Path> + Send>(
         database_path: P,
         message_retrieval_limit: i64,
+        maximum_client_inbox_messages: i64,
+        maximum_client_inbox_size: i64,
     ) -> Result<Self, StorageError> {
         debug!(
             "Attempting to connect to database {:?}",
@@ -186,8 +270,14 @@ impl PersistentStorage {
         // the cloning here are cheap as connection pool is stored behind an Arc
         Ok(PersistentStorage {
             shared_key_manager: SharedKeysManager::new(connection_pool.clone()),
-            inbox_manager: InboxManager::new(connection_pool.clone(), message_retrieval_limit),
-            bandwidth_manager: BandwidthManager::new(connection_pool),
+            inbox_manager: InboxManager::new(
+                connection_pool.clone(),
+                message_retrieval_limit,
+                maximum_client_inbox_messages,
+                maximum_client_inbox_size,
+            ),
+            bandwidth_manager: BandwidthManager::new(connection_pool.clone()),
+            spent_credentials_manager: SpentCredentialsManager::new(connection_pool),
         })
     }
 }
@@ -261,6 +351,20 @@ impl Storage for PersistentStorage {
         Ok(())
     }
 
+    async fn remove_expired_messages(&self, ttl: Duration) -> Result<u64, StorageError> {
+        let removed = self.inbox_manager.remove_expired_messages(ttl).await?;
+        Ok(removed)
+    }
+
+    async fn get_inbox_statistics(&self) -> Result<InboxStatistics, StorageError> {
+        let statistics = self.inbox_manager.get_statistics().await?;
+        Ok(statistics)
+    }
+
+    async fn take_inbox_removals(&self) -> Result<InboxRemovals, StorageError> {
+        Ok(self.inbox_manager.take_removals())
+    }
+
     async fn create_bandwidth_entry(
         &self,
         client_address: DestinationAddressBytes,
@@ -304,6 +408,61 @@ impl Storage for PersistentStorage {
             .await?;
         Ok(())
     }
+
+    async fn insert_spent_credential(
+        &self,
+        blinded_serial_number_bs58: &str,
+        credential: Vec<u8>,
+    ) -> Result<bool, StorageError> {
+        let inserted = self
+            .spent_credentials_manager
+            .insert_spent_credential(blinded_serial_number_bs58, credential)
+            .await?;
+        Ok(inserted)
+    }
+
+    async fn get_unsettled_credentials(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<UnsettledCredential>, StorageError> {
+        let credentials = self
+            .spent_credentials_manager
+            .get_unsettled_credentials(limit)
+            .await?;
+        Ok(credentials)
+    }
+
+    async fn mark_credential_as_settled(
+        &self,
+        blinded_serial_number_bs58: &str,
+    ) -> Result<(), StorageError> {
+        self.spent_credentials_manager
+            .mark_as_settled(blinded_serial_number_bs58)
+            .await?;
+        Ok(())
+    }
+
+    async fn mark_credential_settlement_as_failed(
+        &self,
+        blinded_serial_number_bs58: &str,
+    ) -> Result<(), StorageError> {
+        self.spent_credentials_manager
+            .mark_as_failed(blinded_serial_number_bs58)
+            .await?;
+        Ok(())
+    }
+
+    async fn record_failed_credential_settlement(
+        &self,
+        blinded_serial_number_bs58: &str,
+        max_failed_attempts: i64,
+    ) -> Result<bool, StorageError> {
+        let failed = self
+            .spent_credentials_manager
+            .increment_failed_settlement_attempts(blinded_serial_number_bs58, max_failed_attempts)
+            .await?;
+        Ok(failed)
+    }
 }
 
 /// In-memory implementation of `Storage`. The intention is primarily in testing environments.
@@ -364,6 +523,18 @@ impl Storage for InMemStorage {
         todo!()
     }
 
+    async fn remove_expired_messages(&self, _ttl: Duration) -> Result<u64, StorageError> {
+        todo!()
+    }
+
+    async fn get_inbox_statistics(&self) -> Result<InboxStatistics, StorageError> {
+        todo!()
+    }
+
+    async fn take_inbox_removals(&self) -> Result<InboxRemovals, StorageError> {
+        todo!()
+    }
+
     async fn create_bandwidth_entry(
         &self,
         _client_address: DestinationAddressBytes,
@@ -393,4 +564,41 @@ impl Storage for InMemStorage {
     ) -> Result<(), StorageError> {
         todo!()
     }
+
+    async fn insert_spent_credential(
+        &self,
+        _blinded_serial_number_bs58: &str,
+        _credential: Vec<u8>,
+    ) -> Result<bool, StorageError> {
+        todo!()
+    }
+
+    async fn get_unsettled_credentials(
+        &self,
+        _limit: i64,
+    ) -> Result<Vec<UnsettledCredential>, StorageError> {
+        todo!()
+    }
+
+    async fn mark_credential_as_settled(
+        &self,
+        _blinded_serial_number_bs58: &str,
+    ) -> Result<(), StorageError> {
+        todo!()
+    }
+
+    async fn mark_credential_settlement_as_failed(
+        &self,
+        _blinded_serial_number_bs58: &str,
+    ) -> Result<(), StorageError> {
+        todo!()
+    }
+
+    async fn record_failed_credential_settlement(
+        &self,
+        _blinded_serial_number_bs58: &str,
+        _max_failed_attempts: i64,
+    ) -> Result<bool, StorageError> {
+        todo!()
+    }
 }
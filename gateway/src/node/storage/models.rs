@@ -1,6 +1,8 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
+
 pub(crate) struct PersistedSharedKeys {
     pub(crate) client_address_bs58: String,
     pub(crate) derived_aes128_ctr_blake3_hmac_keys_bs58: String,
@@ -13,8 +15,37 @@ pub(crate) struct StoredMessage {
     pub(crate) content: Vec<u8>,
 }
 
+pub(crate) struct InboxStatistics {
+    /// Number of messages currently stored for all offline clients.
+    pub(crate) stored_messages: u64,
+
+    /// Total size (in bytes) of messages currently stored for all offline clients.
+    pub(crate) stored_bytes: u64,
+
+    /// Number of messages removed since startup due to their client exceeding its quota.
+    pub(crate) evicted_messages: u64,
+
+    /// Number of messages removed since startup due to exceeding their time to live.
+    pub(crate) expired_messages: u64,
+}
+
+/// Number of messages removed from each of the client inboxes within some period.
+#[derive(Debug, Default)]
+pub(crate) struct InboxRemovals {
+    /// Number of messages evicted from each inbox due to its client exceeding its quota.
+    pub(crate) evicted_messages: HashMap<String, u64>,
+
+    /// Number of messages removed from each inbox due to exceeding their time to live.
+    pub(crate) expired_messages: HashMap<String, u64>,
+}
+
 pub(crate) struct PersistedBandwidth {
     #[allow(dead_code)]
     pub(crate) client_address_bs58: String,
     pub(crate) available: i64,
 }
+
+pub(crate) struct UnsettledCredential {
+    pub(crate) blinded_serial_number_bs58: String,
+    pub(crate) credential: Vec<u8>,
+}
@@ -0,0 +1,272 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::storage::models::UnsettledCredential;
+
+#[derive(Clone)]
+pub(crate) struct SpentCredentialsManager {
+    connection_pool: sqlx::SqlitePool,
+}
+
+impl SpentCredentialsManager {
+    /// Creates new instance of the `SpentCredentialsManager` with the provided sqlite connection pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection_pool`: database connection pool to use.
+    pub(crate) fn new(connection_pool: sqlx::SqlitePool) -> Self {
+        SpentCredentialsManager { connection_pool }
+    }
+
+    /// Attempts to insert the credential with the provided blinded serial number to the storage.
+    /// Returns whether the credential was inserted, i.e. `false` if it has been spent before.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number_bs58`: base58-encoded blinded serial number of the credential.
+    /// * `credential`: serialized credential that is going to be used for settlement.
+    pub(crate) async fn insert_spent_credential(
+        &self,
+        blinded_serial_number_bs58: &str,
+        credential: Vec<u8>,
+    ) -> Result<bool, sqlx::Error> {
+        let res = sqlx::query!(
+            r#"
+                INSERT OR IGNORE INTO spent_credential(blinded_serial_number_bs58, credential)
+                VALUES (?, ?)
+            "#,
+            blinded_serial_number_bs58,
+            credential
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(res.rows_affected() == 1)
+    }
+
+    /// Retrieves credentials that have been accepted, but not yet settled on chain
+    /// and that haven't failed to get settled permanently.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit`: maximum number of credentials to retrieve.
+    pub(crate) async fn get_unsettled_credentials(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<UnsettledCredential>, sqlx::Error> {
+        sqlx::query_as!(
+            UnsettledCredential,
+            r#"
+                SELECT blinded_serial_number_bs58, credential
+                FROM spent_credential
+                WHERE settled = FALSE AND settlement_failed = FALSE
+                ORDER BY rowid ASC
+                LIMIT ?;
+            "#,
+            limit
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
+    /// Marks the credential with the provided blinded serial number as settled on chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number_bs58`: base58-encoded blinded serial number of the credential.
+    pub(crate) async fn mark_as_settled(
+        &self,
+        blinded_serial_number_bs58: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE spent_credential SET settled = TRUE WHERE blinded_serial_number_bs58 = ?",
+            blinded_serial_number_bs58
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Marks the credential with the provided blinded serial number as one that can never get settled,
+    /// so that no further attempts are made.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number_bs58`: base58-encoded blinded serial number of the credential.
+    pub(crate) async fn mark_as_failed(
+        &self,
+        blinded_serial_number_bs58: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE spent_credential SET settlement_failed = TRUE WHERE blinded_serial_number_bs58 = ?",
+            blinded_serial_number_bs58
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Increments the number of failed settlement attempts of the credential with the provided
+    /// blinded serial number. Once it reaches the specified maximum, the credential is marked as failed.
+    /// Returns whether that has happened.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number_bs58`: base58-encoded blinded serial number of the credential.
+    /// * `max_failed_attempts`: number of failed attempts after which no further attempts are made.
+    pub(crate) async fn increment_failed_settlement_attempts(
+        &self,
+        blinded_serial_number_bs58: &str,
+        max_failed_attempts: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.connection_pool.begin().await?;
+        sqlx::query!(
+            r#"
+                UPDATE spent_credential
+                SET failed_settlement_attempts = failed_settlement_attempts + 1,
+                    settlement_failed = settlement_failed OR failed_settlement_attempts + 1 >= ?
+                WHERE blinded_serial_number_bs58 = ?
+            "#,
+            max_failed_attempts,
+            blinded_serial_number_bs58
+        )
+        .execute(&mut tx)
+        .await?;
+
+        let failed = sqlx::query!(
+            "SELECT settlement_failed FROM spent_credential WHERE blinded_serial_number_bs58 = ?",
+            blinded_serial_number_bs58
+        )
+        .fetch_optional(&mut tx)
+        .await?
+        .map(|row| row.settlement_failed)
+        .unwrap_or_default();
+        tx.commit().await?;
+
+        Ok(failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn manager() -> SpentCredentialsManager {
+        // every connection to an in-memory database gets its own instance of it
+        let connection_pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations")
+            .run(&connection_pool)
+            .await
+            .unwrap();
+        SpentCredentialsManager::new(connection_pool)
+    }
+
+    fn serial_numbers(credentials: Vec<UnsettledCredential>) -> Vec<String> {
+        credentials
+            .into_iter()
+            .map(|credential| credential.blinded_serial_number_bs58)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn credentials_can_only_be_spent_once() {
+        let manager = manager().await;
+
+        assert!(manager
+            .insert_spent_credential("first", vec![1, 2, 3])
+            .await
+            .unwrap());
+        assert!(!manager
+            .insert_spent_credential("first", vec![4, 5, 6])
+            .await
+            .unwrap());
+
+        let unsettled = manager.get_unsettled_credentials(10).await.unwrap();
+        assert_eq!(unsettled.len(), 1);
+        assert_eq!(unsettled[0].credential, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn unsettled_credentials_are_retrieved_in_insertion_order() {
+        let manager = manager().await;
+        for serial_number in ["c", "a", "b"] {
+            manager
+                .insert_spent_credential(serial_number, vec![])
+                .await
+                .unwrap();
+        }
+
+        let unsettled = manager.get_unsettled_credentials(10).await.unwrap();
+        assert_eq!(serial_numbers(unsettled), vec!["c", "a", "b"]);
+
+        let unsettled = manager.get_unsettled_credentials(2).await.unwrap();
+        assert_eq!(serial_numbers(unsettled), vec!["c", "a"]);
+    }
+
+    #[tokio::test]
+    async fn settled_and_failed_credentials_are_not_retrieved() {
+        let manager = manager().await;
+        for serial_number in ["settled", "failed", "pending"] {
+            manager
+                .insert_spent_credential(serial_number, vec![])
+                .await
+                .unwrap();
+        }
+        manager.mark_as_settled("settled").await.unwrap();
+        manager.mark_as_failed("failed").await.unwrap();
+
+        let unsettled = manager.get_unsettled_credentials(10).await.unwrap();
+        assert_eq!(serial_numbers(unsettled), vec!["pending"]);
+    }
+
+    #[tokio::test]
+    async fn credentials_fail_permanently_after_maximum_attempts() {
+        let manager = manager().await;
+        manager
+            .insert_spent_credential("credential", vec![])
+            .await
+            .unwrap();
+
+        assert!(!manager
+            .increment_failed_settlement_attempts("credential", 3)
+            .await
+            .unwrap());
+        assert!(!manager
+            .increment_failed_settlement_attempts("credential", 3)
+            .await
+            .unwrap());
+        assert_eq!(
+            manager.get_unsettled_credentials(10).await.unwrap().len(),
+            1
+        );
+
+        assert!(manager
+            .increment_failed_settlement_attempts("credential", 3)
+            .await
+            .unwrap());
+        assert!(manager
+            .get_unsettled_credentials(10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // the failure is terminal even if the limit gets increased afterwards
+        assert!(manager
+            .increment_failed_settlement_attempts("credential", 10)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn failing_unknown_credential_is_not_an_error() {
+        let manager = manager().await;
+        assert!(!manager
+            .increment_failed_settlement_attempts("unknown", 1)
+            .await
+            .unwrap());
+    }
+}
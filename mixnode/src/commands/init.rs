@@ -4,9 +4,13 @@
 use super::OverrideConfig;
 use crate::config::Config;
 use crate::node::MixNode;
-use crate::{commands::override_config, config::persistence::pathfinder::MixNodePathfinder};
+use crate::{
+    commands::{override_config, read_key_passphrase_or_exit},
+    config::persistence::pathfinder::MixNodePathfinder,
+};
 use clap::Args;
 use nym_bin_common::output_format::OutputFormat;
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use nym_config::NymConfig;
 use nym_crypto::asymmetric::{encryption, identity};
 use nym_validator_client::nyxd;
@@ -49,6 +53,9 @@ pub(crate) struct Init {
 
     #[clap(short, long, default_value_t = OutputFormat::default())]
     output: OutputFormat,
+
+    #[clap(flatten)]
+    key_passphrase: ArgKeyPassphrase,
 }
 
 impl From<Init> for OverrideConfig {
@@ -67,6 +74,7 @@ impl From<Init> for OverrideConfig {
 }
 
 pub(crate) fn execute(args: &Init) {
+    let key_passphrase = read_key_passphrase_or_exit(&args.key_passphrase);
     let override_config_fields = OverrideConfig::from(args.clone());
     let id = &override_config_fields.id;
     eprintln!("Initialising mixnode {id}...");
@@ -88,21 +96,23 @@ pub(crate) fn execute(args: &Init) {
         let identity_keys = identity::KeyPair::new(&mut rng);
         let sphinx_keys = encryption::KeyPair::new(&mut rng);
         let pathfinder = MixNodePathfinder::new_from_config(&config);
-        nym_pemstore::store_keypair(
+        nym_pemstore::store_keypair_with_passphrase(
             &identity_keys,
             &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_identity_key().to_owned(),
                 pathfinder.public_identity_key().to_owned(),
             ),
+            key_passphrase.as_deref().map(str::as_bytes),
         )
         .expect("Failed to save identity keys");
 
-        nym_pemstore::store_keypair(
+        nym_pemstore::store_keypair_with_passphrase(
             &sphinx_keys,
             &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_encryption_key().to_owned(),
                 pathfinder.public_encryption_key().to_owned(),
             ),
+            key_passphrase.as_deref().map(str::as_bytes),
         )
         .expect("Failed to save sphinx keys");
         eprintln!("Saved mixnet identity and sphinx keypairs");
@@ -115,5 +125,5 @@ pub(crate) fn execute(args: &Init) {
     eprintln!("Saved configuration file to {config_save_location:?}");
     eprintln!("Mixnode configuration completed.\n\n\n");
 
-    MixNode::new(config).print_node_details(args.output)
+    MixNode::new(config, key_passphrase.as_deref()).print_node_details(args.output)
 }
@@ -6,6 +6,7 @@ use clap::CommandFactory;
 use clap::Subcommand;
 use colored::Colorize;
 use nym_bin_common::completions::{fig_generate, ArgShell};
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use nym_bin_common::version_checker;
 use nym_config::defaults::var_names::{BECH32_PREFIX, NYM_API};
 use nym_config::OptionalSet;
@@ -131,6 +132,19 @@ pub(crate) fn validate_bech32_address_or_exit(address: &str) {
     }
 }
 
+/// Reads the passphrase used for encrypting the private keys (if specified), or exits
+pub(crate) fn read_key_passphrase_or_exit(args: &ArgKeyPassphrase) -> Option<String> {
+    match args.read_passphrase() {
+        Ok(passphrase) => passphrase,
+        Err(err) => {
+            let error_message = format!("Error: failed to read the key passphrase: {err}").red();
+            error!("{}", error_message);
+            error!("Exiting...");
+            process::exit(1);
+        }
+    }
+}
+
 // this only checks compatibility between config the binary. It does not take into consideration
 // network version. It might do so in the future.
 pub(crate) fn version_check(cfg: &Config) -> bool {
@@ -1,10 +1,12 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::commands::read_key_passphrase_or_exit;
 use crate::config::Config;
 use crate::node::MixNode;
 use clap::Args;
 use nym_bin_common::output_format::OutputFormat;
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use nym_config::NymConfig;
 
 #[derive(Args)]
@@ -15,6 +17,9 @@ pub(crate) struct NodeDetails {
 
     #[clap(short, long, default_value_t = OutputFormat::default())]
     output: OutputFormat,
+
+    #[clap(flatten)]
+    key_passphrase: ArgKeyPassphrase,
 }
 
 pub(crate) fn execute(args: &NodeDetails) {
@@ -30,5 +35,6 @@ pub(crate) fn execute(args: &NodeDetails) {
         }
     };
 
-    MixNode::new(config).print_node_details(args.output)
+    let key_passphrase = read_key_passphrase_or_exit(&args.key_passphrase);
+    MixNode::new(config, key_passphrase.as_deref()).print_node_details(args.output)
 }
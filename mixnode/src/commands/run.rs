@@ -2,11 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::OverrideConfig;
-use crate::commands::{override_config, version_check};
+use crate::commands::{override_config, read_key_passphrase_or_exit, version_check};
 use crate::config::Config;
 use crate::node::MixNode;
 use clap::Args;
 use nym_bin_common::output_format::OutputFormat;
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use nym_config::NymConfig;
 use nym_validator_client::nyxd;
 use std::net::IpAddr;
@@ -48,6 +49,9 @@ pub(crate) struct Run {
 
     #[clap(short, long, default_value_t = OutputFormat::default())]
     output: OutputFormat,
+
+    #[clap(flatten)]
+    key_passphrase: ArgKeyPassphrase,
 }
 
 impl From<Run> for OverrideConfig {
@@ -105,7 +109,8 @@ pub(crate) async fn execute(args: &Run) {
         show_binding_warning(&config.get_listening_address().to_string());
     }
 
-    let mut mixnode = MixNode::new(config);
+    let key_passphrase = read_key_passphrase_or_exit(&args.key_passphrase);
+    let mut mixnode = MixNode::new(config, key_passphrase.as_deref());
 
     eprintln!(
         "\nTo bond your mixnode you will need to install the Nym wallet, go to https://nymtech.net/get-involved and select the Download button.\n\
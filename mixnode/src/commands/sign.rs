@@ -3,12 +3,13 @@
 
 use std::convert::TryFrom;
 
-use crate::commands::validate_bech32_address_or_exit;
+use crate::commands::{read_key_passphrase_or_exit, validate_bech32_address_or_exit};
 use crate::config::{persistence::pathfinder::MixNodePathfinder, Config};
 use crate::node::MixNode;
 use anyhow::{bail, Result};
 use clap::{ArgGroup, Args};
 use nym_bin_common::output_format::OutputFormat;
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use nym_config::NymConfig;
 use nym_crypto::asymmetric::identity;
 use nym_types::helpers::ConsoleSigningOutput;
@@ -40,6 +41,9 @@ pub(crate) struct Sign {
 
     #[clap(short, long, default_value_t = OutputFormat::default())]
     output: OutputFormat,
+
+    #[clap(flatten)]
+    key_passphrase: ArgKeyPassphrase,
 }
 
 enum SignedTarget {
@@ -141,7 +145,8 @@ pub(crate) fn execute(args: &Sign) {
         }
     };
     let pathfinder = MixNodePathfinder::new_from_config(&config);
-    let identity_keypair = MixNode::load_identity_keys(&pathfinder);
+    let key_passphrase = read_key_passphrase_or_exit(&args.key_passphrase);
+    let identity_keypair = MixNode::load_identity_keys(&pathfinder, key_passphrase.as_deref());
 
     match signed_target {
         SignedTarget::Text(text) => {
@@ -1,8 +1,12 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::commands::read_key_passphrase_or_exit;
+use crate::config::persistence::pathfinder::MixNodePathfinder;
 use crate::config::{missing_string_value, Config};
+use crate::node::MixNode;
 use clap::Args;
+use nym_bin_common::passphrase::ArgKeyPassphrase;
 use nym_bin_common::version_checker::Version;
 use nym_config::NymConfig;
 use std::fmt::Display;
@@ -13,6 +17,12 @@ pub(crate) struct Upgrade {
     /// Id of the nym-mixnode we want to upgrade
     #[clap(long)]
     id: String,
+
+    // if specified, any plaintext private keys will get encrypted with the provided passphrase.
+    // keys that are already encrypted must have been encrypted with the same passphrase,
+    // as changing it is not supported
+    #[clap(flatten)]
+    key_passphrase: ArgKeyPassphrase,
 }
 
 #[allow(dead_code)]
@@ -122,6 +132,42 @@ fn do_upgrade(mut config: Config, args: &Upgrade, package_version: Version) {
     }
 }
 
+fn encrypt_stored_keys(config: &Config, key_passphrase: &str) {
+    let pathfinder = MixNodePathfinder::new_from_config(config);
+
+    // note: plaintext keys are going to be loaded regardless of the passphrase
+    let identity_keys = MixNode::load_identity_keys(&pathfinder, Some(key_passphrase));
+    let sphinx_keys = MixNode::load_sphinx_keys(&pathfinder, Some(key_passphrase));
+
+    nym_pemstore::store_keypair_with_passphrase(
+        &identity_keys,
+        &nym_pemstore::KeyPairPath::new(
+            pathfinder.private_identity_key().to_owned(),
+            pathfinder.public_identity_key().to_owned(),
+        ),
+        Some(key_passphrase.as_bytes()),
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("failed to encrypt the identity keys! - {err}");
+        process::exit(1)
+    });
+
+    nym_pemstore::store_keypair_with_passphrase(
+        &sphinx_keys,
+        &nym_pemstore::KeyPairPath::new(
+            pathfinder.private_encryption_key().to_owned(),
+            pathfinder.public_encryption_key().to_owned(),
+        ),
+        Some(key_passphrase.as_bytes()),
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("failed to encrypt the sphinx keys! - {err}");
+        process::exit(1)
+    });
+
+    println!("Encrypted the stored private keys");
+}
+
 pub(crate) fn execute(args: &Upgrade) {
     let package_version = parse_package_version();
 
@@ -135,5 +181,9 @@ pub(crate) fn execute(args: &Upgrade) {
         process::exit(1);
     }
 
+    if let Some(key_passphrase) = read_key_passphrase_or_exit(&args.key_passphrase) {
+        encrypt_stored_keys(&existing_config, &key_passphrase);
+    }
+
     do_upgrade(existing_config, args, package_version)
 }
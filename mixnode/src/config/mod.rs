@@ -29,6 +29,15 @@ const DEFAULT_BATCH_SIZE: usize = 50;
 const DEFAULT_TESTING_INTERVAL: Duration = Duration::from_secs(60 * 60 * 12);
 const DEFAULT_RETRY_TIMEOUT: Duration = Duration::from_secs(60 * 30);
 
+// 'SPHINX KEY ROTATION'
+const DEFAULT_EPOCHS_PER_SPHINX_KEY: u32 = 24;
+const DEFAULT_SPHINX_KEY_GRACE_PERIOD: Duration = Duration::from_secs(15 * 60);
+const DEFAULT_EPOCH_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+// 'NOISE'
+const DEFAULT_NOISE_KEY_REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const DEFAULT_NOISE_HANDSHAKE_TIMEOUT: Duration = Duration::from_millis(5_000);
+
 // 'DEBUG'
 const DEFAULT_NODE_STATS_LOGGING_DELAY: Duration = Duration::from_millis(60_000);
 const DEFAULT_NODE_STATS_UPDATING_DELAY: Duration = Duration::from_millis(30_000);
@@ -81,6 +90,10 @@ pub struct Config {
     #[serde(default)]
     verloc: Verloc,
     #[serde(default)]
+    sphinx_key_rotation: SphinxKeyRotation,
+    #[serde(default)]
+    noise: Noise,
+    #[serde(default)]
     logging: Logging,
     #[serde(default)]
     debug: Debug,
@@ -226,6 +239,10 @@ impl Config {
         self.mixnode.public_sphinx_key_file.clone()
     }
 
+    pub fn get_rotated_sphinx_keys_directory(&self) -> PathBuf {
+        Config::default_data_directory(&self.mixnode.id).join("rotated_sphinx_keys")
+    }
+
     pub fn get_nym_api_endpoints(&self) -> Vec<Url> {
         self.mixnode.nym_api_urls.clone()
     }
@@ -310,6 +327,38 @@ impl Config {
         self.verloc.retry_timeout
     }
 
+    pub fn get_sphinx_key_rotation_enabled(&self) -> bool {
+        self.sphinx_key_rotation.enabled
+    }
+
+    pub fn get_epochs_per_sphinx_key(&self) -> u32 {
+        self.sphinx_key_rotation.epochs_per_key
+    }
+
+    pub fn get_sphinx_key_grace_period(&self) -> Duration {
+        self.sphinx_key_rotation.grace_period
+    }
+
+    pub fn get_epoch_check_interval(&self) -> Duration {
+        self.sphinx_key_rotation.epoch_check_interval
+    }
+
+    pub fn get_noise_enabled(&self) -> bool {
+        self.noise.enabled
+    }
+
+    pub fn get_accept_legacy_connections(&self) -> bool {
+        self.noise.accept_legacy_connections
+    }
+
+    pub fn get_noise_key_refresh_interval(&self) -> Duration {
+        self.noise.key_refresh_interval
+    }
+
+    pub fn get_noise_handshake_timeout(&self) -> Duration {
+        self.noise.handshake_timeout
+    }
+
     pub fn get_wallet_address(&self) -> Option<nyxd::AccountId> {
         self.mixnode.wallet_address.clone()
     }
@@ -457,6 +506,67 @@ impl Default for Verloc {
     }
 }
 
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+struct SphinxKeyRotation {
+    /// Specifies whether the sphinx key should be periodically replaced with a freshly generated one.
+    /// Otherwise the key from the bond is used indefinitely.
+    enabled: bool,
+
+    /// Number of epochs during which a single sphinx key is used.
+    epochs_per_key: u32,
+
+    /// Specifies for how long packets created for the previous key are still accepted after the rotation.
+    #[serde(with = "humantime_serde")]
+    grace_period: Duration,
+
+    /// Specifies how often the node checks whether the epoch has advanced.
+    #[serde(with = "humantime_serde")]
+    epoch_check_interval: Duration,
+}
+
+impl Default for SphinxKeyRotation {
+    fn default() -> Self {
+        SphinxKeyRotation {
+            enabled: true,
+            epochs_per_key: DEFAULT_EPOCHS_PER_SPHINX_KEY,
+            grace_period: DEFAULT_SPHINX_KEY_GRACE_PERIOD,
+            epoch_check_interval: DEFAULT_EPOCH_CHECK_INTERVAL,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+struct Noise {
+    /// Specifies whether the connections to other nodes should be encrypted and authenticated
+    /// with the noise protocol using the identity keys from the topology.
+    enabled: bool,
+
+    /// Specifies whether unencrypted connections should still be used with the nodes that do not
+    /// support noise yet. It should only be disabled once the entire network got upgraded.
+    accept_legacy_connections: bool,
+
+    /// Specifies how often the noise keys of other nodes are refreshed from the topology.
+    #[serde(with = "humantime_serde")]
+    key_refresh_interval: Duration,
+
+    /// Maximum allowed duration of the noise handshake.
+    #[serde(with = "humantime_serde")]
+    handshake_timeout: Duration,
+}
+
+impl Default for Noise {
+    fn default() -> Self {
+        Noise {
+            enabled: false,
+            accept_legacy_connections: true,
+            key_refresh_interval: DEFAULT_NOISE_KEY_REFRESH_INTERVAL,
+            handshake_timeout: DEFAULT_NOISE_HANDSHAKE_TIMEOUT,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
 struct Debug {
@@ -0,0 +1,24 @@
+use crate::node::node_statistics::SharedNodeStats;
+use nym_metrics::MetricsEncoder;
+use nym_mixnode_common::delay_forwarder::DelayQueueLength;
+use rocket::http::ContentType;
+use rocket::State;
+
+/// Returns the running stats of the node in the Prometheus text format.
+#[get("/metrics")]
+pub(crate) async fn metrics(
+    stats: &State<SharedNodeStats>,
+    delay_queue_length: &State<DelayQueueLength>,
+) -> (ContentType, String) {
+    let mut encoder = MetricsEncoder::new();
+    stats.clone_data().await.encode_metrics(&mut encoder);
+    encoder.gauge(
+        "nym_mixnode_delay_queue_length",
+        "Number of packets currently being delayed before getting forwarded.",
+        delay_queue_length.get(),
+    );
+
+    // the content type is a compile time constant, so it must be valid
+    let content_type = ContentType::parse_flexible(nym_metrics::CONTENT_TYPE).unwrap();
+    (content_type, encoder.finish())
+}
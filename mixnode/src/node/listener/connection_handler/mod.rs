@@ -4,18 +4,18 @@
 use crate::node::listener::connection_handler::packet_processing::{
     MixProcessingResult, PacketProcessor,
 };
-use crate::node::packet_delayforwarder::PacketDelayForwardSender;
 use crate::node::TaskClient;
 use futures::StreamExt;
+use nym_mixnet_client::noise::accept_connection;
+use nym_mixnet_client::NoiseConfig;
+use nym_mixnode_common::delay_forwarder::PacketDelayForwardSender;
 use nym_mixnode_common::measure;
 use nym_sphinx::forwarding::packet::MixPacket;
-use nym_sphinx::framing::codec::SphinxCodec;
 use nym_sphinx::framing::packet::FramedSphinxPacket;
 use nym_sphinx::Delay as SphinxDelay;
 use std::net::SocketAddr;
 use tokio::net::TcpStream;
 use tokio::time::Instant;
-use tokio_util::codec::Framed;
 #[cfg(feature = "cpucycles")]
 use tracing::{error, info, instrument};
 
@@ -25,16 +25,19 @@ pub(crate) mod packet_processing;
 pub(crate) struct ConnectionHandler {
     packet_processor: PacketProcessor,
     delay_forwarding_channel: PacketDelayForwardSender,
+    noise_config: Option<NoiseConfig>,
 }
 
 impl ConnectionHandler {
     pub(crate) fn new(
         packet_processor: PacketProcessor,
         delay_forwarding_channel: PacketDelayForwardSender,
+        noise_config: Option<NoiseConfig>,
     ) -> Self {
         ConnectionHandler {
             packet_processor,
             delay_forwarding_channel,
+            noise_config,
         }
     }
 
@@ -55,13 +58,7 @@ impl ConnectionHandler {
         instrument(skip(self, framed_sphinx_packet), fields(cpucycles))
     )]
     fn handle_received_packet(&self, framed_sphinx_packet: FramedSphinxPacket) {
-        //
-        // TODO: here be replay attack detection - it will require similar key cache to the one in
-        // packet processor for vpn packets,
-        // question: can it also be per connection vs global?
-        //
-
-        // all processing such, key caching, etc. was done.
+        // all processing such, key caching, replay detection, etc. was done.
         // however, if it was a forward hop, we still need to delay it
         measure!({
             match self.packet_processor.process_received(framed_sphinx_packet) {
@@ -86,7 +83,13 @@ impl ConnectionHandler {
     ) {
         debug!("Starting connection handler for {:?}", remote);
         shutdown.mark_as_success();
-        let mut framed_conn = Framed::new(conn, SphinxCodec);
+        let mut framed_conn = match accept_connection(conn, self.noise_config.as_ref()).await {
+            Ok(framed_conn) => framed_conn,
+            Err(err) => {
+                debug!("Failed to accept the connection from {remote} - {err}");
+                return;
+            }
+        };
         while !shutdown.is_shutdown() {
             tokio::select! {
                 biased;
@@ -2,7 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::node::node_statistics;
-use nym_crypto::asymmetric::encryption;
 use nym_mixnode_common::packet_processor::error::MixProcessingError;
 pub use nym_mixnode_common::packet_processor::processor::MixProcessingResult;
 use nym_mixnode_common::packet_processor::processor::SphinxPacketProcessor;
@@ -20,11 +19,11 @@ pub struct PacketProcessor {
 
 impl PacketProcessor {
     pub(crate) fn new(
-        encryption_key: &encryption::PrivateKey,
+        inner_processor: SphinxPacketProcessor,
         node_stats_update_sender: node_statistics::UpdateSender,
     ) -> Self {
         PacketProcessor {
-            inner_processor: SphinxPacketProcessor::new(encryption_key.into()),
+            inner_processor,
             node_stats_update_sender,
         }
     }
@@ -34,6 +33,13 @@ impl PacketProcessor {
         received: FramedSphinxPacket,
     ) -> Result<MixProcessingResult, MixProcessingError> {
         self.node_stats_update_sender.report_received();
-        self.inner_processor.process_received(received)
+        self.inner_processor
+            .process_received(received)
+            .map_err(|err| {
+                if err.is_replay() {
+                    self.node_stats_update_sender.report_replayed();
+                }
+                err
+            })
     }
 }
@@ -6,6 +6,7 @@ use crate::config::Config;
 use crate::node::http::{
     description::description,
     hardware::hardware,
+    metrics::metrics,
     not_found,
     stats::stats,
     verloc::{verloc as verlocRoute, VerlocState},
@@ -15,11 +16,17 @@ use crate::node::listener::connection_handler::ConnectionHandler;
 use crate::node::listener::Listener;
 use crate::node::node_description::NodeDescription;
 use crate::node::node_statistics::SharedNodeStats;
-use crate::node::packet_delayforwarder::{DelayForwarder, PacketDelayForwardSender};
 use nym_bin_common::output_format::OutputFormat;
 use nym_bin_common::version_checker::parse_version;
 use nym_config::NymConfig;
 use nym_crypto::asymmetric::{encryption, identity};
+use nym_mixnet_client::{NoiseConfig, NoiseNodeKeys};
+use nym_mixnode_common::delay_forwarder::{
+    DelayForwarder, DelayQueueLength, PacketDelayForwardSender,
+};
+use nym_mixnode_common::noise_keys::NoiseKeysRefresher;
+use nym_mixnode_common::packet_processor::processor::SphinxPacketProcessor;
+use nym_mixnode_common::sphinx_key_rotation::{self, RotatedKeysStorage, SphinxKeyRotator};
 use nym_mixnode_common::verloc::{self, AtomicVerlocResult, VerlocMeasurer};
 use nym_task::{TaskClient, TaskManager};
 use rand::seq::SliceRandom;
@@ -34,7 +41,6 @@ mod http;
 mod listener;
 pub(crate) mod node_description;
 mod node_statistics;
-mod packet_delayforwarder;
 
 // the MixNode will live for whole duration of this program
 pub struct MixNode {
@@ -42,16 +48,19 @@ pub struct MixNode {
     descriptor: NodeDescription,
     identity_keypair: Arc<identity::KeyPair>,
     sphinx_keypair: Arc<encryption::KeyPair>,
+    /// Passphrase the node keys are encrypted with, if any. It's also used for the rotated sphinx keys.
+    key_passphrase: Option<String>,
 }
 
 impl MixNode {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config, key_passphrase: Option<&str>) -> Self {
         let pathfinder = MixNodePathfinder::new_from_config(&config);
 
         MixNode {
             descriptor: Self::load_node_description(&config),
-            identity_keypair: Arc::new(Self::load_identity_keys(&pathfinder)),
-            sphinx_keypair: Arc::new(Self::load_sphinx_keys(&pathfinder)),
+            identity_keypair: Arc::new(Self::load_identity_keys(&pathfinder, key_passphrase)),
+            sphinx_keypair: Arc::new(Self::load_sphinx_keys(&pathfinder, key_passphrase)),
+            key_passphrase: key_passphrase.map(ToOwned::to_owned),
             config,
         }
     }
@@ -62,24 +71,34 @@ impl MixNode {
     }
 
     /// Loads identity keys stored on disk
-    pub(crate) fn load_identity_keys(pathfinder: &MixNodePathfinder) -> identity::KeyPair {
-        let identity_keypair: identity::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+    pub(crate) fn load_identity_keys(
+        pathfinder: &MixNodePathfinder,
+        key_passphrase: Option<&str>,
+    ) -> identity::KeyPair {
+        let identity_keypair: identity::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_identity_key().to_owned(),
                 pathfinder.public_identity_key().to_owned(),
-            ))
-            .expect("Failed to read stored identity key files");
+            ),
+            key_passphrase.map(str::as_bytes),
+        )
+        .expect("Failed to read stored identity key files");
         identity_keypair
     }
 
     /// Loads Sphinx keys stored on disk
-    fn load_sphinx_keys(pathfinder: &MixNodePathfinder) -> encryption::KeyPair {
-        let sphinx_keypair: encryption::KeyPair =
-            nym_pemstore::load_keypair(&nym_pemstore::KeyPairPath::new(
+    pub(crate) fn load_sphinx_keys(
+        pathfinder: &MixNodePathfinder,
+        key_passphrase: Option<&str>,
+    ) -> encryption::KeyPair {
+        let sphinx_keypair: encryption::KeyPair = nym_pemstore::load_keypair_with_passphrase(
+            &nym_pemstore::KeyPairPath::new(
                 pathfinder.private_encryption_key().to_owned(),
                 pathfinder.public_encryption_key().to_owned(),
-            ))
-            .expect("Failed to read stored sphinx key files");
+            ),
+            key_passphrase.map(str::as_bytes),
+        )
+        .expect("Failed to read stored sphinx key files");
         sphinx_keypair
     }
 
@@ -104,6 +123,7 @@ impl MixNode {
         &self,
         atomic_verloc_result: AtomicVerlocResult,
         node_stats_pointer: SharedNodeStats,
+        delay_queue_length: DelayQueueLength,
     ) {
         info!("Starting HTTP API on http://localhost:8000");
 
@@ -119,11 +139,15 @@ impl MixNode {
         tokio::spawn(async move {
             rocket::build()
                 .configure(config)
-                .mount("/", routes![verlocRoute, description, stats, hardware])
+                .mount(
+                    "/",
+                    routes![verlocRoute, description, stats, hardware, metrics],
+                )
                 .register("/", catchers![not_found])
                 .manage(verloc_state)
                 .manage(descriptor)
                 .manage(node_stats_pointer)
+                .manage(delay_queue_length)
                 .launch()
                 .await
         });
@@ -147,16 +171,19 @@ impl MixNode {
 
     fn start_socket_listener(
         &self,
+        sphinx_packet_processor: SphinxPacketProcessor,
         node_stats_update_sender: node_statistics::UpdateSender,
         delay_forwarding_channel: PacketDelayForwardSender,
+        noise_config: Option<NoiseConfig>,
         shutdown: TaskClient,
     ) {
         info!("Starting socket listener...");
 
         let packet_processor =
-            PacketProcessor::new(self.sphinx_keypair.private_key(), node_stats_update_sender);
+            PacketProcessor::new(sphinx_packet_processor, node_stats_update_sender);
 
-        let connection_handler = ConnectionHandler::new(packet_processor, delay_forwarding_channel);
+        let connection_handler =
+            ConnectionHandler::new(packet_processor, delay_forwarding_channel, noise_config);
 
         let listening_address = SocketAddr::new(
             self.config.get_listening_address(),
@@ -169,8 +196,9 @@ impl MixNode {
     fn start_packet_delay_forwarder(
         &mut self,
         node_stats_update_sender: node_statistics::UpdateSender,
+        noise_config: Option<NoiseConfig>,
         shutdown: TaskClient,
-    ) -> PacketDelayForwardSender {
+    ) -> (PacketDelayForwardSender, DelayQueueLength) {
         info!("Starting packet delay-forwarder...");
 
         let client_config = nym_mixnet_client::Config::new(
@@ -179,6 +207,7 @@ impl MixNode {
             self.config.get_initial_connection_timeout(),
             self.config.get_maximum_connection_buffer_size(),
             self.config.get_use_legacy_sphinx_framing(),
+            noise_config,
         );
 
         let mut packet_forwarder = DelayForwarder::new(
@@ -188,9 +217,10 @@ impl MixNode {
         );
 
         let packet_sender = packet_forwarder.sender();
+        let delay_queue_length = packet_forwarder.delay_queue_length();
 
         tokio::spawn(async move { packet_forwarder.run().await });
-        packet_sender
+        (packet_sender, delay_queue_length)
     }
 
     fn start_verloc_measurements(&self, shutdown: TaskClient) -> AtomicVerlocResult {
@@ -233,6 +263,60 @@ impl MixNode {
         atomic_verloc_results
     }
 
+    fn start_sphinx_key_rotation(
+        &self,
+        sphinx_packet_processor: SphinxPacketProcessor,
+        shutdown: TaskClient,
+    ) {
+        if !self.config.get_sphinx_key_rotation_enabled() {
+            warn!("Sphinx key rotation is disabled - the key from the bond is going to be used indefinitely");
+            return;
+        }
+        info!("Starting the sphinx key rotator...");
+
+        let config = sphinx_key_rotation::ConfigBuilder::new()
+            .epochs_per_key(self.config.get_epochs_per_sphinx_key())
+            .grace_period(self.config.get_sphinx_key_grace_period())
+            .epoch_check_interval(self.config.get_epoch_check_interval())
+            .nym_api_urls(self.config.get_nym_api_endpoints())
+            .build();
+
+        let mut sphinx_key_rotator = SphinxKeyRotator::new(
+            config,
+            Arc::clone(&self.identity_keypair),
+            sphinx_packet_processor,
+            RotatedKeysStorage::new(
+                self.config.get_rotated_sphinx_keys_directory(),
+                self.key_passphrase.clone(),
+            ),
+            shutdown,
+        );
+        tokio::spawn(async move { sphinx_key_rotator.run().await });
+    }
+
+    fn start_noise_keys_refresher(&self, shutdown: TaskClient) -> Option<NoiseConfig> {
+        if !self.config.get_noise_enabled() {
+            return None;
+        }
+        info!("Starting the noise keys refresher...");
+
+        let node_keys = NoiseNodeKeys::new();
+        let mut noise_keys_refresher = NoiseKeysRefresher::new(
+            node_keys.clone(),
+            self.config.get_nym_api_endpoints(),
+            self.config.get_noise_key_refresh_interval(),
+            shutdown,
+        );
+        tokio::spawn(async move { noise_keys_refresher.run().await });
+
+        Some(NoiseConfig::new(
+            Arc::new(self.identity_keypair.private_key().to_x25519()),
+            node_keys,
+            self.config.get_accept_legacy_connections(),
+            self.config.get_noise_handshake_timeout(),
+        ))
+    }
+
     fn random_api_client(&self) -> nym_validator_client::NymApiClient {
         let endpoints = self.config.get_nym_api_endpoints();
         let nym_api = endpoints
@@ -290,19 +374,32 @@ impl MixNode {
 
         let (node_stats_pointer, node_stats_update_sender) =
             self.start_node_stats_controller(shutdown.subscribe());
-        let delay_forwarding_channel = self
-            .start_packet_delay_forwarder(node_stats_update_sender.clone(), shutdown.subscribe());
+        let noise_config = self.start_noise_keys_refresher(shutdown.subscribe());
+        let (delay_forwarding_channel, delay_queue_length) = self.start_packet_delay_forwarder(
+            node_stats_update_sender.clone(),
+            noise_config.clone(),
+            shutdown.subscribe(),
+        );
+        let sphinx_packet_processor =
+            SphinxPacketProcessor::new(self.sphinx_keypair.private_key().into());
         self.start_socket_listener(
+            sphinx_packet_processor.clone(),
             node_stats_update_sender,
             delay_forwarding_channel,
+            noise_config,
             shutdown.subscribe(),
         );
+        self.start_sphinx_key_rotation(sphinx_packet_processor, shutdown.subscribe());
         let atomic_verloc_results = self.start_verloc_measurements(shutdown.subscribe());
 
         // Rocket handles shutdown on it's own, but its shutdown handling should be incorporated
         // with that of the rest of the tasks.
         // Currently it's runtime is forcefully terminated once the mixnode exits.
-        self.start_http_api(atomic_verloc_results, node_stats_pointer);
+        self.start_http_api(
+            atomic_verloc_results,
+            node_stats_pointer,
+            delay_queue_length,
+        );
 
         info!("Finished nym mixnode startup procedure - it should now be able to receive mix traffic!");
         self.wait_for_interrupt(shutdown).await
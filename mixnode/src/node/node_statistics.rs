@@ -1,6 +1,8 @@
 use futures::channel::mpsc;
 use futures::lock::Mutex;
 use futures::StreamExt;
+use nym_metrics::{MetricType, MetricsEncoder};
+use nym_mixnode_common::delay_forwarder::ForwardingStatsReporter;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::ops::DerefMut;
@@ -31,9 +33,11 @@ impl SharedNodeStats {
                 packets_received_since_startup: 0,
                 packets_sent_since_startup: HashMap::new(),
                 packets_explicitly_dropped_since_startup: HashMap::new(),
+                packets_replayed_since_startup: 0,
                 packets_received_since_last_update: 0,
                 packets_sent_since_last_update: HashMap::new(),
                 packets_explicitly_dropped_since_last_update: HashMap::new(),
+                packets_replayed_since_last_update: 0,
             })),
         }
     }
@@ -43,6 +47,7 @@ impl SharedNodeStats {
         new_received: u64,
         new_sent: PacketsMap,
         new_dropped: PacketsMap,
+        new_replayed: u64,
     ) {
         let mut guard = self.inner.write().await;
         let snapshot_time = SystemTime::now();
@@ -51,6 +56,7 @@ impl SharedNodeStats {
         guard.update_time = snapshot_time;
 
         guard.packets_received_since_startup += new_received;
+        guard.packets_replayed_since_startup += new_replayed;
         for (mix, count) in &new_sent {
             *guard
                 .packets_sent_since_startup
@@ -60,7 +66,7 @@ impl SharedNodeStats {
 
         for (mix, count) in &new_dropped {
             *guard
-                .packets_explicitly_dropped_since_last_update
+                .packets_explicitly_dropped_since_startup
                 .entry(mix.clone())
                 .or_insert(0) += *count;
         }
@@ -68,6 +74,7 @@ impl SharedNodeStats {
         guard.packets_received_since_last_update = new_received;
         guard.packets_sent_since_last_update = new_sent;
         guard.packets_explicitly_dropped_since_last_update = new_dropped;
+        guard.packets_replayed_since_last_update = new_replayed;
     }
 
     pub(crate) async fn clone_data(&self) -> NodeStats {
@@ -95,6 +102,9 @@ pub(crate) struct NodeStats {
     // we know for sure we dropped packets to those destinations
     packets_explicitly_dropped_since_startup: PacketsMap,
 
+    // packets we have rejected since we've already seen them before
+    packets_replayed_since_startup: u64,
+
     packets_received_since_last_update: u64,
 
     // note: sent does not imply forwarded. We don't know if it was delivered successfully
@@ -102,6 +112,9 @@ pub(crate) struct NodeStats {
 
     // we know for sure we dropped packets to those destinations
     packets_explicitly_dropped_since_last_update: PacketsMap,
+
+    // packets we have rejected since we've already seen them before
+    packets_replayed_since_last_update: u64,
 }
 
 impl NodeStats {
@@ -115,12 +128,49 @@ impl NodeStats {
                 .packets_explicitly_dropped_since_startup
                 .values()
                 .sum(),
+            packets_replayed_since_startup: self.packets_replayed_since_startup,
             packets_received_since_last_update: self.packets_received_since_last_update,
             packets_sent_since_last_update: self.packets_sent_since_last_update.values().sum(),
             packets_explicitly_dropped_since_last_update: self
                 .packets_explicitly_dropped_since_last_update
                 .values()
                 .sum(),
+            packets_replayed_since_last_update: self.packets_replayed_since_last_update,
+        }
+    }
+
+    pub(crate) fn encode_metrics(&self, encoder: &mut MetricsEncoder) {
+        encoder.counter(
+            "nym_mixnode_packets_received_total",
+            "Number of packets received since startup.",
+            self.packets_received_since_startup,
+        );
+        encoder.counter(
+            "nym_mixnode_packets_replayed_total",
+            "Number of replayed packets rejected since startup.",
+            self.packets_replayed_since_startup,
+        );
+
+        encoder.describe(
+            "nym_mixnode_packets_sent_total",
+            "Number of packets sent to each peer since startup.",
+            MetricType::Counter,
+        );
+        for (peer, count) in &self.packets_sent_since_startup {
+            encoder.sample("nym_mixnode_packets_sent_total", &[("peer", peer)], count);
+        }
+
+        encoder.describe(
+            "nym_mixnode_packets_dropped_total",
+            "Number of packets explicitly dropped for each peer since startup.",
+            MetricType::Counter,
+        );
+        for (peer, count) in &self.packets_explicitly_dropped_since_startup {
+            encoder.sample(
+                "nym_mixnode_packets_dropped_total",
+                &[("peer", peer)],
+                count,
+            );
         }
     }
 }
@@ -141,6 +191,9 @@ pub(crate) struct NodeStatsSimple {
     // we know for sure we dropped those packets
     packets_explicitly_dropped_since_startup: u64,
 
+    // packets we have rejected since we've already seen them before
+    packets_replayed_since_startup: u64,
+
     packets_received_since_last_update: u64,
 
     // note: sent does not imply forwarded. We don't know if it was delivered successfully
@@ -148,12 +201,16 @@ pub(crate) struct NodeStatsSimple {
 
     // we know for sure we dropped those packets
     packets_explicitly_dropped_since_last_update: u64,
+
+    // packets we have rejected since we've already seen them before
+    packets_replayed_since_last_update: u64,
 }
 
 pub(crate) enum PacketEvent {
     Sent(String),
     Received,
     Dropped(String),
+    Replayed,
 }
 
 #[derive(Debug, Clone)]
@@ -164,6 +221,7 @@ struct CurrentPacketData {
 #[derive(Debug)]
 struct PacketDataInner {
     received: AtomicU64,
+    replayed: AtomicU64,
     sent: Mutex<PacketsMap>,
     dropped: Mutex<PacketsMap>,
 }
@@ -173,6 +231,7 @@ impl CurrentPacketData {
         CurrentPacketData {
             inner: Arc::new(PacketDataInner {
                 received: AtomicU64::new(0),
+                replayed: AtomicU64::new(0),
                 sent: Mutex::new(HashMap::new()),
                 dropped: Mutex::new(HashMap::new()),
             }),
@@ -183,6 +242,10 @@ impl CurrentPacketData {
         self.inner.received.fetch_add(1, Ordering::SeqCst);
     }
 
+    fn increment_replayed(&self) {
+        self.inner.replayed.fetch_add(1, Ordering::SeqCst);
+    }
+
     async fn increment_sent(&self, destination: String) {
         let mut unlocked = self.inner.sent.lock().await;
         let receiver_count = unlocked.entry(destination).or_insert(0);
@@ -195,15 +258,16 @@ impl CurrentPacketData {
         *dropped_count += 1;
     }
 
-    async fn acquire_and_reset(&self) -> (u64, PacketsMap, PacketsMap) {
+    async fn acquire_and_reset(&self) -> (u64, PacketsMap, PacketsMap, u64) {
         let mut unlocked_sent = self.inner.sent.lock().await;
         let mut unlocked_dropped = self.inner.dropped.lock().await;
         let received = self.inner.received.swap(0, Ordering::SeqCst);
+        let replayed = self.inner.replayed.swap(0, Ordering::SeqCst);
 
         let sent = std::mem::take(unlocked_sent.deref_mut());
         let dropped = std::mem::take(unlocked_dropped.deref_mut());
 
-        (received, sent, dropped)
+        (received, sent, dropped, replayed)
     }
 }
 
@@ -234,6 +298,7 @@ impl UpdateHandler {
                 Some(packet_data) = self.update_receiver.next() => {
                     match packet_data {
                         PacketEvent::Received => self.current_data.increment_received(),
+                        PacketEvent::Replayed => self.current_data.increment_replayed(),
                         PacketEvent::Sent(destination) => {
                             self.current_data.increment_sent(destination).await
                         }
@@ -262,14 +327,6 @@ impl UpdateSender {
         UpdateSender(update_sender)
     }
 
-    pub(crate) fn report_sent(&self, destination: String) {
-        // in unbounded_send() failed it means that the receiver channel was disconnected
-        // and hence something weird must have happened without a way of recovering
-        self.0
-            .unbounded_send(PacketEvent::Sent(destination))
-            .unwrap()
-    }
-
     // TODO: in the future this could be slightly optimised to get rid of the channel
     // in favour of incrementing value directly
     pub(crate) fn report_received(&self) {
@@ -278,7 +335,23 @@ impl UpdateSender {
         self.0.unbounded_send(PacketEvent::Received).unwrap()
     }
 
-    pub(crate) fn report_dropped(&self, destination: String) {
+    pub(crate) fn report_replayed(&self) {
+        // in unbounded_send() failed it means that the receiver channel was disconnected
+        // and hence something weird must have happened without a way of recovering
+        self.0.unbounded_send(PacketEvent::Replayed).unwrap()
+    }
+}
+
+impl ForwardingStatsReporter for UpdateSender {
+    fn report_sent(&self, destination: String) {
+        // in unbounded_send() failed it means that the receiver channel was disconnected
+        // and hence something weird must have happened without a way of recovering
+        self.0
+            .unbounded_send(PacketEvent::Sent(destination))
+            .unwrap()
+    }
+
+    fn report_dropped(&self, destination: String) {
         // in unbounded_send() failed it means that the receiver channel was disconnected
         // and hence something weird must have happened without a way of recovering
         self.0
@@ -313,8 +386,11 @@ impl StatsUpdater {
 
     async fn update_stats(&self) {
         // grab new data since last update
-        let (received, sent, dropped) = self.current_packet_data.acquire_and_reset().await;
-        self.current_stats.update(received, sent, dropped).await;
+        let (received, sent, dropped, replayed) =
+            self.current_packet_data.acquire_and_reset().await;
+        self.current_stats
+            .update(received, sent, dropped, replayed)
+            .await;
     }
 
     async fn run(&mut self) {
@@ -377,6 +453,15 @@ impl PacketStatsConsoleLogger {
                 );
             }
 
+            if stats.packets_replayed_since_startup > 0 {
+                info!(
+                    "Since startup rejected {} replayed packets! ({} in last {} seconds)",
+                    stats.packets_replayed_since_startup,
+                    stats.packets_replayed_since_last_update,
+                    difference_secs,
+                );
+            }
+
             debug!(
                 "Since startup received {} packets ({} in last {} seconds)",
                 stats.packets_received_since_startup,
@@ -536,5 +621,70 @@ mod tests {
         assert_eq!(&stats.packets_sent_since_last_update.len(), &1);
         assert_eq!(&stats.packets_received_since_startup, &0u64);
         assert!(&stats.packets_explicitly_dropped_since_startup.is_empty());
+        assert_eq!(&stats.packets_replayed_since_startup, &0u64);
+    }
+
+    #[tokio::test]
+    async fn replayed_packets_are_counted() {
+        let logging_delay = Duration::from_millis(20);
+        let stats_updating_delay = Duration::from_millis(10);
+        let shutdown = TaskManager::default();
+        let node_stats_controller =
+            Controller::new(logging_delay, stats_updating_delay, shutdown.subscribe());
+
+        let node_stats_pointer = node_stats_controller.get_node_stats_data_pointer();
+        let update_sender = node_stats_controller.start();
+        tokio::time::pause();
+
+        update_sender.report_received();
+        update_sender.report_received();
+        update_sender.report_replayed();
+        tokio::task::yield_now().await;
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        tokio::task::yield_now().await;
+
+        let stats = node_stats_pointer.read().await;
+        assert_eq!(&stats.packets_received_since_startup, &2u64);
+        assert_eq!(&stats.packets_replayed_since_startup, &1u64);
+        assert_eq!(&stats.packets_replayed_since_last_update, &1u64);
+    }
+
+    #[tokio::test]
+    async fn dropped_packets_are_counted_since_startup() {
+        let logging_delay = Duration::from_millis(20);
+        let stats_updating_delay = Duration::from_millis(10);
+        let shutdown = TaskManager::default();
+        let node_stats_controller =
+            Controller::new(logging_delay, stats_updating_delay, shutdown.subscribe());
+
+        let node_stats_pointer = node_stats_controller.get_node_stats_data_pointer();
+        let update_sender = node_stats_controller.start();
+        tokio::time::pause();
+
+        update_sender.report_dropped("foo".to_string());
+        update_sender.report_sent("bar".to_string());
+        tokio::task::yield_now().await;
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        tokio::task::yield_now().await;
+
+        let stats = node_stats_pointer.read().await;
+        assert_eq!(
+            &stats.packets_explicitly_dropped_since_startup.get("foo"),
+            &Some(&1u64)
+        );
+        assert_eq!(
+            &stats
+                .packets_explicitly_dropped_since_last_update
+                .get("foo"),
+            &Some(&1u64)
+        );
+
+        let mut encoder = MetricsEncoder::new();
+        stats.encode_metrics(&mut encoder);
+        let metrics = encoder.finish();
+        assert!(metrics.contains("nym_mixnode_packets_dropped_total{peer=\"foo\"} 1\n"));
+        assert!(metrics.contains("nym_mixnode_packets_sent_total{peer=\"bar\"} 1\n"));
     }
 }
@@ -0,0 +1,32 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- latency (in milliseconds) each node has added on top of the expected packet delays,
+-- as estimated by the network monitor during a single test run
+CREATE TABLE mixnode_latency
+(
+    mixnode_details_id INTEGER NOT NULL,
+    samples            INTEGER NOT NULL,
+    p50                INTEGER NOT NULL,
+    p90                INTEGER NOT NULL,
+    p99                INTEGER NOT NULL,
+    timestamp          INTEGER NOT NULL
+);
+
+CREATE TABLE gateway_latency
+(
+    gateway_details_id INTEGER NOT NULL,
+    samples            INTEGER NOT NULL,
+    p50                INTEGER NOT NULL,
+    p90                INTEGER NOT NULL,
+    p99                INTEGER NOT NULL,
+    timestamp          INTEGER NOT NULL
+);
+
+CREATE INDEX mixnode_latency_timestamp ON mixnode_latency(`timestamp`);
+CREATE INDEX mixnode_latency_id ON mixnode_latency(`mixnode_details_id`);
+
+CREATE INDEX gateway_latency_timestamp ON gateway_latency(`timestamp`);
+CREATE INDEX gateway_latency_id ON gateway_latency(`gateway_details_id`);
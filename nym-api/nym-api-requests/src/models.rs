@@ -7,7 +7,8 @@ use nym_mixnet_contract_common::mixnode::MixNodeDetails;
 use nym_mixnet_contract_common::reward_params::{Performance, RewardingParams};
 use nym_mixnet_contract_common::rewarding::RewardEstimate;
 use nym_mixnet_contract_common::{
-    GatewayBond, IdentityKey, Interval, MixId, MixNode, Percent, RewardedSetNodeStatus,
+    EpochId, GatewayBond, IdentityKey, Interval, Layer, MixId, MixNode, MixNodeBond, Percent,
+    RewardedSetNodeStatus,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -83,6 +84,33 @@ pub struct GatewayCoreStatusResponse {
     pub count: i32,
 }
 
+/// Latency a node has added on top of the expected packet delays,
+/// as estimated during a single network monitor test run.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LatencyMeasurementResponse {
+    /// Unix timestamp of the test run.
+    pub timestamp: i64,
+
+    /// Number of test packets the estimate is based on.
+    pub samples: u32,
+
+    pub p50_ms: u32,
+    pub p90_ms: u32,
+    pub p99_ms: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct MixnodeLatencyResponse {
+    pub mix_id: MixId,
+    pub measurements: Vec<LatencyMeasurementResponse>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GatewayLatencyResponse {
+    pub identity: String,
+    pub measurements: Vec<LatencyMeasurementResponse>,
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
 #[cfg_attr(
@@ -337,3 +365,209 @@ pub struct CirculatingSupplyResponse {
     pub vesting_tokens: Coin,
     pub circulating_supply: Coin,
 }
+
+/// Minimal information about a mixnode required for constructing the network topology.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct TopologySnapshotMixNode {
+    pub mix_id: MixId,
+    pub owner: String,
+    pub host: String,
+    pub mix_port: u16,
+    pub identity_key: IdentityKey,
+    pub sphinx_key: String,
+
+    /// Epochs during which the `sphinx_key` is valid. It is not set if the node still uses
+    /// the key it has been bonded with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sphinx_key_validity: Option<SphinxKeyValidity>,
+    pub layer: Layer,
+    pub version: String,
+}
+
+impl<'a> From<&'a MixNodeBond> for TopologySnapshotMixNode {
+    fn from(bond: &'a MixNodeBond) -> Self {
+        TopologySnapshotMixNode {
+            mix_id: bond.mix_id,
+            owner: bond.owner.to_string(),
+            host: bond.mix_node.host.clone(),
+            mix_port: bond.mix_node.mix_port,
+            identity_key: bond.mix_node.identity_key.clone(),
+            sphinx_key: bond.mix_node.sphinx_key.clone(),
+            sphinx_key_validity: None,
+            layer: bond.layer,
+            version: bond.mix_node.version.clone(),
+        }
+    }
+}
+
+/// Minimal information about a gateway required for constructing the network topology.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct TopologySnapshotGateway {
+    pub owner: String,
+    pub host: String,
+    pub mix_port: u16,
+    pub clients_port: u16,
+    pub identity_key: IdentityKey,
+    pub sphinx_key: String,
+
+    /// Epochs during which the `sphinx_key` is valid. It is not set if the gateway still uses
+    /// the key it has been bonded with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sphinx_key_validity: Option<SphinxKeyValidity>,
+    pub version: String,
+}
+
+impl<'a> From<&'a GatewayBond> for TopologySnapshotGateway {
+    fn from(bond: &'a GatewayBond) -> Self {
+        TopologySnapshotGateway {
+            owner: bond.owner.to_string(),
+            host: bond.gateway.host.clone(),
+            mix_port: bond.gateway.mix_port,
+            clients_port: bond.gateway.clients_port,
+            identity_key: bond.gateway.identity_key.clone(),
+            sphinx_key: bond.gateway.sphinx_key.clone(),
+            sphinx_key_validity: None,
+            version: bond.gateway.version.clone(),
+        }
+    }
+}
+
+/// Range of epochs during which particular sphinx key is meant to be used.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct SphinxKeyValidity {
+    /// The first epoch in which the key is used.
+    pub valid_from: EpochId,
+
+    /// The first epoch in which the key is no longer used.
+    pub valid_until: EpochId,
+}
+
+impl SphinxKeyValidity {
+    pub fn is_valid_in(&self, epoch_id: EpochId) -> bool {
+        self.valid_from <= epoch_id && epoch_id < self.valid_until
+    }
+}
+
+/// Sphinx key announced by a node alongside the epochs during which it is going to be used.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct AnnouncedSphinxKey {
+    /// Base58-encoded x25519 sphinx key of the node.
+    pub sphinx_key: String,
+
+    pub validity: SphinxKeyValidity,
+}
+
+/// Sphinx keys a node has announced in place of the one it has been bonded with.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct NodeSphinxKeys {
+    /// The first epoch in which the node no longer uses the sphinx key from its bond.
+    pub bonded_key_valid_until: EpochId,
+
+    /// The current and the next keys of the node, sorted by the starting epoch of their validity.
+    pub announced: Vec<AnnouncedSphinxKey>,
+}
+
+/// Mixnode details alongside the sphinx keys the node has announced, if any.
+/// Since the keys are flattened into the details, the responses are still compatible
+/// with the clients that only expect the plain [`MixNodeDetails`].
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct MixNodeDetailsWithSphinxKeys {
+    #[serde(flatten)]
+    pub details: MixNodeDetails,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sphinx_keys: Option<NodeSphinxKeys>,
+}
+
+/// Gateway bond alongside the sphinx keys the gateway has announced, if any.
+/// Since the keys are flattened into the bond, the responses are still compatible
+/// with the clients that only expect the plain [`GatewayBond`].
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GatewayBondWithSphinxKeys {
+    #[serde(flatten)]
+    pub bond: GatewayBond,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sphinx_keys: Option<NodeSphinxKeys>,
+}
+
+/// Sphinx key freshly generated by a node alongside the epochs during which it is going to be used.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct SphinxKeyAnnouncement {
+    /// Base58-encoded identity key of the announcing node.
+    pub identity_key: IdentityKey,
+
+    /// Base58-encoded x25519 sphinx key of the node.
+    pub sphinx_key: String,
+
+    pub validity: SphinxKeyValidity,
+
+    /// Unix timestamp of when the announcement has been created. It's used for rejecting
+    /// replayed announcements that would have overwritten newer keys.
+    pub generated_at: i64,
+}
+
+impl SphinxKeyAnnouncement {
+    /// Bytes of the announcement that get signed by the node, i.e. its json representation.
+    pub fn signable_bytes(&self) -> Vec<u8> {
+        // serialization of a plain struct can't possibly fail
+        serde_json::to_vec(self).unwrap()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct SignedSphinxKeyAnnouncement {
+    pub announcement: SphinxKeyAnnouncement,
+
+    /// Base58-encoded ed25519 signature on the [`SphinxKeyAnnouncement::signable_bytes`]
+    /// made with the identity key of the node.
+    pub signature: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct SphinxKeyAnnouncementResponse {
+    /// Indicates whether the announcement has not been seen before.
+    pub newly_announced: bool,
+}
+
+/// Compact description of the active network topology during particular epoch.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct TopologySnapshot {
+    pub epoch_id: u32,
+    pub generated_at: i64,
+    pub mixnodes: Vec<TopologySnapshotMixNode>,
+    pub gateways: Vec<TopologySnapshotGateway>,
+}
+
+impl TopologySnapshot {
+    /// Encodes the snapshot into its json representation, i.e. the form in which it gets signed.
+    pub fn encode(&self) -> String {
+        // serialization of a plain struct can't possibly fail
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct SignedTopologySnapshot {
+    /// Json-encoded [`TopologySnapshot`]. It's kept in the exact form it has been signed in,
+    /// so that the signature could be verified regardless of the snapshot fields known to the verifier.
+    pub snapshot: String,
+
+    /// Base58-encoded identity key of the nym-api that has produced this snapshot.
+    pub signer: String,
+
+    /// Base58-encoded ed25519 signature on the [`SignedTopologySnapshot::signable_bytes`].
+    pub signature: String,
+}
+
+impl SignedTopologySnapshot {
+    /// Bytes of the snapshot that get signed by the nym-api, i.e. the encoded snapshot itself.
+    pub fn signable_bytes(&self) -> &[u8] {
+        self.snapshot.as_bytes()
+    }
+
+    /// Decodes the underlying snapshot. Note that it does not verify the signature.
+    pub fn decode_snapshot(&self) -> Result<TopologySnapshot, serde_json::Error> {
+        serde_json::from_str(&self.snapshot)
+    }
+}
@@ -9,7 +9,10 @@ use nym_validator_client::ValidatorClientError;
 use rocket::fairing::AdHoc;
 use std::ops::Deref;
 use std::{
-    sync::{atomic::AtomicBool, Arc},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::Duration,
 };
 use thiserror::Error;
@@ -67,6 +70,21 @@ impl CirculatingSupplyCache {
         }
     }
 
+    /// Unix timestamp of the most recent refresh of the cache, if it has ever happened.
+    pub(crate) async fn last_refresh_timestamp(&self) -> Option<i64> {
+        if !self.initialised.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        match time::timeout(Duration::from_millis(100), self.data.read()).await {
+            Ok(cache) => Some(cache.circulating_supply.timestamp()),
+            Err(err) => {
+                error!("{err}");
+                None
+            }
+        }
+    }
+
     pub(crate) fn stage(mix_denom: String) -> AdHoc {
         AdHoc::on_ignite("Circulating Supply Cache Stage", |rocket| async {
             rocket.manage(Self::new(mix_denom))
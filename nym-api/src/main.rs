@@ -17,6 +17,7 @@ use circulating_supply_api::cache::CirculatingSupplyCache;
 use clap::Parser;
 use coconut::dkg::controller::DkgController;
 use log::info;
+use metrics::NetworkMonitorMetrics;
 use node_status_api::NodeStatusCache;
 use nym_bin_common::logging::setup_logging;
 use nym_config::NymConfig;
@@ -30,10 +31,12 @@ use support::{http, nyxd};
 mod circulating_supply_api;
 mod coconut;
 mod epoch_operations;
+mod metrics;
 mod network_monitor;
 pub(crate) mod node_status_api;
 pub(crate) mod nym_contract_cache;
 pub(crate) mod support;
+mod topology_snapshot;
 
 struct ShutdownHandles {
     task_manager_handle: TaskManager,
@@ -62,6 +65,7 @@ async fn start_nym_api_tasks(
     let mix_denom = nyxd_client.chain_details().await.mix_denom.base;
 
     let coconut_keypair = coconut::keypair::KeyPair::new();
+    let identity_keypair = topology_snapshot::load_identity_keypair(&config)?;
 
     // let's build our rocket!
     let rocket = http::setup_rocket(
@@ -69,6 +73,7 @@ async fn start_nym_api_tasks(
         mix_denom,
         nyxd_client.clone(),
         coconut_keypair.clone(),
+        identity_keypair,
     )
     .await?;
 
@@ -84,6 +89,7 @@ async fn start_nym_api_tasks(
     let nym_contract_cache_state = rocket.state::<NymContractCache>().unwrap();
     let node_status_cache_state = rocket.state::<NodeStatusCache>().unwrap();
     let circulating_supply_cache_state = rocket.state::<CirculatingSupplyCache>().unwrap();
+    let network_monitor_metrics_state = rocket.state::<NetworkMonitorMetrics>().unwrap();
     let maybe_storage = rocket.state::<NymApiStorage>();
 
     // start all the caches first
@@ -130,6 +136,7 @@ async fn start_nym_api_tasks(
             &config,
             nym_contract_cache_state,
             storage,
+            network_monitor_metrics_state,
             nyxd_client.clone(),
             &shutdown,
         )
@@ -0,0 +1,70 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nym_metrics::MetricsEncoder;
+use rocket::fairing::AdHoc;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+pub(crate) mod routes;
+
+/// Statistics of the network monitor test runs, exposed via the `/metrics` endpoint.
+#[derive(Clone, Default)]
+pub(crate) struct NetworkMonitorMetrics {
+    inner: Arc<NetworkMonitorMetricsInner>,
+}
+
+#[derive(Default)]
+struct NetworkMonitorMetricsInner {
+    runs: AtomicU64,
+    last_run_duration_millis: AtomicU64,
+    last_run_finished_at: AtomicI64,
+}
+
+impl NetworkMonitorMetrics {
+    pub(crate) fn stage() -> AdHoc {
+        AdHoc::on_ignite("Network Monitor Metrics Stage", |rocket| async {
+            rocket.manage(Self::default())
+        })
+    }
+
+    pub(crate) fn record_run(&self, duration: Duration) {
+        self.inner
+            .last_run_duration_millis
+            .store(duration.as_millis() as u64, Ordering::Relaxed);
+        self.inner.last_run_finished_at.store(
+            OffsetDateTime::now_utc().unix_timestamp(),
+            Ordering::Relaxed,
+        );
+        self.inner.runs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn encode_metrics(&self, encoder: &mut MetricsEncoder) {
+        let runs = self.inner.runs.load(Ordering::Relaxed);
+        encoder.counter(
+            "nym_api_network_monitor_runs_total",
+            "Number of network monitor test runs completed since startup.",
+            runs,
+        );
+
+        // don't report values of a run that has never happened
+        if runs == 0 {
+            return;
+        }
+
+        let duration_millis = self.inner.last_run_duration_millis.load(Ordering::Relaxed);
+        encoder
+            .gauge(
+                "nym_api_network_monitor_last_run_duration_seconds",
+                "Duration of the most recent network monitor test run.",
+                duration_millis as f64 / 1000.,
+            )
+            .gauge(
+                "nym_api_network_monitor_last_run_timestamp_seconds",
+                "Unix timestamp of the completion of the most recent network monitor test run.",
+                self.inner.last_run_finished_at.load(Ordering::Relaxed),
+            );
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::circulating_supply_api::cache::CirculatingSupplyCache;
+use crate::metrics::NetworkMonitorMetrics;
+use crate::node_status_api::NodeStatusCache;
+use crate::nym_contract_cache::cache::NymContractCache;
+use nym_metrics::{MetricType, MetricsEncoder};
+use rocket::http::ContentType;
+use rocket::State;
+use time::OffsetDateTime;
+
+const CACHE_REFRESH_AGE: &str = "nym_api_cache_refresh_age_seconds";
+
+/// Returns the internal statistics of the nym-api in the Prometheus text format.
+#[get("/metrics")]
+pub(crate) async fn metrics(
+    contract_cache: &State<NymContractCache>,
+    node_status_cache: &State<NodeStatusCache>,
+    circulating_supply_cache: &State<CirculatingSupplyCache>,
+    network_monitor_metrics: &State<NetworkMonitorMetrics>,
+) -> (ContentType, String) {
+    let mut encoder = MetricsEncoder::new();
+    network_monitor_metrics.encode_metrics(&mut encoder);
+
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    encoder.describe(
+        CACHE_REFRESH_AGE,
+        "Number of seconds since the cache has last been refreshed.",
+        MetricType::Gauge,
+    );
+    let refreshes = [
+        (
+            "nym_contract",
+            contract_cache.last_refresh_timestamp().await,
+        ),
+        (
+            "node_status",
+            node_status_cache.last_refresh_timestamp().await,
+        ),
+        (
+            "circulating_supply",
+            circulating_supply_cache.last_refresh_timestamp().await,
+        ),
+    ];
+    for (cache, last_refresh) in refreshes {
+        if let Some(last_refresh) = last_refresh {
+            encoder.sample(CACHE_REFRESH_AGE, &[("cache", cache)], now - last_refresh);
+        }
+    }
+
+    // the content type is a compile time constant, so it must be valid
+    let content_type = ContentType::parse_flexible(nym_metrics::CONTENT_TYPE).unwrap();
+    (content_type, encoder.finish())
+}
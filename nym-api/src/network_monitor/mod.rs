@@ -1,6 +1,7 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::metrics::NetworkMonitorMetrics;
 use crate::network_monitor;
 use crate::network_monitor::monitor::preparer::PacketPreparer;
 use crate::network_monitor::monitor::processor::{
@@ -36,6 +37,7 @@ pub(crate) fn setup<'a>(
     config: &'a Config,
     nym_contract_cache_state: &NymContractCache,
     storage: &NymApiStorage,
+    metrics: &NetworkMonitorMetrics,
     nyxd_client: nyxd::Client,
 ) -> NetworkMonitorBuilder<'a> {
     NetworkMonitorBuilder::new(
@@ -43,6 +45,7 @@ pub(crate) fn setup<'a>(
         nyxd_client,
         storage.to_owned(),
         nym_contract_cache_state.to_owned(),
+        metrics.to_owned(),
     )
 }
 
@@ -51,6 +54,7 @@ pub(crate) struct NetworkMonitorBuilder<'a> {
     nyxd_client: nyxd::Client,
     node_status_storage: NymApiStorage,
     validator_cache: NymContractCache,
+    metrics: NetworkMonitorMetrics,
 }
 
 impl<'a> NetworkMonitorBuilder<'a> {
@@ -59,12 +63,14 @@ impl<'a> NetworkMonitorBuilder<'a> {
         nyxd_client: nyxd::Client,
         node_status_storage: NymApiStorage,
         validator_cache: NymContractCache,
+        metrics: NetworkMonitorMetrics,
     ) -> Self {
         NetworkMonitorBuilder {
             config,
             nyxd_client,
             node_status_storage,
             validator_cache,
+            metrics,
         }
     }
 
@@ -129,6 +135,7 @@ impl<'a> NetworkMonitorBuilder<'a> {
             received_processor,
             summary_producer,
             self.node_status_storage,
+            self.metrics,
         );
 
         NetworkMonitorRunnables {
@@ -220,11 +227,17 @@ pub(crate) async fn start<R: MessageReceiver + Send + 'static>(
     config: &Config,
     nym_contract_cache_state: &NymContractCache,
     storage: &NymApiStorage,
+    metrics: &NetworkMonitorMetrics,
     nyxd_client: nyxd::Client,
     shutdown: &TaskManager,
 ) {
-    let monitor_builder =
-        network_monitor::setup(config, nym_contract_cache_state, storage, nyxd_client);
+    let monitor_builder = network_monitor::setup(
+        config,
+        nym_contract_cache_state,
+        storage,
+        metrics,
+        nyxd_client,
+    );
     info!("Starting network monitor...");
     let runnables: NetworkMonitorRunnables<R> = monitor_builder.build().await;
     runnables.spawn_tasks(shutdown);
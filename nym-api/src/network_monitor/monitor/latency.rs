@@ -0,0 +1,280 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::network_monitor::test_packet::ReceivedTestMessage;
+use nym_node_tester_utils::node::TestableNode;
+use nym_sphinx::chunking::fragment::FragmentIdentifier;
+use nym_sphinx::preparer::PreparedFragment;
+use std::collections::HashMap;
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Timing information of a test packet that got sent into the network.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SentPacket {
+    sent_at: Instant,
+
+    /// Sum of all the delays the packet was meant to be held for by the mixnodes on its route.
+    expected_delay: Duration,
+}
+
+impl SentPacket {
+    pub(crate) fn new(sent_at: Instant, expected_delay: Duration) -> Self {
+        SentPacket {
+            sent_at,
+            expected_delay,
+        }
+    }
+}
+
+/// Keeps track of when each test packet was actually sent to its gateway so that upon receiving it back
+/// we could determine how long it took to traverse the network.
+// note: the sending happens concurrently for all gateways, so the map has to be shared
+#[derive(Clone, Default)]
+pub(crate) struct SentPacketsTracker {
+    inner: Arc<Mutex<HashMap<FragmentIdentifier, SentPacket>>>,
+}
+
+impl SentPacketsTracker {
+    pub(crate) fn new() -> Self {
+        Default::default()
+    }
+
+    pub(crate) fn record_sent(&self, packets: &[PreparedFragment]) {
+        let sent_at = Instant::now();
+
+        // the lock can't be poisoned as we never panic while holding it
+        let mut guard = self.inner.lock().unwrap();
+        for packet in packets {
+            guard.insert(
+                packet.fragment_identifier,
+                SentPacket::new(sent_at, packet.total_delay.to_duration()),
+            );
+        }
+    }
+
+    /// Returns information on all packets sent since the previous call.
+    pub(crate) fn take(&self) -> HashMap<FragmentIdentifier, SentPacket> {
+        mem::take(&mut *self.inner.lock().unwrap())
+    }
+}
+
+/// Percentiles of the latency a node has added on top of the expected packet delays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LatencyPercentiles {
+    /// Number of received packets the estimate is based on.
+    pub(crate) samples: u32,
+
+    pub(crate) p50_ms: u32,
+    pub(crate) p90_ms: u32,
+    pub(crate) p99_ms: u32,
+}
+
+impl LatencyPercentiles {
+    // returns `None` if there were no samples
+    fn from_samples(mut samples: Vec<u32>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        samples.sort_unstable();
+        Some(LatencyPercentiles {
+            samples: samples.len() as u32,
+            p50_ms: percentile(&samples, 50),
+            p90_ms: percentile(&samples, 90),
+            p99_ms: percentile(&samples, 99),
+        })
+    }
+}
+
+// nearest-rank percentile of an already sorted, non-empty, slice
+fn percentile(sorted: &[u32], percentile: usize) -> u32 {
+    let rank = (percentile * sorted.len() + 99) / 100;
+    sorted[rank.max(1) - 1]
+}
+
+fn median(mut values: Vec<Duration>) -> Duration {
+    values.sort_unstable();
+    values[values.len() / 2]
+}
+
+/// Estimates latency added by each of the tested nodes.
+///
+/// For each received packet we take its round trip time minus the delays it was meant to be held for
+/// by the mixnodes. Since every test packet goes through all but one of the nodes of its test route,
+/// the median of those values for the given route serves as the baseline of a "typical" node.
+/// Whatever a tested node adds on top of that baseline is attributed to the node itself.
+/// Samples from all the routes are then combined together.
+pub(crate) fn estimate_node_latencies(
+    sent_packets: &HashMap<FragmentIdentifier, SentPacket>,
+    received_packets: &[ReceivedTestMessage],
+) -> HashMap<TestableNode, LatencyPercentiles> {
+    let mut measured = Vec::with_capacity(received_packets.len());
+    for received in received_packets {
+        let Some(sent) = sent_packets.get(&received.fragment_id) else {
+            // we don't know when it was sent (e.g. the packet got somehow replayed from a previous run),
+            // so we can't say anything about its latency
+            continue;
+        };
+
+        let round_trip = received.received_at.saturating_duration_since(sent.sent_at);
+        let excess = round_trip.saturating_sub(sent.expected_delay);
+        measured.push((received, excess));
+    }
+
+    let mut per_route: HashMap<_, Vec<_>> = HashMap::new();
+    for (received, excess) in &measured {
+        per_route
+            .entry(received.message.ext.route_id)
+            .or_default()
+            .push(*excess);
+    }
+
+    let baselines = per_route
+        .into_iter()
+        .map(|(route_id, excesses)| (route_id, median(excesses)))
+        .collect::<HashMap<_, _>>();
+
+    let mut per_node: HashMap<_, Vec<_>> = HashMap::new();
+    for (received, excess) in measured {
+        // the entry must exist as we've just inserted it
+        let baseline = baselines[&received.message.ext.route_id];
+        let added = excess.saturating_sub(baseline).as_millis();
+        per_node
+            .entry(received.message.tested_node.clone())
+            .or_default()
+            .push(added.try_into().unwrap_or(u32::MAX));
+    }
+
+    per_node
+        .into_iter()
+        .filter_map(|(node, samples)| {
+            LatencyPercentiles::from_samples(samples).map(|latency| (node, latency))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network_monitor::test_packet::{NodeTestMessage, NymApiTestMessageExt};
+
+    fn fragment_id(id: u16) -> FragmentIdentifier {
+        // all the test fragments are single-fragment sets
+        let id_bytes = id.to_be_bytes();
+        FragmentIdentifier::try_from_bytes([0, 0, id_bytes[0], id_bytes[1], 0]).unwrap()
+    }
+
+    fn mixnode(mix_id: u32) -> TestableNode {
+        TestableNode::new_mixnode(format!("identity{mix_id}"), "owner".to_string(), mix_id)
+    }
+
+    struct TestRun {
+        start: Instant,
+        sent: HashMap<FragmentIdentifier, SentPacket>,
+        received: Vec<ReceivedTestMessage>,
+        next_id: u16,
+    }
+
+    impl TestRun {
+        fn new() -> Self {
+            TestRun {
+                start: Instant::now(),
+                sent: HashMap::new(),
+                received: Vec::new(),
+                next_id: 1,
+            }
+        }
+
+        fn add(
+            &mut self,
+            node: &TestableNode,
+            route_id: u64,
+            expected_delay_ms: u64,
+            round_trip_ms: Option<u64>,
+        ) {
+            let id = fragment_id(self.next_id);
+            self.next_id += 1;
+
+            self.sent.insert(
+                id,
+                SentPacket::new(self.start, Duration::from_millis(expected_delay_ms)),
+            );
+
+            if let Some(round_trip_ms) = round_trip_ms {
+                self.received.push(ReceivedTestMessage {
+                    message: NodeTestMessage::new(
+                        node.clone(),
+                        1,
+                        1,
+                        NymApiTestMessageExt::new(route_id, 1),
+                    ),
+                    fragment_id: id,
+                    received_at: self.start + Duration::from_millis(round_trip_ms),
+                })
+            }
+        }
+    }
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let values = (1..=100).collect::<Vec<_>>();
+        assert_eq!(percentile(&values, 50), 50);
+        assert_eq!(percentile(&values, 90), 90);
+        assert_eq!(percentile(&values, 99), 99);
+
+        assert_eq!(percentile(&[42], 50), 42);
+        assert_eq!(percentile(&[42], 99), 42);
+        assert_eq!(percentile(&[1, 2, 3], 50), 2);
+        assert_eq!(percentile(&[1, 2, 3], 99), 3);
+    }
+
+    #[test]
+    fn latency_is_measured_relative_to_route_baseline() {
+        let typical1 = mixnode(1);
+        let typical2 = mixnode(2);
+        let slow = mixnode(3);
+
+        let mut run = TestRun::new();
+        // route 1 has 100ms of transport overhead, route 2 has 300ms
+        for (route_id, overhead) in [(1, 100), (2, 300)] {
+            run.add(&typical1, route_id, 50, Some(50 + overhead));
+            run.add(&typical2, route_id, 70, Some(70 + overhead));
+            run.add(&slow, route_id, 60, Some(60 + overhead + 500));
+        }
+
+        let latencies = estimate_node_latencies(&run.sent, &run.received);
+        assert_eq!(latencies[&typical1].p50_ms, 0);
+        assert_eq!(latencies[&typical2].p50_ms, 0);
+
+        let slow_latency = latencies[&slow];
+        assert_eq!(slow_latency.samples, 2);
+        assert_eq!(slow_latency.p50_ms, 500);
+        assert_eq!(slow_latency.p99_ms, 500);
+    }
+
+    #[test]
+    fn lost_and_unknown_packets_are_ignored() {
+        let node1 = mixnode(1);
+        let node2 = mixnode(2);
+
+        let mut run = TestRun::new();
+        run.add(&node1, 1, 50, Some(150));
+        run.add(&node1, 1, 50, None);
+        run.add(&node2, 1, 50, None);
+
+        // packet we have no record of sending
+        run.received.push(ReceivedTestMessage {
+            message: NodeTestMessage::new(node2.clone(), 1, 1, NymApiTestMessageExt::new(1, 1)),
+            fragment_id: fragment_id(1234),
+            received_at: run.start,
+        });
+
+        let latencies = estimate_node_latencies(&run.sent, &run.received);
+        assert_eq!(latencies.len(), 1);
+        assert_eq!(latencies[&node1].samples, 1);
+        assert!(!latencies.contains_key(&node2));
+    }
+}
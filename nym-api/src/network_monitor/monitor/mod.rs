@@ -1,11 +1,12 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::metrics::NetworkMonitorMetrics;
 use crate::network_monitor::monitor::preparer::PacketPreparer;
 use crate::network_monitor::monitor::processor::ReceivedProcessor;
 use crate::network_monitor::monitor::sender::PacketSender;
 use crate::network_monitor::monitor::summary_producer::{SummaryProducer, TestSummary};
-use crate::network_monitor::test_packet::NodeTestMessage;
+use crate::network_monitor::test_packet::ReceivedTestMessage;
 use crate::network_monitor::test_route::TestRoute;
 use crate::storage::NymApiStorage;
 use crate::support::config::Config;
@@ -18,6 +19,7 @@ use tokio::time::{sleep, Duration, Instant};
 
 pub(crate) mod gateway_clients_cache;
 pub(crate) mod gateways_pinger;
+pub(crate) mod latency;
 pub(crate) mod preparer;
 pub(crate) mod processor;
 pub(crate) mod receiver;
@@ -31,6 +33,7 @@ pub(super) struct Monitor<R: MessageReceiver + Send + 'static> {
     received_processor: ReceivedProcessor<R>,
     summary_producer: SummaryProducer,
     node_status_storage: NymApiStorage,
+    metrics: NetworkMonitorMetrics,
     run_interval: Duration,
     gateway_ping_interval: Duration,
     packet_delivery_timeout: Duration,
@@ -54,6 +57,7 @@ impl<R: MessageReceiver + Send> Monitor<R> {
         received_processor: ReceivedProcessor<R>,
         summary_producer: SummaryProducer,
         node_status_storage: NymApiStorage,
+        metrics: NetworkMonitorMetrics,
     ) -> Self {
         Monitor {
             test_nonce: 1,
@@ -62,6 +66,7 @@ impl<R: MessageReceiver + Send> Monitor<R> {
             received_processor,
             summary_producer,
             node_status_storage,
+            metrics,
             run_interval: config.get_network_monitor_run_interval(),
             gateway_ping_interval: config.get_gateway_ping_interval(),
             packet_delivery_timeout: config.get_packet_delivery_timeout(),
@@ -98,11 +103,13 @@ impl<R: MessageReceiver + Send> Monitor<R> {
 
     fn analyse_received_test_route_packets(
         &self,
-        packets: &[NodeTestMessage],
+        packets: &[ReceivedTestMessage],
     ) -> HashMap<u64, usize> {
         let mut received = HashMap::new();
         for packet in packets {
-            *received.entry(packet.ext.route_id).or_insert(0usize) += 1usize
+            *received
+                .entry(packet.message.ext.route_id)
+                .or_insert(0usize) += 1usize
         }
 
         received
@@ -139,6 +146,9 @@ impl<R: MessageReceiver + Send> Monitor<R> {
         let received = self.received_processor.return_received().await;
         let mut results = self.analyse_received_test_route_packets(&received);
 
+        // we only care about the latency of the nodes under test, not of the route candidates
+        self.packet_sender.take_sent_packets();
+
         // create entry for routes that might have not forwarded a single packet
         for route in routes {
             results.entry(route.id()).or_insert(0);
@@ -260,6 +270,7 @@ impl<R: MessageReceiver + Send> Monitor<R> {
         sleep(self.packet_delivery_timeout).await;
 
         let received = self.received_processor.return_received().await;
+        let sent = self.packet_sender.take_sent_packets();
         let total_received = received.len();
         info!("Test routes: {:#?}", routes);
         info!("Received {}/{} packets", total_received, total_sent);
@@ -267,6 +278,7 @@ impl<R: MessageReceiver + Send> Monitor<R> {
         let summary = self.summary_producer.produce_summary(
             prepared_packets.tested_mixnodes,
             prepared_packets.tested_gateways,
+            &sent,
             received,
             prepared_packets.invalid_mixnodes,
             prepared_packets.invalid_gateways,
@@ -293,7 +305,9 @@ impl<R: MessageReceiver + Send> Monitor<R> {
             error!("We failed to construct sufficient number of test routes to test the network against")
         }
 
-        debug!("Test run took {:?}", Instant::now().duration_since(start));
+        let run_duration = Instant::now().duration_since(start);
+        debug!("Test run took {:?}", run_duration);
+        self.metrics.record_run(run_duration);
 
         self.test_nonce += 1;
     }
@@ -11,7 +11,6 @@ use nym_node_tester_utils::node::TestableNode;
 use nym_node_tester_utils::NodeTester;
 use nym_sphinx::acknowledgements::AckKey;
 use nym_sphinx::addressing::clients::Recipient;
-use nym_sphinx::forwarding::packet::MixPacket;
 use nym_sphinx::params::PacketSize;
 use nym_topology::{gateway, mix};
 use rand_07::{rngs::ThreadRng, seq::SliceRandom, thread_rng, Rng};
@@ -250,24 +249,24 @@ impl PacketPreparer {
         let mut routes = Vec::new();
         for i in 0..most_available {
             let Ok(node_1) = self.try_parse_mix_bond(rand_l1[i]) else {
-                    blacklist.insert(rand_l1[i].identity().to_owned());
-                    continue
-                };
+                blacklist.insert(rand_l1[i].identity().to_owned());
+                continue;
+            };
 
             let Ok(node_2) = self.try_parse_mix_bond(rand_l2[i]) else {
-                    blacklist.insert(rand_l2[i].identity().to_owned());
-                    continue
-                };
+                blacklist.insert(rand_l2[i].identity().to_owned());
+                continue;
+            };
 
             let Ok(node_3) = self.try_parse_mix_bond(rand_l3[i]) else {
-                    blacklist.insert(rand_l3[i].identity().to_owned());
-                    continue
-                };
+                blacklist.insert(rand_l3[i].identity().to_owned());
+                continue;
+            };
 
             let Ok(gateway) = self.try_parse_gateway_bond(rand_gateways[i]) else {
-                    blacklist.insert(rand_gateways[i].identity().to_owned());
-                    continue
-                };
+                blacklist.insert(rand_gateways[i].identity().to_owned());
+                continue;
+            };
 
             routes.push(TestRoute::new(rng.gen(), node_1, node_2, node_3, gateway))
         }
@@ -302,7 +301,6 @@ impl PacketPreparer {
         let mix_packets = plaintexts
             .into_iter()
             .map(|p| tester.wrap_plaintext_data(p, topology, None).unwrap())
-            .map(MixPacket::from)
             .collect();
 
         GatewayPackets::new(
@@ -400,12 +398,11 @@ impl PacketPreparer {
                     None,
                 )
                 .unwrap();
-            let mix_packets = mixnode_test_packets.into_iter().map(Into::into).collect();
 
             let gateway_packets = all_gateway_packets
                 .entry(gateway_identity.to_bytes())
                 .or_insert_with(|| GatewayPackets::empty(gateway_address, gateway_identity));
-            gateway_packets.push_packets(mix_packets);
+            gateway_packets.push_packets(mixnode_test_packets);
 
             // and generate test packets for gateways (note the variable recipient)
             for gateway in &gateways {
@@ -425,15 +422,13 @@ impl PacketPreparer {
                         Some(recipient),
                     )
                     .unwrap();
-                let gateway_mix_packets =
-                    gateway_test_packets.into_iter().map(Into::into).collect();
 
                 // and push it into existing struct (if it's a "core" gateway being tested against another route)
                 // or create a new one
                 let gateway_packets = all_gateway_packets
                     .entry(gateway_identity.to_bytes())
                     .or_insert_with(|| GatewayPackets::empty(gateway_address, gateway_identity));
-                gateway_packets.push_packets(gateway_mix_packets);
+                gateway_packets.push_packets(gateway_test_packets);
             }
         }
 
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::network_monitor::gateways_reader::GatewayMessages;
-use crate::network_monitor::test_packet::{NodeTestMessage, NymApiTestMessageExt};
+use crate::network_monitor::test_packet::{NymApiTestMessageExt, ReceivedTestMessage};
 use crate::network_monitor::ROUTE_TESTING_TEST_NONCE;
 use futures::channel::mpsc;
 use futures::lock::{Mutex, MutexGuard};
@@ -16,6 +16,7 @@ use nym_sphinx::receiver::{MessageReceiver, MessageRecoveryError};
 use std::mem;
 use std::sync::Arc;
 use thiserror::Error;
+use tokio::time::Instant;
 
 pub(crate) type ReceivedProcessorSender = mpsc::UnboundedSender<GatewayMessages>;
 pub(crate) type ReceivedProcessorReceiver = mpsc::UnboundedReceiver<GatewayMessages>;
@@ -54,7 +55,7 @@ struct ReceivedProcessorInner<R: MessageReceiver> {
 
     /// Vector containing all received (and decrypted) packets in the current test run.
     // TODO: perhaps a different structure would be better here
-    received_packets: Vec<NodeTestMessage>,
+    received_packets: Vec<ReceivedTestMessage>,
 }
 
 impl<R: MessageReceiver> ReceivedProcessorInner<R> {
@@ -65,7 +66,10 @@ impl<R: MessageReceiver> ReceivedProcessorInner<R> {
             return Err(ProcessingError::ReceivedOutsideTestRun);
         }
 
-        let test_msg = self.test_processor.process_mixnet_message(raw_message)?;
+        let received_at = Instant::now();
+        let (test_msg, fragment_id) = self
+            .test_processor
+            .process_identified_mixnet_message(raw_message)?;
 
         if test_msg.ext.test_nonce != self.test_nonce.unwrap() {
             return Err(ProcessingError::NonMatchingNonce {
@@ -74,7 +78,11 @@ impl<R: MessageReceiver> ReceivedProcessorInner<R> {
             });
         }
 
-        self.received_packets.push(test_msg);
+        self.received_packets.push(ReceivedTestMessage {
+            message: test_msg,
+            fragment_id,
+            received_at,
+        });
         Ok(())
     }
 
@@ -111,7 +119,7 @@ impl<R: MessageReceiver> ReceivedProcessorInner<R> {
         }
     }
 
-    fn finish_run(&mut self) -> Vec<NodeTestMessage> {
+    fn finish_run(&mut self) -> Vec<ReceivedTestMessage> {
         self.test_nonce = None;
         mem::take(&mut self.received_packets)
     }
@@ -220,7 +228,7 @@ impl<R: MessageReceiver + Send + 'static> ReceivedProcessor<R> {
             .expect("processing task has died!");
     }
 
-    pub(super) async fn return_received(&mut self) -> Vec<NodeTestMessage> {
+    pub(super) async fn return_received(&mut self) -> Vec<ReceivedTestMessage> {
         // ask for the lock back
         self.permit_changer
             .as_mut()
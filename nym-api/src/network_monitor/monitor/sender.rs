@@ -5,6 +5,7 @@ use crate::network_monitor::monitor::gateway_clients_cache::{
     ActiveGatewayClients, GatewayClientHandle,
 };
 use crate::network_monitor::monitor::gateways_pinger::GatewayPinger;
+use crate::network_monitor::monitor::latency::{SentPacket, SentPacketsTracker};
 use crate::network_monitor::monitor::receiver::{GatewayClientUpdate, GatewayClientUpdateSender};
 use crate::support::nyxd;
 use futures::channel::mpsc;
@@ -18,9 +19,11 @@ use nym_credential_storage::persistent_storage::PersistentStorage;
 use nym_crypto::asymmetric::identity::{self, PUBLIC_KEY_LENGTH};
 use nym_gateway_client::error::GatewayClientError;
 use nym_gateway_client::{AcknowledgementReceiver, GatewayClient, MixnetMessageReceiver};
-use nym_sphinx::forwarding::packet::MixPacket;
+use nym_sphinx::chunking::fragment::FragmentIdentifier;
+use nym_sphinx::preparer::PreparedFragment;
 use nym_task::TaskClient;
 use pin_project::pin_project;
+use std::collections::HashMap;
 use std::mem;
 use std::num::NonZeroUsize;
 use std::pin::Pin;
@@ -39,14 +42,14 @@ pub(crate) struct GatewayPackets {
     pub(crate) pub_key: identity::PublicKey,
 
     /// All the packets that are going to get sent to the gateway.
-    pub(crate) packets: Vec<MixPacket>,
+    pub(crate) packets: Vec<PreparedFragment>,
 }
 
 impl GatewayPackets {
     pub(crate) fn new(
         clients_address: String,
         pub_key: identity::PublicKey,
-        packets: Vec<MixPacket>,
+        packets: Vec<PreparedFragment>,
     ) -> Self {
         GatewayPackets {
             clients_address,
@@ -63,7 +66,7 @@ impl GatewayPackets {
         }
     }
 
-    pub(super) fn push_packets(&mut self, mut packets: Vec<MixPacket>) {
+    pub(super) fn push_packets(&mut self, mut packets: Vec<PreparedFragment>) {
         if self.packets.is_empty() {
             self.packets = packets
         } else if self.packets.len() > packets.len() {
@@ -122,6 +125,7 @@ pub(crate) struct PacketSender {
     active_gateway_clients: ActiveGatewayClients,
 
     fresh_gateway_client_data: Arc<FreshGatewayClientData>,
+    sent_packets: SentPacketsTracker,
     gateway_connection_timeout: Duration,
     max_concurrent_clients: usize,
     max_sending_rate: usize,
@@ -150,12 +154,18 @@ impl PacketSender {
                 bandwidth_controller,
                 disabled_credentials_mode,
             }),
+            sent_packets: SentPacketsTracker::new(),
             gateway_connection_timeout,
             max_concurrent_clients,
             max_sending_rate,
         }
     }
 
+    /// Returns timing information of all the packets sent since the previous call.
+    pub(crate) fn take_sent_packets(&self) -> HashMap<FragmentIdentifier, SentPacket> {
+        self.sent_packets.take()
+    }
+
     pub(crate) fn spawn_gateways_pinger(&self, pinging_interval: Duration, shutdown: TaskClient) {
         let gateway_pinger = GatewayPinger::new(
             self.active_gateway_clients.clone(),
@@ -207,8 +217,9 @@ impl PacketSender {
 
     async fn attempt_to_send_packets(
         client: &mut GatewayClient<nyxd::Client, PersistentStorage>,
-        mut mix_packets: Vec<MixPacket>,
+        mut mix_packets: Vec<PreparedFragment>,
         max_sending_rate: usize,
+        sent_packets: &SentPacketsTracker,
     ) -> Result<(), GatewayClientError> {
         let gateway_id = client.gateway_identity().to_base58_string();
         info!(
@@ -219,7 +230,10 @@ impl PacketSender {
 
         if mix_packets.len() <= max_sending_rate {
             debug!("Everything is going to get sent as one.");
-            client.batch_send_mix_packets(mix_packets).await?;
+            sent_packets.record_sent(&mix_packets);
+            client
+                .batch_send_mix_packets(mix_packets.into_iter().map(Into::into).collect())
+                .await?;
         } else {
             let packets_per_time_chunk =
                 (max_sending_rate as f64 * TIME_CHUNK_SIZE.as_secs_f64()) as usize;
@@ -231,7 +245,10 @@ impl PacketSender {
                 max_sending_rate, total_expected_time, gateway_id
             );
 
-            fn split_off_vec(vec: &mut Vec<MixPacket>, at: usize) -> Option<Vec<MixPacket>> {
+            fn split_off_vec(
+                vec: &mut Vec<PreparedFragment>,
+                at: usize,
+            ) -> Option<Vec<PreparedFragment>> {
                 if vec.is_empty() {
                     None
                 } else {
@@ -248,10 +265,15 @@ impl PacketSender {
             while let Some(retained) = split_off_vec(&mut mix_packets, packets_per_time_chunk) {
                 trace!("Sending {} packets...", mix_packets.len());
 
+                sent_packets.record_sent(&mix_packets);
                 if mix_packets.len() == 1 {
-                    client.send_mix_packet(mix_packets.pop().unwrap()).await?;
+                    client
+                        .send_mix_packet(mix_packets.pop().unwrap().into())
+                        .await?;
                 } else {
-                    client.batch_send_mix_packets(mix_packets).await?;
+                    client
+                        .batch_send_mix_packets(mix_packets.into_iter().map(Into::into).collect())
+                        .await?;
                 }
 
                 tokio::time::sleep(TIME_CHUNK_SIZE).await;
@@ -334,6 +356,7 @@ impl PacketSender {
         fresh_gateway_client_data: Arc<FreshGatewayClientData>,
         client: Option<GatewayClientHandle>,
         max_sending_rate: usize,
+        sent_packets: SentPacketsTracker,
     ) -> Option<GatewayClientHandle> {
         let existing_client = client.is_some();
 
@@ -380,7 +403,12 @@ impl PacketSender {
 
         match tokio::time::timeout(
             timeout,
-            Self::attempt_to_send_packets(unwrapped_client, packets.packets, max_sending_rate),
+            Self::attempt_to_send_packets(
+                unwrapped_client,
+                packets.packets,
+                max_sending_rate,
+                &sent_packets,
+            ),
         )
         .await
         {
@@ -454,6 +482,7 @@ impl PacketSender {
             None
         };
         let max_sending_rate = self.max_sending_rate;
+        let sent_packets = &self.sent_packets;
 
         let guard = self.active_gateway_clients.lock().await;
         // this clippy warning is a false positive as we cannot get rid of the collect by moving
@@ -491,6 +520,7 @@ impl PacketSender {
                     fresh_data,
                     client,
                     max_sending_rate,
+                    sent_packets.clone(),
                 )
                 .await
             },
@@ -1,11 +1,15 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::network_monitor::monitor::latency::{
+    estimate_node_latencies, LatencyPercentiles, SentPacket,
+};
 use crate::network_monitor::monitor::preparer::InvalidNode;
-use crate::network_monitor::test_packet::NodeTestMessage;
+use crate::network_monitor::test_packet::ReceivedTestMessage;
 use crate::network_monitor::test_route::TestRoute;
 use nym_mixnet_contract_common::MixId;
 use nym_node_tester_utils::node::{NodeType, TestableNode};
+use nym_sphinx::chunking::fragment::FragmentIdentifier;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
@@ -26,15 +30,23 @@ pub(crate) struct MixnodeResult {
     pub(crate) identity: String,
     pub(crate) owner: String,
     pub(crate) reliability: u8,
+    pub(crate) latency: Option<LatencyPercentiles>,
 }
 
 impl MixnodeResult {
-    pub(crate) fn new(mix_id: MixId, identity: String, owner: String, reliability: u8) -> Self {
+    pub(crate) fn new(
+        mix_id: MixId,
+        identity: String,
+        owner: String,
+        reliability: u8,
+        latency: Option<LatencyPercentiles>,
+    ) -> Self {
         MixnodeResult {
             mix_id,
             identity,
             owner,
             reliability,
+            latency,
         }
     }
 }
@@ -44,14 +56,21 @@ pub(crate) struct GatewayResult {
     pub(crate) identity: String,
     pub(crate) owner: String,
     pub(crate) reliability: u8,
+    pub(crate) latency: Option<LatencyPercentiles>,
 }
 
 impl GatewayResult {
-    pub(crate) fn new(identity: String, owner: String, reliability: u8) -> Self {
+    pub(crate) fn new(
+        identity: String,
+        owner: String,
+        reliability: u8,
+        latency: Option<LatencyPercentiles>,
+    ) -> Self {
         GatewayResult {
             identity,
             owner,
             reliability,
+            latency,
         }
     }
 }
@@ -282,7 +301,8 @@ impl SummaryProducer {
         &self,
         tested_mixnodes: Vec<TestableNode>,
         tested_gateways: Vec<TestableNode>,
-        received_packets: Vec<NodeTestMessage>,
+        sent_packets: &HashMap<FragmentIdentifier, SentPacket>,
+        received_packets: Vec<ReceivedTestMessage>,
         invalid_mixnodes: Vec<InvalidNode>,
         invalid_gateways: Vec<InvalidNode>,
         test_routes: &[TestRoute],
@@ -297,6 +317,8 @@ impl SummaryProducer {
             raw_route_results.insert(test_route.id(), 0);
         }
 
+        let mut latencies = estimate_node_latencies(sent_packets, &received_packets);
+
         let mut raw_results = HashMap::new();
 
         for tested_mixnode in tested_mixnodes {
@@ -316,8 +338,10 @@ impl SummaryProducer {
         }
 
         for received in received_packets {
-            *raw_results.entry(received.tested_node).or_default() += 1usize;
-            *raw_route_results.entry(received.ext.route_id).or_default() += 1usize;
+            *raw_results.entry(received.message.tested_node).or_default() += 1usize;
+            *raw_route_results
+                .entry(received.message.ext.route_id)
+                .or_default() += 1usize;
         }
 
         let mut mixnode_results = Vec::new();
@@ -326,15 +350,22 @@ impl SummaryProducer {
         for (node, received) in raw_results {
             let performance = received as f32 / per_node_expected as f32 * 100.0;
             let reliability = performance.round() as u8;
+            let latency = latencies.remove(&node);
 
             match node.typ {
                 NodeType::Mixnode { mix_id } => {
-                    let res =
-                        MixnodeResult::new(mix_id, node.encoded_identity, node.owner, reliability);
+                    let res = MixnodeResult::new(
+                        mix_id,
+                        node.encoded_identity,
+                        node.owner,
+                        reliability,
+                        latency,
+                    );
                     mixnode_results.push(res)
                 }
                 NodeType::Gateway => {
-                    let res = GatewayResult::new(node.encoded_identity, node.owner, reliability);
+                    let res =
+                        GatewayResult::new(node.encoded_identity, node.owner, reliability, latency);
                     gateway_results.push(res)
                 }
             }
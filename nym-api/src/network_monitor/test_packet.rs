@@ -3,11 +3,21 @@
 
 use nym_node_tester_utils::error::NetworkTestingError;
 use nym_node_tester_utils::TestMessage;
+use nym_sphinx::chunking::fragment::FragmentIdentifier;
 use nym_topology::mix;
 use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
 
 pub(crate) type NodeTestMessage = TestMessage<NymApiTestMessageExt>;
 
+/// Test message that got received back from the network alongside the information
+/// required for matching it against the sent packet.
+pub(crate) struct ReceivedTestMessage {
+    pub(crate) message: NodeTestMessage,
+    pub(crate) fragment_id: FragmentIdentifier,
+    pub(crate) received_at: Instant,
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Hash)]
 pub(crate) struct NymApiTestMessageExt {
     pub(crate) route_id: u64,
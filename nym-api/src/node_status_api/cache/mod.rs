@@ -119,6 +119,21 @@ impl NodeStatusCache {
         self.get(|c| c.inclusion_probabilities.clone()).await
     }
 
+    /// Unix timestamp of the most recent refresh of the cache, if it has ever happened.
+    pub(crate) async fn last_refresh_timestamp(&self) -> Option<i64> {
+        match time::timeout(Duration::from_millis(CACHE_TIMEOUT_MS), self.inner.read()).await {
+            Ok(cache) => {
+                // default (i.e. never updated) cache has its timestamp set to 0
+                let timestamp = cache.mixnodes_annotated.timestamp();
+                (timestamp != 0).then_some(timestamp)
+            }
+            Err(err) => {
+                error!("{err}");
+                None
+            }
+        }
+    }
+
     pub async fn mixnode_details(
         &self,
         mix_id: MixId,
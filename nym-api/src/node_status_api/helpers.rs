@@ -8,11 +8,11 @@ use crate::{NodeStatusCache, NymContractCache};
 use cosmwasm_std::Decimal;
 use nym_api_requests::models::{
     AllInclusionProbabilitiesResponse, ComputeRewardEstParam, GatewayBondAnnotated,
-    GatewayCoreStatusResponse, GatewayStatusReportResponse, GatewayUptimeHistoryResponse,
-    GatewayUptimeResponse, InclusionProbabilityResponse, MixNodeBondAnnotated,
-    MixnodeCoreStatusResponse, MixnodeStatusReportResponse, MixnodeStatusResponse,
-    MixnodeUptimeHistoryResponse, RewardEstimationResponse, StakeSaturationResponse,
-    UptimeResponse,
+    GatewayCoreStatusResponse, GatewayLatencyResponse, GatewayStatusReportResponse,
+    GatewayUptimeHistoryResponse, GatewayUptimeResponse, InclusionProbabilityResponse,
+    MixNodeBondAnnotated, MixnodeCoreStatusResponse, MixnodeLatencyResponse,
+    MixnodeStatusReportResponse, MixnodeStatusResponse, MixnodeUptimeHistoryResponse,
+    RewardEstimationResponse, StakeSaturationResponse, UptimeResponse,
 };
 use nym_mixnet_contract_common::{MixId, RewardedSetNodeStatus};
 use rocket::http::Status;
@@ -101,6 +101,22 @@ pub(crate) async fn _gateway_core_status_count(
     })
 }
 
+pub(crate) async fn _gateway_latency(
+    storage: &State<NymApiStorage>,
+    identity: &str,
+    since: Option<i64>,
+) -> Result<GatewayLatencyResponse, ErrorResponse> {
+    let measurements = storage
+        .get_gateway_latencies(identity, since)
+        .await
+        .map_err(|err| ErrorResponse::new(err.to_string(), Status::NotFound))?;
+
+    Ok(GatewayLatencyResponse {
+        identity: identity.to_string(),
+        measurements: measurements.into_iter().map(Into::into).collect(),
+    })
+}
+
 pub(crate) async fn _mixnode_report(
     cache: &NodeStatusCache,
     mix_id: MixId,
@@ -141,6 +157,22 @@ pub(crate) async fn _mixnode_core_status_count(
     Ok(MixnodeCoreStatusResponse { mix_id, count })
 }
 
+pub(crate) async fn _mixnode_latency(
+    storage: &State<NymApiStorage>,
+    mix_id: MixId,
+    since: Option<i64>,
+) -> Result<MixnodeLatencyResponse, ErrorResponse> {
+    let measurements = storage
+        .get_mixnode_latencies(mix_id, since)
+        .await
+        .map_err(|err| ErrorResponse::new(err.to_string(), Status::NotFound))?;
+
+    Ok(MixnodeLatencyResponse {
+        mix_id,
+        measurements: measurements.into_iter().map(Into::into).collect(),
+    })
+}
+
 pub(crate) async fn _get_mixnode_status(
     cache: &NymContractCache,
     mix_id: MixId,
@@ -36,9 +36,11 @@ pub(crate) fn node_status_routes(
             settings: routes::gateway_report,
             routes::gateway_uptime_history,
             routes::gateway_core_status_count,
+            routes::gateway_latency,
             routes::mixnode_report,
             routes::mixnode_uptime_history,
             routes::mixnode_core_status_count,
+            routes::mixnode_latency,
             routes::get_mixnode_status,
             routes::get_mixnode_reward_estimation,
             routes::compute_mixnode_reward_estimation,
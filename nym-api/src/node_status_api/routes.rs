@@ -4,24 +4,24 @@
 use super::helpers::_get_gateways_detailed;
 use super::NodeStatusCache;
 use crate::node_status_api::helpers::{
-    _compute_mixnode_reward_estimation, _gateway_core_status_count, _gateway_report,
-    _gateway_uptime_history, _get_active_set_detailed, _get_gateway_avg_uptime,
+    _compute_mixnode_reward_estimation, _gateway_core_status_count, _gateway_latency,
+    _gateway_report, _gateway_uptime_history, _get_active_set_detailed, _get_gateway_avg_uptime,
     _get_gateways_detailed_unfiltered, _get_mixnode_avg_uptime,
     _get_mixnode_inclusion_probabilities, _get_mixnode_inclusion_probability,
     _get_mixnode_reward_estimation, _get_mixnode_stake_saturation, _get_mixnode_status,
     _get_mixnodes_detailed, _get_mixnodes_detailed_unfiltered, _get_rewarded_set_detailed,
-    _mixnode_core_status_count, _mixnode_report, _mixnode_uptime_history,
+    _mixnode_core_status_count, _mixnode_latency, _mixnode_report, _mixnode_uptime_history,
 };
 use crate::node_status_api::models::ErrorResponse;
 use crate::storage::NymApiStorage;
 use crate::NymContractCache;
 use nym_api_requests::models::{
     AllInclusionProbabilitiesResponse, ComputeRewardEstParam, GatewayBondAnnotated,
-    GatewayCoreStatusResponse, GatewayStatusReportResponse, GatewayUptimeHistoryResponse,
-    GatewayUptimeResponse, InclusionProbabilityResponse, MixNodeBondAnnotated,
-    MixnodeCoreStatusResponse, MixnodeStatusReportResponse, MixnodeStatusResponse,
-    MixnodeUptimeHistoryResponse, RewardEstimationResponse, StakeSaturationResponse,
-    UptimeResponse,
+    GatewayCoreStatusResponse, GatewayLatencyResponse, GatewayStatusReportResponse,
+    GatewayUptimeHistoryResponse, GatewayUptimeResponse, InclusionProbabilityResponse,
+    MixNodeBondAnnotated, MixnodeCoreStatusResponse, MixnodeLatencyResponse,
+    MixnodeStatusReportResponse, MixnodeStatusResponse, MixnodeUptimeHistoryResponse,
+    RewardEstimationResponse, StakeSaturationResponse, UptimeResponse,
 };
 use nym_mixnet_contract_common::MixId;
 use rocket::serde::json::Json;
@@ -58,6 +58,16 @@ pub(crate) async fn gateway_core_status_count(
     ))
 }
 
+#[openapi(tag = "status")]
+#[get("/gateway/<identity>/latency?<since>")]
+pub(crate) async fn gateway_latency(
+    storage: &State<NymApiStorage>,
+    identity: &str,
+    since: Option<i64>,
+) -> Result<Json<GatewayLatencyResponse>, ErrorResponse> {
+    Ok(Json(_gateway_latency(storage, identity, since).await?))
+}
+
 #[openapi(tag = "status")]
 #[get("/mixnode/<mix_id>/report")]
 pub(crate) async fn mixnode_report(
@@ -88,6 +98,16 @@ pub(crate) async fn mixnode_core_status_count(
     ))
 }
 
+#[openapi(tag = "status")]
+#[get("/mixnode/<mix_id>/latency?<since>")]
+pub(crate) async fn mixnode_latency(
+    storage: &State<NymApiStorage>,
+    mix_id: MixId,
+    since: Option<i64>,
+) -> Result<Json<MixnodeLatencyResponse>, ErrorResponse> {
+    Ok(Json(_mixnode_latency(storage, mix_id, since).await?))
+}
+
 #[openapi(tag = "status")]
 #[get("/mixnode/<mix_id>/status")]
 pub(crate) async fn get_mixnode_status(
@@ -317,6 +317,21 @@ impl NymContractCache {
         self.initialised.load(Ordering::Relaxed)
     }
 
+    /// Unix timestamp of the most recent refresh of the cache, if it has ever happened.
+    pub(crate) async fn last_refresh_timestamp(&self) -> Option<i64> {
+        if !self.initialised() {
+            return None;
+        }
+
+        match time::timeout(Duration::from_millis(100), self.inner.read()).await {
+            Ok(cache) => Some(cache.mixnodes.timestamp()),
+            Err(err) => {
+                error!("{err}");
+                None
+            }
+        }
+    }
+
     pub(crate) async fn wait_for_initial_values(&self) {
         let initialisation_backoff = Duration::from_secs(5);
         loop {
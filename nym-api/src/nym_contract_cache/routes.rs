@@ -7,11 +7,12 @@ use crate::{
         NodeStatusCache,
     },
     nym_contract_cache::cache::NymContractCache,
+    topology_snapshot::TopologySnapshotSigner,
 };
-use nym_api_requests::models::MixNodeBondAnnotated;
-use nym_mixnet_contract_common::{
-    mixnode::MixNodeDetails, reward_params::RewardingParams, GatewayBond, Interval, MixId,
+use nym_api_requests::models::{
+    GatewayBondWithSphinxKeys, MixNodeBondAnnotated, MixNodeDetailsWithSphinxKeys,
 };
+use nym_mixnet_contract_common::{reward_params::RewardingParams, Interval, MixId};
 
 use nym_name_service_common::response::NamesListResponse;
 use nym_service_provider_directory_common::response::ServicesListResponse;
@@ -21,8 +22,12 @@ use std::collections::HashSet;
 
 #[openapi(tag = "contract-cache")]
 #[get("/mixnodes")]
-pub async fn get_mixnodes(cache: &State<NymContractCache>) -> Json<Vec<MixNodeDetails>> {
-    Json(cache.mixnodes_filtered().await)
+pub async fn get_mixnodes(
+    cache: &State<NymContractCache>,
+    signer: &State<TopologySnapshotSigner>,
+) -> Json<Vec<MixNodeDetailsWithSphinxKeys>> {
+    let mixnodes = cache.mixnodes_filtered().await;
+    Json(signer.with_mixnodes_sphinx_keys(cache, mixnodes).await)
 }
 
 // DEPRECATED: this endpoint now lives in `node_status_api`. Once all consumers are updated,
@@ -42,14 +47,22 @@ pub async fn get_mixnodes_detailed(
 
 #[openapi(tag = "contract-cache")]
 #[get("/gateways")]
-pub async fn get_gateways(cache: &State<NymContractCache>) -> Json<Vec<GatewayBond>> {
-    Json(cache.gateways_filtered().await)
+pub async fn get_gateways(
+    cache: &State<NymContractCache>,
+    signer: &State<TopologySnapshotSigner>,
+) -> Json<Vec<GatewayBondWithSphinxKeys>> {
+    let gateways = cache.gateways_filtered().await;
+    Json(signer.with_gateways_sphinx_keys(cache, gateways).await)
 }
 
 #[openapi(tag = "contract-cache")]
 #[get("/mixnodes/rewarded")]
-pub async fn get_rewarded_set(cache: &State<NymContractCache>) -> Json<Vec<MixNodeDetails>> {
-    Json(cache.rewarded_set().await.value)
+pub async fn get_rewarded_set(
+    cache: &State<NymContractCache>,
+    signer: &State<TopologySnapshotSigner>,
+) -> Json<Vec<MixNodeDetailsWithSphinxKeys>> {
+    let mixnodes = cache.rewarded_set().await.value;
+    Json(signer.with_mixnodes_sphinx_keys(cache, mixnodes).await)
 }
 
 // DEPRECATED: this endpoint now lives in `node_status_api`. Once all consumers are updated,
@@ -69,8 +82,12 @@ pub async fn get_rewarded_set_detailed(
 
 #[openapi(tag = "contract-cache")]
 #[get("/mixnodes/active")]
-pub async fn get_active_set(cache: &State<NymContractCache>) -> Json<Vec<MixNodeDetails>> {
-    Json(cache.active_set().await.value)
+pub async fn get_active_set(
+    cache: &State<NymContractCache>,
+    signer: &State<TopologySnapshotSigner>,
+) -> Json<Vec<MixNodeDetailsWithSphinxKeys>> {
+    let mixnodes = cache.active_set().await.value;
+    Json(signer.with_mixnodes_sphinx_keys(cache, mixnodes).await)
 }
 
 // DEPRECATED: this endpoint now lives in `node_status_api`. Once all consumers are updated,
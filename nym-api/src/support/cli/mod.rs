@@ -135,6 +135,11 @@ pub(crate) fn build_config(args: CliArgs) -> Result<Config> {
         crate::coconut::dkg::controller::init_keypair(&config)?;
     }
 
+    // nym-apis initialised before the identity keys were introduced won't have them just yet
+    if !config.get_private_identity_key_file().exists() {
+        crate::topology_snapshot::init_identity_keypair(&config)?;
+    }
+
     Ok(config)
 }
 
@@ -115,6 +115,25 @@ pub struct Base {
 
     /// Mnemonic used for rewarding and/or multisig operations
     mnemonic: bip39::Mnemonic,
+
+    /// Path to file containing private identity key of the nym-api.
+    private_identity_key_file: PathBuf,
+
+    /// Path to file containing public identity key of the nym-api.
+    public_identity_key_file: PathBuf,
+}
+
+impl Base {
+    pub const PRIVATE_IDENTITY_KEY_FILE: &'static str = "private_identity.pem";
+    pub const PUBLIC_IDENTITY_KEY_FILE: &'static str = "public_identity.pem";
+
+    fn default_private_identity_key_file(id: &str) -> PathBuf {
+        Config::default_data_directory(id).join(Self::PRIVATE_IDENTITY_KEY_FILE)
+    }
+
+    fn default_public_identity_key_file(id: &str) -> PathBuf {
+        Config::default_data_directory(id).join(Self::PUBLIC_IDENTITY_KEY_FILE)
+    }
 }
 
 impl Default for Base {
@@ -134,6 +153,8 @@ impl Default for Base {
             mixnet_contract_address: MIXNET_CONTRACT_ADDRESS.parse().unwrap(),
             vesting_contract_address: VESTING_CONTRACT_ADDRESS.parse().unwrap(),
             mnemonic: bip39::Mnemonic::generate(24).unwrap(),
+            private_identity_key_file: Default::default(),
+            public_identity_key_file: Default::default(),
         }
     }
 }
@@ -385,6 +406,8 @@ impl Config {
 
     pub fn with_id(mut self, id: &str) -> Self {
         self.base.id = id.to_string();
+        self.base.private_identity_key_file = Base::default_private_identity_key_file(id);
+        self.base.public_identity_key_file = Base::default_public_identity_key_file(id);
         self.node_status_api.database_path = NodeStatusAPI::default_database_path(id);
         self.network_monitor.credentials_database_path =
             NetworkMonitor::default_credentials_database_path(id);
@@ -500,6 +523,14 @@ impl Config {
         self.base.vesting_contract_address.clone()
     }
 
+    pub fn get_private_identity_key_file(&self) -> PathBuf {
+        self.base.private_identity_key_file.clone()
+    }
+
+    pub fn get_public_identity_key_file(&self) -> PathBuf {
+        self.base.public_identity_key_file.clone()
+    }
+
     pub fn get_mnemonic(&self) -> bip39::Mnemonic {
         self.base.mnemonic.clone()
     }
@@ -30,6 +30,12 @@ vesting_contract_address = '{{ base.vesting_contract_address }}'
 # Mnemonic used for rewarding and validator interaction
 mnemonic = '{{ base.mnemonic }}'
 
+# Path to file containing private identity key of the nym-api.
+private_identity_key_file = '{{ base.private_identity_key_file }}'
+
+# Path to file containing public identity key of the nym-api.
+public_identity_key_file = '{{ base.public_identity_key_file }}'
+
 ##### network monitor config options #####
 
 [network_monitor]
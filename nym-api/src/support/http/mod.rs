@@ -3,12 +3,15 @@
 
 use crate::circulating_supply_api::cache::CirculatingSupplyCache;
 use crate::coconut::{self, comm::QueryCommunicationChannel, InternalSignRequest};
+use crate::metrics::{self, NetworkMonitorMetrics};
 use crate::node_status_api::{self, NodeStatusCache};
 use crate::nym_contract_cache::cache::NymContractCache;
 use crate::support::config::Config;
 use crate::support::{nyxd, storage};
+use crate::topology_snapshot::{self, TopologySnapshotSigner};
 use crate::{circulating_supply_api, nym_contract_cache};
 use anyhow::Result;
+use nym_crypto::asymmetric::identity;
 use rocket::http::Method;
 use rocket::{Ignite, Rocket};
 use rocket_cors::{AllowedHeaders, AllowedOrigins, Cors};
@@ -22,6 +25,7 @@ pub(crate) async fn setup_rocket(
     mix_denom: String,
     _nyxd_client: nyxd::Client,
     coconut_keypair: coconut::keypair::KeyPair,
+    identity_keypair: identity::KeyPair,
 ) -> anyhow::Result<Rocket<Ignite>> {
     let openapi_settings = rocket_okapi::settings::OpenApiSettings::default();
     let mut rocket = rocket::build();
@@ -34,14 +38,18 @@ pub(crate) async fn setup_rocket(
         "" => circulating_supply_api::circulating_supply_routes(&openapi_settings),
         "" => nym_contract_cache::nym_contract_cache_routes(&openapi_settings),
         "/status" => node_status_api::node_status_routes(&openapi_settings, config.get_network_monitor_enabled()),
+        "/topology" => topology_snapshot::topology_snapshot_routes(&openapi_settings),
     }
 
     let rocket = rocket
         .mount("/swagger", make_swagger_ui(&openapi::get_docs()))
+        .mount("/", routes![metrics::routes::metrics])
         .attach(setup_cors()?)
         .attach(NymContractCache::stage())
         .attach(NodeStatusCache::stage())
-        .attach(CirculatingSupplyCache::stage(mix_denom.clone()));
+        .attach(CirculatingSupplyCache::stage(mix_denom.clone()))
+        .attach(TopologySnapshotSigner::stage(identity_keypair))
+        .attach(NetworkMonitorMetrics::stage());
 
     // This is not a very nice approach. A lazy value would be more suitable, but that's still
     // a nightly feature: https://github.com/rust-lang/rust/issues/74465
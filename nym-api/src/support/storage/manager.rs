@@ -4,7 +4,7 @@ use crate::network_monitor::monitor::summary_producer::{GatewayResult, MixnodeRe
 use crate::node_status_api::models::{HistoricalUptime, Uptime};
 use crate::node_status_api::utils::{ActiveGatewayStatuses, ActiveMixnodeStatuses};
 use crate::support::storage::models::{
-    ActiveGateway, ActiveMixnode, NodeStatus, RewardingReport, TestingRoute,
+    ActiveGateway, ActiveMixnode, NodeLatency, NodeStatus, RewardingReport, TestingRoute,
 };
 use nym_mixnet_contract_common::{EpochId, IdentityKey, MixId};
 use std::convert::TryFrom;
@@ -284,6 +284,64 @@ impl StorageManager {
         .await
     }
 
+    /// Gets all latency measurements for mixnode with particular identity that were inserted
+    /// into the database after the specified unix timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `mix_id`: mix-id (as assigned by the smart contract) of the mixnode.
+    /// * `timestamp`: unix timestamp of the lower bound of the selection.
+    pub(crate) async fn get_mixnode_latencies_since(
+        &self,
+        mix_id: MixId,
+        timestamp: i64,
+    ) -> Result<Vec<NodeLatency>, sqlx::Error> {
+        sqlx::query_as!(
+            NodeLatency,
+            r#"
+                SELECT timestamp, samples as "samples: u32", p50 as "p50: u32", p90 as "p90: u32", p99 as "p99: u32"
+                    FROM mixnode_latency
+                    JOIN mixnode_details
+                    ON mixnode_latency.mixnode_details_id = mixnode_details.id
+                    WHERE mixnode_details.mix_id=? AND mixnode_latency.timestamp > ?
+                    ORDER BY mixnode_latency.timestamp;
+            "#,
+            mix_id,
+            timestamp,
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
+    /// Gets all latency measurements for gateway with particular identity that were inserted
+    /// into the database after the specified unix timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity`: identity (base58-encoded public key) of the gateway.
+    /// * `timestamp`: unix timestamp of the lower bound of the selection.
+    pub(crate) async fn get_gateway_latencies_since(
+        &self,
+        identity: &str,
+        timestamp: i64,
+    ) -> Result<Vec<NodeLatency>, sqlx::Error> {
+        sqlx::query_as!(
+            NodeLatency,
+            r#"
+                SELECT timestamp, samples as "samples: u32", p50 as "p50: u32", p90 as "p90: u32", p99 as "p99: u32"
+                    FROM gateway_latency
+                    JOIN gateway_details
+                    ON gateway_latency.gateway_details_id = gateway_details.id
+                    WHERE gateway_details.identity=? AND gateway_latency.timestamp > ?
+                    ORDER BY gateway_latency.timestamp;
+            "#,
+            identity,
+            timestamp,
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
     /// Gets the historical daily uptime associated with the particular mixnode
     ///
     /// # Arguments
@@ -496,6 +554,23 @@ impl StorageManager {
                 )
                 .execute(&mut tx)
                 .await?;
+
+            // and the latency, if we managed to measure it
+            if let Some(latency) = mixnode_result.latency {
+                sqlx::query!(
+                    r#"
+                        INSERT INTO mixnode_latency (mixnode_details_id, samples, p50, p90, p99, timestamp) VALUES (?, ?, ?, ?, ?, ?);
+                    "#,
+                    mixnode_id,
+                    latency.samples,
+                    latency.p50_ms,
+                    latency.p90_ms,
+                    latency.p99_ms,
+                    timestamp
+                )
+                .execute(&mut tx)
+                .await?;
+            }
         }
 
         // finally commit the transaction
@@ -545,6 +620,23 @@ impl StorageManager {
                 )
                 .execute(&mut tx)
                 .await?;
+
+            // and the latency, if we managed to measure it
+            if let Some(latency) = gateway_result.latency {
+                sqlx::query!(
+                    r#"
+                        INSERT INTO gateway_latency (gateway_details_id, samples, p50, p90, p99, timestamp) VALUES (?, ?, ?, ?, ?, ?);
+                    "#,
+                    gateway_id,
+                    latency.samples,
+                    latency.p50_ms,
+                    latency.p90_ms,
+                    latency.p99_ms,
+                    timestamp
+                )
+                .execute(&mut tx)
+                .await?;
+            }
         }
 
         // finally commit the transaction
@@ -780,6 +872,38 @@ impl StorageManager {
         Ok(())
     }
 
+    /// Removes all latency measurements for all mixnodes that are older than the
+    /// provided timestamp. This method is indirectly called at every reward cycle.
+    ///
+    /// # Arguments
+    ///
+    /// * `until`: timestamp specifying the purge cutoff.
+    pub(crate) async fn purge_old_mixnode_latencies(
+        &self,
+        timestamp: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM mixnode_latency WHERE timestamp < ?", timestamp)
+            .execute(&self.connection_pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Removes all latency measurements for all gateways that are older than the
+    /// provided timestamp. This method is indirectly called at every reward cycle.
+    ///
+    /// # Arguments
+    ///
+    /// * `until`: timestamp specifying the purge cutoff.
+    pub(crate) async fn purge_old_gateway_latencies(
+        &self,
+        timestamp: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM gateway_latency WHERE timestamp < ?", timestamp)
+            .execute(&self.connection_pool)
+            .await?;
+        Ok(())
+    }
+
     /// Returns public key, owner and id of all mixnodes that have had any statuses submitted
     /// within the provided time interval.
     ///
@@ -9,7 +9,7 @@ use crate::node_status_api::models::{
 };
 use crate::node_status_api::{ONE_DAY, ONE_HOUR};
 use crate::storage::manager::StorageManager;
-use crate::storage::models::{NodeStatus, TestingRoute};
+use crate::storage::models::{NodeLatency, NodeStatus, TestingRoute};
 use nym_mixnet_contract_common::MixId;
 use rocket::fairing::AdHoc;
 use sqlx::ConnectOptions;
@@ -581,6 +581,46 @@ impl NymApiStorage {
         }
     }
 
+    /// Retrieves all latency measurements of particular mixnode performed by the network monitor
+    /// since the specified unix timestamp. If no value is provided, last 24h of data are used instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `mix_id`: mix-id (as assigned by the smart contract) of the mixnode.
+    /// * `since`: optional unix timestamp indicating the lower bound interval of the selection.
+    pub(crate) async fn get_mixnode_latencies(
+        &self,
+        mix_id: MixId,
+        since: Option<i64>,
+    ) -> Result<Vec<NodeLatency>, NymApiStorageError> {
+        let since = since.unwrap_or_else(|| (OffsetDateTime::now_utc() - ONE_DAY).unix_timestamp());
+
+        self.manager
+            .get_mixnode_latencies_since(mix_id, since)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Retrieves all latency measurements of particular gateway performed by the network monitor
+    /// since the specified unix timestamp. If no value is provided, last 24h of data are used instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity`: identity (base58-encoded public key) of the gateway.
+    /// * `since`: optional unix timestamp indicating the lower bound interval of the selection.
+    pub(crate) async fn get_gateway_latencies(
+        &self,
+        identity: &str,
+        since: Option<i64>,
+    ) -> Result<Vec<NodeLatency>, NymApiStorageError> {
+        let since = since.unwrap_or_else(|| (OffsetDateTime::now_utc() - ONE_DAY).unix_timestamp());
+
+        self.manager
+            .get_gateway_latencies_since(identity, since)
+            .await
+            .map_err(|err| err.into())
+    }
+
     /// Inserts an entry to the database with the network monitor test run information
     /// that has occurred at this instant alongside the results of all the measurements performed.
     ///
@@ -703,13 +743,15 @@ impl NymApiStorage {
             .map_err(|err| err.into())
     }
 
-    /// Removes all ipv4 and ipv6 statuses for all mixnodes and gateways that are older than the
-    /// provided timestamp. This method is called at every reward cycle.
+    /// Removes all ipv4 and ipv6 statuses (and latency measurements) for all mixnodes and gateways
+    /// that are older than the provided timestamp. This method is called at every reward cycle.
     ///
     /// # Arguments
     ///
     /// * `until`: timestamp specifying the purge cutoff.
     pub(crate) async fn purge_old_statuses(&self, until: i64) -> Result<(), NymApiStorageError> {
+        self.manager.purge_old_mixnode_latencies(until).await?;
+        self.manager.purge_old_gateway_latencies(until).await?;
         self.manager.purge_old_mixnode_statuses(until).await?;
         self.manager
             .purge_old_gateway_statuses(until)
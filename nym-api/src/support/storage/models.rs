@@ -1,6 +1,7 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use nym_api_requests::models::LatencyMeasurementResponse;
 use nym_mixnet_contract_common::MixId;
 
 // Internally used struct to catch results from the database to calculate uptimes for given mixnode/gateway
@@ -19,6 +20,27 @@ impl NodeStatus {
     }
 }
 
+// Internally used struct to catch latency measurements of given mixnode/gateway from the database
+pub(crate) struct NodeLatency {
+    pub(crate) timestamp: i64,
+    pub(crate) samples: u32,
+    pub(crate) p50: u32,
+    pub(crate) p90: u32,
+    pub(crate) p99: u32,
+}
+
+impl From<NodeLatency> for LatencyMeasurementResponse {
+    fn from(latency: NodeLatency) -> Self {
+        LatencyMeasurementResponse {
+            timestamp: latency.timestamp,
+            samples: latency.samples,
+            p50_ms: latency.p50,
+            p90_ms: latency.p90,
+            p99_ms: latency.p99,
+        }
+    }
+}
+
 // Internally used structs to catch results from the database to find active mixnodes
 pub(crate) struct ActiveMixnode {
     pub(crate) id: i64,
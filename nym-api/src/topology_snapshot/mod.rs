@@ -0,0 +1,277 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nym_contract_cache::cache::NymContractCache;
+use crate::support::config::Config;
+use crate::topology_snapshot::sphinx_keys::{
+    verify_announcement, AnnouncedSphinxKeys, SphinxKeyAnnouncementError,
+};
+use anyhow::Result;
+use nym_api_requests::models::{
+    GatewayBondWithSphinxKeys, MixNodeDetailsWithSphinxKeys, SignedSphinxKeyAnnouncement,
+    SignedTopologySnapshot, TopologySnapshot, TopologySnapshotGateway, TopologySnapshotMixNode,
+};
+use nym_crypto::asymmetric::identity;
+use nym_mixnet_contract_common::mixnode::MixNodeDetails;
+use nym_mixnet_contract_common::GatewayBond;
+use okapi::openapi3::OpenApi;
+use rand_07::rngs::OsRng;
+use rocket::fairing::AdHoc;
+use rocket::Route;
+use rocket_okapi::openapi_get_routes_spec;
+use rocket_okapi::settings::OpenApiSettings;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tokio::sync::RwLock;
+
+pub(crate) mod routes;
+pub(crate) mod sphinx_keys;
+
+pub(crate) fn topology_snapshot_routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+    openapi_get_routes_spec![settings: routes::get_topology_snapshot, routes::announce_sphinx_key]
+}
+
+pub(crate) fn init_identity_keypair(config: &Config) -> Result<()> {
+    let keypair = identity::KeyPair::new(&mut OsRng);
+    nym_pemstore::store_keypair(
+        &keypair,
+        &nym_pemstore::KeyPairPath::new(
+            config.get_private_identity_key_file(),
+            config.get_public_identity_key_file(),
+        ),
+    )?;
+    Ok(())
+}
+
+pub(crate) fn load_identity_keypair(config: &Config) -> Result<identity::KeyPair> {
+    Ok(nym_pemstore::load_keypair(
+        &nym_pemstore::KeyPairPath::new(
+            config.get_private_identity_key_file(),
+            config.get_public_identity_key_file(),
+        ),
+    )?)
+}
+
+// if the epoch is not yet known, all the keys that have not been removed as expired are returned
+async fn current_epoch_id(contract_cache: &NymContractCache) -> u32 {
+    contract_cache
+        .current_interval()
+        .await
+        .value
+        .map(|interval| interval.current_epoch_absolute_id())
+        .unwrap_or_default()
+}
+
+/// Signed snapshot alongside the epoch it has been created for.
+#[derive(Clone)]
+struct EpochSnapshot {
+    epoch_id: u32,
+    signed: SignedTopologySnapshot,
+}
+
+/// Produces signed snapshots of the active network topology. A single snapshot is created
+/// per epoch and served to all the clients until the epoch advances or until some node announces
+/// a new sphinx key.
+#[derive(Clone)]
+pub(crate) struct TopologySnapshotSigner {
+    identity_keypair: Arc<identity::KeyPair>,
+    latest: Arc<RwLock<Option<EpochSnapshot>>>,
+    announced_sphinx_keys: Arc<RwLock<AnnouncedSphinxKeys>>,
+}
+
+impl TopologySnapshotSigner {
+    fn new(identity_keypair: identity::KeyPair) -> Self {
+        TopologySnapshotSigner {
+            identity_keypair: Arc::new(identity_keypair),
+            latest: Arc::new(RwLock::new(None)),
+            announced_sphinx_keys: Arc::new(RwLock::new(AnnouncedSphinxKeys::default())),
+        }
+    }
+
+    pub(crate) fn stage(identity_keypair: identity::KeyPair) -> AdHoc {
+        AdHoc::on_ignite("Topology Snapshot Stage", |rocket| async {
+            rocket.manage(Self::new(identity_keypair))
+        })
+    }
+
+    fn sign(&self, snapshot: &TopologySnapshot) -> SignedTopologySnapshot {
+        let snapshot = snapshot.encode();
+        let signature = self
+            .identity_keypair
+            .private_key()
+            .sign(snapshot.as_bytes());
+
+        SignedTopologySnapshot {
+            snapshot,
+            signer: self.identity_keypair.public_key().to_base58_string(),
+            signature: signature.to_base58_string(),
+        }
+    }
+
+    /// Verifies and stores the sphinx key announced by a bonded node so that it would be included
+    /// in the subsequent snapshots. Returns whether the announcement has not been seen before.
+    pub(crate) async fn announce_sphinx_key(
+        &self,
+        contract_cache: &NymContractCache,
+        signed: SignedSphinxKeyAnnouncement,
+    ) -> Result<bool, SphinxKeyAnnouncementError> {
+        if !contract_cache.initialised() {
+            return Err(SphinxKeyAnnouncementError::UnknownEpoch);
+        }
+        let epoch_id = contract_cache
+            .current_interval()
+            .await
+            .value
+            .ok_or(SphinxKeyAnnouncementError::UnknownEpoch)?
+            .current_epoch_absolute_id();
+
+        verify_announcement(&signed, epoch_id)?;
+
+        let identity = &signed.announcement.identity_key;
+        let is_bonded = contract_cache
+            .mixnodes_all()
+            .await
+            .iter()
+            .any(|details| &details.bond_information.mix_node.identity_key == identity)
+            || contract_cache
+                .gateways_all()
+                .await
+                .iter()
+                .any(|bond| &bond.gateway.identity_key == identity);
+        if !is_bonded {
+            return Err(SphinxKeyAnnouncementError::NotBonded {
+                identity: identity.clone(),
+            });
+        }
+
+        let newly_announced = self
+            .announced_sphinx_keys
+            .write()
+            .await
+            .insert(signed.announcement)?;
+        if newly_announced {
+            // make sure the clients learn about the new key as soon as possible
+            *self.latest.write().await = None;
+        }
+        Ok(newly_announced)
+    }
+
+    /// Attaches the current and the next sphinx keys announced by the mixnodes, so that the clients
+    /// using the regular topology endpoints would know which key to use in which epoch.
+    pub(crate) async fn with_mixnodes_sphinx_keys(
+        &self,
+        contract_cache: &NymContractCache,
+        mixnodes: Vec<MixNodeDetails>,
+    ) -> Vec<MixNodeDetailsWithSphinxKeys> {
+        let epoch_id = current_epoch_id(contract_cache).await;
+        let announced = self.announced_sphinx_keys.read().await;
+
+        mixnodes
+            .into_iter()
+            .map(|details| MixNodeDetailsWithSphinxKeys {
+                sphinx_keys: announced
+                    .node_keys(&details.bond_information.mix_node.identity_key, epoch_id),
+                details,
+            })
+            .collect()
+    }
+
+    /// Attaches the current and the next sphinx keys announced by the gateways, so that the clients
+    /// using the regular topology endpoints would know which key to use in which epoch.
+    pub(crate) async fn with_gateways_sphinx_keys(
+        &self,
+        contract_cache: &NymContractCache,
+        gateways: Vec<GatewayBond>,
+    ) -> Vec<GatewayBondWithSphinxKeys> {
+        let epoch_id = current_epoch_id(contract_cache).await;
+        let announced = self.announced_sphinx_keys.read().await;
+
+        gateways
+            .into_iter()
+            .map(|bond| GatewayBondWithSphinxKeys {
+                sphinx_keys: announced.node_keys(&bond.gateway.identity_key, epoch_id),
+                bond,
+            })
+            .collect()
+    }
+
+    /// Returns the snapshot for the current epoch, creating it if it doesn't exist yet.
+    pub(crate) async fn current_snapshot(
+        &self,
+        contract_cache: &NymContractCache,
+    ) -> Option<SignedTopologySnapshot> {
+        if !contract_cache.initialised() {
+            return None;
+        }
+        let epoch_id = contract_cache
+            .current_interval()
+            .await
+            .value?
+            .current_epoch_absolute_id();
+
+        if let Some(latest) = self.latest.read().await.as_ref() {
+            if latest.epoch_id == epoch_id {
+                return Some(latest.signed.clone());
+            }
+        }
+
+        let mut latest = self.latest.write().await;
+        // somebody might have created it while we were waiting for the lock
+        if let Some(latest) = latest.as_ref() {
+            if latest.epoch_id == epoch_id {
+                return Some(latest.signed.clone());
+            }
+        }
+
+        let mut announced = self.announced_sphinx_keys.write().await;
+        announced.remove_expired(epoch_id);
+
+        // nodes that have rotated away from their bonded keys, but have no valid key for this epoch
+        // are not going to be able to process any packets, so they're left out
+        let mixnodes = contract_cache
+            .active_set()
+            .await
+            .value
+            .iter()
+            .map(|details| TopologySnapshotMixNode::from(&details.bond_information))
+            .filter_map(|mut node| {
+                announced
+                    .apply(
+                        &node.identity_key,
+                        epoch_id,
+                        &mut node.sphinx_key,
+                        &mut node.sphinx_key_validity,
+                    )
+                    .then_some(node)
+            })
+            .collect();
+        let gateways = contract_cache
+            .gateways_filtered()
+            .await
+            .iter()
+            .map(TopologySnapshotGateway::from)
+            .filter_map(|mut node| {
+                announced
+                    .apply(
+                        &node.identity_key,
+                        epoch_id,
+                        &mut node.sphinx_key,
+                        &mut node.sphinx_key_validity,
+                    )
+                    .then_some(node)
+            })
+            .collect();
+
+        let signed = self.sign(&TopologySnapshot {
+            epoch_id,
+            generated_at: OffsetDateTime::now_utc().unix_timestamp(),
+            mixnodes,
+            gateways,
+        });
+        *latest = Some(EpochSnapshot {
+            epoch_id,
+            signed: signed.clone(),
+        });
+        Some(signed)
+    }
+}
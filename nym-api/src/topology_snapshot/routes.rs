@@ -0,0 +1,49 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node_status_api::models::ErrorResponse;
+use crate::nym_contract_cache::cache::NymContractCache;
+use crate::topology_snapshot::sphinx_keys::SphinxKeyAnnouncementError;
+use crate::topology_snapshot::TopologySnapshotSigner;
+use nym_api_requests::models::{
+    SignedSphinxKeyAnnouncement, SignedTopologySnapshot, SphinxKeyAnnouncementResponse,
+};
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+
+#[openapi(tag = "topology")]
+#[get("/snapshot")]
+pub(crate) async fn get_topology_snapshot(
+    cache: &State<NymContractCache>,
+    signer: &State<TopologySnapshotSigner>,
+) -> Result<Json<SignedTopologySnapshot>, ErrorResponse> {
+    match signer.current_snapshot(cache).await {
+        Some(snapshot) => Ok(Json(snapshot)),
+        None => Err(ErrorResponse::new(
+            "topology snapshot is not yet available",
+            Status::ServiceUnavailable,
+        )),
+    }
+}
+
+#[openapi(tag = "topology")]
+#[post("/sphinx-key", data = "<announcement>")]
+pub(crate) async fn announce_sphinx_key(
+    announcement: Json<SignedSphinxKeyAnnouncement>,
+    cache: &State<NymContractCache>,
+    signer: &State<TopologySnapshotSigner>,
+) -> Result<Json<SphinxKeyAnnouncementResponse>, ErrorResponse> {
+    match signer
+        .announce_sphinx_key(cache, announcement.into_inner())
+        .await
+    {
+        Ok(newly_announced) => Ok(Json(SphinxKeyAnnouncementResponse { newly_announced })),
+        Err(err @ SphinxKeyAnnouncementError::UnknownEpoch) => Err(ErrorResponse::new(
+            err.to_string(),
+            Status::ServiceUnavailable,
+        )),
+        Err(err) => Err(ErrorResponse::new(err.to_string(), Status::BadRequest)),
+    }
+}
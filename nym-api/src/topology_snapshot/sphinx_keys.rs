@@ -0,0 +1,411 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nym_api_requests::models::{
+    AnnouncedSphinxKey, NodeSphinxKeys, SignedSphinxKeyAnnouncement, SphinxKeyAnnouncement,
+    SphinxKeyValidity,
+};
+use nym_crypto::asymmetric::{encryption, identity};
+use nym_mixnet_contract_common::{EpochId, IdentityKey};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Maximum number of epochs in advance a node can announce its next sphinx key.
+pub(crate) const MAX_ANNOUNCEMENT_LEAD_EPOCHS: EpochId = 2;
+
+/// Maximum number of epochs a single sphinx key can be used for.
+pub(crate) const MAX_KEY_VALIDITY_EPOCHS: EpochId = 24 * 7;
+
+#[derive(Debug, Error)]
+pub(crate) enum SphinxKeyAnnouncementError {
+    #[error("the current epoch is not yet known")]
+    UnknownEpoch,
+
+    #[error("node {identity} is not bonded")]
+    NotBonded { identity: IdentityKey },
+
+    #[error("the announced key is malformed: {0}")]
+    MalformedSphinxKey(#[from] encryption::KeyRecoveryError),
+
+    #[error("the identity key or the signature is malformed: {0}")]
+    MalformedSignature(#[from] identity::Ed25519RecoveryError),
+
+    #[error("the announcement signature is invalid: {0}")]
+    InvalidSignature(#[from] identity::SignatureError),
+
+    #[error("the key validity ({valid_from}..{valid_until}) is invalid in epoch {current_epoch}")]
+    InvalidValidity {
+        valid_from: EpochId,
+        valid_until: EpochId,
+        current_epoch: EpochId,
+    },
+
+    #[error("the announcement is older than the latest one received from this node")]
+    StaleAnnouncement,
+}
+
+/// Makes sure the announcement has been signed by the node itself and that it's meant
+/// for the (near) future.
+pub(crate) fn verify_announcement(
+    signed: &SignedSphinxKeyAnnouncement,
+    current_epoch: EpochId,
+) -> Result<(), SphinxKeyAnnouncementError> {
+    let announcement = &signed.announcement;
+    encryption::PublicKey::from_base58_string(&announcement.sphinx_key)?;
+
+    let identity = identity::PublicKey::from_base58_string(&announcement.identity_key)?;
+    let signature = identity::Signature::from_base58_string(&signed.signature)?;
+    identity.verify(&announcement.signable_bytes(), &signature)?;
+
+    let validity = announcement.validity;
+    if validity.valid_from >= validity.valid_until
+        || validity.valid_until - validity.valid_from > MAX_KEY_VALIDITY_EPOCHS
+        || validity.valid_until <= current_epoch
+        || validity.valid_from > current_epoch + MAX_ANNOUNCEMENT_LEAD_EPOCHS
+    {
+        return Err(SphinxKeyAnnouncementError::InvalidValidity {
+            valid_from: validity.valid_from,
+            valid_until: validity.valid_until,
+            current_epoch,
+        });
+    }
+
+    Ok(())
+}
+
+struct NodeSphinxKeys {
+    latest_generated_at: i64,
+
+    /// The first epoch in which the node no longer uses the key from its bond.
+    bonded_key_valid_until: EpochId,
+
+    /// Non-overlapping announcements, sorted by the starting epoch of their validity.
+    announcements: Vec<SphinxKeyAnnouncement>,
+}
+
+/// Sphinx key of particular node that is meant to be used during the given epoch.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum EpochSphinxKey<'a> {
+    /// The node is still using the key from its bond as it has not yet switched to any announced key.
+    Bonded,
+
+    Announced {
+        sphinx_key: &'a str,
+        validity: SphinxKeyValidity,
+    },
+
+    /// The node has announced its keys before, but none of them is valid in this epoch.
+    Unavailable,
+}
+
+/// Latest sphinx keys announced by the nodes.
+#[derive(Default)]
+pub(crate) struct AnnouncedSphinxKeys {
+    nodes: HashMap<IdentityKey, NodeSphinxKeys>,
+}
+
+impl AnnouncedSphinxKeys {
+    /// Inserts the verified announcement and returns whether it has not been seen before.
+    /// Any previous announcements of the node that would have started at the same or later epoch
+    /// are replaced by it.
+    pub(crate) fn insert(
+        &mut self,
+        announcement: SphinxKeyAnnouncement,
+    ) -> Result<bool, SphinxKeyAnnouncementError> {
+        let node = self
+            .nodes
+            .entry(announcement.identity_key.clone())
+            .or_insert_with(|| NodeSphinxKeys {
+                latest_generated_at: announcement.generated_at,
+                bonded_key_valid_until: announcement.validity.valid_from,
+                announcements: Vec::new(),
+            });
+
+        // nodes periodically re-announce their keys, so this is not an error
+        if node.announcements.contains(&announcement) {
+            return Ok(false);
+        }
+        if announcement.generated_at < node.latest_generated_at {
+            return Err(SphinxKeyAnnouncementError::StaleAnnouncement);
+        }
+
+        let valid_from = announcement.validity.valid_from;
+        node.latest_generated_at = announcement.generated_at;
+        node.announcements
+            .retain(|existing| existing.validity.valid_from < valid_from);
+        for existing in node.announcements.iter_mut() {
+            existing.validity.valid_until = existing.validity.valid_until.min(valid_from);
+        }
+        node.announcements.push(announcement);
+        Ok(true)
+    }
+
+    pub(crate) fn key_for_epoch(&self, identity: &str, epoch_id: EpochId) -> EpochSphinxKey<'_> {
+        let Some(node) = self.nodes.get(identity) else {
+            return EpochSphinxKey::Bonded;
+        };
+
+        node.announcements
+            .iter()
+            .find(|announcement| announcement.validity.is_valid_in(epoch_id))
+            .map(|announcement| EpochSphinxKey::Announced {
+                sphinx_key: &announcement.sphinx_key,
+                validity: announcement.validity,
+            })
+            .unwrap_or(if epoch_id < node.bonded_key_valid_until {
+                EpochSphinxKey::Bonded
+            } else {
+                EpochSphinxKey::Unavailable
+            })
+    }
+
+    /// Returns the keys announced by the node that are valid in the provided or any later epoch,
+    /// i.e. its current and its next key, or `None` if the node still uses its bonded key.
+    pub(crate) fn node_keys(&self, identity: &str, epoch_id: EpochId) -> Option<NodeSphinxKeys> {
+        let node = self.nodes.get(identity)?;
+
+        Some(NodeSphinxKeys {
+            bonded_key_valid_until: node.bonded_key_valid_until,
+            announced: node
+                .announcements
+                .iter()
+                .filter(|announcement| announcement.validity.valid_until > epoch_id)
+                .map(|announcement| AnnouncedSphinxKey {
+                    sphinx_key: announcement.sphinx_key.clone(),
+                    validity: announcement.validity,
+                })
+                .collect(),
+        })
+    }
+
+    /// Replaces the sphinx key of the node with the one announced for the provided epoch.
+    /// Returns `false` if the node does not have any valid key.
+    pub(crate) fn apply(
+        &self,
+        identity: &str,
+        epoch_id: EpochId,
+        sphinx_key: &mut String,
+        sphinx_key_validity: &mut Option<SphinxKeyValidity>,
+    ) -> bool {
+        match self.key_for_epoch(identity, epoch_id) {
+            EpochSphinxKey::Bonded => true,
+            EpochSphinxKey::Announced {
+                sphinx_key: announced,
+                validity,
+            } => {
+                *sphinx_key = announced.to_owned();
+                *sphinx_key_validity = Some(validity);
+                true
+            }
+            EpochSphinxKey::Unavailable => false,
+        }
+    }
+
+    /// Removes all announcements that are no longer valid. Note that the nodes themselves are retained
+    /// so that their bonded keys would not be used again.
+    pub(crate) fn remove_expired(&mut self, current_epoch: EpochId) {
+        for node in self.nodes.values_mut() {
+            node.announcements
+                .retain(|announcement| announcement.validity.valid_until > current_epoch)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_07::rngs::OsRng;
+
+    fn announcement(
+        keys: &identity::KeyPair,
+        valid_from: EpochId,
+        valid_until: EpochId,
+        generated_at: i64,
+    ) -> SignedSphinxKeyAnnouncement {
+        let sphinx_keys = encryption::KeyPair::new(&mut OsRng);
+        let announcement = SphinxKeyAnnouncement {
+            identity_key: keys.public_key().to_base58_string(),
+            sphinx_key: sphinx_keys.public_key().to_base58_string(),
+            validity: SphinxKeyValidity {
+                valid_from,
+                valid_until,
+            },
+            generated_at,
+        };
+        let signature = keys
+            .private_key()
+            .sign(&announcement.signable_bytes())
+            .to_base58_string();
+        SignedSphinxKeyAnnouncement {
+            announcement,
+            signature,
+        }
+    }
+
+    #[test]
+    fn announcements_are_verified() {
+        let keys = identity::KeyPair::new(&mut OsRng);
+        assert!(verify_announcement(&announcement(&keys, 10, 20, 1), 10).is_ok());
+        assert!(verify_announcement(&announcement(&keys, 11, 20, 1), 10).is_ok());
+
+        // already expired
+        assert!(verify_announcement(&announcement(&keys, 5, 10, 1), 10).is_err());
+        // too far in the future
+        assert!(verify_announcement(&announcement(&keys, 13, 20, 1), 10).is_err());
+        // empty
+        assert!(verify_announcement(&announcement(&keys, 10, 10, 1), 10).is_err());
+        // too long
+        assert!(verify_announcement(&announcement(&keys, 10, 1000, 1), 10).is_err());
+
+        // signed by someone else
+        let mut forged = announcement(&keys, 10, 20, 1);
+        forged.announcement.identity_key = identity::KeyPair::new(&mut OsRng)
+            .public_key()
+            .to_base58_string();
+        assert!(matches!(
+            verify_announcement(&forged, 10),
+            Err(SphinxKeyAnnouncementError::InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn bonded_key_is_used_until_the_first_announcement() {
+        let keys = identity::KeyPair::new(&mut OsRng);
+        let identity = keys.public_key().to_base58_string();
+        let mut announced = AnnouncedSphinxKeys::default();
+        assert_eq!(
+            announced.key_for_epoch(&identity, 10),
+            EpochSphinxKey::Bonded
+        );
+
+        let first = announcement(&keys, 11, 20, 1).announcement;
+        assert!(announced.insert(first.clone()).unwrap());
+        assert_eq!(
+            announced.key_for_epoch(&identity, 10),
+            EpochSphinxKey::Bonded
+        );
+        assert_eq!(
+            announced.key_for_epoch(&identity, 15),
+            EpochSphinxKey::Announced {
+                sphinx_key: &first.sphinx_key,
+                validity: first.validity
+            }
+        );
+        assert_eq!(
+            announced.key_for_epoch(&identity, 20),
+            EpochSphinxKey::Unavailable
+        );
+
+        announced.remove_expired(20);
+        assert_eq!(
+            announced.key_for_epoch(&identity, 20),
+            EpochSphinxKey::Unavailable
+        );
+    }
+
+    #[test]
+    fn upcoming_key_is_used_from_its_first_epoch() {
+        let keys = identity::KeyPair::new(&mut OsRng);
+        let identity = keys.public_key().to_base58_string();
+        let mut announced = AnnouncedSphinxKeys::default();
+
+        let current = announcement(&keys, 10, 20, 1).announcement;
+        let upcoming = announcement(&keys, 20, 30, 2).announcement;
+        announced.insert(current.clone()).unwrap();
+        announced.insert(upcoming.clone()).unwrap();
+
+        let mut sphinx_key = "bonded".to_string();
+        let mut validity = None;
+        assert!(announced.apply(&identity, 19, &mut sphinx_key, &mut validity));
+        assert_eq!(sphinx_key, current.sphinx_key);
+
+        assert!(announced.apply(&identity, 20, &mut sphinx_key, &mut validity));
+        assert_eq!(sphinx_key, upcoming.sphinx_key);
+        assert_eq!(validity, Some(upcoming.validity));
+    }
+
+    #[test]
+    fn current_and_next_keys_are_exposed() {
+        let keys = identity::KeyPair::new(&mut OsRng);
+        let identity = keys.public_key().to_base58_string();
+        let mut announced = AnnouncedSphinxKeys::default();
+        assert!(announced.node_keys(&identity, 10).is_none());
+
+        let current = announcement(&keys, 10, 20, 1).announcement;
+        let upcoming = announcement(&keys, 20, 30, 2).announcement;
+        announced.insert(current.clone()).unwrap();
+        announced.insert(upcoming.clone()).unwrap();
+
+        let node_keys = announced.node_keys(&identity, 19).unwrap();
+        assert_eq!(node_keys.bonded_key_valid_until, 10);
+        assert_eq!(
+            node_keys.announced,
+            vec![
+                AnnouncedSphinxKey {
+                    sphinx_key: current.sphinx_key,
+                    validity: current.validity,
+                },
+                AnnouncedSphinxKey {
+                    sphinx_key: upcoming.sphinx_key.clone(),
+                    validity: upcoming.validity,
+                },
+            ]
+        );
+
+        let node_keys = announced.node_keys(&identity, 20).unwrap();
+        assert_eq!(
+            node_keys.announced,
+            vec![AnnouncedSphinxKey {
+                sphinx_key: upcoming.sphinx_key,
+                validity: upcoming.validity,
+            }]
+        );
+    }
+
+    #[test]
+    fn newer_announcements_replace_overlapping_ones() {
+        let keys = identity::KeyPair::new(&mut OsRng);
+        let identity = keys.public_key().to_base58_string();
+        let mut announced = AnnouncedSphinxKeys::default();
+
+        let current = announcement(&keys, 10, 20, 1).announcement;
+        let upcoming = announcement(&keys, 20, 30, 2).announcement;
+        announced.insert(current.clone()).unwrap();
+        announced.insert(upcoming).unwrap();
+
+        // the node got restarted and has lost its keys
+        let replacement = announcement(&keys, 15, 25, 3).announcement;
+        announced.insert(replacement.clone()).unwrap();
+
+        for (epoch, expected) in [(14, &current), (15, &replacement), (24, &replacement)] {
+            match announced.key_for_epoch(&identity, epoch) {
+                EpochSphinxKey::Announced { sphinx_key, .. } => {
+                    assert_eq!(sphinx_key, expected.sphinx_key)
+                }
+                other => panic!("unexpected key for epoch {epoch}: {other:?}"),
+            }
+        }
+        assert_eq!(
+            announced.key_for_epoch(&identity, 25),
+            EpochSphinxKey::Unavailable
+        );
+    }
+
+    #[test]
+    fn replayed_announcements_are_rejected() {
+        let keys = identity::KeyPair::new(&mut OsRng);
+        let mut announced = AnnouncedSphinxKeys::default();
+
+        let old = announcement(&keys, 10, 20, 1).announcement;
+        let new = announcement(&keys, 11, 20, 2).announcement;
+        announced.insert(old.clone()).unwrap();
+        announced.insert(new.clone()).unwrap();
+
+        // re-announcing the latest key is fine
+        assert!(!announced.insert(new).unwrap());
+        assert!(matches!(
+            announced.insert(old),
+            Err(SphinxKeyAnnouncementError::StaleAnnouncement)
+        ));
+    }
+}
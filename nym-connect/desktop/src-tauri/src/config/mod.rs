@@ -146,6 +146,7 @@ pub async fn init_socks5_config(provider_address: String, chosen_gateway_id: Str
             config.get_base(),
             // TODO: another instance where this setting should probably get used
             false,
+            None,
         )
         .await?;
 
@@ -157,7 +158,8 @@ pub async fn init_socks5_config(provider_address: String, chosen_gateway_id: Str
 
     print_saved_config(&config);
 
-    let address = nym_client_core::init::get_client_address_from_stored_keys(config.get_base())?;
+    let address =
+        nym_client_core::init::get_client_address_from_stored_keys(config.get_base(), None)?;
     log::info!("The address of this client is: {}", address);
     Ok(())
 }
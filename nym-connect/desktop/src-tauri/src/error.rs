@@ -70,6 +70,8 @@ pub enum BackendError {
     NewWindowError,
     #[error("unable to parse the specified gateway")]
     UnableToParseGateway,
+    #[error("the client status message does not describe the gateway connectivity")]
+    NotAGatewayConnectivityStatus,
 
     #[error("unable to load keys: {source}")]
     UnableToLoadKeys {
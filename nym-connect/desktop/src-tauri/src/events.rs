@@ -58,9 +58,10 @@ pub async fn handle_client_status_message(
 ) {
     // TODO: use this instead once we change on the frontend too
     let _event_name = match client_status_message {
-        ClientCoreStatusMessage::GatewayIsSlow | ClientCoreStatusMessage::GatewayIsVerySlow => {
-            "socks5-gateway-status"
-        }
+        ClientCoreStatusMessage::GatewayIsSlow
+        | ClientCoreStatusMessage::GatewayIsVerySlow
+        | ClientCoreStatusMessage::GatewayFailover { .. } => "socks5-gateway-status",
+        ClientCoreStatusMessage::DroppedIncompleteMessages { .. } => "socks5-status",
     };
 
     if let Ok(connectivity) = GatewayConnectivity::try_from(client_status_message) {
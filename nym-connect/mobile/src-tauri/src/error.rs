@@ -65,6 +65,8 @@ pub enum BackendError {
     NewWindowError,
     #[error("unable to parse the specified gateway")]
     UnableToParseGateway,
+    #[error("the client status message does not describe the gateway connectivity")]
+    NotAGatewayConnectivityStatus,
 
     #[error("HTTP get request failed: {status_code}")]
     RequestFail {
@@ -50,6 +50,10 @@ impl TryFrom<&ClientCoreStatusMessage> for GatewayConnectivity {
             ClientCoreStatusMessage::GatewayIsVerySlow => GatewayConnectivity::VeryBad {
                 when: Instant::now(),
             },
+            ClientCoreStatusMessage::DroppedIncompleteMessages { .. }
+            | ClientCoreStatusMessage::GatewayFailover { .. } => {
+                return Err(BackendError::NotAGatewayConnectivityStatus)
+            }
         };
         Ok(conn)
     }
@@ -19,6 +19,12 @@ pub enum OutfoxError {
     InvalidKeyLength,
     #[error("Message length must be greater then {MIN_MESSAGE_LEN} bytes")]
     InvalidMessageLength,
+    #[error("Route must consist of between 1 and 255 nodes, got: {0}")]
+    InvalidRouteLength(usize),
+    #[error("Invalid number of remaining layers: {remaining} (out of {total})")]
+    InvalidRemainingLayers { remaining: usize, total: usize },
+    #[error("Failed to obtain randomness - {0}")]
+    Randomness(String),
     #[error("{source}")]
     TryFromSluce {
         #[from]
@@ -1,46 +1,231 @@
+use std::convert::TryInto;
 use std::ops::Range;
 
 use crate::{
     error::OutfoxError,
-    format::{MixCreationParameters, MixStageParameters},
+    format::{MixCreationParameters, MixStageParameters, GROUPELEMENTBYTES, TAGBYTES},
+    lion::MIN_MESSAGE_LEN,
 };
 
-use sphinx_packet::{packet::builder::DEFAULT_PAYLOAD_SIZE, route::Node};
+use sphinx_packet::{
+    constants::NODE_ADDRESS_LENGTH, header::delays::Delay, route::DestinationAddressBytes,
+    route::Node,
+};
+
+/// Number of bytes used to encode the delay inside the routing information.
+pub const DELAY_LENGTH: usize = 8;
+
+/// The routing information at each layer consists of the address of the next hop
+/// (or of the destination for the final layer) alongside the delay the mix should apply.
+pub const OUTFOX_ROUTING_INFO_SIZE: usize = NODE_ADDRESS_LENGTH + DELAY_LENGTH;
+
+/// Number of layers (3 mixes and the egress gateway) of packets sent through the network.
+pub const DEFAULT_NUM_OUTFOX_LAYERS: usize = 4;
+
+/// Number of bytes of the unencrypted prefix of a serialized packet. It contains the total number
+/// of layers of the packet and the number of layers that still have to be processed.
+/// Note that since outfox assumes stratified topology, the position of the mix in the route
+/// is not considered secret.
+pub const OUTFOX_PACKET_PREFIX_SIZE: usize = 2;
+
+/// Size of each layer of encryption of the packet.
+pub const OUTFOX_LAYER_OVERHEAD: usize = GROUPELEMENTBYTES + TAGBYTES + OUTFOX_ROUTING_INFO_SIZE;
+
+/// Total overhead of the packet, i.e. the number of bytes on top of the payload,
+/// assuming it uses the default number of layers.
+pub const OUTFOX_PACKET_OVERHEAD: usize =
+    OUTFOX_PACKET_PREFIX_SIZE + DEFAULT_NUM_OUTFOX_LAYERS * OUTFOX_LAYER_OVERHEAD;
+
+/// Routing information recovered from a single processed layer of the packet.
+#[derive(Debug, Clone, Copy)]
+pub struct OutfoxRoutingInformation {
+    /// Address of the next hop or, for the final layer, of the destination.
+    pub next_address: [u8; NODE_ADDRESS_LENGTH],
+
+    /// Delay the mix should apply before forwarding the packet.
+    pub delay: Delay,
+}
+
+impl OutfoxRoutingInformation {
+    fn to_bytes(&self) -> [u8; OUTFOX_ROUTING_INFO_SIZE] {
+        let mut bytes = [0u8; OUTFOX_ROUTING_INFO_SIZE];
+        bytes[..NODE_ADDRESS_LENGTH].copy_from_slice(&self.next_address);
+        bytes[NODE_ADDRESS_LENGTH..].copy_from_slice(&self.delay.to_nanos().to_be_bytes());
+        bytes
+    }
+
+    fn try_from_bytes(bytes: &[u8]) -> Result<Self, OutfoxError> {
+        if bytes.len() != OUTFOX_ROUTING_INFO_SIZE {
+            return Err(OutfoxError::LenMismatch {
+                expected: OUTFOX_ROUTING_INFO_SIZE,
+                got: bytes.len(),
+            });
+        }
+
+        Ok(OutfoxRoutingInformation {
+            next_address: bytes[..NODE_ADDRESS_LENGTH].try_into()?,
+            delay: Delay::new_from_nanos(u64::from_be_bytes(
+                bytes[NODE_ADDRESS_LENGTH..].try_into()?,
+            )),
+        })
+    }
+}
+
+fn random_bytes(buffer: &mut [u8]) -> Result<(), OutfoxError> {
+    getrandom::getrandom(buffer).map_err(|err| OutfoxError::Randomness(err.to_string()))
+}
 
 pub struct OutfoxPacket {
     mix_params: MixCreationParameters,
+    /// Number of layers of the packet that have not yet been decoded.
+    remaining_layers: usize,
     payload: Vec<u8>,
 }
 
-pub const DEFAULT_ROUTING_INFO_SIZE: usize = 32;
-
 impl OutfoxPacket {
+    /// Builds a packet going through the provided route and eventually reaching the specified
+    /// destination. The last node on the route is the final hop which is going to recover the payload.
+    ///
+    /// Each layer is encoded with a fresh ephemeral key. Note that the payload is not padded,
+    /// i.e. its length directly determines the size of the packet.
     pub fn build(
         payload: &[u8],
-        route: &[Node; 3],
-        user_secret_key: &[u8],
+        route: &[Node],
+        destination: &DestinationAddressBytes,
+        delays: &[Delay],
     ) -> Result<OutfoxPacket, OutfoxError> {
-        let mut mix_params = MixCreationParameters::new(DEFAULT_PAYLOAD_SIZE);
+        if route.is_empty() || route.len() > u8::MAX as usize {
+            return Err(OutfoxError::InvalidRouteLength(route.len()));
+        }
+        if delays.len() != route.len() {
+            return Err(OutfoxError::LenMismatch {
+                expected: route.len(),
+                got: delays.len(),
+            });
+        }
+        if payload.len() < MIN_MESSAGE_LEN {
+            return Err(OutfoxError::InvalidMessageLength);
+        }
 
-        for node in route.iter() {
-            mix_params.add_outer_layer(node.address.as_bytes_ref().len());
+        let mut mix_params = MixCreationParameters::new(payload.len());
+        for _ in route {
+            mix_params.add_outer_layer(OUTFOX_ROUTING_INFO_SIZE);
         }
 
         let padding = mix_params.total_packet_length() - payload.len();
         let mut buffer = vec![0; padding];
         buffer.extend_from_slice(payload);
 
-        for (idx, node) in route.iter().rev().enumerate() {
-            let (range, stage_params) = mix_params.get_stage_params(idx);
-            stage_params.encode_mix_layer(&mut buffer[range], user_secret_key, node)?;
+        // layers are encoded starting from the innermost one, i.e. the final hop
+        for (layer, node) in route.iter().rev().enumerate() {
+            let next_address = match layer {
+                0 => *destination.as_bytes_ref(),
+                n => *route[route.len() - n].address.as_bytes_ref(),
+            };
+            let routing_data = OutfoxRoutingInformation {
+                next_address,
+                delay: delays[route.len() - 1 - layer],
+            };
+
+            let mut user_secret_key = [0u8; GROUPELEMENTBYTES];
+            random_bytes(&mut user_secret_key)?;
+
+            let (range, stage_params) = mix_params.get_stage_params(layer);
+            stage_params.encode_mix_layer_with_routing_data(
+                &mut buffer[range],
+                &user_secret_key,
+                node.pub_key.as_bytes(),
+                &routing_data.to_bytes(),
+            )?;
         }
 
         Ok(OutfoxPacket {
             mix_params,
+            remaining_layers: route.len(),
             payload: buffer,
         })
     }
 
+    /// Attempts to recover a (possibly partially processed) packet from its serialized representation.
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<OutfoxPacket, OutfoxError> {
+        if bytes.len() < OUTFOX_PACKET_PREFIX_SIZE {
+            return Err(OutfoxError::LenMismatch {
+                expected: OUTFOX_PACKET_PREFIX_SIZE,
+                got: bytes.len(),
+            });
+        }
+
+        let num_layers = bytes[0] as usize;
+        let remaining_layers = bytes[1] as usize;
+        if num_layers == 0 {
+            return Err(OutfoxError::InvalidRouteLength(num_layers));
+        }
+        if remaining_layers == 0 || remaining_layers > num_layers {
+            return Err(OutfoxError::InvalidRemainingLayers {
+                remaining: remaining_layers,
+                total: num_layers,
+            });
+        }
+
+        let buffer = &bytes[OUTFOX_PACKET_PREFIX_SIZE..];
+        let layers_len = num_layers * OUTFOX_LAYER_OVERHEAD;
+        if buffer.len() < layers_len + MIN_MESSAGE_LEN {
+            return Err(OutfoxError::LenMismatch {
+                expected: layers_len + MIN_MESSAGE_LEN,
+                got: buffer.len(),
+            });
+        }
+
+        let mut mix_params = MixCreationParameters::new(buffer.len() - layers_len);
+        for _ in 0..num_layers {
+            mix_params.add_outer_layer(OUTFOX_ROUTING_INFO_SIZE);
+        }
+
+        Ok(OutfoxPacket {
+            mix_params,
+            remaining_layers,
+            payload: buffer.to_vec(),
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let num_layers = self.mix_params.routing_information_length_by_stage.len();
+
+        std::iter::once(num_layers as u8)
+            .chain(std::iter::once(self.remaining_layers as u8))
+            .chain(self.payload.iter().copied())
+            .collect()
+    }
+
+    /// Length of the serialized packet.
+    pub fn len(&self) -> usize {
+        OUTFOX_PACKET_PREFIX_SIZE + self.payload.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.payload.is_empty()
+    }
+
+    /// Indicates whether the next layer to get decoded is the final one, i.e. whether the packet
+    /// is currently at its final hop.
+    pub fn is_final_hop(&self) -> bool {
+        self.remaining_layers == 1
+    }
+
+    pub fn remaining_layers(&self) -> usize {
+        self.remaining_layers
+    }
+
+    /// The public group element of the next layer to get decoded. Since it's freshly generated
+    /// for each layer of each packet, it can be used for detecting replayed packets.
+    pub fn current_group_element(&self) -> Option<[u8; GROUPELEMENTBYTES]> {
+        let layer = self.remaining_layers.checked_sub(1)?;
+        let (range, params) = self.stage_params(layer);
+        self.payload[range][params.pub_element_range()]
+            .try_into()
+            .ok()
+    }
+
     pub fn stage_params(&self, layer_number: usize) -> (Range<usize>, MixStageParameters) {
         self.mix_params().get_stage_params(layer_number)
     }
@@ -54,13 +239,25 @@ impl OutfoxPacket {
     }
 
     pub fn payload_range(&self) -> Range<usize> {
-        self.stage_params(2).1.payload_range()
+        let total_length = self.mix_params.total_packet_length();
+        total_length - self.mix_params.payload_length_bytes..total_length
     }
 
     pub fn payload_mut(&mut self) -> &mut [u8] {
         &mut self.payload
     }
 
+    /// Recovers the payload of the packet once all of its layers have been decoded.
+    pub fn recover_plaintext(&self) -> Result<&[u8], OutfoxError> {
+        if self.remaining_layers != 0 {
+            return Err(OutfoxError::InvalidRemainingLayers {
+                remaining: self.remaining_layers,
+                total: self.mix_params.routing_information_length_by_stage.len(),
+            });
+        }
+        Ok(&self.payload[self.payload_range()])
+    }
+
     pub fn decode_mix_layer(
         &mut self,
         layer: usize,
@@ -70,4 +267,33 @@ impl OutfoxPacket {
         params.decode_mix_layer(&mut self.payload_mut()[range], mix_secret_key)?;
         Ok(())
     }
+
+    /// Decodes the next layer of the packet and recovers the routing information contained within.
+    /// All data of the processed layer is then replaced with random bytes so that nodes further
+    /// down the route could not learn anything about the previous hops.
+    pub fn decode_next_layer(
+        &mut self,
+        mix_secret_key: &[u8; 32],
+    ) -> Result<OutfoxRoutingInformation, OutfoxError> {
+        let layer =
+            self.remaining_layers
+                .checked_sub(1)
+                .ok_or(OutfoxError::InvalidRemainingLayers {
+                    remaining: 0,
+                    total: self.mix_params.routing_information_length_by_stage.len(),
+                })?;
+
+        let (range, params) = self.stage_params(layer);
+        params.decode_mix_layer(&mut self.payload[range.clone()], mix_secret_key)?;
+
+        let routing_range = range.start + params.routing_data_range().start
+            ..range.start + params.routing_data_range().end;
+        let routing_information =
+            OutfoxRoutingInformation::try_from_bytes(&self.payload[routing_range.clone()])?;
+
+        random_bytes(&mut self.payload[..routing_range.end])?;
+        self.remaining_layers = layer;
+
+        Ok(routing_information)
+    }
 }
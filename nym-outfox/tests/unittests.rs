@@ -5,12 +5,13 @@ mod tests {
 
     use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
     use curve25519_dalek::scalar::Scalar;
-    use nym_outfox::packet::OutfoxPacket;
-    use sphinx_packet::constants::NODE_ADDRESS_LENGTH;
+    use nym_outfox::packet::{OutfoxPacket, OUTFOX_PACKET_OVERHEAD};
+    use sphinx_packet::constants::{DESTINATION_ADDRESS_LENGTH, NODE_ADDRESS_LENGTH};
     use sphinx_packet::crypto::PublicKey;
+    use sphinx_packet::header::delays::Delay;
     use sphinx_packet::packet::builder::DEFAULT_PAYLOAD_SIZE;
     use sphinx_packet::route::Node;
-    use sphinx_packet::route::NodeAddressBytes;
+    use sphinx_packet::route::{DestinationAddressBytes, NodeAddressBytes};
     use std::convert::TryInto;
 
     use nym_outfox::format::*;
@@ -81,8 +82,6 @@ mod tests {
 
     #[test]
     fn test_packet_params() {
-        let user_secret = randombytes(32);
-
         let (node1_pk, node1_pub) = sphinx_packet::crypto::keygen();
         let node1 = Node::new(
             NodeAddressBytes::from_bytes([0u8; NODE_ADDRESS_LENGTH]),
@@ -100,10 +99,12 @@ mod tests {
         );
 
         let route = [node1, node2, node3];
+        let destination = DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]);
+        let delays = vec![Delay::new_from_nanos(42); 3];
 
         let payload = randombytes(DEFAULT_PAYLOAD_SIZE);
 
-        let mut packet = OutfoxPacket::build(&payload, &route, &user_secret).unwrap();
+        let mut packet = OutfoxPacket::build(&payload, &route, &destination, &delays).unwrap();
 
         packet.decode_mix_layer(2, &node1_pk.to_bytes()).unwrap();
         packet.decode_mix_layer(1, &node2_pk.to_bytes()).unwrap();
@@ -111,4 +112,63 @@ mod tests {
 
         assert_eq!(payload, &packet.payload()[packet.payload_range()]);
     }
+
+    #[test]
+    fn test_packet_routing() {
+        let keys = (0..4)
+            .map(|_| sphinx_packet::crypto::keygen())
+            .collect::<Vec<_>>();
+        let route = keys
+            .iter()
+            .enumerate()
+            .map(|(i, (_, public))| {
+                Node::new(
+                    NodeAddressBytes::from_bytes([i as u8; NODE_ADDRESS_LENGTH]),
+                    *public,
+                )
+            })
+            .collect::<Vec<_>>();
+        let destination = DestinationAddressBytes::from_bytes([42u8; DESTINATION_ADDRESS_LENGTH]);
+        let delays = (0..4).map(Delay::new_from_nanos).collect::<Vec<_>>();
+
+        let payload = randombytes(DEFAULT_PAYLOAD_SIZE);
+        let packet = OutfoxPacket::build(&payload, &route, &destination, &delays).unwrap();
+        assert_eq!(packet.len(), DEFAULT_PAYLOAD_SIZE + OUTFOX_PACKET_OVERHEAD);
+
+        let mut bytes = packet.to_bytes();
+        for (i, (private, _)) in keys.iter().enumerate() {
+            let mut packet = OutfoxPacket::try_from_bytes(&bytes).unwrap();
+            assert_eq!(packet.is_final_hop(), i == 3);
+
+            let routing = packet.decode_next_layer(&private.to_bytes()).unwrap();
+            assert_eq!(routing.delay.to_nanos(), i as u64);
+            if i == 3 {
+                assert_eq!(routing.next_address, [42u8; DESTINATION_ADDRESS_LENGTH]);
+                assert_eq!(payload, packet.recover_plaintext().unwrap());
+            } else {
+                assert_eq!(routing.next_address, [i as u8 + 1; NODE_ADDRESS_LENGTH]);
+                assert!(packet.recover_plaintext().is_err());
+            }
+
+            // the size of the packet never changes
+            bytes = packet.to_bytes();
+            assert_eq!(bytes.len(), DEFAULT_PAYLOAD_SIZE + OUTFOX_PACKET_OVERHEAD);
+        }
+    }
+
+    #[test]
+    fn test_packet_rejects_wrong_key() {
+        let (_, public) = sphinx_packet::crypto::keygen();
+        let (other_private, _) = sphinx_packet::crypto::keygen();
+        let route = [Node::new(
+            NodeAddressBytes::from_bytes([0u8; NODE_ADDRESS_LENGTH]),
+            public,
+        )];
+        let destination = DestinationAddressBytes::from_bytes([42u8; DESTINATION_ADDRESS_LENGTH]);
+        let delays = [Delay::new_from_nanos(0)];
+
+        let payload = randombytes(DEFAULT_PAYLOAD_SIZE);
+        let mut packet = OutfoxPacket::build(&payload, &route, &destination, &delays).unwrap();
+        assert!(packet.decode_next_layer(&other_private.to_bytes()).is_err());
+    }
 }
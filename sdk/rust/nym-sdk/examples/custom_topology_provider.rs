@@ -1,9 +1,10 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use nym_client_core::client::topology_control::nym_topology_with_sphinx_keys;
 use nym_sdk::mixnet;
 use nym_topology::provider_trait::{async_trait, TopologyProvider};
-use nym_topology::{nym_topology_from_detailed, NymTopology};
+use nym_topology::NymTopology;
 use url::Url;
 
 struct MyTopologyProvider {
@@ -20,7 +21,7 @@ impl MyTopologyProvider {
     async fn get_topology(&self) -> NymTopology {
         let mixnodes = self
             .validator_client
-            .get_cached_active_mixnodes()
+            .get_cached_active_mixnodes_with_sphinx_keys()
             .await
             .unwrap();
 
@@ -30,13 +31,26 @@ impl MyTopologyProvider {
         let filtered_mixnodes = mixnodes
             .into_iter()
             .filter(|mix| {
-                mix.mix_id() % 3 == 0 && mix.total_stake() > "100000000000".parse().unwrap()
+                mix.details.mix_id() % 3 == 0
+                    && mix.details.total_stake() > "100000000000".parse().unwrap()
             })
             .collect::<Vec<_>>();
 
-        let gateways = self.validator_client.get_cached_gateways().await.unwrap();
+        let gateways = self
+            .validator_client
+            .get_cached_gateways_with_sphinx_keys()
+            .await
+            .unwrap();
+
+        // the nodes periodically rotate their sphinx keys, so make sure to use the current ones
+        let epoch_id = self
+            .validator_client
+            .get_current_epoch()
+            .await
+            .unwrap()
+            .current_epoch_absolute_id();
 
-        nym_topology_from_detailed(filtered_mixnodes, gateways)
+        nym_topology_with_sphinx_keys(filtered_mixnodes, gateways, epoch_id)
     }
 }
 
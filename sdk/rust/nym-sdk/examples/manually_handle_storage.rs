@@ -185,6 +185,14 @@ impl KeyStore for MockKeyStore {
 //     async fn consume_coconut_credential(&self, id: i64) -> Result<(), Self::StorageError> {
 //         todo!()
 //     }
+//
+//     async fn get_spent_tickets(&self, _id: i64) -> Result<Vec<u64>, Self::StorageError> {
+//         todo!()
+//     }
+//
+//     async fn insert_spent_ticket(&self, _id: i64, _ticket_index: u64) -> Result<(), Self::StorageError> {
+//         todo!()
+//     }
 // }
 
 #[derive(thiserror::Error, Debug)]
@@ -29,6 +29,8 @@ async fn main() {
             sphinx_key: "CBmYewWf43iarBq349KhbfYMc9ys2ebXWd4Vp4CLQ5Rq"
                 .parse()
                 .unwrap(),
+            sphinx_key_validity: None,
+            announced_sphinx_keys: None,
             layer: Layer::One,
             version: "1.1.0".to_string(),
         }],
@@ -46,6 +48,8 @@ async fn main() {
             sphinx_key: "8ndjk5oZ6HxUZNScLJJ7hk39XtUqGexdKgW7hSX6kpWG"
                 .parse()
                 .unwrap(),
+            sphinx_key_validity: None,
+            announced_sphinx_keys: None,
             layer: Layer::Two,
             version: "1.1.0".to_string(),
         }],
@@ -63,6 +67,8 @@ async fn main() {
             sphinx_key: "7KyZh8Z8KxuVunqytAJ2eXFuZkCS7BLTZSzujHJZsGa2"
                 .parse()
                 .unwrap(),
+            sphinx_key_validity: None,
+            announced_sphinx_keys: None,
             layer: Layer::Three,
             version: "1.1.0".to_string(),
         }],
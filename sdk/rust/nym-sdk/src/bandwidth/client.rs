@@ -60,6 +60,23 @@ where
             })
     }
 
+    /// Buy a ticketbook, i.e. a credential worth `TICKETBOOK_VALUE` utokens that gets spent in
+    /// `TICKETBOOK_SIZE` tickets which can't be linked to each other, even if they are used with
+    /// different gateways. The failure handling is the same as for [`Self::acquire`].
+    pub async fn acquire_ticketbook(&self) -> Result<()> {
+        let state = nym_bandwidth_controller::acquire::deposit_ticketbook(
+            &self.client.nyxd,
+            &self.network_details.chain_details.mix_denom.base,
+        )
+        .await?;
+        nym_bandwidth_controller::acquire::get_credential(&state, &self.client, self.storage)
+            .await
+            .map_err(|reason| Error::UnconvertedDeposit {
+                reason,
+                voucher_blob: state.voucher.to_bytes(),
+            })
+    }
+
     /// In case of an error in the mid of the acquire process, this function should be used for
     /// later retries to recover the bandwidth credential, either immediately or after some time.
     pub async fn recover(&self, voucher_blob: &VoucherBlob) -> Result<()> {
@@ -89,6 +89,9 @@ pub enum Error {
 
     #[error("loaded shared gateway key without providing information about what gateway it corresponds to")]
     GatewayWithUnknownEndpoint,
+
+    #[error("the mixnet client is no longer accepting any messages")]
+    ClientInputClosed,
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
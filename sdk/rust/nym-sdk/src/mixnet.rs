@@ -36,6 +36,7 @@ mod connection_state;
 mod native_client;
 mod paths;
 mod socks5_client;
+mod stream;
 
 pub use client::{DisconnectedMixnetClient, IncludedSurbs, MixnetClientBuilder};
 pub use config::{Config, KeyMode};
@@ -43,7 +44,9 @@ pub use native_client::MixnetClient;
 pub use native_client::MixnetClientSender;
 pub use nym_client_core::{
     client::{
+        address::{ClientAddress, ClientAddressReceiver},
         base_client::storage::{Ephemeral, MixnetClientStorage, OnDiskPersistent},
+        delivery_events::{DeliveryEvent, DeliveryEventReceiver, MessageId},
         inbound_messages::InputMessage,
         key_manager::{
             persistence::{InMemEphemeralKeys, KeyStore, OnDiskKeys},
@@ -53,6 +56,7 @@ pub use nym_client_core::{
             fs_backend::Backend as ReplyStorage, CombinedReplyStorage, Empty as EmptyReplyStorage,
             ReplyStorageBackend,
         },
+        topology_control::snapshot_provider::SignedSnapshotTopologyProvider,
     },
     config::GatewayEndpointConfig,
 };
@@ -64,8 +68,10 @@ pub use nym_network_defaults::NymNetworkDetails;
 pub use nym_socks5_client_core::config::Socks5;
 pub use nym_sphinx::{
     addressing::clients::{ClientIdentity, Recipient},
+    anonymous_replies::requests::AnonymousSenderTag,
     receiver::ReconstructedMessage,
 };
 pub use nym_topology::{provider_trait::TopologyProvider, NymTopology};
 pub use paths::StoragePaths;
 pub use socks5_client::Socks5MixnetClient;
+pub use stream::{MixnetStream, MixnetStreamPeer};
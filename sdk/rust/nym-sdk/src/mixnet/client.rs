@@ -31,12 +31,13 @@ use nym_topology::provider_trait::TopologyProvider;
 use nym_validator_client::nyxd::QueryNyxdClient;
 use nym_validator_client::Client;
 use rand::thread_rng;
+use std::collections::VecDeque;
 use std::path::Path;
 use std::path::PathBuf;
 use url::Url;
 
 // The number of surbs to include in a message by default
-const DEFAULT_NUMBER_OF_SURBS: u32 = 5;
+pub(crate) const DEFAULT_NUMBER_OF_SURBS: u32 = 5;
 
 #[derive(Default)]
 pub struct MixnetClientBuilder<S: MixnetClientStorage = Ephemeral> {
@@ -604,6 +605,7 @@ where
             client_output,
             client_state,
             reconstructed_receiver,
+            buffered_messages: VecDeque::new(),
             task_manager: started_client.task_manager,
         })
     }
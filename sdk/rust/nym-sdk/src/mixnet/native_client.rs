@@ -1,5 +1,7 @@
 use nym_client_core::client::{
+    address::ClientAddressReceiver,
     base_client::{ClientInput, ClientOutput, ClientState},
+    delivery_events::{DeliveryEventReceiver, MessageId},
     inbound_messages::InputMessage,
     received_buffer::ReconstructedMessagesReceiver,
 };
@@ -9,11 +11,15 @@ use nym_task::{
     TaskManager,
 };
 
-use futures::StreamExt;
+use futures::{ready, Sink, SinkExt, Stream, StreamExt};
 use nym_topology::NymTopology;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio_util::sync::PollSender;
 
 use crate::mixnet::client::{IncludedSurbs, MixnetClientBuilder};
-use crate::Result;
+use crate::{Error, Result};
 
 /// Client connected to the Nym mixnet.
 pub struct MixnetClient {
@@ -26,7 +32,6 @@ pub struct MixnetClient {
 
     /// Output from the client from the users perspective. This is typically messages arriving from
     /// the mixnet.
-    #[allow(dead_code)]
     pub(crate) client_output: ClientOutput,
 
     /// The current state of the client that is exposed to the user. This includes things like
@@ -36,6 +41,10 @@ pub struct MixnetClient {
     /// A channel for messages arriving from the mixnet after they have been reconstructed.
     pub(crate) reconstructed_receiver: ReconstructedMessagesReceiver,
 
+    /// Messages that have already been received from the mixnet, but were not yet returned
+    /// from the [`Stream`] implementation.
+    pub(crate) buffered_messages: VecDeque<ReconstructedMessage>,
+
     /// The task manager that controlls all the spawned tasks that the clients uses to do it's job.
     pub(crate) task_manager: TaskManager,
 }
@@ -69,12 +78,17 @@ impl MixnetClient {
         &self.nym_address
     }
 
+    /// Get a receiver for the current nym address of this client. It changes if the client fails
+    /// over to another gateway, in which case the new address has to be re-announced to anyone
+    /// wishing to reach this client.
+    pub fn nym_address_changes(&self) -> ClientAddressReceiver {
+        self.client_state.client_address.clone()
+    }
+
     /// Get a shallow clone of [`MixnetClientSender`]. Useful if you want split the send and
     /// receive logic in different locations.
     pub fn sender(&self) -> MixnetClientSender {
-        MixnetClientSender {
-            client_input: self.client_input.clone(),
-        }
+        MixnetClientSender::new(self.client_input.clone())
     }
 
     /// Get a shallow clone of [`ConnectionCommandSender`]. This is useful if you want to e.g
@@ -109,6 +123,33 @@ impl MixnetClient {
         self.client_state.topology_accessor.release_manual_control()
     }
 
+    /// Subscribe to the [`DeliveryEvent`](crate::mixnet::DeliveryEvent)s of messages sent with
+    /// [`Self::send_str`] and [`Self::send_bytes`]. Only events of messages sent after the
+    /// subscription are going to be received.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use nym_sdk::mixnet;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mixnet::MixnetClient::connect_new().await.unwrap();
+    ///     let mut delivery_events = client.delivery_events().unwrap();
+    ///     let message_id = client.send_str(*client.nym_address(), "hi").await;
+    ///
+    ///     while let Some(event) = delivery_events.next().await {
+    ///         if event.message_id() == message_id {
+    ///             println!("{event:?}");
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn delivery_events(&mut self) -> Result<DeliveryEventReceiver> {
+        Ok(self.client_output.register_delivery_listener()?)
+    }
+
     /// Sends stringy data to the supplied Nym address
     ///
     /// # Example
@@ -124,15 +165,18 @@ impl MixnetClient {
     ///     client.send_str(recipient, "hi").await;
     /// }
     /// ```
-    pub async fn send_str(&self, address: Recipient, message: &str) {
+    pub async fn send_str(&self, address: Recipient, message: &str) -> MessageId {
         let message_bytes = message.to_string().into_bytes();
         self.send_bytes(address, message_bytes, IncludedSurbs::default())
-            .await;
+            .await
     }
 
     /// Sends bytes to the supplied Nym address. There is the option to specify the number of
     /// reply-SURBs to include.
     ///
+    /// Returns the identifier of the message that can be matched against the received
+    /// [`DeliveryEvent`](crate::mixnet::DeliveryEvent)s.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -147,7 +191,12 @@ impl MixnetClient {
     ///     client.send_bytes(recipient, "hi".to_owned().into_bytes(), surbs).await;
     /// }
     /// ```
-    pub async fn send_bytes(&self, address: Recipient, message: Vec<u8>, surbs: IncludedSurbs) {
+    pub async fn send_bytes(
+        &self,
+        address: Recipient,
+        message: Vec<u8>,
+        surbs: IncludedSurbs,
+    ) -> MessageId {
         let lane = TransmissionLane::General;
         let input_msg = match surbs {
             IncludedSurbs::Amount(surbs) => {
@@ -155,7 +204,10 @@ impl MixnetClient {
             }
             IncludedSurbs::ExposeSelfAddress => InputMessage::new_regular(address, message, lane),
         };
-        self.send(input_msg).await
+        let message_id = MessageId::new_random();
+        self.send(InputMessage::new_tracked(message_id, input_msg))
+            .await;
+        message_id
     }
 
     /// Sends a [`InputMessage`] to the mixnet. This is the most low-level sending function, for
@@ -179,6 +231,10 @@ impl MixnetClient {
 
     /// Wait for messages from the mixnet
     pub async fn wait_for_messages(&mut self) -> Option<Vec<ReconstructedMessage>> {
+        // make sure to not lose any messages that might have been buffered by the `Stream` impl
+        if !self.buffered_messages.is_empty() {
+            return Some(self.buffered_messages.drain(..).collect());
+        }
         self.reconstructed_receiver.next().await
     }
 
@@ -202,14 +258,68 @@ impl MixnetClient {
     }
 }
 
+impl Stream for MixnetClient {
+    type Item = ReconstructedMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(message) = self.buffered_messages.pop_front() {
+                return Poll::Ready(Some(message));
+            }
+
+            match ready!(self.reconstructed_receiver.poll_next_unpin(cx)) {
+                Some(messages) => self.buffered_messages.extend(messages),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
 pub struct MixnetClientSender {
     client_input: ClientInput,
+    input_sink: PollSender<InputMessage>,
 }
 
 impl MixnetClientSender {
+    pub(crate) fn new(client_input: ClientInput) -> Self {
+        let input_sink = PollSender::new(client_input.input_sender.clone());
+        MixnetClientSender {
+            client_input,
+            input_sink,
+        }
+    }
+
     pub async fn send_input_message(&mut self, message: InputMessage) {
         if self.client_input.send(message).await.is_err() {
             log::error!("Failed to send message");
         }
     }
 }
+
+impl Sink<InputMessage> for MixnetClientSender {
+    type Error = Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.input_sink
+            .poll_ready_unpin(cx)
+            .map_err(|_| Error::ClientInputClosed)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: InputMessage) -> Result<()> {
+        self.input_sink
+            .start_send_unpin(item)
+            .map_err(|_| Error::ClientInputClosed)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.input_sink
+            .poll_flush_unpin(cx)
+            .map_err(|_| Error::ClientInputClosed)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.input_sink
+            .poll_close_unpin(cx)
+            .map_err(|_| Error::ClientInputClosed)
+    }
+}
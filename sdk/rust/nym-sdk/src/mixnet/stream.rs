@@ -0,0 +1,666 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::mixnet::client::DEFAULT_NUMBER_OF_SURBS;
+use crate::mixnet::MixnetClient;
+use crate::Result;
+use futures::{ready, Stream, StreamExt};
+use nym_client_core::client::delivery_events::{DeliveryEvent, DeliveryEventReceiver, MessageId};
+use nym_client_core::client::inbound_messages::{InputMessage, InputMessageSender};
+use nym_ordered_buffer::{OrderedMessage, OrderedMessageBuffer, OrderedMessageSender};
+use nym_sphinx::addressing::clients::Recipient;
+use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nym_sphinx::receiver::ReconstructedMessage;
+use nym_task::connections::TransmissionLane;
+use std::collections::HashSet;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio_util::sync::PollSender;
+
+/// The other side of a [`MixnetStream`].
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MixnetStreamPeer {
+    /// Peer with a known nym address. Any data sent to it is accompanied by reply SURBs,
+    /// so that it could respond without learning our address.
+    Known(Recipient),
+
+    /// Anonymous peer that we can only respond to using the reply SURBs it has sent us.
+    Anonymous(AnonymousSenderTag),
+}
+
+impl MixnetStreamPeer {
+    // returns the message alongside its id if its delivery is going to be tracked
+    fn input_message(&self, data: Vec<u8>, reply_surbs: u32) -> (InputMessage, Option<MessageId>) {
+        let lane = TransmissionLane::General;
+        match self {
+            MixnetStreamPeer::Known(recipient) => {
+                let message_id = MessageId::new_random();
+                let message = InputMessage::new_anonymous(*recipient, data, reply_surbs, lane);
+                (
+                    InputMessage::new_tracked(message_id, message),
+                    Some(message_id),
+                )
+            }
+            // delivery of replies can't be tracked
+            MixnetStreamPeer::Anonymous(sender_tag) => {
+                (InputMessage::new_reply(*sender_tag, data, lane), None)
+            }
+        }
+    }
+}
+
+fn delivery_failure() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::ConnectionAborted,
+        "the client has given up on delivering stream data to the peer",
+    )
+}
+
+/// Ordered and reliable byte stream with a single peer, running on top of a [`MixnetClient`].
+/// It implements tokio's [`AsyncRead`] and [`AsyncWrite`], so that any existing protocol could
+/// be run through the mixnet.
+///
+/// The mixnet itself takes care of the reliability via acknowledgements and retransmissions,
+/// while the stream makes sure the data is read in the same order it has been written.
+/// If the client is configured with a maximum number of retransmissions and gives up on delivering
+/// any of the data sent to a known peer, the stream is broken and all further reads and writes fail.
+///
+/// Shutting down the stream sends an explicit close message to the peer, whose reads reach EOF
+/// once it has received all the data written before that.
+///
+/// Note that the stream takes over the underlying client, so all messages it receives are
+/// assumed to be part of the stream. When the peer is anonymous, messages coming from any other
+/// sender are ignored.
+///
+/// # Example
+///
+/// ```no_run
+/// use nym_sdk::mixnet::{self, MixnetStream, MixnetStreamPeer};
+/// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let address = "foobar";
+///     let recipient = mixnet::Recipient::try_from_base58_string(address).unwrap();
+///     let client = mixnet::MixnetClient::connect_new().await.unwrap();
+///
+///     let mut stream = MixnetStream::new(client, MixnetStreamPeer::Known(recipient)).unwrap();
+///     stream.write_all(b"hello there").await.unwrap();
+///
+///     let mut buf = [0u8; 1024];
+///     let n = stream.read(&mut buf).await.unwrap();
+///     println!("Received: {}", String::from_utf8_lossy(&buf[..n]));
+/// }
+/// ```
+pub struct MixnetStream {
+    inner: StreamInner<MixnetClient>,
+}
+
+impl MixnetStream {
+    /// Creates a new stream with the specified peer using the already connected client.
+    pub fn new(mut client: MixnetClient, peer: MixnetStreamPeer) -> Result<Self> {
+        let delivery_events = client.delivery_events()?;
+        Ok(Self::new_with_delivery_events(
+            client,
+            delivery_events,
+            peer,
+        ))
+    }
+
+    fn new_with_delivery_events(
+        client: MixnetClient,
+        delivery_events: DeliveryEventReceiver,
+        peer: MixnetStreamPeer,
+    ) -> Self {
+        let input_sender = client.client_input.input_sender.clone();
+        MixnetStream {
+            inner: StreamInner::new(client, input_sender, delivery_events, peer),
+        }
+    }
+
+    /// Waits for the first message sent by an anonymous peer and creates a stream with it.
+    /// Returns `None` if the client has stopped before any such message has been received.
+    pub async fn accept(mut client: MixnetClient) -> Result<Option<Self>> {
+        let delivery_events = client.delivery_events()?;
+        loop {
+            let Some(message) = client.next().await else {
+                return Ok(None);
+            };
+            let Some(sender_tag) = message.sender_tag else {
+                log::warn!(
+                    "received a message without a sender tag - can't accept a stream from it"
+                );
+                continue;
+            };
+
+            let mut stream = MixnetStream::new_with_delivery_events(
+                client,
+                delivery_events,
+                MixnetStreamPeer::Anonymous(sender_tag),
+            );
+            stream.inner.handle_received_message(message);
+            return Ok(Some(stream));
+        }
+    }
+
+    /// Sets the number of reply SURBs sent alongside every message to a known peer.
+    #[must_use]
+    pub fn with_reply_surbs(mut self, reply_surbs: u32) -> Self {
+        self.inner.reply_surbs = reply_surbs;
+        self
+    }
+
+    /// Returns the other side of this stream.
+    pub fn peer(&self) -> MixnetStreamPeer {
+        self.inner.peer
+    }
+
+    /// Disconnects the underlying client from the mixnet.
+    pub async fn disconnect(mut self) {
+        self.inner.messages.disconnect().await
+    }
+}
+
+impl AsyncRead for MixnetStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for MixnetStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// The actual stream logic, independent of where the received messages are coming from.
+struct StreamInner<M> {
+    messages: M,
+    peer: MixnetStreamPeer,
+    reply_surbs: u32,
+
+    input_sink: PollSender<InputMessage>,
+    message_sender: OrderedMessageSender,
+    message_buffer: OrderedMessageBuffer,
+
+    delivery_events: DeliveryEventReceiver,
+
+    /// Tracked messages of this stream that have not yet been delivered to the peer.
+    undelivered: HashSet<MessageId>,
+
+    /// Set once the client has given up on delivering any of the messages of this stream.
+    delivery_failed: bool,
+
+    /// Contiguous data that has been received, but has not yet been read.
+    read_buffer: Vec<u8>,
+
+    /// Index of the close message received from the peer, if any.
+    peer_close_index: Option<u64>,
+
+    /// Set once all the data sent by the peer before closing the stream has been received.
+    peer_closed: bool,
+}
+
+impl<M> StreamInner<M> {
+    fn new(
+        messages: M,
+        input_sender: InputMessageSender,
+        delivery_events: DeliveryEventReceiver,
+        peer: MixnetStreamPeer,
+    ) -> Self {
+        StreamInner {
+            messages,
+            peer,
+            reply_surbs: DEFAULT_NUMBER_OF_SURBS,
+            input_sink: PollSender::new(input_sender),
+            message_sender: OrderedMessageSender::new(),
+            message_buffer: OrderedMessageBuffer::new(),
+            delivery_events,
+            undelivered: HashSet::new(),
+            delivery_failed: false,
+            read_buffer: Vec::new(),
+            peer_close_index: None,
+            peer_closed: false,
+        }
+    }
+
+    // note: empty messages are never sent as data, so an empty message marks the end of the stream
+    fn send_message(&mut self, data: Vec<u8>) -> io::Result<()> {
+        let ordered_message = self.message_sender.wrap_message(data);
+        let (input_message, message_id) = self
+            .peer
+            .input_message(ordered_message.into_bytes(), self.reply_surbs);
+        self.input_sink
+            .send_item(input_message)
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+
+        if let Some(message_id) = message_id {
+            self.undelivered.insert(message_id);
+        }
+        Ok(())
+    }
+
+    fn poll_delivery_events(&mut self, cx: &mut Context<'_>) -> io::Result<()> {
+        while let Poll::Ready(Some(event)) = self.delivery_events.poll_next_unpin(cx) {
+            match event {
+                DeliveryEvent::Delivered(message_id) => {
+                    self.undelivered.remove(&message_id);
+                }
+                DeliveryEvent::GivenUp(message_id) => {
+                    if self.undelivered.remove(&message_id) {
+                        log::warn!(
+                            "the client has given up on delivering stream message {message_id}"
+                        );
+                        self.delivery_failed = true;
+                    }
+                }
+                DeliveryEvent::Retransmitting(_) => (),
+            }
+        }
+
+        if self.delivery_failed {
+            Err(delivery_failure())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn is_past_close(&self, index: u64) -> bool {
+        matches!(self.peer_close_index, Some(close_index) if index > close_index)
+    }
+
+    fn handle_received_message(&mut self, message: ReconstructedMessage) {
+        if let MixnetStreamPeer::Anonymous(sender_tag) = self.peer {
+            if message.sender_tag != Some(sender_tag) {
+                log::debug!("received a message that does not belong to the stream");
+                return;
+            }
+        }
+
+        let ordered_message = match OrderedMessage::try_from_bytes(message.message) {
+            Ok(ordered_message) => ordered_message,
+            Err(err) => {
+                log::warn!("received malformed stream message: {err}");
+                return;
+            }
+        };
+
+        if ordered_message.data.is_empty() {
+            self.peer_close_index = Some(ordered_message.index);
+        } else if self.is_past_close(ordered_message.index) {
+            log::debug!("received stream data past its end");
+            return;
+        }
+
+        self.message_buffer.write(ordered_message);
+        if let Some(contiguous) = self.message_buffer.read() {
+            self.read_buffer.extend(contiguous.data);
+            // `last_index` is the index of the next message that is yet to be read
+            if self.is_past_close(contiguous.last_index) {
+                self.peer_closed = true;
+            }
+        }
+    }
+}
+
+impl<M> AsyncRead for StreamInner<M>
+where
+    M: Stream<Item = ReconstructedMessage> + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.poll_delivery_events(cx)?;
+
+        while self.read_buffer.is_empty() {
+            if self.peer_closed {
+                return Poll::Ready(Ok(()));
+            }
+            match ready!(self.messages.poll_next_unpin(cx)) {
+                Some(message) => self.handle_received_message(message),
+                // the client has shut down, so there's no more data to read
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+
+        let n = buf.remaining().min(self.read_buffer.len());
+        buf.put_slice(&self.read_buffer[..n]);
+        self.read_buffer.drain(..n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<M: Unpin> AsyncWrite for StreamInner<M> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_delivery_events(cx)?;
+
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        ready!(self.input_sink.poll_reserve(cx))
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        self.send_message(buf.to_vec())?;
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // once the message is handed over to the client, it's its responsibility to deliver it
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.input_sink.is_closed() {
+            return Poll::Ready(Ok(()));
+        }
+
+        // let the peer know there's not going to be any more data
+        ready!(self.input_sink.poll_reserve(cx))
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        self.send_message(Vec::new())?;
+
+        self.input_sink.close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::mpsc;
+    use futures::FutureExt;
+    use nym_crypto::asymmetric::{encryption, identity};
+    use rand::rngs::OsRng;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    type TestStream = StreamInner<mpsc::UnboundedReceiver<ReconstructedMessage>>;
+
+    struct TestConnection {
+        stream: TestStream,
+        incoming: mpsc::UnboundedSender<ReconstructedMessage>,
+        outgoing: tokio::sync::mpsc::Receiver<InputMessage>,
+        delivery_events: mpsc::UnboundedSender<DeliveryEvent>,
+
+        /// Ids of all the tracked messages that have been sent so far.
+        tracked: Vec<MessageId>,
+    }
+
+    impl TestConnection {
+        fn new(peer: MixnetStreamPeer, input_capacity: usize) -> Self {
+            let (incoming, messages) = mpsc::unbounded();
+            let (input_sender, outgoing) = tokio::sync::mpsc::channel(input_capacity);
+            let (delivery_events, delivery_receiver) = mpsc::unbounded();
+            TestConnection {
+                stream: StreamInner::new(messages, input_sender, delivery_receiver, peer),
+                incoming,
+                outgoing,
+                delivery_events,
+                tracked: Vec::new(),
+            }
+        }
+
+        // returns the sent messages stripped of their tracking wrappers
+        fn sent_messages(&mut self) -> Vec<InputMessage> {
+            let mut sent = Vec::new();
+            while let Ok(message) = self.outgoing.try_recv() {
+                match message {
+                    InputMessage::Tracked {
+                        message_id,
+                        message,
+                    } => {
+                        self.tracked.push(message_id);
+                        sent.push(*message)
+                    }
+                    message => sent.push(message),
+                }
+            }
+            sent
+        }
+
+        fn emit_delivery_event(&self, event: DeliveryEvent) {
+            self.delivery_events.unbounded_send(event).unwrap()
+        }
+
+        fn receive(&self, data: Vec<u8>, sender_tag: Option<AnonymousSenderTag>) {
+            self.incoming
+                .unbounded_send(ReconstructedMessage {
+                    message: data,
+                    sender_tag,
+                })
+                .unwrap()
+        }
+    }
+
+    fn random_recipient() -> Recipient {
+        Recipient::new(
+            *identity::KeyPair::new(&mut OsRng).public_key(),
+            *encryption::KeyPair::new(&mut OsRng).public_key(),
+            *identity::KeyPair::new(&mut OsRng).public_key(),
+        )
+    }
+
+    #[tokio::test]
+    async fn data_makes_a_round_trip_in_order() {
+        let recipient = random_recipient();
+        let sender_tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let mut client = TestConnection::new(MixnetStreamPeer::Known(recipient), 10);
+        let mut server = TestConnection::new(MixnetStreamPeer::Anonymous(sender_tag), 10);
+
+        for chunk in ["hello", " ", "there"] {
+            client.stream.write_all(chunk.as_bytes()).await.unwrap();
+        }
+
+        // the mixnet doesn't guarantee the ordering of messages
+        let sent = client.sent_messages();
+        assert_eq!(sent.len(), 3);
+        for message in sent.into_iter().rev() {
+            match message {
+                InputMessage::Anonymous {
+                    recipient: sent_to,
+                    data,
+                    reply_surbs,
+                    ..
+                } => {
+                    assert_eq!(sent_to, recipient);
+                    assert_eq!(reply_surbs, DEFAULT_NUMBER_OF_SURBS);
+                    server.receive(data, Some(sender_tag))
+                }
+                _ => panic!("unexpected input message"),
+            }
+        }
+
+        let mut received = [0u8; 11];
+        server.stream.read_exact(&mut received).await.unwrap();
+        assert_eq!(&received, b"hello there");
+
+        // and the server can respond using the reply surbs
+        server.stream.write_all(b"general kenobi").await.unwrap();
+        for message in server.sent_messages() {
+            match message {
+                InputMessage::Reply {
+                    recipient_tag,
+                    data,
+                    ..
+                } => {
+                    assert_eq!(recipient_tag, sender_tag);
+                    client.receive(data, None)
+                }
+                _ => panic!("unexpected input message"),
+            }
+        }
+
+        let mut received = [0u8; 14];
+        client.stream.read_exact(&mut received).await.unwrap();
+        assert_eq!(&received, b"general kenobi");
+    }
+
+    #[tokio::test]
+    async fn messages_from_other_senders_are_ignored() {
+        let sender_tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let other_tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let mut client = TestConnection::new(MixnetStreamPeer::Known(random_recipient()), 10);
+        let mut server = TestConnection::new(MixnetStreamPeer::Anonymous(sender_tag), 10);
+
+        client.stream.write_all(b"foo").await.unwrap();
+        let Some(InputMessage::Anonymous { data, .. }) = client.sent_messages().pop() else {
+            panic!("unexpected input message")
+        };
+        server.receive(data.clone(), Some(other_tag));
+        server.receive(data.clone(), None);
+        server.receive(b"bad".to_vec(), Some(sender_tag));
+        assert!(server.stream.read(&mut [0u8; 3]).now_or_never().is_none());
+
+        server.receive(data, Some(sender_tag));
+        let mut received = [0u8; 3];
+        server.stream.read_exact(&mut received).await.unwrap();
+        assert_eq!(&received, b"foo");
+    }
+
+    #[tokio::test]
+    async fn writes_are_applied_back_pressure() {
+        let mut connection = TestConnection::new(MixnetStreamPeer::Known(random_recipient()), 1);
+
+        connection.stream.write_all(b"foo").await.unwrap();
+
+        // the client hasn't yet consumed the previous message
+        assert!(connection.stream.write(b"bar").now_or_never().is_none());
+        assert_eq!(connection.sent_messages().len(), 1);
+
+        assert_eq!(connection.stream.write(b"bar").await.unwrap(), 3);
+        assert_eq!(connection.sent_messages().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn nothing_can_be_written_after_shutdown() {
+        let mut connection = TestConnection::new(MixnetStreamPeer::Known(random_recipient()), 10);
+
+        connection.stream.write_all(b"foo").await.unwrap();
+        connection.stream.shutdown().await.unwrap();
+
+        // shutting down for the second time doesn't send anything
+        connection.stream.shutdown().await.unwrap();
+
+        let err = connection.stream.write_all(b"bar").await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+
+        // the data and the close message
+        assert_eq!(connection.sent_messages().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn nothing_can_be_written_once_client_has_stopped() {
+        let mut connection = TestConnection::new(MixnetStreamPeer::Known(random_recipient()), 10);
+        connection.outgoing.close();
+
+        let err = connection.stream.write_all(b"foo").await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    #[tokio::test]
+    async fn reading_reaches_eof_once_client_has_stopped() {
+        let sender_tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let mut client = TestConnection::new(MixnetStreamPeer::Known(random_recipient()), 10);
+        let mut server = TestConnection::new(MixnetStreamPeer::Anonymous(sender_tag), 10);
+
+        client.stream.write_all(b"foo").await.unwrap();
+        let Some(InputMessage::Anonymous { data, .. }) = client.sent_messages().pop() else {
+            panic!("unexpected input message")
+        };
+        server.receive(data, Some(sender_tag));
+        server.incoming.close_channel();
+
+        // any data received before the client has stopped can still be read
+        let mut received = Vec::new();
+        server.stream.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, b"foo");
+    }
+
+    #[tokio::test]
+    async fn reading_reaches_eof_once_peer_has_shut_down() {
+        let sender_tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let mut client = TestConnection::new(MixnetStreamPeer::Known(random_recipient()), 10);
+        let mut server = TestConnection::new(MixnetStreamPeer::Anonymous(sender_tag), 10);
+
+        client.stream.write_all(b"foo").await.unwrap();
+        client.stream.write_all(b"bar").await.unwrap();
+        client.stream.shutdown().await.unwrap();
+
+        // the close message overtakes the data
+        let mut sent = client.sent_messages();
+        assert_eq!(sent.len(), 3);
+        let Some(InputMessage::Anonymous { data, .. }) = sent.pop() else {
+            panic!("unexpected input message")
+        };
+        server.receive(data, Some(sender_tag));
+        assert!(server.stream.read(&mut [0u8; 3]).now_or_never().is_none());
+
+        for message in sent {
+            let InputMessage::Anonymous { data, .. } = message else {
+                panic!("unexpected input message")
+            };
+            server.receive(data, Some(sender_tag));
+        }
+
+        // the client is still running, but all of the data has already been received
+        let mut received = Vec::new();
+        server.stream.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, b"foobar");
+    }
+
+    #[tokio::test]
+    async fn giving_up_on_delivery_breaks_the_stream() {
+        let mut connection = TestConnection::new(MixnetStreamPeer::Known(random_recipient()), 10);
+
+        connection.stream.write_all(b"foo").await.unwrap();
+        connection.stream.write_all(b"bar").await.unwrap();
+        connection.sent_messages();
+        assert_eq!(connection.tracked.len(), 2);
+
+        // events of other messages are irrelevant
+        connection.emit_delivery_event(DeliveryEvent::GivenUp(MessageId::from(42)));
+        connection.emit_delivery_event(DeliveryEvent::Delivered(connection.tracked[0]));
+        connection.emit_delivery_event(DeliveryEvent::Retransmitting(connection.tracked[1]));
+        connection.stream.write_all(b"baz").await.unwrap();
+
+        // a pending read fails once the delivery does
+        let mut buf = [0u8; 3];
+        let read = connection.stream.read(&mut buf);
+        tokio::pin!(read);
+        assert!(read.as_mut().now_or_never().is_none());
+        connection
+            .delivery_events
+            .unbounded_send(DeliveryEvent::GivenUp(connection.tracked[1]))
+            .unwrap();
+
+        let err = read.await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionAborted);
+        let err = connection.stream.write_all(b"qux").await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionAborted);
+    }
+}
@@ -133,6 +133,7 @@ pub(crate) async fn execute(args: &Init) -> Result<(), NetworkRequesterError> {
         user_chosen_gateway_id,
         config.get_base(),
         args.latency_based_selection,
+        None,
     )
     .await
     .map_err(|source| {
@@ -148,7 +149,8 @@ pub(crate) async fn execute(args: &Init) -> Result<(), NetworkRequesterError> {
 
     print_saved_config(&config);
 
-    let address = nym_client_core::init::get_client_address_from_stored_keys(config.get_base())?;
+    let address =
+        nym_client_core::init::get_client_address_from_stored_keys(config.get_base(), None)?;
     let init_results = InitResults::new(&config, &address);
     println!("{}", args.output.format(&init_results));
 
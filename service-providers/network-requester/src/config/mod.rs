@@ -4,6 +4,7 @@
 use crate::config::template::config_template;
 use nym_client_core::config::ClientCoreConfigTrait;
 use nym_config::{NymConfig, OptionalSet};
+use nym_statistics_common::privacy::PrivacyConfig;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -29,6 +30,9 @@ pub struct Config {
 
     #[serde(default)]
     pub network_requester_debug: Debug,
+
+    #[serde(default)]
+    pub statistics_privacy: PrivacyConfig,
 }
 
 impl NymConfig for Config {
@@ -137,6 +141,10 @@ impl Config {
         self.network_requester.unknown_list_location.clone()
     }
 
+    pub fn statistics_privacy(&self) -> PrivacyConfig {
+        self.statistics_privacy
+    }
+
     pub fn get_base(&self) -> &BaseConfig<Self> {
         &self.base
     }
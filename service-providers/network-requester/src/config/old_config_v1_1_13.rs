@@ -56,6 +56,7 @@ impl From<OldConfigV1_1_13> for Config {
             base: value.base.into(),
             network_requester: Default::default(),
             network_requester_debug: Default::default(),
+            statistics_privacy: Default::default(),
         }
     }
 }
@@ -85,6 +85,21 @@ allowed_list_location = '{{ network_requester.allowed_list_location }}'
 # Location of the file containing our unknown.list
 unknown_list_location = '{{ network_requester.unknown_list_location }}'
 
+[statistics_privacy]
+# Specifies whether the statistics should be made differentially private before being sent.
+enabled = {{ statistics_privacy.enabled }}
+
+# Privacy budget (epsilon) spent on all the values reported within a single interval.
+# Lower values result in stronger privacy guarantees at the cost of less accurate statistics.
+epsilon_per_interval = {{ statistics_privacy.epsilon_per_interval }}
+
+# Values which, after adding the noise, are lower than this threshold (in the units of sensitivity
+# of the particular value) are not reported individually.
+small_count_threshold = {{ statistics_privacy.small_count_threshold }}
+
+# Granularity (in the units of sensitivity of the particular value) to which the reported values are rounded.
+rounding_granularity = {{ statistics_privacy.rounding_granularity }}
+
 ##### logging configuration options #####
 
 [logging]
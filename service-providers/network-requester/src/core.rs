@@ -8,7 +8,7 @@ use crate::allowed_hosts::{OutboundRequestFilter, StandardList};
 use crate::config::Config;
 use crate::error::NetworkRequesterError;
 use crate::reply::MixnetMessage;
-use crate::statistics::ServiceStatisticsCollector;
+use crate::statistics::{client_identifier, ConnectedService, ServiceStatisticsCollector};
 use crate::{reply, socks5};
 use async_trait::async_trait;
 use futures::channel::mpsc;
@@ -24,8 +24,9 @@ use nym_socks5_proxy_helpers::connection_controller::{
 };
 use nym_socks5_proxy_helpers::proxy_runner::{MixProxyReader, MixProxySender};
 use nym_socks5_requests::{
-    ConnectRequest, ConnectionId, NetworkData, SendRequest, Socks5ProtocolVersion,
-    Socks5ProviderRequest, Socks5Request, Socks5RequestContent, Socks5Response,
+    ConnectRequest, ConnectionId, NetworkData, SendDatagramRequest, SendRequest,
+    Socks5ProtocolVersion, Socks5ProviderRequest, Socks5Request, Socks5RequestContent,
+    Socks5Response,
 };
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
@@ -33,6 +34,7 @@ use nym_sphinx::params::PacketSize;
 use nym_statistics_common::collector::StatisticsSender;
 use nym_task::connections::LaneQueueLengths;
 use nym_task::{TaskClient, TaskManager};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 // Since it's an atomic, it's safe to be kept static and shared across threads
@@ -65,6 +67,7 @@ struct NRServiceProvider {
     mixnet_client: nym_sdk::mixnet::MixnetClient,
 
     controller_sender: ControllerSender,
+    udp_associations: HashMap<ConnectionId, socks5::udp::DatagramSender>,
     mix_input_sender: MixProxySender<MixnetMessage>,
     //shared_lane_queue_lengths: LaneQueueLengths,
     stats_collector: Option<ServiceStatisticsCollector>,
@@ -122,32 +125,47 @@ impl ServiceProvider<Socks5Request> for NRServiceProvider {
         match request.content {
             Socks5RequestContent::Connect(req) => {
                 if let Some(stats_collector) = &self.stats_collector {
+                    let connected_service = ConnectedService {
+                        remote_addr: req.remote_addr.clone(),
+                        client: client_identifier(req.return_address.as_ref(), sender),
+                    };
                     stats_collector
                         .connected_services
                         .write()
                         .await
-                        .insert(req.conn_id, req.remote_addr.clone());
+                        .insert(req.conn_id, connected_service);
                 }
                 self.handle_proxy_connect(request_version, sender, req)
                     .await
             }
             Socks5RequestContent::Send(req) => {
                 if let Some(stats_collector) = &self.stats_collector {
-                    if let Some(remote_addr) = stats_collector
+                    if let Some(service) = stats_collector
                         .connected_services
                         .read()
                         .await
                         .get(&req.conn_id)
                     {
-                        stats_collector
-                            .request_stats_data
-                            .write()
-                            .await
-                            .processed(remote_addr, req.data.len() as u32);
+                        stats_collector.request_stats_data.write().await.processed(
+                            &service.remote_addr,
+                            &service.client,
+                            req.data.len() as u32,
+                        );
                     }
                 }
                 self.handle_proxy_send(req)
             }
+            Socks5RequestContent::SendDatagram(req) => {
+                if let Some(stats_collector) = &self.stats_collector {
+                    stats_collector.request_stats_data.write().await.processed(
+                        &req.remote_addr,
+                        &client_identifier(req.return_address.as_ref(), sender),
+                        req.data.len() as u32,
+                    );
+                }
+                self.handle_proxy_send_datagram(request_version, sender, req)
+                    .await
+            }
         }
 
         Ok(None)
@@ -203,10 +221,13 @@ impl NRServiceProviderBuilder {
         });
 
         let stats_collector = if self.enable_statistics {
-            let stats_collector =
-                ServiceStatisticsCollector::new(self.stats_provider_addr, mix_input_sender.clone())
-                    .await
-                    .expect("Service statistics collector could not be bootstrapped");
+            let stats_collector = ServiceStatisticsCollector::new(
+                self.stats_provider_addr,
+                mix_input_sender.clone(),
+                self.config.statistics_privacy(),
+            )
+            .await
+            .expect("Service statistics collector could not be bootstrapped");
             let mut stats_sender = StatisticsSender::new(stats_collector.clone());
 
             tokio::spawn(async move {
@@ -250,6 +271,7 @@ impl NRServiceProviderBuilder {
             open_proxy: self.open_proxy,
             mixnet_client,
             controller_sender,
+            udp_associations: HashMap::new(),
             mix_input_sender,
             //shared_lane_queue_lengths: mixnet_client.shared_lane_queue_lengths(),
             stats_collector,
@@ -302,17 +324,17 @@ impl NRServiceProvider {
                 socks5_msg = mix_input_reader.recv() => {
                     if let Some(msg) = socks5_msg {
                         if let Some(stats_collector) = stats_collector.as_ref() {
-                            if let Some(remote_addr) = stats_collector
+                            if let Some(service) = stats_collector
                                 .connected_services
                                 .read()
                                 .await
                                 .get(&msg.connection_id)
                             {
-                                stats_collector
-                                    .response_stats_data
-                                    .write()
-                                    .await
-                                    .processed(remote_addr, msg.data_size() as u32);
+                                stats_collector.response_stats_data.write().await.processed(
+                                    &service.remote_addr,
+                                    &service.client,
+                                    msg.data_size() as u32,
+                                );
                             }
                         }
 
@@ -417,7 +439,9 @@ impl NRServiceProvider {
         sender_tag: Option<AnonymousSenderTag>,
         connect_req: Box<ConnectRequest>,
     ) {
-        let Some(return_address) = reply::MixnetAddress::new(connect_req.return_address, sender_tag) else {
+        let Some(return_address) =
+            reply::MixnetAddress::new(connect_req.return_address, sender_tag)
+        else {
             log::warn!(
                 "attempted to start connection with no way of returning data back to the sender"
             );
@@ -473,6 +497,89 @@ impl NRServiceProvider {
     fn handle_proxy_send(&mut self, req: SendRequest) {
         self.controller_sender.unbounded_send(req.into()).unwrap()
     }
+
+    async fn handle_proxy_send_datagram(
+        &mut self,
+        remote_version: RequestVersion<Socks5Request>,
+        sender_tag: Option<AnonymousSenderTag>,
+        datagram_req: Box<SendDatagramRequest>,
+    ) {
+        let SendDatagramRequest {
+            conn_id,
+            remote_addr,
+            data,
+            return_address,
+        } = *datagram_req;
+
+        let Some(return_address) = reply::MixnetAddress::new(return_address, sender_tag) else {
+            log::warn!(
+                "attempted to send a datagram with no way of returning data back to the sender"
+            );
+            return;
+        };
+
+        if !self.open_proxy && !self.outbound_request_filter.check(&remote_addr).await {
+            let log_msg = format!("Domain {remote_addr:?} failed filter check");
+            log::info!("{}", log_msg);
+            let msg = MixnetMessage::new_connection_error(
+                return_address,
+                remote_version,
+                conn_id,
+                log_msg,
+            );
+            self.mix_input_sender
+                .send(msg)
+                .await
+                .expect("InputMessageReceiver has stopped receiving!");
+            return;
+        }
+
+        // the association might have already timed out, in which case we just start a new one
+        if let Some(datagram_sender) = self.udp_associations.get(&conn_id) {
+            if !datagram_sender.is_closed() {
+                datagram_sender
+                    .unbounded_send((remote_addr, data))
+                    .expect("the channel has just been checked for being open");
+                return;
+            }
+        }
+
+        let (association, datagram_sender) =
+            match socks5::udp::UdpAssociation::new(conn_id, return_address.clone()).await {
+                Ok(association) => association,
+                Err(err) => {
+                    log::error!("failed to start udp association {conn_id}: {err}");
+                    let msg = MixnetMessage::new_connection_error(
+                        return_address,
+                        remote_version,
+                        conn_id,
+                        format!("failed to start udp association: {err}"),
+                    );
+                    self.mix_input_sender
+                        .send(msg)
+                        .await
+                        .expect("InputMessageReceiver has stopped receiving!");
+                    return;
+                }
+            };
+
+        datagram_sender
+            .unbounded_send((remote_addr, data))
+            .expect("the association has just been created");
+        self.udp_associations
+            .retain(|_, datagram_sender| !datagram_sender.is_closed());
+        self.udp_associations.insert(conn_id, datagram_sender);
+
+        let mix_input_sender_clone = self.mix_input_sender.clone();
+        let shutdown = self.shutdown.subscribe();
+        tokio::spawn(async move {
+            log::info!("Starting udp association {conn_id}");
+            association
+                .run(remote_version, mix_input_sender_clone, shutdown)
+                .await;
+            log::info!("Udp association {conn_id} is finished");
+        });
+    }
 }
 
 // Helper function to create the mixnet client.
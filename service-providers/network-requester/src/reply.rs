@@ -6,8 +6,8 @@ use nym_service_providers_common::interface::{
     ControlRequest, ControlResponse, ProviderInterfaceVersion, RequestVersion,
 };
 use nym_socks5_requests::{
-    ConnectionId, NetworkData, Socks5ProviderRequest, Socks5ProviderResponse, Socks5Request,
-    Socks5RequestContent, Socks5Response, Socks5ResponseContent,
+    ConnectionId, NetworkData, RemoteAddress, Socks5ProviderRequest, Socks5ProviderResponse,
+    Socks5Request, Socks5RequestContent, Socks5Response, Socks5ResponseContent,
 };
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
@@ -143,6 +143,25 @@ impl MixnetMessage {
         Self::new_network_data_response(address, request_version, connection_id, response_content)
     }
 
+    pub(crate) fn new_datagram_response(
+        address: MixnetAddress,
+        request_version: RequestVersion<Socks5Request>,
+        connection_id: ConnectionId,
+        remote_addr: RemoteAddress,
+        data: Vec<u8>,
+    ) -> Self {
+        let res = Socks5Response::new_datagram(
+            request_version.provider_protocol,
+            connection_id,
+            remote_addr,
+            data,
+        );
+        let msg =
+            Socks5ProviderResponse::new_provider_data(request_version.provider_interface, res);
+
+        Self::new_provider_response(address, connection_id, msg)
+    }
+
     pub(crate) fn data_size(&self) -> usize {
         self.data.len()
     }
@@ -0,0 +1,155 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::reply;
+use crate::reply::MixnetMessage;
+use futures::channel::mpsc;
+use futures::StreamExt;
+use nym_service_providers_common::interface::RequestVersion;
+use nym_socks5_proxy_helpers::proxy_runner::MixProxySender;
+use nym_socks5_requests::{ConnectionId, RemoteAddress, Socks5Request};
+use nym_task::TaskClient;
+use std::collections::HashSet;
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::Instant;
+
+/// Maximum amount of time an association is kept alive without any datagrams
+/// going through it in either direction.
+const ASSOCIATION_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Maximum size of a UDP datagram we might receive.
+const MAX_DATAGRAM_SIZE: usize = 65535;
+
+pub(crate) type DatagramSender = mpsc::UnboundedSender<(RemoteAddress, Vec<u8>)>;
+type DatagramReceiver = mpsc::UnboundedReceiver<(RemoteAddress, Vec<u8>)>;
+
+/// An outbound UDP association between the Socks5 service provider and any number of remote
+/// hosts. Datagrams are sent on behalf of the user and any datagrams received back from the
+/// contacted hosts are returned through the mixnet.
+#[derive(Debug)]
+pub(crate) struct UdpAssociation {
+    id: ConnectionId,
+    socket: UdpSocket,
+    return_address: reply::MixnetAddress,
+    datagram_receiver: DatagramReceiver,
+
+    /// Remote hosts we have sent datagrams to. We only ever accept datagrams coming from them.
+    contacted: HashSet<SocketAddr>,
+}
+
+impl UdpAssociation {
+    pub(crate) async fn new(
+        id: ConnectionId,
+        return_address: reply::MixnetAddress,
+    ) -> io::Result<(Self, DatagramSender)> {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        let (datagram_sender, datagram_receiver) = mpsc::unbounded();
+
+        Ok((
+            UdpAssociation {
+                id,
+                socket,
+                return_address,
+                datagram_receiver,
+                contacted: HashSet::new(),
+            },
+            datagram_sender,
+        ))
+    }
+
+    async fn send_datagram(&mut self, remote_addr: RemoteAddress, data: Vec<u8>) {
+        let target = match tokio::net::lookup_host(&remote_addr).await {
+            Ok(mut addresses) => addresses.find(SocketAddr::is_ipv4),
+            Err(err) => {
+                log::debug!("failed to resolve {remote_addr}: {err}");
+                return;
+            }
+        };
+
+        let Some(target) = target else {
+            log::debug!("{remote_addr} did not resolve to any ipv4 address");
+            return;
+        };
+
+        if let Err(err) = self.socket.send_to(&data, target).await {
+            log::debug!("failed to send datagram to {target}: {err}");
+            return;
+        }
+        self.contacted.insert(target);
+    }
+
+    fn handle_received_datagram(
+        &self,
+        source: SocketAddr,
+        data: &[u8],
+        remote_version: &RequestVersion<Socks5Request>,
+    ) -> Option<MixnetMessage> {
+        if !self.contacted.contains(&source) {
+            log::debug!(
+                "received an unsolicited datagram from {source} on association {}",
+                self.id
+            );
+            return None;
+        }
+
+        Some(MixnetMessage::new_datagram_response(
+            self.return_address.clone(),
+            remote_version.clone(),
+            self.id,
+            source.to_string(),
+            data.to_vec(),
+        ))
+    }
+
+    pub(crate) async fn run(
+        mut self,
+        remote_version: RequestVersion<Socks5Request>,
+        mix_sender: MixProxySender<MixnetMessage>,
+        mut shutdown: TaskClient,
+    ) {
+        let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+        let idle_timeout = tokio::time::sleep(ASSOCIATION_IDLE_TIMEOUT);
+        tokio::pin!(idle_timeout);
+
+        loop {
+            tokio::select! {
+                datagram = self.datagram_receiver.next() => {
+                    let Some((remote_addr, data)) = datagram else {
+                        log::trace!("UdpAssociation {}: datagram channel closed", self.id);
+                        break;
+                    };
+                    self.send_datagram(remote_addr, data).await;
+                    idle_timeout.as_mut().reset(Instant::now() + ASSOCIATION_IDLE_TIMEOUT);
+                }
+                received = self.socket.recv_from(&mut buf) => {
+                    let (n, source) = match received {
+                        Ok(received) => received,
+                        Err(err) => {
+                            log::debug!("UdpAssociation {}: failed to receive datagram: {err}", self.id);
+                            continue;
+                        }
+                    };
+                    if let Some(msg) = self.handle_received_datagram(source, &buf[..n], &remote_version) {
+                        if mix_sender.send(msg).await.is_err() {
+                            log::error!("InputMessageReceiver has stopped receiving!");
+                            break;
+                        }
+                        idle_timeout.as_mut().reset(Instant::now() + ASSOCIATION_IDLE_TIMEOUT);
+                    }
+                }
+                _ = &mut idle_timeout => {
+                    log::debug!("UdpAssociation {} has been idle for too long", self.id);
+                    break;
+                }
+                _ = shutdown.recv() => {
+                    log::trace!("UdpAssociation {}: Received shutdown", self.id);
+                    break;
+                }
+            }
+        }
+        shutdown.mark_as_success();
+    }
+}
@@ -11,13 +11,16 @@ use nym_service_providers_common::interface::RequestVersion;
 use nym_socks5_proxy_helpers::proxy_runner::MixProxySender;
 use nym_socks5_requests::{ConnectionId, RemoteAddress, Socks5Request, Socks5RequestContent};
 use nym_sphinx::addressing::clients::Recipient;
+use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nym_statistics_common::api::{
     build_statistics_request_bytes, DEFAULT_STATISTICS_SERVICE_ADDRESS,
     DEFAULT_STATISTICS_SERVICE_PORT,
 };
 use nym_statistics_common::{
-    collector::StatisticsCollector, error::StatsError as CommonStatsError, StatsMessage,
-    StatsServiceData,
+    collector::StatisticsCollector,
+    error::StatsError as CommonStatsError,
+    privacy::{PrivacyConfig, PrivacyFilter, SensitiveValue, SMALL_COUNTS_BUCKET},
+    StatsMessage, StatsServiceData,
 };
 use rand::RngCore;
 use serde::Deserialize;
@@ -30,9 +33,36 @@ use tokio::sync::RwLock;
 const REMOTE_SOURCE_OF_STATS_PROVIDER_CONFIG: &str =
     "https://nymtech.net/.wellknown/network-requester/stats-provider.json";
 
+// maximum number of bytes a single client can contribute to the statistics of a single service
+// within a reporting interval if they are made private. Anything above it is not counted.
+const MAX_CLIENT_PROCESSED_BYTES: u64 = 1024 * 1024;
+
+// all the clients that can't be identified are treated as a single one
+const UNIDENTIFIED_CLIENT: &str = "unidentified";
+
+/// Identifies the client on whose behalf the data is being processed,
+/// so that its contribution to the statistics could be bounded.
+pub(crate) fn client_identifier(
+    return_address: Option<&Recipient>,
+    sender_tag: Option<AnonymousSenderTag>,
+) -> String {
+    match (return_address, sender_tag) {
+        (Some(recipient), _) => recipient.to_string(),
+        (None, Some(sender_tag)) => sender_tag.to_base58_string(),
+        (None, None) => UNIDENTIFIED_CLIENT.to_string(),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ConnectedService {
+    pub(crate) remote_addr: RemoteAddress,
+    pub(crate) client: String,
+}
+
 #[derive(Clone, Debug)]
 pub struct StatsData {
-    client_processed_bytes: HashMap<String, u32>,
+    // bytes processed for each service, split by the clients on whose behalf they were processed
+    client_processed_bytes: HashMap<String, HashMap<String, u64>>,
 }
 
 impl StatsData {
@@ -42,13 +72,30 @@ impl StatsData {
         }
     }
 
-    pub fn processed(&mut self, remote_addr: &str, bytes: u32) {
-        if let Some(curr_bytes) = self.client_processed_bytes.get_mut(remote_addr) {
-            *curr_bytes += bytes;
-        } else {
-            self.client_processed_bytes
-                .insert(remote_addr.to_string(), bytes);
-        }
+    pub fn processed(&mut self, remote_addr: &str, client: &str, bytes: u32) {
+        let processed = self
+            .client_processed_bytes
+            .entry(remote_addr.to_string())
+            .or_default()
+            .entry(client.to_string())
+            .or_default();
+        *processed = processed.saturating_add(bytes as u64);
+    }
+
+    /// Total number of bytes processed for the particular service,
+    /// optionally with the contribution of each client bounded by the provided maximum.
+    fn service_processed_bytes(&self, remote_addr: &str, max_client_bytes: Option<u64>) -> u32 {
+        let total = self
+            .client_processed_bytes
+            .get(remote_addr)
+            .map(|clients| {
+                clients
+                    .values()
+                    .map(|&bytes| max_client_bytes.map_or(bytes, |max| bytes.min(max)))
+                    .fold(0u64, u64::saturating_add)
+            })
+            .unwrap_or_default();
+        total.min(u32::MAX as u64) as u32
     }
 }
 
@@ -76,17 +123,21 @@ impl OptionalStatsProviderConfig {
 pub(crate) struct ServiceStatisticsCollector {
     pub(crate) request_stats_data: Arc<RwLock<StatsData>>,
     pub(crate) response_stats_data: Arc<RwLock<StatsData>>,
-    pub(crate) connected_services: Arc<RwLock<HashMap<ConnectionId, RemoteAddress>>>,
+    pub(crate) connected_services: Arc<RwLock<HashMap<ConnectionId, ConnectedService>>>,
     stats_provider_addr: Recipient,
     mix_input_sender: MixProxySender<MixnetMessage>,
     request_version: RequestVersion<Socks5Request>,
+    privacy_filter: Option<PrivacyFilter>,
 }
 
 impl ServiceStatisticsCollector {
     pub(crate) async fn new(
         stats_provider_addr: Option<Recipient>,
         mix_input_sender: MixProxySender<MixnetMessage>,
+        privacy_config: PrivacyConfig,
     ) -> Result<Self, StatsError> {
+        let privacy_filter = PrivacyFilter::from_config(privacy_config)?;
+
         let client = reqwest::Client::builder()
             .timeout(Duration::from_secs(3))
             .build()?;
@@ -114,8 +165,62 @@ impl ServiceStatisticsCollector {
             // for now always use legacy serialization since we'll never be sending control
             // messages to the stats collector anyway and we can't be sure they're using the updated interfaces
             request_version: new_legacy_request_version(),
+            privacy_filter,
         })
     }
+
+    /// Adds noise to the processed bytes of all the services. The ones whose values end up
+    /// being too small are not reported individually, but are merged into a single bucket instead,
+    /// so that rarely used services wouldn't stand out.
+    fn privatize(
+        privacy_filter: &PrivacyFilter,
+        services: Vec<StatsServiceData>,
+    ) -> Vec<StatsServiceData> {
+        let values: Vec<_> = services
+            .iter()
+            .flat_map(|service| {
+                [
+                    SensitiveValue::new(service.request_bytes as u64, MAX_CLIENT_PROCESSED_BYTES),
+                    SensitiveValue::new(service.response_bytes as u64, MAX_CLIENT_PROCESSED_BYTES),
+                ]
+            })
+            .collect();
+        let noisy_values = privacy_filter.add_noise(&values);
+
+        let to_reported =
+            |value: Option<u64>| value.unwrap_or_default().min(u32::MAX as u64) as u32;
+        let mut small_request_bytes = 0.;
+        let mut small_response_bytes = 0.;
+        let mut private_services = Vec::new();
+        for (service, noisy) in services.into_iter().zip(noisy_values.chunks(2)) {
+            let request_bytes = privacy_filter.postprocess(noisy[0], MAX_CLIENT_PROCESSED_BYTES);
+            let response_bytes = privacy_filter.postprocess(noisy[1], MAX_CLIENT_PROCESSED_BYTES);
+            if request_bytes.is_none() && response_bytes.is_none() {
+                small_request_bytes += noisy[0];
+                small_response_bytes += noisy[1];
+            } else {
+                private_services.push(StatsServiceData::new(
+                    service.requested_service,
+                    to_reported(request_bytes),
+                    to_reported(response_bytes),
+                ))
+            }
+        }
+
+        let request_bytes =
+            privacy_filter.postprocess(small_request_bytes, MAX_CLIENT_PROCESSED_BYTES);
+        let response_bytes =
+            privacy_filter.postprocess(small_response_bytes, MAX_CLIENT_PROCESSED_BYTES);
+        if request_bytes.is_some() || response_bytes.is_some() {
+            private_services.push(StatsServiceData::new(
+                SMALL_COUNTS_BUCKET.to_string(),
+                to_reported(request_bytes),
+                to_reported(response_bytes),
+            ))
+        }
+
+        private_services
+    }
 }
 
 #[async_trait]
@@ -125,7 +230,9 @@ impl StatisticsCollector for ServiceStatisticsCollector {
         interval: Duration,
         timestamp: DateTime<Utc>,
     ) -> StatsMessage {
-        let stats_data = {
+        // the contribution of each client has to be bounded for the added noise to hide it
+        let max_client_bytes = self.privacy_filter.map(|_| MAX_CLIENT_PROCESSED_BYTES);
+        let services: Vec<_> = {
             let request_data_bytes = self.request_stats_data.read().await;
             let response_data_bytes = self.response_stats_data.read().await;
             let services: HashSet<String> = request_data_bytes
@@ -138,23 +245,21 @@ impl StatisticsCollector for ServiceStatisticsCollector {
                 .into_iter()
                 .map(|requested_service| {
                     let request_bytes = request_data_bytes
-                        .client_processed_bytes
-                        .get(&requested_service)
-                        .copied()
-                        .unwrap_or(0);
+                        .service_processed_bytes(&requested_service, max_client_bytes);
                     let response_bytes = response_data_bytes
-                        .client_processed_bytes
-                        .get(&requested_service)
-                        .copied()
-                        .unwrap_or(0);
-                    nym_statistics_common::StatsData::Service(StatsServiceData::new(
-                        requested_service,
-                        request_bytes,
-                        response_bytes,
-                    ))
+                        .service_processed_bytes(&requested_service, max_client_bytes);
+                    StatsServiceData::new(requested_service, request_bytes, response_bytes)
                 })
                 .collect()
         };
+        let services = match &self.privacy_filter {
+            Some(privacy_filter) => Self::privatize(privacy_filter, services),
+            None => services,
+        };
+        let stats_data = services
+            .into_iter()
+            .map(nym_statistics_common::StatsData::Service)
+            .collect();
 
         StatsMessage {
             stats_data,
@@ -217,3 +322,48 @@ impl StatisticsCollector for ServiceStatisticsCollector {
             .client_processed_bytes = HashMap::new();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn processed_bytes_are_summed_per_service() {
+        let mut data = StatsData::new();
+        data.processed("service1", "client1", 100);
+        data.processed("service1", "client1", 200);
+        data.processed("service1", "client2", 300);
+        data.processed("service2", "client1", 400);
+
+        assert_eq!(data.service_processed_bytes("service1", None), 600);
+        assert_eq!(data.service_processed_bytes("service2", None), 400);
+        assert_eq!(data.service_processed_bytes("service3", None), 0);
+    }
+
+    #[test]
+    fn contribution_of_each_client_is_bounded() {
+        let mut data = StatsData::new();
+        data.processed("service", "heavy", 3000);
+        data.processed("service", "heavy", 3000);
+        data.processed("service", "light", 500);
+
+        assert_eq!(data.service_processed_bytes("service", Some(1000)), 1500);
+    }
+
+    #[test]
+    fn clients_are_identified_by_their_sender_tags() {
+        let mut rng = rand::rngs::OsRng;
+        let tag1 = AnonymousSenderTag::new_random(&mut rng);
+        let tag2 = AnonymousSenderTag::new_random(&mut rng);
+
+        assert_eq!(client_identifier(None, None), UNIDENTIFIED_CLIENT);
+        assert_eq!(
+            client_identifier(None, Some(tag1)),
+            client_identifier(None, Some(tag1))
+        );
+        assert_ne!(
+            client_identifier(None, Some(tag1)),
+            client_identifier(None, Some(tag2))
+        );
+    }
+}
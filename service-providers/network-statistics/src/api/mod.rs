@@ -6,7 +6,7 @@ use rocket::{Ignite, Rocket};
 
 use crate::storage::NetworkStatisticsStorage;
 use error::Result;
-use routes::{post_all_statistics, post_statistic};
+use routes::{post_aggregated_statistics, post_all_statistics, post_statistic};
 
 use nym_statistics_common::api::STATISTICS_SERVICE_VERSION;
 use nym_task::TaskManager;
@@ -23,7 +23,11 @@ impl NetworkStatisticsAPI {
         let rocket = rocket::build()
             .mount(
                 STATISTICS_SERVICE_VERSION,
-                rocket::routes![post_all_statistics, post_statistic],
+                rocket::routes![
+                    post_aggregated_statistics,
+                    post_all_statistics,
+                    post_statistic
+                ],
             )
             .manage(storage.clone())
             .ignite()
@@ -40,6 +40,27 @@ pub struct GatewayStatistic {
     pub timestamp: String,
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct AggregatedStatistics {
+    pub services: Vec<AggregatedServiceStatistic>,
+    pub gateways: AggregatedGatewayStatistic,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct AggregatedServiceStatistic {
+    pub requested_service: String,
+    pub request_processed_bytes: u64,
+    pub response_processed_bytes: u64,
+    pub reports: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct AggregatedGatewayStatistic {
+    pub gateways: u64,
+    pub reports: u64,
+    pub average_inbox_count: Option<f64>,
+}
+
 #[rocket::post("/all-statistics", data = "<all_statistics_request>")]
 pub(crate) async fn post_all_statistics(
     all_statistics_request: Json<StatisticsRequest>,
@@ -82,6 +103,45 @@ pub(crate) async fn post_all_statistics(
     Ok(Json(all_statistics))
 }
 
+/// Returns only the statistics aggregated over all the reporting nodes,
+/// so that they could be shared without revealing the usage of any particular node.
+#[rocket::post("/aggregated-statistics", data = "<statistics_request>")]
+pub(crate) async fn post_aggregated_statistics(
+    statistics_request: Json<StatisticsRequest>,
+    storage: &State<NetworkStatisticsStorage>,
+) -> Result<Json<AggregatedStatistics>> {
+    let services = storage
+        .get_aggregated_service_statistics_in_interval(
+            &statistics_request.since,
+            &statistics_request.until,
+        )
+        .await?
+        .into_iter()
+        .map(|data| AggregatedServiceStatistic {
+            requested_service: data.requested_service,
+            request_processed_bytes: data.request_processed_bytes as u64,
+            response_processed_bytes: data.response_processed_bytes as u64,
+            reports: data.reports as u64,
+        })
+        .collect();
+
+    let gateways = storage
+        .get_aggregated_gateway_statistics_in_interval(
+            &statistics_request.since,
+            &statistics_request.until,
+        )
+        .await?;
+
+    Ok(Json(AggregatedStatistics {
+        services,
+        gateways: AggregatedGatewayStatistic {
+            gateways: gateways.gateways as u64,
+            reports: gateways.reports as u64,
+            average_inbox_count: gateways.average_inbox_count,
+        },
+    }))
+}
+
 #[rocket::post("/statistic", data = "<statistic>")]
 pub(crate) async fn post_statistic(
     statistic: Json<StatsMessage>,
@@ -3,7 +3,9 @@
 
 use sqlx::types::chrono::{DateTime, Utc};
 
-use crate::storage::models::{GatewayStatistics, ServiceStatistics};
+use crate::storage::models::{
+    AggregatedGatewayStatistics, AggregatedServiceStatistics, GatewayStatistics, ServiceStatistics,
+};
 
 #[derive(Clone)]
 pub(crate) struct StorageManager {
@@ -109,4 +111,64 @@ impl StorageManager {
         .fetch_all(&self.connection_pool)
         .await
     }
+
+    /// Returns service statistical data submitted within the provided time interval,
+    /// summed up per each requested service.
+    ///
+    /// # Arguments
+    ///
+    /// * `since`: indicates the lower bound timestamp for the data
+    /// * `until`: indicates the upper bound timestamp for the data
+    pub(super) async fn get_aggregated_service_statistics_in_interval(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<AggregatedServiceStatistics>, sqlx::Error> {
+        sqlx::query_as!(
+            AggregatedServiceStatistics,
+            r#"
+                SELECT
+                    requested_service,
+                    SUM(request_processed_bytes) as "request_processed_bytes!: i64",
+                    SUM(response_processed_bytes) as "response_processed_bytes!: i64",
+                    COUNT(*) as "reports!: i64"
+                FROM service_statistics
+                WHERE timestamp BETWEEN ? AND ?
+                GROUP BY requested_service
+            "#,
+            since,
+            until
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
+    /// Returns gateway statistical data submitted within the provided time interval,
+    /// aggregated over all the gateways.
+    ///
+    /// # Arguments
+    ///
+    /// * `since`: indicates the lower bound timestamp for the data
+    /// * `until`: indicates the upper bound timestamp for the data
+    pub(super) async fn get_aggregated_gateway_statistics_in_interval(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<AggregatedGatewayStatistics, sqlx::Error> {
+        sqlx::query_as!(
+            AggregatedGatewayStatistics,
+            r#"
+                SELECT
+                    COUNT(DISTINCT gateway_id) as "gateways!: i64",
+                    COUNT(*) as "reports!: i64",
+                    AVG(inbox_count) as "average_inbox_count: f64"
+                FROM gateway_statistics
+                WHERE timestamp BETWEEN ? AND ?
+            "#,
+            since,
+            until
+        )
+        .fetch_one(&self.connection_pool)
+        .await
+    }
 }
@@ -6,16 +6,33 @@ use sqlx::types::chrono::{DateTime, Utc};
 use sqlx::ConnectOptions;
 use std::path::PathBuf;
 
+use nym_statistics_common::privacy::SMALL_COUNTS_BUCKET;
 use nym_statistics_common::StatsMessage;
 
 use crate::storage::error::NetworkStatisticsStorageError;
 use crate::storage::manager::StorageManager;
-use crate::storage::models::{GatewayStatistics, ServiceStatistics};
+use crate::storage::models::{
+    AggregatedGatewayStatistics, AggregatedServiceStatistics, GatewayStatistics, ServiceStatistics,
+};
 
 pub(crate) mod error;
 mod manager;
 mod models;
 
+/// Minimum number of reports of a service within the queried interval for it to be included
+/// in the aggregated statistics on its own. All the others are merged into a single bucket.
+const MIN_AGGREGATED_SERVICE_REPORTS: i64 = 5;
+
+/// Minimum number of distinct gateways that have to submit their data within the queried interval
+/// for the aggregated gateway statistics to be returned, as otherwise they'd describe individual gateways.
+const MIN_AGGREGATED_GATEWAYS: i64 = 3;
+
+fn parse_timestamp(timestamp: &str) -> Result<DateTime<Utc>, NetworkStatisticsStorageError> {
+    Ok(DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|_| NetworkStatisticsStorageError::TimestampParse)?
+        .into())
+}
+
 // note that clone here is fine as upon cloning the same underlying pool will be used
 #[derive(Clone)]
 pub(crate) struct NetworkStatisticsStorage {
@@ -129,4 +146,205 @@ impl NetworkStatisticsStorage {
             .get_gateway_statistics_in_interval(since, until)
             .await?)
     }
+
+    /// Returns service data submitted within the provided time interval summed up per service.
+    /// Services that were reported too few times are merged together,
+    /// so that the usage of a particular service provider couldn't be singled out.
+    ///
+    /// # Arguments
+    ///
+    /// * `since`: indicates the lower bound timestamp for the data, RFC 3339 format
+    /// * `until`: indicates the upper bound timestamp for the data, RFC 3339 format
+    pub(super) async fn get_aggregated_service_statistics_in_interval(
+        &self,
+        since: &str,
+        until: &str,
+    ) -> Result<Vec<AggregatedServiceStatistics>, NetworkStatisticsStorageError> {
+        let aggregated = self
+            .manager
+            .get_aggregated_service_statistics_in_interval(
+                parse_timestamp(since)?,
+                parse_timestamp(until)?,
+            )
+            .await?;
+
+        let mut small_services = AggregatedServiceStatistics {
+            requested_service: SMALL_COUNTS_BUCKET.to_string(),
+            request_processed_bytes: 0,
+            response_processed_bytes: 0,
+            reports: 0,
+        };
+        let mut services = Vec::new();
+        for service in aggregated {
+            if service.reports >= MIN_AGGREGATED_SERVICE_REPORTS
+                && service.requested_service != SMALL_COUNTS_BUCKET
+            {
+                services.push(service)
+            } else {
+                small_services.request_processed_bytes += service.request_processed_bytes;
+                small_services.response_processed_bytes += service.response_processed_bytes;
+                small_services.reports += service.reports;
+            }
+        }
+        if small_services.reports > 0 {
+            services.push(small_services)
+        }
+
+        Ok(services)
+    }
+
+    /// Returns gateway data submitted within the provided time interval aggregated over all gateways.
+    /// The average number of clients is omitted if too few gateways have submitted their data.
+    ///
+    /// # Arguments
+    ///
+    /// * `since`: indicates the lower bound timestamp for the data, RFC 3339 format
+    /// * `until`: indicates the upper bound timestamp for the data, RFC 3339 format
+    pub(super) async fn get_aggregated_gateway_statistics_in_interval(
+        &self,
+        since: &str,
+        until: &str,
+    ) -> Result<AggregatedGatewayStatistics, NetworkStatisticsStorageError> {
+        let mut aggregated = self
+            .manager
+            .get_aggregated_gateway_statistics_in_interval(
+                parse_timestamp(since)?,
+                parse_timestamp(until)?,
+            )
+            .await?;
+        if aggregated.gateways < MIN_AGGREGATED_GATEWAYS {
+            aggregated.average_inbox_count = None;
+        }
+
+        Ok(aggregated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_statistics_common::{StatsData, StatsGatewayData, StatsServiceData};
+    use tempfile::TempDir;
+
+    const TIMESTAMP: &str = "2023-06-01T12:00:00+00:00";
+    const SINCE: &str = "2023-06-01T00:00:00+00:00";
+    const UNTIL: &str = "2023-06-02T00:00:00+00:00";
+
+    async fn storage() -> (NetworkStatisticsStorage, TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = NetworkStatisticsStorage::init(&dir.path().to_path_buf())
+            .await
+            .unwrap();
+        (storage, dir)
+    }
+
+    async fn report(storage: &NetworkStatisticsStorage, stats_data: Vec<StatsData>) {
+        let msg = StatsMessage {
+            stats_data,
+            interval_seconds: 60,
+            timestamp: TIMESTAMP.to_string(),
+        };
+        storage.insert_statistics(msg).await.unwrap()
+    }
+
+    async fn report_service(storage: &NetworkStatisticsStorage, service: &str, times: i64) {
+        for _ in 0..times {
+            let data = StatsServiceData::new(service.to_string(), 10, 100);
+            report(storage, vec![StatsData::Service(data)]).await
+        }
+    }
+
+    async fn report_gateways(storage: &NetworkStatisticsStorage, inbox_counts: &[u32]) {
+        for (i, inbox_count) in inbox_counts.iter().enumerate() {
+            let data = StatsGatewayData::new(format!("gateway{i}"), *inbox_count);
+            report(storage, vec![StatsData::Gateway(data)]).await
+        }
+    }
+
+    #[tokio::test]
+    async fn services_with_enough_reports_are_aggregated_individually() {
+        let (storage, _dir) = storage().await;
+        report_service(&storage, "popular", MIN_AGGREGATED_SERVICE_REPORTS).await;
+        report_service(&storage, "rare", MIN_AGGREGATED_SERVICE_REPORTS - 1).await;
+        report_service(&storage, "obscure", 1).await;
+
+        let mut aggregated = storage
+            .get_aggregated_service_statistics_in_interval(SINCE, UNTIL)
+            .await
+            .unwrap();
+        aggregated.sort_by(|a, b| a.requested_service.cmp(&b.requested_service));
+
+        assert_eq!(aggregated.len(), 2);
+        assert_eq!(aggregated[0].requested_service, SMALL_COUNTS_BUCKET);
+        assert_eq!(aggregated[0].reports, MIN_AGGREGATED_SERVICE_REPORTS);
+        assert_eq!(
+            aggregated[0].request_processed_bytes,
+            10 * MIN_AGGREGATED_SERVICE_REPORTS
+        );
+        assert_eq!(
+            aggregated[0].response_processed_bytes,
+            100 * MIN_AGGREGATED_SERVICE_REPORTS
+        );
+
+        assert_eq!(aggregated[1].requested_service, "popular");
+        assert_eq!(aggregated[1].reports, MIN_AGGREGATED_SERVICE_REPORTS);
+    }
+
+    #[tokio::test]
+    async fn reported_small_counts_bucket_is_merged_with_rare_services() {
+        let (storage, _dir) = storage().await;
+        report_service(
+            &storage,
+            SMALL_COUNTS_BUCKET,
+            MIN_AGGREGATED_SERVICE_REPORTS,
+        )
+        .await;
+        report_service(&storage, "rare", 1).await;
+
+        let aggregated = storage
+            .get_aggregated_service_statistics_in_interval(SINCE, UNTIL)
+            .await
+            .unwrap();
+
+        assert_eq!(aggregated.len(), 1);
+        assert_eq!(aggregated[0].requested_service, SMALL_COUNTS_BUCKET);
+        assert_eq!(aggregated[0].reports, MIN_AGGREGATED_SERVICE_REPORTS + 1);
+    }
+
+    #[tokio::test]
+    async fn no_services_are_reported_without_data() {
+        let (storage, _dir) = storage().await;
+        let aggregated = storage
+            .get_aggregated_service_statistics_in_interval(SINCE, UNTIL)
+            .await
+            .unwrap();
+        assert!(aggregated.is_empty())
+    }
+
+    #[tokio::test]
+    async fn gateway_average_is_hidden_with_too_few_gateways() {
+        let (storage, _dir) = storage().await;
+        report_gateways(&storage, &[10, 20]).await;
+
+        let aggregated = storage
+            .get_aggregated_gateway_statistics_in_interval(SINCE, UNTIL)
+            .await
+            .unwrap();
+        assert_eq!(aggregated.gateways, MIN_AGGREGATED_GATEWAYS - 1);
+        assert_eq!(aggregated.reports, 2);
+        assert_eq!(aggregated.average_inbox_count, None);
+    }
+
+    #[tokio::test]
+    async fn gateway_average_is_returned_with_enough_gateways() {
+        let (storage, _dir) = storage().await;
+        report_gateways(&storage, &[10, 20, 30]).await;
+
+        let aggregated = storage
+            .get_aggregated_gateway_statistics_in_interval(SINCE, UNTIL)
+            .await
+            .unwrap();
+        assert_eq!(aggregated.gateways, MIN_AGGREGATED_GATEWAYS);
+        assert_eq!(aggregated.average_inbox_count, Some(20.));
+    }
 }
@@ -21,3 +21,18 @@ pub(crate) struct GatewayStatistics {
     pub(crate) inbox_count: i64,
     pub(crate) timestamp: NaiveDateTime,
 }
+
+// Internally used struct to catch results of the service statistics aggregated by the service
+pub(crate) struct AggregatedServiceStatistics {
+    pub(crate) requested_service: String,
+    pub(crate) request_processed_bytes: i64,
+    pub(crate) response_processed_bytes: i64,
+    pub(crate) reports: i64,
+}
+
+// Internally used struct to catch results of the gateway statistics aggregated over all gateways
+pub(crate) struct AggregatedGatewayStatistics {
+    pub(crate) gateways: i64,
+    pub(crate) reports: i64,
+    pub(crate) average_inbox_count: Option<f64>,
+}
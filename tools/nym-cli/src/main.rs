@@ -20,6 +20,12 @@ pub(crate) struct Cli {
     )]
     pub(crate) mnemonic: Option<bip39::Mnemonic>,
 
+    #[clap(long, global = true, conflicts_with = "mnemonic")]
+    #[clap(
+        help = "Sign transactions with the account of a connected Ledger device running the Cosmos app instead of using a mnemonic."
+    )]
+    pub(crate) ledger: bool,
+
     #[clap(short, long, global = true)]
     #[clap(
         help = "Overrides configuration as a file of environment variables. Note: individual env vars take precedence over this file."
@@ -64,7 +70,7 @@ pub(crate) enum Commands {
     Block(nym_cli_commands::validator::block::Block),
     /// Manage and execute WASM smart contracts
     Cosmwasm(nym_cli_commands::validator::cosmwasm::Cosmwasm),
-    /// Query for transactions
+    /// Query for transactions or build, sign and broadcast them in separate steps
     Tx(nym_cli_commands::validator::transactions::Transactions),
     /// Create and query for a vesting schedule
     VestingSchedule(nym_cli_commands::validator::vesting::VestingSchedule),
@@ -79,6 +85,7 @@ async fn execute(cli: Cli) -> anyhow::Result<()> {
         nyxd_url: cli.nyxd_url,
         nym_api_url: cli.nym_api_url,
         mnemonic: cli.mnemonic,
+        ledger: cli.ledger,
         mixnet_contract_address: cli.mixnet_contract_address,
         vesting_contract_address: cli.vesting_contract_address,
         config_env_file: cli.config_env_file,
@@ -105,7 +112,7 @@ async fn execute(cli: Cli) -> anyhow::Result<()> {
             validator::cosmwasm::execute(args, cosmwasm, &network_details).await?
         }
         Commands::Tx(transactions) => {
-            validator::transactions::execute(transactions, &network_details).await?
+            validator::transactions::execute(args, transactions, &network_details).await?
         }
         Commands::VestingSchedule(vesting) => {
             validator::vesting::execute(args, vesting, &network_details).await?
@@ -6,6 +6,7 @@ use nym_network_defaults::NymNetworkDetails;
 
 pub(crate) mod gateways;
 pub(crate) mod mixnodes;
+pub(crate) mod names;
 pub(crate) mod services;
 
 pub(crate) async fn execute(
@@ -21,6 +22,9 @@ pub(crate) async fn execute(
             mixnode,
         ) => mixnodes::execute(global_args, mixnode, network_details).await?,
         nym_cli_commands::validator::mixnet::operators::MixnetOperatorsCommands::ServiceProvider(service) => services::execute(global_args, service, network_details).await?,
+        nym_cli_commands::validator::mixnet::operators::MixnetOperatorsCommands::Name(name) => {
+            names::execute(global_args, name, network_details).await?
+        }
     }
     Ok(())
 }
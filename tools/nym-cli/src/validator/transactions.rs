@@ -1,10 +1,11 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use nym_cli_commands::context::create_query_client;
+use nym_cli_commands::context::{create_query_client, create_signer, ClientArgs};
 use nym_network_defaults::NymNetworkDetails;
 
 pub(crate) async fn execute(
+    global_args: ClientArgs,
     transactions: nym_cli_commands::validator::transactions::Transactions,
     network_details: &NymNetworkDetails,
 ) -> anyhow::Result<()> {
@@ -23,6 +24,29 @@ pub(crate) async fn execute(
             )
             .await
         }
+        Some(nym_cli_commands::validator::transactions::TransactionsCommands::Build(args)) => {
+            nym_cli_commands::validator::transactions::build::build(
+                args,
+                &create_query_client(network_details)?,
+            )
+            .await
+        }
+        Some(nym_cli_commands::validator::transactions::TransactionsCommands::Sign(args)) => {
+            nym_cli_commands::validator::transactions::sign::sign(
+                args,
+                create_signer(global_args, network_details)?,
+            )
+        }
+        Some(nym_cli_commands::validator::transactions::TransactionsCommands::Multisign(args)) => {
+            nym_cli_commands::validator::transactions::multisign::multisign(args)
+        }
+        Some(nym_cli_commands::validator::transactions::TransactionsCommands::Broadcast(args)) => {
+            nym_cli_commands::validator::transactions::broadcast::broadcast(
+                args,
+                &create_query_client(network_details)?,
+            )
+            .await
+        }
         _ => unreachable!(),
     }
     Ok(())